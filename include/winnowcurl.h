@@ -0,0 +1,20 @@
+#ifndef WINNOWCURL_H
+#define WINNOWCURL_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Parse a curl command into JSON: {"command": ...} or {"error": ...}.
+ * Returns NULL when command is NULL or not UTF-8. Free the result with
+ * winnowcurl_free. Built with `cargo build --release --features ffi`. */
+char *winnowcurl_parse(const char *command);
+
+/* Free a string returned by winnowcurl_parse; NULL is ignored. */
+void winnowcurl_free(char *json);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
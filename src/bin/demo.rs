@@ -1,7 +1,7 @@
-use winnowcurl::curl::curl_parsers::curl_cmd_parse;
+use winnowcurl::curl::backend::{NomBackend, ParserBackend};
 
 fn main() {
     let curl_command = "curl 'http://example.com' -H 'Accept: application/json'";
-    let result = curl_cmd_parse(curl_command);
+    let result = NomBackend.parse(curl_command);
     println!("{:?}", result);
 }
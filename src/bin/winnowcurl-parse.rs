@@ -0,0 +1,71 @@
+use std::io::Read;
+use std::process::ExitCode;
+
+use winnowcurl::curl::lexer::Dialect;
+use winnowcurl::curl::output::ParsedCommand;
+use winnowcurl::curl::parser::curl_cmd_try_parse_dialect;
+
+const USAGE: &str = "\
+Prints a curl command as JSON, as `winnowcurl parse --format json` does
+
+Usage: winnowcurl-parse [--dialect <auto|bash|cmd|powershell>] [COMMAND]
+
+Reads the command from stdin when COMMAND is `-` or not given, and exits
+with 1 when it does not parse, the error printed as JSON.";
+
+/// The dialect and command of the arguments, `Err` holding what to print
+/// otherwise.
+fn arguments(mut args: impl Iterator<Item = String>) -> Result<(Dialect, Option<String>), String> {
+    let mut dialect = Dialect::Auto;
+    let mut command = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(USAGE.into()),
+            "--dialect" => {
+                let value = args.next().ok_or("--dialect needs a value")?;
+                dialect = value.parse()?;
+            }
+            _ if command.is_none() => command = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'\n\n{}", arg, USAGE)),
+        }
+    }
+    Ok((dialect, command.filter(|command| command != "-")))
+}
+
+fn main() -> ExitCode {
+    let (dialect, command) = match arguments(std::env::args().skip(1)) {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        }
+    };
+    let command = match command {
+        Some(command) => command,
+        None => {
+            let mut text = String::new();
+            if let Err(e) = std::io::stdin().read_to_string(&mut text) {
+                eprintln!("Error reading stdin: {}", e);
+                return ExitCode::from(2);
+            }
+            text
+        }
+    };
+    let (json, code) = match curl_cmd_try_parse_dialect(&command, dialect) {
+        Ok(curls) => (
+            serde_json::to_string_pretty(&ParsedCommand::new(&curls)),
+            ExitCode::SUCCESS,
+        ),
+        Err(e) => (serde_json::to_string_pretty(&e), ExitCode::FAILURE),
+    };
+    match json {
+        Ok(json) => {
+            println!("{}", json);
+            code
+        }
+        Err(e) => {
+            eprintln!("Error serializing the command: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
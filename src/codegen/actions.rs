@@ -0,0 +1,284 @@
+use std::fmt::Write;
+
+use super::shell::{escape_double_quoted, is_key_header, secret_query, variable_name};
+use super::{CodeGenerator, Target, quote};
+use crate::curl::form::{FormField, FormValue};
+use crate::curl::header::split_header;
+use crate::curl::options::has_flag;
+use crate::curl::parser::Curl;
+use crate::curl::secrets::{Redaction, is_secret_name, redact_url};
+use crate::diagnostic::Diagnostic;
+use crate::request::Request;
+
+/// Emits a GitHub Actions workflow running the command on a schedule, its
+/// credentials read from repository secrets.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ActionsGenerator;
+
+/// The repository secrets a command needs, in order of appearance.
+#[derive(Debug, Default)]
+struct Secrets {
+    names: Vec<String>,
+}
+
+impl Secrets {
+    /// Add a secret, numbering it when the name is already taken, and return
+    /// the shell expansion of its environment variable.
+    fn add(&mut self, name: &str) -> String {
+        let mut unique = name.to_string();
+        let mut n = 1;
+        while self.names.contains(&unique) {
+            n += 1;
+            unique = format!("{}_{}", name, n);
+        }
+        let expansion = format!("${{{}}}", unique);
+        self.names.push(unique);
+        expansion
+    }
+
+    /// A header as a double-quoted word with its credential replaced,
+    /// `None` for headers carrying none.
+    fn header(&mut self, raw: &str) -> Option<String> {
+        let (name, value) = split_header(raw)?;
+        let secret = if name.eq_ignore_ascii_case("authorization") {
+            match value.split_once(' ') {
+                Some((scheme, _)) => {
+                    format!("{} {}", escape_double_quoted(scheme), self.add("TOKEN"))
+                }
+                None => self.add("TOKEN"),
+            }
+        } else if is_key_header(name) {
+            let bare = match name.get(..2) {
+                Some(prefix) if prefix.eq_ignore_ascii_case("x-") => &name[2..],
+                _ => name,
+            };
+            self.add(&variable_name(bare))
+        } else {
+            return None;
+        };
+        Some(format!("\"{}: {}\"", escape_double_quoted(name), secret))
+    }
+
+    /// The URL as a double-quoted word with its secret query parameters
+    /// replaced, `None` when it has none.
+    fn url(&mut self, url: &str) -> Option<String> {
+        let query = url.find(['?', '#']).unwrap_or(url.len());
+        let known = self.names.len();
+        let rest = secret_query(&url[query..], |name| self.add(name));
+        (self.names.len() > known)
+            .then(|| format!("\"{}{}\"", escape_double_quoted(&url[..query]), rest))
+    }
+
+    /// A text field with a secret name as a double-quoted word, its value
+    /// replaced, `None` for other fields.
+    fn form(&mut self, field: &FormField) -> Option<String> {
+        if !is_secret_name(&field.name) || !matches!(field.value, FormValue::Text(_)) {
+            return None;
+        }
+        let mut word = format!(
+            "{}={}",
+            escape_double_quoted(&field.name),
+            self.add(&variable_name(&field.name))
+        );
+        if let Some(content_type) = &field.content_type {
+            word.push_str(";type=");
+            word.push_str(&escape_double_quoted(content_type));
+        }
+        for headers in &field.headers {
+            word.push_str(";headers=");
+            word.push_str(&escape_double_quoted(headers));
+        }
+        Some(format!("{} \"{}\"", field.identifier, word))
+    }
+
+    /// `-u user:password` with the password replaced.
+    fn user(&mut self, value: &str) -> String {
+        match value.split_once(':') {
+            Some((user, _)) => format!(
+                "\"{}:{}\"",
+                escape_double_quoted(user),
+                self.add("API_PASSWORD")
+            ),
+            None => format!("\"{}\"", escape_double_quoted(value)),
+        }
+    }
+}
+
+impl CodeGenerator for ActionsGenerator {
+    fn target(&self) -> Target {
+        Target::GithubActions
+    }
+
+    fn generate(&self, curls: &[Curl]) -> Result<String, Diagnostic> {
+        let request = Request::try_from(curls)?;
+        let mut secrets = Secrets::default();
+        // A check has to fail when the server answers with an error.
+        let mut args = Vec::new();
+        if !has_flag(curls, "--fail") && !has_flag(curls, "--fail-with-body") {
+            args.push("--fail".to_string());
+        }
+        for curl in curls {
+            let arg = match curl {
+                Curl::Header(stru) => {
+                    let raw = stru.data.as_deref().unwrap_or_default();
+                    match secrets.header(raw) {
+                        Some(header) => format!("{} {}", stru.identifier, header),
+                        None => curl.to_string(),
+                    }
+                }
+                Curl::URL(url) => {
+                    let url = url.to_string();
+                    secrets.url(&url).unwrap_or_else(|| curl.to_string())
+                }
+                Curl::Form(field) => secrets.form(field).unwrap_or_else(|| curl.to_string()),
+                Curl::Option { name, value } if name == "--user" => {
+                    format!("{} {}", name, secrets.user(value))
+                }
+                Curl::Option { name, .. } if name == "--oauth2-bearer" => {
+                    format!("{} \"{}\"", name, secrets.add("TOKEN"))
+                }
+                curl => curl.to_string(),
+            };
+            args.push(arg);
+        }
+
+        // The names are shown in the logs, the secrets are kept out of them.
+        let label = format!(
+            "{} {}",
+            request.method,
+            redact_url(&request.url, &Redaction::Mask)
+        );
+        let mut code = String::new();
+        let _ = writeln!(code, "name: {}", quote(&format!("Check {}", label)));
+        code.push_str(concat!(
+            "on:\n",
+            "  schedule:\n",
+            "    - cron: \"0 * * * *\"\n",
+            "  workflow_dispatch:\n",
+            "jobs:\n",
+            "  check:\n",
+            "    runs-on: ubuntu-latest\n",
+            "    steps:\n",
+        ));
+        let _ = writeln!(code, "      - name: {}", quote(&label));
+        if !secrets.names.is_empty() {
+            code.push_str("        env:\n");
+            for name in &secrets.names {
+                let _ = writeln!(code, "          {}: ${{{{ secrets.{} }}}}", name, name);
+            }
+        }
+        code.push_str("        run: |\n          curl");
+        for (i, arg) in args.iter().enumerate() {
+            let separator = if i == 0 { " " } else { " \\\n            " };
+            let _ = write!(code, "{}{}", separator, arg);
+        }
+        code.push('\n');
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    fn test_actions_generate() {
+        let curls = curl_cmd_parse(concat!(
+            "curl 'https://api.example.com/health' -H 'Authorization: Bearer abc.def' ",
+            "-H 'X-Api-Key: k' -H 'Accept: */*' -u 'me:pw'"
+        ))
+        .unwrap();
+        assert_eq!(
+            ActionsGenerator.generate(&curls).unwrap(),
+            concat!(
+                "name: \"Check GET https://api.example.com/health\"\n",
+                "on:\n",
+                "  schedule:\n",
+                "    - cron: \"0 * * * *\"\n",
+                "  workflow_dispatch:\n",
+                "jobs:\n",
+                "  check:\n",
+                "    runs-on: ubuntu-latest\n",
+                "    steps:\n",
+                "      - name: \"GET https://api.example.com/health\"\n",
+                "        env:\n",
+                "          TOKEN: ${{ secrets.TOKEN }}\n",
+                "          API_KEY: ${{ secrets.API_KEY }}\n",
+                "          API_PASSWORD: ${{ secrets.API_PASSWORD }}\n",
+                "        run: |\n",
+                "          curl --fail \\\n",
+                "            'https://api.example.com/health' \\\n",
+                "            -H \"Authorization: Bearer ${TOKEN}\" \\\n",
+                "            -H \"X-Api-Key: ${API_KEY}\" \\\n",
+                "            -H 'Accept: */*' \\\n",
+                "            --user \"me:${API_PASSWORD}\"\n",
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_actions_generate_without_secrets() {
+        let curls = curl_cmd_parse("curl 'https://a.com' --fail -d 'a=1'").unwrap();
+        let workflow = ActionsGenerator.generate(&curls).unwrap();
+        assert!(!workflow.contains("env:"));
+        assert!(workflow.contains("      - name: \"POST https://a.com\"\n"));
+        assert!(workflow.ends_with("        run: |\n          curl 'https://a.com' \\\n            --fail \\\n            -d 'a=1'\n"));
+    }
+
+    #[rstest]
+    fn test_actions_generate_is_yaml() {
+        let curls = curl_cmd_parse("curl 'https://a.com/x?q=\"1\"' -H 'Authorization: Basic Zm9v'")
+            .unwrap();
+        let workflow: serde_yaml::Value =
+            serde_yaml::from_str(&ActionsGenerator.generate(&curls).unwrap()).unwrap();
+        let step = &workflow["jobs"]["check"]["steps"][0];
+        assert_eq!(step["env"]["TOKEN"], "${{ secrets.TOKEN }}");
+        assert!(step["run"].as_str().unwrap().starts_with("curl --fail"));
+    }
+
+    #[rstest]
+    fn test_actions_generate_bearer_token() {
+        let curls = curl_cmd_parse("curl 'https://a.com' --oauth2-bearer 't0k'").unwrap();
+        let workflow: serde_yaml::Value =
+            serde_yaml::from_str(&ActionsGenerator.generate(&curls).unwrap()).unwrap();
+        let step = &workflow["jobs"]["check"]["steps"][0];
+        assert_eq!(step["env"]["TOKEN"], "${{ secrets.TOKEN }}");
+        assert!(
+            step["run"]
+                .as_str()
+                .unwrap()
+                .contains("--oauth2-bearer \"${TOKEN}\"")
+        );
+    }
+
+    #[rstest]
+    fn test_actions_generate_secret_query_and_form() {
+        let curls = curl_cmd_parse(
+            "curl 'https://a.com/x?page=2&token=zzz' -F 'api_key=k1' -F 'name=me' -F 'token=@t.txt'",
+        )
+        .unwrap();
+        let generated = ActionsGenerator.generate(&curls).unwrap();
+        assert!(!generated.contains("zzz"), "{}", generated);
+        assert!(!generated.contains("k1"), "{}", generated);
+        let workflow: serde_yaml::Value = serde_yaml::from_str(&generated).unwrap();
+        assert_eq!(
+            workflow["name"],
+            "Check POST https://a.com/x?page=2&token=***"
+        );
+        let step = &workflow["jobs"]["check"]["steps"][0];
+        assert_eq!(step["name"], "POST https://a.com/x?page=2&token=***");
+        assert_eq!(step["env"]["TOKEN"], "${{ secrets.TOKEN }}");
+        assert_eq!(step["env"]["API_KEY"], "${{ secrets.API_KEY }}");
+        let run = step["run"].as_str().unwrap();
+        assert!(
+            run.contains("\"https://a.com/x?page=2&token=${TOKEN}\""),
+            "{}",
+            run
+        );
+        assert!(run.contains("-F \"api_key=${API_KEY}\""), "{}", run);
+        assert!(run.contains("-F 'name=me'"), "{}", run);
+        assert!(run.contains("-F 'token=@t.txt'"), "{}", run);
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::shell::variable_name;
+use super::{CodeGenerator, Target, headers_for};
+use crate::curl::options::has_flag;
+use crate::curl::parser::Curl;
+use crate::diagnostic::Diagnostic;
+use crate::request::Request;
+use crate::request::auth::Auth;
+
+/// The statuses curl takes for a success with `--fail` when redirects are
+/// not followed: every registered 2xx and 3xx status.
+const FAIL_VALID_STATUS_CODES: &[u16] = &[
+    200, 201, 202, 203, 204, 205, 206, 207, 208, 226, 300, 301, 302, 303, 304, 305, 307, 308,
+];
+
+/// Emits a Prometheus blackbox exporter module probing the request.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BlackboxGenerator;
+
+#[derive(Debug, Serialize)]
+struct Config {
+    modules: BTreeMap<String, Module>,
+}
+
+#[derive(Debug, Serialize)]
+struct Module {
+    prober: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeout: Option<String>,
+    http: HttpProbe,
+}
+
+#[derive(Debug, Serialize)]
+struct HttpProbe {
+    method: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    valid_status_codes: Vec<u16>,
+    /// curl only follows redirects with `-L`, unlike the exporter.
+    no_follow_redirects: bool,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    basic_auth: Option<BasicAuth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authorization: Option<Authorization>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tls_config: Option<TlsConfig>,
+}
+
+#[derive(Debug, Serialize)]
+struct BasicAuth {
+    username: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+}
+
+/// A token sent as `Authorization: Bearer`, the default type.
+#[derive(Debug, Serialize)]
+struct Authorization {
+    credentials: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TlsConfig {
+    insecure_skip_verify: bool,
+}
+
+/// The name of the module, e.g. `http_get_health` for `GET /v1/health`.
+fn module_name(method: &str, url: &str) -> String {
+    let path = url.split_once("://").map_or(url, |(_, rest)| {
+        rest.find('/').map_or("", |start| &rest[start..])
+    });
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let mut name = format!("http_{}", method.to_ascii_lowercase());
+    if let Some(segment) = path
+        .split('/')
+        .map(variable_name)
+        .rfind(|segment| segment.starts_with(|c: char| c.is_ascii_alphabetic()))
+    {
+        name.push('_');
+        name.push_str(&segment.to_ascii_lowercase());
+    }
+    name
+}
+
+/// The `--max-time` of the command as a Prometheus duration.
+fn timeout(curls: &[Curl]) -> Option<String> {
+    curls.iter().rev().find_map(|curl| match curl {
+        Curl::Option { name, value } if name == "--max-time" => {
+            let seconds: f64 = value.parse().ok()?;
+            Some(format!("{}ms", (seconds * 1000.0).round() as u64))
+        }
+        _ => None,
+    })
+}
+
+impl CodeGenerator for BlackboxGenerator {
+    fn target(&self) -> Target {
+        Target::Blackbox
+    }
+
+    fn generate(&self, curls: &[Curl]) -> Result<String, Diagnostic> {
+        let request = Request::try_from(curls)?;
+        let fail = has_flag(curls, "--fail") || has_flag(curls, "--fail-with-body");
+        // With `-L` the final status is never a redirect, which the default
+        // 2xx statuses of the exporter already cover.
+        let valid_status_codes = if fail && !request.follow_redirects {
+            FAIL_VALID_STATUS_CODES.to_vec()
+        } else {
+            Vec::new()
+        };
+        let http = HttpProbe {
+            method: request.method.to_string(),
+            valid_status_codes,
+            no_follow_redirects: !request.follow_redirects,
+            headers: headers_for(&request, Target::Blackbox)
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+            body: request.body.clone(),
+            basic_auth: match &request.auth {
+                Some(Auth::Basic(credentials)) => Some(BasicAuth {
+                    username: credentials.user.clone(),
+                    password: credentials.password.clone(),
+                }),
+                _ => None,
+            },
+            authorization: match &request.auth {
+                Some(Auth::Bearer { token }) => Some(Authorization {
+                    credentials: token.clone(),
+                }),
+                _ => None,
+            },
+            tls_config: request.insecure.then_some(TlsConfig {
+                insecure_skip_verify: true,
+            }),
+        };
+        let name = module_name(request.method.as_str(), &request.url);
+        let module = Module {
+            prober: "http",
+            timeout: timeout(curls),
+            http,
+        };
+        let config = Config {
+            modules: BTreeMap::from([(name.clone(), module)]),
+        };
+
+        let mut code = format!(
+            concat!(
+                "# Blackbox exporter module probing {url}, scraped with\n",
+                "#   metrics_path: /probe\n",
+                "#   params: {{module: [{name}]}}\n",
+                "#   static_configs: [{{targets: [\"{url}\"]}}]\n",
+            ),
+            url = request.url,
+            name = name,
+        );
+        if !fail {
+            code.push_str(
+                "# curl succeeds whatever the status without --fail; the probe expects a 2xx.\n",
+            );
+        }
+        code.push_str(&serde_yaml::to_string(&config).unwrap_or_default());
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case("GET", "https://a.com/v1/health?x=1", "http_get_health")]
+    #[case("POST", "https://a.com/users/42", "http_post_users")]
+    #[case("GET", "https://a.com", "http_get")]
+    fn test_module_name(#[case] method: &str, #[case] url: &str, #[case] expected: &str) {
+        assert_eq!(module_name(method, url), expected);
+    }
+
+    #[rstest]
+    fn test_blackbox_generate() {
+        let curls = curl_cmd_parse(concat!(
+            "curl 'https://api.example.com/health' --fail -m 2.5 -k --compressed ",
+            "-H 'Accept: application/json' -u 'me:pw' -d 'ping=1'"
+        ))
+        .unwrap();
+        let generated = BlackboxGenerator.generate(&curls).unwrap();
+        assert!(generated.starts_with(concat!(
+            "# Blackbox exporter module probing https://api.example.com/health, scraped with\n",
+            "#   metrics_path: /probe\n",
+            "#   params: {module: [http_post_health]}\n",
+        )));
+        assert!(!generated.contains("without --fail"));
+
+        let config: serde_yaml::Value = serde_yaml::from_str(&generated).unwrap();
+        let module = &config["modules"]["http_post_health"];
+        assert_eq!(module["prober"], "http");
+        assert_eq!(module["timeout"], "2500ms");
+        let http = &module["http"];
+        assert_eq!(http["method"], "POST");
+        assert_eq!(http["valid_status_codes"].as_sequence().unwrap().len(), 18);
+        assert_eq!(http["no_follow_redirects"], true);
+        assert_eq!(http["headers"]["Accept"], "application/json");
+        assert_eq!(http["body"], "ping=1");
+        assert_eq!(http["basic_auth"]["username"], "me");
+        assert_eq!(http["basic_auth"]["password"], "pw");
+        assert_eq!(http["tls_config"]["insecure_skip_verify"], true);
+    }
+
+    #[rstest]
+    fn test_blackbox_generate_defaults() {
+        let curls = curl_cmd_parse("curl 'https://a.com/' -L --fail").unwrap();
+        let generated = BlackboxGenerator.generate(&curls).unwrap();
+        let config: serde_yaml::Value = serde_yaml::from_str(&generated).unwrap();
+        let http = &config["modules"]["http_get"]["http"];
+        assert!(http.get("valid_status_codes").is_none());
+        assert_eq!(http["no_follow_redirects"], false);
+
+        let curls = curl_cmd_parse("curl 'https://a.com/'").unwrap();
+        assert!(
+            BlackboxGenerator
+                .generate(&curls)
+                .unwrap()
+                .contains("without --fail; the probe expects a 2xx")
+        );
+    }
+
+    #[rstest]
+    fn test_blackbox_generate_bearer_token() {
+        let curls = curl_cmd_parse("curl 'https://a.com/' --oauth2-bearer 't0k'").unwrap();
+        let config: serde_yaml::Value =
+            serde_yaml::from_str(&BlackboxGenerator.generate(&curls).unwrap()).unwrap();
+        let http = &config["modules"]["http_get"]["http"];
+        assert_eq!(http["authorization"]["credentials"], "t0k");
+        assert!(http.get("basic_auth").is_none());
+    }
+}
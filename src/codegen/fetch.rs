@@ -0,0 +1,167 @@
+use std::fmt::Write;
+
+use super::{CodeGenerator, Target, headers_for, quote};
+use crate::curl::parser::Curl;
+use crate::diagnostic::Diagnostic;
+use crate::request::Request;
+use crate::request::auth::Auth;
+
+/// Emits a JavaScript snippet using the Fetch API.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FetchGenerator;
+
+impl CodeGenerator for FetchGenerator {
+    fn target(&self) -> Target {
+        Target::Fetch
+    }
+
+    fn generate(&self, curls: &[Curl]) -> Result<String, Diagnostic> {
+        let request = Request::try_from(curls)?;
+        let mut options = Vec::new();
+
+        if request.method != "GET" {
+            options.push(format!("method: {}", quote(request.method.as_str())));
+        }
+
+        let mut headers: Vec<String> = headers_for(&request, Target::Fetch)
+            .into_iter()
+            .map(|(name, value)| format!("{}: {}", quote(name), quote(&value)))
+            .collect();
+        match &request.auth {
+            Some(Auth::Basic(credentials)) => {
+                let credentials = format!(
+                    "{}:{}",
+                    credentials.user,
+                    credentials.password.as_deref().unwrap_or_default()
+                );
+                headers.push(format!(
+                    "\"Authorization\": \"Basic \" + btoa({})",
+                    quote(&credentials)
+                ));
+            }
+            Some(Auth::Bearer { token }) => {
+                headers.push(format!(
+                    "\"Authorization\": {}",
+                    quote(&format!("Bearer {}", token))
+                ));
+            }
+            _ => {}
+        }
+        if !headers.is_empty() {
+            let mut block = String::from("headers: {\n");
+            for header in headers {
+                let _ = writeln!(block, "    {},", header);
+            }
+            block.push_str("  }");
+            options.push(block);
+        }
+
+        // Local files are read with Node's fs module.
+        let file = match (&request.body_file, &request.upload_file) {
+            (Some(file), _) if file.binary => {
+                Some(format!("fs.readFileSync({})", quote(&file.path)))
+            }
+            (Some(file), _) => Some(format!(
+                "fs.readFileSync({}, \"utf8\").replace(/[\\r\\n]/g, \"\")",
+                quote(&file.path)
+            )),
+            (None, Some(path)) if request.body.is_none() => {
+                Some(format!("fs.readFileSync({})", quote(path)))
+            }
+            _ => None,
+        };
+        if let Some(read) = &file {
+            options.push(format!("body: {}", read));
+        } else if let Some(body) = &request.body {
+            options.push(format!("body: {}", quote(body)));
+        }
+        // fetch follows redirects by default, curl only with -L.
+        if !request.follow_redirects {
+            options.push("redirect: \"manual\"".into());
+        }
+
+        let mut code = String::new();
+        if file.is_some() {
+            code.push_str("import fs from \"node:fs\";\n\n");
+        }
+        let _ = write!(code, "const response = await fetch({}", quote(&request.url));
+        if !options.is_empty() {
+            code.push_str(", {\n");
+            for option in options {
+                let _ = writeln!(code, "  {},", option);
+            }
+            code.push('}');
+        }
+        code.push_str(");\nconsole.log(await response.text());\n");
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    fn test_fetch_generate() {
+        let curls = curl_cmd_parse(
+            r#"curl 'https://example.com/api' -X 'PUT' -H 'Accept: */*' -H 'Accept-Encoding: br' -d 'a=1' -u 'me:secret'"#,
+        )
+        .unwrap();
+        assert_eq!(
+            FetchGenerator.generate(&curls).unwrap(),
+            concat!(
+                "const response = await fetch(\"https://example.com/api\", {\n",
+                "  method: \"PUT\",\n",
+                "  headers: {\n",
+                "    \"Accept\": \"*/*\",\n",
+                "    \"Content-Type\": \"application/x-www-form-urlencoded\",\n",
+                "    \"Authorization\": \"Basic \" + btoa(\"me:secret\"),\n",
+                "  },\n",
+                "  body: \"a=1\",\n",
+                "  redirect: \"manual\",\n",
+                "});\n",
+                "console.log(await response.text());\n",
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_fetch_follows_redirects_by_default() {
+        let curls = curl_cmd_parse(r#"curl 'https://example.com/a' -L"#).unwrap();
+        assert_eq!(
+            FetchGenerator.generate(&curls).unwrap(),
+            "const response = await fetch(\"https://example.com/a\");\nconsole.log(await response.text());\n"
+        );
+    }
+
+    #[rstest]
+    fn test_fetch_bearer_auth() {
+        let curls =
+            curl_cmd_parse(r#"curl 'https://example.com/a' --oauth2-bearer 't0k'"#).unwrap();
+        let code = FetchGenerator.generate(&curls).unwrap();
+        assert!(
+            code.contains("\"Authorization\": \"Bearer t0k\""),
+            "{}",
+            code
+        );
+    }
+
+    #[rstest]
+    fn test_fetch_upload_file() {
+        let curls = curl_cmd_parse("curl 'https://example.com/a' -T 'notes.txt'").unwrap();
+        assert_eq!(
+            FetchGenerator.generate(&curls).unwrap(),
+            concat!(
+                "import fs from \"node:fs\";\n\n",
+                "const response = await fetch(\"https://example.com/a\", {\n",
+                "  method: \"PUT\",\n",
+                "  body: fs.readFileSync(\"notes.txt\"),\n",
+                "  redirect: \"manual\",\n",
+                "});\n",
+                "console.log(await response.text());\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use super::{CodeGenerator, Target};
+use crate::curl::parser::Curl;
+use crate::diagnostic::Diagnostic;
+use crate::har::{Har, Request};
+
+/// Emits a HAR document with a single entry for the request.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HarGenerator;
+
+impl CodeGenerator for HarGenerator {
+    fn target(&self) -> Target {
+        Target::Har
+    }
+
+    fn generate(&self, curls: &[Curl]) -> Result<String, Diagnostic> {
+        let har = Har::new([Request::from_curls(curls)?]);
+        let mut json = serde_json::to_string_pretty(&har).unwrap_or_default();
+        json.push('\n');
+        Ok(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    fn test_har_generate() {
+        let curls = curl_cmd_parse(r#"curl 'https://example.com/a' -H 'Accept: */*'"#).unwrap();
+        let har = Har::from_json(&HarGenerator.generate(&curls).unwrap()).unwrap();
+        assert_eq!(har.log.version, "1.2");
+        assert_eq!(har.log.entries.len(), 1);
+        assert_eq!(
+            har.log.entries[0].request,
+            Request::from_curls(&curls).unwrap()
+        );
+    }
+}
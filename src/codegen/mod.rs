@@ -0,0 +1,537 @@
+pub mod actions;
+pub mod blackbox;
+pub mod fetch;
+pub mod har;
+pub mod openapi;
+pub mod postman;
+pub mod python;
+pub mod report;
+pub mod rust;
+pub mod shell;
+
+use crate::curl::{header::Headers, parser::Curl};
+use crate::diagnostic::Diagnostic;
+use crate::request::Request;
+use crate::request::body::FORM_URLENCODED;
+
+/// Languages/libraries a curl command can be converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Target {
+    Reqwest,
+    Python,
+    Fetch,
+    /// A HAR document holding the request, for devtools and proxies.
+    Har,
+    /// A Postman Collection v2.1 holding the request.
+    Postman,
+    /// An OpenAPI 3.1 document describing the request.
+    Openapi,
+    /// A shell function running the command, its host, credentials and IDs
+    /// read from environment variables.
+    Shell,
+    /// A GitHub Actions workflow running the command on a schedule, its
+    /// credentials read from repository secrets.
+    GithubActions,
+    /// A Prometheus blackbox exporter module probing the request.
+    Blackbox,
+}
+
+/// How a target expresses a curl feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Support {
+    /// The target has a direct equivalent, described by the idiom.
+    Native(&'static str),
+    /// The target always behaves this way, nothing needs to be emitted.
+    Implicit(&'static str),
+    /// The target cannot express the feature.
+    Unsupported(&'static str),
+}
+
+/// What a target can express, consulted by the generators before emitting code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// `--compressed`: advertise encodings and decode the response.
+    pub compressed: Support,
+    /// An explicit `Accept-Encoding` header set by the user.
+    pub accept_encoding_header: Support,
+    /// `-k`/`--insecure`: skip TLS certificate verification.
+    pub insecure: Support,
+    /// `-L`/`--location`: follow redirects.
+    pub location: Support,
+    /// `-u`/`--user`: HTTP basic authentication.
+    pub basic_auth: Support,
+    /// `--oauth2-bearer`: an `Authorization: Bearer` token.
+    pub bearer_auth: Support,
+    /// `--digest`: HTTP digest authentication.
+    pub digest_auth: Support,
+    /// `-T`/`--upload-file`: a local file sent as the body.
+    pub upload_file: Support,
+    /// `-d @file`: a body read from a local file.
+    pub data_file: Support,
+    /// `-F`/`--form`: a multipart body.
+    pub form: Support,
+}
+
+/// Flags that only change how curl itself prints things and have no
+/// bearing on the request, so they never need translating.
+pub const OUTPUT_ONLY_FLAGS: &[&str] = &[
+    "-s",
+    "--silent",
+    "-S",
+    "--show-error",
+    "-v",
+    "--verbose",
+    "-i",
+    "--include",
+    "-#",
+    "--progress-bar",
+    "--no-progress-meter",
+];
+
+/// Flags picking the HTTP version or how transfers are decoded, which every
+/// target negotiates on its own.
+pub const PROTOCOL_FLAGS: &[&str] = &[
+    "-0",
+    "--http0.9",
+    "--http1.0",
+    "--http1.1",
+    "--http2",
+    "--http2-prior-knowledge",
+    "--http3",
+    "--http3-only",
+    "--raw",
+    "--tr-encoding",
+];
+
+/// Whether `flag`, or the flag it negates with `--no-`, is a protocol flag.
+fn is_protocol_flag(flag: &str) -> bool {
+    let negated = flag.strip_prefix("--no-");
+    PROTOCOL_FLAGS.iter().any(|protocol| {
+        flag == *protocol || negated.is_some_and(|name| protocol.strip_prefix("--") == Some(name))
+    })
+}
+
+impl Target {
+    pub fn capabilities(&self) -> Capabilities {
+        match self {
+            Target::Reqwest => Capabilities {
+                compressed: Support::Native(
+                    "reqwest::Client::builder().gzip(true).brotli(true).deflate(true) (needs the matching reqwest cargo features)",
+                ),
+                accept_encoding_header: Support::Native(".header(\"Accept-Encoding\", ...)"),
+                insecure: Support::Native(".danger_accept_invalid_certs(true)"),
+                location: Support::Native(".redirect(reqwest::redirect::Policy::limited(10))"),
+                basic_auth: Support::Native(".basic_auth(user, Some(password))"),
+                bearer_auth: Support::Native(".bearer_auth(token)"),
+                digest_auth: Support::Unsupported("reqwest has no digest authentication"),
+                upload_file: Support::Native(".body(std::fs::read(path)?)"),
+                data_file: Support::Native(".body(std::fs::read(path)?)"),
+                form: Support::Unsupported("multipart bodies are not generated yet"),
+            },
+            Target::Python => Capabilities {
+                compressed: Support::Implicit(
+                    "requests sends Accept-Encoding: gzip, deflate and decodes responses automatically",
+                ),
+                accept_encoding_header: Support::Native("headers={\"Accept-Encoding\": ...}"),
+                insecure: Support::Native("verify=False"),
+                location: Support::Native("allow_redirects=True"),
+                basic_auth: Support::Native("auth=(user, password)"),
+                bearer_auth: Support::Native("headers={\"Authorization\": \"Bearer ...\"}"),
+                digest_auth: Support::Native("auth=requests.auth.HTTPDigestAuth(user, password)"),
+                upload_file: Support::Native("data=open(path, \"rb\")"),
+                data_file: Support::Native("data=open(path, \"rb\").read()"),
+                form: Support::Unsupported("multipart bodies are not generated yet"),
+            },
+            Target::Fetch => Capabilities {
+                compressed: Support::Implicit(
+                    "fetch negotiates and decodes compressed responses by default",
+                ),
+                accept_encoding_header: Support::Unsupported(
+                    "Accept-Encoding is a forbidden header name in browsers and is ignored by fetch",
+                ),
+                insecure: Support::Unsupported(
+                    "fetch always verifies TLS certificates; use an https.Agent in Node instead",
+                ),
+                location: Support::Native("redirect: \"follow\""),
+                basic_auth: Support::Native("headers: { Authorization: \"Basic \" + btoa(...) }"),
+                bearer_auth: Support::Native("headers: { Authorization: \"Bearer ...\" }"),
+                digest_auth: Support::Unsupported("fetch cannot answer digest challenges"),
+                upload_file: Support::Native("body: fs.readFileSync(path), in Node"),
+                data_file: Support::Native("body: fs.readFileSync(path), in Node"),
+                form: Support::Unsupported("multipart bodies are not generated yet"),
+            },
+            Target::Har => Capabilities {
+                compressed: Support::Implicit(
+                    "the Accept-Encoding header curl would send is not recorded",
+                ),
+                accept_encoding_header: Support::Native(
+                    "headers: [{\"name\": \"Accept-Encoding\", ...}]",
+                ),
+                insecure: Support::Unsupported("HAR records requests, not TLS settings"),
+                location: Support::Unsupported(
+                    "HAR records a single request, not how to follow redirects",
+                ),
+                basic_auth: Support::Native("an Authorization: Basic header"),
+                bearer_auth: Support::Native("an Authorization: Bearer header"),
+                digest_auth: Support::Unsupported(
+                    "the digest Authorization header answers a challenge of the server",
+                ),
+                upload_file: Support::Unsupported(
+                    "HAR records the body sent, not the file it is read from",
+                ),
+                data_file: Support::Unsupported(
+                    "HAR records the body sent, not the file it is read from",
+                ),
+                form: Support::Unsupported("multipart bodies are not generated yet"),
+            },
+            Target::Postman => Capabilities {
+                compressed: Support::Implicit(
+                    "Postman sends Accept-Encoding and decodes responses by default",
+                ),
+                accept_encoding_header: Support::Native(
+                    "header: [{\"key\": \"Accept-Encoding\", ...}]",
+                ),
+                insecure: Support::Native("protocolProfileBehavior: {\"strictSSL\": false}"),
+                location: Support::Native("protocolProfileBehavior: {\"followRedirects\": true}"),
+                basic_auth: Support::Native("auth: {\"type\": \"basic\", ...}"),
+                bearer_auth: Support::Native("auth: {\"type\": \"bearer\", ...}"),
+                digest_auth: Support::Native("auth: {\"type\": \"digest\", ...}"),
+                upload_file: Support::Native("body: {\"mode\": \"file\", ...}"),
+                data_file: Support::Native("body: {\"mode\": \"file\", ...}"),
+                form: Support::Unsupported("multipart bodies are not generated yet"),
+            },
+            Target::Openapi => Capabilities {
+                compressed: Support::Implicit(
+                    "compression is negotiated by clients, not described by the API",
+                ),
+                accept_encoding_header: Support::Implicit(
+                    "Accept-Encoding is negotiated by clients, not described by the API",
+                ),
+                insecure: Support::Implicit(
+                    "certificate checks belong to clients, not to the API description",
+                ),
+                location: Support::Implicit(
+                    "following redirects belongs to clients, not to the API description",
+                ),
+                basic_auth: Support::Native(
+                    "securitySchemes: {\"basicAuth\": {\"type\": \"http\", \"scheme\": \"basic\"}}",
+                ),
+                bearer_auth: Support::Native(
+                    "securitySchemes: {\"bearerAuth\": {\"type\": \"http\", \"scheme\": \"bearer\"}}",
+                ),
+                digest_auth: Support::Native(
+                    "securitySchemes: {\"digestAuth\": {\"type\": \"http\", \"scheme\": \"digest\"}}",
+                ),
+                upload_file: Support::Implicit(
+                    "the content of the file is not known to describe it",
+                ),
+                data_file: Support::Implicit("the content of the file is not known to describe it"),
+                form: Support::Native("requestBody: {\"content\": {\"multipart/form-data\": ...}}"),
+            },
+            Target::Shell => Capabilities {
+                compressed: Support::Native("--compressed"),
+                accept_encoding_header: Support::Native("-H 'Accept-Encoding: ...'"),
+                insecure: Support::Native("-k"),
+                location: Support::Native("-L"),
+                basic_auth: Support::Native("-u \"${API_USER}:${API_PASSWORD}\""),
+                bearer_auth: Support::Native("--oauth2-bearer \"${TOKEN}\""),
+                digest_auth: Support::Native("--digest"),
+                upload_file: Support::Native("-T"),
+                data_file: Support::Native("-d @file"),
+                form: Support::Native("-F"),
+            },
+            Target::GithubActions => Capabilities {
+                compressed: Support::Native("--compressed"),
+                accept_encoding_header: Support::Native("-H 'Accept-Encoding: ...'"),
+                insecure: Support::Native("-k"),
+                location: Support::Native("-L"),
+                basic_auth: Support::Native("--user \"user:${API_PASSWORD}\""),
+                bearer_auth: Support::Native("--oauth2-bearer \"${TOKEN}\""),
+                digest_auth: Support::Native("--digest"),
+                upload_file: Support::Native("-T"),
+                data_file: Support::Native("-d @file"),
+                form: Support::Native("-F"),
+            },
+            Target::Blackbox => Capabilities {
+                compressed: Support::Implicit(
+                    "the probe checks the status without decoding the body",
+                ),
+                accept_encoding_header: Support::Native("headers: {Accept-Encoding: ...}"),
+                insecure: Support::Native("tls_config: {insecure_skip_verify: true}"),
+                location: Support::Native("no_follow_redirects: false"),
+                basic_auth: Support::Native("basic_auth: {username: ..., password: ...}"),
+                bearer_auth: Support::Native("authorization: {credentials: ...}"),
+                digest_auth: Support::Unsupported(
+                    "the prober only sends basic credentials and tokens",
+                ),
+                upload_file: Support::Unsupported("the prober sends an inline body, not a file"),
+                data_file: Support::Unsupported("the prober sends an inline body, not a file"),
+                form: Support::Unsupported("the prober sends an inline body, not a multipart form"),
+            },
+        }
+    }
+
+    /// How the target handles a bare curl flag such as `-k` or `--resolve`.
+    pub fn flag_support(&self, flag: &str) -> Support {
+        if matches!(self, Target::Shell | Target::GithubActions) {
+            return Support::Native("passed to curl as is");
+        }
+        let capabilities = self.capabilities();
+        match flag {
+            "-f" | "--fail" | "--fail-with-body" if *self == Target::Blackbox => {
+                Support::Native("valid_status_codes")
+            }
+            "-m" | "--max-time" if *self == Target::Blackbox => Support::Native("timeout"),
+            "-k" | "--insecure" => capabilities.insecure,
+            "-L" | "--location" => capabilities.location,
+            "--compressed" => capabilities.compressed,
+            "-u" | "--user" | "--basic" => capabilities.basic_auth,
+            "--oauth2-bearer" => capabilities.bearer_auth,
+            "--digest" => capabilities.digest_auth,
+            "-T" | "--upload-file" => capabilities.upload_file,
+            "-F" | "--form" | "--form-string" => capabilities.form,
+            // Applied to the method, URL and body of the request.
+            "-G" | "--get" | "-I" | "--head" | "-X" | "--request" | "-d" | "--data"
+            | "--data-ascii" | "--data-binary" | "--data-raw" | "--data-urlencode" | "--json" => {
+                Support::Native("applied to the request")
+            }
+            "-A" | "--user-agent" | "-e" | "--referer" | "-b" | "--cookie" => {
+                Support::Native("sent as a header")
+            }
+            _ if *self == Target::Openapi => {
+                Support::Implicit("client options are not part of an API description")
+            }
+            "--ntlm" | "--ntlm-wb" | "--negotiate" | "--anyauth" | "--aws-sigv4" => {
+                Support::Unsupported("the authentication scheme is not generated")
+            }
+            _ if OUTPUT_ONLY_FLAGS.contains(&flag) => {
+                Support::Implicit("only affects curl's own output")
+            }
+            _ if is_protocol_flag(flag) => {
+                Support::Implicit("the client negotiates the protocol on its own")
+            }
+            _ => Support::Unsupported("no equivalent in the target"),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Target::Reqwest => "reqwest",
+            Target::Python => "python",
+            Target::Fetch => "fetch",
+            Target::Har => "har",
+            Target::Postman => "postman",
+            Target::Openapi => "openapi",
+            Target::Shell => "shell",
+            Target::GithubActions => "github-actions",
+            Target::Blackbox => "blackbox",
+        }
+    }
+
+    /// The generator emitting code for this target.
+    pub fn generator(&self) -> &'static dyn CodeGenerator {
+        match self {
+            Target::Reqwest => &rust::ReqwestGenerator,
+            Target::Python => &python::PythonGenerator,
+            Target::Fetch => &fetch::FetchGenerator,
+            Target::Har => &har::HarGenerator,
+            Target::Postman => &postman::PostmanGenerator,
+            Target::Openapi => &openapi::OpenApiGenerator,
+            Target::Shell => &shell::ShellGenerator,
+            Target::GithubActions => &actions::ActionsGenerator,
+            Target::Blackbox => &blackbox::BlackboxGenerator,
+        }
+    }
+}
+
+/// Turns a parsed curl command into client code for one target.
+///
+/// Generators only emit what the target can express; anything dropped on
+/// the way is listed by [`report::ConversionReport`].
+pub trait CodeGenerator {
+    fn target(&self) -> Target;
+
+    /// The code for `curls`, or why curl would refuse to send the request,
+    /// e.g. for a command without a URL.
+    fn generate(&self, curls: &[Curl]) -> Result<String, Diagnostic>;
+}
+
+/// Headers of `request` to emit for `target`, repeated ones joined into a
+/// single value and those the target cannot send left out. A `-d` body
+/// without a `Content-Type` gets the one curl sends it with, which HTTP
+/// libraries would not add on their own.
+pub fn headers_for(request: &Request, target: Target) -> Vec<(&str, String)> {
+    let skip_accept_encoding = matches!(
+        target.capabilities().accept_encoding_header,
+        Support::Unsupported(_)
+    );
+    let mut headers: Vec<(&str, String)> = request
+        .headers
+        .names()
+        .filter(|name| !(skip_accept_encoding && name.eq_ignore_ascii_case("accept-encoding")))
+        .map(|name| (name, request.headers.get_all(name).join(", ")))
+        .collect();
+    if request.body.is_some()
+        && request.form.is_empty()
+        && !request.headers.contains("Content-Type")
+    {
+        headers.push(("Content-Type", FORM_URLENCODED.to_string()));
+    }
+    headers
+}
+
+/// A double-quoted string literal, valid in both JavaScript and Python.
+pub(crate) fn quote(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// How the original command asked for a compressed response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compression {
+    /// `--compressed` was given; curl picks the encodings and decodes.
+    Flag,
+    /// Only an explicit `Accept-Encoding` header was given; curl leaves the body encoded.
+    Header(String),
+}
+
+impl Compression {
+    pub fn detect(curls: &[Curl]) -> Option<Self> {
+        let flag = curls
+            .iter()
+            .any(|curl| matches!(curl, Curl::Flag(flag) if flag.identifier == "--compressed"));
+        if flag {
+            return Some(Compression::Flag);
+        }
+
+        Headers::from(curls)
+            .get("accept-encoding")
+            .map(|value| Compression::Header(value.into()))
+    }
+
+    /// The target support relevant to this compression request.
+    pub fn support(&self, target: Target) -> Support {
+        let capabilities = target.capabilities();
+        match self {
+            Compression::Flag => capabilities.compressed,
+            Compression::Header(_) => capabilities.accept_encoding_header,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case(r#"curl "https://example.com" --compressed"#, Some(Compression::Flag))]
+    #[case(
+        r#"curl "https://example.com" -H 'accept-encoding: gzip, br' --compressed"#,
+        Some(Compression::Flag)
+    )]
+    #[case(
+        r#"curl "https://example.com" -H 'Accept-Encoding: gzip, br'"#,
+        Some(Compression::Header("gzip, br".into()))
+    )]
+    #[case(r#"curl "https://example.com" -v"#, None)]
+    fn test_compression_detect(#[case] input: String, #[case] expected: Option<Compression>) {
+        let curls = curl_cmd_parse(&input).unwrap();
+        assert_eq!(Compression::detect(&curls), expected)
+    }
+
+    #[rstest]
+    #[case(Target::Reqwest, "-k", "native")]
+    #[case(Target::Fetch, "--insecure", "unsupported")]
+    #[case(Target::Fetch, "-L", "native")]
+    #[case(Target::Python, "-s", "implicit")]
+    #[case(Target::Python, "--resolve", "unsupported")]
+    #[case(Target::Blackbox, "--fail", "native")]
+    #[case(Target::Blackbox, "--max-time", "native")]
+    #[case(Target::Reqwest, "--fail", "unsupported")]
+    #[case(Target::Openapi, "--resolve", "implicit")]
+    #[case(Target::Openapi, "-u", "native")]
+    #[case(Target::Reqwest, "--oauth2-bearer", "native")]
+    #[case(Target::Reqwest, "--digest", "unsupported")]
+    #[case(Target::Python, "--digest", "native")]
+    #[case(Target::Fetch, "--ntlm", "unsupported")]
+    #[case(Target::Python, "--user-agent", "native")]
+    #[case(Target::Fetch, "--cookie", "native")]
+    #[case(Target::Python, "--upload-file", "native")]
+    #[case(Target::Fetch, "--upload-file", "native")]
+    #[case(Target::Har, "--upload-file", "unsupported")]
+    #[case(Target::Python, "-G", "native")]
+    #[case(Target::Har, "--form", "unsupported")]
+    #[case(Target::Openapi, "-F", "native")]
+    #[case(Target::Har, "--head", "native")]
+    fn test_flag_support(#[case] target: Target, #[case] flag: &str, #[case] expected: &str) {
+        let kind = match target.flag_support(flag) {
+            Support::Native(_) => "native",
+            Support::Implicit(_) => "implicit",
+            Support::Unsupported(_) => "unsupported",
+        };
+        assert_eq!(kind, expected)
+    }
+
+    #[rstest]
+    fn test_headers_for() {
+        let request = Request::parse(
+            "curl https://a.com -H 'X-A: 1' -H 'x-a: 2' -H 'Accept-Encoding: br' -A 'bot/1'",
+        )
+        .unwrap();
+        assert_eq!(
+            headers_for(&request, Target::Python),
+            vec![
+                ("X-A", "1, 2".to_string()),
+                ("Accept-Encoding", "br".to_string()),
+                ("User-Agent", "bot/1".to_string())
+            ]
+        );
+        assert_eq!(
+            headers_for(&request, Target::Fetch),
+            vec![
+                ("X-A", "1, 2".to_string()),
+                ("User-Agent", "bot/1".to_string())
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("curl https://a.com -d 'a=1'", Some(FORM_URLENCODED))]
+    #[case(
+        "curl https://a.com -d 'a=1' -H 'content-type: text/plain'",
+        Some("text/plain")
+    )]
+    #[case("curl https://a.com --json '{}'", Some("application/json"))]
+    #[case("curl https://a.com -F 'a=1'", None)]
+    #[case("curl https://a.com", None)]
+    fn test_headers_for_content_type(#[case] command: &str, #[case] expected: Option<&str>) {
+        let request = Request::parse(command).unwrap();
+        let headers = headers_for(&request, Target::Reqwest);
+        let content_type = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.as_str());
+        assert_eq!(content_type, expected);
+    }
+
+    #[rstest]
+    #[case(Compression::Flag, Target::Reqwest, "native")]
+    #[case(Compression::Flag, Target::Python, "implicit")]
+    #[case(Compression::Flag, Target::Fetch, "implicit")]
+    #[case(Compression::Header("gzip".into()), Target::Python, "native")]
+    #[case(Compression::Header("gzip".into()), Target::Fetch, "unsupported")]
+    fn test_compression_support(
+        #[case] compression: Compression,
+        #[case] target: Target,
+        #[case] expected: &str,
+    ) {
+        let kind = match compression.support(target) {
+            Support::Native(_) => "native",
+            Support::Implicit(_) => "implicit",
+            Support::Unsupported(_) => "unsupported",
+        };
+        assert_eq!(kind, expected)
+    }
+}
@@ -0,0 +1,25 @@
+use super::postman::DEFAULT_COLLECTION_NAME;
+use super::{CodeGenerator, Target};
+use crate::curl::parser::Curl;
+use crate::diagnostic::Diagnostic;
+use crate::export::openapi::Document;
+use crate::request::Request;
+
+/// Emits an OpenAPI document describing the single request.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenApiGenerator;
+
+impl CodeGenerator for OpenApiGenerator {
+    fn target(&self) -> Target {
+        Target::Openapi
+    }
+
+    fn generate(&self, curls: &[Curl]) -> Result<String, Diagnostic> {
+        // The document leaves out the commands curl refuses to send.
+        Request::try_from(curls)?;
+        let document = Document::new(DEFAULT_COLLECTION_NAME, [curls]);
+        let mut json = serde_json::to_string_pretty(&document).unwrap_or_default();
+        json.push('\n');
+        Ok(json)
+    }
+}
@@ -0,0 +1,24 @@
+use super::{CodeGenerator, Target};
+use crate::curl::parser::Curl;
+use crate::diagnostic::Diagnostic;
+use crate::export::postman::{Collection, Item};
+
+/// The collection name used when none is given.
+pub const DEFAULT_COLLECTION_NAME: &str = "winnowcurl";
+
+/// Emits a Postman collection with a single item for the request.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PostmanGenerator;
+
+impl CodeGenerator for PostmanGenerator {
+    fn target(&self) -> Target {
+        Target::Postman
+    }
+
+    fn generate(&self, curls: &[Curl]) -> Result<String, Diagnostic> {
+        let collection = Collection::with_items(DEFAULT_COLLECTION_NAME, vec![Item::new(curls)?]);
+        let mut json = serde_json::to_string_pretty(&collection).unwrap_or_default();
+        json.push('\n');
+        Ok(json)
+    }
+}
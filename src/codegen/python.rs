@@ -0,0 +1,236 @@
+use std::fmt::Write;
+
+use super::{CodeGenerator, Target, headers_for, quote};
+use crate::curl::parser::Curl;
+use crate::diagnostic::Diagnostic;
+use crate::request::Request;
+use crate::request::auth::Auth;
+
+/// Emits a Python snippet using the `requests` library.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PythonGenerator;
+
+impl CodeGenerator for PythonGenerator {
+    fn target(&self) -> Target {
+        Target::Python
+    }
+
+    fn generate(&self, curls: &[Curl]) -> Result<String, Diagnostic> {
+        let request = Request::try_from(curls)?;
+        let credentials = request.auth.as_ref().and_then(Auth::credentials);
+        // curl prompts for the password a `-u user` leaves out.
+        let prompt = credentials
+            .filter(|credentials| credentials.password.is_none())
+            .map(|credentials| {
+                format!(
+                    "getpass.getpass({})",
+                    quote(&format!(
+                        "Enter host password for user '{}':",
+                        credentials.user
+                    ))
+                )
+            });
+        let mut code = String::new();
+        if prompt.is_some() {
+            code.push_str("import getpass\n\n");
+        }
+        code.push_str("import requests\n\n");
+
+        let mut headers = headers_for(&request, Target::Python);
+        if let Some(Auth::Bearer { token }) = &request.auth {
+            headers.push(("Authorization", format!("Bearer {}", token)));
+        }
+        if !headers.is_empty() {
+            code.push_str("headers = {\n");
+            for (name, value) in &headers {
+                let _ = writeln!(code, "    {}: {},", quote(name), quote(value));
+            }
+            code.push_str("}\n\n");
+        }
+
+        let mut arguments = vec![quote(&request.url)];
+        let function = match request.method.as_str() {
+            "GET" | "POST" | "PUT" | "PATCH" | "DELETE" | "HEAD" => {
+                request.method.as_str().to_ascii_lowercase()
+            }
+            _ => {
+                arguments.insert(0, quote(request.method.as_str()));
+                "request".into()
+            }
+        };
+        if !headers.is_empty() {
+            arguments.push("headers=headers".into());
+        }
+        if let Some(file) = &request.body_file {
+            let mut read = format!("open({}, \"rb\").read()", quote(&file.path));
+            if !file.binary {
+                read.push_str(".replace(b\"\\r\", b\"\").replace(b\"\\n\", b\"\")");
+            }
+            arguments.push(format!("data={}", read));
+        } else if let Some(body) = &request.body {
+            arguments.push(format!("data={}", quote(body)));
+        } else if let Some(path) = &request.upload_file {
+            arguments.push(format!("data=open({}, \"rb\")", quote(path)));
+        }
+        match &request.auth {
+            Some(Auth::Basic(credentials)) => {
+                let password = prompt
+                    .unwrap_or_else(|| quote(credentials.password.as_deref().unwrap_or_default()));
+                arguments.push(format!("auth=({}, {})", quote(&credentials.user), password));
+            }
+            Some(Auth::Digest(credentials)) => {
+                let password = prompt
+                    .unwrap_or_else(|| quote(credentials.password.as_deref().unwrap_or_default()));
+                arguments.push(format!(
+                    "auth=requests.auth.HTTPDigestAuth({}, {})",
+                    quote(&credentials.user),
+                    password
+                ));
+            }
+            Some(Auth::Bearer { .. }) | None => {}
+            // requests has no such scheme, the report lists the flag.
+            Some(Auth::Ntlm(_) | Auth::Negotiate(_) | Auth::Any(_) | Auth::AwsSigv4 { .. }) => {}
+        }
+        if request.insecure {
+            arguments.push("verify=False".into());
+        }
+        // requests follows redirects for everything but HEAD, curl only with -L.
+        let redirects = if request.follow_redirects {
+            "True"
+        } else {
+            "False"
+        };
+        arguments.push(format!("allow_redirects={}", redirects));
+
+        let _ = writeln!(code, "response = requests.{}(", function);
+        for argument in arguments {
+            let _ = writeln!(code, "    {},", argument);
+        }
+        code.push_str(")\nprint(response.text)\n");
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    fn test_python_generate() {
+        let curls = curl_cmd_parse(
+            r#"curl 'https://example.com/api' -H 'Accept: */*' -H 'X-A: 1' -H 'x-a: 2' -d 'a=1' -u 'me:secret' -k"#,
+        )
+        .unwrap();
+        assert_eq!(
+            PythonGenerator.generate(&curls).unwrap(),
+            concat!(
+                "import requests\n",
+                "\n",
+                "headers = {\n",
+                "    \"Accept\": \"*/*\",\n",
+                "    \"X-A\": \"1, 2\",\n",
+                "    \"Content-Type\": \"application/x-www-form-urlencoded\",\n",
+                "}\n",
+                "\n",
+                "response = requests.post(\n",
+                "    \"https://example.com/api\",\n",
+                "    headers=headers,\n",
+                "    data=\"a=1\",\n",
+                "    auth=(\"me\", \"secret\"),\n",
+                "    verify=False,\n",
+                "    allow_redirects=False,\n",
+                ")\n",
+                "print(response.text)\n",
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_python_custom_method() {
+        let curls = curl_cmd_parse(r#"curl 'https://example.com/a' -X 'PURGE' -L"#).unwrap();
+        let code = PythonGenerator.generate(&curls).unwrap();
+        assert!(code.contains(concat!(
+            "response = requests.request(\n",
+            "    \"PURGE\",\n",
+            "    \"https://example.com/a\",\n",
+            "    allow_redirects=True,\n",
+        )));
+    }
+
+    #[rstest]
+    fn test_python_request_semantics() {
+        let curls =
+            curl_cmd_parse(r#"curl 'https://example.com/s' -G -d 'q=rust' -A 'bot/1' -b 'sid=1'"#)
+                .unwrap();
+        let code = PythonGenerator.generate(&curls).unwrap();
+        assert!(
+            code.contains("    \"User-Agent\": \"bot/1\",\n"),
+            "{}",
+            code
+        );
+        assert!(code.contains("    \"Cookie\": \"sid=1\",\n"), "{}", code);
+        assert!(
+            code.contains("response = requests.get(\n    \"https://example.com/s?q=rust\",\n"),
+            "{}",
+            code
+        );
+        assert!(!code.contains("data="), "{}", code);
+
+        let curls = curl_cmd_parse("curl 'https://example.com/a' -T 'notes.txt'").unwrap();
+        let code = PythonGenerator.generate(&curls).unwrap();
+        assert!(code.contains("response = requests.put(\n"), "{}", code);
+        assert!(
+            code.contains("    data=open(\"notes.txt\", \"rb\"),\n"),
+            "{}",
+            code
+        );
+
+        let curls = curl_cmd_parse("curl 'https://example.com/a' -I").unwrap();
+        let code = PythonGenerator.generate(&curls).unwrap();
+        assert!(code.contains("response = requests.head(\n"), "{}", code);
+    }
+
+    #[rstest]
+    fn test_python_digest_auth() {
+        let curls = curl_cmd_parse(r#"curl 'https://example.com/a' --digest -u 'me:pw'"#).unwrap();
+        let code = PythonGenerator.generate(&curls).unwrap();
+        assert!(
+            code.contains("    auth=requests.auth.HTTPDigestAuth(\"me\", \"pw\"),\n"),
+            "{}",
+            code
+        );
+    }
+
+    #[rstest]
+    fn test_python_password_prompt() {
+        let curls = curl_cmd_parse("curl 'https://example.com/a' -u bob").unwrap();
+        let code = PythonGenerator.generate(&curls).unwrap();
+        assert!(
+            code.starts_with("import getpass\n\nimport requests\n"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains(
+                "    auth=(\"bob\", getpass.getpass(\"Enter host password for user 'bob':\")),\n"
+            ),
+            "{}",
+            code
+        );
+    }
+
+    #[rstest]
+    fn test_python_bearer_auth() {
+        let curls =
+            curl_cmd_parse(r#"curl 'https://example.com/a' --oauth2-bearer 't0k'"#).unwrap();
+        let code = PythonGenerator.generate(&curls).unwrap();
+        assert!(
+            code.contains("headers = {\n    \"Authorization\": \"Bearer t0k\",\n}\n"),
+            "{}",
+            code
+        );
+        assert!(code.contains("    headers=headers,\n"), "{}", code);
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt;
+
+use super::{Compression, Support, Target};
+use crate::curl::body::{BodySource, DataArg};
+use crate::curl::options::{has_flag, long_flag_names};
+use crate::curl::parser::Curl;
+use crate::diagnostic::{Code, Diagnostic};
+use crate::request::auth::Auth;
+use crate::request::body::BodyFile;
+
+/// An option the target could not translate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsupported {
+    /// The option as written in the curl command.
+    pub option: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} was not converted: {}", self.option, self.reason)
+    }
+}
+
+/// Everything that got lost converting a command to a target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionReport {
+    pub target: Target,
+    pub unsupported: Vec<Unsupported>,
+    /// The user of a `-u` without a password, which curl prompts for.
+    pub prompted_user: Option<String>,
+}
+
+/// The options of a flag as written, each with its long name: a cluster
+/// like `-sSk` stands for `-s`, `-S` and `-k`.
+fn flag_members(identifier: &str) -> Vec<(String, String)> {
+    let mut longs = long_flag_names(identifier);
+    if longs.len() == 1 {
+        return vec![(identifier.to_string(), longs.remove(0))];
+    }
+    identifier
+        .chars()
+        .skip(1)
+        .map(|c| format!("-{}", c))
+        .zip(longs)
+        .collect()
+}
+
+impl ConversionReport {
+    /// Check every option of `curls` against the capabilities of `target`.
+    pub fn new(target: Target, curls: &[Curl]) -> Self {
+        let mut unsupported = Vec::new();
+        let shell = matches!(target, Target::Shell | Target::GithubActions);
+        let body_file = BodyFile::new(curls).filter(|_| !has_flag(curls, "--get"));
+
+        for curl in curls {
+            // Data read from a file or stdin, which only curl reads for the
+            // shell targets.
+            if let Curl::Data(stru) = curl
+                && !shell
+            {
+                let reason = match DataArg::from_stru(stru).source {
+                    BodySource::Inline(_) => None,
+                    BodySource::Stdin => Some("standard input is not read"),
+                    BodySource::File(_) => match target.capabilities().data_file {
+                        Support::Unsupported(reason) => Some(reason),
+                        Support::Native(_) if body_file.is_none() => {
+                            Some("a file is only read when it is the whole body")
+                        }
+                        _ => None,
+                    },
+                };
+                if let Some(reason) = reason {
+                    unsupported.push(Unsupported {
+                        option: format!(
+                            "{} {}",
+                            stru.identifier,
+                            stru.data.as_deref().unwrap_or_default()
+                        ),
+                        reason,
+                    });
+                }
+                continue;
+            }
+            // `-b` without `=` names a cookie file, which no target reads.
+            if let Curl::Option { name, value } = curl
+                && name == "--cookie"
+                && !value.contains('=')
+                && !shell
+            {
+                unsupported.push(Unsupported {
+                    option: name.to_string(),
+                    reason: "cookie files are not read",
+                });
+                continue;
+            }
+            if let Curl::Flag(flag) = curl {
+                for (option, long) in flag_members(&flag.identifier) {
+                    if let Support::Unsupported(reason) = target.flag_support(&long) {
+                        unsupported.push(Unsupported { option, reason });
+                    }
+                }
+                continue;
+            }
+            let option = match curl {
+                Curl::Form(field) => &field.identifier,
+                Curl::Option { name, .. } => &**name,
+                _ => continue,
+            };
+            if let Support::Unsupported(reason) = target.flag_support(option) {
+                unsupported.push(Unsupported {
+                    option: option.to_string(),
+                    reason,
+                });
+            }
+        }
+
+        if let Some(Compression::Header(value)) = Compression::detect(curls)
+            && let Support::Unsupported(reason) = target.capabilities().accept_encoding_header
+        {
+            unsupported.push(Unsupported {
+                option: format!("-H 'Accept-Encoding: {}'", value),
+                reason,
+            });
+        }
+
+        // The shell targets run curl, reading the password from a parameter.
+        let prompted_user = Auth::new(curls)
+            .filter(|_| !shell)
+            .and_then(|auth| auth.credentials().cloned())
+            .filter(|credentials| credentials.password.is_none())
+            .map(|credentials| credentials.user);
+
+        Self {
+            target,
+            unsupported,
+            prompted_user,
+        }
+    }
+
+    /// Whether the conversion kept every option.
+    pub fn is_lossless(&self) -> bool {
+        self.unsupported.is_empty() && self.prompted_user.is_none()
+    }
+
+    /// One coded warning per untranslated option, and one for a password
+    /// curl would prompt for.
+    pub fn diagnostics(&self) -> impl Iterator<Item = Diagnostic> + '_ {
+        let unsupported = self.unsupported.iter().map(|u| {
+            Diagnostic::new(
+                Code::OptionNotConverted,
+                format!("[{}] {}", self.target.name(), u),
+            )
+        });
+        let prompt = self.prompted_user.as_ref().map(|user| {
+            Diagnostic::new(
+                Code::PasswordPrompt,
+                format!(
+                    "[{}] -u '{}' has no password, which curl prompts for",
+                    self.target.name(),
+                    user
+                ),
+            )
+        });
+        unsupported.chain(prompt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    fn test_report_lossless() {
+        let curls = curl_cmd_parse(r#"curl "https://example.com" -L -k -s --compressed"#).unwrap();
+        let report = ConversionReport::new(Target::Reqwest, &curls);
+        assert!(report.is_lossless());
+    }
+
+    #[rstest]
+    fn test_report_protocol_flags() {
+        let curls = curl_cmd_parse(
+            r#"curl "https://example.com" --http1.1 -0 --http0.9 --raw --no-tr-encoding"#,
+        )
+        .unwrap();
+        for target in [Target::Reqwest, Target::Python, Target::Fetch] {
+            assert!(ConversionReport::new(target, &curls).is_lossless());
+        }
+    }
+
+    #[rstest]
+    #[case("curl https://a.com -u bob", Target::Python, Some("bob"))]
+    #[case("curl https://a.com --digest -u bob", Target::Reqwest, Some("bob"))]
+    #[case("curl https://a.com -u bob:pw", Target::Python, None)]
+    #[case("curl https://a.com -u bob", Target::Shell, None)]
+    fn test_report_password_prompt(
+        #[case] input: &str,
+        #[case] target: Target,
+        #[case] expected: Option<&str>,
+    ) {
+        let report = ConversionReport::new(target, &curl_cmd_parse(input).unwrap());
+        assert_eq!(report.prompted_user.as_deref(), expected);
+        let codes: Vec<_> = report.diagnostics().map(|d| d.code).collect();
+        assert_eq!(codes.contains(&Code::PasswordPrompt), expected.is_some());
+    }
+
+    #[rstest]
+    fn test_report_shorthand_headers() {
+        let curls = curl_cmd_parse(
+            r#"curl "https://example.com" -A 'bot/1' -e 'https://b.com' -b 'a=1' -b jar.txt"#,
+        )
+        .unwrap();
+        let report = ConversionReport::new(Target::Python, &curls);
+        let options: Vec<_> = report
+            .unsupported
+            .iter()
+            .map(|u| u.option.as_str())
+            .collect();
+        assert_eq!(options, vec!["--cookie"]);
+    }
+
+    #[rstest]
+    fn test_report_unsupported() {
+        let curls = curl_cmd_parse(
+            r#"curl "https://example.com" -H 'Accept-Encoding: br' -k --resolve example.com:443:127.0.0.1 -L"#,
+        )
+        .unwrap();
+        let report = ConversionReport::new(Target::Fetch, &curls);
+        let options: Vec<_> = report
+            .unsupported
+            .iter()
+            .map(|u| u.option.as_str())
+            .collect();
+        assert_eq!(options, vec!["-k", "--resolve", "-H 'Accept-Encoding: br'"]);
+        assert!(
+            report
+                .diagnostics()
+                .next()
+                .unwrap()
+                .to_string()
+                .starts_with("warning[WCC001]: [fetch] -k was not converted")
+        );
+    }
+
+    #[rstest]
+    #[case(Target::Reqwest, Some(".body(std::fs::read_to_string(\"body.json\")?"))]
+    #[case(Target::Python, Some("data=open(\"body.json\", \"rb\").read()"))]
+    #[case(Target::Fetch, Some("body: fs.readFileSync(\"body.json\", \"utf8\")"))]
+    #[case(Target::Postman, Some("\"src\": \"body.json\""))]
+    #[case(Target::Shell, Some("-d '@body.json'"))]
+    #[case(Target::GithubActions, Some("@body.json"))]
+    #[case(Target::Openapi, None)]
+    #[case(Target::Har, None)]
+    #[case(Target::Blackbox, None)]
+    fn test_report_data_file(#[case] target: Target, #[case] read: Option<&str>) {
+        let curls = curl_cmd_parse("curl https://example.com -d @body.json").unwrap();
+        let report = ConversionReport::new(target, &curls);
+        let code = target.generator().generate(&curls).unwrap();
+        match read {
+            Some(read) => {
+                assert!(code.contains(read), "{}", code);
+                assert!(report.is_lossless(), "{:?}", report);
+            }
+            // OpenAPI describes no body contents, the others warn.
+            None => assert_eq!(
+                report.is_lossless(),
+                target == Target::Openapi,
+                "{:?}",
+                report
+            ),
+        }
+
+        let curls = curl_cmd_parse("curl https://example.com -d @body.json -d a=1").unwrap();
+        let lossless = ConversionReport::new(target, &curls).is_lossless();
+        assert_eq!(
+            lossless,
+            matches!(
+                target,
+                Target::Shell | Target::GithubActions | Target::Openapi
+            )
+        );
+    }
+
+    #[rstest]
+    #[case("--ntlm")]
+    #[case("--negotiate")]
+    #[case("--anyauth")]
+    #[case("--aws-sigv4 aws:amz")]
+    fn test_report_auth_schemes(#[case] scheme: &str) {
+        let input = format!("curl https://example.com -u bob:pw {}", scheme);
+        let report = ConversionReport::new(Target::Python, &curl_cmd_parse(&input).unwrap());
+        let reasons: Vec<_> = report.unsupported.iter().map(|u| u.reason).collect();
+        assert_eq!(reasons, vec!["the authentication scheme is not generated"]);
+    }
+
+    #[rstest]
+    #[case("-sSLk -G -d q=1 -X GET", vec!["-k"])]
+    #[case("-sSLkZ", vec!["-k", "-Z"])]
+    #[case("-I", vec![])]
+    #[case("--json '{}' -X PATCH", vec![])]
+    fn test_report_flags_applied(#[case] options: &str, #[case] expected: Vec<&str>) {
+        let input = format!("curl https://example.com {}", options);
+        let report = ConversionReport::new(Target::Fetch, &curl_cmd_parse(&input).unwrap());
+        let options: Vec<_> = report
+            .unsupported
+            .iter()
+            .map(|u| u.option.as_str())
+            .collect();
+        assert_eq!(options, expected);
+    }
+
+    #[rstest]
+    fn test_report_form() {
+        let curls = curl_cmd_parse("curl https://example.com -F a=1").unwrap();
+        for target in [Target::Reqwest, Target::Python, Target::Fetch, Target::Har] {
+            let reasons: Vec<_> = ConversionReport::new(target, &curls)
+                .unsupported
+                .iter()
+                .map(|u| u.reason)
+                .collect();
+            assert_eq!(reasons, vec!["multipart bodies are not generated yet"]);
+        }
+        assert!(ConversionReport::new(Target::Shell, &curls).is_lossless());
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt::Write;
+
+use super::{CodeGenerator, Target, headers_for};
+use crate::curl::parser::Curl;
+use crate::diagnostic::Diagnostic;
+use crate::request::Request;
+use crate::request::auth::Auth;
+
+/// Emits an async Rust snippet using a `reqwest::Client`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReqwestGenerator;
+
+/// The client method call starting the request, up to its URL argument.
+fn method_call(method: &str) -> String {
+    match method {
+        "GET" | "POST" | "PUT" | "PATCH" | "DELETE" | "HEAD" => {
+            format!("{}(", method.to_ascii_lowercase())
+        }
+        _ => format!(
+            "request(reqwest::Method::from_bytes({:?}.as_bytes())?, ",
+            method
+        ),
+    }
+}
+
+impl CodeGenerator for ReqwestGenerator {
+    fn target(&self) -> Target {
+        Target::Reqwest
+    }
+
+    fn generate(&self, curls: &[Curl]) -> Result<String, Diagnostic> {
+        let request = Request::try_from(curls)?;
+        let mut code = String::from("let client = reqwest::Client::builder()\n");
+        if request.insecure {
+            code.push_str("    .danger_accept_invalid_certs(true)\n");
+        }
+        if request.compressed {
+            code.push_str("    .gzip(true)\n    .brotli(true)\n    .deflate(true)\n");
+        }
+        // reqwest follows redirects unless told otherwise, curl only with -L.
+        let policy = if request.follow_redirects {
+            "limited(10)"
+        } else {
+            "none()"
+        };
+        let _ = writeln!(code, "    .redirect(reqwest::redirect::Policy::{})", policy);
+        code.push_str("    .build()?;\n\n");
+
+        let _ = writeln!(
+            code,
+            "let response = client\n    .{}{:?})",
+            method_call(request.method.as_str()),
+            request.url
+        );
+        for (name, value) in headers_for(&request, Target::Reqwest) {
+            let _ = writeln!(code, "    .header({:?}, {:?})", name, value);
+        }
+        match &request.auth {
+            Some(Auth::Basic(credentials)) => {
+                let password = match &credentials.password {
+                    Some(password) => format!("Some({:?})", password),
+                    None => "None::<&str>".into(),
+                };
+                let _ = writeln!(
+                    code,
+                    "    .basic_auth({:?}, {})",
+                    credentials.user, password
+                );
+            }
+            Some(Auth::Bearer { token }) => {
+                let _ = writeln!(code, "    .bearer_auth({:?})", token);
+            }
+            _ => {}
+        }
+        if let Some(file) = &request.body_file {
+            if file.binary {
+                let _ = writeln!(code, "    .body(std::fs::read({:?})?)", file.path);
+            } else {
+                let _ = writeln!(
+                    code,
+                    "    .body(std::fs::read_to_string({:?})?.replace(['\\r', '\\n'], \"\"))",
+                    file.path
+                );
+            }
+        } else if let Some(body) = &request.body {
+            let _ = writeln!(code, "    .body({:?})", body);
+        } else if let Some(path) = &request.upload_file {
+            let _ = writeln!(code, "    .body(std::fs::read({:?})?)", path);
+        }
+        code.push_str("    .send()\n    .await?;\n\n");
+        code.push_str("println!(\"{}\", response.text().await?);\n");
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    fn test_reqwest_generate() {
+        let curls = curl_cmd_parse(
+            r#"curl 'https://example.com/api' -H 'Accept: */*' -d '{"a": 1}' -u 'me:secret' -k -L"#,
+        )
+        .unwrap();
+        assert_eq!(
+            ReqwestGenerator.generate(&curls).unwrap(),
+            concat!(
+                "let client = reqwest::Client::builder()\n",
+                "    .danger_accept_invalid_certs(true)\n",
+                "    .redirect(reqwest::redirect::Policy::limited(10))\n",
+                "    .build()?;\n",
+                "\n",
+                "let response = client\n",
+                "    .post(\"https://example.com/api\")\n",
+                "    .header(\"Accept\", \"*/*\")\n",
+                "    .header(\"Content-Type\", \"application/x-www-form-urlencoded\")\n",
+                "    .basic_auth(\"me\", Some(\"secret\"))\n",
+                "    .body(\"{\\\"a\\\": 1}\")\n",
+                "    .send()\n",
+                "    .await?;\n",
+                "\n",
+                "println!(\"{}\", response.text().await?);\n",
+            )
+        );
+    }
+
+    #[rstest]
+    #[case("GET", ".get(")]
+    #[case(
+        "PURGE",
+        ".request(reqwest::Method::from_bytes(\"PURGE\".as_bytes())?, "
+    )]
+    fn test_reqwest_method(#[case] method: &str, #[case] expected: &str) {
+        let input = format!("curl 'https://example.com/a' -X '{}'", method);
+        let curls = curl_cmd_parse(&input).unwrap();
+        let code = ReqwestGenerator.generate(&curls).unwrap();
+        assert!(code.contains(expected), "{}", code);
+    }
+
+    #[rstest]
+    #[case("-u 'me'", ".basic_auth(\"me\", None::<&str>)")]
+    #[case("--oauth2-bearer 't0k'", ".bearer_auth(\"t0k\")")]
+    fn test_reqwest_auth(#[case] options: &str, #[case] expected: &str) {
+        let input = format!("curl 'https://example.com/a' {}", options);
+        let curls = curl_cmd_parse(&input).unwrap();
+        let code = ReqwestGenerator.generate(&curls).unwrap();
+        assert!(code.contains(expected), "{}", code);
+    }
+
+    #[rstest]
+    fn test_reqwest_upload_file() {
+        let curls = curl_cmd_parse("curl 'https://example.com/a' -T 'notes.txt'").unwrap();
+        let code = ReqwestGenerator.generate(&curls).unwrap();
+        assert!(
+            code.contains("    .put(\"https://example.com/a\")\n"),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains("    .body(std::fs::read(\"notes.txt\")?)\n"),
+            "{}",
+            code
+        );
+    }
+}
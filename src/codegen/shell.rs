@@ -0,0 +1,388 @@
+use std::fmt::Write;
+
+use super::{CodeGenerator, Target};
+use crate::curl::header::split_header;
+use crate::curl::parser::Curl;
+use crate::curl::secrets::is_secret_name;
+use crate::diagnostic::Diagnostic;
+use crate::request::Request;
+use crate::url::codec::UrlCodec;
+
+/// Emits a shell function running the command, with the host, credentials
+/// and IDs of the request read from environment variables.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShellGenerator;
+
+/// A value of the command replaced by an environment variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Param {
+    name: String,
+    /// The value of the command, `None` for secrets which have to be set.
+    default: Option<String>,
+}
+
+/// Escape `value` for use inside double quotes.
+pub(super) fn escape_double_quoted(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '"' | '$' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl Param {
+    /// The expansion of the variable, falling back to the default or
+    /// failing when a secret is not set.
+    fn expansion(&self) -> String {
+        match &self.default {
+            Some(default) => format!(
+                "${{{}:-{}}}",
+                self.name,
+                escape_double_quoted(default).replace('}', "\\}")
+            ),
+            None => format!("${{{}:?{} is not set}}", self.name, self.name),
+        }
+    }
+}
+
+/// A name made of uppercase letters, digits and underscores.
+pub(super) fn variable_name(text: &str) -> String {
+    let mut name = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c.to_ascii_uppercase());
+        } else if !name.is_empty() && !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    name.trim_end_matches('_').to_string()
+}
+
+/// The query and fragment of a URL escaped for double quotes, the values of
+/// its secret parameters replaced by what `secret` gives for the variable
+/// named after them.
+pub(super) fn secret_query(query: &str, mut secret: impl FnMut(&str) -> String) -> String {
+    let (query, fragment) = query.split_at(query.find('#').unwrap_or(query.len()));
+    let fragment = escape_double_quoted(fragment);
+    let Some(pairs) = query.strip_prefix('?') else {
+        return fragment;
+    };
+    let codec = UrlCodec::form();
+    let pairs: Vec<_> = pairs
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) if !value.is_empty() && is_secret_name(&codec.decode(name)) => {
+                let variable = secret(&variable_name(&codec.decode(name)));
+                format!("{}={}", escape_double_quoted(name), variable)
+            }
+            _ => escape_double_quoted(pair),
+        })
+        .collect();
+    format!("?{}{}", pairs.join("&"), fragment)
+}
+
+/// Whether a path segment identifies a resource: a number, a UUID or a
+/// long hexadecimal key.
+fn is_id(segment: &str) -> bool {
+    let hex = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit());
+    let uuid = segment.len() == 36
+        && segment.split('-').map(str::len).eq([8, 4, 4, 4, 12])
+        && segment.split('-').all(hex);
+    (!segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()))
+        || uuid
+        || (segment.len() >= 16 && hex(segment))
+}
+
+/// Headers carrying a credential of their own, e.g. `X-Api-Key`.
+pub(super) fn is_key_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.contains("token") || name.contains("api-key") || name.contains("apikey")
+}
+
+/// The parameters of a command and the shell words using them.
+#[derive(Debug, Default)]
+struct Template {
+    params: Vec<Param>,
+    /// The path segments which are not IDs, naming the function.
+    words: Vec<String>,
+}
+
+impl Template {
+    /// Add a parameter, numbering it when the name is already taken.
+    fn param(&mut self, name: &str, default: Option<&str>) -> String {
+        let mut unique = name.to_string();
+        let mut n = 1;
+        while self.params.iter().any(|param| param.name == unique) {
+            n += 1;
+            unique = format!("{}_{}", name, n);
+        }
+        let param = Param {
+            name: unique,
+            default: default.map(Into::into),
+        };
+        let expansion = param.expansion();
+        self.params.push(param);
+        expansion
+    }
+
+    /// The URL as a double-quoted word, with its origin, the IDs of its path
+    /// and its secret query parameters as parameters.
+    fn url(&mut self, url: &str) -> String {
+        let Some(scheme_end) = url.find("://") else {
+            return format!("\"{}\"", escape_double_quoted(url));
+        };
+        let authority = scheme_end + 3;
+        let origin_end = url[authority..]
+            .find(['/', '?', '#'])
+            .map_or(url.len(), |end| authority + end);
+        let (origin, rest) = url.split_at(origin_end);
+        let path_end = rest.find(['?', '#']).unwrap_or(rest.len());
+        let (path, query) = rest.split_at(path_end);
+
+        let mut word = self.param("BASE_URL", Some(origin));
+        let mut previous = None;
+        for (i, segment) in path.split('/').enumerate() {
+            if i > 0 {
+                word.push('/');
+            }
+            if is_id(segment) {
+                let name = match previous.map(variable_name) {
+                    Some(resource) if resource.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                        format!("{}_ID", resource.strip_suffix('S').unwrap_or(&resource))
+                    }
+                    _ => "ID".into(),
+                };
+                word.push_str(&self.param(&name, Some(segment)));
+            } else {
+                if !segment.is_empty() {
+                    self.words.push(segment.to_string());
+                }
+                word.push_str(&escape_double_quoted(segment));
+                previous = Some(segment);
+            }
+        }
+        word.push_str(&secret_query(query, |name| self.param(name, None)));
+        format!("\"{}\"", word)
+    }
+
+    /// A header as a double-quoted word, its credential as a parameter,
+    /// `None` for headers carrying none.
+    fn header(&mut self, raw: &str) -> Option<String> {
+        let (name, value) = split_header(raw)?;
+        let secret = if name.eq_ignore_ascii_case("authorization") {
+            match value.split_once(' ') {
+                Some((scheme, _)) => format!("{} {}", scheme, self.param("TOKEN", None)),
+                None => self.param("TOKEN", None),
+            }
+        } else if is_key_header(name) {
+            let bare = match name.get(..2) {
+                Some(prefix) if prefix.eq_ignore_ascii_case("x-") => &name[2..],
+                _ => name,
+            };
+            self.param(&variable_name(bare), None)
+        } else {
+            return None;
+        };
+        Some(format!("\"{}: {}\"", escape_double_quoted(name), secret))
+    }
+
+    /// `-u user:password`, the password as a parameter.
+    fn user(&mut self, value: &str) -> String {
+        match value.split_once(':') {
+            Some((user, _)) => format!(
+                "\"{}:{}\"",
+                self.param("API_USER", Some(user)),
+                self.param("API_PASSWORD", None)
+            ),
+            None => format!("\"{}\"", self.param("API_USER", Some(value))),
+        }
+    }
+
+    /// The name of the function, e.g. `get_users` for `GET /users/42`.
+    fn function_name(&self, method: &str) -> String {
+        let mut name = method.to_ascii_lowercase();
+        if let Some(word) = self.words.last() {
+            name.push('_');
+            name.push_str(&variable_name(word).to_ascii_lowercase());
+        }
+        let name = name.trim_end_matches('_');
+        if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') && !name.is_empty() {
+            name.into()
+        } else {
+            "request".into()
+        }
+    }
+}
+
+impl CodeGenerator for ShellGenerator {
+    fn target(&self) -> Target {
+        Target::Shell
+    }
+
+    fn generate(&self, curls: &[Curl]) -> Result<String, Diagnostic> {
+        let request = Request::try_from(curls)?;
+        let mut template = Template::default();
+        let urls = curls.iter().filter(|curl| matches!(curl, Curl::URL(_)));
+        let rest = curls.iter().filter(|curl| !matches!(curl, Curl::URL(_)));
+        let mut args = Vec::new();
+        for curl in urls.chain(rest) {
+            let arg = match curl {
+                Curl::URL(url) => template.url(&url.to_string()),
+                Curl::Header(stru) => {
+                    let raw = stru.data.as_deref().unwrap_or_default();
+                    match template.header(raw) {
+                        Some(header) => format!("{} {}", stru.identifier, header),
+                        None => curl.to_string(),
+                    }
+                }
+                Curl::Option { name, value } if name == "--user" => {
+                    format!("{} {}", name, template.user(value))
+                }
+                Curl::Option { name, .. } if name == "--oauth2-bearer" => {
+                    format!("{} \"{}\"", name, template.param("TOKEN", None))
+                }
+                curl => curl.to_string(),
+            };
+            args.push(arg);
+        }
+        args.push("\"$@\"".into());
+
+        let mut code = String::from("# Parameters, read from the environment:\n");
+        let width = template
+            .params
+            .iter()
+            .map(|param| param.name.len())
+            .max()
+            .unwrap_or_default();
+        for param in &template.params {
+            let _ = match &param.default {
+                Some(default) => {
+                    writeln!(code, "#   {:width$}  defaults to {}", param.name, default)
+                }
+                None => writeln!(code, "#   {:width$}  required", param.name),
+            };
+        }
+        let _ = writeln!(
+            code,
+            "{}() {{",
+            template.function_name(request.method.as_str())
+        );
+        code.push_str("  curl");
+        for (i, arg) in args.iter().enumerate() {
+            let separator = if i == 0 { " " } else { " \\\n    " };
+            let _ = write!(code, "{}{}", separator, arg);
+        }
+        code.push_str("\n}\n");
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case("42", true)]
+    #[case("123e4567-e89b-12d3-a456-426614174000", true)]
+    #[case("507f1f77bcf86cd799439011", true)]
+    #[case("users", false)]
+    #[case("v2", false)]
+    #[case("", false)]
+    fn test_is_id(#[case] segment: &str, #[case] expected: bool) {
+        assert_eq!(is_id(segment), expected);
+    }
+
+    #[rstest]
+    fn test_shell_generate() {
+        let curls = curl_cmd_parse(concat!(
+            "curl 'https://api.example.com/users/42/orders/7?expand=items' ",
+            "-H 'Authorization: Bearer abc.def' -H 'X-Api-Key: k' -H 'Accept: */*' -d 'a=1'"
+        ))
+        .unwrap();
+        assert_eq!(
+            ShellGenerator.generate(&curls).unwrap(),
+            concat!(
+                "# Parameters, read from the environment:\n",
+                "#   BASE_URL  defaults to https://api.example.com\n",
+                "#   USER_ID   defaults to 42\n",
+                "#   ORDER_ID  defaults to 7\n",
+                "#   TOKEN     required\n",
+                "#   API_KEY   required\n",
+                "post_orders() {\n",
+                "  curl \"${BASE_URL:-https://api.example.com}/users/${USER_ID:-42}/orders/${ORDER_ID:-7}?expand=items\" \\\n",
+                "    -H \"Authorization: Bearer ${TOKEN:?TOKEN is not set}\" \\\n",
+                "    -H \"X-Api-Key: ${API_KEY:?API_KEY is not set}\" \\\n",
+                "    -H 'Accept: */*' \\\n",
+                "    -d 'a=1' \\\n",
+                "    \"$@\"\n",
+                "}\n",
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_shell_generate_user_and_repeated_ids() {
+        let curls =
+            curl_cmd_parse("curl 'http://localhost:8080/1/2' -X DELETE -u 'me:secret'").unwrap();
+        assert_eq!(
+            ShellGenerator.generate(&curls).unwrap(),
+            concat!(
+                "# Parameters, read from the environment:\n",
+                "#   BASE_URL      defaults to http://localhost:8080\n",
+                "#   ID            defaults to 1\n",
+                "#   ID_2          defaults to 2\n",
+                "#   API_USER      defaults to me\n",
+                "#   API_PASSWORD  required\n",
+                "delete() {\n",
+                "  curl \"${BASE_URL:-http://localhost:8080}/${ID:-1}/${ID_2:-2}\" \\\n",
+                "    -X 'DELETE' \\\n",
+                "    --user \"${API_USER:-me}:${API_PASSWORD:?API_PASSWORD is not set}\" \\\n",
+                "    \"$@\"\n",
+                "}\n",
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_shell_generate_secret_query() {
+        let curls = curl_cmd_parse("curl 'https://a.com/items?api_key=zzz&page=2#top'").unwrap();
+        let code = ShellGenerator.generate(&curls).unwrap();
+        assert!(!code.contains("zzz"), "{}", code);
+        assert!(code.contains("#   API_KEY   required\n"), "{}", code);
+        assert!(
+            code.contains(
+                "curl \"${BASE_URL:-https://a.com}/items?api_key=${API_KEY:?API_KEY is not set}&page=2#top\""
+            ),
+            "{}",
+            code
+        );
+    }
+
+    #[rstest]
+    fn test_shell_generate_escapes() {
+        let curls = curl_cmd_parse(r#"curl 'https://a.com/$x"y'"#).unwrap();
+        assert!(
+            ShellGenerator
+                .generate(&curls)
+                .unwrap()
+                .contains(r#"curl "${BASE_URL:-https://a.com}/\$x\"y" \"#)
+        );
+    }
+
+    #[rstest]
+    fn test_shell_generate_bearer_token() {
+        let curls = curl_cmd_parse("curl 'https://a.com/x' --oauth2-bearer 't0k'").unwrap();
+        let script = ShellGenerator.generate(&curls).unwrap();
+        assert!(script.contains("#   TOKEN     required\n"), "{}", script);
+        assert!(
+            script.contains("    --oauth2-bearer \"${TOKEN:?TOKEN is not set}\" \\\n"),
+            "{}",
+            script
+        );
+    }
+}
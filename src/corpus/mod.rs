@@ -0,0 +1,222 @@
+pub mod wire;
+
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::curl::output::parse_to_json;
+use crate::diff::json_patch::{self, PatchOp};
+
+/// The extension of the files holding a command of a corpus.
+pub const COMMAND_EXTENSION: &str = "curl";
+/// The extension of the files holding the JSON expected for a command.
+pub const EXPECTED_EXTENSION: &str = "json";
+
+/// A command of a corpus and the JSON it should parse into, as
+/// [`parse_to_json`] writes it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    /// The path of the command file relative to the corpus, without its
+    /// extension, e.g. `browsers/chrome-bash`.
+    pub name: String,
+    /// Where the command was read from.
+    pub path: PathBuf,
+    pub command: String,
+    /// `None` when the command has no expected file yet.
+    pub expected: Option<Value>,
+}
+
+impl Case {
+    /// The file holding the expected JSON.
+    pub fn expected_path(&self) -> PathBuf {
+        self.path.with_extension(EXPECTED_EXTENSION)
+    }
+
+    /// Write `actual` as the expected JSON, after checking a change is
+    /// meant to be.
+    pub fn bless(&self, actual: &Value) -> io::Result<()> {
+        let mut json = serde_json::to_string_pretty(actual).map_err(io::Error::other)?;
+        json.push('\n');
+        fs::write(self.expected_path(), json)
+    }
+}
+
+fn collect(root: &Path, dir: &Path, cases: &mut Vec<Case>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect(root, &path, cases)?;
+            continue;
+        }
+        if path
+            .extension()
+            .is_none_or(|extension| extension != COMMAND_EXTENSION)
+        {
+            continue;
+        }
+        let command = fs::read_to_string(&path)?;
+        let expected_path = path.with_extension(EXPECTED_EXTENSION);
+        let expected = match fs::read_to_string(&expected_path) {
+            Ok(json) => Some(serde_json::from_str(&json).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {}", expected_path.display(), e),
+                )
+            })?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        let name = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .with_extension("")
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        cases.push(Case {
+            name,
+            path,
+            command: command.trim_end().to_string(),
+            expected,
+        });
+    }
+    Ok(())
+}
+
+/// Read every `.curl` file under `dir`, along with the `.json` file next to
+/// it, sorted by name.
+///
+/// The layout is the one of `tests/corpus`, so that other parsers can be
+/// checked against the same commands.
+pub fn load(dir: &Path) -> io::Result<Vec<Case>> {
+    let mut cases = Vec::new();
+    collect(dir, dir, &mut cases)?;
+    Ok(cases)
+}
+
+/// A case whose command did not parse into the expected JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub name: String,
+    /// `None` when the case has no expected JSON.
+    pub expected: Option<Value>,
+    pub actual: Value,
+}
+
+impl Mismatch {
+    /// What turns the expected JSON into the actual one.
+    pub fn patch(&self) -> Vec<PatchOp> {
+        json_patch::diff(self.expected.as_ref().unwrap_or(&Value::Null), &self.actual)
+    }
+}
+
+/// The case name, then one line per difference, e.g.
+///
+/// ```text
+/// browsers/chrome-bash:
+///   ~ /command/methods/0 "POST"
+/// ```
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.expected.is_none() {
+            return write!(f, "{}: no expected JSON", self.name);
+        }
+        writeln!(f, "{}:", self.name)?;
+        for op in self.patch() {
+            writeln!(f, "  {}", op)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse the command of every case with `parse` and compare the result
+/// with the expected JSON, returning the cases which differ.
+///
+/// [`parse_to_json`] is the parser of this crate; other parsers pass a
+/// function writing the same JSON.
+pub fn run(cases: &[Case], parse: impl Fn(&str) -> Value) -> Vec<Mismatch> {
+    cases
+        .iter()
+        .filter_map(|case| {
+            let actual = parse(&case.command);
+            (case.expected.as_ref() != Some(&actual)).then(|| Mismatch {
+                name: case.name.clone(),
+                expected: case.expected.clone(),
+                actual,
+            })
+        })
+        .collect()
+}
+
+/// [`run`] with this crate's parser.
+pub fn check(cases: &[Case]) -> Vec<Mismatch> {
+    run(cases, parse_to_json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use serde_json::json;
+
+    #[rstest]
+    fn test_load_and_run() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/get.curl"), "curl https://a.com\n").unwrap();
+        fs::write(dir.path().join("new.curl"), "curl https://b.com -X PUT").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a case").unwrap();
+
+        let cases = load(dir.path()).unwrap();
+        let names: Vec<_> = cases.iter().map(|case| case.name.as_str()).collect();
+        assert_eq!(names, ["docs/get", "new"]);
+        assert_eq!(cases[0].command, "curl https://a.com");
+        assert_eq!(cases[1].expected, None);
+
+        let mismatches = check(&cases);
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[1].to_string(), "new: no expected JSON");
+
+        for (case, mismatch) in cases.iter().zip(&mismatches) {
+            case.bless(&mismatch.actual).unwrap();
+        }
+        assert_eq!(check(&load(dir.path()).unwrap()), []);
+    }
+
+    #[rstest]
+    fn test_mismatch_display() {
+        let cases = [Case {
+            name: "docs/post".into(),
+            path: "docs/post.curl".into(),
+            command: "curl https://a.com -X POST".into(),
+            expected: Some(parse_to_json("curl https://a.com -X PUT")),
+        }];
+        let mismatches = check(&cases);
+        assert_eq!(
+            mismatches[0].patch(),
+            [PatchOp::Replace {
+                path: "/command/methods/0".into(),
+                value: json!("POST")
+            }]
+        );
+        assert_eq!(
+            mismatches[0].to_string(),
+            "docs/post:\n  ~ /command/methods/0 \"POST\"\n"
+        );
+    }
+
+    #[rstest]
+    fn test_load_invalid_expected() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.curl"), "curl https://a.com").unwrap();
+        fs::write(dir.path().join("a.json"), "{").unwrap();
+        let error = load(dir.path()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,223 @@
+use std::fmt;
+
+use crate::curl::header::{Headers, split_header};
+use crate::request::Request;
+
+/// A request as a server received it, e.g. from the system curl.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WireRequest {
+    pub method: String,
+    /// The path and query of the request line, e.g. `/search?q=rust`.
+    pub target: String,
+    pub headers: Headers,
+    pub body: Vec<u8>,
+}
+
+impl WireRequest {
+    /// Parse the bytes of an HTTP/1.1 request, `None` when its head is
+    /// incomplete. Everything after the head is the body, which is not
+    /// decoded when chunked.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let end = bytes.windows(4).position(|window| window == b"\r\n\r\n")?;
+        let head = std::str::from_utf8(&bytes[..end]).ok()?;
+        let mut lines = head.split("\r\n");
+        let mut request_line = lines.next()?.split(' ');
+        let method = request_line.next()?.to_string();
+        let target = request_line.next()?.to_string();
+        let headers = lines.filter_map(split_header).collect();
+        Some(WireRequest {
+            method,
+            target,
+            headers,
+            body: bytes[end + 4..].to_vec(),
+        })
+    }
+}
+
+/// The request target of `url`: its path and query, without the fragment.
+fn request_target(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let target = rest.find(['/', '?']).map_or("", |start| &rest[start..]);
+    let target = target.split_once('#').map_or(target, |(target, _)| target);
+    match target.chars().next() {
+        Some('/') => target.into(),
+        _ => format!("/{}", target),
+    }
+}
+
+/// Where what curl sent differs from what winnowcurl expects it to send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Divergence {
+    Method {
+        expected: String,
+        actual: String,
+    },
+    Target {
+        expected: String,
+        actual: String,
+    },
+    /// The values of a header; none expected when an empty `-H 'Name:'`
+    /// removes it.
+    Header {
+        name: String,
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+    Body {
+        expected: String,
+        actual: String,
+    },
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Divergence::Method { expected, actual } => {
+                write!(f, "method: expected {}, sent {}", expected, actual)
+            }
+            Divergence::Target { expected, actual } => {
+                write!(f, "target: expected {}, sent {}", expected, actual)
+            }
+            Divergence::Header {
+                name,
+                expected,
+                actual,
+            } => write!(f, "{}: expected {:?}, sent {:?}", name, expected, actual),
+            Divergence::Body { expected, actual } => {
+                write!(f, "body: expected {:?}, sent {:?}", expected, actual)
+            }
+        }
+    }
+}
+
+/// Compare what `request` should send with what a server received.
+///
+/// Only what the command decides is compared: the method, the request
+/// target, the headers it sets, the `Authorization` of `-u` and
+/// `--oauth2-bearer`, and a body without `@`, which may read a file. The headers
+/// curl adds on its own, such as `User-Agent`, are left out, and so is
+/// `Host` since the request is sent to a local server.
+///
+/// ```
+/// use winnowcurl::Request;
+/// use winnowcurl::corpus::wire::{WireRequest, compare};
+///
+/// let request = Request::parse("curl http://127.0.0.1:8080/a -H 'Accept: text/html'")?;
+/// let wire = WireRequest::parse(b"GET /a HTTP/1.1\r\nHost: 127.0.0.1:8080\r\nAccept: */*\r\n\r\n").unwrap();
+/// let divergences = compare(&request, &wire);
+/// assert_eq!(divergences[0].to_string(), r#"Accept: expected ["text/html"], sent ["*/*"]"#);
+/// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+/// ```
+pub fn compare(request: &Request, wire: &WireRequest) -> Vec<Divergence> {
+    let mut divergences = Vec::new();
+    if request.method != *wire.method {
+        divergences.push(Divergence::Method {
+            expected: request.method.to_string(),
+            actual: wire.method.clone(),
+        });
+    }
+    let target = request_target(&request.url);
+    if target != wire.target {
+        divergences.push(Divergence::Target {
+            expected: target,
+            actual: wire.target.clone(),
+        });
+    }
+
+    let mut headers = request.headers.clone();
+    headers.remove("host");
+    if !headers.contains("authorization")
+        && let Some(authorization) = request.auth.as_ref().and_then(|auth| auth.authorization())
+    {
+        headers.append("Authorization", &authorization);
+    }
+    for name in headers.names() {
+        let expected: Vec<String> = headers
+            .get_all(name)
+            .iter()
+            .filter(|value| !value.is_empty())
+            .cloned()
+            .collect();
+        let actual = wire.headers.get_all(name);
+        if expected != actual {
+            divergences.push(Divergence::Header {
+                name: name.into(),
+                expected,
+                actual: actual.to_vec(),
+            });
+        }
+    }
+
+    if let Some(body) = request.body.as_deref().filter(|body| !body.contains('@')) {
+        let actual = String::from_utf8_lossy(&wire.body);
+        if body != actual {
+            divergences.push(Divergence::Body {
+                expected: body.into(),
+                actual: actual.into_owned(),
+            });
+        }
+    }
+    divergences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn wire(head: &str, body: &str) -> WireRequest {
+        WireRequest::parse(format!("{}\r\n\r\n{}", head, body).as_bytes()).unwrap()
+    }
+
+    #[rstest]
+    #[case("https://a.com", "/")]
+    #[case("https://a.com?q=1", "/?q=1")]
+    #[case("https://a.com/s?q=1#top", "/s?q=1")]
+    #[case("http://u:p@a.com:81/a/b", "/a/b")]
+    fn test_request_target(#[case] url: &str, #[case] expected: &str) {
+        assert_eq!(request_target(url), expected);
+    }
+
+    #[rstest]
+    fn test_parse() {
+        let request = wire("POST /a?b=1 HTTP/1.1\r\nHost: x\r\nX-A: 1\r\nX-A: 2", "a=1");
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.target, "/a?b=1");
+        assert_eq!(request.headers.get_all("x-a"), ["1", "2"]);
+        assert_eq!(request.body, b"a=1");
+        assert_eq!(WireRequest::parse(b"GET / HTTP/1.1\r\nHost: x\r\n"), None);
+    }
+
+    #[rstest]
+    #[case(
+        "curl http://h/a -d 'a=1' -H 'X-A: 1'",
+        "POST /a HTTP/1.1\r\nX-A: 1\r\nUser-Agent: curl",
+        "a=1"
+    )]
+    #[case(
+        "curl http://h -u bob:pw -H 'Accept:'",
+        "GET / HTTP/1.1\r\nAuthorization: Basic Ym9iOnB3",
+        ""
+    )]
+    #[case("curl http://h -F 'a=@f'", "POST / HTTP/1.1", "--boundary")]
+    fn test_compare_same(#[case] command: &str, #[case] head: &str, #[case] body: &str) {
+        let request = Request::parse(command).unwrap();
+        assert_eq!(compare(&request, &wire(head, body)), []);
+    }
+
+    #[rstest]
+    fn test_compare_divergences() {
+        let request = Request::parse("curl http://h/a -X PUT -d 'a=1' -H 'X-A: 1'").unwrap();
+        let divergences = compare(&request, &wire("POST /b HTTP/1.1\r\nX-A: 2", "a=2"));
+        let messages: Vec<_> = divergences.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "method: expected PUT, sent POST",
+                "target: expected /a, sent /b",
+                r#"X-A: expected ["1"], sent ["2"]"#,
+                r#"body: expected "a=1", sent "a=2""#,
+            ]
+        );
+    }
+}
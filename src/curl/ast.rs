@@ -0,0 +1,278 @@
+use serde::Serialize;
+use std::borrow::Cow;
+use std::ops::Range;
+use winnow::LocatingSlice;
+
+use super::form::FormField;
+use super::method::Method;
+use super::options;
+use crate::url::parser::{CurlURL, host_port, parse_url};
+use crate::url::protocol::Schema;
+
+/// An option of a curl command and the argument it was given, if any,
+/// borrowed from the command when they could be taken from it as written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct CurlStru<'a> {
+    pub identifier: Cow<'a, str>,
+    pub data: Option<Cow<'a, str>>,
+}
+
+impl<'a> CurlStru<'a> {
+    pub fn new(identifier: impl Into<Cow<'a, str>>) -> Self {
+        CurlStru {
+            identifier: identifier.into(),
+            data: None,
+        }
+    }
+
+    pub fn new_with_data(
+        identifier: impl Into<Cow<'a, str>>,
+        data: impl Into<Cow<'a, str>>,
+    ) -> Self {
+        CurlStru {
+            identifier: identifier.into(),
+            data: Some(data.into()),
+        }
+    }
+
+    pub fn set_data(&mut self, data: Option<Cow<'a, str>>) {
+        self.data = data;
+    }
+
+    /// Detach the option from the input it was parsed from.
+    pub fn into_owned(self) -> CurlStru<'static> {
+        CurlStru {
+            identifier: Cow::Owned(self.identifier.into_owned()),
+            data: self.data.map(|data| Cow::Owned(data.into_owned())),
+        }
+    }
+}
+
+/// The canonical syntax tree of a curl command, produced by every parser backend.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub enum Curl<'a> {
+    Method(CurlStru<'a>),
+    URL(CurlURL<'a>),
+    Header(CurlStru<'a>),
+    Data(CurlStru<'a>),
+    Flag(CurlStru<'a>),
+    /// A `-F`/`--form` field of a multipart request.
+    Form(FormField),
+    /// Any other option taking an argument, e.g. `-u user:pass` or
+    /// `--max-time 30`, named by its long form.
+    Option {
+        name: Cow<'a, str>,
+        value: Cow<'a, str>,
+    },
+    /// An argument skipped by [`curl_cmd_parse_lossy`], as written at the
+    /// byte span it was found at.
+    ///
+    /// [`curl_cmd_parse_lossy`]: super::parser::curl_cmd_parse_lossy
+    Unknown(Range<usize>, String),
+}
+
+impl<'a> Curl<'a> {
+    /// Build the node for an option taking an argument, e.g. `-H "Accept: */*"`.
+    pub fn new(identifier: &'a str, param: impl Into<Cow<'a, str>>) -> Option<Self> {
+        let param = param.into();
+        let empty = param.is_empty();
+        match identifier {
+            "-X" | "--request" => {
+                (!empty).then(|| Curl::Method(CurlStru::new_with_data(identifier, param)))
+            }
+            "-H" | "--header" => {
+                (!empty).then(|| Curl::Header(CurlStru::new_with_data(identifier, param)))
+            }
+            "-d" | "--data" | "--data-ascii" | "--data-binary" | "--data-raw"
+            | "--data-urlencode" | "--json" => {
+                (!empty).then(|| Curl::Data(CurlStru::new_with_data(identifier, param)))
+            }
+            "-F" | "--form" | "--form-string" => {
+                FormField::parse(identifier, &param).map(Curl::Form)
+            }
+            _ => match options::lookup(identifier) {
+                Some(spec) if spec.takes_value() => Some(Curl::Option {
+                    name: Cow::Borrowed(spec.long),
+                    value: param,
+                }),
+                _ => None,
+            },
+        }
+    }
+
+    pub fn new_as_flag(identifier: &'a str) -> Option<Self> {
+        // TODO: Do more check to ensure it's a flag param for curl
+        if identifier.is_empty() {
+            None
+        } else {
+            Some(Curl::Flag(CurlStru::new(identifier)))
+        }
+    }
+
+    pub fn new_as_url(url: CurlURL<'a>) -> Self {
+        Curl::URL(url)
+    }
+
+    /// Like [`Curl::new_as_url_str`], for a URL argument that may have been
+    /// unquoted into a new string.
+    pub fn new_as_url_word(word: Cow<'a, str>) -> Self {
+        match word {
+            Cow::Borrowed(url_str) => Self::new_as_url_str(url_str),
+            Cow::Owned(url_string) => Curl::new_as_url_str(&url_string).into_owned(),
+        }
+    }
+
+    /// Detach the node from the input it was parsed from.
+    pub fn into_owned(self) -> Curl<'static> {
+        match self {
+            Curl::Method(stru) => Curl::Method(stru.into_owned()),
+            Curl::URL(url) => Curl::URL(url.into_owned()),
+            Curl::Header(stru) => Curl::Header(stru.into_owned()),
+            Curl::Data(stru) => Curl::Data(stru.into_owned()),
+            Curl::Flag(stru) => Curl::Flag(stru.into_owned()),
+            Curl::Form(field) => Curl::Form(field),
+            Curl::Option { name, value } => Curl::Option {
+                name: Cow::Owned(name.into_owned()),
+                value: Cow::Owned(value.into_owned()),
+            },
+            Curl::Unknown(span, text) => Curl::Unknown(span, text),
+        }
+    }
+
+    /// The method of a `-X` node, standard methods in uppercase; `None` for
+    /// other nodes. The node keeps the argument as written, which is what
+    /// curl sends.
+    pub fn method(&self) -> Option<Method> {
+        match self {
+            Curl::Method(stru) => stru.data.as_deref().map(Method::from),
+            _ => None,
+        }
+    }
+
+    /// Decompose a raw URL argument, keeping it whole as the path when it
+    /// cannot be decomposed.
+    pub fn new_as_url_str(url_str: &'a str) -> Self {
+        let mut url_input = LocatingSlice::new(url_str);
+        match parse_url(&mut url_input) {
+            // Only a full decomposition printing back the same, so that no
+            // part of the URL is lost or altered.
+            Ok(url) if url_input.is_empty() && url.to_string() == url_str => Curl::URL(url),
+            _ => {
+                // Fallback - create a simple URL structure
+                let (host, port) = host_port(url_str).unwrap_or_default();
+                Curl::URL(CurlURL {
+                    schema: Schema::HTTP,
+                    authority: None,
+                    path: url_str.into(),
+                    host: host.into(),
+                    port,
+                    slash: false,
+                    uri: "".into(),
+                    queries: vec![],
+                    fragment: None,
+                })
+            }
+        }
+    }
+}
+
+/// A parse result detached from its input, free to move across threads and
+/// async tasks.
+pub type OwnedCurl = Curl<'static>;
+
+#[macro_export]
+macro_rules! new_curl {
+    ($identifier:expr) => {
+        Curl::new_as_flag($identifier).unwrap()
+    };
+    ($identifier:expr,$data:expr) => {
+        Curl::new(stringify!($identifier), $data).unwrap()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("-X", "POST", "Method")]
+    #[case("-H", "Accept: */*", "Header")]
+    #[case("-d", "a=1", "Data")]
+    #[case("--data", "a=1", "Data")]
+    #[case("--json", "{}", "Data")]
+    fn test_curl_new(#[case] identifier: &str, #[case] param: &str, #[case] expected: &str) {
+        let kind = match Curl::new(identifier, param).unwrap() {
+            Curl::Method(stru) | Curl::Header(stru) | Curl::Data(stru) => {
+                assert_eq!(stru.identifier, identifier);
+                assert_eq!(stru.data.as_deref(), Some(param));
+                match Curl::new(identifier, param).unwrap() {
+                    Curl::Method(_) => "Method",
+                    Curl::Header(_) => "Header",
+                    _ => "Data",
+                }
+            }
+            _ => "Other",
+        };
+        assert_eq!(kind, expected)
+    }
+
+    #[rstest]
+    #[case("-F", "file=@photo.png;type=image/png")]
+    #[case("--form", "name=bob")]
+    #[case("--form-string", "name=@bob")]
+    fn test_curl_new_form(#[case] identifier: &str, #[case] param: &str) {
+        match Curl::new(identifier, param) {
+            Some(Curl::Form(field)) => {
+                assert_eq!(field.identifier, identifier);
+                assert_eq!(field.to_string(), param);
+            }
+            other => panic!("Expected Form variant, got {:?}", other),
+        }
+    }
+
+    #[rstest]
+    fn test_curl_new_empty() {
+        assert_eq!(Curl::new("-H", ""), None);
+        assert_eq!(Curl::new_as_flag(""), None);
+    }
+
+    #[rstest]
+    #[case("-u", "user:pass", "--user")]
+    #[case("--max-time", "30", "--max-time")]
+    #[case("-A", "", "--user-agent")]
+    fn test_curl_new_option(#[case] identifier: &str, #[case] param: &str, #[case] name: &str) {
+        assert_eq!(
+            Curl::new(identifier, param),
+            Some(Curl::Option {
+                name: name.into(),
+                value: param.into()
+            })
+        );
+    }
+
+    #[rstest]
+    #[case("-k")]
+    #[case("--not-an-option")]
+    fn test_curl_new_not_an_option(#[case] identifier: &str) {
+        assert_eq!(Curl::new(identifier, "value"), None);
+    }
+
+    #[rstest]
+    fn test_new_as_url_str_fallback() {
+        match Curl::new_as_url_str("not a url") {
+            Curl::URL(url) => assert_eq!(url.path, "not a url"),
+            _ => panic!("Expected URL variant"),
+        }
+    }
+
+    #[rstest]
+    #[case("http://a.com/x?q={1,2}")]
+    #[case("http://a.com/x#top-1")]
+    fn test_new_as_url_str_keeps_undecomposed_rest(#[case] raw: &str) {
+        match Curl::new_as_url_str(raw) {
+            Curl::URL(url) => assert_eq!(url.to_string(), raw),
+            _ => panic!("Expected URL variant"),
+        }
+    }
+}
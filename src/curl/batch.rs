@@ -0,0 +1,224 @@
+use super::lexer::Dialect;
+
+/// One curl invocation found in a document holding several of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Invocation<'a> {
+    /// The 1-based line the invocation starts on.
+    pub line: usize,
+    /// The invocation without its separators or surrounding whitespace.
+    pub source: &'a str,
+}
+
+/// Whether a `curl` word starts at `rest`.
+pub(crate) fn starts_with_curl(rest: &str) -> bool {
+    rest.get(..4)
+        .is_some_and(|word| word.eq_ignore_ascii_case("curl"))
+        && rest[4..].chars().next().is_none_or(char::is_whitespace)
+}
+
+/// Offset of the quote closing a string opened right before `rest`, honouring
+/// the `escape` character if any.
+fn closing_quote(rest: &str, quote: u8, escape: Option<u8>) -> Option<usize> {
+    let bytes = rest.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            byte if Some(byte) == escape => i += 2,
+            byte if byte == quote => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Whether a `rem` comment of cmd starts at `rest`.
+fn starts_with_rem(rest: &str) -> bool {
+    rest.get(..3)
+        .is_some_and(|word| word.eq_ignore_ascii_case("rem"))
+        && rest[3..].chars().next().is_none_or(char::is_whitespace)
+}
+
+/// Split a document written for `dialect` into the curl invocations it
+/// holds.
+///
+/// Invocations end at a newline, `;`, `&&` and `||` like shell commands
+/// do, and at `&` in cmd, or start anew with a `curl` word at the beginning
+/// of a line. A newline escaped with the escape character of the dialect,
+/// `\`, `^` or `` ` ``, continues the invocation, separators inside quotes
+/// don't count, and comments are dropped. Anything else is left to the
+/// parser, so a malformed invocation is still returned and fails on its own.
+pub fn split_commands(input: &str, dialect: Dialect) -> Vec<Invocation<'_>> {
+    let dialect = dialect.resolve(input);
+    let escape = match dialect {
+        Dialect::Cmd => b'^',
+        Dialect::PowerShell => b'`',
+        _ => b'\\',
+    };
+    let cmd = dialect == Dialect::Cmd;
+    let bytes = input.as_bytes();
+    let mut invocations = Vec::new();
+    let mut start = 0;
+    let mut line_start = true;
+    let mut word_start = true;
+    let mut i = 0;
+
+    // Lines are counted incrementally as invocations are pushed in order.
+    let mut counted = 0;
+    let mut line = 1;
+    let mut push = |from: usize, to: usize| {
+        let source = input[from..to].trim();
+        if source.is_empty() {
+            return;
+        }
+        let offset = from + (input[from..to].len() - input[from..to].trim_start().len());
+        line += input[counted..offset].matches('\n').count();
+        counted = offset;
+        invocations.push(Invocation { line, source });
+    };
+
+    while i < bytes.len() {
+        let rest = &input[i..];
+        let (separator, skip) = match bytes[i] {
+            // Single quotes are plain characters in cmd.
+            b'\'' if !cmd => {
+                let end = closing_quote(&rest[1..], b'\'', None);
+                i = end.map_or(bytes.len(), |end| i + end + 2);
+                (false, 0)
+            }
+            b'"' => {
+                let end = closing_quote(&rest[1..], b'"', (!cmd).then_some(escape));
+                i = end.map_or(bytes.len(), |end| i + end + 2);
+                (false, 0)
+            }
+            b'$' if dialect == Dialect::Bash && rest.starts_with("$'") => {
+                let end = closing_quote(&rest[2..], b'\'', Some(b'\\'));
+                i = end.map_or(bytes.len(), |end| i + end + 3);
+                (false, 0)
+            }
+            byte if byte == escape => {
+                // A line continuation keeps the invocation going.
+                let escaped = if rest[1..].starts_with("\r\n") {
+                    2
+                } else {
+                    rest[1..].chars().next().map_or(0, char::len_utf8)
+                };
+                i += 1 + escaped;
+                word_start = false;
+                line_start = false;
+                continue;
+            }
+            b'#' if word_start && !cmd => (true, rest.find('\n').unwrap_or(rest.len())),
+            b'r' | b'R' | b':'
+                if cmd && line_start && (starts_with_rem(rest) || rest.starts_with("::")) =>
+            {
+                (true, rest.find('\n').unwrap_or(rest.len()))
+            }
+            b';' => (true, 1),
+            b'&' | b'|' if rest[1..].starts_with(bytes[i] as char) => (true, 2),
+            b'&' if cmd => (true, 1),
+            b'\n' => (true, 1),
+            b'c' | b'C' if line_start && word_start && starts_with_curl(rest) => {
+                push(start, i);
+                start = i;
+                i += 4;
+                word_start = false;
+                line_start = false;
+                continue;
+            }
+            byte => {
+                let whitespace = byte.is_ascii_whitespace();
+                i += rest.chars().next().map_or(1, char::len_utf8);
+                word_start = whitespace;
+                line_start &= whitespace;
+                continue;
+            }
+        };
+
+        if separator {
+            push(start, i);
+            i += skip;
+            start = i;
+            word_start = true;
+            line_start = true;
+        } else {
+            word_start = false;
+            line_start = false;
+        }
+    }
+    push(start, bytes.len());
+    invocations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn sources(input: &str) -> Vec<&str> {
+        split_commands(input, Dialect::Bash)
+            .into_iter()
+            .map(|invocation| invocation.source)
+            .collect()
+    }
+
+    #[rstest]
+    #[case("curl a.com", vec!["curl a.com"])]
+    #[case("curl a.com\n\ncurl b.com\n", vec!["curl a.com", "curl b.com"])]
+    #[case("curl a.com && curl b.com; curl c.com", vec!["curl a.com", "curl b.com", "curl c.com"])]
+    #[case("curl a.com || curl b.com", vec!["curl a.com", "curl b.com"])]
+    #[case("curl a.com\ncurl b.com", vec!["curl a.com", "curl b.com"])]
+    #[case("curl a.com \\\n  -H 'X: 1'\nCURL b.com", vec!["curl a.com \\\n  -H 'X: 1'", "CURL b.com"])]
+    #[case("curl a.com -A curl", vec!["curl a.com -A curl"])]
+    #[case("curl a.com -d 'x;y && z\n\ncurl'", vec!["curl a.com -d 'x;y && z\n\ncurl'"])]
+    #[case(r#"curl a.com -d "a\";b""#, vec![r#"curl a.com -d "a\";b""#])]
+    #[case(r#"curl a.com -d $'a\';b'"#, vec![r#"curl a.com -d $'a\';b'"#])]
+    #[case("curl a.com -d a\\;b", vec!["curl a.com -d a\\;b"])]
+    #[case("# fetch a\ncurl a.com/#top # done\n", vec!["curl a.com/#top"])]
+    #[case("curl a.com -d 'open", vec!["curl a.com -d 'open"])]
+    #[case("  \n\n ;; ", vec![])]
+    #[case("wget a.com\ncurl b.com", vec!["wget a.com", "curl b.com"])]
+    #[case("curl a.com -o out.json\ncat out.json", vec!["curl a.com -o out.json", "cat out.json"])]
+    #[case("curl a.com \\\r\n  -v\r\ncurl b.com", vec!["curl a.com \\\r\n  -v", "curl b.com"])]
+    fn test_split_commands(#[case] input: &str, #[case] expected: Vec<&str>) {
+        assert_eq!(sources(input), expected);
+    }
+
+    #[rstest]
+    fn test_split_commands_lines() {
+        let input = "# header\ncurl a.com \\\n  -v\n\n\ncurl b.com; curl c.com\n";
+        let lines: Vec<usize> = split_commands(input, Dialect::Bash)
+            .into_iter()
+            .map(|invocation| invocation.line)
+            .collect();
+        assert_eq!(lines, vec![2, 6, 6]);
+    }
+
+    #[rstest]
+    #[case(
+        "curl.exe a.com `\n  -H 'X: 1'\ncurl.exe b.com",
+        Dialect::Auto,
+        vec!["curl.exe a.com `\n  -H 'X: 1'", "curl.exe b.com"]
+    )]
+    #[case(
+        "curl a.com `\r\n  -d \"a`\";b\"\r\ncurl b.com",
+        Dialect::PowerShell,
+        vec!["curl a.com `\r\n  -d \"a`\";b\"", "curl b.com"]
+    )]
+    #[case(
+        "rem tab: 1\ncurl ^\"https://a.com^\" ^\n  -H ^\"X: 1^\" &\r\ncurl b.com",
+        Dialect::Auto,
+        vec!["curl ^\"https://a.com^\" ^\n  -H ^\"X: 1^\"", "curl b.com"]
+    )]
+    #[case("curl a.com -d \"it's\" & curl b.com", Dialect::Cmd, vec!["curl a.com -d \"it's\"", "curl b.com"])]
+    fn test_split_commands_dialect(
+        #[case] input: &str,
+        #[case] dialect: Dialect,
+        #[case] expected: Vec<&str>,
+    ) {
+        let sources: Vec<_> = split_commands(input, dialect)
+            .into_iter()
+            .map(|invocation| invocation.source)
+            .collect();
+        assert_eq!(sources, expected);
+    }
+}
@@ -0,0 +1,279 @@
+use std::path::PathBuf;
+
+use super::ast::{Curl, CurlStru, OwnedCurl};
+use super::files::{FileResolver, ResolveError};
+
+/// Where the content of a `-d`-style argument comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BodySource {
+    /// The argument itself, e.g. `-d 'a=1'`.
+    Inline(String),
+    /// A file, e.g. `-d @payload.json`.
+    File(PathBuf),
+    /// Standard input, `-d @-`.
+    Stdin,
+}
+
+impl BodySource {
+    /// Read an `@file` reference, anything else being inline content.
+    fn at_reference(value: &str) -> Self {
+        match value.strip_prefix('@') {
+            Some("-") => BodySource::Stdin,
+            Some(path) if !path.is_empty() => BodySource::File(path.into()),
+            _ => BodySource::Inline(value.into()),
+        }
+    }
+}
+
+/// A data argument: where its content comes from and, for
+/// `--data-urlencode`, the name it is sent under.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DataArg {
+    pub name: Option<String>,
+    pub source: BodySource,
+}
+
+impl DataArg {
+    /// Interpret the argument of a data option the way curl does.
+    ///
+    /// `--data-raw` is always inline. `--data-urlencode` takes `content`,
+    /// `=content`, `name=content`, `@file` or `name@file`, whichever of `=`
+    /// and `@` comes first deciding.
+    pub fn parse(identifier: &str, value: &str) -> Self {
+        match identifier {
+            "--data-raw" => DataArg {
+                name: None,
+                source: BodySource::Inline(value.into()),
+            },
+            "--data-urlencode" => match value.find(['=', '@']) {
+                Some(at) if value.as_bytes()[at] == b'@' => DataArg {
+                    name: (at > 0).then(|| value[..at].into()),
+                    source: BodySource::at_reference(&value[at..]),
+                },
+                Some(eq) => DataArg {
+                    name: (eq > 0).then(|| value[..eq].into()),
+                    source: BodySource::Inline(value[eq + 1..].into()),
+                },
+                None => DataArg {
+                    name: None,
+                    source: BodySource::Inline(value.into()),
+                },
+            },
+            _ => DataArg {
+                name: None,
+                source: BodySource::at_reference(value),
+            },
+        }
+    }
+
+    /// The argument of a `Curl::Data` node.
+    pub fn from_stru(stru: &CurlStru) -> Self {
+        Self::parse(&stru.identifier, stru.data.as_deref().unwrap_or_default())
+    }
+}
+
+/// The node sending `contents` the way `stru` sends its file.
+fn inline(stru: &CurlStru, name: Option<&str>, contents: &str) -> OwnedCurl {
+    let (identifier, value) = match &*stru.identifier {
+        "--data-urlencode" => (
+            "--data-urlencode",
+            format!("{}={}", name.unwrap_or_default(), contents),
+        ),
+        // `--data-binary` and `--json` send the file as is, the others drop
+        // line breaks.
+        "--data-binary" => ("--data-raw", contents.into()),
+        "--json" => ("--json", contents.into()),
+        _ => ("--data-raw", contents.replace(['\r', '\n'], "")),
+    };
+    Curl::Data(CurlStru::new_with_data(identifier, value))
+}
+
+/// Replace every `@file` data argument by the contents of the file, read
+/// through `resolver`.
+///
+/// Arguments reading stdin or a file which isn't UTF-8 text are kept as
+/// references.
+pub fn inline_files(
+    curls: &[Curl<'_>],
+    resolver: &FileResolver,
+) -> Result<Vec<OwnedCurl>, ResolveError> {
+    curls
+        .iter()
+        .map(|curl| {
+            let Curl::Data(stru) = curl else {
+                return Ok(curl.clone().into_owned());
+            };
+            let DataArg {
+                name,
+                source: BodySource::File(path),
+            } = DataArg::from_stru(stru)
+            else {
+                return Ok(curl.clone().into_owned());
+            };
+            Ok(match String::from_utf8(resolver.read_file(&path)?) {
+                Ok(contents) => inline(stru, name.as_deref(), &contents),
+                Err(_) => curl.clone().into_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Join the contents of the data arguments of a command, given with their
+/// option, the way curl builds the body: with `&`, except that `--json`
+/// contents are appended as is.
+pub fn join_data<'s>(parts: impl IntoIterator<Item = (&'s str, String)>) -> Option<String> {
+    parts
+        .into_iter()
+        .reduce(|(_, mut body), (identifier, part)| {
+            if identifier != "--json" {
+                body.push('&');
+            }
+            body.push_str(&part);
+            ("", body)
+        })
+        .map(|(_, body)| body)
+}
+
+/// Indent the inline JSON data arguments, leaving the others as written.
+pub fn pretty_json(curls: &[Curl<'_>]) -> Vec<OwnedCurl> {
+    curls
+        .iter()
+        .map(|curl| {
+            let pretty = match curl {
+                Curl::Data(stru) if stru.identifier != "--data-urlencode" => {
+                    match DataArg::from_stru(stru).source {
+                        BodySource::Inline(value) => {
+                            serde_json::from_str::<serde_json::Value>(&value)
+                                .ok()
+                                .filter(|json| json.is_object() || json.is_array())
+                                .and_then(|json| serde_json::to_string_pretty(&json).ok())
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            match (curl, pretty) {
+                (Curl::Data(stru), Some(pretty)) => {
+                    Curl::Data(CurlStru::new_with_data(stru.identifier.to_string(), pretty))
+                }
+                _ => curl.clone().into_owned(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+
+    fn inline_source(value: &str) -> BodySource {
+        BodySource::Inline(value.into())
+    }
+
+    fn file(path: &str) -> BodySource {
+        BodySource::File(path.into())
+    }
+
+    #[rstest]
+    #[case("-d", "a=1", None, inline_source("a=1"))]
+    #[case("-d", "@payload.json", None, file("payload.json"))]
+    #[case("--data", "@-", None, BodySource::Stdin)]
+    #[case("--data-ascii", "@", None, inline_source("@"))]
+    #[case("--data-binary", "@-", None, BodySource::Stdin)]
+    #[case("--data-binary", "@img.png", None, file("img.png"))]
+    #[case("--data-raw", "@payload.json", None, inline_source("@payload.json"))]
+    #[case("--data-urlencode", "a b", None, inline_source("a b"))]
+    #[case("--data-urlencode", "=a@b", None, inline_source("a@b"))]
+    #[case("--data-urlencode", "q=a@b", Some("q"), inline_source("a@b"))]
+    #[case("--data-urlencode", "@notes.txt", None, file("notes.txt"))]
+    #[case("--data-urlencode", "q@notes.txt", Some("q"), file("notes.txt"))]
+    #[case("--data-urlencode", "q@-", Some("q"), BodySource::Stdin)]
+    fn test_data_arg_parse(
+        #[case] identifier: &str,
+        #[case] value: &str,
+        #[case] name: Option<&str>,
+        #[case] source: BodySource,
+    ) {
+        assert_eq!(
+            DataArg::parse(identifier, value),
+            DataArg {
+                name: name.map(Into::into),
+                source
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_inline_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("body.txt"), "a=1\r\n&b=2\n").unwrap();
+        std::fs::write(dir.path().join("bin.dat"), [0xff, 0x00]).unwrap();
+
+        let curls = curl_cmd_parse(concat!(
+            "curl 'https://a.com' -d @body.txt --data-binary @body.txt ",
+            "--data-urlencode q@body.txt --data-binary @bin.dat -d @- -d 'c=3'"
+        ))
+        .unwrap();
+        let resolver = FileResolver::new().with_base_dir(dir.path());
+        let inlined = inline_files(&curls, &resolver).unwrap();
+        assert_eq!(
+            to_curl_string(&inlined),
+            concat!(
+                "curl 'https://a.com' \\\n",
+                "  --data-raw 'a=1&b=2' \\\n",
+                "  --data-raw $'a=1\\r\\n&b=2\\n' \\\n",
+                "  --data-urlencode $'q=a=1\\r\\n&b=2\\n' \\\n",
+                "  --data-binary '@bin.dat' \\\n",
+                "  -d '@-' \\\n",
+                "  -d 'c=3'",
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_inline_files_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let curls = curl_cmd_parse("curl 'https://a.com' -d @missing.json").unwrap();
+        let resolver = FileResolver::new().with_base_dir(dir.path());
+        assert!(matches!(
+            inline_files(&curls, &resolver),
+            Err(ResolveError::Io { .. })
+        ));
+    }
+
+    #[rstest]
+    #[case(vec![("-d", "a=1"), ("--data-raw", "b=2")], Some("a=1&b=2"))]
+    #[case(vec![("--json", "{\"a\":"), ("--json", "1}")], Some("{\"a\":1}"))]
+    #[case(vec![("--json", "[1"), ("-d", "2]")], Some("[1&2]"))]
+    #[case(vec![], None)]
+    fn test_join_data(#[case] parts: Vec<(&str, &str)>, #[case] expected: Option<&str>) {
+        let parts = parts
+            .into_iter()
+            .map(|(identifier, part)| (identifier, part.to_string()));
+        assert_eq!(join_data(parts).as_deref(), expected);
+    }
+
+    #[rstest]
+    fn test_pretty_json() {
+        let curls = curl_cmd_parse(concat!(
+            r#"curl 'https://a.com' --json '{"a":[1]}' -d '42' -d @body.json "#,
+            r#"--data-urlencode '{"b":2}' -d '{oops'"#
+        ))
+        .unwrap();
+        assert_eq!(
+            to_curl_string(&pretty_json(&curls)),
+            concat!(
+                "curl 'https://a.com' \\\n",
+                "  --json $'{\\n  \"a\": [\\n    1\\n  ]\\n}' \\\n",
+                "  -d '42' \\\n",
+                "  -d '@body.json' \\\n",
+                "  --data-urlencode '{\"b\":2}' \\\n",
+                "  -d '{oops'",
+            )
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use super::ast::{Curl, CurlStru, OwnedCurl};
+use super::header::split_header;
+use super::options;
+use crate::diagnostic::{Code, Diagnostic};
+use crate::url::codec::UrlCodec;
+use crate::url::parser::CurlURL;
+
+/// Builds or rewrites a curl command in code, producing the same nodes the
+/// parsers do.
+///
+/// ```
+/// use winnowcurl::curl::builder::CurlBuilder;
+/// use winnowcurl::curl::parser::curl_cmd_parse;
+/// use winnowcurl::curl::render::to_curl_string;
+///
+/// let curls = CurlBuilder::new("https://example.com/api")
+///     .method("POST")
+///     .header("Accept", "*/*")
+///     .data(r#"{"a": 1}"#)
+///     .flag("--insecure")
+///     .build()?;
+/// assert_eq!(
+///     curls,
+///     curl_cmd_parse(r#"curl 'https://example.com/api' -X POST -H 'Accept: */*' -d '{"a": 1}' --insecure"#)?
+/// );
+///
+/// // Swap the host of a captured command and strip its cookies.
+/// let mut builder = CurlBuilder::from(curl_cmd_parse("curl 'https://prod.example.com/a' -H 'Cookie: s=1' -b 't=2'")?);
+/// builder.url_mut().unwrap().set_host("staging.example.com").unwrap();
+/// builder.remove_header("cookie").remove_option("--cookie");
+/// assert_eq!(to_curl_string(&builder.build()?), "curl 'https://staging.example.com/a'");
+/// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurlBuilder {
+    curls: Vec<OwnedCurl>,
+    /// The first argument which could not be added, reported by `build`.
+    error: Option<Diagnostic>,
+}
+
+impl CurlBuilder {
+    /// Start a command requesting `url`.
+    pub fn new(url: &str) -> Self {
+        CurlBuilder {
+            curls: vec![Curl::new_as_url_str(url).into_owned()],
+            error: None,
+        }
+    }
+
+    fn push(mut self, curl: Option<OwnedCurl>, what: impl FnOnce() -> String) -> Self {
+        self.add(curl, what);
+        self
+    }
+
+    fn add(&mut self, curl: Option<OwnedCurl>, what: impl FnOnce() -> String) {
+        match curl {
+            Some(curl) => self.curls.push(curl),
+            None => {
+                self.error
+                    .get_or_insert_with(|| Diagnostic::new(Code::InvalidOptionValue, what()));
+            }
+        }
+    }
+
+    /// Set the request method with `-X`, replacing any previous one.
+    pub fn method(mut self, method: &str) -> Self {
+        self.set_method(method);
+        self
+    }
+
+    /// Add a `-H` header.
+    pub fn header(self, name: &str, value: &str) -> Self {
+        let curl = (!name.is_empty()).then(|| header_node(name, value));
+        self.push(curl, || "A header has an empty name".into())
+    }
+
+    /// Add a `-d` data argument.
+    pub fn data(self, data: &str) -> Self {
+        self.push(Curl::new("-d", data.to_string()), || {
+            "The data is empty".into()
+        })
+    }
+
+    /// Add a flag, e.g. `--insecure` or `-L`.
+    pub fn flag(self, flag: &str) -> Self {
+        let known = options::lookup(flag).is_some_and(|spec| !spec.takes_value());
+        self.push(
+            known.then(|| Curl::Flag(CurlStru::new(flag.to_string()))),
+            || format!("{} is not a curl flag", flag),
+        )
+    }
+
+    /// Add an option taking an argument, e.g. `--max-time 30` or
+    /// `-F 'file=@a.png'`.
+    pub fn option(self, name: &str, value: &str) -> Self {
+        let curl = options::lookup(name)
+            .filter(|spec| spec.takes_value())
+            .and_then(|_| Curl::new(name, value))
+            .map(Curl::into_owned);
+        self.push(curl, || {
+            format!("{} is not a curl option taking `{}`", name, value)
+        })
+    }
+
+    /// The URL, to change its components in place with the setters of
+    /// [`CurlURL`]; `None` when the command has none.
+    pub fn url_mut(&mut self) -> Option<&mut CurlURL<'static>> {
+        self.curls.iter_mut().find_map(|curl| match curl {
+            Curl::URL(url) => Some(url),
+            _ => None,
+        })
+    }
+
+    /// Replace the URL, or add it when the command has none.
+    pub fn set_url(&mut self, url: &str) -> &mut Self {
+        let url = Curl::new_as_url_str(url).into_owned();
+        match self
+            .curls
+            .iter_mut()
+            .find(|curl| matches!(curl, Curl::URL(_)))
+        {
+            Some(curl) => *curl = url,
+            None => self.curls.insert(0, url),
+        }
+        self
+    }
+
+    /// Set the request method with `-X`, replacing any previous one.
+    pub fn set_method(&mut self, method: &str) -> &mut Self {
+        self.curls.retain(|curl| !matches!(curl, Curl::Method(_)));
+        self.add(Curl::new("-X", method.to_string()), || {
+            "The method is empty".into()
+        });
+        self
+    }
+
+    /// Set the header `name`, ignoring case: the first such header takes the
+    /// value and the others are removed. The header is added when missing.
+    pub fn set_header(&mut self, name: &str, value: &str) -> &mut Self {
+        let mut found = false;
+        self.curls.retain_mut(|curl| {
+            if !is_header(curl, name) {
+                return true;
+            }
+            if found {
+                return false;
+            }
+            found = true;
+            *curl = header_node(name, value);
+            true
+        });
+        if !found {
+            self.curls.push(header_node(name, value));
+        }
+        self
+    }
+
+    /// Remove every header called `name`, ignoring case.
+    pub fn remove_header(&mut self, name: &str) -> &mut Self {
+        self.curls.retain(|curl| !is_header(curl, name));
+        self
+    }
+
+    /// Remove every occurrence of an option or flag, given by its short or
+    /// long name, e.g. `-b` or `--cookie`.
+    pub fn remove_option(&mut self, name: &str) -> &mut Self {
+        let Some(spec) = options::lookup(name) else {
+            return self;
+        };
+        self.curls.retain(|curl| match curl {
+            Curl::Option { name, .. } => name != spec.long,
+            Curl::Flag(flag) => !options::flag_matches(&flag.identifier, spec.long),
+            Curl::Method(stru) | Curl::Header(stru) | Curl::Data(stru) => {
+                !spec.matches(&stru.identifier)
+            }
+            Curl::Form(field) => !spec.matches(&field.identifier),
+            Curl::URL(_) | Curl::Unknown(..) => true,
+        });
+        self
+    }
+
+    /// Remove every query parameter of the URL called `name`, compared
+    /// decoded.
+    pub fn remove_query_param(&mut self, name: &str) -> &mut Self {
+        let Some(url) = self.url_mut() else {
+            return self;
+        };
+        let url = url.to_string();
+        let (rest, fragment) = match url.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (url.as_str(), None),
+        };
+        let Some((base, query)) = rest.split_once('?') else {
+            return self;
+        };
+        let codec = UrlCodec::form();
+        let query: Vec<&str> = query
+            .split('&')
+            .filter(|pair| codec.decode(pair.split_once('=').map_or(*pair, |(key, _)| key)) != name)
+            .collect();
+        let mut rewritten = base.to_string();
+        if !query.is_empty() {
+            rewritten.push('?');
+            rewritten.push_str(&query.join("&"));
+        }
+        if let Some(fragment) = fragment {
+            rewritten.push('#');
+            rewritten.push_str(fragment);
+        }
+        self.set_url(&rewritten)
+    }
+
+    /// Append the query parameter `name=value` to the URL, both encoded.
+    pub fn add_query_param(&mut self, name: &str, value: &str) -> &mut Self {
+        let Some(url) = self.url_mut() else {
+            return self;
+        };
+        let url = url.to_string();
+        let (rest, fragment) = match url.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (url.as_str(), None),
+        };
+        let separator = match rest.split_once('?') {
+            Some((_, "")) => "",
+            Some(_) => "&",
+            None => "?",
+        };
+        let codec = UrlCodec::form();
+        let mut rewritten = format!(
+            "{}{}{}={}",
+            rest,
+            separator,
+            codec.encode(name),
+            codec.encode(value)
+        );
+        if let Some(fragment) = fragment {
+            rewritten.push('#');
+            rewritten.push_str(fragment);
+        }
+        self.set_url(&rewritten)
+    }
+
+    /// The nodes of the command, or the first argument which could not be
+    /// added or whose value curl would refuse.
+    pub fn build(&self) -> Result<Vec<OwnedCurl>, Diagnostic> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+        options::validate(&self.curls)?;
+        Ok(self.curls.clone())
+    }
+}
+
+/// Start from the nodes of a parsed command to rewrite it.
+impl From<Vec<Curl<'_>>> for CurlBuilder {
+    fn from(curls: Vec<Curl<'_>>) -> Self {
+        CurlBuilder {
+            curls: curls.into_iter().map(Curl::into_owned).collect(),
+            error: None,
+        }
+    }
+}
+
+fn header_node(name: &str, value: &str) -> OwnedCurl {
+    Curl::Header(CurlStru::new_with_data(
+        "-H",
+        format!("{}: {}", name, value),
+    ))
+}
+
+fn is_header(curl: &Curl, name: &str) -> bool {
+    matches!(curl, Curl::Header(stru)
+        if stru.data.as_deref().and_then(split_header).is_some_and(|(header, _)| header.eq_ignore_ascii_case(name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+
+    #[rstest]
+    fn test_build_matches_parser() {
+        let curls = CurlBuilder::new("https://example.com/api?a=1")
+            .method("PUT")
+            .method("POST")
+            .header("Accept", "*/*")
+            .data("a=1")
+            .flag("-L")
+            .option("--max-time", "30")
+            .option("-F", "f=@a.png")
+            .build()
+            .unwrap();
+        assert_eq!(
+            curls,
+            curl_cmd_parse(
+                "curl 'https://example.com/api?a=1' -X POST -H 'Accept: */*' -d 'a=1' -L --max-time 30 -F 'f=@a.png'"
+            )
+            .unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(CurlBuilder::new("a.com").method(""), Code::InvalidOptionValue)]
+    #[case(CurlBuilder::new("a.com").flag("--max-time"), Code::InvalidOptionValue)]
+    #[case(CurlBuilder::new("a.com").option("--bogus", "1"), Code::InvalidOptionValue)]
+    #[case(CurlBuilder::new("a.com").option("-m", "soon"), Code::InvalidOptionValue)]
+    fn test_build_errors(#[case] builder: CurlBuilder, #[case] expected: Code) {
+        assert_eq!(builder.build().unwrap_err().code, expected);
+    }
+
+    #[rstest]
+    fn test_mutations() {
+        let curls = curl_cmd_parse(concat!(
+            "curl 'https://a.com/x?token=t&page=2#top' -H 'Accept: text/html' ",
+            "-H 'accept: */*' -H 'Cookie: s=1' -b 'c=2' -v"
+        ))
+        .unwrap();
+        let mut builder = CurlBuilder::from(curls);
+        builder
+            .set_header("Accept", "application/json")
+            .set_header("X-Trace", "1")
+            .remove_header("COOKIE")
+            .remove_option("-b")
+            .remove_option("--verbose")
+            .remove_query_param("token");
+        assert_eq!(
+            to_curl_string(&builder.build().unwrap()),
+            concat!(
+                "curl 'https://a.com/x?page=2#top' \\\n",
+                "  -H 'Accept: application/json' \\\n",
+                "  -H 'X-Trace: 1'",
+            )
+        );
+
+        builder
+            .remove_query_param("page")
+            .set_url("https://b.com/y");
+        builder.url_mut().unwrap().set_port(Some(8443)).unwrap();
+        assert_eq!(
+            builder.build().unwrap()[0].to_string(),
+            "'https://b.com:8443/y'"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Mutex;
+
+use super::ast::OwnedCurl;
+use super::parser::curl_cmd_parse_owned;
+use crate::diagnostic::Diagnostic;
+
+/// An owned parse result, detached from the input.
+pub type ParseResult = Result<Vec<OwnedCurl>, Diagnostic>;
+
+#[derive(Debug)]
+struct Entry {
+    input: Box<str>,
+    result: ParseResult,
+    /// When the entry was last used, its key in [`Entries::order`].
+    generation: u64,
+}
+
+/// The cached entries, along with the order they were last used in: every
+/// use takes the next generation, so the oldest generation is the least
+/// recently used entry and each use costs a logarithmic time.
+#[derive(Debug, Default)]
+struct Entries {
+    by_key: HashMap<u64, Entry>,
+    /// Input hashes by the generation they were last used in.
+    order: BTreeMap<u64, u64>,
+    generation: u64,
+}
+
+impl Entries {
+    fn next_generation(&mut self) -> u64 {
+        self.generation += 1;
+        self.generation
+    }
+
+    /// The result cached for `input`, marked as the most recently used.
+    fn get(&mut self, key: u64, input: &str) -> Option<ParseResult> {
+        let generation = self.next_generation();
+        let entry = self
+            .by_key
+            .get_mut(&key)
+            .filter(|entry| *entry.input == *input)?;
+        self.order.remove(&entry.generation);
+        self.order.insert(generation, key);
+        entry.generation = generation;
+        Some(entry.result.clone())
+    }
+
+    /// Cache `result`, evicting the least recently used entry beyond
+    /// `capacity`.
+    fn insert(&mut self, key: u64, input: &str, result: ParseResult, capacity: usize) {
+        let generation = self.next_generation();
+        let entry = Entry {
+            input: input.into(),
+            result,
+            generation,
+        };
+        if let Some(replaced) = self.by_key.insert(key, entry) {
+            self.order.remove(&replaced.generation);
+        } else if self.by_key.len() > capacity
+            && let Some((_, oldest)) = self.order.pop_first()
+        {
+            self.by_key.remove(&oldest);
+        }
+        self.order.insert(generation, key);
+    }
+
+    fn len(&self) -> usize {
+        self.by_key.len()
+    }
+
+    fn clear(&mut self) {
+        self.by_key.clear();
+        self.order.clear();
+    }
+}
+
+/// Least recently used cache of parse results, for services that parse the
+/// same user-submitted commands over and over.
+///
+/// Entries are keyed by the hash of the input; the input itself is kept to
+/// rule out collisions. The cache can be shared between threads.
+#[derive(Debug)]
+pub struct ParseCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+fn hash_input(input: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl ParseCache {
+    /// A cache holding at most `capacity` results; zero disables caching.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Parse `input`, reusing the result of an earlier identical call.
+    pub fn parse(&self, input: &str) -> ParseResult {
+        let key = hash_input(input);
+
+        if let Some(result) = self.entries.lock().unwrap().get(key, input) {
+            return result;
+        }
+
+        let result = curl_cmd_parse_owned(input);
+        if self.capacity == 0 {
+            return result;
+        }
+
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key, input, result.clone(), self.capacity);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_parse_cache_reuses_results() {
+        let cache = ParseCache::new(2);
+        let input = String::from("curl 'https://example.com' -H 'Accept: */*'");
+        let first = cache.parse(&input);
+        drop(input);
+
+        let second = cache.parse("curl 'https://example.com' -H 'Accept: */*'");
+        assert_eq!(first, second);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[rstest]
+    fn test_parse_cache_evicts_least_recently_used() {
+        let cache = ParseCache::new(2);
+        for host in ["a", "b", "a", "c"] {
+            assert!(cache.parse(&format!("curl 'https://{}.com'", host)).is_ok());
+        }
+
+        let entries = cache.entries.lock().unwrap();
+        let inputs: Vec<&str> = entries
+            .order
+            .values()
+            .map(|key| &*entries.by_key[key].input)
+            .collect();
+        assert_eq!(inputs, vec!["curl 'https://a.com'", "curl 'https://c.com'"]);
+    }
+
+    #[rstest]
+    fn test_parse_cache_keeps_errors() {
+        let cache = ParseCache::new(1);
+        assert!(cache.parse("wget 'https://a.com'").is_err());
+        assert!(cache.parse("wget 'https://a.com'").is_err());
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[rstest]
+    fn test_parse_cache_disabled() {
+        let cache = ParseCache::new(0);
+        assert!(cache.parse("curl 'https://a.com'").is_ok());
+        assert!(cache.is_empty());
+    }
+}
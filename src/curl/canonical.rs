@@ -0,0 +1,236 @@
+use super::ast::{Curl, CurlStru, OwnedCurl};
+use super::header::split_header;
+use super::options;
+use crate::url::normalize::{VolatileParams, normalize_url};
+
+/// The long name of an option as written, e.g. `--header` for `-H`.
+fn long_name(identifier: &str) -> String {
+    options::lookup(identifier).map_or_else(|| identifier.to_string(), |spec| spec.long.into())
+}
+
+/// A header name with each word capitalized, e.g. `Content-Type`.
+fn title_case(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// A `-H` argument with its name title-cased, written `Name: value`, or
+/// `Name:` and `Name;` for headers removed or sent empty.
+fn header_line(raw: &str) -> String {
+    let Some(end) = raw.find([':', ';']) else {
+        return raw.to_string();
+    };
+    let (name, rest) = raw.split_at(end);
+    let name = title_case(name.trim());
+    if name.is_empty() {
+        return raw.to_string();
+    }
+    match split_header(raw) {
+        Some((_, value)) if rest.starts_with(':') && !value.is_empty() => {
+            format!("{}: {}", name, value)
+        }
+        Some(_) => format!("{}:", name),
+        None if rest == ";" => format!("{};", name),
+        None => raw.to_string(),
+    }
+}
+
+/// The node with its option under its long name and its argument in
+/// canonical form; flag clusters like `-sSL` give one node per flag.
+fn canonical_nodes(curl: &Curl) -> Vec<OwnedCurl> {
+    match curl {
+        Curl::URL(url) => {
+            let url = normalize_url(&url.to_string(), &VolatileParams::none());
+            vec![Curl::new_as_url_str(&url).into_owned()]
+        }
+        Curl::Method(stru) => vec![Curl::Method(CurlStru {
+            identifier: long_name(&stru.identifier).into(),
+            data: stru.data.clone().map(|data| data.into_owned().into()),
+        })],
+        Curl::Header(stru) => vec![Curl::Header(CurlStru {
+            identifier: long_name(&stru.identifier).into(),
+            data: stru.data.as_deref().map(|raw| header_line(raw).into()),
+        })],
+        Curl::Data(stru) => vec![Curl::Data(CurlStru {
+            identifier: long_name(&stru.identifier).into(),
+            data: stru.data.clone().map(|data| data.into_owned().into()),
+        })],
+        Curl::Form(field) => {
+            let mut field = field.clone();
+            field.identifier = long_name(&field.identifier);
+            vec![Curl::Form(field)]
+        }
+        Curl::Flag(stru) => options::long_flag_names(&stru.identifier)
+            .into_iter()
+            .map(|name| Curl::Flag(CurlStru::new(name)))
+            .collect(),
+        curl => vec![curl.clone().into_owned()],
+    }
+}
+
+/// Where a node goes: the method, headers by name, data and form fields in
+/// their order, then the other options by name, a `--no-` flag next to the
+/// flag it turns off so that the last one still wins.
+fn sort_key(curl: &Curl) -> (u8, String) {
+    match curl {
+        Curl::URL(_) => (0, String::new()),
+        Curl::Method(_) => (1, String::new()),
+        Curl::Header(stru) => {
+            let raw = stru.data.as_deref().unwrap_or_default();
+            let name = raw.split([':', ';']).next().unwrap_or_default();
+            (2, name.trim().to_ascii_lowercase())
+        }
+        Curl::Data(_) => (3, String::new()),
+        Curl::Form(_) => (4, String::new()),
+        Curl::Flag(CurlStru {
+            identifier: name, ..
+        })
+        | Curl::Option { name, .. } => {
+            let name = name.strip_prefix("--no-").unwrap_or(name);
+            (5, name.trim_start_matches('-').to_string())
+        }
+        Curl::Unknown(..) => (6, String::new()),
+    }
+}
+
+/// Rewrite a command into a canonical form, so that equivalent commands
+/// render identically with [`to_curl_string`]: options under their long
+/// names, flag clusters split, URLs normalized with [`normalize_url`] and
+/// headers written `Name: value` with their names title-cased, then sorted
+/// as follows.
+///
+/// Headers come by name and options other than data and form fields by
+/// long name, keeping their order when they share it, e.g. repeated
+/// headers or options of which the last one wins; repeated flags are given
+/// once. Each part of a command separated by `--next` is sorted on its own.
+///
+/// ```
+/// use winnowcurl::curl::canonical::canonicalize;
+/// use winnowcurl::curl::parser::curl_cmd_parse;
+/// use winnowcurl::curl::render::to_curl_string;
+///
+/// let a = curl_cmd_parse("curl -sL -H 'X-B:2' HTTPS://A.com:443 -H 'accept: */*'")?;
+/// let b = curl_cmd_parse("curl 'https://a.com/' --header 'Accept: */*' --location -s -H 'X-B: 2'")?;
+/// assert_eq!(to_curl_string(&canonicalize(&a)), to_curl_string(&canonicalize(&b)));
+/// assert_eq!(
+///     to_curl_string(&canonicalize(&a)),
+///     "curl 'https://a.com/' \\\n  --header 'Accept: */*' \\\n  --header 'X-B: 2' \\\n  --location \\\n  --silent"
+/// );
+/// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+/// ```
+///
+/// [`to_curl_string`]: super::render::to_curl_string
+pub fn canonicalize(curls: &[Curl]) -> Vec<OwnedCurl> {
+    let nodes: Vec<OwnedCurl> = curls.iter().flat_map(canonical_nodes).collect();
+    let mut canonical = Vec::with_capacity(nodes.len());
+    let is_next =
+        |curl: &OwnedCurl| matches!(curl, Curl::Flag(flag) if flag.identifier == "--next");
+    for segment in nodes.split_inclusive(is_next) {
+        let (next, segment) = match segment.split_last() {
+            Some((last, rest)) if is_next(last) => (Some(last), rest),
+            _ => (None, segment),
+        };
+        let mut segment = segment.to_vec();
+        segment.sort_by_cached_key(sort_key);
+        segment.dedup_by(|a, b| matches!(a, Curl::Flag(_)) && a == b);
+        canonical.extend(segment);
+        canonical.extend(next.cloned());
+    }
+    canonical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+
+    fn canonical(input: &str) -> String {
+        to_curl_string(&canonicalize(&curl_cmd_parse(input).unwrap()))
+    }
+
+    #[rstest]
+    #[case("Accept:*/*", "Accept: */*")]
+    #[case(" x-request-ID :  b ", "X-Request-Id: b")]
+    #[case("x-removed:", "X-Removed:")]
+    #[case("x-empty;", "X-Empty;")]
+    #[case("not a header", "not a header")]
+    fn test_header_line(#[case] raw: &str, #[case] expected: &str) {
+        assert_eq!(header_line(raw), expected);
+    }
+
+    #[rstest]
+    fn test_canonicalize() {
+        assert_eq!(
+            canonical(concat!(
+                "curl -m 5 -vk -X POST 'HTTP://Example.com:80/p?b=2&a=1#top' ",
+                "-H 'X-Z: 1' -d 'z=1' -H 'x-a:2' -d 'a=2' -F 'f=1' --insecure -H 'X-Z: 0'"
+            )),
+            concat!(
+                "curl 'http://example.com/p?a=1&b=2' \\\n",
+                "  --request 'POST' \\\n",
+                "  --header 'X-A: 2' \\\n",
+                "  --header 'X-Z: 1' \\\n",
+                "  --header 'X-Z: 0' \\\n",
+                "  --data 'z=1' \\\n",
+                "  --data 'a=2' \\\n",
+                "  --form 'f=1' \\\n",
+                "  --insecure \\\n",
+                "  --max-time '5' \\\n",
+                "  --verbose",
+            )
+        );
+    }
+
+    #[rstest]
+    #[case("curl https://a.com --no-location -L -m 1 -m 2")]
+    #[case("curl https://a.com -sSL --compressed -H 'A: 1' -H 'a: 2'")]
+    #[case("curl https://a.com/x -d 'a' --next -k")]
+    fn test_canonicalize_is_idempotent(#[case] input: &str) {
+        let once = canonical(input);
+        assert_eq!(canonical(&once), once);
+    }
+
+    #[rstest]
+    fn test_last_option_still_wins() {
+        assert_eq!(
+            canonical("curl https://a.com --no-location -L -m 2 --compressed -m 1"),
+            concat!(
+                "curl 'https://a.com/' \\\n",
+                "  --compressed \\\n",
+                "  --no-location \\\n",
+                "  --location \\\n",
+                "  --max-time '2' \\\n",
+                "  --max-time '1'",
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_next_parts_are_sorted_apart() {
+        let curls = canonicalize(
+            &curl_cmd_parse("curl https://a.com -v -k --next -H 'B: 1' -s -H 'A: 1'").unwrap(),
+        );
+        let rendered: Vec<String> = curls.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered,
+            [
+                "'https://a.com/'",
+                "--insecure",
+                "--verbose",
+                "--next",
+                "--header 'A: 1'",
+                "--header 'B: 1'",
+                "--silent",
+            ]
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use std::path::{Path, PathBuf};
+
+/// Limits applied when a parsed command is allowed to touch the filesystem.
+///
+/// The defaults impose no restriction. Services handling untrusted commands
+/// should set a sandbox root, so `@../../etc/passwd` and absolute paths
+/// cannot escape it, and a maximum file size, so `@/dev/zero` cannot
+/// exhaust memory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParserConfig {
+    sandbox_root: Option<PathBuf>,
+    max_file_size: Option<u64>,
+}
+
+impl ParserConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only allow files below `root`; relative paths are taken from it.
+    pub fn with_sandbox_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.sandbox_root = Some(root.into());
+        self
+    }
+
+    /// Refuse files larger than `bytes`.
+    pub fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    pub fn sandbox_root(&self) -> Option<&Path> {
+        self.sandbox_root.as_deref()
+    }
+
+    pub fn max_file_size(&self) -> Option<u64> {
+        self.max_file_size
+    }
+}
@@ -1,20 +1,20 @@
 use nom::{
+    IResult,
     branch::alt,
-    bytes::complete::{tag, take_until},
+    bytes::complete::{tag, take_until, take_while1},
     character::{
         self,
         complete::{alphanumeric0, anychar, char, multispace0, multispace1},
     },
-    combinator::{map, map_res, opt, peek, recognize, rest},
-    error::{context, Error, ErrorKind},
+    combinator::{map, map_opt, map_res, opt, peek, recognize, rest, verify},
+    error::{Error, ErrorKind, context},
     multi::fold_many0,
     sequence::{delimited, preceded, tuple},
-    IResult,
 };
 
-use crate::curl::Curl;
+use std::borrow::Cow;
 
-use super::url_parser;
+use crate::curl::{Curl, lexer, options};
 
 const CURL_CMD: &str = "curl";
 pub fn is_curl(input: &str) -> bool {
@@ -25,20 +25,15 @@ pub fn remove_curl_cmd_header(input: &str) -> &str {
     &input[4..]
 }
 
-pub fn url_parse(input: &str) -> IResult<&str, Curl> {
+pub fn url_parse(input: &str) -> IResult<&str, Curl<'_>> {
     context(
         "url parse",
         preceded(
             multispace0,
-            map_res(quoted_data_parse, |d| {
-                // let url_parsed = url::Url::parse(d);
-                let url_parsed = url_parser::curl_url_parse(d);
-                if let Ok((_, u)) = url_parsed {
-                    Ok(Curl::new_as_url(u))
-                } else {
-                    Err(url_parsed)
-                }
-            }),
+            map(
+                verify(quoted_data_parse, |url: &str| !url.starts_with('-')),
+                Curl::new_as_url_word,
+            ),
         ),
     )(input)
 }
@@ -79,36 +74,20 @@ fn single_quoted_data_parse(input: &str) -> IResult<&str, &str> {
     )(input)
 }
 
-/// Get the longest one quoted data between single / double quoted data.
-fn quoted_data_parse<'a>(input: &str) -> IResult<&str, &str> {
-    let double_res = double_quoted_data_parse(input);
-    let single_res = single_quoted_data_parse(input);
-
-    if double_res.is_ok() && single_res.is_ok() {
-        let (_double_rest, double_data) = double_res.unwrap();
-        let (_single_rest, single_data) = single_res.unwrap();
-
-        if double_data.len() >= single_data.len() {
-            Ok((_double_rest, double_data))
-        } else {
-            Ok((_single_rest, single_data))
+/// Get the value of an argument, quoted or not.
+fn quoted_data_parse(input: &str) -> IResult<&str, Cow<'_, str>> {
+    let (input, _) = multispace0(input)?;
+    match lexer::shell_word(input) {
+        Ok((word, len)) => {
+            let (rest, _) = multispace0(&input[len..])?;
+            Ok((rest, word))
         }
-    } else if double_res.is_err() && single_res.is_ok() {
-        single_res
-    } else if double_res.is_ok() && single_res.is_err() {
-        double_res
-    } else {
-        // Both parsing failed, return an error
-        let _single_err = single_res.unwrap_err();
-        let _double_err = double_res.unwrap_err();
-
-        #[cfg(feature = "debug-print")]
-        eprintln!(
-            "The origin: ({})\r\nThe single parse error: {}\r\nThe double parse error: {}",
-            input, _single_err, _double_err
-        );
+        Err(_err) => {
+            #[cfg(feature = "debug-print")]
+            eprintln!("The origin: ({})\r\nThe parse error: {:?}", input, _err);
 
-        Err(nom::Err::Failure(Error::new(&input, ErrorKind::Fail)))
+            Err(nom::Err::Error(Error::new(input, ErrorKind::Fail)))
+        }
     }
 }
 
@@ -129,7 +108,7 @@ pub fn iter_quoted_data_parse(input: &str) -> IResult<&str, Vec<String>> {
 #[macro_export]
 macro_rules! parse_command {
     ($name:ident,$($tag:expr),+) => {
-        pub fn $name(input: &str) -> IResult<&str, Curl> {
+        pub fn $name(input: &str) -> IResult<&str, Curl<'_>> {
             context(
                 stringify!($name),
                 preceded(
@@ -147,7 +126,7 @@ macro_rules! parse_command {
 #[macro_export]
 macro_rules! parse_commands {
     ($name:ident,$inner_func:ident) => {
-        pub fn $name(input: &str) -> IResult<&str, Vec<Curl>> {
+        pub fn $name(input: &str) -> IResult<&str, Vec<Curl<'_>>> {
             context(
                 stringify!($name),
                 fold_many0($inner_func, Vec::new, |mut acc: Vec<Curl>, m| {
@@ -167,32 +146,56 @@ parse_command!(data_parse, "-d", "--data");
 parse_commands!(datas_parse, data_parse);
 parse_commands!(flags_parse, flag_parse);
 
-pub fn flag_parse(input: &str) -> IResult<&str, Curl> {
+/// Identify an option name like `-u`, `-sSL` or `--max-time`
+pub fn option_name(input: &str) -> IResult<&str, &str> {
     context(
-        "flag parse",
+        "option name",
+        alt((
+            recognize(tuple((
+                tag("--"),
+                take_while1(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '.'),
+            ))),
+            recognize(tuple((char('-'), anychar, alphanumeric0))),
+        )),
+    )(input)
+}
+
+pub fn option_parse(input: &str) -> IResult<&str, Curl<'_>> {
+    context(
+        "option parse",
         preceded(
             opt(slash_line_ending),
-            map_res(
+            map_opt(
                 tuple((
-                    tuple((
-                        multispace0,
-                        character::complete::char('-'),
-                        anychar,
-                        alphanumeric0,
-                    )),
-                    peek(rest),
+                    multispace0,
+                    verify(option_name, |name: &str| {
+                        options::lookup(name).is_some_and(|spec| spec.takes_value())
+                    }),
+                    multispace1,
+                    quoted_data_parse,
                 )),
-                |(flag, r)| {
-                    let followed_by_data = quoted_data_parse(r);
-                    match followed_by_data.is_err() {
-                        true => {
-                            let (_, a, b, c) = flag;
-                            let flag = format!("{}{}{}", a, b, c);
-                            match Curl::new_as_flag(&flag) {
-                                Some(f) => Ok(f),
-                                None => Err(nom::Err::Failure(Error::new(r, ErrorKind::Fail))),
-                            }
-                        }
+                |(_, name, _, value)| Curl::new(name, value),
+            ),
+        ),
+    )(input)
+}
+
+pub fn flag_parse(input: &str) -> IResult<&str, Curl<'_>> {
+    context(
+        "flag parse",
+        preceded(
+            opt(slash_line_ending),
+            map_res(
+                tuple((preceded(multispace0, option_name), peek(rest))),
+                |(flag, r): (&str, &str)| {
+                    let followed_by_quote = r
+                        .trim_start_matches([' ', '\t', '\r', '\n'])
+                        .starts_with(['"', '\'']);
+                    match !followed_by_quote {
+                        true => match Curl::new_as_flag(flag) {
+                            Some(f) => Ok(f),
+                            None => Err(nom::Err::Failure(Error::new(r, ErrorKind::Fail))),
+                        },
                         false => {
                             // Success parsed the quote data
                             Err(nom::Err::Failure(Error::new(r, ErrorKind::Fail)))
@@ -204,22 +207,62 @@ pub fn flag_parse(input: &str) -> IResult<&str, Curl> {
     )(input)
 }
 
-pub fn commands_parse(input: &str) -> IResult<&str, Vec<Curl>> {
+/// Parse short options curl reads together, a cluster ending with an
+/// option taking a value such as `-sSLo out.json`, or an option with its
+/// value attached such as `-XPOST`.
+pub fn short_options_parse(input: &str) -> IResult<&str, Vec<Curl<'_>>> {
+    let fail = |input| nom::Err::Error(Error::new(input, ErrorKind::Fail));
+    let (input, _) = tuple((opt(slash_line_ending), multispace0))(input)?;
+    let Some((at, spec)) = options::short_value_option(input) else {
+        return Err(fail(input));
+    };
+    let attached = input[at + 1..].starts_with(|c: char| !c.is_whitespace());
+    if at == 1 && !attached {
+        return Err(fail(input));
+    }
+
+    let rest = &input[at + 1..];
+    let (rest, value) = if attached {
+        quoted_data_parse(rest)?
+    } else {
+        preceded(multispace1, quoted_data_parse)(rest)?
+    };
+    let option = Curl::new(spec.short.unwrap_or(spec.long), value).ok_or_else(|| fail(input))?;
+    let mut curls = Vec::with_capacity(2);
+    if at > 1 {
+        curls.extend(Curl::new_as_flag(&input[..at]));
+    }
+    curls.push(option);
+    Ok((rest, curls))
+}
+
+pub fn commands_parse(input: &str) -> IResult<&str, Vec<Curl<'_>>> {
     context(
         "all commands parse",
         fold_many0(
-            alt((method_parse, header_parse, data_parse, flag_parse)),
+            alt((
+                short_options_parse,
+                map(
+                    alt((
+                        method_parse,
+                        header_parse,
+                        data_parse,
+                        option_parse,
+                        flag_parse,
+                    )),
+                    |curl| vec![curl],
+                ),
+            )),
             Vec::new,
-            |mut acc, d| {
-                acc.push(d);
-                // acc.append(&mut d);
+            |mut acc, mut d| {
+                acc.append(&mut d);
                 acc
             },
         ),
     )(input)
 }
 
-pub fn curl_cmd_parse(input: &str) -> IResult<&str, Vec<Curl>> {
+pub fn curl_cmd_parse(input: &str) -> IResult<&str, Vec<Curl<'_>>> {
     if is_curl(input) {
         let mut curl_cmds = Vec::new();
         let input = remove_curl_cmd_header(input.trim_start()); // Remove Curl header firstly
@@ -252,7 +295,7 @@ pub fn curl_cmd_parse(input: &str) -> IResult<&str, Vec<Curl>> {
             )))
         }
     } else {
-        Err(nom::Err::Error(Error::new(&input, ErrorKind::Fail)))
+        Err(nom::Err::Error(Error::new(input, ErrorKind::Fail)))
     }
 }
 
@@ -260,8 +303,8 @@ pub fn curl_cmd_parse(input: &str) -> IResult<&str, Vec<Curl>> {
 mod tests {
     use nom::InputTake;
     // use url::Url;
+    use crate::new_curl;
     use crate::test_util::generic_command_parse;
-    use crate::{curl::url_parser, new_curl};
 
     use super::*;
 
@@ -285,7 +328,7 @@ mod tests {
         }
     }
 
-    const TEST_CURL_CMD_FULL: &'static str = r#"
+    const TEST_CURL_CMD_FULL: &str = r#"
         curl 'http://query.sse.com.cn/commonQuery.do?jsonCallBack=jsonpCallback89469743&sqlId=COMMON_SSE_SJ_GPSJ_CJGK_MRGK_C&PRODUCT_CODE=01%2C02%2C03%2C11%2C17&type=inParams&SEARCH_DATE=2024-03-18&_=1710914422498'  \
         -H 'Accept: */*' -X 'TEST' \
         -H 'Accept-Language: en-US,en;q=0.9,zh-CN;q=0.8,zh;q=0.7' --b \
@@ -303,33 +346,29 @@ mod tests {
     #[test]
     fn test_curl_cmd_parse() {
         let full_url_str = "http://query.sse.com.cn/commonQuery.do?jsonCallBack=jsonpCallback89469743&sqlId=COMMON_SSE_SJ_GPSJ_CJGK_MRGK_C&PRODUCT_CODE=01%2C02%2C03%2C11%2C17&type=inParams&SEARCH_DATE=2024-03-18&_=1710914422498";
-        // let expect_url = match Url::parse(full_url_str) {
-        //     Ok(u) => u,
-        //     Err(e) => panic!("Error: {:?}", e),
-        // };
-
-        let (_, expect_url) = match url_parser::curl_url_parse(full_url_str) {
-            Ok(u) => u,
-            Err(e) => panic!("Error: {:?}", e),
-        };
-
-        let _url = Curl::new_as_url(expect_url);
+        let _url = Curl::new_as_url_str(full_url_str);
 
         let input = TEST_CURL_CMD_FULL;
         let expect = vec![
             _url,
-            new_curl!(-H,"Accept: */*"),
-            new_curl!(-X,"TEST"),
-            new_curl!(-H,"Accept-Language: en-US,en;q=0.9,zh-CN;q=0.8,zh;q=0.7"),
+            new_curl!(-H, "Accept: */*"),
+            new_curl!(-X, "TEST"),
+            new_curl!(-H, "Accept-Language: en-US,en;q=0.9,zh-CN;q=0.8,zh;q=0.7"),
             new_curl!("--b"),
-            new_curl!(-H,"Cache-Control: no-cache"),
-            new_curl!(-H,"Connection: keep-alive"),
-            new_curl!(-d,"data1:90"),
-            new_curl!(-d,"data2:90/i9fi0sdfsdfk\\\\jfhaoe"),
-            new_curl!(-H,"Cookie: gdp_user_id=gioenc-c2b256a9%2C5442%2C561b%2C9c02%2C71199e7e89g9; VISITED_MENU=%5B%228312%22%5D; ba17301551dcbaf9_gdp_session_id=2e27fee0-b184-4efa-a66f-f651e5be47e0; ba17301551dcbaf9_gdp_session_id_sent=2e27fee0-b184-4efa-a66f-f651e5be47e0; ba17301551dcbaf9_gdp_sequence_ids={%22globalKey%22:139%2C%22VISIT%22:4%2C%22PAGE%22:18%2C%22VIEW_CLICK%22:117%2C%22VIEW_CHANGE%22:3}"),
-            new_curl!(-H,"Pragma: no-cache"),
-            new_curl!(-H,"Referer: http://www.sse.com.cn/"),
-            new_curl!(-H,"User-Agent: Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36"),
+            new_curl!(-H, "Cache-Control: no-cache"),
+            new_curl!(-H, "Connection: keep-alive"),
+            new_curl!(-d, "data1:90"),
+            Curl::new("--data", "data2:90/i9fi0sdfsdfk\\\\jfhaoe").unwrap(),
+            new_curl!(
+                -H,
+                "Cookie: gdp_user_id=gioenc-c2b256a9%2C5442%2C561b%2C9c02%2C71199e7e89g9; VISITED_MENU=%5B%228312%22%5D; ba17301551dcbaf9_gdp_session_id=2e27fee0-b184-4efa-a66f-f651e5be47e0; ba17301551dcbaf9_gdp_session_id_sent=2e27fee0-b184-4efa-a66f-f651e5be47e0; ba17301551dcbaf9_gdp_sequence_ids={%22globalKey%22:139%2C%22VISIT%22:4%2C%22PAGE%22:18%2C%22VIEW_CLICK%22:117%2C%22VIEW_CHANGE%22:3}"
+            ),
+            new_curl!(-H, "Pragma: no-cache"),
+            new_curl!(-H, "Referer: http://www.sse.com.cn/"),
+            new_curl!(
+                -H,
+                "User-Agent: Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36"
+            ),
             new_curl!("--insecure"),
         ];
 
@@ -339,7 +378,7 @@ mod tests {
     #[test]
     fn test_is_curl() {
         let cmd = "\t \r  \n Curl asdjfnv\n";
-        assert!(is_curl(&cmd));
+        assert!(is_curl(cmd));
         let cmd = cmd.trim().to_uppercase();
         assert!(is_curl(&cmd));
     }
@@ -357,38 +396,34 @@ mod tests {
     #[test]
     fn test_url_parse() {
         let full_url_str = "http://query.sse.com.cn/commonQuery.do?jsonCallBack=jsonpCallback89469743&sqlId=COMMON_SSE_SJ_GPSJ_CJGK_MRGK_C&PRODUCT_CODE=01%2C02%2C03%2C11%2C17&type=inParams&SEARCH_DATE=2024-03-18&_=1710914422498";
-        let (_, expect_url) = match url_parser::curl_url_parse(full_url_str) {
-            Ok(u) => u,
-            Err(e) => panic!("Error: {:?}", e),
-        };
-
-        // let expect_url = match Url::parse(full_url_str) {
-        //     Ok(u) => u,
-        //     Err(e) => panic!("Error: {:?}", e),
-        // };
-
-        let expect = Curl::new_as_url(expect_url);
+        let expect = Curl::new_as_url_str(full_url_str);
         let input = format!(" curl \r \t   '{}' \\ \r\n-H 'Accept: */*'", full_url_str);
-        let input = remove_curl_cmd_header(&input.trim_start());
+        let input = remove_curl_cmd_header(input.trim_start());
 
-        generic_command_parse(url_parse, &input, expect);
+        generic_command_parse(url_parse, input, expect);
     }
 
     #[test]
     fn test_commands_parse() {
         let expect = vec![
-            new_curl!(-H,"Accept: */*"),
-            new_curl!(-X,"TEST"),
-            new_curl!(-H,"Accept-Language: en-US,en;q=0.9,zh-CN;q=0.8,zh;q=0.7"),
+            new_curl!(-H, "Accept: */*"),
+            new_curl!(-X, "TEST"),
+            new_curl!(-H, "Accept-Language: en-US,en;q=0.9,zh-CN;q=0.8,zh;q=0.7"),
             new_curl!("--b"),
-            new_curl!(-H,"Cache-Control: no-cache"),
-            new_curl!(-H,"Connection: keep-alive"),
-            new_curl!(-d,"data1:90"),
-            new_curl!(-d,"data2:90/i9fi0sdfsdfk\\\\jfhaoe"),
-            new_curl!(-H,"Cookie: gdp_user_id=gioenc-c2b256a9%2C5442%2C561b%2C9c02%2C71199e7e89g9; VISITED_MENU=%5B%228312%22%5D; ba17301551dcbaf9_gdp_session_id=2e27fee0-b184-4efa-a66f-f651e5be47e0; ba17301551dcbaf9_gdp_session_id_sent=2e27fee0-b184-4efa-a66f-f651e5be47e0; ba17301551dcbaf9_gdp_sequence_ids={%22globalKey%22:139%2C%22VISIT%22:4%2C%22PAGE%22:18%2C%22VIEW_CLICK%22:117%2C%22VIEW_CHANGE%22:3}"),
-            new_curl!(-H,"Pragma: no-cache"),
-            new_curl!(-H,"Referer: http://www.sse.com.cn/"),
-            new_curl!(-H,"User-Agent: Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36"),
+            new_curl!(-H, "Cache-Control: no-cache"),
+            new_curl!(-H, "Connection: keep-alive"),
+            new_curl!(-d, "data1:90"),
+            Curl::new("--data", "data2:90/i9fi0sdfsdfk\\\\jfhaoe").unwrap(),
+            new_curl!(
+                -H,
+                "Cookie: gdp_user_id=gioenc-c2b256a9%2C5442%2C561b%2C9c02%2C71199e7e89g9; VISITED_MENU=%5B%228312%22%5D; ba17301551dcbaf9_gdp_session_id=2e27fee0-b184-4efa-a66f-f651e5be47e0; ba17301551dcbaf9_gdp_session_id_sent=2e27fee0-b184-4efa-a66f-f651e5be47e0; ba17301551dcbaf9_gdp_sequence_ids={%22globalKey%22:139%2C%22VISIT%22:4%2C%22PAGE%22:18%2C%22VIEW_CLICK%22:117%2C%22VIEW_CHANGE%22:3}"
+            ),
+            new_curl!(-H, "Pragma: no-cache"),
+            new_curl!(-H, "Referer: http://www.sse.com.cn/"),
+            new_curl!(
+                -H,
+                "User-Agent: Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36"
+            ),
             new_curl!("--insecure"),
         ];
 
@@ -437,7 +472,7 @@ mod tests {
     fn test_quoted_data_parse() {
         let expect = " hhdf,\\fjsdfjl**''";
         let input = format!("\t \r  \n \n \"{}\" woaini \" \r \n 'nmihao'", expect);
-        generic_command_parse(quoted_data_parse, &input, expect);
+        generic_command_parse(quoted_data_parse, &input, Cow::from(expect));
     }
 
     #[test]
@@ -464,7 +499,7 @@ mod tests {
     fn test_datas_parse() {
         let expect = vec![
             new_curl!(-d, "AJFjfdslf"),
-            new_curl!(-d, "abc fjdfl  ii\\hhfjsdkf:90"),
+            Curl::new("--data", "abc fjdfl  ii\\hhfjsdkf:90").unwrap(),
         ];
         let input = "\t \r  \n -d \"AJFjfdslf\" --data \"abc fjdfl  ii\\hhfjsdkf:90\" \t\r jflksfl";
         generic_command_parse(datas_parse, input, expect);
@@ -0,0 +1,265 @@
+use std::path::Path;
+
+use super::ast::{Curl, OwnedCurl};
+use super::files::FileResolver;
+use super::options;
+use crate::diagnostic::{Code, Diagnostic};
+
+/// How deep config files may read other config files.
+const MAX_NESTING: usize = 8;
+
+/// Split a line into its option, dashes added, and its value.
+fn split_line(line: &str) -> (String, Option<String>) {
+    let line = line.trim_start();
+    let end = line
+        .find(|c: char| c.is_whitespace() || c == '=' || c == ':')
+        .unwrap_or(line.len());
+    let (name, rest) = line.split_at(end);
+    let option = if name.starts_with('-') {
+        name.to_string()
+    } else {
+        format!("--{}", name)
+    };
+    let rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '=' || c == ':');
+    let value = match rest.strip_prefix('"') {
+        Some(quoted) => {
+            let mut value = String::new();
+            let mut chars = quoted.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => match chars.next() {
+                        Some('t') => value.push('\t'),
+                        Some('n') => value.push('\n'),
+                        Some('r') => value.push('\r'),
+                        Some('v') => value.push('\u{b}'),
+                        Some(c) => value.push(c),
+                        None => break,
+                    },
+                    c => value.push(c),
+                }
+            }
+            value
+        }
+        None => rest
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    };
+    (option, (!value.is_empty()).then_some(value))
+}
+
+/// The node of one option of a config file.
+fn node(option: &str, value: Option<String>, line: usize) -> Result<OwnedCurl, Diagnostic> {
+    let spec = options::lookup(option).ok_or_else(|| {
+        Diagnostic::new(
+            Code::UnexpectedToken,
+            format!("line {}: unknown option {}", line, option),
+        )
+    })?;
+    // Like curl, ignore the value given to a flag.
+    if !spec.takes_value() {
+        return Ok(Curl::new_as_flag(option)
+            .expect("options are not empty")
+            .into_owned());
+    }
+    let invalid = || {
+        Diagnostic::new(
+            Code::InvalidOptionValue,
+            format!("line {}: {} needs a value", line, option),
+        )
+    };
+    let value = value.ok_or_else(invalid)?;
+    if spec.long == "--url" {
+        return Ok(Curl::new_as_url_str(&value).into_owned());
+    }
+    Curl::new(option, &value)
+        .map(Curl::into_owned)
+        .ok_or_else(invalid)
+}
+
+/// Parse the options of a curl config file, as read with `-K`/`--config`
+/// and from `.curlrc`, into the nodes the same options give on the command
+/// line, `url` ones becoming URLs.
+///
+/// Each line holds one option, its long name with or without the leading
+/// `--` or its short name with a `-`, followed by its value after blanks,
+/// `=` or `:`. Double-quoted values may hold blanks and the escapes `\"`,
+/// `\\`, `\t`, `\n`, `\r` and `\v`; other values end at the first blank.
+/// Lines starting with `#` are comments.
+///
+/// ```
+/// use winnowcurl::curl::curlrc::parse_config;
+/// use winnowcurl::curl::render::to_curl_string;
+///
+/// let curls = parse_config("# API\nurl = \"https://a.com\"\nheader: \"Accept: */*\"\n-s\n")?;
+/// assert_eq!(
+///     to_curl_string(&curls),
+///     "curl 'https://a.com' \\\n  --header 'Accept: */*' \\\n  -s"
+/// );
+/// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+/// ```
+pub fn parse_config(text: &str) -> Result<Vec<OwnedCurl>, Diagnostic> {
+    let curls = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.trim_start();
+            !line.is_empty() && !line.starts_with('#')
+        })
+        .map(|(i, line)| {
+            let (option, value) = split_line(line);
+            node(&option, value, i + 1)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    match options::find_invalid(&curls) {
+        Some(invalid) => Err(invalid.diagnostic()),
+        None => Ok(curls),
+    }
+}
+
+fn inline(
+    curls: &[Curl<'_>],
+    resolver: &FileResolver,
+    depth: usize,
+) -> Result<Vec<OwnedCurl>, Diagnostic> {
+    let mut inlined = Vec::new();
+    for curl in curls {
+        match curl {
+            Curl::Option { name, value } if name == "--config" && value != "-" => {
+                if depth == MAX_NESTING {
+                    return Err(Diagnostic::new(
+                        Code::InvalidOptionValue,
+                        format!("{}: config files are nested too deep", value),
+                    ));
+                }
+                let contents = resolver
+                    .read_file(Path::new(&**value))
+                    .map_err(|e| e.diagnostic())?;
+                let text = String::from_utf8_lossy(&contents);
+                let curls = parse_config(&text)
+                    .map_err(|e| Diagnostic::new(e.code, format!("{}: {}", value, e.message)))?;
+                inlined.extend(inline(&curls, resolver, depth + 1)?);
+            }
+            curl => inlined.push(curl.clone().into_owned()),
+        }
+    }
+    Ok(inlined)
+}
+
+/// Replace every `-K file` of a command by the options of the file, read
+/// through `resolver`, so the nodes describe the request curl sends.
+///
+/// Config files may read other config files. `-K -`, reading stdin, is
+/// kept as is.
+pub fn inline_configs(
+    curls: &[Curl<'_>],
+    resolver: &FileResolver,
+) -> Result<Vec<OwnedCurl>, Diagnostic> {
+    inline(curls, resolver, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+
+    #[rstest]
+    #[case("silent", "--silent", None)]
+    #[case("--max-time 30", "--max-time", Some("30"))]
+    #[case(
+        "  user-agent = \"my agent\" # ignored",
+        "--user-agent",
+        Some("my agent")
+    )]
+    #[case("header:\"X-A: \\\"b\\\"\\tc\"", "--header", Some("X-A: \"b\"\tc"))]
+    #[case("-d a=1 b=2", "-d", Some("a=1"))]
+    #[case("referer=", "--referer", None)]
+    fn test_split_line(#[case] line: &str, #[case] option: &str, #[case] value: Option<&str>) {
+        assert_eq!(
+            split_line(line),
+            (option.to_string(), value.map(String::from))
+        );
+    }
+
+    #[rstest]
+    fn test_parse_config() {
+        let curls = parse_config(concat!(
+            "# defaults\n",
+            "\n",
+            "url = \"https://a.com/x?q=1\"\n",
+            "request POST\n",
+            "data = \"{\\\"a\\\": 1}\"\n",
+            "location\n",
+            "-A bot\n",
+            "max-time: 5\n",
+        ))
+        .unwrap();
+        assert_eq!(
+            to_curl_string(&curls),
+            concat!(
+                "curl 'https://a.com/x?q=1' \\\n",
+                "  --request 'POST' \\\n",
+                "  --data '{\"a\": 1}' \\\n",
+                "  --location \\\n",
+                "  --user-agent 'bot' \\\n",
+                "  --max-time '5'",
+            )
+        );
+    }
+
+    #[rstest]
+    #[case("bogus\n", Code::UnexpectedToken, "line 1: unknown option --bogus")]
+    #[case(
+        "# a\nheader\n",
+        Code::InvalidOptionValue,
+        "line 2: --header needs a value"
+    )]
+    #[case("max-time soon\n", Code::InvalidOptionValue, "")]
+    fn test_parse_config_errors(#[case] text: &str, #[case] code: Code, #[case] message: &str) {
+        let error = parse_config(text).unwrap_err();
+        assert_eq!(error.code, code);
+        assert!(error.message.starts_with(message), "{}", error.message);
+    }
+
+    #[rstest]
+    fn test_inline_configs() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("api.cfg"),
+            "url https://a.com\n-K auth.cfg\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("auth.cfg"), "user = \"me:pw\"\n").unwrap();
+        std::fs::write(dir.path().join("loop.cfg"), "config loop.cfg\n").unwrap();
+        let resolver = FileResolver::new().with_base_dir(dir.path());
+
+        let curls = curl_cmd_parse("curl -K api.cfg -H 'Accept: */*' -K -").unwrap();
+        assert_eq!(
+            to_curl_string(&inline_configs(&curls, &resolver).unwrap()),
+            concat!(
+                "curl 'https://a.com' \\\n",
+                "  --user 'me:pw' \\\n",
+                "  -H 'Accept: */*' \\\n",
+                "  --config '-'",
+            )
+        );
+
+        let curls = curl_cmd_parse("curl https://a.com -K loop.cfg").unwrap();
+        let error = inline_configs(&curls, &resolver).unwrap_err();
+        assert!(
+            error.message.contains("nested too deep"),
+            "{}",
+            error.message
+        );
+
+        let curls = curl_cmd_parse("curl https://a.com -K missing.cfg").unwrap();
+        assert_eq!(
+            inline_configs(&curls, &resolver).unwrap_err().code,
+            Code::UnreadableFile
+        );
+    }
+}
@@ -0,0 +1,254 @@
+/// The days of the week, from Thursday the 1st of January 1970 on.
+pub(crate) const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+pub(crate) const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// The time zones curl knows by name, with their offset to GMT in minutes.
+const ZONES: &[(&str, i64)] = &[
+    ("GMT", 0),
+    ("UT", 0),
+    ("UTC", 0),
+    ("Z", 0),
+    ("WET", 0),
+    ("BST", 60),
+    ("CET", 60),
+    ("MET", 60),
+    ("MEST", 120),
+    ("CEST", 120),
+    ("EET", 120),
+    ("EST", -300),
+    ("EDT", -240),
+    ("CST", -360),
+    ("CDT", -300),
+    ("MST", -420),
+    ("MDT", -360),
+    ("PST", -480),
+    ("PDT", -420),
+    ("JST", 540),
+];
+
+/// The year, month and day of the `days`th day since the epoch, before it
+/// when negative.
+pub(crate) fn civil_date(days: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's `civil_from_days`, with eras starting on 0000-03-01.
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month as u32, day as u32)
+}
+
+/// The number of days from the epoch to `year`-`month`-`day`, the inverse
+/// of [`civil_date`].
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let shifted_month = i64::from((month + 9) % 12);
+    let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// `seconds` since the epoch as an HTTP date, e.g.
+/// `Wed, 01 May 2024 12:30:00 GMT`.
+pub fn http_date(seconds: i64) -> String {
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+    let (year, month, day) = civil_date(days);
+    format!(
+        "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[days.rem_euclid(7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+/// The index of a day or month name, written in full or by its first
+/// three letters, in any case.
+fn name_index(names: &[&str], word: &str) -> Option<usize> {
+    const FULL: &[&str] = &[
+        "thursday",
+        "friday",
+        "saturday",
+        "sunday",
+        "monday",
+        "tuesday",
+        "wednesday",
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    let word = word.to_ascii_lowercase();
+    let known = word.len() == 3 || FULL.contains(&word.as_str());
+    known
+        .then(|| {
+            names
+                .iter()
+                .position(|name| word.starts_with(&name.to_ascii_lowercase()))
+        })
+        .flatten()
+}
+
+/// A `HH:MM` or `HH:MM:SS` time, in seconds.
+fn clock(text: &str) -> Option<i64> {
+    let parts: Vec<&str> = text.split(':').collect();
+    if !(2..=3).contains(&parts.len())
+        || parts
+            .iter()
+            .any(|part| part.len() != 2 || !part.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+    let field = |i: usize| parts.get(i).map_or(Ok(0), |part| part.parse::<i64>());
+    let (hour, minute, second) = (field(0).ok()?, field(1).ok()?, field(2).ok()?);
+    (hour < 24 && minute < 60 && second <= 60).then_some(hour * 3600 + minute * 60 + second)
+}
+
+/// Parse a date the way curl's `curl_getdate` reads the date of
+/// `-z`/`--time-cond`, in seconds since the epoch.
+///
+/// Only English day and month names and ASCII digits are read, whatever
+/// the locale, so the same command means the same date everywhere. The
+/// fields may come in any order, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`,
+/// `Sunday, 06-Nov-94 08:49:37 GMT`, `Sun Nov  6 08:49:37 1994`,
+/// `06 Nov 1994` or `19941106`; times are in GMT unless a zone is given,
+/// by name or as `+0100`. Two-digit years below 70 are in the 2000s.
+pub fn parse_date(text: &str) -> Option<i64> {
+    let (mut weekday, mut time, mut zone) = (None, None, None);
+    let (mut year, mut month, mut day) = (None, None, None);
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        if bytes[i].is_ascii_alphabetic() {
+            while i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+                i += 1;
+            }
+            let word = &text[start..i];
+            if let Some(index) = name_index(&WEEKDAYS, word) {
+                if weekday.replace(index).is_some() {
+                    return None;
+                }
+            } else if let Some(index) = name_index(&MONTHS, word) {
+                if month.replace(index as u32 + 1).is_some() {
+                    return None;
+                }
+            } else {
+                let (_, offset) = ZONES
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(word))?;
+                if zone.replace(*offset).is_some() {
+                    return None;
+                }
+            }
+        } else if bytes[i].is_ascii_digit() {
+            while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b':') {
+                i += 1;
+            }
+            let word = &text[start..i];
+            if word.contains(':') {
+                if time.replace(clock(word)?).is_some() {
+                    return None;
+                }
+                continue;
+            }
+            let signed = start > 0 && matches!(bytes[start - 1], b'+' | b'-');
+            let number: i64 = word.parse().ok()?;
+            if signed && word.len() == 4 && time.is_some() && zone.is_none() {
+                let minutes = number / 100 * 60 + number % 100;
+                zone = Some(if bytes[start - 1] == b'-' {
+                    -minutes
+                } else {
+                    minutes
+                });
+            } else if word.len() == 8 && year.is_none() && month.is_none() && day.is_none() {
+                year = Some(number / 10_000);
+                month = Some((number / 100 % 100) as u32);
+                day = Some((number % 100) as u32);
+            } else if day.is_none() && word.len() <= 2 && (1..=31).contains(&number) {
+                day = Some(number as u32);
+            } else if year.is_none() && (word.len() == 4 || word.len() == 2) {
+                year = Some(match word.len() {
+                    2 if number < 70 => number + 2000,
+                    2 => number + 1900,
+                    _ => number,
+                });
+            } else {
+                return None;
+            }
+        } else {
+            i += text[i..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    let (year, month, day) = (year?, month?, day?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let seconds = days_from_civil(year, month, day) * 86_400 + time.unwrap_or(0);
+    Some(seconds - zone.unwrap_or(0) * 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("Sun, 06 Nov 1994 08:49:37 GMT", Some(784_111_777))]
+    #[case("Sunday, 06-Nov-94 08:49:37 GMT", Some(784_111_777))]
+    #[case("Sun Nov  6 08:49:37 1994", Some(784_111_777))]
+    #[case("06 Nov 1994 09:49:37 +0100", Some(784_111_777))]
+    #[case("6 november 1994 03:49:37 EST", Some(784_111_777))]
+    #[case("19941106", Some(784_080_000))]
+    #[case("Nov 6 1994", Some(784_080_000))]
+    #[case("1 Jan 2030", Some(1_893_456_000))]
+    #[case("1 Jan 69", Some(3_124_224_000))]
+    #[case("31 Dec 1969 23:59:59", Some(-1))]
+    #[case("29 Feb 2000 12:00", Some(951_825_600))]
+    #[case("Sun, 06 Nov 1994 08:49:37 CEST", Some(784_104_577))]
+    #[case("yesterday", None)]
+    #[case("06 Nov 1994 25:00:00", None)]
+    #[case("06 Nov", None)]
+    #[case("Nov Dec 6 1994", None)]
+    #[case("2024-05-01", None)]
+    #[case("06 Nov 1994,5", None)]
+    #[case("", None)]
+    fn test_parse_date(#[case] text: &str, #[case] expected: Option<i64>) {
+        assert_eq!(parse_date(text), expected);
+    }
+
+    #[rstest]
+    #[case(784_111_777, "Sun, 06 Nov 1994 08:49:37 GMT")]
+    #[case(0, "Thu, 01 Jan 1970 00:00:00 GMT")]
+    #[case(-1, "Wed, 31 Dec 1969 23:59:59 GMT")]
+    #[case(951_825_600, "Tue, 29 Feb 2000 12:00:00 GMT")]
+    fn test_http_date(#[case] seconds: i64, #[case] expected: &str) {
+        assert_eq!(http_date(seconds), expected);
+        assert_eq!(parse_date(expected), Some(seconds));
+    }
+}
@@ -0,0 +1,295 @@
+use super::ast::Curl;
+use super::batch::{self, starts_with_curl};
+use super::lexer::Dialect;
+use super::parser::curl_cmd_parse_dialect;
+use crate::diagnostic::{Code, Diagnostic};
+
+/// One request of a browser's "Copy all as cURL" export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopiedRequest<'a> {
+    /// The 1-based line the command starts on.
+    pub line: usize,
+    /// The command, without the separator joining it to the next one.
+    pub source: &'a str,
+    /// The shell the export was copied for.
+    pub dialect: Dialect,
+    /// The tab the request was sent from, given by a `tab:` comment.
+    pub tab: Option<&'a str>,
+    /// The frame the request was sent from, given by a `frame:` comment.
+    pub frame: Option<&'a str>,
+}
+
+impl<'a> CopiedRequest<'a> {
+    /// Parse the command of the request.
+    pub fn parse(&self) -> Result<Vec<Curl<'a>>, Diagnostic> {
+        curl_cmd_parse_dialect(self.source, self.dialect)
+    }
+}
+
+/// The text of a comment line: `#` in bash, `rem` or `::` in cmd.
+fn comment(line: &str, dialect: Dialect) -> Option<&str> {
+    let line = line.trim();
+    match dialect {
+        Dialect::Cmd => line.strip_prefix("::").or_else(|| {
+            line.get(..4)
+                .filter(|rem| rem.eq_ignore_ascii_case("rem "))
+                .map(|_| &line[4..])
+        }),
+        _ => line.strip_prefix('#'),
+    }
+}
+
+/// The `tab:` and `frame:` comments right above the command starting on
+/// the 1-based `line`, the nearest one winning.
+fn annotations<'a>(
+    lines: &[&'a str],
+    line: usize,
+    dialect: Dialect,
+) -> (Option<&'a str>, Option<&'a str>) {
+    let (mut tab, mut frame) = (None, None);
+    let comments = lines[..line - 1]
+        .iter()
+        .rev()
+        .map_while(|line| comment(line, dialect));
+    for text in comments {
+        let Some((key, value)) = text.split_once(':') else {
+            continue;
+        };
+        let value = Some(value.trim()).filter(|value| !value.is_empty());
+        match key.trim().to_ascii_lowercase().as_str() {
+            "tab" => tab = tab.or(value),
+            "frame" => frame = frame.or(value),
+            _ => {}
+        }
+    }
+    (tab, frame)
+}
+
+/// A cmd command without the `&` joining it to the next one.
+fn unjoined(source: &str) -> &str {
+    let source = source.trim();
+    match source.strip_suffix('&') {
+        Some(joined) if !joined.ends_with('^') => joined.trim_end(),
+        _ => source,
+    }
+}
+
+/// Split a cmd export: a command ends on the first line not continued by
+/// a caret, `^` before an empty line escaping a newline of an argument.
+fn split_cmd(text: &str) -> Vec<(usize, &str)> {
+    let mut commands = Vec::new();
+    let mut current: Option<(usize, usize, usize)> = None;
+    let mut continued = false;
+    let mut offset = 0;
+    for (i, line) in text.split('\n').enumerate() {
+        let (start, end) = (offset, offset + line.len());
+        offset = end + 1;
+        let trimmed = line.trim();
+        if continued {
+            if let Some((_, _, current_end)) = &mut current {
+                *current_end = end;
+            }
+            continued = trimmed.ends_with('^') || trimmed.is_empty();
+            continue;
+        }
+        commands.extend(current.take());
+        if trimmed.is_empty() || comment(line, Dialect::Cmd).is_some() {
+            continue;
+        }
+        current = Some((i + 1, start, end));
+        continued = trimmed.ends_with('^');
+    }
+    commands.extend(current);
+    commands
+        .into_iter()
+        .map(|(line, from, to)| (line, unjoined(&text[from..to])))
+        .filter(|(_, source)| !source.is_empty())
+        .collect()
+}
+
+/// Split the commands of a "Copy all as cURL" export of the network panel
+/// of Chrome's DevTools, in the order they were copied, guessing the shell
+/// it was copied for with [`Dialect::detect`] for [`Dialect::Auto`].
+///
+/// Chrome joins the commands with ` ;` for bash and ` &` for cmd, each on
+/// its own line. `tab:` and `frame:` comments right above a command, e.g.
+/// `# tab: 3` or `rem frame: checkout`, tell where its request was sent
+/// from.
+///
+/// ```
+/// use winnowcurl::curl::devtools::split_copy_all;
+/// use winnowcurl::curl::lexer::Dialect;
+///
+/// let export = "# tab: 1\ncurl 'https://a.com/' -H 'accept: */*' ;\ncurl 'https://a.com/app.js'";
+/// let requests = split_copy_all(export, Dialect::Auto);
+/// assert_eq!(requests.len(), 2);
+/// assert_eq!(requests[0].tab, Some("1"));
+/// assert_eq!(requests[1].source, "curl 'https://a.com/app.js'");
+/// assert_eq!(requests[1].line, 3);
+/// ```
+pub fn split_copy_all(text: &str, dialect: Dialect) -> Vec<CopiedRequest<'_>> {
+    let dialect = dialect.resolve(text);
+    let commands = match dialect {
+        Dialect::Cmd => split_cmd(text),
+        _ => batch::split_commands(text, dialect)
+            .into_iter()
+            .map(|invocation| (invocation.line, invocation.source))
+            .collect(),
+    };
+    let lines: Vec<&str> = text.split('\n').collect();
+    commands
+        .into_iter()
+        .map(|(line, source)| {
+            let (tab, frame) = annotations(&lines, line, dialect);
+            CopiedRequest {
+                line,
+                source,
+                dialect,
+                tab,
+                frame,
+            }
+        })
+        .collect()
+}
+
+/// Parse every command of a "Copy all as cURL" export, see
+/// [`split_copy_all`], failing on the first one that doesn't parse.
+///
+/// Lines not starting a curl command, such as the ones left over when a
+/// command is cut short, are reported rather than skipped.
+pub fn import_copy_all(text: &str, dialect: Dialect) -> Result<Vec<Vec<Curl<'_>>>, Diagnostic> {
+    split_copy_all(text, dialect)
+        .iter()
+        .map(|request| {
+            if !starts_with_curl(request.source) {
+                return Err(Diagnostic::new(
+                    Code::UnexpectedToken,
+                    format!("line {}: expected a curl command", request.line),
+                ));
+            }
+            request.parse().map_err(|e| {
+                Diagnostic::new(e.code, format!("line {}: {}", request.line, e.message))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+
+    const BASH: &str = concat!(
+        "curl 'https://a.com/' \\\n",
+        "  -H 'accept: text/html' \\\n",
+        "  -H 'sec-fetch-dest: document' \\\n",
+        "  --compressed ;\n",
+        "curl 'https://a.com/api' \\\n",
+        "  -H 'content-type: application/json' \\\n",
+        "  --data-raw $'{\"a\":\"b;c\"}' \\\n",
+        "  --compressed",
+    );
+
+    const CMD: &str = concat!(
+        "curl ^\"https://a.com/^\" ^\r\n",
+        "  -H ^\"accept: text/html^\" ^\r\n",
+        "  --compressed &\r\n",
+        "curl ^\"https://a.com/api^\" ^\r\n",
+        "  -H ^\"content-type: text/plain^\" ^\r\n",
+        "  --data-raw ^\"a^\r\n",
+        "\r\n",
+        "curl b^\" ^\r\n",
+        "  --compressed",
+    );
+
+    #[rstest]
+    fn test_split_copy_all_bash() {
+        let requests = split_copy_all(BASH, Dialect::Auto);
+        let lines: Vec<usize> = requests.iter().map(|request| request.line).collect();
+        assert_eq!(lines, vec![1, 5]);
+        assert!(
+            requests
+                .iter()
+                .all(|request| request.dialect == Dialect::Bash)
+        );
+        assert!(requests[0].source.ends_with("--compressed"));
+    }
+
+    #[rstest]
+    fn test_split_copy_all_cmd() {
+        let requests = split_copy_all(CMD, Dialect::Auto);
+        let lines: Vec<usize> = requests.iter().map(|request| request.line).collect();
+        assert_eq!(lines, vec![1, 4]);
+        assert!(
+            requests
+                .iter()
+                .all(|request| request.dialect == Dialect::Cmd)
+        );
+        assert!(requests[0].source.ends_with("--compressed"));
+        assert!(requests[1].source.contains("curl b"));
+    }
+
+    #[rstest]
+    fn test_import_copy_all() {
+        let bash = import_copy_all(BASH, Dialect::Auto).unwrap();
+        assert_eq!(bash.len(), 2);
+        assert_eq!(
+            to_curl_string(&bash[1]),
+            concat!(
+                "curl 'https://a.com/api' \\\n",
+                "  -H 'content-type: application/json' \\\n",
+                "  --data-raw '{\"a\":\"b;c\"}' \\\n",
+                "  --compressed",
+            )
+        );
+
+        let cmd = import_copy_all(CMD, Dialect::Cmd).unwrap();
+        assert_eq!(cmd.len(), 2);
+        assert_eq!(cmd[0][0].to_string(), "'https://a.com/'");
+        assert!(
+            cmd[1].contains(&Curl::new("--data-raw", "a\r\ncurl b").unwrap()),
+            "{:?}",
+            cmd[1]
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "# tab: 7\n# frame: main\ncurl https://a.com ;\n# frame: ads\ncurl https://b.com",
+        Dialect::Bash,
+        vec![(Some("7"), Some("main")), (None, Some("ads"))]
+    )]
+    #[case(
+        ":: tab: 2\r\nrem Frame: login\r\ncurl ^\"https://a.com^\" &\r\ncurl ^\"https://b.com^\"",
+        Dialect::Cmd,
+        vec![(Some("2"), Some("login")), (None, None)]
+    )]
+    #[case(
+        "# frame: a\n# frame: b\ncurl https://a.com",
+        Dialect::Bash,
+        vec![(None, Some("b"))]
+    )]
+    fn test_split_copy_all_annotations(
+        #[case] text: &str,
+        #[case] dialect: Dialect,
+        #[case] expected: Vec<(Option<&str>, Option<&str>)>,
+    ) {
+        let annotations: Vec<_> = split_copy_all(text, dialect)
+            .iter()
+            .map(|request| (request.tab, request.frame))
+            .collect();
+        assert_eq!(annotations, expected);
+    }
+
+    #[rstest]
+    #[case("curl https://a.com ;\ncurl https://b.com -m soon", "line 2: ")]
+    #[case(
+        "curl https://a.com ;\n-H 'accept: */*'",
+        "line 2: expected a curl command"
+    )]
+    fn test_import_copy_all_errors(#[case] text: &str, #[case] message: &str) {
+        let error = import_copy_all(text, Dialect::Bash).unwrap_err();
+        assert!(error.message.starts_with(message), "{}", error.message);
+    }
+}
@@ -0,0 +1,401 @@
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::ops::Range;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+
+use crate::diagnostic::{Code, Diagnostic};
+
+/// How many characters of a long line [`ParseError::render`] shows on either
+/// side of the span.
+const CONTEXT: usize = 40;
+
+/// How many characters of an argument an error message quotes.
+const QUOTED: usize = 60;
+
+/// `text` cut after [`QUOTED`] characters, for quoting in a message without
+/// copying a megabyte-long body into it.
+pub(crate) fn shorten(text: &str) -> Cow<'_, str> {
+    match text.char_indices().nth(QUOTED) {
+        Some((end, _)) => Cow::Owned(format!("{}…", &text[..end])),
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// The byte `n` characters after `at` in `text`, or the end of the line
+/// if nearer.
+fn advance(text: &str, at: usize, n: usize) -> usize {
+    let mut end = at;
+    for c in text[at..].chars().take(n) {
+        if c == '\n' {
+            break;
+        }
+        end += c.len_utf8();
+    }
+    end
+}
+
+/// The line starting `text` around the span from byte `start` to byte `end`,
+/// with the column and width of the span in it. Lines longer than the span
+/// and [`CONTEXT`] characters on either side are cut that far from the span,
+/// an ellipsis marking the cuts; only the characters shown are read, however
+/// long the line.
+fn excerpt(text: &str, start: usize, end: usize) -> (Cow<'_, str>, usize, usize) {
+    let width = advance(text, start, CONTEXT.min(end - start));
+    let width = text[start..width].chars().count().max(1);
+    let line_end = advance(text, 0, 2 * CONTEXT + width);
+    if line_end >= start && matches!(text[line_end..].chars().next(), None | Some('\r' | '\n')) {
+        let line = text[..line_end].trim_end_matches('\r');
+        return (Cow::Borrowed(line), text[..start].chars().count(), width);
+    }
+    let from = text[..start]
+        .char_indices()
+        .nth_back(CONTEXT - 1)
+        .map_or(0, |(i, _)| i);
+    let to = advance(text, advance(text, start, width), CONTEXT);
+    let column = text[from..start].chars().count();
+    let cut = !matches!(text[to..].chars().next(), None | Some('\r' | '\n'));
+    let line = text[from..to].trim_end_matches('\r');
+    if from == 0 && !cut {
+        return (Cow::Borrowed(line), column, width);
+    }
+    let mut shown = String::new();
+    if from > 0 {
+        shown.push('…');
+    }
+    shown.push_str(line);
+    if cut {
+        shown.push('…');
+    }
+    (Cow::Owned(shown), column + usize::from(from > 0), width)
+}
+
+/// The line and column of positions in a command, reading it once when asked
+/// for positions in order, as those of the warnings of one parse are.
+struct Locator<'a> {
+    input: &'a str,
+    position: usize,
+    line_start: usize,
+    /// Counted from 1.
+    line: usize,
+    /// In characters, counted from 0.
+    column: usize,
+}
+
+impl<'a> Locator<'a> {
+    fn new(input: &'a str) -> Self {
+        Locator {
+            input,
+            position: 0,
+            line_start: 0,
+            line: 1,
+            column: 0,
+        }
+    }
+
+    fn locate(&mut self, position: usize) {
+        if position < self.position {
+            *self = Locator::new(self.input);
+        }
+        let skipped = &self.input[self.position..position];
+        match skipped.rfind('\n') {
+            Some(i) => {
+                self.line += skipped.matches('\n').count();
+                self.line_start = self.position + i + 1;
+                self.column = self.input[self.line_start..position].chars().count();
+            }
+            None => self.column += skipped.chars().count(),
+        }
+        self.position = position;
+    }
+}
+
+/// Where and why a curl command could not be parsed.
+///
+/// Unlike a [`Diagnostic`] it keeps the command, so that [`ParseError::render`]
+/// can point at the offending part of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub code: Code,
+    pub message: String,
+    /// The bytes of the command parsing broke on, `None` when the error
+    /// is not about a single place.
+    pub span: Option<Range<usize>>,
+    /// What the parser was looking for, e.g. `a URL`.
+    pub expected: Option<String>,
+    /// The command, shared by the errors of one parse.
+    pub input: Arc<str>,
+}
+
+impl ParseError {
+    pub fn new(code: Code, message: impl Into<String>, input: impl Into<Arc<str>>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            span: None,
+            expected: None,
+            input: input.into(),
+        }
+    }
+
+    pub fn with_span(mut self, span: Range<usize>) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_expected(mut self, expected: impl Into<String>) -> Self {
+        self.expected = Some(expected.into());
+        self
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(self.code, self.message.clone())
+    }
+
+    /// The diagnostic followed by the line of the command holding the span,
+    /// underlined with carets, a long line cut around the span:
+    ///
+    /// ```text
+    /// error[WCE003]: Expected a URL, found `-v`
+    ///  --> 1:6
+    ///   |
+    /// 1 | curl -v
+    ///   |      ^^ expected a URL
+    /// ```
+    pub fn render(&self) -> String {
+        self.render_with(&mut Locator::new(&self.input))
+    }
+
+    /// [`ParseError::render`] every error in turn, those of one command
+    /// reading it once, however long it is and however many they are.
+    pub fn render_all<'e>(errors: impl IntoIterator<Item = &'e ParseError>) -> Vec<String> {
+        let mut locator: Option<(&Arc<str>, Locator)> = None;
+        errors
+            .into_iter()
+            .map(|error| {
+                if !matches!(&locator, Some((input, _)) if Arc::ptr_eq(input, &error.input)) {
+                    locator = None;
+                }
+                let (_, locator) =
+                    locator.get_or_insert_with(|| (&error.input, Locator::new(&error.input)));
+                error.render_with(locator)
+            })
+            .collect()
+    }
+
+    fn render_with(&self, locator: &mut Locator) -> String {
+        let mut rendered = self.diagnostic().to_string();
+        let Some(span) = &self.span else {
+            return rendered;
+        };
+        let start = span.start.min(self.input.len());
+        let end = span.end.clamp(start, self.input.len());
+        locator.locate(start);
+        let (line, offset, width) = excerpt(
+            &self.input[locator.line_start..],
+            start - locator.line_start,
+            end - locator.line_start,
+        );
+
+        let number = locator.line;
+        let gutter = " ".repeat(number.to_string().len());
+        let _ = write!(
+            rendered,
+            "\n{gutter}--> {}:{}\n{gutter} |\n{} | {}\n{gutter} | {}{}",
+            number,
+            locator.column + 1,
+            number,
+            line,
+            " ".repeat(offset),
+            "^".repeat(width),
+        );
+        if let Some(expected) = &self.expected {
+            let _ = write!(rendered, " expected {}", expected);
+        }
+        rendered
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.diagnostic().fmt(f)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for Diagnostic {
+    fn from(error: ParseError) -> Self {
+        Diagnostic::new(error.code, error.message)
+    }
+}
+
+impl From<Diagnostic> for ParseError {
+    fn from(diagnostic: Diagnostic) -> Self {
+        ParseError::new(diagnostic.code, diagnostic.message, "")
+    }
+}
+
+impl Serialize for ParseError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Span {
+            start: usize,
+            end: usize,
+        }
+
+        let mut state = serializer.serialize_struct("ParseError", 6)?;
+        state.serialize_field("code", &self.code)?;
+        state.serialize_field("name", self.code.name())?;
+        state.serialize_field("severity", self.code.severity().as_str())?;
+        state.serialize_field("message", &self.message)?;
+        state.serialize_field(
+            "span",
+            &self.span.as_ref().map(|span| Span {
+                start: span.start,
+                end: span.end,
+            }),
+        )?;
+        state.serialize_field("expected", &self.expected)?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_render() {
+        let error = ParseError::new(Code::MissingUrl, "Expected a URL, found `-v`", "curl -v")
+            .with_span(5..7)
+            .with_expected("a URL");
+        assert_eq!(
+            error.render(),
+            concat!(
+                "error[WCE003]: Expected a URL, found `-v`\n",
+                " --> 1:6\n",
+                "  |\n",
+                "1 | curl -v\n",
+                "  |      ^^ expected a URL",
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_render_multiline_and_empty_span() {
+        let input = "curl \\\n  -H 'A: 1' \\\n  -X";
+        let error = ParseError::new(Code::MissingUrl, "The command has no URL", input)
+            .with_span(input.len()..input.len());
+        assert_eq!(
+            error.render(),
+            concat!(
+                "error[WCE003]: The command has no URL\n",
+                " --> 3:5\n",
+                "  |\n",
+                "3 |   -X\n",
+                "  |     ^",
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_render_long_line() {
+        let input = format!("curl https://a.com -d '{}' -X", "é".repeat(100));
+        let error = ParseError::new(Code::MissingUrl, "Expected a URL, found `-d`", &*input)
+            .with_span(19..21);
+        assert_eq!(
+            error.render(),
+            format!(
+                concat!(
+                    "error[WCE003]: Expected a URL, found `-d`\n",
+                    " --> 1:20\n",
+                    "  |\n",
+                    "1 | curl https://a.com -d '{}…\n",
+                    "  |                    ^^",
+                ),
+                "é".repeat(38)
+            )
+        );
+
+        let start = input.len() - 2;
+        let error = ParseError::new(Code::MissingUrl, "The command has no URL", &*input)
+            .with_span(start..input.len());
+        assert_eq!(
+            error.render(),
+            format!(
+                concat!(
+                    "error[WCE003]: The command has no URL\n",
+                    " --> 1:126\n",
+                    "  |\n",
+                    "1 | …{}' -X\n",
+                    "  |                                          ^^",
+                ),
+                "é".repeat(38)
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_render_short_line() {
+        let input = format!("curl -d '{}' -X", "a".repeat(60));
+        let start = input.len() - 2;
+        let error = ParseError::new(Code::MissingUrl, "The command has no URL", &*input)
+            .with_span(start..input.len());
+        assert!(error.render().contains(&format!("1 | {}\n", input)));
+    }
+
+    #[rstest]
+    fn test_render_all() {
+        let input: Arc<str> = "curl \\\n  -H 'A: 1' \\\n  -X".into();
+        let errors = [
+            ParseError::new(Code::MissingUrl, "a", input.clone()).with_span(10..18),
+            ParseError::new(Code::MissingUrl, "b", input.clone()).with_span(23..25),
+            ParseError::new(Code::MissingUrl, "c", input.clone()).with_span(5..6),
+            ParseError::new(Code::MissingUrl, "d", "curl -v").with_span(5..7),
+            ParseError::new(Code::NotCurl, "e", "wget"),
+        ];
+        let rendered: Vec<_> = errors.iter().map(ParseError::render).collect();
+        assert_eq!(ParseError::render_all(&errors), rendered);
+        assert!(rendered[1].contains(" --> 3:3\n"));
+    }
+
+    #[rstest]
+    #[case("-v", "-v")]
+    #[case(&"a".repeat(60), &"a".repeat(60))]
+    #[case(&"é".repeat(61), &format!("{}…", "é".repeat(60)))]
+    fn test_shorten(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(shorten(text), expected);
+    }
+
+    #[rstest]
+    fn test_render_without_span() {
+        let error = ParseError::new(Code::NotCurl, "Input does not start with curl", "wget x");
+        assert_eq!(
+            error.render(),
+            "error[WCE002]: Input does not start with curl"
+        );
+        assert_eq!(Diagnostic::from(error.clone()), error.diagnostic());
+    }
+
+    #[rstest]
+    fn test_serialize() {
+        let error = ParseError::new(Code::NotCurl, "Input does not start with curl", "wget x")
+            .with_span(0..4)
+            .with_expected("curl");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "WCE002",
+                "name": "not-curl",
+                "severity": "error",
+                "message": "Input does not start with curl",
+                "span": {"start": 0, "end": 4},
+                "expected": "curl"
+            })
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt::Write;
+
+use super::header::Headers;
+use super::options::has_flag;
+use super::parser::{Curl, CurlStru};
+use crate::diagnostic::{Code, Diagnostic};
+
+/// A character of a URL or body that curl would reject or mangle unless it
+/// is percent-encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unencoded {
+    /// Where the character is: `URL` or the data option as written.
+    pub option: String,
+    /// The offset of the character in the value, counted in characters.
+    pub offset: usize,
+    pub character: char,
+}
+
+impl Unencoded {
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(
+            Code::UnencodedCharacter,
+            format!(
+                "{} has an unencoded {:?} at position {}",
+                self.option,
+                self.character,
+                self.offset + 1
+            ),
+        )
+    }
+}
+
+/// Characters never allowed raw in a URL or a form-encoded body.
+fn is_unsafe(c: char) -> bool {
+    c.is_ascii_control() || matches!(c, ' ' | '"' | '<' | '>' | '\\' | '^' | '`' | '|')
+}
+
+/// The byte range of a bracketed IPv6 host, which is not a glob.
+fn ipv6_host(url: &str) -> Option<std::ops::Range<usize>> {
+    let host = url.find("://").map_or(0, |scheme| scheme + 3);
+    let host = url[host..].find('@').map_or(host, |at| host + at + 1);
+    let end = url[host..].strip_prefix('[')?.find(']')?;
+    Some(host..host + end + 2)
+}
+
+/// Characters of `url` to encode; braces and brackets only when curl would
+/// take them for a glob.
+fn url_offenders(url: &str, globoff: bool) -> Vec<(usize, char)> {
+    let host = ipv6_host(url).unwrap_or_default();
+    url.char_indices()
+        .enumerate()
+        .filter(|(_, (i, c))| {
+            is_unsafe(*c) || (!globoff && matches!(c, '{' | '}' | '[' | ']') && !host.contains(i))
+        })
+        .map(|(offset, (_, c))| (offset, c))
+        .collect()
+}
+
+/// Characters of a form-encoded body value to encode.
+fn data_offenders(value: &str) -> Vec<(usize, char)> {
+    value
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| is_unsafe(*c) || matches!(c, '{' | '}'))
+        .collect()
+}
+
+/// Whether a data option is sent as a form-encoded value curl leaves as is.
+///
+/// `--data-urlencode` encodes by itself, `--data-binary` and `@file`
+/// arguments are sent verbatim on purpose, and bodies with another content
+/// type or holding JSON follow their own rules.
+fn is_form_data(stru: &CurlStru, form_encoded: bool) -> bool {
+    let value = stru.data.as_deref().unwrap_or_default();
+    form_encoded
+        && matches!(
+            &*stru.identifier,
+            "-d" | "--data" | "--data-ascii" | "--data-raw"
+        )
+        && (stru.identifier == "--data-raw" || !value.starts_with('@'))
+        && serde_json::from_str::<serde_json::Value>(value).is_err()
+}
+
+/// Whether the body of the command is sent as `application/x-www-form-urlencoded`.
+pub(crate) fn is_form_encoded(curls: &[Curl]) -> bool {
+    Headers::from(curls)
+        .get("content-type")
+        .is_none_or(|content_type| {
+            content_type
+                .to_ascii_lowercase()
+                .starts_with("application/x-www-form-urlencoded")
+        })
+}
+
+/// Find every character of the URL and form-encoded data of a command that
+/// should have been percent-encoded.
+pub fn find_unencoded(curls: &[Curl]) -> Vec<Unencoded> {
+    let globoff = has_flag(curls, "--globoff");
+    let form_encoded = is_form_encoded(curls);
+    let mut found = Vec::new();
+    for curl in curls {
+        let (option, offenders) = match curl {
+            Curl::URL(url) => ("URL", url_offenders(&url.to_string(), globoff)),
+            Curl::Data(stru) if is_form_data(stru, form_encoded) => (
+                &*stru.identifier,
+                data_offenders(stru.data.as_deref().unwrap_or_default()),
+            ),
+            _ => continue,
+        };
+        found.extend(offenders.into_iter().map(|(offset, character)| Unencoded {
+            option: option.into(),
+            offset,
+            character,
+        }));
+    }
+    found
+}
+
+/// Percent-encode the characters of `value` at `offsets`.
+fn encode_at(value: &str, offsets: &[(usize, char)]) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    let mut offsets = offsets.iter().map(|(offset, _)| *offset).peekable();
+    for (i, c) in value.chars().enumerate() {
+        if offsets.next_if_eq(&i).is_some() {
+            for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                let _ = write!(encoded, "%{:02X}", byte);
+            }
+        } else {
+            encoded.push(c);
+        }
+    }
+    encoded
+}
+
+/// Rewrite the command with every character reported by [`find_unencoded`]
+/// percent-encoded.
+pub fn fix(curls: &[Curl]) -> Vec<Curl<'static>> {
+    let globoff = has_flag(curls, "--globoff");
+    let form_encoded = is_form_encoded(curls);
+    curls
+        .iter()
+        .map(|curl| match curl {
+            Curl::URL(url) => {
+                let url = url.to_string();
+                let encoded = encode_at(&url, &url_offenders(&url, globoff));
+                Curl::new_as_url_str(&encoded).into_owned()
+            }
+            Curl::Data(stru) if is_form_data(stru, form_encoded) => {
+                let value = stru.data.as_deref().unwrap_or_default();
+                let encoded = encode_at(value, &data_offenders(value));
+                Curl::Data(CurlStru::new_with_data(
+                    stru.identifier.to_string(),
+                    encoded,
+                ))
+            }
+            curl => curl.clone().into_owned(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+
+    fn characters(input: &str) -> Vec<(String, char)> {
+        let curls = curl_cmd_parse(input).unwrap();
+        find_unencoded(&curls)
+            .into_iter()
+            .map(|unencoded| (unencoded.option, unencoded.character))
+            .collect()
+    }
+
+    #[rstest]
+    #[case("curl 'https://a.com/a b'", vec![("URL", ' ')])]
+    #[case("curl 'https://a.com/x?q={\"a\":1}'", vec![("URL", '{'), ("URL", '"'), ("URL", '"'), ("URL", '}')])]
+    #[case("curl 'https://a.com/x?q={a}' -g", vec![])]
+    #[case("curl 'https://a.com/x?q=[1]|2' -g", vec![("URL", '|')])]
+    #[case("curl 'http://[::1]:80/[a]'", vec![("URL", '['), ("URL", ']')])]
+    #[case("curl 'https://a.com/%20ok'", vec![])]
+    #[case("curl 'https://a.com' -d 'q=a b&r=<x>'", vec![("-d", ' '), ("-d", '<'), ("-d", '>')])]
+    #[case("curl 'https://a.com' --data-raw '@a b'", vec![("--data-raw", ' ')])]
+    #[case("curl 'https://a.com' -d '@my file.txt'", vec![])]
+    #[case("curl 'https://a.com' --data-binary 'a b'", vec![])]
+    #[case("curl 'https://a.com' --data-urlencode 'q=a b'", vec![])]
+    #[case("curl 'https://a.com' -d '{\"a\": \"b c\"}'", vec![])]
+    #[case("curl 'https://a.com' -H 'Content-Type: text/plain' -d 'a b'", vec![])]
+    fn test_find_unencoded(#[case] input: &str, #[case] expected: Vec<(&str, char)>) {
+        let expected: Vec<(String, char)> = expected
+            .into_iter()
+            .map(|(option, c)| (option.into(), c))
+            .collect();
+        assert_eq!(characters(input), expected);
+    }
+
+    #[rstest]
+    fn test_diagnostic() {
+        let curls = curl_cmd_parse("curl 'https://a.com/a b'").unwrap();
+        let diagnostic = find_unencoded(&curls)[0].diagnostic();
+        assert_eq!(
+            diagnostic.to_string(),
+            "warning[WCL012]: URL has an unencoded ' ' at position 16"
+        );
+    }
+
+    #[rstest]
+    fn test_fix() {
+        let curls =
+            curl_cmd_parse("curl 'https://a.com/a b?q={x}' -d 'n=é a|b' --data-binary 'keep me'")
+                .unwrap();
+        let fixed = fix(&curls);
+        assert!(find_unencoded(&fixed).is_empty());
+        assert_eq!(
+            to_curl_string(&fixed),
+            concat!(
+                "curl 'https://a.com/a%20b?q=%7Bx%7D' \\\n",
+                "  -d 'n=é%20a%7Cb' \\\n",
+                "  --data-binary 'keep me'",
+            )
+        );
+    }
+}
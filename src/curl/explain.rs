@@ -0,0 +1,391 @@
+use serde::Serialize;
+use std::fmt;
+
+use super::ast::Curl;
+use super::body::{BodySource, DataArg};
+use super::form::FormValue;
+use super::header::Header;
+use super::method::Method;
+use super::options::{self, OptionSpec};
+use crate::diagnostic::Severity;
+use crate::url::protocol::Schema;
+
+/// What one argument of a command does, e.g. `-k` "disables TLS
+/// certificate verification".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Annotation {
+    /// The argument as written back by [`to_curl_string`].
+    ///
+    /// [`to_curl_string`]: super::render::to_curl_string
+    pub text: String,
+    pub meaning: String,
+    /// `Error` for arguments that make the transfer unsafe, `Warning` for
+    /// those to look at twice.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk: Option<Severity>,
+}
+
+impl Annotation {
+    fn new(curl: &Curl, meaning: impl Into<String>, risk: Option<Severity>) -> Self {
+        Annotation {
+            text: curl.to_string(),
+            meaning: meaning.into(),
+            risk,
+        }
+    }
+}
+
+/// One annotation per line, the argument and its meaning in two columns,
+/// unsafe arguments marked with `!!` and the others to look at with `!`.
+pub struct Explanation<'e>(pub &'e [Annotation]);
+
+impl fmt::Display for Explanation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .0
+            .iter()
+            .map(|annotation| annotation.text.chars().count())
+            .max()
+            .unwrap_or_default();
+        for annotation in self.0 {
+            let marker = match annotation.risk {
+                Some(Severity::Error) => "!!",
+                Some(Severity::Warning) => "! ",
+                None => "  ",
+            };
+            writeln!(
+                f,
+                "{} {:width$}  → {}",
+                marker, annotation.text, annotation.meaning
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn plural(n: usize, one: &str) -> String {
+    if n == 1 {
+        format!("1 {}", one)
+    } else {
+        format!("{} {}s", n, one)
+    }
+}
+
+fn header_meaning(raw: &str) -> (String, Option<Severity>) {
+    let Some(header) = Header::parse(raw) else {
+        return ("sends a header without a name".into(), None);
+    };
+    if header.is("cookie") {
+        return (
+            format!("sends {}", plural(header.cookies().len(), "cookie")),
+            None,
+        );
+    }
+    if header.is("authorization") || header.is("proxy-authorization") {
+        return (
+            format!("sends credentials in the {} header", header.name),
+            Some(Severity::Warning),
+        );
+    }
+    if header.value.is_empty() {
+        return (format!("removes the {} header", header.name), None);
+    }
+    (format!("sends the {} header", header.name), None)
+}
+
+fn data_meaning(identifier: &str, value: &str, summary: &str) -> String {
+    let arg = DataArg::parse(identifier, value);
+    let source = match &arg.source {
+        BodySource::Inline(content) => plural(content.len(), "byte"),
+        BodySource::File(path) => format!("read from {}", path.display()),
+        BodySource::Stdin => "read from standard input".into(),
+    };
+    match arg.name {
+        Some(name) => format!("{} {}, {}", summary, name, source),
+        None => format!("{}, {}", summary, source),
+    }
+}
+
+fn option_meaning(value: &str, spec: &OptionSpec) -> (String, Option<Severity>) {
+    match spec.long {
+        "--user" | "--proxy-user" if !value.contains(':') => {
+            (format!("{}, asking for the password", spec.summary), None)
+        }
+        "--user" | "--proxy-user" => (
+            format!("{}, the password in the command", spec.summary),
+            Some(Severity::Warning),
+        ),
+        "--cookie" if value.contains('=') => (
+            format!(
+                "sends {}",
+                plural(
+                    value.split(';').filter(|c| c.contains('=')).count(),
+                    "cookie"
+                )
+            ),
+            None,
+        ),
+        "--cookie" => (format!("reads cookies from {}", value), None),
+        _ => (spec.summary.into(), spec.risk),
+    }
+}
+
+fn flag_meaning(identifier: &str) -> (String, Option<Severity>) {
+    let meanings = options::long_flag_names(identifier)
+        .into_iter()
+        .map(|long| match options::lookup(&long) {
+            // `--no-raw` turns `--raw` off.
+            Some(spec) if spec.long != long => (format!("turns off {}", spec.long), None),
+            Some(spec) => (spec.summary.to_string(), spec.risk),
+            None => (format!("{} is not a curl option", long), None),
+        })
+        .collect::<Vec<_>>();
+    let risk = meanings.iter().filter_map(|(_, risk)| *risk).min();
+    let meaning = meanings
+        .into_iter()
+        .map(|(meaning, _)| meaning)
+        .collect::<Vec<_>>()
+        .join("; ");
+    (meaning, risk)
+}
+
+fn annotate(curl: &Curl, curls: &[Curl]) -> Annotation {
+    match curl {
+        Curl::URL(url) => {
+            let risk = (url.schema == Schema::HTTP).then_some(Severity::Warning);
+            let host = if url.host.is_empty() {
+                url.to_string()
+            } else {
+                url.host.to_string()
+            };
+            let meaning = match risk {
+                Some(_) => format!("requests {} without encryption", host),
+                None => format!("requests {}", host),
+            };
+            Annotation::new(curl, meaning, risk)
+        }
+        Curl::Method(stru) => {
+            let method = Method::from(stru.data.as_deref().unwrap_or_default());
+            let implied = Method::implied(curls);
+            let meaning = if method == implied {
+                format!("sends {}, which curl would anyway", method)
+            } else {
+                format!("sends {} instead of {}", method, implied)
+            };
+            Annotation::new(curl, meaning, None)
+        }
+        Curl::Header(stru) => {
+            let (meaning, risk) = header_meaning(stru.data.as_deref().unwrap_or_default());
+            Annotation::new(curl, meaning, risk)
+        }
+        Curl::Data(stru) => {
+            let summary =
+                options::lookup(&stru.identifier).map_or("sends a body", |spec| spec.summary);
+            let meaning = data_meaning(
+                &stru.identifier,
+                stru.data.as_deref().unwrap_or_default(),
+                summary,
+            );
+            Annotation::new(curl, meaning, None)
+        }
+        Curl::Form(field) => {
+            let meaning = match &field.value {
+                FormValue::Text(_) => format!("sends the form field {}", field.name),
+                FormValue::File(path) => {
+                    format!("uploads {} as the form field {}", path, field.name)
+                }
+                FormValue::FileContents(path) => {
+                    format!(
+                        "sends the contents of {} as the form field {}",
+                        path, field.name
+                    )
+                }
+            };
+            Annotation::new(curl, meaning, None)
+        }
+        Curl::Flag(stru) => {
+            let (meaning, risk) = flag_meaning(&stru.identifier);
+            Annotation::new(curl, meaning, risk)
+        }
+        Curl::Option { name, value } => {
+            let (meaning, risk) = match options::lookup(name) {
+                Some(spec) => option_meaning(value, spec),
+                None => (format!("{} is not a curl option", name), None),
+            };
+            Annotation::new(curl, meaning, risk)
+        }
+        Curl::Unknown(..) => Annotation::new(curl, "not understood, curl may reject it", None),
+    }
+}
+
+/// Say what every argument of a command does, in command order, flagging
+/// the risky ones.
+///
+/// ```
+/// use winnowcurl::curl::explain::explain;
+/// use winnowcurl::curl::parser::curl_cmd_parse;
+/// use winnowcurl::diagnostic::Severity;
+///
+/// let curls = curl_cmd_parse("curl https://example.com -k").unwrap();
+/// let annotations = explain(&curls);
+/// assert_eq!(annotations[1].meaning, "disables TLS certificate verification");
+/// assert_eq!(annotations[1].risk, Some(Severity::Error));
+/// ```
+pub fn explain(curls: &[Curl]) -> Vec<Annotation> {
+    // The method a part implies depends on that part's options only.
+    curls
+        .split_inclusive(options::is_next)
+        .flat_map(|part| part.iter().map(|curl| annotate(curl, part)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    fn explained(command: &str) -> Vec<Annotation> {
+        explain(&curl_cmd_parse(command).unwrap())
+    }
+
+    #[rstest]
+    #[case(
+        "curl https://a.com --insecure",
+        "disables TLS certificate verification",
+        Some(Severity::Error)
+    )]
+    #[case(
+        "curl https://a.com -sSL",
+        "hides the progress meter and errors; shows errors even when silent; follows redirects",
+        None
+    )]
+    #[case(
+        "curl https://a.com -kL",
+        "disables TLS certificate verification; follows redirects",
+        Some(Severity::Error)
+    )]
+    #[case(
+        "curl https://a.com --location-trusted",
+        "follows redirects, sending the credentials to other hosts too",
+        Some(Severity::Warning)
+    )]
+    #[case("curl https://a.com --no-raw", "turns off --raw", None)]
+    #[case(
+        "curl https://a.com -H 'Cookie: a=1; b=2; c=3'",
+        "sends 3 cookies",
+        None
+    )]
+    #[case("curl https://a.com -H 'Cookie: a=1'", "sends 1 cookie", None)]
+    #[case(
+        "curl https://a.com -H 'Authorization: Bearer x'",
+        "sends credentials in the Authorization header",
+        Some(Severity::Warning)
+    )]
+    #[case("curl https://a.com -H 'Accept:'", "removes the Accept header", None)]
+    #[case("curl https://a.com -b 'a=1; b=2'", "sends 2 cookies", None)]
+    #[case(
+        "curl https://a.com -b cookies.txt",
+        "reads cookies from cookies.txt",
+        None
+    )]
+    #[case(
+        "curl https://a.com -u alice",
+        "sends credentials, asking for the password",
+        None
+    )]
+    #[case(
+        "curl https://a.com -u alice:secret",
+        "sends credentials, the password in the command",
+        Some(Severity::Warning)
+    )]
+    #[case(
+        "curl https://a.com -m 30",
+        "gives up the whole transfer after a number of seconds",
+        None
+    )]
+    #[case(
+        "curl https://a.com -d a=1",
+        "sends a form-encoded body, 3 bytes",
+        None
+    )]
+    #[case(
+        "curl https://a.com -d @body.json",
+        "sends a form-encoded body, read from body.json",
+        None
+    )]
+    #[case(
+        "curl https://a.com --data-urlencode q@query.txt",
+        "sends a URL-encoded body field q, read from query.txt",
+        None
+    )]
+    #[case(
+        "curl https://a.com -F file=@photo.png",
+        "uploads photo.png as the form field file",
+        None
+    )]
+    #[case("curl https://a.com -X PATCH", "sends PATCH instead of GET", None)]
+    #[case(
+        "curl https://a.com -d a=1 -X POST",
+        "sends POST, which curl would anyway",
+        None
+    )]
+    fn test_explain(#[case] command: &str, #[case] meaning: &str, #[case] risk: Option<Severity>) {
+        let annotations = explained(command);
+        let last = annotations.last().unwrap();
+        assert_eq!(last.meaning, meaning);
+        assert_eq!(last.risk, risk);
+    }
+
+    #[rstest]
+    #[case(
+        "curl http://a.com",
+        "requests a.com without encryption",
+        Some(Severity::Warning)
+    )]
+    #[case("curl https://a.com", "requests a.com", None)]
+    fn test_explain_url(
+        #[case] command: &str,
+        #[case] meaning: &str,
+        #[case] risk: Option<Severity>,
+    ) {
+        let annotations = explained(command);
+        assert_eq!(annotations[0].meaning, meaning);
+        assert_eq!(annotations[0].risk, risk);
+    }
+
+    #[rstest]
+    fn test_explain_next_parts() {
+        let annotations = explained("curl https://a.com -d a=1 --next https://b.com -X POST");
+        assert_eq!(
+            annotations.last().unwrap().meaning,
+            "sends POST instead of GET"
+        );
+    }
+
+    #[rstest]
+    fn test_explanation_display() {
+        let annotations = explained("curl https://a.com -k -u alice:secret");
+        assert_eq!(
+            Explanation(&annotations).to_string(),
+            concat!(
+                "   'https://a.com'        → requests a.com\n",
+                "!! -k                     → disables TLS certificate verification\n",
+                "!  --user 'alice:secret'  → sends credentials, the password in the command\n",
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_explain_serialize() {
+        let annotations = explained("curl https://a.com -k");
+        assert_eq!(
+            serde_json::to_value(&annotations[1]).unwrap(),
+            serde_json::json!({
+                "text": "-k",
+                "meaning": "disables TLS certificate verification",
+                "risk": "error",
+            })
+        );
+    }
+}
@@ -0,0 +1,545 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::ast::Curl;
+use super::body::{BodySource, DataArg};
+use super::config::ParserConfig;
+use super::form::{FormField, FormValue};
+use crate::diagnostic::{Code, Diagnostic};
+
+/// What a file referenced by a curl command is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileKind {
+    /// A request body, e.g. `-d @body.json` or `-T upload.bin`.
+    Body,
+    /// Extra headers, e.g. `-H @headers.txt`.
+    Headers,
+    /// A cookie file, e.g. `-b cookies.txt`.
+    Cookies,
+    /// A client certificate, key or CA bundle, e.g. `--cacert ca.pem`.
+    Certificate,
+    /// A config file holding more options, e.g. `-K api.cfg`.
+    Config,
+}
+
+/// A file a curl command reads from, with the option referencing it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileRef {
+    pub kind: FileKind,
+    pub option: String,
+    pub path: PathBuf,
+}
+
+impl FileRef {
+    fn new(kind: FileKind, option: &str, path: &str) -> Self {
+        FileRef {
+            kind,
+            option: option.into(),
+            path: path.into(),
+        }
+    }
+}
+
+/// A referenced file together with its contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedFile {
+    pub file: FileRef,
+    pub contents: Vec<u8>,
+}
+
+/// Why a referenced file could not be read.
+#[derive(Debug)]
+pub enum ResolveError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// The file lies outside the sandbox root of the [`ParserConfig`].
+    OutsideSandbox {
+        path: PathBuf,
+    },
+    /// The file is bigger than the maximum size of the [`ParserConfig`].
+    TooLarge {
+        path: PathBuf,
+        limit: u64,
+    },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Io { path, source } => {
+                write!(f, "cannot read {}: {}", path.display(), source)
+            }
+            ResolveError::OutsideSandbox { path } => {
+                write!(f, "{} is outside the sandbox root", path.display())
+            }
+            ResolveError::TooLarge { path, limit } => {
+                write!(f, "{} is larger than {} bytes", path.display(), limit)
+            }
+        }
+    }
+}
+
+impl ResolveError {
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(Code::UnreadableFile, self.to_string())
+    }
+}
+
+impl std::error::Error for ResolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ResolveError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// The file named by an `@file` argument; `@-` (stdin) is not a file.
+fn at_file(value: &str) -> Option<&str> {
+    value
+        .strip_prefix('@')
+        .filter(|path| *path != "-" && !path.is_empty())
+}
+
+fn option_file_ref(name: &str, value: &str) -> Option<FileRef> {
+    let path = match name {
+        "--json" | "--proxy-header" => at_file(value)?,
+        "--upload-file" if value != "-" && value != "." => value,
+        "--cookie" if !value.contains('=') && value != "-" => value,
+        "--config" if value != "-" => value,
+        "--cacert" | "--capath" | "--crlfile" | "--key" | "--proxy-cacert" | "--proxy-capath"
+        | "--proxy-crlfile" | "--proxy-key" | "--pubkey" => value,
+        // A certificate may carry its password after a colon.
+        "--cert" | "--proxy-cert" if !value.starts_with("pkcs11:") => {
+            value.split_once(':').map_or(value, |(path, _)| path)
+        }
+        _ => return None,
+    };
+    let kind = match name {
+        "--json" | "--upload-file" => FileKind::Body,
+        "--proxy-header" => FileKind::Headers,
+        "--cookie" => FileKind::Cookies,
+        "--config" => FileKind::Config,
+        _ => FileKind::Certificate,
+    };
+    (!path.is_empty()).then(|| FileRef::new(kind, name, path))
+}
+
+/// The file read for a single option, if any.
+fn file_ref(curl: &Curl<'_>) -> Option<FileRef> {
+    match curl {
+        Curl::Data(stru) => match DataArg::from_stru(stru).source {
+            BodySource::File(path) => Some(FileRef {
+                kind: FileKind::Body,
+                option: stru.identifier.to_string(),
+                path,
+            }),
+            _ => None,
+        },
+        Curl::Header(stru) => {
+            let path = at_file(stru.data.as_deref()?)?;
+            Some(FileRef::new(FileKind::Headers, &stru.identifier, path))
+        }
+        Curl::Option { name, value } => option_file_ref(name, value),
+        _ => None,
+    }
+}
+
+/// The files read for a form field: its `@` or `<` content and any
+/// `;headers=@file`.
+fn form_file_refs(field: &FormField) -> impl Iterator<Item = FileRef> + '_ {
+    let content = match &field.value {
+        FormValue::File(path) | FormValue::FileContents(path) if path != "-" => {
+            Some(FileRef::new(FileKind::Body, &field.identifier, path))
+        }
+        _ => None,
+    };
+    let headers = field
+        .headers
+        .iter()
+        .filter_map(|headers| at_file(headers))
+        .map(|path| FileRef::new(FileKind::Headers, &field.identifier, path));
+    content.into_iter().chain(headers)
+}
+
+/// Collect the files referenced by a parsed command, in command order.
+pub fn file_refs(curls: &[Curl<'_>]) -> Vec<FileRef> {
+    let mut refs = Vec::new();
+    for curl in curls {
+        match curl {
+            Curl::Form(field) => refs.extend(form_file_refs(field)),
+            curl => refs.extend(file_ref(curl)),
+        }
+    }
+    refs
+}
+
+/// Reads the files referenced by a command, relative paths being taken from
+/// a base directory.
+///
+/// [`FileResolver::resolve`] blocks on `std::fs`; with the `async` feature,
+/// [`FileResolver::resolve_async`] reads through `tokio::fs` instead so it
+/// can be awaited from a runtime without stalling it. Both honour the
+/// sandbox root and size limit of the [`ParserConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct FileResolver {
+    base_dir: Option<PathBuf>,
+    config: ParserConfig,
+}
+
+impl FileResolver {
+    /// A resolver reading relative paths from the current directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_base_dir(mut self, base_dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(base_dir.into());
+        self
+    }
+
+    pub fn with_config(mut self, config: ParserConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    fn full_path(&self, path: &Path) -> PathBuf {
+        match self.base_dir.as_deref().or(self.config.sandbox_root()) {
+            Some(base_dir) => base_dir.join(path),
+            None => path.to_path_buf(),
+        }
+    }
+
+    /// Check the canonical form of `path` against the sandbox root.
+    fn check_sandbox(
+        &self,
+        path: &Path,
+        canonical: &Path,
+        canonical_root: Option<&Path>,
+    ) -> Result<(), ResolveError> {
+        match canonical_root {
+            Some(root) if !canonical.starts_with(root) => Err(ResolveError::OutsideSandbox {
+                path: path.to_path_buf(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_size(&self, path: &Path, size: u64) -> Result<(), ResolveError> {
+        match self.config.max_file_size() {
+            Some(limit) if size > limit => Err(ResolveError::TooLarge {
+                path: path.to_path_buf(),
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>, ResolveError> {
+        use std::io::Read;
+
+        let io_error = |source| ResolveError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let canonical_root = match self.config.sandbox_root() {
+            Some(root) => Some(std::fs::canonicalize(root).map_err(io_error)?),
+            None => None,
+        };
+        let canonical = std::fs::canonicalize(path).map_err(io_error)?;
+        self.check_sandbox(path, &canonical, canonical_root.as_deref())?;
+
+        let file = std::fs::File::open(&canonical).map_err(io_error)?;
+        let size = file.metadata().map_err(io_error)?.len();
+        self.check_size(path, size)?;
+
+        // The file may grow after the metadata check, so cap the read too.
+        let limit = self
+            .config
+            .max_file_size()
+            .map_or(u64::MAX, |limit| limit.saturating_add(1));
+        let mut contents = Vec::new();
+        file.take(limit)
+            .read_to_end(&mut contents)
+            .map_err(io_error)?;
+        self.check_size(path, contents.len() as u64)?;
+        Ok(contents)
+    }
+
+    #[cfg(feature = "async")]
+    async fn read_async(&self, path: &Path) -> Result<Vec<u8>, ResolveError> {
+        use tokio::io::AsyncReadExt;
+
+        let io_error = |source| ResolveError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let canonical_root = match self.config.sandbox_root() {
+            Some(root) => Some(tokio::fs::canonicalize(root).await.map_err(io_error)?),
+            None => None,
+        };
+        let canonical = tokio::fs::canonicalize(path).await.map_err(io_error)?;
+        self.check_sandbox(path, &canonical, canonical_root.as_deref())?;
+
+        let file = tokio::fs::File::open(&canonical).await.map_err(io_error)?;
+        let size = file.metadata().await.map_err(io_error)?.len();
+        self.check_size(path, size)?;
+
+        let limit = self
+            .config
+            .max_file_size()
+            .map_or(u64::MAX, |limit| limit.saturating_add(1));
+        let mut contents = Vec::new();
+        file.take(limit)
+            .read_to_end(&mut contents)
+            .await
+            .map_err(io_error)?;
+        self.check_size(path, contents.len() as u64)?;
+        Ok(contents)
+    }
+
+    /// Read one file, relative paths being taken from the base directory.
+    pub fn read_file(&self, path: &Path) -> Result<Vec<u8>, ResolveError> {
+        self.read(&self.full_path(path))
+    }
+
+    /// Read every file referenced by `curls`, blocking the current thread.
+    pub fn resolve(&self, curls: &[Curl<'_>]) -> Result<Vec<ResolvedFile>, ResolveError> {
+        file_refs(curls)
+            .into_iter()
+            .map(|file| {
+                let contents = self.read(&self.full_path(&file.path))?;
+                Ok(ResolvedFile { file, contents })
+            })
+            .collect()
+    }
+
+    /// Read every file referenced by `curls` without blocking the runtime.
+    #[cfg(feature = "async")]
+    pub async fn resolve_async(
+        &self,
+        curls: &[Curl<'_>],
+    ) -> Result<Vec<ResolvedFile>, ResolveError> {
+        let mut resolved = Vec::new();
+        for file in file_refs(curls) {
+            let contents = self.read_async(&self.full_path(&file.path)).await?;
+            resolved.push(ResolvedFile { file, contents });
+        }
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        "curl 'https://a.com' -d @body.json",
+        FileKind::Body,
+        "-d",
+        "body.json"
+    )]
+    #[case(
+        "curl 'https://a.com' --data-urlencode name@msg.txt",
+        FileKind::Body,
+        "--data-urlencode",
+        "msg.txt"
+    )]
+    #[case(
+        "curl 'https://a.com' --json @req.json",
+        FileKind::Body,
+        "--json",
+        "req.json"
+    )]
+    #[case(
+        "curl 'https://a.com' -T up.bin",
+        FileKind::Body,
+        "--upload-file",
+        "up.bin"
+    )]
+    #[case(
+        "curl 'https://a.com' -H @headers.txt",
+        FileKind::Headers,
+        "-H",
+        "headers.txt"
+    )]
+    #[case(
+        "curl 'https://a.com' -b jar.txt",
+        FileKind::Cookies,
+        "--cookie",
+        "jar.txt"
+    )]
+    #[case(
+        "curl 'https://a.com' -E client.pem:secret",
+        FileKind::Certificate,
+        "--cert",
+        "client.pem"
+    )]
+    #[case(
+        "curl 'https://a.com' --cacert ca.pem",
+        FileKind::Certificate,
+        "--cacert",
+        "ca.pem"
+    )]
+    #[case(
+        "curl 'https://a.com' -K api.cfg",
+        FileKind::Config,
+        "--config",
+        "api.cfg"
+    )]
+    fn test_file_refs(
+        #[case] input: &str,
+        #[case] kind: FileKind,
+        #[case] option: &str,
+        #[case] path: &str,
+    ) {
+        let curls = curl_cmd_parse(input).unwrap();
+        assert_eq!(file_refs(&curls), vec![FileRef::new(kind, option, path)]);
+    }
+
+    #[rstest]
+    #[case("curl 'https://a.com' --data-raw @literal")]
+    #[case("curl 'https://a.com' -d @-")]
+    #[case("curl 'https://a.com' -d 'a=1'")]
+    #[case("curl 'https://a.com' --data-urlencode 'q=a@b'")]
+    #[case("curl 'https://a.com' -b 'session=1'")]
+    #[case("curl 'https://a.com' -H 'Accept: */*'")]
+    #[case("curl 'https://a.com' -F 'name=bob'")]
+    #[case("curl 'https://a.com' -F 'file=@-'")]
+    #[case("curl 'https://a.com' --form-string 'file=@a.png'")]
+    fn test_file_refs_ignores_inline_values(#[case] input: &str) {
+        let curls = curl_cmd_parse(input).unwrap();
+        assert!(file_refs(&curls).is_empty());
+    }
+
+    #[rstest]
+    fn test_file_refs_form() {
+        let curls = curl_cmd_parse(
+            "curl 'https://a.com' -F 'file=@a.png;headers=@h.txt' --form 'story=<story.txt'",
+        )
+        .unwrap();
+        assert_eq!(
+            file_refs(&curls),
+            vec![
+                FileRef::new(FileKind::Body, "-F", "a.png"),
+                FileRef::new(FileKind::Headers, "-F", "h.txt"),
+                FileRef::new(FileKind::Body, "--form", "story.txt"),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_resolve_reads_from_base_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("body.json"), r#"{"a": 1}"#).unwrap();
+
+        let curls = curl_cmd_parse("curl 'https://a.com' -d @body.json").unwrap();
+        let resolved = FileResolver::new()
+            .with_base_dir(dir.path())
+            .resolve(&curls)
+            .unwrap();
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].contents, br#"{"a": 1}"#);
+    }
+
+    #[rstest]
+    fn test_resolve_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let curls = curl_cmd_parse("curl 'https://a.com' --cacert ca.pem").unwrap();
+        let err = FileResolver::new()
+            .with_base_dir(dir.path())
+            .resolve(&curls)
+            .unwrap_err();
+        let ResolveError::Io { path, source } = err else {
+            panic!("expected an I/O error, got {:?}", err);
+        };
+        assert_eq!(path, dir.path().join("ca.pem"));
+        assert_eq!(source.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[rstest]
+    #[case("-d @../secret.txt")]
+    #[case("--cacert ../secret.txt")]
+    fn test_resolve_outside_sandbox(#[case] option: &str) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("root");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "s3cret").unwrap();
+
+        let input = format!("curl 'https://a.com' {}", option);
+        let curls = curl_cmd_parse(&input).unwrap();
+        let resolver =
+            FileResolver::new().with_config(ParserConfig::new().with_sandbox_root(&root));
+        assert!(matches!(
+            resolver.resolve(&curls),
+            Err(ResolveError::OutsideSandbox { .. })
+        ));
+    }
+
+    #[rstest]
+    fn test_resolve_absolute_path_outside_sandbox() {
+        let dir = tempfile::tempdir().unwrap();
+        let secret = dir.path().join("secret.txt");
+        std::fs::write(&secret, "s3cret").unwrap();
+        let root = tempfile::tempdir().unwrap();
+
+        let input = format!("curl 'https://a.com' -d @{}", secret.display());
+        let curls = curl_cmd_parse(&input).unwrap();
+        let resolver =
+            FileResolver::new().with_config(ParserConfig::new().with_sandbox_root(root.path()));
+        assert!(matches!(
+            resolver.resolve(&curls),
+            Err(ResolveError::OutsideSandbox { .. })
+        ));
+    }
+
+    #[rstest]
+    #[case(4, true)]
+    #[case(u64::MAX, true)]
+    #[case(3, false)]
+    fn test_resolve_max_file_size(#[case] limit: u64, #[case] ok: bool) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("body"), "1234").unwrap();
+
+        let curls = curl_cmd_parse("curl 'https://a.com' -d @body").unwrap();
+        let config = ParserConfig::new()
+            .with_sandbox_root(dir.path())
+            .with_max_file_size(limit);
+        let result = FileResolver::new().with_config(config).resolve(&curls);
+        if ok {
+            assert_eq!(result.unwrap()[0].contents, b"1234");
+        } else {
+            assert!(matches!(
+                result,
+                Err(ResolveError::TooLarge { limit: 3, .. })
+            ));
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_resolve_async_matches_blocking() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("h.txt"), "Accept: */*\n").unwrap();
+        std::fs::write(dir.path().join("up.bin"), [0u8, 1, 2]).unwrap();
+
+        let curls = curl_cmd_parse("curl 'https://a.com' -H @h.txt -T up.bin").unwrap();
+        let resolver = FileResolver::new().with_base_dir(dir.path());
+        let blocking = resolver.resolve(&curls).unwrap();
+        let awaited = resolver.resolve_async(&curls).await.unwrap();
+        assert_eq!(blocking, awaited);
+        assert_eq!(awaited.len(), 2);
+
+        let limited = resolver.with_config(ParserConfig::new().with_max_file_size(2));
+        assert!(matches!(
+            limited.resolve_async(&curls).await,
+            Err(ResolveError::TooLarge { .. })
+        ));
+    }
+}
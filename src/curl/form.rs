@@ -0,0 +1,250 @@
+use serde::Serialize;
+use std::fmt;
+use winnow::{
+    ModalResult, Parser,
+    combinator::{alt, delimited, eof, opt, preceded, repeat, terminated},
+    token::{any, none_of, one_of, take_till},
+};
+
+/// What a multipart form field sends.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum FormValue {
+    /// `name=value`: a text value.
+    Text(String),
+    /// `name=@path`: the file, uploaded as an attachment.
+    File(String),
+    /// `name=<path`: the contents of the file, sent as a text value.
+    FileContents(String),
+}
+
+/// One `-F`/`--form` field of a multipart request, e.g.
+/// `file=@photo.png;type=image/png`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct FormField {
+    /// The option as written, `-F`, `--form` or `--form-string`.
+    pub identifier: String,
+    pub name: String,
+    pub value: FormValue,
+    /// `;type=`, the content type of the part.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// `;filename=`, the file name sent instead of the local one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// `;headers=`, extra headers of the part, or `@file` to read them from.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<String>,
+}
+
+/// A double-quoted word, where a backslash escapes the next character.
+fn quoted(s: &mut &str) -> ModalResult<String> {
+    delimited(
+        '"',
+        repeat(0.., alt((preceded('\\', any), none_of(['"', '\\'])))),
+        '"',
+    )
+    .parse_next(s)
+}
+
+/// A value up to the next `;`, or a quoted word which may contain one.
+fn word(s: &mut &str) -> ModalResult<String> {
+    alt((
+        terminated(quoted, take_till(0.., ';')),
+        take_till(0.., ';').map(str::to_string),
+    ))
+    .parse_next(s)
+}
+
+/// A `;key=value` attribute following the content.
+type Attribute<'a> = (&'a str, Option<String>);
+
+fn attribute<'a>(s: &mut &'a str) -> ModalResult<Attribute<'a>> {
+    preceded(
+        ';',
+        (
+            take_till(0.., ['=', ';']).map(str::trim),
+            opt(preceded('=', word)),
+        ),
+    )
+    .parse_next(s)
+}
+
+fn form_field<'a>(s: &mut &'a str) -> ModalResult<(&'a str, FormValue, Vec<Attribute<'a>>)> {
+    let name = terminated(take_till(0.., '='), '=').parse_next(s)?;
+    let value = (opt(one_of(['@', '<'])), word)
+        .map(|(kind, content)| match kind {
+            Some('@') => FormValue::File(content),
+            Some(_) => FormValue::FileContents(content),
+            None => FormValue::Text(content),
+        })
+        .parse_next(s)?;
+    let attributes = terminated(repeat(0.., attribute), eof).parse_next(s)?;
+    Ok((name, value, attributes))
+}
+
+impl FormField {
+    /// Parse the argument of `-F`/`--form` or `--form-string`, `None` when it
+    /// has no `name=`.
+    ///
+    /// `--form-string` values are taken literally. Attributes curl doesn't
+    /// know, such as `;encoder=`, are skipped like curl does.
+    pub fn parse(identifier: &str, arg: &str) -> Option<Self> {
+        let mut field = FormField {
+            identifier: identifier.into(),
+            name: String::new(),
+            value: FormValue::Text(String::new()),
+            content_type: None,
+            filename: None,
+            headers: Vec::new(),
+        };
+
+        if identifier == "--form-string" {
+            let (name, value) = arg.split_once('=')?;
+            field.name = name.into();
+            field.value = FormValue::Text(value.into());
+            return Some(field);
+        }
+
+        let (name, value, attributes) = form_field.parse(arg).ok()?;
+        field.name = name.into();
+        field.value = value;
+        for (key, value) in attributes {
+            match (key, value) {
+                ("type", Some(value)) => field.content_type = Some(value),
+                ("filename", Some(value)) => field.filename = Some(value),
+                ("headers", Some(value)) => field.headers.push(value),
+                _ => {}
+            }
+        }
+        Some(field)
+    }
+
+    /// Whether the field is a file upload.
+    pub fn is_file(&self) -> bool {
+        matches!(self.value, FormValue::File(_))
+    }
+}
+
+/// Write `value`, quoting it when it holds characters with a meaning of
+/// their own in a form argument.
+fn write_word(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    if !value.contains([';', '"']) && !value.starts_with(char::is_whitespace) {
+        return f.write_str(value);
+    }
+    f.write_str("\"")?;
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            f.write_str("\\")?;
+        }
+        write!(f, "{}", c)?;
+    }
+    f.write_str("\"")
+}
+
+/// The argument of the option, e.g. `file=@photo.png;type=image/png`.
+impl fmt::Display for FormField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}=", self.name)?;
+        if self.identifier == "--form-string"
+            && let FormValue::Text(value) = &self.value
+        {
+            return f.write_str(value);
+        }
+
+        match &self.value {
+            FormValue::Text(value) => write_word(f, value)?,
+            FormValue::File(path) => {
+                f.write_str("@")?;
+                write_word(f, path)?;
+            }
+            FormValue::FileContents(path) => {
+                f.write_str("<")?;
+                write_word(f, path)?;
+            }
+        }
+        if let Some(content_type) = &self.content_type {
+            f.write_str(";type=")?;
+            write_word(f, content_type)?;
+        }
+        if let Some(filename) = &self.filename {
+            f.write_str(";filename=")?;
+            write_word(f, filename)?;
+        }
+        for headers in &self.headers {
+            f.write_str(";headers=")?;
+            write_word(f, headers)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn field(name: &str, value: FormValue) -> FormField {
+        FormField {
+            identifier: "-F".into(),
+            name: name.into(),
+            value,
+            content_type: None,
+            filename: None,
+            headers: Vec::new(),
+        }
+    }
+
+    #[rstest]
+    #[case("name=bob", field("name", FormValue::Text("bob".into())))]
+    #[case("empty=", field("empty", FormValue::Text("".into())))]
+    #[case("file=@photo.png", field("file", FormValue::File("photo.png".into())))]
+    #[case("story=<story.txt", field("story", FormValue::FileContents("story.txt".into())))]
+    #[case("eq=a=b", field("eq", FormValue::Text("a=b".into())))]
+    #[case(r#"msg="a;b \"c\"""#, field("msg", FormValue::Text(r#"a;b "c""#.into())))]
+    #[case("msg=a;b", field("msg", FormValue::Text("a".into())))]
+    #[case("file=@a.png;encoder=base64", field("file", FormValue::File("a.png".into())))]
+    fn test_parse(#[case] arg: &str, #[case] expected: FormField) {
+        assert_eq!(FormField::parse("-F", arg), Some(expected));
+    }
+
+    #[rstest]
+    fn test_parse_attributes() {
+        let parsed = FormField::parse(
+            "--form",
+            r#"file=@"my;photo.png";type=image/png; filename="me.png";headers="X-A: 1";headers=@extra.txt"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.value, FormValue::File("my;photo.png".into()));
+        assert_eq!(parsed.content_type.as_deref(), Some("image/png"));
+        assert_eq!(parsed.filename.as_deref(), Some("me.png"));
+        assert_eq!(parsed.headers, vec!["X-A: 1", "@extra.txt"]);
+        assert!(parsed.is_file());
+    }
+
+    #[rstest]
+    fn test_parse_form_string() {
+        let parsed = FormField::parse("--form-string", "msg=@not-a-file;type=x").unwrap();
+        assert_eq!(parsed.value, FormValue::Text("@not-a-file;type=x".into()));
+        assert_eq!(parsed.content_type, None);
+        assert_eq!(parsed.to_string(), "msg=@not-a-file;type=x");
+    }
+
+    #[rstest]
+    #[case("no-equals")]
+    #[case("")]
+    fn test_parse_invalid(#[case] arg: &str) {
+        assert_eq!(FormField::parse("-F", arg), None);
+    }
+
+    #[rstest]
+    #[case("name=bob")]
+    #[case("file=@photo.png;type=image/png;filename=me.png")]
+    #[case(r#"msg="a;b \"c\"""#)]
+    #[case(r#"file=<"x;y.txt";headers="X-A: a;b""#)]
+    fn test_display_round_trip(#[case] arg: &str) {
+        let parsed = FormField::parse("-F", arg).unwrap();
+        assert_eq!(parsed.to_string(), arg);
+        assert_eq!(FormField::parse("-F", &parsed.to_string()), Some(parsed));
+    }
+}
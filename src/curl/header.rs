@@ -0,0 +1,444 @@
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde::ser::{SerializeMap, Serializer};
+use std::fmt;
+use std::sync::Arc;
+
+use super::intern::Interner;
+use super::parser::Curl;
+
+/// Split a raw `-H` argument like `"Accept: */*"` into its name and value.
+pub fn split_header(raw: &str) -> Option<(&str, &str)> {
+    let (name, value) = raw.split_once(':')?;
+    let name = name.trim();
+    if name.is_empty() {
+        None
+    } else {
+        Some((name, value.trim()))
+    }
+}
+
+/// One `-H` header, e.g. `Accept: */*`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Header {
+    pub name: String,
+    pub value: String,
+}
+
+impl Header {
+    pub fn new(name: &str, value: &str) -> Self {
+        Header {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Parse a raw `-H` argument, `None` when it has no name.
+    pub fn parse(raw: &str) -> Option<Self> {
+        split_header(raw).map(|(name, value)| Header::new(name, value))
+    }
+
+    /// The header of a `Curl::Header` node.
+    pub fn from_curl(curl: &Curl) -> Option<Self> {
+        match curl {
+            Curl::Header(stru) => stru.data.as_deref().and_then(Header::parse),
+            _ => None,
+        }
+    }
+
+    /// Whether the header is called `name`, ignoring case.
+    pub fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+
+    /// The cookies of a `Cookie` header, none for other headers.
+    pub fn cookies(&self) -> Vec<Cookie> {
+        if self.is("cookie") {
+            parse_cookies(&self.value).collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl fmt::Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.value)
+    }
+}
+
+/// Serialized as `{"name": ..., "value": ...}`, with the `cookies` of a
+/// `Cookie` header next to them.
+impl Serialize for Header {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let cookies = self.cookies();
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("name", &self.name)?;
+        map.serialize_entry("value", &self.value)?;
+        if self.is("cookie") {
+            map.serialize_entry("cookies", &cookies)?;
+        }
+        map.end()
+    }
+}
+
+/// One `name=value` pair of a `Cookie` header.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+}
+
+/// Split a `Cookie` header value like `a=1; b=2` into its cookies, skipping
+/// pairs without a `=`.
+pub fn parse_cookies(value: &str) -> impl Iterator<Item = Cookie> + '_ {
+    value.split(';').filter_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        let name = name.trim();
+        (!name.is_empty()).then(|| Cookie {
+            name: name.into(),
+            value: value.trim().into(),
+        })
+    })
+}
+
+/// Headers of a curl command, kept in the order they were first given.
+///
+/// Names are matched case-insensitively, while the casing of the first
+/// occurrence is kept for display. Repeated headers keep all their values,
+/// grouped under the position where the name first appeared.
+///
+/// Names are reference counted so that headers built through a shared
+/// [`Interner`] reuse one allocation per distinct name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Headers {
+    entries: IndexMap<Arc<str>, HeaderEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct HeaderEntry {
+    name: Arc<str>,
+    values: Vec<String>,
+}
+
+fn header_key(name: &str) -> String {
+    name.to_ascii_lowercase()
+}
+
+impl Headers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push_value(&mut self, key: &str, names: impl FnOnce() -> (Arc<str>, Arc<str>), value: &str) {
+        match self.entries.get_mut(key) {
+            Some(entry) => entry.values.push(value.into()),
+            None => {
+                let (key, name) = names();
+                self.entries.insert(
+                    key,
+                    HeaderEntry {
+                        name,
+                        values: vec![value.into()],
+                    },
+                );
+            }
+        }
+    }
+
+    /// Append a value, keeping the position of an already present name.
+    pub fn append(&mut self, name: &str, value: &str) {
+        let key = header_key(name);
+        self.push_value(&key, || (Arc::from(key.as_str()), Arc::from(name)), value);
+    }
+
+    /// Like [`Headers::append`], but takes the name allocations from `interner`.
+    pub fn append_interned(&mut self, interner: &mut Interner, name: &str, value: &str) {
+        let symbol = interner.intern(name);
+        let key = interner.fold(symbol);
+        self.push_value(
+            interner.resolve(key),
+            || (interner.resolve_arc(key), interner.resolve_arc(symbol)),
+            value,
+        );
+    }
+
+    /// Collect the headers of `curls`, sharing name allocations through `interner`.
+    pub fn from_curls_interned(curls: &[Curl<'_>], interner: &mut Interner) -> Self {
+        let mut headers = Headers::new();
+        for curl in curls {
+            if let Curl::Header(header) = curl
+                && let Some((name, value)) = header.data.as_deref().and_then(split_header)
+            {
+                headers.append_interned(interner, name, value);
+            }
+        }
+        headers
+    }
+
+    /// Replace every value of `name`, keeping its original position and casing.
+    pub fn insert(&mut self, name: &str, value: &str) {
+        let key = header_key(name);
+        match self.entries.get_mut(key.as_str()) {
+            Some(entry) => entry.values = vec![value.into()],
+            None => {
+                self.entries.insert(
+                    Arc::from(key),
+                    HeaderEntry {
+                        name: Arc::from(name),
+                        values: vec![value.into()],
+                    },
+                );
+            }
+        }
+    }
+
+    /// Remove `name`, preserving the order of the remaining headers.
+    pub fn remove(&mut self, name: &str) -> Option<Vec<String>> {
+        self.entries
+            .shift_remove(header_key(name).as_str())
+            .map(|entry| entry.values)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.get_all(name).first().map(String::as_str)
+    }
+
+    pub fn get_all(&self, name: &str) -> &[String] {
+        self.entries
+            .get(header_key(name).as_str())
+            .map(|entry| entry.values.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(header_key(name).as_str())
+    }
+
+    /// The header names as the user first wrote them.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.values().map(|entry| &*entry.name)
+    }
+
+    /// Iterate over `(name, value)` pairs in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .values()
+            .flat_map(|entry| entry.values.iter().map(move |v| (&*entry.name, v.as_str())))
+    }
+
+    /// The cookies of every `Cookie` header, in order.
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.get_all("cookie")
+            .iter()
+            .flat_map(|value| parse_cookies(value))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.values().map(|entry| entry.values.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for Headers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}: {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> FromIterator<(&'a str, &'a str)> for Headers {
+    fn from_iter<T: IntoIterator<Item = (&'a str, &'a str)>>(iter: T) -> Self {
+        let mut headers = Headers::new();
+        for (name, value) in iter {
+            headers.append(name, value);
+        }
+        headers
+    }
+}
+
+impl From<&[Curl<'_>]> for Headers {
+    fn from(curls: &[Curl<'_>]) -> Self {
+        curls
+            .iter()
+            .filter_map(|curl| match curl {
+                Curl::Header(header) => header.data.as_deref().and_then(split_header),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case("Accept: */*", Some(("Accept", "*/*")))]
+    #[case("X-Empty:", Some(("X-Empty", "")))]
+    #[case("Host:example.com:8080", Some(("Host", "example.com:8080")))]
+    #[case(": value", None)]
+    #[case("no colon", None)]
+    fn test_split_header(#[case] input: String, #[case] expected: Option<(&str, &str)>) {
+        assert_eq!(split_header(&input), expected)
+    }
+
+    #[rstest]
+    #[case("a=1; b=2", vec![("a", "1"), ("b", "2")])]
+    #[case(" sid = x=y ;theme=dark;", vec![("sid", "x=y"), ("theme", "dark")])]
+    #[case("flag; =orphan; a=", vec![("a", "")])]
+    #[case("", vec![])]
+    fn test_parse_cookies(#[case] value: &str, #[case] expected: Vec<(&str, &str)>) {
+        let cookies: Vec<(String, String)> = parse_cookies(value)
+            .map(|cookie| (cookie.name, cookie.value))
+            .collect();
+        let expected: Vec<(String, String)> = expected
+            .into_iter()
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect();
+        assert_eq!(cookies, expected);
+    }
+
+    #[rstest]
+    fn test_header() {
+        let curls =
+            curl_cmd_parse(r#"curl "https://a.com" -H 'cookie: a=1; b=2' -H 'Accept: */*'"#)
+                .unwrap();
+        let headers: Vec<Header> = curls.iter().filter_map(Header::from_curl).collect();
+        assert_eq!(
+            headers,
+            vec![
+                Header::new("cookie", "a=1; b=2"),
+                Header::new("Accept", "*/*")
+            ]
+        );
+        assert!(headers[0].is("Cookie"));
+        assert_eq!(headers[0].cookies().len(), 2);
+        assert!(headers[1].cookies().is_empty());
+        assert_eq!(headers[1].to_string(), "Accept: */*");
+
+        assert_eq!(
+            serde_json::to_value(&headers).unwrap(),
+            serde_json::json!([
+                {"name": "cookie", "value": "a=1; b=2", "cookies": [
+                    {"name": "a", "value": "1"},
+                    {"name": "b", "value": "2"},
+                ]},
+                {"name": "Accept", "value": "*/*"},
+            ])
+        );
+    }
+
+    #[rstest]
+    fn test_headers_cookies() {
+        let headers: Headers = [("Cookie", "a=1"), ("X", "y=2"), ("COOKIE", "b=2")]
+            .into_iter()
+            .collect();
+        let names: Vec<String> = headers.cookies().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, vec!["a", "b"]);
+    }
+
+    #[rstest]
+    fn test_headers_keep_source_order() {
+        let input =
+            r#"curl "https://example.com" -H 'Zeta: 1' -H 'Alpha: 2' -H 'Zeta: 3' -H 'Mid: 4'"#;
+        let curls = curl_cmd_parse(input).unwrap();
+        let headers = Headers::from(curls.as_slice());
+
+        assert_eq!(
+            headers.names().collect::<Vec<_>>(),
+            vec!["Zeta", "Alpha", "Mid"]
+        );
+        assert_eq!(
+            headers.iter().collect::<Vec<_>>(),
+            vec![("Zeta", "1"), ("Zeta", "3"), ("Alpha", "2"), ("Mid", "4")]
+        );
+        assert_eq!(headers.len(), 4);
+        assert_eq!(headers.get("Zeta"), Some("1"));
+        assert_eq!(headers.get_all("Zeta"), ["1", "3"]);
+    }
+
+    #[rstest]
+    fn test_headers_mutation_keeps_order() {
+        let mut headers: Headers = [("A", "1"), ("B", "2"), ("C", "3")].into_iter().collect();
+        headers.insert("A", "one");
+        headers.remove("B");
+        headers.append("D", "4");
+
+        assert_eq!(
+            headers.iter().collect::<Vec<_>>(),
+            vec![("A", "one"), ("C", "3"), ("D", "4")]
+        );
+    }
+
+    #[rstest]
+    #[case("content-type")]
+    #[case("CONTENT-TYPE")]
+    #[case("Content-Type")]
+    fn test_headers_case_insensitive_lookup(#[case] name: String) {
+        let headers: Headers = [("Content-Type", "application/json"), ("accept", "*/*")]
+            .into_iter()
+            .collect();
+        assert_eq!(headers.get(&name), Some("application/json"));
+        assert!(headers.contains(&name));
+    }
+
+    #[rstest]
+    fn test_headers_retain_original_case() {
+        let mut headers: Headers = [("X-Request-ID", "1"), ("x-request-id", "2")]
+            .into_iter()
+            .collect();
+        headers.append("ACCEPT", "*/*");
+        headers.insert("accept", "text/html");
+
+        assert_eq!(headers.get_all("X-REQUEST-ID"), ["1", "2"]);
+        assert_eq!(
+            headers.to_string(),
+            "X-Request-ID: 1\nX-Request-ID: 2\nACCEPT: text/html"
+        );
+        assert_eq!(
+            headers.remove("Accept"),
+            Some(vec!["text/html".to_string()])
+        );
+        assert!(!headers.contains("accept"));
+    }
+
+    #[rstest]
+    fn test_headers_interned_share_names() {
+        let mut interner = Interner::new();
+        let a = curl_cmd_parse(r#"curl "https://a.com" -H 'Accept: */*' -H 'X-Id: 1'"#).unwrap();
+        let b = curl_cmd_parse(r#"curl "https://b.com" -H 'accept: text/html'"#).unwrap();
+        let a = Headers::from_curls_interned(&a, &mut interner);
+        let b = Headers::from_curls_interned(&b, &mut interner);
+
+        assert_eq!(
+            a,
+            Headers::from(
+                curl_cmd_parse(r#"curl "https://a.com" -H 'Accept: */*' -H 'X-Id: 1'"#)
+                    .unwrap()
+                    .as_slice()
+            )
+        );
+        assert_eq!(b.get("ACCEPT"), Some("text/html"));
+        assert_eq!(b.names().collect::<Vec<_>>(), vec!["accept"]);
+
+        let name_a = a.names().next().unwrap();
+        let key_b = b.entries.keys().next().unwrap();
+        assert_eq!(name_a, "Accept");
+        // "accept" is shared between a's key and b's key and name
+        assert!(Arc::ptr_eq(a.entries.keys().next().unwrap(), key_b));
+        // Accept, accept, X-Id, x-id
+        assert_eq!(interner.len(), 4);
+    }
+}
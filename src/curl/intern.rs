@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Handle to an interned string, cheap to copy and compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+/// String arena for names that repeat across many parsed commands, such as
+/// the header names of a large HAR import.
+///
+/// Every string is stored once, and each symbol also knows the symbol of its
+/// ASCII-lowercased form so case-insensitive comparison is an integer compare.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    lookup: HashMap<Arc<str>, Symbol>,
+    strings: Vec<Arc<str>>,
+    folded: Vec<Symbol>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert(&mut self, s: &str) -> Symbol {
+        if let Some(symbol) = self.lookup.get(s) {
+            return *symbol;
+        }
+
+        let symbol = Symbol(self.strings.len() as u32);
+        let arc: Arc<str> = Arc::from(s);
+        self.lookup.insert(arc.clone(), symbol);
+        self.strings.push(arc);
+        // Filled in by `intern` once the folded form is known.
+        self.folded.push(symbol);
+        symbol
+    }
+
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        let symbol = self.insert(s);
+        if s.bytes().any(|b| b.is_ascii_uppercase()) {
+            let folded = self.insert(&s.to_ascii_lowercase());
+            self.folded[symbol.0 as usize] = folded;
+        }
+        symbol
+    }
+
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.0 as usize]
+    }
+
+    /// The shared allocation behind a symbol.
+    pub fn resolve_arc(&self, symbol: Symbol) -> Arc<str> {
+        self.strings[symbol.0 as usize].clone()
+    }
+
+    /// The symbol of the ASCII-lowercased string.
+    pub fn fold(&self, symbol: Symbol) -> Symbol {
+        self.folded[symbol.0 as usize]
+    }
+
+    pub fn eq_ignore_case(&self, a: Symbol, b: Symbol) -> bool {
+        self.fold(a) == self.fold(b)
+    }
+
+    /// Number of distinct strings stored.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_intern_dedups() {
+        let mut interner = Interner::new();
+        let a = interner.intern("Accept");
+        let b = interner.intern("Accept");
+        assert_eq!(a, b);
+        assert_eq!(interner.resolve(a), "Accept");
+        // "Accept" and its folded form "accept"
+        assert_eq!(interner.len(), 2);
+        assert!(Arc::ptr_eq(
+            &interner.resolve_arc(a),
+            &interner.resolve_arc(b)
+        ));
+    }
+
+    #[rstest]
+    #[case("Content-Type", "content-type", true)]
+    #[case("CONTENT-TYPE", "Content-Type", true)]
+    #[case("accept", "accept", true)]
+    #[case("Accept", "Accept-Language", false)]
+    fn test_eq_ignore_case(#[case] a: &str, #[case] b: &str, #[case] expected: bool) {
+        let mut interner = Interner::new();
+        let a = interner.intern(a);
+        let b = interner.intern(b);
+        assert_eq!(interner.eq_ignore_case(a, b), expected)
+    }
+
+    #[rstest]
+    fn test_fold() {
+        let mut interner = Interner::new();
+        let upper = interner.intern("X-Token");
+        let lower = interner.intern("x-token");
+        assert_eq!(interner.fold(upper), lower);
+        assert_eq!(interner.fold(lower), lower);
+    }
+}
@@ -0,0 +1,660 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// Why no shell word could be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexError {
+    /// The input starts with whitespace, a line continuation or nothing at all.
+    Empty,
+    UnterminatedQuote,
+}
+
+fn find_byte(haystack: &str, byte: u8) -> Option<usize> {
+    #[cfg(feature = "simd")]
+    {
+        memchr::memchr(byte, haystack.as_bytes())
+    }
+    #[cfg(not(feature = "simd"))]
+    {
+        haystack.bytes().position(|b| b == byte)
+    }
+}
+
+/// Position of the first `"` or `\` in a double-quoted string.
+fn find_quote_or_escape(haystack: &str) -> Option<usize> {
+    #[cfg(feature = "simd")]
+    {
+        memchr::memchr2(b'"', b'\\', haystack.as_bytes())
+    }
+    #[cfg(not(feature = "simd"))]
+    {
+        haystack.bytes().position(|b| b == b'"' || b == b'\\')
+    }
+}
+
+/// Whether `rest` starts with a `\` continuing the command on the next line,
+/// or ending the input.
+fn is_line_continuation(rest: &str) -> bool {
+    rest.strip_prefix('\\')
+        .is_some_and(|after| after.is_empty() || after.starts_with(['\n', '\r']))
+}
+
+/// The character escaped by a backslash at the start of `rest`, as the
+/// quote of the `'it'\''s'` of Firefox's "Copy as cURL" or the space of
+/// `a\ b`, taken literally as bash does outside quotes.
+fn escaped_char(rest: &str) -> Option<&str> {
+    if is_line_continuation(rest) {
+        return None;
+    }
+    let after = rest.strip_prefix('\\')?;
+    after.chars().next().map(|c| &after[..c.len_utf8()])
+}
+
+/// Length of the unquoted run at the start of `rest`.
+fn unquoted_len(rest: &str) -> usize {
+    rest.char_indices()
+        .find(|&(i, c)| {
+            c.is_whitespace()
+                || c == '"'
+                || c == '\''
+                || rest[i..].starts_with("$'")
+                || escaped_char(&rest[i..]).is_some()
+                || is_line_continuation(&rest[i..])
+        })
+        .map_or(rest.len(), |(i, _)| i)
+}
+
+/// Read the body of a double-quoted string, `rest` starting after the quote.
+///
+/// Per POSIX, a backslash only escapes `"`, `\`, `$`, `` ` `` and a newline
+/// (which is removed) and is kept literally before anything else.
+fn double_quoted(rest: &str) -> Result<(Cow<'_, str>, usize), LexError> {
+    let first = find_quote_or_escape(rest).ok_or(LexError::UnterminatedQuote)?;
+    if rest.as_bytes()[first] == b'"' {
+        return Ok((Cow::Borrowed(&rest[..first]), first + 1));
+    }
+
+    let mut body = String::from(&rest[..first]);
+    let mut chars = rest[first..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((Cow::Owned(body), first + i + 1)),
+            '\\' => match chars.next() {
+                Some((_, escaped @ ('"' | '\\' | '$' | '`'))) => body.push(escaped),
+                Some((_, '\n')) => {}
+                Some((_, other)) => {
+                    body.push('\\');
+                    body.push(other);
+                }
+                None => break,
+            },
+            _ => body.push(c),
+        }
+    }
+    Err(LexError::UnterminatedQuote)
+}
+
+/// Read the body of a bash ANSI-C quoted string `$'...'`, `rest` starting
+/// after the opening quote.
+fn ansi_c_quoted(rest: &str) -> Result<(Cow<'_, str>, usize), LexError> {
+    let bytes = rest.as_bytes();
+    let mut body: Vec<u8> = Vec::new();
+    let mut i = 0;
+
+    // Read up to `max` digits of `radix` starting at `i`.
+    let digits = |i: usize, radix: u32, max: usize| -> (u32, usize) {
+        let len = bytes[i..]
+            .iter()
+            .take(max)
+            .take_while(|b| (**b as char).is_digit(radix))
+            .count();
+        let value = u32::from_str_radix(&rest[i..i + len], radix).unwrap_or(0);
+        (value, len)
+    };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\'' => {
+                let body = match String::from_utf8(body) {
+                    Ok(body) => body,
+                    Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+                };
+                return Ok((Cow::Owned(body), i + 1));
+            }
+            b'\\' if i + 1 < bytes.len() => {
+                let escaped = bytes[i + 1];
+                i += 2;
+                let simple = match escaped {
+                    b'a' => Some(0x07),
+                    b'b' => Some(0x08),
+                    b'e' | b'E' => Some(0x1b),
+                    b'f' => Some(0x0c),
+                    b'n' => Some(b'\n'),
+                    b'r' => Some(b'\r'),
+                    b't' => Some(b'\t'),
+                    b'v' => Some(0x0b),
+                    b'\\' | b'\'' | b'"' | b'?' => Some(escaped),
+                    _ => None,
+                };
+                if let Some(byte) = simple {
+                    body.push(byte);
+                    continue;
+                }
+
+                match escaped {
+                    b'0'..=b'7' => {
+                        let (value, len) = digits(i - 1, 8, 3);
+                        body.push(value as u8);
+                        i += len - 1;
+                    }
+                    b'x' if digits(i, 16, 2).1 > 0 => {
+                        let (value, len) = digits(i, 16, 2);
+                        body.push(value as u8);
+                        i += len;
+                    }
+                    b'u' | b'U' if digits(i, 16, 1).1 > 0 => {
+                        let max = if escaped == b'u' { 4 } else { 8 };
+                        let (value, len) = digits(i, 16, max);
+                        let c = char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER);
+                        body.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                        i += len;
+                    }
+                    b'c' if i < bytes.len() && bytes[i].is_ascii() && bytes[i] != b'\'' => {
+                        body.push(bytes[i].to_ascii_uppercase() ^ 0x40);
+                        i += 1;
+                    }
+                    _ => body.extend_from_slice(&[b'\\', escaped]),
+                }
+            }
+            byte => {
+                body.push(byte);
+                i += 1;
+            }
+        }
+    }
+    Err(LexError::UnterminatedQuote)
+}
+
+/// How a segment of a shell word is quoted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quoting {
+    Unquoted,
+    /// `'...'`: taken literally.
+    Single,
+    /// `"..."`: the shell expands `$` and `` ` `` inside.
+    Double,
+    /// `$'...'`: backslash escapes are resolved.
+    AnsiC,
+    /// A character escaped by a backslash outside quotes, e.g. `\'` or `\ `.
+    Escaped,
+}
+
+/// One of the quoted or unquoted runs a shell word is made of, e.g. the
+/// `'X: '`, `"$VALUE"` and `' suffix'` of `'X: '"$VALUE"' suffix'`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment<'a> {
+    pub quoting: Quoting,
+    /// The text with quotes and escapes resolved.
+    pub text: Cow<'a, str>,
+    /// The bytes of the word the segment spans, quotes included.
+    pub span: Range<usize>,
+}
+
+/// Read the segment at the start of `rest`, `None` at the end of the word.
+fn segment(rest: &str) -> Result<Option<(Quoting, Cow<'_, str>, usize)>, LexError> {
+    Ok(Some(if let Some(body) = rest.strip_prefix("$'") {
+        let (text, len) = ansi_c_quoted(body)?;
+        (Quoting::AnsiC, text, len + 2)
+    } else if let Some(body) = rest.strip_prefix('"') {
+        let (text, len) = double_quoted(body)?;
+        (Quoting::Double, text, len + 1)
+    } else if let Some(body) = rest.strip_prefix('\'') {
+        let end = find_byte(body, b'\'').ok_or(LexError::UnterminatedQuote)?;
+        (Quoting::Single, Cow::Borrowed(&body[..end]), end + 2)
+    } else if let Some(escaped) = escaped_char(rest) {
+        (Quoting::Escaped, Cow::Borrowed(escaped), escaped.len() + 1)
+    } else {
+        match unquoted_len(rest) {
+            0 => return Ok(None),
+            len => (Quoting::Unquoted, Cow::Borrowed(&rest[..len]), len),
+        }
+    }))
+}
+
+/// Read the shell word at the start of `input`.
+///
+/// A word is any concatenation of unquoted text, `'single quoted'`,
+/// `"double quoted"` and `$'ANSI-C quoted'` segments, e.g. `foo'bar'"baz"`,
+/// ending at whitespace. Returns the word with quotes and escapes resolved and
+/// the number of bytes it spans. The word borrows from `input` unless
+/// segments had to be joined or unescaped.
+pub fn shell_word(input: &str) -> Result<(Cow<'_, str>, usize), LexError> {
+    let mut word: Option<Cow<'_, str>> = None;
+    let mut pos = 0;
+
+    while let Some((_, text, len)) = segment(&input[pos..])? {
+        word = Some(match word {
+            None => text,
+            Some(word) => Cow::Owned(word.into_owned() + &text),
+        });
+        pos += len;
+    }
+
+    word.map(|word| (word, pos)).ok_or(LexError::Empty)
+}
+
+/// Read the shell word at the start of `input` as [`shell_word`] does,
+/// keeping its segments apart.
+///
+/// ```
+/// use winnowcurl::curl::lexer::{Quoting, shell_word_segments};
+///
+/// let (segments, len) = shell_word_segments(r#"'X: '"$VALUE"' suffix' -v"#)?;
+/// let quoting: Vec<Quoting> = segments.iter().map(|segment| segment.quoting).collect();
+/// assert_eq!(quoting, [Quoting::Single, Quoting::Double, Quoting::Single]);
+/// assert_eq!(segments[1].text, "$VALUE");
+/// assert_eq!((segments[1].span.clone(), len), (5..13, 22));
+/// # Ok::<(), winnowcurl::curl::lexer::LexError>(())
+/// ```
+pub fn shell_word_segments(input: &str) -> Result<(Vec<Segment<'_>>, usize), LexError> {
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    while let Some((quoting, text, len)) = segment(&input[pos..])? {
+        segments.push(Segment {
+            quoting,
+            text,
+            span: pos..pos + len,
+        });
+        pos += len;
+    }
+    if segments.is_empty() {
+        return Err(LexError::Empty);
+    }
+    Ok((segments, pos))
+}
+
+/// The shell a command was written for, which decides how it is split into
+/// words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Dialect {
+    /// Guessed from the line continuations and quotes of the command.
+    #[default]
+    Auto,
+    /// A POSIX shell such as bash, with `\` line continuations.
+    Bash,
+    /// Windows `cmd.exe`, with `^` escapes and line continuations, and
+    /// arguments split the way the C runtime does, `""` being a quote.
+    Cmd,
+    /// PowerShell, usually running `curl.exe`, with `` ` `` escapes and line
+    /// continuations.
+    #[cfg_attr(feature = "cli", value(name = "powershell"))]
+    PowerShell,
+}
+
+impl Dialect {
+    /// Guess the dialect of a command from its line continuations, a
+    /// `curl.exe` program or the `^"` quotes of browsers' "Copy as cURL
+    /// (cmd)", falling back to bash.
+    pub fn detect(input: &str) -> Dialect {
+        let continuation =
+            input
+                .lines()
+                .find_map(|line| match line.trim_end().chars().next_back() {
+                    Some('\\') => Some(Dialect::Bash),
+                    Some('^') => Some(Dialect::Cmd),
+                    Some('`') => Some(Dialect::PowerShell),
+                    _ => None,
+                });
+        if let Some(dialect) = continuation {
+            return dialect;
+        }
+        let mut words = input.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some(program), _) if program.eq_ignore_ascii_case("curl.exe") => Dialect::PowerShell,
+            (_, Some(url)) if url.starts_with("^\"") => Dialect::Cmd,
+            _ => Dialect::Bash,
+        }
+    }
+
+    /// The dialect itself, or the detected one for [`Dialect::Auto`].
+    pub fn resolve(self, input: &str) -> Dialect {
+        match self {
+            Dialect::Auto => Dialect::detect(input),
+            dialect => dialect,
+        }
+    }
+}
+
+/// `auto`, `bash`, `cmd` or `powershell`, ignoring case, for callers
+/// without clap.
+impl std::str::FromStr for Dialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            ("auto", Dialect::Auto),
+            ("bash", Dialect::Bash),
+            ("cmd", Dialect::Cmd),
+            ("powershell", Dialect::PowerShell),
+        ]
+        .into_iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        .map(|(_, dialect)| dialect)
+        .ok_or_else(|| {
+            format!(
+                "unknown dialect '{}', expected auto, bash, cmd or powershell",
+                s
+            )
+        })
+    }
+}
+
+/// Split a command line into arguments as the Microsoft C runtime does:
+/// `2n` backslashes before a quote are `n` backslashes, `2n + 1` ones an
+/// escaped quote, and `""` inside quotes is a quote.
+fn msvcrt_words(line: &str) -> Result<Vec<String>, LexError> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let mut backslashes = 1;
+                while chars.next_if_eq(&'\\').is_some() {
+                    backslashes += 1;
+                }
+                let word = word.get_or_insert_with(String::new);
+                if chars.peek() == Some(&'"') {
+                    word.extend(std::iter::repeat_n('\\', backslashes / 2));
+                    if backslashes % 2 == 1 {
+                        chars.next();
+                        word.push('"');
+                    }
+                } else {
+                    word.extend(std::iter::repeat_n('\\', backslashes));
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                if quoted && chars.next_if_eq(&'"').is_some() {
+                    word.push('"');
+                } else {
+                    quoted = !quoted;
+                }
+            }
+            c if c.is_whitespace() && !quoted => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quoted {
+        return Err(LexError::UnterminatedQuote);
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Split a `cmd.exe` command into words: carets outside quotes escape the
+/// next character or join the next line, then the C runtime splits the
+/// arguments.
+fn cmd_words(input: &str) -> Result<Vec<String>, LexError> {
+    let mut line = String::with_capacity(input.len());
+    let mut quoted = false;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '^' if !quoted => match chars.next() {
+                Some('\r') => {
+                    chars.next_if_eq(&'\n');
+                }
+                Some('\n') | None => {}
+                Some(escaped) => line.push(escaped),
+            },
+            '"' => {
+                quoted = !quoted;
+                line.push(c);
+            }
+            c => line.push(c),
+        }
+    }
+    msvcrt_words(&line)
+}
+
+/// Split a PowerShell command into words.
+///
+/// A backtick escapes the next character or joins the next line, and
+/// inside double quotes starts escapes like `` `n ``. Single quotes are
+/// literal, `''` and `""` standing for a quote inside them. Variables are
+/// not expanded.
+fn powershell_words(input: &str) -> Result<Vec<String>, LexError> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '`' => match chars.next() {
+                Some('\r') => {
+                    chars.next_if_eq(&'\n');
+                }
+                Some('\n') | None => {}
+                Some(escaped) => word.get_or_insert_with(String::new).push(escaped),
+            },
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') if chars.next_if_eq(&'\'').is_some() => word.push('\''),
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(LexError::UnterminatedQuote),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.next_if_eq(&'"').is_some() => word.push('"'),
+                        Some('"') => break,
+                        Some('`') => match chars.next() {
+                            Some('0') => word.push('\0'),
+                            Some('a') => word.push('\u{7}'),
+                            Some('b') => word.push('\u{8}'),
+                            Some('e') => word.push('\u{1b}'),
+                            Some('f') => word.push('\u{c}'),
+                            Some('n') => word.push('\n'),
+                            Some('r') => word.push('\r'),
+                            Some('t') => word.push('\t'),
+                            Some('v') => word.push('\u{b}'),
+                            Some(escaped) => word.push(escaped),
+                            None => return Err(LexError::UnterminatedQuote),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(LexError::UnterminatedQuote),
+                    }
+                }
+            }
+            c if c.is_whitespace() => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Split a whole command written for `dialect` into its words, quotes and
+/// escapes resolved.
+///
+/// Bash commands are split with [`shell_word`], `\` line continuations
+/// skipped.
+pub fn words(input: &str, dialect: Dialect) -> Result<Vec<String>, LexError> {
+    match dialect.resolve(input) {
+        Dialect::Cmd => cmd_words(input),
+        Dialect::PowerShell => powershell_words(input),
+        Dialect::Bash | Dialect::Auto => {
+            let mut words = Vec::new();
+            let mut rest = input.trim_start();
+            while !rest.is_empty() {
+                match shell_word(rest) {
+                    Ok((word, len)) => {
+                        words.push(word.into_owned());
+                        rest = &rest[len..];
+                    }
+                    Err(LexError::Empty) => rest = &rest[1..],
+                    Err(e) => return Err(e),
+                }
+                rest = rest.trim_start();
+            }
+            Ok(words)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("POST -H", "POST", 4)]
+    #[case("'a b' c", "a b", 5)]
+    #[case(r#""it's" c"#, "it's", 6)]
+    #[case("foo'bar'baz -v", "foobarbaz", 11)]
+    #[case(r#"Accept:"*/*""#, "Accept:*/*", 12)]
+    #[case("''", "", 2)]
+    #[case("http://a.com/\\\n-v", "http://a.com/", 13)]
+    #[case("a\\b", "ab", 3)]
+    #[case("a\\ b -v", "a b", 4)]
+    #[case("a\\\\b", "a\\b", 4)]
+    #[case(r#"\"a\" -v"#, r#""a""#, 5)]
+    fn test_shell_word(#[case] input: &str, #[case] expected: &str, #[case] len: usize) {
+        assert_eq!(shell_word(input), Ok((Cow::Borrowed(expected), len)));
+    }
+
+    #[rstest]
+    #[case(r#""{\"key\": \"value\"}" -H"#, r#"{"key": "value"}"#)]
+    #[case(r#""a\\b""#, r#"a\b"#)]
+    #[case(r#""\$HOME \`id\`""#, "$HOME `id`")]
+    #[case(r#""keep \n and \d""#, r#"keep \n and \d"#)]
+    #[case("\"line\\\nbreak\"", "linebreak")]
+    #[case(r#"'single \" \\ stays'"#, r#"single \" \\ stays"#)]
+    #[case(r#"'it'\''s'"#, "it's")]
+    fn test_shell_word_double_quote_escapes(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(shell_word(input).unwrap().0, expected);
+    }
+
+    #[rstest]
+    #[case(r#"$'a\nb\tc'"#, "a\nb\tc")]
+    #[case(r#"$'it\'s'"#, "it's")]
+    #[case(r#"$'\x41\102é\U0001F600'"#, "AB\u{e9}\u{1F600}")]
+    #[case(r#"$'\x7b\"a\":1\x7d'"#, r#"{"a":1}"#)]
+    #[case(r#"$'\0'"#, "\0")]
+    #[case(r#"$'\ca'"#, "\u{1}")]
+    #[case(r#"$'\q'"#, r#"\q"#)]
+    #[case(r#"pre$'\n'post"#, "pre\npost")]
+    fn test_shell_word_ansi_c(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(shell_word(input).unwrap().0, expected);
+    }
+
+    #[rstest]
+    fn test_shell_word_borrows_single_segments() {
+        let (word, _) = shell_word("'quoted' rest").unwrap();
+        assert!(matches!(word, Cow::Borrowed(_)));
+        let (word, _) = shell_word(r#""plain double" rest"#).unwrap();
+        assert!(matches!(word, Cow::Borrowed(_)));
+        let (word, _) = shell_word("un'quoted'").unwrap();
+        assert!(matches!(word, Cow::Owned(_)));
+    }
+
+    #[rstest]
+    #[case(
+        r#"'X: '"$VALUE"' suffix'"#,
+        vec![(Quoting::Single, "X: "), (Quoting::Double, "$VALUE"), (Quoting::Single, " suffix")]
+    )]
+    #[case(
+        r#"a=$'\t'b\'c"d\"e" f"#,
+        vec![(Quoting::Unquoted, "a="), (Quoting::AnsiC, "\t"), (Quoting::Unquoted, "b"), (Quoting::Escaped, "'"), (Quoting::Unquoted, "c"), (Quoting::Double, "d\"e")]
+    )]
+    #[case("plain", vec![(Quoting::Unquoted, "plain")])]
+    fn test_shell_word_segments(#[case] input: &str, #[case] expected: Vec<(Quoting, &str)>) {
+        let (segments, len) = shell_word_segments(input).unwrap();
+        let found: Vec<(Quoting, &str)> = segments
+            .iter()
+            .map(|segment| (segment.quoting, &*segment.text))
+            .collect();
+        assert_eq!(found, expected);
+        // The segments cover the word and join into it.
+        assert_eq!(segments.last().unwrap().span.end, len);
+        let joined: String = segments.iter().map(|segment| &*segment.text).collect();
+        assert_eq!(joined, shell_word(input).unwrap().0);
+    }
+
+    #[rstest]
+    #[case("", LexError::Empty)]
+    #[case(" word", LexError::Empty)]
+    #[case("\\\n-H", LexError::Empty)]
+    #[case("'open", LexError::UnterminatedQuote)]
+    #[case("ok\"open", LexError::UnterminatedQuote)]
+    #[case(r#""escaped \" quote"#, LexError::UnterminatedQuote)]
+    #[case(r#"$'open\'"#, LexError::UnterminatedQuote)]
+    fn test_shell_word_errors(#[case] input: &str, #[case] expected: LexError) {
+        assert_eq!(shell_word(input), Err(expected));
+    }
+
+    #[rstest]
+    #[case("curl 'https://a.com' \\\n  -H 'A: 1'", Dialect::Bash)]
+    #[case("curl \"https://a.com\" ^\n  -H \"A: 1\"", Dialect::Cmd)]
+    #[case("curl ^\"https://a.com^\" -H ^\"A: 1^\"", Dialect::Cmd)]
+    #[case("curl.exe \"https://a.com\" `\n  -H \"A: 1\"", Dialect::PowerShell)]
+    #[case("curl.exe 'https://a.com'", Dialect::PowerShell)]
+    #[case("curl 'https://a.com' -d '^\"'", Dialect::Bash)]
+    fn test_dialect_detect(#[case] input: &str, #[case] expected: Dialect) {
+        assert_eq!(Dialect::detect(input), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "curl \"https://a.com/?a=1&b=2\" ^\r\n  -H \"Accept: */*\" ^\n  --data-raw \"{\"\"k\"\":\"\"v w\"\"}\"",
+        vec!["curl", "https://a.com/?a=1&b=2", "-H", "Accept: */*", "--data-raw", r#"{"k":"v w"}"#]
+    )]
+    #[case(
+        r#"curl ^"https://a.com/^" -H ^"x: 1^" --data-raw ^"^{^\^"k^\^":^\^"100%^ ^\^"^}^""#,
+        vec!["curl", "https://a.com/", "-H", "x: 1", "--data-raw", r#"{"k":"100% "}"#]
+    )]
+    #[case(r#"curl "a"%"b" C:\dir\ "x\\\"y""#, vec!["curl", "a%b", r"C:\dir\", r#"x\"y"#])]
+    fn test_words_cmd(#[case] input: &str, #[case] expected: Vec<&str>) {
+        assert_eq!(words(input, Dialect::Cmd).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "curl.exe \"https://a.com\" `\r\n  -H 'Accept: */*' `\n  -d '{\"it''s\": 1}'",
+        vec!["curl.exe", "https://a.com", "-H", "Accept: */*", "-d", r#"{"it's": 1}"#]
+    )]
+    #[case(
+        r#"curl.exe -d "a`tb ""q"" `$x `"" --url C:\x"#,
+        vec!["curl.exe", "-d", "a\tb \"q\" $x \"", "--url", r"C:\x"]
+    )]
+    fn test_words_powershell(#[case] input: &str, #[case] expected: Vec<&str>) {
+        assert_eq!(words(input, Dialect::PowerShell).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("curl \"open", Dialect::Cmd)]
+    #[case("curl.exe 'open", Dialect::PowerShell)]
+    #[case("curl.exe \"open`\"", Dialect::PowerShell)]
+    fn test_words_unterminated(#[case] input: &str, #[case] dialect: Dialect) {
+        assert_eq!(words(input, dialect), Err(LexError::UnterminatedQuote));
+    }
+
+    #[rstest]
+    #[case("auto", Ok(Dialect::Auto))]
+    #[case("Bash", Ok(Dialect::Bash))]
+    #[case("cmd", Ok(Dialect::Cmd))]
+    #[case("PowerShell", Ok(Dialect::PowerShell))]
+    #[case("zsh", Err(()))]
+    fn test_dialect_from_str(#[case] input: &str, #[case] expected: Result<Dialect, ()>) {
+        assert_eq!(input.parse::<Dialect>().map_err(|_| ()), expected);
+    }
+}
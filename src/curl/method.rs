@@ -0,0 +1,261 @@
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+
+use super::ast::Curl;
+use super::options::has_flag;
+use crate::diagnostic::{Code, Diagnostic};
+
+/// An HTTP request method, the standard ones in uppercase whatever case
+/// they were given in.
+///
+/// ```
+/// use winnowcurl::curl::method::Method;
+///
+/// assert_eq!(Method::from("post"), Method::Post);
+/// assert_eq!(Method::from("PROPFIND"), Method::Custom("PROPFIND".into()));
+/// assert!(Method::parse_strict("TEST").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Head,
+    Options,
+    Trace,
+    /// Any other method, as written.
+    Custom(String),
+}
+
+/// The methods besides the standard ones registered with IANA, which the
+/// strict mode accepts as well.
+const REGISTERED: &[&str] = &[
+    "ACL",
+    "BASELINE-CONTROL",
+    "BIND",
+    "CHECKIN",
+    "CHECKOUT",
+    "CONNECT",
+    "COPY",
+    "LABEL",
+    "LINK",
+    "LOCK",
+    "MERGE",
+    "MKACTIVITY",
+    "MKCALENDAR",
+    "MKCOL",
+    "MKREDIRECTREF",
+    "MKWORKSPACE",
+    "MOVE",
+    "ORDERPATCH",
+    "PRI",
+    "PROPFIND",
+    "PROPPATCH",
+    "QUERY",
+    "REBIND",
+    "REPORT",
+    "SEARCH",
+    "UNBIND",
+    "UNCHECKOUT",
+    "UNLINK",
+    "UNLOCK",
+    "UPDATE",
+    "UPDATEREDIRECTREF",
+    "VERSION-CONTROL",
+];
+
+/// Whether `text` is an RFC 9110 token, the only form a method or a
+/// header name may take.
+pub(crate) fn is_token(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+impl Method {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+            Method::Head => "HEAD",
+            Method::Options => "OPTIONS",
+            Method::Trace => "TRACE",
+            Method::Custom(method) => method,
+        }
+    }
+
+    /// Parse a method, refusing what no server would take for one: an
+    /// argument that isn't a token, such as `-X 'GET /'`, or a method that
+    /// is neither standard nor registered, such as `-X TEST`.
+    pub fn parse_strict(method: &str) -> Result<Self, Diagnostic> {
+        let parsed = Method::from(method);
+        let known = match &parsed {
+            Method::Custom(custom) => {
+                is_token(custom) && REGISTERED.iter().any(|r| r.eq_ignore_ascii_case(custom))
+            }
+            _ => true,
+        };
+        if !known {
+            return Err(Diagnostic::new(
+                Code::InvalidOptionValue,
+                format!("-X expects an HTTP method, got '{}'", method),
+            ));
+        }
+        Ok(parsed)
+    }
+
+    /// The method curl sends when `-X` is absent: `HEAD` for `-I`, `GET`
+    /// for `-G`, `POST` when a body is sent with `-d` or `-F`, `PUT` for
+    /// `-T` and `GET` if nothing else applies.
+    pub fn implied(curls: &[Curl]) -> Self {
+        let uploads = curls
+            .iter()
+            .any(|curl| matches!(curl, Curl::Option { name, .. } if name == "--upload-file"));
+        if has_flag(curls, "--head") {
+            Method::Head
+        } else if has_flag(curls, "--get") {
+            Method::Get
+        } else if curls
+            .iter()
+            .any(|curl| matches!(curl, Curl::Data(_) | Curl::Form(_)))
+        {
+            Method::Post
+        } else if uploads {
+            Method::Put
+        } else {
+            Method::Get
+        }
+    }
+
+    /// The method of a command: the last `-X`, otherwise the one
+    /// [implied](Method::implied) by its options.
+    pub fn of(curls: &[Curl]) -> Self {
+        curls
+            .iter()
+            .rev()
+            .find_map(Curl::method)
+            .unwrap_or_else(|| Method::implied(curls))
+    }
+}
+
+impl From<&str> for Method {
+    fn from(method: &str) -> Self {
+        const STANDARD: &[Method] = &[
+            Method::Get,
+            Method::Post,
+            Method::Put,
+            Method::Patch,
+            Method::Delete,
+            Method::Head,
+            Method::Options,
+            Method::Trace,
+        ];
+        STANDARD
+            .iter()
+            .find(|standard| standard.as_str().eq_ignore_ascii_case(method))
+            .cloned()
+            .unwrap_or_else(|| Method::Custom(method.into()))
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for Method {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Method {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Serialize for Method {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Check that every `-X` method is one [`Method::parse_strict`] accepts.
+pub fn validate_strict(curls: &[Curl]) -> Result<(), Diagnostic> {
+    curls.iter().try_for_each(|curl| match curl {
+        Curl::Method(stru) => {
+            Method::parse_strict(stru.data.as_deref().unwrap_or_default()).map(drop)
+        }
+        _ => Ok(()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case("GET", Method::Get)]
+    #[case("get", Method::Get)]
+    #[case("Patch", Method::Patch)]
+    #[case("options", Method::Options)]
+    #[case("PURGE", Method::Custom("PURGE".into()))]
+    #[case("propfind", Method::Custom("propfind".into()))]
+    fn test_from(#[case] input: &str, #[case] expected: Method) {
+        assert_eq!(Method::from(input), expected);
+    }
+
+    #[rstest]
+    #[case("delete", true)]
+    #[case("PROPFIND", true)]
+    #[case("mkcol", true)]
+    #[case("TEST", false)]
+    #[case("GET /", false)]
+    #[case("", false)]
+    fn test_parse_strict(#[case] input: &str, #[case] valid: bool) {
+        let result = Method::parse_strict(input);
+        assert_eq!(result.is_ok(), valid);
+        if let Err(e) = result {
+            assert_eq!(e.code, Code::InvalidOptionValue);
+        }
+    }
+
+    #[rstest]
+    #[case("curl a.com", Method::Get)]
+    #[case("curl a.com -d x=1", Method::Post)]
+    #[case("curl a.com -F f=1", Method::Post)]
+    #[case("curl a.com -I", Method::Head)]
+    #[case("curl a.com -G -d x=1", Method::Get)]
+    #[case("curl a.com -T file.txt", Method::Put)]
+    #[case("curl a.com -T file.txt -d x=1", Method::Post)]
+    #[case("curl a.com -d x=1 -X patch", Method::Patch)]
+    #[case("curl a.com -X PUT -X purge", Method::Custom("purge".into()))]
+    fn test_of(#[case] input: &str, #[case] expected: Method) {
+        assert_eq!(Method::of(&curl_cmd_parse(input).unwrap()), expected);
+    }
+
+    #[rstest]
+    fn test_validate_strict() {
+        assert!(validate_strict(&curl_cmd_parse("curl a.com -X put").unwrap()).is_ok());
+        assert!(validate_strict(&curl_cmd_parse("curl a.com -X TEST").unwrap()).is_err());
+    }
+
+    #[rstest]
+    fn test_serialize() {
+        assert_eq!(serde_json::to_value(Method::Head).unwrap(), "HEAD");
+        assert_eq!(
+            serde_json::to_value(Method::Custom("PURGE".into())).unwrap(),
+            "PURGE"
+        );
+    }
+}
@@ -1,87 +1,69 @@
+pub mod ast;
+pub mod backend;
+pub mod batch;
+pub mod body;
+pub mod builder;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod canonical;
+pub mod config;
 pub mod curl_parsers;
+pub mod curlrc;
+pub mod date;
+pub mod devtools;
+pub mod error;
+pub mod escape;
+pub mod explain;
+pub mod files;
+pub mod form;
+pub mod header;
+pub mod intern;
+pub mod lexer;
+pub mod method;
+pub mod network;
+pub mod options;
+pub mod output;
+pub mod parallel;
 pub mod parser;
+pub mod quirks;
+pub mod render;
+pub mod resume;
+pub mod rewrite;
+pub mod routing;
+pub mod secrets;
+pub mod shell;
+pub mod stream;
+pub mod time_cond;
+pub mod timestamps;
+pub mod trace;
+pub mod transfer;
 pub mod url_parser;
+pub mod volatile;
 
-// use url::Url;
-use url_parser::CurlURL;
+pub use ast::{Curl, CurlStru, OwnedCurl};
 
-#[macro_export]
-macro_rules! new_curl {
-    ($identifier:expr) => {
-        Curl::new_as_flag($identifier).unwrap()
-    };
-    ($identifier:expr,$data:expr) => {
-        Curl::new(stringify!($identifier), $data).unwrap()
-    };
-}
-
-#[derive(Debug, PartialEq, Clone)]
-pub struct CurlStru {
-    pub identifier: String,
-    pub data: Option<String>,
-}
-
-impl CurlStru {
-    pub fn new(identifier: &str) -> Self {
-        CurlStru {
-            identifier: identifier.into(),
-            data: None,
-        }
-    }
-
-    pub fn new_with_data(identifier: &str, data: &str) -> Self {
-        CurlStru {
-            identifier: identifier.into(),
-            data: Some(data.into()),
-        }
-    }
-
-    pub fn set_data(&mut self, data: Option<String>) {
-        self.data = data;
-    }
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum Curl {
-    Method(CurlStru),
-    // URL(Url),
-    URL(CurlURL),
-    Header(CurlStru),
-    Data(CurlStru),
-    Flag(CurlStru),
-}
-
-impl Curl {
-    pub fn new(identifier: &str, param: &str) -> Option<Self> {
-        if param.is_empty() {
-            return None;
-        }
-
-        match identifier {
-            "-X" => Some(Curl::Method(CurlStru::new_with_data(identifier, param))),
-            "-H" => Some(Curl::Header(CurlStru::new_with_data(identifier, param))),
-            "-d" | "--data" => Some(Curl::Data(CurlStru::new_with_data("-d", param))),
-            _ => {
-                eprintln!("Haven't implement it yet...");
-                None
-            }
-        }
-    }
-
-    pub fn new_as_flag(identifier: &str) -> Option<Self> {
-        // TODO: Do more check to ensure it's a flag param for curl
-        if identifier.is_empty() {
-            None
-        } else {
-            Some(Curl::Flag(CurlStru::new(identifier)))
-        }
-    }
-
-    pub fn new_as_url(url: CurlURL) -> Self {
-        Curl::URL(url)
-    }
-
-    // pub fn new_as_url(url: url::Url) -> Self {
-    //     Curl::URL(url)
-    // }
-}
+// Owned parse results and their errors must stay free of borrows and
+// thread-unsafe state so they can be sent to other threads and async tasks.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync + 'static>() {}
+    assert_send_sync::<OwnedCurl>();
+    assert_send_sync::<CurlStru<'static>>();
+    assert_send_sync::<crate::url::parser::CurlURL<'static>>();
+    assert_send_sync::<error::ParseError>();
+    assert_send_sync::<files::ResolveError>();
+    assert_send_sync::<config::ParserConfig>();
+    assert_send_sync::<header::Headers>();
+    assert_send_sync::<intern::Interner>();
+    assert_send_sync::<lexer::LexError>();
+    assert_send_sync::<method::Method>();
+    assert_send_sync::<options::OptionSpec>();
+    assert_send_sync::<parallel::ParallelSettings>();
+    assert_send_sync::<resume::ContinueAt>();
+    assert_send_sync::<network::NetworkOptions>();
+    assert_send_sync::<routing::Routing>();
+    assert_send_sync::<stream::ParsedCommand>();
+    assert_send_sync::<stream::StreamError>();
+    assert_send_sync::<time_cond::TimeCondition>();
+    assert_send_sync::<trace::TraceSettings>();
+    assert_send_sync::<transfer::TransferSettings>();
+};
@@ -0,0 +1,97 @@
+use super::ast::Curl;
+use super::options::PortRange;
+
+/// What `--interface` binds the outgoing connection to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Interface {
+    /// `if!eth0`: a network interface, never taken for an address.
+    Device(String),
+    /// `host!192.0.2.1`: a local address or host name, never an interface.
+    Host(String),
+    /// A bare value, which curl tries as an interface name first and then
+    /// as an address or host name.
+    Any(String),
+}
+
+impl Interface {
+    pub fn new(value: &str) -> Self {
+        if let Some(name) = value.strip_prefix("if!") {
+            Interface::Device(name.into())
+        } else if let Some(host) = value.strip_prefix("host!") {
+            Interface::Host(host.into())
+        } else {
+            Interface::Any(value.into())
+        }
+    }
+}
+
+/// How the connection is set up locally: `--interface`, `--local-port` and
+/// `--dns-interface`. When an option repeats, the last one wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkOptions {
+    pub interface: Option<Interface>,
+    pub local_port: Option<PortRange>,
+    /// The interface name resolver requests go out of.
+    pub dns_interface: Option<String>,
+}
+
+impl NetworkOptions {
+    pub fn new(curls: &[Curl]) -> Self {
+        let mut network = Self::default();
+        for curl in curls {
+            if let Curl::Option { name, value } = curl {
+                match &**name {
+                    "--interface" => network.interface = Some(Interface::new(value)),
+                    // Invalid ranges are rejected while parsing.
+                    "--local-port" => network.local_port = value.parse().ok(),
+                    "--dns-interface" => network.dns_interface = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+        }
+        network
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case("eth0", Interface::Any("eth0".into()))]
+    #[case("if!eth0", Interface::Device("eth0".into()))]
+    #[case("host!192.0.2.1", Interface::Host("192.0.2.1".into()))]
+    fn test_interface(#[case] value: &str, #[case] expected: Interface) {
+        assert_eq!(Interface::new(value), expected);
+    }
+
+    #[rstest]
+    fn test_network_options() {
+        let curls = curl_cmd_parse(
+            "curl 'https://a.com' --interface eth0 --local-port 4000-4200 --dns-interface wlan0 --interface if!eth1",
+        )
+        .unwrap();
+        assert_eq!(
+            NetworkOptions::new(&curls),
+            NetworkOptions {
+                interface: Some(Interface::Device("eth1".into())),
+                local_port: Some(PortRange {
+                    first: 4000,
+                    last: 4200
+                }),
+                dns_interface: Some("wlan0".into()),
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_invalid_local_port_is_rejected() {
+        let err = curl_cmd_parse("curl 'https://a.com' --local-port 9-1").unwrap_err();
+        assert_eq!(
+            err.message,
+            "--local-port expects a port or port range, got '9-1'"
+        );
+    }
+}
@@ -0,0 +1,1571 @@
+use std::time::Duration;
+
+use super::ast::Curl;
+use super::error::shorten;
+use super::resume::ContinueAt;
+use super::routing::{ConnectTo, Resolve};
+use crate::diagnostic::{Code, Diagnostic, Severity};
+
+/// What kind of argument an option expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    Text,
+    Path,
+    Url,
+    /// A whole number, e.g. `--retry 3`.
+    Integer,
+    /// A possibly fractional number of seconds, e.g. `--max-time 2.5`.
+    Seconds,
+    /// A number of bytes, possibly with a `k`, `M`, `G`, `T` or `P` suffix,
+    /// e.g. `--limit-rate 200K`.
+    Size,
+    /// A port or an inclusive range of ports, e.g. `--local-port 4000-4200`.
+    Ports,
+    /// A `--resolve` entry, e.g. `example.com:443:127.0.0.1`.
+    Resolve,
+    /// A `--connect-to` mapping, e.g. `example.com:443:backend:8443`.
+    ConnectTo,
+    /// `-` or a number of bytes, e.g. `--continue-at 1024`.
+    ResumeOffset,
+}
+
+impl ValueType {
+    /// Whether curl would accept `value` for an option of this type.
+    pub fn accepts(&self, value: &str) -> bool {
+        match self {
+            ValueType::Integer => parse_integer(value).is_some(),
+            ValueType::Seconds => parse_seconds(value).is_some(),
+            ValueType::Size => parse_size(value).is_some(),
+            ValueType::Ports => value.parse::<PortRange>().is_ok(),
+            ValueType::Resolve => value.parse::<Resolve>().is_ok(),
+            ValueType::ConnectTo => value.parse::<ConnectTo>().is_ok(),
+            ValueType::ResumeOffset => value.parse::<ContinueAt>().is_ok(),
+            ValueType::Text | ValueType::Path | ValueType::Url => true,
+        }
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ValueType::Text => "a value",
+            ValueType::Path => "a file path",
+            ValueType::Url => "a URL",
+            ValueType::Integer => "an integer",
+            ValueType::Seconds => "a number of seconds",
+            ValueType::Size => "a size in bytes, with an optional k, M, G, T or P suffix",
+            ValueType::Ports => "a port or port range",
+            ValueType::Resolve => "a host:port:address entry",
+            ValueType::ConnectTo => "a HOST1:PORT1:HOST2:PORT2 mapping",
+            ValueType::ResumeOffset => "- or a byte offset",
+        }
+    }
+}
+
+/// Whether `text` is made of ASCII digits only, and at least one.
+fn is_digits(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Parse a whole number written with ASCII digits and an optional leading
+/// `-`, e.g. `--retry 3`.
+///
+/// Numbers are read the same in every locale: no `+`, no spaces and no
+/// digit grouping.
+pub fn parse_integer(value: &str) -> Option<i64> {
+    is_digits(value.strip_prefix('-').unwrap_or(value))
+        .then(|| value.parse().ok())
+        .flatten()
+}
+
+/// Parse a number of seconds written as `2` or `2.5`, e.g. `--max-time 2.5`.
+///
+/// The decimal separator is always `.`, whatever the locale; `2,5`, `1e3`,
+/// `inf` and negative numbers are refused.
+pub fn parse_seconds(value: &str) -> Option<Duration> {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, "0"));
+    if !is_digits(whole) || !is_digits(fraction) {
+        return None;
+    }
+    Duration::try_from_secs_f64(value.parse().ok()?).ok()
+}
+
+/// Parse a size in bytes with an optional suffix, `k`, `M`, `G`, `T` and
+/// `P` counting in powers of 1024 in either case, e.g. `200K` or
+/// `--max-filesize 1G`; `None` for other suffixes, fractions and sizes
+/// overflowing a `u64`.
+pub fn parse_size(value: &str) -> Option<u64> {
+    let digits = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let shift = match &value[digits.len()..] {
+        "" | "b" | "B" => 0,
+        "k" | "K" => 10,
+        "m" | "M" => 20,
+        "g" | "G" => 30,
+        "t" | "T" => 40,
+        "p" | "P" => 50,
+        _ => return None,
+    };
+    if !is_digits(digits) {
+        return None;
+    }
+    digits.parse::<u64>().ok()?.checked_mul(1 << shift)
+}
+
+/// An inclusive range of ports, written `4000` or `4000-4200`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PortRange {
+    pub first: u16,
+    pub last: u16,
+}
+
+/// A port range that is not numeric, includes port 0 or is reversed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidPortRange;
+
+impl std::fmt::Display for InvalidPortRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid port range")
+    }
+}
+
+impl std::error::Error for InvalidPortRange {}
+
+impl std::str::FromStr for PortRange {
+    type Err = InvalidPortRange;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (first, last) = s.split_once('-').unwrap_or((s, s));
+        let port = |port: &str| port.trim().parse::<u16>().map_err(|_| InvalidPortRange);
+        let (first, last) = (port(first)?, port(last)?);
+        if first == 0 || last < first {
+            return Err(InvalidPortRange);
+        }
+        Ok(PortRange { first, last })
+    }
+}
+
+/// Metadata of a curl command line option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OptionSpec {
+    pub short: Option<&'static str>,
+    pub long: &'static str,
+    /// `None` for boolean options that take no argument.
+    pub value: Option<ValueType>,
+    /// What the option does, e.g. "follows redirects".
+    pub summary: &'static str,
+    /// `Error` for options that make the transfer unsafe, `Warning` for
+    /// those to use with care, such as options carrying credentials.
+    pub risk: Option<Severity>,
+}
+
+impl OptionSpec {
+    pub fn takes_value(&self) -> bool {
+        self.value.is_some()
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.long == name || self.short == Some(name)
+    }
+
+    const fn risky(self, risk: Severity) -> Self {
+        OptionSpec {
+            risk: Some(risk),
+            ..self
+        }
+    }
+}
+
+const fn arg(
+    short: Option<&'static str>,
+    long: &'static str,
+    value: ValueType,
+    summary: &'static str,
+) -> OptionSpec {
+    OptionSpec {
+        short,
+        long,
+        value: Some(value),
+        summary,
+        risk: None,
+    }
+}
+
+const fn flag(
+    short: Option<&'static str>,
+    long: &'static str,
+    summary: &'static str,
+) -> OptionSpec {
+    OptionSpec {
+        short,
+        long,
+        value: None,
+        summary,
+        risk: None,
+    }
+}
+
+use Severity::{Error, Warning};
+use ValueType::{Integer, Path, Ports, ResumeOffset, Seconds, Size, Text, Url};
+
+/// curl's options, as listed by `curl --help all`.
+pub const OPTIONS: &[OptionSpec] = &[
+    arg(
+        None,
+        "--abstract-unix-socket",
+        Path,
+        "connects through an abstract Unix domain socket",
+    ),
+    arg(
+        None,
+        "--alt-svc",
+        Path,
+        "reads and saves Alt-Svc alternative services in a cache file",
+    ),
+    flag(
+        None,
+        "--anyauth",
+        "picks the most secure authentication the server offers",
+    ),
+    flag(
+        Some("-a"),
+        "--append",
+        "appends to the remote file when uploading over FTP or SFTP",
+    ),
+    arg(
+        None,
+        "--aws-sigv4",
+        Text,
+        "signs the request with AWS Signature Version 4",
+    ),
+    flag(
+        None,
+        "--basic",
+        "sends -u credentials with HTTP Basic authentication",
+    ),
+    arg(
+        None,
+        "--cacert",
+        Path,
+        "verifies the server with the CA certificates of a file",
+    ),
+    arg(
+        None,
+        "--capath",
+        Path,
+        "verifies the server with the CA certificates of a directory",
+    ),
+    arg(
+        Some("-E"),
+        "--cert",
+        Text,
+        "authenticates with a client certificate",
+    ),
+    flag(
+        None,
+        "--cert-status",
+        "checks the certificate status of the server with OCSP stapling",
+    ),
+    arg(
+        None,
+        "--cert-type",
+        Text,
+        "sets the type of the client certificate",
+    ),
+    arg(
+        None,
+        "--ciphers",
+        Text,
+        "restricts the TLS 1.2 cipher suites",
+    ),
+    flag(
+        None,
+        "--compressed",
+        "asks for a compressed response and decodes it",
+    ),
+    flag(None, "--compressed-ssh", "compresses SSH connections"),
+    arg(
+        Some("-K"),
+        "--config",
+        Path,
+        "reads more options from a config file",
+    ),
+    arg(
+        None,
+        "--connect-timeout",
+        Seconds,
+        "gives up connecting after a number of seconds",
+    ),
+    arg(
+        None,
+        "--connect-to",
+        ValueType::ConnectTo,
+        "connects to another host and port than the URL's",
+    ),
+    arg(
+        Some("-C"),
+        "--continue-at",
+        ResumeOffset,
+        "resumes a transfer at an offset",
+    ),
+    arg(
+        Some("-b"),
+        "--cookie",
+        Text,
+        "sends cookies, or reads them from a cookie file",
+    ),
+    arg(
+        Some("-c"),
+        "--cookie-jar",
+        Path,
+        "writes the cookies received to a file",
+    ),
+    flag(
+        None,
+        "--create-dirs",
+        "creates the local directories of the output file",
+    ),
+    arg(
+        None,
+        "--create-file-mode",
+        Text,
+        "sets the permissions of files created remotely",
+    ),
+    flag(
+        None,
+        "--crlf",
+        "converts line endings to CRLF when uploading",
+    ),
+    arg(
+        None,
+        "--crlfile",
+        Path,
+        "checks certificates against a revocation list",
+    ),
+    arg(
+        None,
+        "--curves",
+        Text,
+        "restricts the TLS key exchange curves",
+    ),
+    arg(Some("-d"), "--data", Text, "sends a form-encoded body"),
+    arg(None, "--data-ascii", Text, "sends a form-encoded body"),
+    arg(None, "--data-binary", Text, "sends a body as is"),
+    arg(
+        None,
+        "--data-raw",
+        Text,
+        "sends a body without reading @files",
+    ),
+    arg(
+        None,
+        "--data-urlencode",
+        Text,
+        "sends a URL-encoded body field",
+    ),
+    arg(
+        None,
+        "--delegation",
+        Text,
+        "sets the GSS-API delegation policy",
+    ),
+    flag(
+        None,
+        "--digest",
+        "sends -u credentials with HTTP Digest authentication",
+    ),
+    flag(Some("-q"), "--disable", "ignores the .curlrc file"),
+    flag(
+        None,
+        "--disable-eprt",
+        "does not use EPRT or LPRT for active FTP",
+    ),
+    flag(None, "--disable-epsv", "does not use EPSV for passive FTP"),
+    flag(
+        None,
+        "--disallow-username-in-url",
+        "refuses URLs holding a user name",
+    ),
+    arg(
+        None,
+        "--dns-interface",
+        Text,
+        "sends DNS queries through a network interface",
+    ),
+    arg(
+        None,
+        "--dns-ipv4-addr",
+        Text,
+        "sends DNS queries from an IPv4 address",
+    ),
+    arg(
+        None,
+        "--dns-ipv6-addr",
+        Text,
+        "sends DNS queries from an IPv6 address",
+    ),
+    arg(
+        None,
+        "--dns-servers",
+        Text,
+        "resolves names with other DNS servers",
+    ),
+    flag(
+        None,
+        "--doh-cert-status",
+        "checks the certificate status of the DNS-over-HTTPS server",
+    ),
+    flag(
+        None,
+        "--doh-insecure",
+        "does not verify the certificate of the DNS-over-HTTPS server",
+    )
+    .risky(Error),
+    arg(None, "--doh-url", Url, "resolves names with DNS over HTTPS"),
+    arg(
+        Some("-D"),
+        "--dump-header",
+        Path,
+        "writes the response headers to a file",
+    ),
+    arg(
+        None,
+        "--ech",
+        Text,
+        "sets how TLS Encrypted Client Hello is used",
+    ),
+    arg(
+        None,
+        "--egd-file",
+        Path,
+        "reads random data from an entropy gathering daemon socket",
+    ),
+    arg(None, "--engine", Text, "uses an OpenSSL crypto engine"),
+    arg(
+        None,
+        "--etag-compare",
+        Path,
+        "only downloads if the ETag saved in a file changed",
+    ),
+    arg(
+        None,
+        "--etag-save",
+        Path,
+        "saves the ETag of the response to a file",
+    ),
+    arg(
+        None,
+        "--expect100-timeout",
+        Seconds,
+        "waits this many seconds for a 100-continue answer",
+    ),
+    flag(Some("-f"), "--fail", "fails without output on HTTP errors"),
+    flag(None, "--fail-early", "stops at the first failed transfer"),
+    flag(
+        None,
+        "--fail-with-body",
+        "fails on HTTP errors, still printing the body",
+    ),
+    flag(None, "--false-start", "uses TLS False Start"),
+    arg(Some("-F"), "--form", Text, "sends a multipart form field"),
+    flag(
+        None,
+        "--form-escape",
+        "escapes multipart field and file names with backslashes",
+    ),
+    arg(
+        None,
+        "--form-string",
+        Text,
+        "sends a multipart form field as is",
+    ),
+    arg(None, "--ftp-account", Text, "sends FTP account data"),
+    arg(
+        None,
+        "--ftp-alternative-to-user",
+        Text,
+        "sends a command when FTP USER fails",
+    ),
+    flag(
+        None,
+        "--ftp-create-dirs",
+        "creates missing remote FTP directories",
+    ),
+    arg(None, "--ftp-method", Text, "sets how FTP reaches a file"),
+    flag(None, "--ftp-pasv", "uses passive FTP"),
+    arg(
+        Some("-P"),
+        "--ftp-port",
+        Text,
+        "uses active FTP from an address",
+    ),
+    flag(None, "--ftp-pret", "sends PRET before PASV"),
+    flag(
+        None,
+        "--ftp-skip-pasv-ip",
+        "ignores the address the FTP server suggests for passive mode",
+    ),
+    flag(None, "--ftp-ssl-ccc", "drops TLS after FTP authentication").risky(Warning),
+    arg(
+        None,
+        "--ftp-ssl-ccc-mode",
+        Text,
+        "sets how the FTP clear command channel is shut down",
+    ),
+    flag(
+        None,
+        "--ftp-ssl-control",
+        "requires TLS for the FTP login only",
+    ),
+    flag(
+        Some("-G"),
+        "--get",
+        "sends the data in the query string with GET",
+    ),
+    flag(
+        Some("-g"),
+        "--globoff",
+        "reads {} and [] in the URL literally instead of as globs",
+    ),
+    arg(
+        None,
+        "--happy-eyeballs-timeout-ms",
+        Integer,
+        "waits this many milliseconds before trying IPv4 alongside IPv6",
+    ),
+    flag(
+        None,
+        "--haproxy-protocol",
+        "sends a HAProxy PROXY protocol header",
+    ),
+    arg(
+        None,
+        "--haproxy-clientip",
+        Text,
+        "sets the client address of the HAProxy PROXY header",
+    ),
+    flag(Some("-I"), "--head", "asks for the headers only with HEAD"),
+    arg(Some("-H"), "--header", Text, "sends a header"),
+    flag(Some("-h"), "--help", "prints help"),
+    arg(
+        None,
+        "--hostpubmd5",
+        Text,
+        "checks the MD5 hash of the SSH host key",
+    ),
+    arg(
+        None,
+        "--hostpubsha256",
+        Text,
+        "checks the SHA-256 hash of the SSH host key",
+    ),
+    arg(
+        None,
+        "--hsts",
+        Path,
+        "reads and saves HSTS hosts in a cache file",
+    ),
+    flag(None, "--http0.9", "accepts HTTP/0.9 responses").risky(Warning),
+    flag(Some("-0"), "--http1.0", "uses HTTP/1.0"),
+    flag(None, "--http1.1", "uses HTTP/1.1"),
+    flag(None, "--http2", "tries HTTP/2"),
+    flag(
+        None,
+        "--http2-prior-knowledge",
+        "uses HTTP/2 without upgrading",
+    ),
+    flag(None, "--http3", "tries HTTP/3"),
+    flag(None, "--http3-only", "uses HTTP/3 only"),
+    flag(
+        None,
+        "--ignore-content-length",
+        "ignores the Content-Length of the response",
+    ),
+    flag(
+        Some("-i"),
+        "--include",
+        "prints the response headers along with the body",
+    ),
+    flag(
+        Some("-k"),
+        "--insecure",
+        "disables TLS certificate verification",
+    )
+    .risky(Error),
+    arg(
+        None,
+        "--interface",
+        Text,
+        "sends from a network interface or address",
+    ),
+    arg(None, "--ip-tos", Text, "sets the IP type of service"),
+    arg(
+        None,
+        "--ipfs-gateway",
+        Url,
+        "fetches ipfs:// URLs through a gateway",
+    ),
+    flag(
+        Some("-4"),
+        "--ipv4",
+        "resolves names to IPv4 addresses only",
+    ),
+    flag(
+        Some("-6"),
+        "--ipv6",
+        "resolves names to IPv6 addresses only",
+    ),
+    arg(
+        None,
+        "--json",
+        Text,
+        "sends a JSON body with JSON Content-Type and Accept headers",
+    ),
+    flag(
+        Some("-j"),
+        "--junk-session-cookies",
+        "drops the session cookies read from a file",
+    ),
+    arg(
+        None,
+        "--keepalive-time",
+        Integer,
+        "sends TCP keepalive probes after this many idle seconds",
+    ),
+    arg(
+        None,
+        "--key",
+        Path,
+        "reads the private key of the client certificate from a file",
+    ),
+    arg(None, "--key-type", Text, "sets the type of the private key"),
+    arg(
+        None,
+        "--krb",
+        Text,
+        "uses Kerberos for FTP at a security level",
+    ),
+    arg(
+        None,
+        "--libcurl",
+        Path,
+        "writes C code doing the same transfer to a file",
+    ),
+    arg(None, "--limit-rate", Size, "caps the transfer speed"),
+    flag(
+        Some("-l"),
+        "--list-only",
+        "lists names only in FTP directory listings",
+    ),
+    arg(
+        None,
+        "--local-port",
+        Ports,
+        "sends from a local port or port range",
+    ),
+    flag(Some("-L"), "--location", "follows redirects"),
+    flag(
+        None,
+        "--location-trusted",
+        "follows redirects, sending the credentials to other hosts too",
+    )
+    .risky(Warning),
+    arg(
+        None,
+        "--login-options",
+        Text,
+        "sets mail server login options",
+    ),
+    arg(
+        None,
+        "--mail-auth",
+        Text,
+        "sets the originating mail address",
+    ),
+    arg(None, "--mail-from", Text, "sets the sender mail address"),
+    arg(None, "--mail-rcpt", Text, "adds a recipient mail address"),
+    flag(
+        None,
+        "--mail-rcpt-allowfails",
+        "goes on when some recipients are refused",
+    ),
+    flag(Some("-M"), "--manual", "prints the manual"),
+    arg(
+        None,
+        "--max-filesize",
+        Size,
+        "refuses downloads larger than a size",
+    ),
+    arg(
+        None,
+        "--max-redirs",
+        Integer,
+        "follows at most this many redirects",
+    ),
+    arg(
+        Some("-m"),
+        "--max-time",
+        Seconds,
+        "gives up the whole transfer after a number of seconds",
+    ),
+    flag(
+        None,
+        "--metalink",
+        "reads a Metalink file (no longer supported)",
+    ),
+    flag(
+        None,
+        "--negotiate",
+        "sends -u credentials with SPNEGO/Negotiate authentication",
+    ),
+    flag(Some("-n"), "--netrc", "reads credentials from ~/.netrc").risky(Warning),
+    arg(
+        None,
+        "--netrc-file",
+        Path,
+        "reads credentials from a netrc file",
+    )
+    .risky(Warning),
+    flag(
+        None,
+        "--netrc-optional",
+        "reads credentials from ~/.netrc if it exists",
+    )
+    .risky(Warning),
+    flag(
+        Some("-:"),
+        "--next",
+        "starts another request with its own options",
+    ),
+    flag(None, "--no-alpn", "disables the TLS ALPN extension"),
+    flag(
+        Some("-N"),
+        "--no-buffer",
+        "writes the output without buffering",
+    ),
+    flag(
+        None,
+        "--no-clobber",
+        "does not overwrite existing output files",
+    ),
+    flag(None, "--no-keepalive", "disables TCP keepalive probes"),
+    flag(None, "--no-npn", "disables the TLS NPN extension"),
+    flag(None, "--no-progress-meter", "hides the progress meter"),
+    flag(None, "--no-sessionid", "does not reuse TLS sessions"),
+    arg(
+        None,
+        "--noproxy",
+        Text,
+        "bypasses the proxy for these hosts",
+    ),
+    flag(
+        None,
+        "--ntlm",
+        "sends -u credentials with NTLM authentication",
+    ),
+    flag(
+        None,
+        "--ntlm-wb",
+        "sends -u credentials with NTLM through winbind",
+    ),
+    arg(
+        None,
+        "--oauth2-bearer",
+        Text,
+        "sends an OAuth 2 bearer token",
+    )
+    .risky(Warning),
+    arg(Some("-o"), "--output", Path, "writes the body to a file"),
+    arg(
+        None,
+        "--output-dir",
+        Path,
+        "writes output files to a directory",
+    ),
+    flag(
+        Some("-Z"),
+        "--parallel",
+        "performs the transfers in parallel",
+    ),
+    flag(
+        None,
+        "--parallel-immediate",
+        "opens parallel connections right away",
+    ),
+    arg(
+        None,
+        "--parallel-max",
+        Integer,
+        "runs at most this many transfers in parallel",
+    ),
+    arg(
+        None,
+        "--pass",
+        Text,
+        "sets the passphrase of the private key",
+    )
+    .risky(Warning),
+    flag(
+        None,
+        "--path-as-is",
+        "sends /../ and /./ in the URL path unchanged",
+    )
+    .risky(Warning),
+    arg(
+        None,
+        "--pinnedpubkey",
+        Text,
+        "only accepts a server with this public key",
+    ),
+    flag(None, "--post301", "keeps POST after a 301 redirect"),
+    flag(None, "--post302", "keeps POST after a 302 redirect"),
+    flag(None, "--post303", "keeps POST after a 303 redirect"),
+    arg(
+        None,
+        "--preproxy",
+        Url,
+        "goes through a SOCKS proxy before the proxy",
+    ),
+    flag(Some("-#"), "--progress-bar", "shows a progress bar"),
+    arg(None, "--proto", Text, "allows only these protocols"),
+    arg(
+        None,
+        "--proto-default",
+        Text,
+        "assumes this protocol for URLs without a scheme",
+    ),
+    arg(
+        None,
+        "--proto-redir",
+        Text,
+        "allows only these protocols when redirected",
+    ),
+    arg(Some("-x"), "--proxy", Url, "goes through a proxy"),
+    flag(
+        None,
+        "--proxy-anyauth",
+        "picks the most secure proxy authentication",
+    ),
+    flag(
+        None,
+        "--proxy-basic",
+        "authenticates to the proxy with Basic",
+    ),
+    arg(
+        None,
+        "--proxy-cacert",
+        Path,
+        "verifies the proxy with the CA certificates of a file",
+    ),
+    arg(
+        None,
+        "--proxy-capath",
+        Path,
+        "verifies the proxy with the CA certificates of a directory",
+    ),
+    arg(
+        None,
+        "--proxy-cert",
+        Text,
+        "authenticates to the proxy with a client certificate",
+    ),
+    arg(
+        None,
+        "--proxy-cert-type",
+        Text,
+        "sets the type of the proxy client certificate",
+    ),
+    arg(
+        None,
+        "--proxy-ciphers",
+        Text,
+        "restricts the cipher suites with the proxy",
+    ),
+    flag(
+        None,
+        "--proxy-digest",
+        "authenticates to the proxy with Digest",
+    ),
+    arg(None, "--proxy-header", Text, "sends a header to the proxy"),
+    flag(None, "--proxy-http2", "talks HTTP/2 to the proxy"),
+    flag(
+        None,
+        "--proxy-insecure",
+        "disables TLS certificate verification of the proxy",
+    )
+    .risky(Error),
+    arg(
+        None,
+        "--proxy-key",
+        Path,
+        "reads the private key of the proxy client certificate",
+    ),
+    arg(
+        None,
+        "--proxy-key-type",
+        Text,
+        "sets the type of the proxy private key",
+    ),
+    flag(
+        None,
+        "--proxy-negotiate",
+        "authenticates to the proxy with SPNEGO/Negotiate",
+    ),
+    flag(None, "--proxy-ntlm", "authenticates to the proxy with NTLM"),
+    arg(
+        None,
+        "--proxy-pass",
+        Text,
+        "sets the passphrase of the proxy private key",
+    )
+    .risky(Warning),
+    arg(
+        None,
+        "--proxy-pinnedpubkey",
+        Text,
+        "only accepts a proxy with this public key",
+    ),
+    arg(
+        None,
+        "--proxy-service-name",
+        Text,
+        "sets the SPNEGO service name of the proxy",
+    ),
+    flag(
+        None,
+        "--proxy-ssl-allow-beast",
+        "allows the BEAST TLS flaw with the proxy",
+    )
+    .risky(Error),
+    flag(
+        None,
+        "--proxy-ssl-auto-client-cert",
+        "uses the Windows client certificate with the proxy",
+    ),
+    arg(
+        None,
+        "--proxy-tls13-ciphers",
+        Text,
+        "restricts the TLS 1.3 cipher suites with the proxy",
+    ),
+    arg(
+        None,
+        "--proxy-tlsauthtype",
+        Text,
+        "sets the TLS authentication type with the proxy",
+    ),
+    arg(
+        None,
+        "--proxy-tlspassword",
+        Text,
+        "sets the TLS-SRP password with the proxy",
+    )
+    .risky(Warning),
+    arg(
+        None,
+        "--proxy-tlsuser",
+        Text,
+        "sets the TLS-SRP user with the proxy",
+    ),
+    flag(
+        None,
+        "--proxy-tlsv1",
+        "uses TLS 1.0 or later with the proxy",
+    )
+    .risky(Warning),
+    arg(
+        Some("-U"),
+        "--proxy-user",
+        Text,
+        "authenticates to the proxy with credentials",
+    )
+    .risky(Warning),
+    arg(None, "--proxy1.0", Url, "goes through an HTTP/1.0 proxy"),
+    flag(
+        Some("-p"),
+        "--proxytunnel",
+        "tunnels through the proxy with CONNECT",
+    ),
+    arg(
+        None,
+        "--pubkey",
+        Path,
+        "reads the SSH public key from a file",
+    ),
+    arg(
+        Some("-Q"),
+        "--quote",
+        Text,
+        "sends a raw command to an FTP or SFTP server",
+    ),
+    arg(
+        None,
+        "--random-file",
+        Path,
+        "reads random data from a file (no longer supported)",
+    ),
+    arg(Some("-r"), "--range", Text, "asks for a byte range only"),
+    arg(None, "--rate", Text, "caps the rate of transfers started"),
+    flag(None, "--raw", "passes the HTTP body on without decoding"),
+    arg(Some("-e"), "--referer", Text, "sends a Referer header"),
+    flag(
+        Some("-J"),
+        "--remote-header-name",
+        "names the output file after Content-Disposition",
+    ),
+    flag(
+        Some("-O"),
+        "--remote-name",
+        "names the output file after the URL",
+    ),
+    flag(
+        None,
+        "--remote-name-all",
+        "names every output file after its URL",
+    ),
+    flag(
+        Some("-R"),
+        "--remote-time",
+        "gives the output file the time of the remote file",
+    ),
+    flag(
+        None,
+        "--remove-on-error",
+        "deletes the output file on errors",
+    ),
+    arg(Some("-X"), "--request", Text, "sets the request method"),
+    arg(
+        None,
+        "--request-target",
+        Text,
+        "sends another request target than the URL path",
+    ),
+    arg(
+        None,
+        "--resolve",
+        ValueType::Resolve,
+        "resolves a host and port to a chosen address",
+    ),
+    arg(
+        None,
+        "--retry",
+        Integer,
+        "retries transient failures this many times",
+    ),
+    flag(None, "--retry-all-errors", "retries on any error"),
+    flag(
+        None,
+        "--retry-connrefused",
+        "retries when the connection is refused",
+    ),
+    arg(
+        None,
+        "--retry-delay",
+        Seconds,
+        "waits this many seconds between retries",
+    ),
+    arg(
+        None,
+        "--retry-max-time",
+        Seconds,
+        "stops retrying after this many seconds",
+    ),
+    arg(
+        None,
+        "--sasl-authzid",
+        Text,
+        "sets the SASL authorization identity",
+    ),
+    flag(None, "--sasl-ir", "sends the SASL initial response"),
+    arg(None, "--service-name", Text, "sets the SPNEGO service name"),
+    flag(Some("-S"), "--show-error", "shows errors even when silent"),
+    flag(
+        Some("-s"),
+        "--silent",
+        "hides the progress meter and errors",
+    ),
+    arg(None, "--socks4", Text, "goes through a SOCKS4 proxy"),
+    arg(None, "--socks4a", Text, "goes through a SOCKS4a proxy"),
+    arg(None, "--socks5", Text, "goes through a SOCKS5 proxy"),
+    flag(
+        None,
+        "--socks5-basic",
+        "authenticates to the SOCKS5 proxy with a user and password",
+    ),
+    flag(
+        None,
+        "--socks5-gssapi",
+        "authenticates to the SOCKS5 proxy with GSS-API",
+    ),
+    flag(
+        None,
+        "--socks5-gssapi-nec",
+        "protects the SOCKS5 GSS-API negotiation the NEC way",
+    ),
+    arg(
+        None,
+        "--socks5-gssapi-service",
+        Text,
+        "sets the GSS-API service name of the SOCKS5 proxy",
+    ),
+    arg(
+        None,
+        "--socks5-hostname",
+        Text,
+        "goes through a SOCKS5 proxy resolving names",
+    ),
+    arg(
+        Some("-Y"),
+        "--speed-limit",
+        Integer,
+        "gives up below this many bytes per second",
+    ),
+    arg(
+        Some("-y"),
+        "--speed-time",
+        Seconds,
+        "gives up after this many seconds below the speed limit",
+    ),
+    flag(None, "--ssl", "tries TLS for FTP, IMAP, POP3 or SMTP"),
+    flag(None, "--ssl-allow-beast", "allows the BEAST TLS flaw").risky(Error),
+    flag(
+        None,
+        "--ssl-auto-client-cert",
+        "uses the Windows client certificate",
+    ),
+    flag(
+        None,
+        "--ssl-no-revoke",
+        "does not check certificate revocation",
+    )
+    .risky(Warning),
+    flag(
+        None,
+        "--ssl-reqd",
+        "requires TLS for FTP, IMAP, POP3 or SMTP",
+    ),
+    flag(
+        None,
+        "--ssl-revoke-best-effort",
+        "ignores revocation checks that cannot be made",
+    )
+    .risky(Warning),
+    flag(Some("-2"), "--sslv2", "uses SSLv2 (no longer supported)").risky(Error),
+    flag(Some("-3"), "--sslv3", "uses SSLv3 (no longer supported)").risky(Error),
+    arg(None, "--stderr", Path, "writes error output to a file"),
+    flag(None, "--styled-output", "prints headers in bold"),
+    flag(
+        None,
+        "--suppress-connect-headers",
+        "hides the proxy CONNECT response headers",
+    ),
+    flag(None, "--tcp-fastopen", "uses TCP Fast Open"),
+    flag(None, "--tcp-nodelay", "disables Nagle's algorithm"),
+    arg(Some("-t"), "--telnet-option", Text, "sets a telnet option"),
+    arg(None, "--tftp-blksize", Integer, "sets the TFTP block size"),
+    flag(None, "--tftp-no-options", "sends no TFTP options"),
+    arg(
+        Some("-z"),
+        "--time-cond",
+        Text,
+        "only transfers a file modified after or before a date",
+    ),
+    arg(None, "--tls-max", Text, "uses at most this TLS version"),
+    arg(
+        None,
+        "--tls13-ciphers",
+        Text,
+        "restricts the TLS 1.3 cipher suites",
+    ),
+    arg(
+        None,
+        "--tlsauthtype",
+        Text,
+        "sets the TLS authentication type",
+    ),
+    arg(None, "--tlspassword", Text, "sets the TLS-SRP password").risky(Warning),
+    arg(None, "--tlsuser", Text, "sets the TLS-SRP user"),
+    flag(Some("-1"), "--tlsv1", "uses TLS 1.0 or later").risky(Warning),
+    flag(None, "--tlsv1.0", "uses TLS 1.0 or later").risky(Warning),
+    flag(None, "--tlsv1.1", "uses TLS 1.1 or later").risky(Warning),
+    flag(None, "--tlsv1.2", "uses TLS 1.2 or later"),
+    flag(None, "--tlsv1.3", "uses TLS 1.3 or later"),
+    flag(
+        None,
+        "--tr-encoding",
+        "asks for a compressed Transfer-Encoding",
+    ),
+    arg(
+        None,
+        "--trace",
+        Path,
+        "writes a full trace of the transfer to a file",
+    ),
+    arg(
+        None,
+        "--trace-ascii",
+        Path,
+        "writes a readable trace of the transfer to a file",
+    ),
+    arg(
+        None,
+        "--trace-config",
+        Text,
+        "picks the components to trace",
+    ),
+    flag(
+        None,
+        "--trace-ids",
+        "adds transfer and connection ids to the trace",
+    ),
+    flag(None, "--trace-time", "adds timestamps to the trace"),
+    arg(
+        None,
+        "--unix-socket",
+        Path,
+        "connects through a Unix domain socket",
+    ),
+    arg(Some("-T"), "--upload-file", Path, "uploads a file with PUT"),
+    arg(None, "--url", Url, "requests another URL"),
+    arg(None, "--url-query", Text, "adds a query string parameter"),
+    flag(Some("-B"), "--use-ascii", "transfers in ASCII mode"),
+    arg(Some("-u"), "--user", Text, "sends credentials").risky(Warning),
+    arg(
+        Some("-A"),
+        "--user-agent",
+        Text,
+        "sends a User-Agent header",
+    ),
+    arg(
+        None,
+        "--variable",
+        Text,
+        "sets a variable for --expand options",
+    ),
+    flag(
+        Some("-v"),
+        "--verbose",
+        "prints the request and response headers",
+    ),
+    flag(Some("-V"), "--version", "prints the curl version"),
+    arg(
+        Some("-w"),
+        "--write-out",
+        Text,
+        "prints transfer details after the transfer",
+    ),
+    flag(
+        None,
+        "--xattr",
+        "stores metadata in extended file attributes",
+    ),
+];
+
+/// Find an option by its short (`-u`) or long (`--user`) name.
+///
+/// The `--no-` form of a boolean option, e.g. `--no-raw`, resolves to the
+/// option it turns off.
+pub fn lookup(name: &str) -> Option<&'static OptionSpec> {
+    OPTIONS.iter().find(|spec| spec.matches(name)).or_else(|| {
+        let negated = name.strip_prefix("--no-")?;
+        OPTIONS
+            .iter()
+            .find(|spec| !spec.takes_value() && spec.long.strip_prefix("--") == Some(negated))
+    })
+}
+
+/// Where a cluster of short options as written, e.g. `-sSLo` or `-XPOST`,
+/// reaches the first option taking a value: its offset in `word` and its
+/// spec. Like curl, the rest of the word, if any, is then its value.
+///
+/// `None` when `word` does not start with short options curl knows, or
+/// none of them takes a value before the word ends.
+pub fn short_value_option(word: &str) -> Option<(usize, &'static OptionSpec)> {
+    let letters = word
+        .strip_prefix('-')
+        .filter(|rest| !rest.starts_with('-'))?;
+    for (i, c) in letters.char_indices() {
+        if c.is_whitespace() || c == '\'' || c == '"' {
+            return None;
+        }
+        let spec = lookup(&format!("-{}", c))?;
+        if spec.takes_value() {
+            return Some((i + 1, spec));
+        }
+    }
+    None
+}
+
+/// The long names of the options of a flag as written, `-sSL` standing for
+/// three; unknown short flags are kept as written.
+pub fn long_flag_names(identifier: &str) -> Vec<String> {
+    if identifier.starts_with("--") {
+        return vec![identifier.to_string()];
+    }
+    if let Some(spec) = lookup(identifier) {
+        return vec![spec.long.to_string()];
+    }
+    identifier
+        .chars()
+        .skip(1)
+        .map(|c| {
+            let short = format!("-{}", c);
+            lookup(&short).map_or(short, |spec| spec.long.to_string())
+        })
+        .collect()
+}
+
+/// Whether a flag as written, e.g. `-v`, `--verbose` or a cluster like
+/// `-sSv`, turns on the boolean option `long`.
+pub fn flag_matches(identifier: &str, long: &str) -> bool {
+    if identifier == long {
+        return true;
+    }
+    let Some(short) = lookup(long).and_then(|spec| spec.short) else {
+        return false;
+    };
+    identifier == short
+        || (!identifier.starts_with("--")
+            && identifier.len() > 2
+            && identifier[1..].contains(&short[1..]))
+}
+
+/// Whether the boolean option `long` is set anywhere in the command.
+pub fn has_flag(curls: &[Curl], long: &str) -> bool {
+    curls
+        .iter()
+        .any(|curl| matches!(curl, Curl::Flag(flag) if flag_matches(&flag.identifier, long)))
+}
+
+/// Whether a node is a `--next` flag, which starts a new part of the
+/// command with its own URL and options.
+pub fn is_next(curl: &Curl) -> bool {
+    matches!(curl, Curl::Flag(flag) if flag_matches(&flag.identifier, "--next"))
+}
+
+/// An option whose value curl would refuse, or which was given none.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidValue<'c> {
+    /// The option as written, e.g. `-sSo` for a cluster.
+    pub name: &'c str,
+    /// `None` when the command ends before the value, e.g. a trailing `-H`.
+    pub value: Option<&'c str>,
+    pub value_type: ValueType,
+}
+
+impl InvalidValue<'_> {
+    pub fn diagnostic(&self) -> Diagnostic {
+        let message = match self.value {
+            Some(value) => format!(
+                "{} expects {}, got '{}'",
+                self.name,
+                self.value_type.describe(),
+                shorten(value)
+            ),
+            None => format!("option {}: requires parameter", self.name),
+        };
+        Diagnostic::new(Code::InvalidOptionValue, message)
+    }
+}
+
+/// The option taking a value that a flag as written stands for, e.g. `-H`,
+/// `--max-time` or the `-o` ending `-sSo`.
+fn value_option_of_flag(identifier: &str) -> Option<&'static OptionSpec> {
+    if identifier.starts_with("--") {
+        lookup(identifier).filter(|spec| spec.takes_value())
+    } else {
+        short_value_option(identifier).map(|(_, spec)| spec)
+    }
+}
+
+/// The first typed option whose value curl would refuse, or the first
+/// option taking a value given none.
+pub fn find_invalid<'c>(curls: &'c [Curl]) -> Option<InvalidValue<'c>> {
+    curls.iter().find_map(|curl| match curl {
+        Curl::Option { name, value } => {
+            let value_type = lookup(name).and_then(|spec| spec.value)?;
+            (!value_type.accepts(value)).then_some(InvalidValue {
+                name,
+                value: Some(value),
+                value_type,
+            })
+        }
+        Curl::Flag(flag) => {
+            let value_type = value_option_of_flag(&flag.identifier)?.value?;
+            Some(InvalidValue {
+                name: &flag.identifier,
+                value: None,
+                value_type,
+            })
+        }
+        _ => None,
+    })
+}
+
+/// Check that every typed option value is one curl would accept.
+pub fn validate(curls: &[Curl]) -> Result<(), Diagnostic> {
+    match find_invalid(curls) {
+        Some(invalid) => Err(invalid.diagnostic()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use std::collections::HashSet;
+
+    #[rstest]
+    fn test_options_are_unique() {
+        let mut names = HashSet::new();
+        for spec in OPTIONS {
+            assert!(spec.long.starts_with("--"), "{}", spec.long);
+            assert!(names.insert(spec.long), "duplicate {}", spec.long);
+            assert!(!spec.summary.is_empty(), "{} has no summary", spec.long);
+            if let Some(short) = spec.short {
+                assert_eq!(short.len(), 2, "{}", short);
+                assert!(names.insert(short), "duplicate {}", short);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case("-u", "--user", true)]
+    #[case("--user", "--user", true)]
+    #[case("-m", "--max-time", true)]
+    #[case("-k", "--insecure", false)]
+    #[case("--compressed", "--compressed", false)]
+    #[case("--http0.9", "--http0.9", false)]
+    #[case("--no-http0.9", "--http0.9", false)]
+    #[case("--no-raw", "--raw", false)]
+    #[case("--no-tr-encoding", "--tr-encoding", false)]
+    #[case("--no-keepalive", "--no-keepalive", false)]
+    fn test_lookup(#[case] name: &str, #[case] long: &str, #[case] takes_value: bool) {
+        let spec = lookup(name).unwrap();
+        assert_eq!(spec.long, long);
+        assert_eq!(spec.takes_value(), takes_value);
+    }
+
+    #[rstest]
+    #[case("2.5", Some(Duration::from_millis(2500)))]
+    #[case("0", Some(Duration::ZERO))]
+    #[case("2,5", None)]
+    fn test_parse_seconds(#[case] value: &str, #[case] expected: Option<Duration>) {
+        assert_eq!(parse_seconds(value), expected);
+    }
+
+    #[rstest]
+    #[case("200K", Some(200 * 1024))]
+    #[case("3m", Some(3 << 20))]
+    #[case("5b", Some(5))]
+    #[case("15", Some(15))]
+    #[case("k", None)]
+    #[case("-1K", None)]
+    fn test_parse_size(#[case] value: &str, #[case] expected: Option<u64>) {
+        assert_eq!(parse_size(value), expected);
+    }
+
+    #[rstest]
+    #[case("--no-user")]
+    #[case("--no-such-option")]
+    #[case("-no-raw")]
+    fn test_lookup_unknown(#[case] name: &str) {
+        assert_eq!(lookup(name), None);
+    }
+
+    #[rstest]
+    #[case("-k", vec!["--insecure"])]
+    #[case("-sSL", vec!["--silent", "--show-error", "--location"])]
+    #[case("--no-buffer", vec!["--no-buffer"])]
+    fn test_long_flag_names(#[case] identifier: &str, #[case] expected: Vec<&str>) {
+        assert_eq!(long_flag_names(identifier), expected);
+    }
+
+    #[rstest]
+    #[case(Integer, "3", true)]
+    #[case(Integer, "-1", true)]
+    #[case(Integer, "3.5", false)]
+    #[case(Integer, "+3", false)]
+    #[case(Integer, " 3", false)]
+    #[case(Integer, "1,000", false)]
+    #[case(Integer, "99999999999999999999", false)]
+    #[case(Seconds, "2.5", true)]
+    #[case(Seconds, "30", true)]
+    #[case(Seconds, "2,5", false)]
+    #[case(Seconds, "1e3", false)]
+    #[case(Seconds, "inf", false)]
+    #[case(Seconds, ".5", false)]
+    #[case(Seconds, "-1", false)]
+    #[case(Seconds, "soon", false)]
+    #[case(Size, "200K", true)]
+    #[case(Size, "1g", true)]
+    #[case(Size, "1000", true)]
+    #[case(Size, "1.5M", false)]
+    #[case(Size, "10KB", false)]
+    #[case(Size, "16384P", false)]
+    #[case(ResumeOffset, "-", true)]
+    #[case(ResumeOffset, "1024", true)]
+    #[case(ResumeOffset, "-1024", false)]
+    #[case(Path, "out.json", true)]
+    #[case(Ports, "4000", true)]
+    #[case(Ports, "4000-4200", true)]
+    #[case(Ports, "4200-4000", false)]
+    #[case(Ports, "0", false)]
+    #[case(Ports, "70000", false)]
+    fn test_value_type_accepts(
+        #[case] value_type: ValueType,
+        #[case] value: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(value_type.accepts(value), expected)
+    }
+
+    #[rstest]
+    #[case("-v", "--verbose", true)]
+    #[case("--verbose", "--verbose", true)]
+    #[case("-sSvL", "--verbose", true)]
+    #[case("-sSL", "--verbose", false)]
+    #[case("--trace-time", "--verbose", false)]
+    #[case("--compressed", "--compressed", true)]
+    fn test_has_flag(#[case] flag: &str, #[case] long: &str, #[case] expected: bool) {
+        let curls = [Curl::new_as_flag(flag).unwrap()];
+        assert_eq!(has_flag(&curls, long), expected);
+    }
+
+    #[rstest]
+    fn test_validate() {
+        let ok = [Curl::new("-m", "30").unwrap()];
+        assert!(validate(&ok).is_ok());
+
+        let bad = [Curl::new("--retry", "often").unwrap()];
+        let err = validate(&bad).unwrap_err();
+        assert_eq!(err.code, Code::InvalidOptionValue);
+        assert_eq!(err.message, "--retry expects an integer, got 'often'");
+    }
+
+    #[rstest]
+    #[case("-H", Some("option -H: requires parameter"))]
+    #[case("--max-time", Some("option --max-time: requires parameter"))]
+    #[case("-sSo", Some("option -sSo: requires parameter"))]
+    #[case("-sSL", None)]
+    #[case("--compressed", None)]
+    fn test_validate_missing_value(#[case] flag: &str, #[case] expected: Option<&str>) {
+        let curls = [Curl::new_as_flag(flag).unwrap()];
+        assert_eq!(
+            validate(&curls).err().map(|e| e.message),
+            expected.map(String::from)
+        );
+    }
+
+    #[rstest]
+    #[case("-XPOST", Some((1, "--request")))]
+    #[case("-sSLo", Some((4, "--output")))]
+    #[case("-sSL", None)]
+    #[case("-sy1", Some((2, "--speed-time")))]
+    #[case("--max-time", None)]
+    #[case("-sWo", None)]
+    fn test_short_value_option(#[case] word: &str, #[case] expected: Option<(usize, &str)>) {
+        assert_eq!(
+            short_value_option(word).map(|(at, spec)| (at, spec.long)),
+            expected
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use serde::Serialize;
+use serde::ser::{SerializeMap, SerializeSeq, SerializeStruct, Serializer};
+
+use super::header::Headers;
+use super::lexer::Dialect;
+use super::parser::{Curl, CurlStru, curl_cmd_try_parse_dialect};
+
+/// Machine-readable view of a parsed command with a stable layout.
+///
+/// Keys always come out as `url`, `methods`, `headers`, `data`, `forms`,
+/// `flags`, `options`. Headers, data and form fields keep their source order
+/// since it is significant to curl, while flags are sorted because their
+/// order is not. Options keep their source order as some, like `--resolve`,
+/// may repeat.
+#[derive(Debug, Clone, Copy)]
+pub struct ParsedCommand<'c, 'a> {
+    curls: &'c [Curl<'a>],
+}
+
+impl<'c, 'a> ParsedCommand<'c, 'a> {
+    pub fn new(curls: &'c [Curl<'a>]) -> Self {
+        Self { curls }
+    }
+
+    fn values<'s>(&'s self, select: fn(&'s Curl<'a>) -> Option<&'s CurlStru<'a>>) -> Vec<&'s str> {
+        self.curls
+            .iter()
+            .filter_map(select)
+            .filter_map(|stru| stru.data.as_deref())
+            .collect()
+    }
+}
+
+impl Serialize for ParsedCommand<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let url = self.curls.iter().find_map(|curl| match curl {
+            Curl::URL(url) => Some(url),
+            _ => None,
+        });
+        let methods = self.values(|curl| match curl {
+            Curl::Method(stru) => Some(stru),
+            _ => None,
+        });
+        let data = self.values(|curl| match curl {
+            Curl::Data(stru) => Some(stru),
+            _ => None,
+        });
+        let forms: Vec<_> = self
+            .curls
+            .iter()
+            .filter_map(|curl| match curl {
+                Curl::Form(field) => Some(field),
+                _ => None,
+            })
+            .collect();
+        let mut flags: Vec<&str> = self
+            .curls
+            .iter()
+            .filter_map(|curl| match curl {
+                Curl::Flag(stru) => Some(&*stru.identifier),
+                _ => None,
+            })
+            .collect();
+        flags.sort_unstable();
+        let options: Vec<OptionEntry> = self
+            .curls
+            .iter()
+            .filter_map(|curl| match curl {
+                Curl::Option { name, value } => Some(OptionEntry { name, value }),
+                _ => None,
+            })
+            .collect();
+
+        let mut state = serializer.serialize_struct("ParsedCommand", 7)?;
+        state.serialize_field("url", &url)?;
+        state.serialize_field("methods", &methods)?;
+        state.serialize_field("headers", &Headers::from(self.curls))?;
+        state.serialize_field("data", &data)?;
+        state.serialize_field("forms", &forms)?;
+        state.serialize_field("flags", &flags)?;
+        state.serialize_field("options", &options)?;
+        state.end()
+    }
+}
+
+/// Parse a command into `{"command": ...}`, the [`ParsedCommand`], or
+/// `{"error": ...}`, the [`ParseError`](super::error::ParseError), for
+/// callers that only speak JSON such as the C and WebAssembly bindings.
+///
+/// The shell the command was written for is guessed, as `winnowcurl parse`
+/// does.
+pub fn parse_to_json(command: &str) -> serde_json::Value {
+    match curl_cmd_try_parse_dialect(command, Dialect::Auto) {
+        Ok(curls) => serde_json::json!({ "command": ParsedCommand::new(&curls) }),
+        Err(e) => serde_json::json!({ "error": e }),
+    }
+}
+
+#[derive(Serialize)]
+struct OptionEntry<'s> {
+    name: &'s str,
+    value: &'s str,
+}
+
+/// Serialized as a list of `{"name": ..., "value": ...}` objects in insertion
+/// order, so repeated headers survive and nothing depends on map ordering.
+impl Serialize for Headers {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Entry<'h>(&'h str, &'h str);
+
+        impl Serialize for Entry<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("name", self.0)?;
+                map.serialize_entry("value", self.1)?;
+                map.end()
+            }
+        }
+
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for (name, value) in self.iter() {
+            seq.serialize_element(&Entry(name, value))?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    fn test_parse_to_json() {
+        let parsed = parse_to_json("curl https://a.com -H 'Accept: */*'");
+        assert_eq!(parsed["command"]["headers"][0]["name"], "Accept");
+
+        let failed = parse_to_json("curl 'https://a.com");
+        assert_eq!(failed["error"]["name"], "unterminated-quote");
+    }
+
+    #[rstest]
+    fn test_parsed_command_json_is_stable() {
+        let input = r#"curl 'https://example.com/a?x=1' -v -H 'Zeta: 1' -X 'POST' -d 'b=2' -L -H 'Alpha: 2' -d 'a=1' -k -m 30 -F 'f=@a.png;type=image/png'"#;
+        let curls = curl_cmd_parse(input).unwrap();
+        let json = serde_json::to_string(&ParsedCommand::new(&curls)).unwrap();
+
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"url":{"schema":"https","authority":null,"path":"example.com","host":"example.com","port":null,"uri":"a","#,
+                r#""queries":[{"key":"x","value":"1"}],"fragment":null},"#,
+                r#""methods":["POST"],"#,
+                r#""headers":[{"name":"Zeta","value":"1"},{"name":"Alpha","value":"2"}],"#,
+                r#""data":["b=2","a=1"],"#,
+                r#""forms":[{"identifier":"-F","name":"f","value":{"kind":"file","value":"a.png"},"type":"image/png"}],"#,
+                r#""flags":["-L","-k","-v"],"#,
+                r#""options":[{"name":"--max-time","value":"30"}]}"#
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_parsed_command_json_ignores_flag_order() {
+        let a = curl_cmd_parse(r#"curl 'https://example.com' -k -v -L"#).unwrap();
+        let b = curl_cmd_parse(r#"curl 'https://example.com' -L -k -v"#).unwrap();
+        assert_eq!(
+            serde_json::to_string(&ParsedCommand::new(&a)).unwrap(),
+            serde_json::to_string(&ParsedCommand::new(&b)).unwrap()
+        );
+    }
+
+    #[rstest]
+    fn test_parsed_command_yaml() {
+        let curls =
+            curl_cmd_parse(r#"curl 'https://example.com/a?x=1' -H 'Accept: */*' -k"#).unwrap();
+        let yaml = serde_yaml::to_string(&ParsedCommand::new(&curls)).unwrap();
+
+        assert_eq!(
+            yaml,
+            concat!(
+                "url:\n  schema: https\n  authority: null\n  path: example.com\n  host: example.com\n  port: null\n  uri: a\n",
+                "  queries:\n  - key: x\n    value: '1'\n  fragment: null\n",
+                "methods: []\n",
+                "headers:\n- name: Accept\n  value: '*/*'\n",
+                "data: []\n",
+                "forms: []\n",
+                "flags:\n- -k\n",
+                "options: []\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use std::borrow::Cow;
+
+use super::ast::Curl;
+use super::options;
+use crate::url::glob::{OutputTemplate, UrlGlob};
+
+/// How the transfers of a command run: one after the other, or with
+/// `-Z`/`--parallel` up to `--parallel-max` at a time.
+///
+/// These are global options: they apply to the transfers of every `--next`
+/// part of the command, wherever they are given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParallelSettings {
+    /// `-Z`/`--parallel`.
+    pub parallel: bool,
+    /// `--parallel-max`: how many transfers run at once.
+    pub max: usize,
+    /// `--parallel-immediate`: new connections are opened right away rather
+    /// than waiting to multiplex over an existing one.
+    pub immediate: bool,
+}
+
+impl Default for ParallelSettings {
+    fn default() -> Self {
+        ParallelSettings {
+            parallel: false,
+            max: Self::DEFAULT_MAX,
+            immediate: false,
+        }
+    }
+}
+
+impl ParallelSettings {
+    /// curl's `--parallel-max` when none is given, or a value below 1.
+    pub const DEFAULT_MAX: usize = 50;
+    /// The most transfers curl runs at once, whatever `--parallel-max` says.
+    pub const MAX: usize = 300;
+
+    pub fn new(curls: &[Curl]) -> Self {
+        let mut max = Self::DEFAULT_MAX;
+        // Values were checked against their types while parsing.
+        for curl in curls {
+            if let Curl::Option { name, value } = curl
+                && name == "--parallel-max"
+            {
+                max = match value.parse::<usize>() {
+                    Ok(0) | Err(_) => Self::DEFAULT_MAX,
+                    Ok(value) => value.min(Self::MAX),
+                };
+            }
+        }
+        Self {
+            parallel: options::has_flag(curls, "--parallel"),
+            max,
+            immediate: options::has_flag(curls, "--parallel-immediate"),
+        }
+    }
+
+    /// How many transfers run at once: `max` with `-Z`, one otherwise.
+    pub fn concurrency(&self) -> usize {
+        if self.parallel { self.max } else { 1 }
+    }
+}
+
+/// Whether a node names where the body of a transfer goes: `-o file` or
+/// `-O`.
+pub fn is_output(curl: &Curl) -> bool {
+    match curl {
+        Curl::Option { name, .. } => name == "--output",
+        Curl::Flag(flag) => options::flag_matches(&flag.identifier, "--remote-name"),
+        _ => false,
+    }
+}
+
+/// The transfers of a part of a command, one per URL its globs stand for,
+/// each with the options of the part, e.g. two for `curl -Z https://a.com/1
+/// https://a.com/2 -H 'Accept: */*'`.
+///
+/// Like curl, the first `-o`/`-O` goes with the first URL, the second with
+/// the second, and so on, wherever they are given; the `#1`, `#2`... of an
+/// `-o` take the texts of the globs of its URL, e.g. `-o 'page_#1.html'`
+/// saves `https://a.com/[1-3]` as `page_1.html` to `page_3.html`. A URL
+/// which is not a valid glob is requested as written.
+pub fn transfers<'a>(part: &[Curl<'a>]) -> Vec<Vec<Curl<'a>>> {
+    let globoff = options::has_flag(part, "--globoff");
+    let options: Vec<&Curl<'a>> = part
+        .iter()
+        .filter(|curl| !matches!(curl, Curl::URL(_)) && !is_output(curl))
+        .collect();
+    let mut outputs = part.iter().filter(|curl| is_output(curl));
+    let mut transfers = Vec::new();
+    for curl in part {
+        let Curl::URL(url) = curl else {
+            continue;
+        };
+        let output = outputs.next();
+        let glob = match UrlGlob::new(&url.to_string(), globoff) {
+            Ok(glob) if !glob.is_literal() => glob,
+            _ => {
+                let transfer = std::iter::once(curl)
+                    .chain(options.iter().copied())
+                    .chain(output)
+                    .cloned()
+                    .collect();
+                transfers.push(transfer);
+                continue;
+            }
+        };
+        for found in glob.matches() {
+            let mut transfer = vec![Curl::new_as_url_word(Cow::Owned(found.url))];
+            transfer.extend(options.iter().copied().cloned());
+            transfer.extend(output.map(|output| match output {
+                Curl::Option { name, value } => Curl::Option {
+                    name: name.clone(),
+                    value: Cow::Owned(OutputTemplate::parse(value).resolve(&found.values)),
+                },
+                output => output.clone(),
+            }));
+            transfers.push(transfer);
+        }
+    }
+    if transfers.is_empty() {
+        // The URL comes from a config file.
+        return vec![part.to_vec()];
+    }
+    transfers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+
+    #[rstest]
+    #[case("", false, 50, false)]
+    #[case("-Z", true, 50, false)]
+    #[case("-sZ --parallel-max 4", true, 4, false)]
+    #[case("--parallel --parallel-immediate", true, 50, true)]
+    #[case("-Z --parallel-max 1000", true, 300, false)]
+    #[case("-Z --parallel-max 0", true, 50, false)]
+    #[case("-Z --parallel-max -3", true, 50, false)]
+    fn test_parallel_settings(
+        #[case] options: &str,
+        #[case] parallel: bool,
+        #[case] max: usize,
+        #[case] immediate: bool,
+    ) {
+        let input = format!("curl 'https://a.com' {}", options);
+        let settings = ParallelSettings::new(&curl_cmd_parse(&input).unwrap());
+        assert_eq!(
+            settings,
+            ParallelSettings {
+                parallel,
+                max,
+                immediate
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_concurrency() {
+        let curls = curl_cmd_parse("curl 'https://a.com' --parallel-max 4").unwrap();
+        assert_eq!(ParallelSettings::new(&curls).concurrency(), 1);
+        let curls = curl_cmd_parse("curl 'https://a.com' -Z --parallel-max 4").unwrap();
+        assert_eq!(ParallelSettings::new(&curls).concurrency(), 4);
+    }
+
+    #[rstest]
+    fn test_transfers() {
+        let curls =
+            curl_cmd_parse("curl -Z https://a.com/1 -H 'A: 1' https://a.com/2 -O -O").unwrap();
+        let commands: Vec<_> = transfers(&curls)
+            .iter()
+            .map(|transfer| to_curl_string(transfer))
+            .collect();
+        assert_eq!(
+            commands,
+            [
+                "curl 'https://a.com/1' \\\n  -Z \\\n  -H 'A: 1' \\\n  -O",
+                "curl 'https://a.com/2' \\\n  -Z \\\n  -H 'A: 1' \\\n  -O",
+            ]
+        );
+        let curls = curl_cmd_parse("curl -K api.cfg -v").unwrap();
+        assert_eq!(transfers(&curls), [curls]);
+    }
+
+    fn outputs(command: &str) -> Vec<String> {
+        let curls = curl_cmd_parse(command).unwrap();
+        transfers(&curls)
+            .iter()
+            .map(|transfer| {
+                let url = transfer[0].to_string();
+                let output = transfer.iter().find(|curl| is_output(curl));
+                match output {
+                    Some(output) => format!("{} {}", url, output),
+                    None => url,
+                }
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case(
+        "curl 'https://a.com/{img,doc}/[1-2].png' -o '#1_#2.png'",
+        vec![
+            "'https://a.com/img/1.png' --output 'img_1.png'",
+            "'https://a.com/img/2.png' --output 'img_2.png'",
+            "'https://a.com/doc/1.png' --output 'doc_1.png'",
+            "'https://a.com/doc/2.png' --output 'doc_2.png'",
+        ]
+    )]
+    #[case(
+        "curl -o 'first_#1' 'https://a.com/[1-2]' 'https://b.com/{x,y}' -o 'second_#1'",
+        vec![
+            "'https://a.com/1' --output 'first_1'",
+            "'https://a.com/2' --output 'first_2'",
+            "'https://b.com/x' --output 'second_x'",
+            "'https://b.com/y' --output 'second_y'",
+        ]
+    )]
+    #[case(
+        "curl 'https://a.com/[1-2]' -O 'https://b.com/x'",
+        vec!["'https://a.com/1' -O", "'https://a.com/2' -O", "'https://b.com/x'"]
+    )]
+    #[case(
+        "curl 'https://a.com/[1-2]' -g -o 'page_#1'",
+        vec!["'https://a.com/[1-2]' --output 'page_#1'"]
+    )]
+    #[case(
+        "curl 'https://a.com/[2-1]' -o 'page_#1'",
+        vec!["'https://a.com/[2-1]' --output 'page_#1'"]
+    )]
+    fn test_transfers_outputs(#[case] command: &str, #[case] expected: Vec<&str>) {
+        assert_eq!(outputs(command), expected);
+    }
+}
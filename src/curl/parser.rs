@@ -1,47 +1,44 @@
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::Arc;
 use winnow::{
     LocatingSlice, ModalResult, Parser,
     ascii::{alphanumeric0, multispace0, multispace1},
-    combinator::{alt, delimited, opt, preceded, repeat},
-    token::{any, literal, take_until},
+    combinator::{alt, delimited, not, opt, preceded, repeat},
+    error::{ContextError, ErrMode},
+    stream::Stream,
+    token::{any, literal, one_of, take_while},
 };
 
-use crate::url::parser::{CurlURL, parse_url};
+pub use super::ast::{Curl, CurlStru, OwnedCurl};
+use super::error::{ParseError, shorten};
+use super::lexer::{self, Dialect, LexError};
+use super::options;
+use super::render::shell_quote;
+use crate::diagnostic::{Code, Diagnostic};
 
 type Input<'a> = LocatingSlice<&'a str>;
 
 const CURL_CMD: &str = "curl";
 
-#[derive(Debug, PartialEq, Clone)]
-pub struct CurlStru {
-    pub identifier: String,
-    pub data: Option<String>,
+/// Parse one shell word: unquoted, single / double quoted, or a
+/// concatenation of those like `foo'bar'baz`
+pub fn shell_word_parse<'a>(s: &mut Input<'a>) -> ModalResult<Cow<'a, str>> {
+    let input: &'a str = s;
+    let (word, len) =
+        lexer::shell_word(input).map_err(|_| ErrMode::Backtrack(ContextError::new()))?;
+    s.next_slice(len);
+    Ok(word)
 }
 
-#[derive(Debug, PartialEq)]
-pub enum Curl<'a> {
-    Method(CurlStru),
-    URL(CurlURL<'a>),
-    Header(CurlStru),
-    Data(CurlStru),
-    Flag(CurlStru),
+/// Get the value of an argument, quoted or not.
+pub fn quoted_data_parse<'a>(s: &mut Input<'a>) -> ModalResult<Cow<'a, str>> {
+    delimited(multispace0, shell_word_parse, multispace0).parse_next(s)
 }
 
-fn parse_double_quoted_data<'a>(s: &mut Input<'a>) -> ModalResult<&'a str> {
-    delimited((multispace0, '"'), take_until(0.., '"'), ('"', multispace0)).parse_next(s)
-}
-
-fn parse_single_quoted_data<'a>(s: &mut Input<'a>) -> ModalResult<&'a str> {
-    delimited(
-        (multispace0, '\''),
-        take_until(0.., '\''),
-        ('\'', multispace0),
-    )
-    .parse_next(s)
-}
-
-/// Get the longest quoted data between single / double quoted data.
-pub fn quoted_data_parse<'a>(s: &mut Input<'a>) -> ModalResult<&'a str> {
-    alt((parse_double_quoted_data, parse_single_quoted_data)).parse_next(s)
+/// Whether a quoted argument follows, which a flag cannot take.
+fn quote_ahead<'a>(s: &mut Input<'a>) -> ModalResult<char> {
+    preceded(multispace0, one_of(['"', '\''])).parse_next(s)
 }
 
 /// Identify the ending pattern: <space*>\<space*>\r\n
@@ -61,25 +58,24 @@ pub fn remove_curl_cmd_header(input: &str) -> &str {
 
 /// Parse URL in curl command
 pub fn url_parse<'a>(s: &mut Input<'a>) -> ModalResult<Curl<'a>> {
-    preceded(multispace0, quoted_data_parse)
-        .map(|url_str| {
-            let mut url_input = LocatingSlice::new(url_str);
-            match parse_url(&mut url_input) {
-                Ok(url) => Curl::URL(url),
-                Err(_) => {
-                    // Fallback - create a simple URL structure
-                    Curl::URL(crate::url::parser::CurlURL {
-                        schema: crate::url::protocol::Schema::HTTP,
-                        authority: None,
-                        path: url_str,
-                        uri: "",
-                        queries: vec![],
-                        fragment: None,
-                    })
-                }
-            }
-        })
-        .parse_next(s)
+    preceded(
+        (opt(slash_line_ending), multispace0),
+        quoted_data_parse.verify(|url: &str| !url.starts_with('-')),
+    )
+    .map(Curl::new_as_url_word)
+    .parse_next(s)
+}
+
+/// Parse a URL following the first one, e.g. `curl -Z https://a.com/1
+/// https://a.com/2`. It needs a scheme, so that the words of a shell
+/// pipeline such as `| jq` are not taken for URLs.
+pub fn another_url_parse<'a>(s: &mut Input<'a>) -> ModalResult<Curl<'a>> {
+    preceded(
+        (opt(slash_line_ending), multispace0),
+        quoted_data_parse.verify(|url: &str| url.contains("://")),
+    )
+    .map(Curl::new_as_url_word)
+    .parse_next(s)
 }
 
 /// Parse method arguments like -X
@@ -88,8 +84,8 @@ pub fn method_parse<'a>(s: &mut Input<'a>) -> ModalResult<Curl<'a>> {
         opt(slash_line_ending),
         (multispace0, literal("-X"), multispace1, quoted_data_parse).map(|(_, method, _, data)| {
             Curl::Method(CurlStru {
-                identifier: method.to_string(),
-                data: Some(data.to_string()),
+                identifier: Cow::Borrowed(method),
+                data: Some(data),
             })
         }),
     )
@@ -102,8 +98,8 @@ pub fn header_parse<'a>(s: &mut Input<'a>) -> ModalResult<Curl<'a>> {
         opt(slash_line_ending),
         (multispace0, literal("-H"), multispace1, quoted_data_parse).map(|(_, header, _, data)| {
             Curl::Header(CurlStru {
-                identifier: header.to_string(),
-                data: Some(data.to_string()),
+                identifier: Cow::Borrowed(header),
+                data: Some(data),
             })
         }),
     )
@@ -122,59 +118,606 @@ pub fn data_parse<'a>(s: &mut Input<'a>) -> ModalResult<Curl<'a>> {
         )
             .map(|(_, data_flag, _, data)| {
                 Curl::Data(CurlStru {
-                    identifier: data_flag.to_string(),
-                    data: Some(data.to_string()),
+                    identifier: Cow::Borrowed(data_flag),
+                    data: Some(data),
                 })
             }),
     )
     .parse_next(s)
 }
 
-/// Parse flag arguments
-pub fn flag_parse<'a>(s: &mut Input<'a>) -> ModalResult<Curl<'a>> {
+/// Identify an option name like `-u`, `-sSL` or `--max-time`
+pub fn option_name<'a>(s: &mut Input<'a>) -> ModalResult<&'a str> {
+    alt((
+        (
+            "--",
+            take_while(1.., |c: char| {
+                c.is_ascii_alphanumeric() || c == '-' || c == '.'
+            }),
+        )
+            .take(),
+        ('-', any, alphanumeric0).take(),
+    ))
+    .parse_next(s)
+}
+
+/// Parse any option taking an argument, like -u or --max-time
+pub fn option_parse<'a>(s: &mut Input<'a>) -> ModalResult<Curl<'a>> {
     preceded(
         opt(slash_line_ending),
-        (multispace0, '-', any, alphanumeric0).map(|(_, first_char, second_char, rest_chars)| {
-            let flag_str = format!("{}{}{}", first_char, second_char, rest_chars);
-            Curl::Flag(CurlStru {
-                identifier: flag_str,
-                data: None,
-            })
-        }),
+        (
+            multispace0,
+            option_name
+                .verify(|name: &str| options::lookup(name).is_some_and(|spec| spec.takes_value())),
+            multispace1,
+            quoted_data_parse,
+        )
+            .verify_map(|(_, name, _, value)| Curl::new(name, value)),
     )
     .parse_next(s)
 }
 
-/// Parse all commands (methods, headers, data, flags)
+/// Parse short options the way curl reads them when they are not given
+/// one by one: a cluster of flags ending with an option taking a value, as
+/// in `-sSLo out.json`, or an option with its value attached, as in
+/// `-XPOST`, `-H'Accept: */*'` or `-sSfo-`.
+pub fn short_options_parse<'a>(s: &mut Input<'a>) -> ModalResult<Vec<Curl<'a>>> {
+    (opt(slash_line_ending), multispace0).parse_next(s)?;
+    let input: &'a str = s;
+    let (at, spec) = options::short_value_option(input)
+        .ok_or_else(|| ErrMode::Backtrack(ContextError::new()))?;
+    let attached = input[at + 1..].starts_with(|c: char| !c.is_whitespace());
+    // A lone `-X POST` is left to the parsers of single options.
+    if at == 1 && !attached {
+        return Err(ErrMode::Backtrack(ContextError::new()));
+    }
+
+    let flags = s.next_slice(at);
+    s.next_slice(1);
+    let value = if attached {
+        (shell_word_parse, multispace0)
+            .map(|(value, _)| value)
+            .parse_next(s)?
+    } else {
+        preceded(multispace1, quoted_data_parse).parse_next(s)?
+    };
+    let short = spec.short.unwrap_or(spec.long);
+    let option = Curl::new(short, value).ok_or_else(|| ErrMode::Backtrack(ContextError::new()))?;
+    let mut curls = Vec::with_capacity(2);
+    if at > 1 {
+        curls.push(Curl::Flag(CurlStru {
+            identifier: Cow::Borrowed(flags),
+            data: None,
+        }));
+    }
+    curls.push(option);
+    Ok(curls)
+}
+
+/// Whether another URL comes next, without consuming it.
+fn url_ahead(s: &mut Input<'_>) -> bool {
+    let checkpoint = s.checkpoint();
+    let ahead = another_url_parse(s).is_ok();
+    s.reset(&checkpoint);
+    ahead
+}
+
+/// Parse flag arguments, refusing options followed by a quoted argument
+/// unless it is the URL following a `--next`, or another URL following a
+/// known flag, e.g. `-O 'https://a.com/2'`
+pub fn flag_parse<'a>(s: &mut Input<'a>) -> ModalResult<Curl<'a>> {
+    let flag = preceded((opt(slash_line_ending), multispace0), option_name).parse_next(s)?;
+    let url_follows = options::flag_matches(flag, "--next")
+        || options::lookup(flag).is_some_and(|spec| !spec.takes_value()) && url_ahead(s);
+    if !url_follows {
+        not(quote_ahead).parse_next(s)?;
+    }
+    Ok(Curl::Flag(CurlStru {
+        identifier: Cow::Borrowed(flag),
+        data: None,
+    }))
+}
+
+/// Parse all commands (methods, headers, data, options, flags)
 pub fn commands_parse<'a>(s: &mut Input<'a>) -> ModalResult<Vec<Curl<'a>>> {
-    repeat(
+    let commands: Vec<Vec<Curl<'a>>> = repeat(
         0..,
-        alt((method_parse, header_parse, data_parse, flag_parse)),
+        alt((
+            short_options_parse,
+            alt((
+                method_parse,
+                header_parse,
+                data_parse,
+                option_parse,
+                flag_parse,
+            ))
+            .map(|curl| vec![curl]),
+        )),
     )
-    .parse_next(s)
+    .parse_next(s)?;
+    Ok(commands.into_iter().flatten().collect())
+}
+
+/// Offset of the word whose quote is left open, if any.
+fn unterminated_quote(input: &str) -> Option<usize> {
+    let mut rest = input;
+    loop {
+        rest = rest.trim_start();
+        match lexer::shell_word(rest) {
+            Ok((_, len)) => rest = &rest[len..],
+            Err(LexError::UnterminatedQuote) => return Some(input.len() - rest.len()),
+            Err(LexError::Empty) if rest.is_empty() => return None,
+            // A line continuation
+            Err(LexError::Empty) => rest = &rest[1..],
+        }
+    }
+}
+
+/// Check whether a single or double quote is left open
+pub fn has_unterminated_quote(input: &str) -> bool {
+    unterminated_quote(input).is_some()
+}
+
+/// The spans of the words of `input` from `from` on, with quotes and escapes
+/// resolved, stopping at an unterminated quote. Words are read as they are
+/// asked for, so that looking at the first one does not unquote the rest.
+fn words_from(input: &str, from: usize) -> impl Iterator<Item = (Range<usize>, Cow<'_, str>)> {
+    let mut pos = from;
+    std::iter::from_fn(move || {
+        while pos < input.len() {
+            let rest = &input[pos..];
+            let trimmed = rest.trim_start();
+            pos += rest.len() - trimmed.len();
+            match lexer::shell_word(trimmed) {
+                Ok((word, len)) => {
+                    let span = pos..pos + len;
+                    pos += len;
+                    return Some((span, word));
+                }
+                Err(LexError::Empty) if !trimmed.is_empty() => pos += 1,
+                Err(_) => pos = input.len(),
+            }
+        }
+        None
+    })
+}
+
+/// The error for a command whose URL could not be read at `position`.
+fn missing_url(input: &str, offset: usize, position: usize) -> ParseError {
+    if let Some(quote) = unterminated_quote(&input[offset..]) {
+        return ParseError::new(
+            Code::UnterminatedQuote,
+            "A quote is opened but never closed",
+            input,
+        )
+        .with_span(offset + quote..input.len())
+        .with_expected("a closing quote");
+    }
+    let error = match words_from(input, position).next() {
+        Some((span, word)) => ParseError::new(
+            Code::MissingUrl,
+            format!("Expected a URL, found `{}`", shorten(&word)),
+            input,
+        )
+        .with_span(span),
+        None => ParseError::new(Code::MissingUrl, "The command has no URL", input)
+            .with_span(input.len()..input.len()),
+    };
+    error.with_expected("a URL")
+}
+
+/// The span of the option written as `name` which is given no value,
+/// looking from `from` on.
+fn option_span(input: &str, from: usize, name: &str) -> Option<Range<usize>> {
+    words_from(input, from)
+        .filter(|(_, word)| word == name)
+        .last()
+        .map(|(span, _)| span)
+}
+
+/// The span of the value of the option `name` given as `value`, looking
+/// from `from` on.
+fn option_value_span(input: &str, from: usize, name: &str, value: &str) -> Option<Range<usize>> {
+    let long = options::lookup(name)?.long;
+    let mut words = words_from(input, from);
+    let (_, mut option) = words.next()?;
+    for (span, word) in words {
+        let same = options::lookup(&option).is_some_and(|spec| spec.long == long);
+        if same && word == value {
+            return Some(span);
+        }
+        option = word;
+    }
+    None
+}
+
+/// The error for an input not starting with `curl`.
+fn not_curl(input: &str) -> ParseError {
+    let error = ParseError::new(Code::NotCurl, "Input does not start with curl", input)
+        .with_expected("curl");
+    match words_from(input, 0).next() {
+        Some((span, _)) => error.with_span(span),
+        None => error,
+    }
+}
+
+/// The error for an option given a value curl would refuse, its value
+/// looked for from `from` on.
+fn invalid_value(input: &Arc<str>, from: usize, invalid: options::InvalidValue) -> ParseError {
+    let mut error = ParseError::from(invalid.diagnostic());
+    error.span = match invalid.value {
+        Some(value) => option_value_span(input, from, invalid.name, value),
+        None => option_span(input, from, invalid.name),
+    };
+    error.input = input.clone();
+    error.expected = Some(invalid.value_type.describe().into());
+    error
+}
+
+/// The span in `rest` of the argument no parser took once a command was
+/// read, with the message reporting it; `None` when nothing is left but
+/// what ends the command for the shell, e.g. the `| jq` of a pipeline, a
+/// `> out` redirection or a `# comment`.
+pub(crate) fn leftover(rest: &str) -> Option<(Range<usize>, String)> {
+    let mut trimmed = rest.trim_start();
+    while let Some(after) = trimmed
+        .strip_prefix("\\\n")
+        .or_else(|| trimmed.strip_prefix("\\\r\n"))
+    {
+        trimmed = after.trim_start();
+    }
+    if trimmed.is_empty() || trimmed.starts_with(['|', '&', ';', '<', '>', '#']) {
+        return None;
+    }
+    let start = rest.len() - trimmed.len();
+    let len = unknown_len(trimmed).unwrap_or(trimmed.len());
+    let message = format!("Unexpected argument `{}`", shorten(&trimmed[..len]));
+    Some((start..start + len, message))
+}
+
+/// Whether a command reads options from a config file with `-K`.
+fn reads_config(curls: &[Curl]) -> bool {
+    curls
+        .iter()
+        .any(|curl| matches!(curl, Curl::Option { name, .. } if name == "--config"))
 }
 
-/// Parse complete curl command
-pub fn curl_cmd_parse(input: &str) -> Result<Vec<Curl<'_>>, String> {
+/// Parse complete curl command, pointing at where parsing broke on failure.
+pub fn curl_cmd_try_parse(input: &str) -> Result<Vec<Curl<'_>>, ParseError> {
     if !is_curl(input) {
-        return Err("Input does not start with curl".to_string());
+        return Err(not_curl(input));
     }
 
+    let offset = input.len() - input.trim_start().len() + CURL_CMD.len();
     let input_without_curl = remove_curl_cmd_header(input.trim_start());
     let mut s = LocatingSlice::new(input_without_curl);
+    let position = |s: &Input| offset + input_without_curl.len() - s.len();
+    let unexpected = |e, position| {
+        ParseError::new(
+            Code::UnexpectedToken,
+            format!("Failed to parse commands: {:?}", e),
+            input,
+        )
+        .with_span(position..position)
+    };
 
-    // Parse URL first
-    let url = url_parse(&mut s).map_err(|e| format!("Failed to parse URL: {:?}", e))?;
-    let mut curl_cmds = vec![url];
+    let mut curl_cmds = Vec::new();
+    // Options may come before the URL, e.g. `curl -X POST http://example.com`
+    let mut leading = commands_parse(&mut s).map_err(|e| unexpected(e, position(&s)))?;
+    // Whether the URL looked for is another one of the same part, e.g.
+    // `curl -Z https://a.com/1 https://a.com/2`, rather than a required one.
+    let mut another = false;
+    loop {
+        let url_position = position(&s);
+        let checkpoint = s.checkpoint();
+        let url = if another {
+            another_url_parse(&mut s)
+        } else {
+            url_parse(&mut s)
+        };
+        match url {
+            Ok(url) => curl_cmds.push(url),
+            Err(_) if another => {
+                s.reset(&checkpoint);
+                break;
+            }
+            // The URL may be given by a config file, e.g. `curl -K api.cfg`.
+            Err(_) if reads_config(&leading) => s.reset(&checkpoint),
+            Err(_) => return Err(missing_url(input, offset, url_position)),
+        }
+        curl_cmds.append(&mut leading);
 
-    // Parse remaining commands
-    let mut commands =
-        commands_parse(&mut s).map_err(|e| format!("Failed to parse commands: {:?}", e))?;
-    curl_cmds.append(&mut commands);
+        // Parse remaining commands
+        let mut commands = commands_parse(&mut s).map_err(|e| unexpected(e, position(&s)))?;
+        // A part after `--next` has a URL of its own, which its options
+        // may come before too.
+        match commands.iter().rposition(options::is_next) {
+            Some(next) if !s.trim_start().is_empty() => {
+                leading = commands.split_off(next + 1);
+                curl_cmds.append(&mut commands);
+                another = false;
+            }
+            _ => {
+                curl_cmds.append(&mut commands);
+                if s.trim_start().is_empty() || position(&s) == url_position {
+                    break;
+                }
+                another = true;
+            }
+        }
+    }
+    // What no parser took, e.g. the `)(` of `curl https://a.com )( -v`,
+    // would otherwise be dropped along with the options after it.
+    if let Some((span, message)) = leftover(&s) {
+        let start = position(&s);
+        return Err(ParseError::new(Code::UnexpectedToken, message, input)
+            .with_span(start + span.start..start + span.end));
+    }
+    if let Some(invalid) = options::find_invalid(&curl_cmds) {
+        return Err(invalid_value(&input.into(), offset, invalid));
+    }
 
     Ok(curl_cmds)
 }
 
+/// Parse complete curl command. The nodes borrow their options and
+/// arguments from `input` unless they had to be unquoted or unescaped; see
+/// [`curl_cmd_parse_owned`] to detach them.
+pub fn curl_cmd_parse(input: &str) -> Result<Vec<Curl<'_>>, Diagnostic> {
+    curl_cmd_try_parse(input).map_err(Diagnostic::from)
+}
+
+/// Parse a curl command into nodes that own their data, so the input can be
+/// dropped and the result moved to another thread or task.
+pub fn curl_cmd_parse_owned(input: &str) -> Result<Vec<OwnedCurl>, Diagnostic> {
+    curl_cmd_parse(input).map(|curls| curls.into_iter().map(Curl::into_owned).collect())
+}
+
+/// A best-effort parse of a curl command: the nodes that could be parsed
+/// and the problems met on the way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LossyParse<'a> {
+    pub curls: Vec<Curl<'a>>,
+    pub warnings: Vec<ParseError>,
+}
+
+impl LossyParse<'_> {
+    /// Detach the nodes from the input they were parsed from.
+    pub fn into_owned(self) -> LossyParse<'static> {
+        LossyParse {
+            curls: self.curls.into_iter().map(Curl::into_owned).collect(),
+            warnings: self.warnings,
+        }
+    }
+}
+
+/// Skip blanks and line continuations.
+fn blanks(s: &mut Input) {
+    let _: ModalResult<Vec<&str>> =
+        repeat(0.., alt((multispace1, slash_line_ending))).parse_next(s);
+}
+
+/// The length of the argument at the start of `rest` no parser recognized,
+/// taking along the quoted value of an unknown option, e.g.
+/// `--unknown 'value'`. `None` when a quote is left open.
+fn unknown_len(rest: &str) -> Option<usize> {
+    match lexer::shell_word(rest) {
+        Ok((word, len)) if word.starts_with('-') => {
+            let value = rest[len..].trim_start_matches([' ', '\t']);
+            match lexer::shell_word(value) {
+                Ok((_, value_len)) if value.starts_with(['"', '\'']) => {
+                    Some(rest.len() - value.len() + value_len)
+                }
+                _ => Some(len),
+            }
+        }
+        Ok((_, len)) => Some(len),
+        Err(LexError::UnterminatedQuote) => None,
+        // A shell operator the lexer does not read as a word
+        Err(LexError::Empty) => rest.chars().next().map(char::len_utf8),
+    }
+}
+
+/// Parse a curl command without giving up on the first problem.
+///
+/// Arguments no parser recognizes are kept as [`Curl::Unknown`] nodes and
+/// parsing resumes after them; they, an unterminated quote, a missing URL
+/// and option values curl would refuse are reported as warnings. Meant for
+/// bulk ingestion, where one odd flag should not lose the whole command.
+pub fn curl_cmd_parse_lossy(input: &str) -> LossyParse<'_> {
+    let mut parsed = LossyParse {
+        curls: Vec::new(),
+        warnings: Vec::new(),
+    };
+    if !is_curl(input) {
+        parsed.warnings.push(not_curl(input));
+        return parsed;
+    }
+
+    let offset = input.len() - input.trim_start().len() + CURL_CMD.len();
+    let input_without_curl = remove_curl_cmd_header(input.trim_start());
+    let mut s = LocatingSlice::new(input_without_curl);
+    let position = |s: &Input| offset + input_without_curl.len() - s.len();
+    // One copy of the command for all the warnings, however many.
+    let shared: Arc<str> = input.into();
+    let mut url = None;
+    loop {
+        if let Ok(mut commands) = commands_parse(&mut s) {
+            parsed.curls.append(&mut commands);
+        }
+        if url.is_none() {
+            url = url_parse(&mut s).ok();
+            if url.is_some() {
+                continue;
+            }
+        } else if let Ok(another) = another_url_parse(&mut s) {
+            parsed.curls.push(another);
+            continue;
+        }
+        blanks(&mut s);
+        if s.is_empty() {
+            break;
+        }
+
+        let start = position(&s);
+        match unknown_len(&s) {
+            Some(len) => {
+                let text = s.next_slice(len);
+                parsed.warnings.push(
+                    ParseError::new(
+                        Code::UnexpectedToken,
+                        format!("Skipped unknown argument `{}`", shorten(text)),
+                        shared.clone(),
+                    )
+                    .with_span(start..start + len),
+                );
+                parsed
+                    .curls
+                    .push(Curl::Unknown(start..start + len, text.into()));
+            }
+            None => {
+                let text = s.next_slice(s.eof_offset());
+                parsed.warnings.push(
+                    ParseError::new(
+                        Code::UnterminatedQuote,
+                        "A quote is opened but never closed",
+                        shared.clone(),
+                    )
+                    .with_span(start..input.len())
+                    .with_expected("a closing quote"),
+                );
+                parsed
+                    .curls
+                    .push(Curl::Unknown(start..input.len(), text.into()));
+                break;
+            }
+        }
+    }
+
+    match url {
+        Some(url) => parsed.curls.insert(0, url),
+        None if reads_config(&parsed.curls) => {}
+        None => parsed.warnings.push(
+            ParseError::new(Code::MissingUrl, "The command has no URL", shared.clone())
+                .with_span(input.len()..input.len())
+                .with_expected("a URL"),
+        ),
+    }
+    // Values come in the order of the command, so each is looked for after
+    // the previous one, reading the command once.
+    let mut from = offset;
+    for curl in &parsed.curls {
+        if let Some(invalid) = options::find_invalid(std::slice::from_ref(curl)) {
+            let warning = invalid_value(&shared, from, invalid);
+            if let Some(span) = &warning.span {
+                from = span.end;
+            }
+            parsed.warnings.push(warning);
+        }
+    }
+    parsed
+}
+
+/// The command written for the shell `dialect` rewritten as a bash one,
+/// `None` when it already is one.
+fn bash_command(input: &str, dialect: Dialect) -> Result<Option<String>, ParseError> {
+    let dialect = dialect.resolve(input);
+    if matches!(dialect, Dialect::Bash | Dialect::Auto) {
+        return Ok(None);
+    }
+
+    let not_curl = || ParseError::new(Code::NotCurl, "Input does not start with curl", input);
+    let words = lexer::words(input, dialect).map_err(|e| match e {
+        LexError::UnterminatedQuote => ParseError::new(
+            Code::UnterminatedQuote,
+            "A quote is opened but never closed",
+            input,
+        ),
+        LexError::Empty => not_curl(),
+    })?;
+    let is_curl = words.first().is_some_and(|program| {
+        program.eq_ignore_ascii_case(CURL_CMD) || program.eq_ignore_ascii_case("curl.exe")
+    });
+    if !is_curl {
+        return Err(not_curl());
+    }
+
+    // Option names have to stay bare for the parsers to recognize them.
+    let mut command = String::from(CURL_CMD);
+    for word in &words[1..] {
+        command.push(' ');
+        if word.starts_with('-') && word.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            command.push_str(word);
+        } else {
+            command.push_str(&shell_quote(word));
+        }
+    }
+    Ok(Some(command))
+}
+
+/// An error about the rewritten `command`, reported against `input`. Its
+/// span is one of the rewritten command, so it is dropped.
+fn rewritten_error(input: &str, error: ParseError) -> ParseError {
+    ParseError {
+        span: None,
+        input: input.into(),
+        ..error
+    }
+}
+
+/// Parse a curl command written for the shell `dialect`, e.g. one copied
+/// from a browser on Windows as `curl "..." ^` lines or `curl.exe` under
+/// PowerShell.
+///
+/// Bash commands borrow from `input` as with [`curl_cmd_parse`]; the words
+/// of other dialects are unquoted first, so their nodes own their data and
+/// errors about them carry no span.
+pub fn curl_cmd_try_parse_dialect(
+    input: &str,
+    dialect: Dialect,
+) -> Result<Vec<Curl<'_>>, ParseError> {
+    match bash_command(input, dialect)? {
+        None => curl_cmd_try_parse(input),
+        Some(command) => match curl_cmd_try_parse(&command) {
+            Ok(curls) => Ok(curls.into_iter().map(Curl::into_owned).collect()),
+            Err(e) => Err(rewritten_error(input, e)),
+        },
+    }
+}
+
+/// Parse a curl command written for the shell `dialect` without giving up
+/// on the first problem, see [`curl_cmd_parse_lossy`].
+///
+/// As with [`curl_cmd_try_parse_dialect`], warnings about dialects other
+/// than bash carry no span, and the spans of their unknown nodes are ones
+/// of the command rewritten for bash.
+pub fn curl_cmd_parse_dialect_lossy(input: &str, dialect: Dialect) -> LossyParse<'_> {
+    match bash_command(input, dialect) {
+        Ok(None) => curl_cmd_parse_lossy(input),
+        Ok(Some(command)) => {
+            let parsed = curl_cmd_parse_lossy(&command).into_owned();
+            LossyParse {
+                curls: parsed.curls,
+                warnings: parsed
+                    .warnings
+                    .into_iter()
+                    .map(|e| rewritten_error(input, e))
+                    .collect(),
+            }
+        }
+        Err(e) => LossyParse {
+            curls: Vec::new(),
+            warnings: vec![e],
+        },
+    }
+}
+
+/// Parse a curl command written for the shell `dialect`, see
+/// [`curl_cmd_try_parse_dialect`].
+pub fn curl_cmd_parse_dialect(input: &str, dialect: Dialect) -> Result<Vec<Curl<'_>>, Diagnostic> {
+    curl_cmd_try_parse_dialect(input, dialect).map_err(Diagnostic::from)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,32 +726,111 @@ mod tests {
     #[rstest]
     #[case(r#" "rakudo star" "#, "rakudo star")]
     #[case(r#""rakulang 'rocks'""#, "rakulang 'rocks'")]
-    fn test_parse_double_quoted_data(#[case] input: String, #[case] expected: String) {
-        let mut input = LocatingSlice::new(input.as_str());
-        let double_quoted_data = parse_double_quoted_data(&mut input).unwrap();
-        assert_eq!(double_quoted_data, expected)
-    }
-
-    #[rstest]
     #[case(r#" 'rakudo star' "#, "rakudo star")]
     #[case(r#"'rakulang "rocks"'"#, r#"rakulang "rocks""#)]
-    fn test_parse_single_quoted_data(#[case] input: String, #[case] expected: String) {
-        let mut input = LocatingSlice::new(input.as_str());
-        let single_quoted_data = parse_single_quoted_data(&mut input).unwrap();
-        assert_eq!(single_quoted_data, expected)
-    }
-
-    #[rstest]
     #[case(r#" "hello world" "#, "hello world")]
     #[case(r#" 'hello world' "#, "hello world")]
     #[case(r#""longer string data""#, "longer string data")]
     #[case(r#"'single quoted data'"#, "single quoted data")]
+    #[case("POST -H", "POST")]
+    #[case("Accept:application/json", "Accept:application/json")]
+    #[case("foo'bar'baz -v", "foobarbaz")]
+    #[case(r#"a'b c'"d e" -v"#, "ab cd e")]
     fn test_quoted_data_parse(#[case] input: String, #[case] expected: String) {
         let mut input = LocatingSlice::new(input.as_str());
         let quoted_data = quoted_data_parse(&mut input).unwrap();
         assert_eq!(quoted_data, expected)
     }
 
+    #[rstest]
+    fn test_parse_borrows_from_input() {
+        let curls = curl_cmd_parse(
+            "curl 'https://a.com/x' -X PUT -H 'Accept: */*' -d 'a=1' -sSL --max-time 5",
+        )
+        .unwrap();
+        let borrowed = |value: &Cow<str>| matches!(value, Cow::Borrowed(_));
+        for curl in &curls {
+            match curl {
+                Curl::Method(stru) | Curl::Header(stru) | Curl::Data(stru) | Curl::Flag(stru) => {
+                    assert!(borrowed(&stru.identifier), "{:?}", curl);
+                    assert!(stru.data.as_ref().is_none_or(borrowed), "{:?}", curl);
+                }
+                Curl::Option { name, value } => {
+                    assert!(borrowed(name) && borrowed(value), "{:?}", curl);
+                }
+                _ => {}
+            }
+        }
+
+        // Unquoting joins words into new strings, which the nodes own.
+        let curls = curl_cmd_parse(r#"curl https://a.com -H "X-A: "'b'"#).unwrap();
+        let Curl::Header(stru) = &curls[1] else {
+            panic!("{:?}", curls);
+        };
+        assert!(matches!(stru.data, Some(Cow::Owned(_))));
+        assert_eq!(stru.data.as_deref(), Some("X-A: b"));
+    }
+
+    #[rstest]
+    #[case(r#"-H 'X: '"$VALUE"' suffix' -v"#, Curl::new("-H", "X: $VALUE suffix"))]
+    #[case(
+        r#"-d '{"a":'"1"', "b": 2}' -v"#,
+        Curl::new("-d", r#"{"a":1, "b": 2}"#)
+    )]
+    #[case(
+        r#"-A Mozilla' (X11; '$'\t'"Linux)" -v"#,
+        Curl::new("-A", "Mozilla (X11; \tLinux)")
+    )]
+    #[case(r#"-d a\ b\'c -v"#, Curl::new("-d", "a b'c"))]
+    fn test_option_value_spanning_segments(#[case] options: &str, #[case] expected: Option<Curl>) {
+        let input = format!("curl https://a.com {}", options);
+        let curls = curl_cmd_parse(&input).unwrap();
+        assert_eq!(curls.len(), 3, "{:?}", curls);
+        assert_eq!(Some(curls[1].clone()), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "curl https://a.com/login -c jar --next https://a.com/me -b jar",
+        vec!["'https://a.com/login'", "--cookie-jar 'jar'", "--next", "'https://a.com/me'", "--cookie 'jar'"]
+    )]
+    #[case(
+        "curl https://a.com -: -X PUT https://b.com -d 1",
+        vec!["'https://a.com'", "-:", "'https://b.com'", "-X 'PUT'", "-d '1'"]
+    )]
+    #[case(
+        "curl https://a.com -v --next",
+        vec!["'https://a.com'", "-v", "--next"]
+    )]
+    #[case(
+        "curl -Z https://a.com/1 -O 'https://a.com/2' -O | jq",
+        vec!["'https://a.com/1'", "-Z", "-O", "'https://a.com/2'", "-O"]
+    )]
+    #[case(
+        "curl https://a.com -o out > log 2>&1",
+        vec!["'https://a.com'", "--output 'out'"]
+    )]
+    fn test_curl_cmd_parse_next(#[case] input: &str, #[case] expected: Vec<&str>) {
+        let curls = curl_cmd_parse(input).unwrap();
+        let rendered: Vec<String> = curls.iter().map(ToString::to_string).collect();
+        assert_eq!(rendered, expected);
+    }
+
+    #[rstest]
+    fn test_curl_cmd_parse_next_without_url() {
+        let e = curl_cmd_try_parse("curl https://a.com --next -v 'b.com' --next -k").unwrap_err();
+        assert_eq!(e.code, Code::MissingUrl);
+    }
+
+    #[rstest]
+    fn test_quoted_data_parse_large_payload() {
+        let payload = "x".repeat(1 << 20);
+        let input = format!("'{}' -H 'Accept: */*'", payload);
+        let mut input = LocatingSlice::new(input.as_str());
+        assert_eq!(quoted_data_parse(&mut input).unwrap(), payload);
+        assert_eq!(*input, "-H 'Accept: */*'");
+    }
+
     #[rstest]
     #[case("  \\ ", "  \\ ")]
     #[case("\t\\\t", "\t\\\t")]
@@ -307,6 +929,9 @@ mod tests {
     #[case("--insecure", "--insecure")]
     #[case(" -k ", "-k")]
     #[case("-L", "-L")]
+    #[case("--no-buffer", "--no-buffer")]
+    #[case("--http1.1 -v", "--http1.1")]
+    #[case("-sSL", "-sSL")]
     fn test_flag_parse(#[case] input: String, #[case] expected_identifier: String) {
         let mut input = LocatingSlice::new(input.as_str());
         let result = flag_parse(&mut input).unwrap();
@@ -318,6 +943,119 @@ mod tests {
         }
     }
 
+    #[rstest]
+    #[case(" -u 'user:pass' ", "--user", "user:pass")]
+    #[case("--max-time 30 -v", "--max-time", "30")]
+    #[case(r#"-o "out file.json""#, "--output", "out file.json")]
+    #[case("-A ''", "--user-agent", "")]
+    #[case("--connect-timeout 2.5", "--connect-timeout", "2.5")]
+    fn test_option_parse(#[case] input: &str, #[case] name: &str, #[case] value: &str) {
+        let mut input = LocatingSlice::new(input);
+        let result = option_parse(&mut input).unwrap();
+        assert_eq!(
+            result,
+            Curl::Option {
+                name: name.into(),
+                value: value.into()
+            }
+        );
+    }
+
+    #[rstest]
+    #[case("-k 'quoted'")]
+    #[case("--unknown 30")]
+    #[case("-u")]
+    fn test_option_parse_rejects(#[case] input: &str) {
+        let mut input = LocatingSlice::new(input);
+        assert!(option_parse(&mut input).is_err());
+    }
+
+    #[rstest]
+    #[case("curl 'https://a.com' --request POST", Curl::new("--request", "POST"))]
+    #[case("curl 'https://a.com' -X PUT", Curl::new("-X", "PUT"))]
+    #[case("curl 'https://a.com' --data-raw '{}'", Curl::new("--data-raw", "{}"))]
+    fn test_curl_cmd_parse_long_forms(#[case] input: &str, #[case] expected: Option<Curl>) {
+        let result = curl_cmd_parse(input).unwrap();
+        assert_eq!(result.get(1), expected.as_ref());
+    }
+
+    #[rstest]
+    fn test_curl_cmd_parse_unquoted() {
+        let result = curl_cmd_parse(
+            "curl -X POST http://example.com/api -H Accept:application/json -d a'=b'",
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            vec![
+                Curl::new_as_url_str("http://example.com/api"),
+                Curl::new("-X", "POST").unwrap(),
+                Curl::new("-H", "Accept:application/json").unwrap(),
+                Curl::new("-d", "a=b").unwrap(),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_curl_cmd_parse_escapes() {
+        let input = r#"curl 'https://example.com' -d "{\"key\": \"value\"}" --data-raw $'a\nb'"#;
+        let result = curl_cmd_parse(input).unwrap();
+        assert_eq!(result[1], Curl::new("-d", r#"{"key": "value"}"#).unwrap());
+        assert_eq!(result[2], Curl::new("--data-raw", "a\nb").unwrap());
+    }
+
+    #[rstest]
+    fn test_curl_cmd_parse_owned_crosses_threads() {
+        let command = "curl 'https://example.com/a?x=1' -H 'Accept: */*'";
+        let input = String::from(command);
+        let owned = curl_cmd_parse_owned(&input).unwrap();
+        drop(input);
+
+        let moved = std::thread::spawn(move || owned).join().unwrap();
+        assert_eq!(moved, curl_cmd_parse(command).unwrap());
+    }
+
+    #[rstest]
+    fn test_diagnostic_boxes_as_send_sync_error() {
+        let err: Box<dyn std::error::Error + Send + Sync> =
+            curl_cmd_parse_owned("wget 'https://a.com'")
+                .unwrap_err()
+                .into();
+        assert!(err.to_string().contains("WCE002"));
+    }
+
+    #[rstest]
+    fn test_curl_cmd_parse_short_clusters() {
+        let result =
+            curl_cmd_parse("curl -XPOST -sSLo out.json 'https://a.com' -H'Accept: */*'").unwrap();
+        assert_eq!(
+            result,
+            vec![
+                Curl::new_as_url_str("https://a.com"),
+                Curl::new("-X", "POST").unwrap(),
+                Curl::new_as_flag("-sSL").unwrap(),
+                Curl::new("-o", "out.json").unwrap(),
+                Curl::new("-H", "Accept: */*").unwrap(),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("curl 'https://a.com' -H")]
+    #[case("curl 'https://a.com' --max-time")]
+    #[case("curl 'https://a.com' -sSo")]
+    fn test_curl_cmd_parse_missing_parameter(#[case] input: &str) {
+        let err = curl_cmd_parse(input).unwrap_err();
+        assert_eq!(err.code, Code::InvalidOptionValue);
+        assert!(err.message.contains("requires parameter"));
+    }
+
+    #[rstest]
+    fn test_curl_cmd_parse_invalid_option_value() {
+        let err = curl_cmd_parse("curl 'https://a.com' --max-time soon").unwrap_err();
+        assert_eq!(err.code, Code::InvalidOptionValue);
+    }
+
     #[rstest]
     fn test_commands_parse() {
         let input = r#" -X "GET" -H "Accept: */*" -d "test" -v "#;
@@ -333,7 +1071,10 @@ mod tests {
                 Curl::Header(_) => "Header",
                 Curl::Data(_) => "Data",
                 Curl::Flag(_) => "Flag",
+                Curl::Form(_) => "Form",
+                Curl::Option { .. } => "Option",
                 Curl::URL(_) => "URL",
+                Curl::Unknown(..) => "Unknown",
             })
             .collect();
 
@@ -375,7 +1116,10 @@ mod tests {
                 Curl::Header(_) => "Header",
                 Curl::Data(_) => "Data",
                 Curl::Flag(_) => "Flag",
+                Curl::Form(_) => "Form",
+                Curl::Option { .. } => "Option",
                 Curl::URL(_) => "URL",
+                Curl::Unknown(..) => "Unknown",
             })
             .collect();
 
@@ -390,6 +1134,7 @@ mod tests {
         assert!(
             result
                 .unwrap_err()
+                .to_string()
                 .contains("Input does not start with curl")
         );
     }
@@ -431,7 +1176,10 @@ mod tests {
                 Curl::Header(_) => "Header",
                 Curl::Data(_) => "Data",
                 Curl::Flag(_) => "Flag",
+                Curl::Form(_) => "Form",
+                Curl::Option { .. } => "Option",
                 Curl::URL(_) => "URL",
+                Curl::Unknown(..) => "Unknown",
             })
             .collect();
 
@@ -442,6 +1190,42 @@ mod tests {
         assert!(types.contains(&"Flag"));
     }
 
+    #[rstest]
+    #[case("not a curl command", Code::NotCurl)]
+    #[case("curl -v", Code::MissingUrl)]
+    #[case("curl 'https://example.com -v", Code::UnterminatedQuote)]
+    fn test_curl_cmd_parse_error_code(#[case] input: String, #[case] expected: Code) {
+        assert_eq!(curl_cmd_parse(&input).unwrap_err().code, expected)
+    }
+
+    #[rstest]
+    fn test_curl_cmd_parse_config_url() {
+        let curls = curl_cmd_parse("curl -K api.cfg -s").unwrap();
+        assert_eq!(
+            curls,
+            vec![
+                Curl::new("-K", "api.cfg").unwrap(),
+                Curl::new_as_flag("-s").unwrap()
+            ]
+        );
+        assert!(curl_cmd_parse_lossy("curl -K api.cfg").warnings.is_empty());
+    }
+
+    #[rstest]
+    #[case(r#"'a "b" c'"#, false)]
+    #[case(r#""it's""#, false)]
+    #[case(r#"'open"#, true)]
+    #[case(r#"'done' "open"#, true)]
+    #[case(r#""escaped \" quote""#, false)]
+    #[case(
+        r#"$'it\'s' \
+        -v"#,
+        false
+    )]
+    fn test_has_unterminated_quote(#[case] input: String, #[case] expected: bool) {
+        assert_eq!(has_unterminated_quote(&input), expected)
+    }
+
     #[rstest]
     #[case(r#" "test with spaces" "#, "test with spaces")]
     #[case(r#"'test with "nested" quotes'"#, r#"test with "nested" quotes"#)]
@@ -509,10 +1293,200 @@ mod tests {
                 Curl::Header(_) => "Header",
                 Curl::Data(_) => "Data",
                 Curl::Flag(_) => "Flag",
+                Curl::Form(_) => "Form",
+                Curl::Option { .. } => "Option",
                 Curl::URL(_) => "URL",
+                Curl::Unknown(..) => "Unknown",
             })
             .collect();
 
         assert_eq!(types, vec!["URL", "Method", "Header", "Data"]);
     }
+
+    #[rstest]
+    #[case(
+        "curl \"https://a.com/api\" ^\n  -H \"Content-Type: application/json\" ^\n  --data-raw \"{\"\"a\"\":1}\" ^\n  --compressed",
+        Dialect::Auto
+    )]
+    #[case(
+        r#"curl ^"https://a.com/api^" -H ^"Content-Type: application/json^" --data-raw ^"^{^\^"a^\^":1^}^" --compressed"#,
+        Dialect::Cmd
+    )]
+    #[case(
+        "curl.exe \"https://a.com/api\" `\n  -H 'Content-Type: application/json' `\n  --data-raw '{\"a\":1}' `\n  --compressed",
+        Dialect::Auto
+    )]
+    #[case(
+        "curl 'https://a.com/api' -H 'Content-Type: application/json' --data-raw '{\"a\":1}' --compressed",
+        Dialect::Bash
+    )]
+    fn test_curl_cmd_parse_dialect(#[case] input: &str, #[case] dialect: Dialect) {
+        let expected = curl_cmd_parse(
+            "curl 'https://a.com/api' -H 'Content-Type: application/json' --data-raw '{\"a\":1}' --compressed",
+        )
+        .unwrap();
+        assert_eq!(curl_cmd_parse_dialect(input, dialect).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("wget.exe \"https://a.com\"", Dialect::PowerShell, Code::NotCurl)]
+    #[case("curl \"https://a.com", Dialect::Cmd, Code::UnterminatedQuote)]
+    fn test_curl_cmd_parse_dialect_errors(
+        #[case] input: &str,
+        #[case] dialect: Dialect,
+        #[case] expected: Code,
+    ) {
+        assert_eq!(
+            curl_cmd_parse_dialect(input, dialect).unwrap_err().code,
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("wget 'https://a.com'", Code::NotCurl, Some(0..4), Some("curl"))]
+    #[case("curl -v", Code::MissingUrl, Some(7..7), Some("a URL"))]
+    #[case("curl -X POST", Code::MissingUrl, Some(12..12), Some("a URL"))]
+    #[case("curl 'https://a.com -v", Code::UnterminatedQuote, Some(5..22), Some("a closing quote"))]
+    #[case("curl 'https://a.com' --max-time soon", Code::InvalidOptionValue, Some(32..36), Some("a number of seconds"))]
+    #[case("curl http://x.com -H 'A: b' )( -H 'X: y'", Code::UnexpectedToken, Some(28..30), None)]
+    #[case("curl http://x.com -d 'a' 'b' -H 'X: y'", Code::UnexpectedToken, Some(25..28), None)]
+    fn test_curl_cmd_try_parse_errors(
+        #[case] input: &str,
+        #[case] code: Code,
+        #[case] span: Option<Range<usize>>,
+        #[case] expected: Option<&str>,
+    ) {
+        let error = curl_cmd_try_parse(input).unwrap_err();
+        assert_eq!(error.code, code);
+        assert_eq!(error.span, span);
+        assert_eq!(error.expected.as_deref(), expected);
+        assert_eq!(&*error.input, input);
+    }
+
+    #[rstest]
+    fn test_curl_cmd_try_parse_render() {
+        let error = curl_cmd_try_parse("curl 'https://a.com' \\\n  --max-time soon").unwrap_err();
+        assert!(error.render().ends_with(concat!(
+            "2 |   --max-time soon\n",
+            "  |              ^^^^ expected a number of seconds",
+        )));
+        assert_eq!(
+            curl_cmd_parse("curl -v").unwrap_err(),
+            curl_cmd_try_parse("curl -v").unwrap_err().diagnostic()
+        );
+    }
+
+    #[rstest]
+    fn test_curl_cmd_parse_lossy() {
+        let input = "curl --weird 'x' 'https://a.com' --unknown 'value' -H 'A: 1' | jq";
+        let parsed = curl_cmd_parse_lossy(input);
+        assert_eq!(
+            parsed.curls,
+            vec![
+                Curl::new_as_url_str("https://a.com"),
+                Curl::Unknown(5..16, "--weird 'x'".into()),
+                Curl::Unknown(33..50, "--unknown 'value'".into()),
+                Curl::new("-H", "A: 1").unwrap(),
+                Curl::Unknown(61..62, "|".into()),
+                Curl::Unknown(63..65, "jq".into()),
+            ]
+        );
+        let spans: Vec<_> = parsed.warnings.iter().map(|w| w.span.clone()).collect();
+        assert_eq!(
+            spans,
+            [Some(5..16), Some(33..50), Some(61..62), Some(63..65)]
+        );
+        assert!(
+            parsed
+                .warnings
+                .iter()
+                .all(|w| w.code == Code::UnexpectedToken)
+        );
+    }
+
+    #[rstest]
+    #[case("curl -v", vec![Code::MissingUrl])]
+    #[case("curl 'https://a.com' -H 'A: 1", vec![Code::UnexpectedToken, Code::UnterminatedQuote])]
+    #[case("curl 'https://a.com' --max-time soon", vec![Code::InvalidOptionValue])]
+    #[case("wget 'https://a.com'", vec![Code::NotCurl])]
+    #[case("curl 'https://a.com' -v --max-time 3", vec![])]
+    #[case("curl -Z 'https://a.com/1' 'https://a.com/2' -O -O", vec![])]
+    fn test_curl_cmd_parse_lossy_warnings(#[case] input: &str, #[case] expected: Vec<Code>) {
+        let parsed = curl_cmd_parse_lossy(input);
+        let codes: Vec<Code> = parsed.warnings.iter().map(|w| w.code).collect();
+        assert_eq!(codes, expected);
+        if expected.is_empty() {
+            assert_eq!(parsed.curls, curl_cmd_parse(input).unwrap());
+        }
+    }
+
+    #[rstest]
+    fn test_curl_cmd_parse_dialect_lossy() {
+        let parsed = curl_cmd_parse_dialect_lossy(
+            r#"curl ^"https://a.com^" --weird ^"x^" -v"#,
+            Dialect::Cmd,
+        );
+        assert_eq!(parsed.curls.len(), 3);
+        assert!(matches!(&parsed.curls[1], Curl::Unknown(_, text) if text == "--weird 'x'"));
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!(parsed.warnings[0].span, None);
+    }
+
+    /// A JSON body of about `size` bytes, as pasted from an API's docs.
+    fn long_json(size: usize) -> String {
+        let item = r#"{"id":1234,"name":"widget","tags":["a","b"]},"#;
+        format!("[{}{{}}]", item.repeat(size / item.len()))
+    }
+
+    #[rstest]
+    fn test_long_single_line_borrows() {
+        let json = long_json(4 << 20);
+        let url = format!("data:text/plain;base64,{}", "QUJD".repeat(1 << 18));
+        let input = format!(
+            "curl '{}' -H 'Content-Type: application/json' -d '{}' --data-raw '{}'",
+            url, json, json
+        );
+        let curls = curl_cmd_parse(&input).unwrap();
+        assert_eq!(curls.len(), 4);
+        assert!(curls[0].to_string() == format!("'{}'", url));
+        for curl in &curls[2..] {
+            let Curl::Data(data) = curl else {
+                panic!("expected data, got {:?}", curl);
+            };
+            assert!(matches!(data.data, Some(Cow::Borrowed(data)) if data == json));
+        }
+    }
+
+    #[rstest]
+    fn test_long_single_line_errors() {
+        let json = long_json(1 << 20);
+        let unknown = "| jq . ".repeat(10_000);
+        let input = format!(
+            "curl https://a.com -d '{}' --max-time '{}' {}--max-time soon",
+            json, json, unknown
+        );
+        let parsed = curl_cmd_parse_lossy(&input);
+        assert_eq!(parsed.warnings.len(), 30_002);
+        // One copy of the command, however many warnings.
+        assert!(
+            parsed
+                .warnings
+                .iter()
+                .all(|w| Arc::ptr_eq(&w.input, &parsed.warnings[0].input))
+        );
+        let spans: Vec<_> = parsed.warnings[30_000..]
+            .iter()
+            .map(|w| w.span.clone().unwrap())
+            .collect();
+        assert!(input[spans[0].clone()] == format!("'{}'", json));
+        assert_eq!(&input[spans[1].clone()], "soon");
+        let rendered = ParseError::render_all(&parsed.warnings);
+        assert!(rendered.iter().all(|warning| warning.len() < 400));
+        assert_eq!(rendered[30_001], parsed.warnings[30_001].render());
+
+        let input = format!("curl -d '{}'", json);
+        let error = curl_cmd_try_parse(&input).unwrap_err();
+        assert_eq!(error.code, Code::MissingUrl);
+        assert!(error.message.len() < 100, "{}", error.message);
+    }
 }
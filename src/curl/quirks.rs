@@ -0,0 +1,257 @@
+use super::ast::{Curl, CurlStru, OwnedCurl};
+use super::canonical::canonicalize;
+use super::header::split_header;
+use super::method::Method;
+
+/// Headers describing the connection of the browser rather than the
+/// request, sent by some browsers only, e.g. Firefox's `Connection:
+/// keep-alive` over HTTP/1.1 or Chrome's `priority`.
+const CONNECTION_HEADERS: &[&str] = &["connection", "keep-alive", "priority", "te"];
+
+/// The node one browser writes in place of another's, `None` for the ones
+/// left out.
+fn unquirked<'a>(curl: &Curl<'a>) -> Option<Curl<'a>> {
+    match curl {
+        // `-d`, `--data-ascii` and `--data-binary` only differ from
+        // `--data-raw` when reading a file.
+        Curl::Data(stru)
+            if matches!(
+                &*stru.identifier,
+                "-d" | "--data" | "--data-ascii" | "--data-binary"
+            ) && !stru.data.as_deref().unwrap_or_default().starts_with('@') =>
+        {
+            Some(Curl::Data(CurlStru {
+                identifier: "--data-raw".into(),
+                data: stru.data.clone(),
+            }))
+        }
+        Curl::Header(stru) => {
+            let Some((name, value)) = stru.data.as_deref().and_then(split_header) else {
+                return Some(curl.clone());
+            };
+            let name = name.to_ascii_lowercase();
+            if CONNECTION_HEADERS.contains(&name.as_str()) {
+                return None;
+            }
+            match name.as_str() {
+                "cookie" if value.contains('=') => Some(Curl::Option {
+                    name: "--cookie".into(),
+                    value: value.to_string().into(),
+                }),
+                // `--compressed` asks for the encodings curl supports.
+                "accept-encoding"
+                    if ["gzip", "deflate", "br"]
+                        .iter()
+                        .any(|encoding| value.contains(encoding)) =>
+                {
+                    Some(Curl::Flag(CurlStru::new("--compressed")))
+                }
+                _ => Some(curl.clone()),
+            }
+        }
+        curl => Some(curl.clone()),
+    }
+}
+
+/// Rewrite a command copied with a browser's "Copy as cURL" into the form
+/// it takes whichever of Chrome, Firefox and Safari copied it, then into
+/// its [canonical] form.
+///
+/// The browsers write the same request differently: Safari always gives
+/// `-X`, Firefox does for anything but `GET`, Chrome never does when curl
+/// would use the method anyway; Safari sends bodies with `--data-binary`
+/// and the others with `--data-raw`; Firefox and Safari send cookies in a
+/// `Cookie` header where Chrome uses `-b`; Chrome asks for compression with
+/// `--compressed` where Safari sends an `Accept-Encoding` header; each of
+/// them adds headers of its own connection, such as `Connection` or
+/// `Priority`, which are dropped. Header names are title-cased and sorted,
+/// Chrome writing them in lowercase.
+///
+/// Windows commands, e.g. Firefox's `^"` quoting for cmd, are parsed by
+/// [`curl_cmd_parse_dialect`] beforehand.
+///
+/// ```
+/// use winnowcurl::curl::parser::curl_cmd_parse;
+/// use winnowcurl::curl::quirks::normalize_copied;
+/// use winnowcurl::curl::render::to_curl_string;
+///
+/// let chrome = curl_cmd_parse("curl 'https://a.com/' -H 'accept: */*' -b 'sid=1' --compressed")?;
+/// let safari = curl_cmd_parse("curl 'https://a.com/' -X 'GET' -H 'Accept: */*' -H 'Cookie: sid=1' -H 'Accept-Encoding: gzip'")?;
+/// assert_eq!(
+///     to_curl_string(&normalize_copied(&chrome)),
+///     to_curl_string(&normalize_copied(&safari))
+/// );
+/// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+/// ```
+///
+/// [canonical]: super::canonical::canonicalize
+/// [`curl_cmd_parse_dialect`]: super::parser::curl_cmd_parse_dialect
+pub fn normalize_copied(curls: &[Curl]) -> Vec<OwnedCurl> {
+    let mut nodes: Vec<Curl> = curls.iter().filter_map(unquirked).collect();
+    let explicit = nodes.iter().rev().find_map(|curl| match curl {
+        Curl::Method(stru) => stru.data.clone(),
+        _ => None,
+    });
+    // Only the same method as written: curl sends `-X get` in lowercase.
+    if explicit.is_some_and(|method| Method::implied(&nodes) == *method) {
+        nodes.retain(|curl| !matches!(curl, Curl::Method(_)));
+    }
+    canonicalize(&nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::lexer::Dialect;
+    use crate::curl::parser::{curl_cmd_parse, curl_cmd_parse_dialect};
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+
+    /// The same JSON POST, copied from each browser.
+    const POST: &[(&str, Dialect, &str)] = &[
+        (
+            "chrome",
+            Dialect::Bash,
+            concat!(
+                "curl 'https://api.example.com/v1/items?page=2' \\\n",
+                "  -H 'accept: application/json' \\\n",
+                "  -H 'content-type: application/json' \\\n",
+                "  -b 'sid=abc; theme=dark' \\\n",
+                "  -H 'priority: u=1, i' \\\n",
+                "  --data-raw '{\"name\":\"it'\\''s\"}' \\\n",
+                "  --compressed",
+            ),
+        ),
+        (
+            "chrome-cmd",
+            Dialect::Cmd,
+            concat!(
+                "curl ^\"https://api.example.com/v1/items?page=2^\" ^\r\n",
+                "  -H ^\"accept: application/json^\" ^\r\n",
+                "  -H ^\"content-type: application/json^\" ^\r\n",
+                "  -b ^\"sid=abc; theme=dark^\" ^\r\n",
+                "  --data-raw ^\"^{^\\^\"name^\\^\":^\\^\"it's^\\^\"^}^\" ^\r\n",
+                "  --compressed",
+            ),
+        ),
+        (
+            "firefox",
+            Dialect::Bash,
+            concat!(
+                "curl 'https://api.example.com/v1/items?page=2' ",
+                "-X POST ",
+                "-H 'Accept: application/json' ",
+                "--compressed ",
+                "-H 'Content-Type: application/json' ",
+                "-H 'Connection: keep-alive' ",
+                "-H 'Cookie: sid=abc; theme=dark' ",
+                "-H 'Priority: u=0' ",
+                "-H 'TE: trailers' ",
+                "--data-raw '{\"name\":\"it'\"'\"'s\"}'",
+            ),
+        ),
+        (
+            "firefox-cmd",
+            Dialect::Cmd,
+            concat!(
+                "curl ^\"https://api.example.com/v1/items?page=2^\" ",
+                "-X POST ",
+                "-H ^\"Accept: application/json^\" ",
+                "--compressed ",
+                "-H ^\"Content-Type: application/json^\" ",
+                "-H ^\"Connection: keep-alive^\" ",
+                "-H ^\"Cookie: sid=abc; theme=dark^\" ",
+                "--data-raw ^\"^{^\\^\"name^\\^\":^\\^\"it's^\\^\"^}^\"",
+            ),
+        ),
+        (
+            "safari",
+            Dialect::Bash,
+            concat!(
+                "curl 'https://api.example.com/v1/items?page=2' \\\n",
+                "-X 'POST' \\\n",
+                "-H 'Content-Type: application/json' \\\n",
+                "-H 'Accept: application/json' \\\n",
+                "-H 'Cookie: sid=abc; theme=dark' \\\n",
+                "-H 'Accept-Encoding: gzip, deflate, br' \\\n",
+                "-H 'Connection: keep-alive' \\\n",
+                "--data-binary $'{\"name\":\"it\\'s\"}'",
+            ),
+        ),
+    ];
+
+    /// The same page load, copied from each browser.
+    const GET: &[(&str, Dialect, &str)] = &[
+        (
+            "chrome",
+            Dialect::Bash,
+            "curl 'https://example.com/' -H 'accept: text/html' -H 'accept-language: en' --compressed",
+        ),
+        (
+            "firefox",
+            Dialect::Bash,
+            "curl 'https://example.com/' --compressed -H 'Accept: text/html' -H 'Accept-Language: en' -H 'Connection: keep-alive'",
+        ),
+        (
+            "safari",
+            Dialect::Bash,
+            "curl 'https://example.com/' \\\n-X 'GET' \\\n-H 'Accept: text/html' \\\n-H 'Accept-Encoding: gzip, deflate, br' \\\n-H 'Accept-Language: en'",
+        ),
+    ];
+
+    fn normalized(corpus: &[(&str, Dialect, &str)]) -> Vec<(String, String)> {
+        corpus
+            .iter()
+            .map(|(browser, dialect, command)| {
+                let curls = curl_cmd_parse_dialect(command, *dialect)
+                    .unwrap_or_else(|e| panic!("{}: {}", browser, e));
+                (
+                    browser.to_string(),
+                    to_curl_string(&normalize_copied(&curls)),
+                )
+            })
+            .collect()
+    }
+
+    #[rstest]
+    fn test_post_corpus() {
+        let expected = concat!(
+            "curl 'https://api.example.com/v1/items?page=2' \\\n",
+            "  --header 'Accept: application/json' \\\n",
+            "  --header 'Content-Type: application/json' \\\n",
+            "  --data-raw '{\"name\":\"it'\"'\"'s\"}' \\\n",
+            "  --compressed \\\n",
+            "  --cookie 'sid=abc; theme=dark'",
+        );
+        for (browser, command) in normalized(POST) {
+            assert_eq!(command, expected, "{}", browser);
+        }
+    }
+
+    #[rstest]
+    fn test_get_corpus() {
+        let expected = concat!(
+            "curl 'https://example.com/' \\\n",
+            "  --header 'Accept: text/html' \\\n",
+            "  --header 'Accept-Language: en' \\\n",
+            "  --compressed",
+        );
+        for (browser, command) in normalized(GET) {
+            assert_eq!(command, expected, "{}", browser);
+        }
+    }
+
+    #[rstest]
+    #[case("curl https://a.com -X GET -d a=1", "--request 'GET'")]
+    #[case("curl https://a.com -X PUT -d a=1", "--request 'PUT'")]
+    #[case("curl https://a.com -X POST -G -d a=1", "--request 'POST'")]
+    #[case("curl https://a.com -d @body.json", "--data '@body.json'")]
+    #[case(
+        "curl https://a.com -H 'Cookie: session'",
+        "--header 'Cookie: session'"
+    )]
+    fn test_normalize_copied_keeps(#[case] input: &str, #[case] kept: &str) {
+        let command = to_curl_string(&normalize_copied(&curl_cmd_parse(input).unwrap()));
+        assert!(command.contains(kept), "{}", command);
+    }
+}
@@ -0,0 +1,106 @@
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+use super::ast::Curl;
+
+/// Where `-C`/`--continue-at` resumes a transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContinueAt {
+    /// `-C -`: after what the output file already holds, from the start
+    /// when there is none or the body is printed.
+    Auto,
+    /// `-C 1024`: after that many bytes.
+    Offset(u64),
+}
+
+/// A `-C` argument which is neither `-` nor a byte offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidOffset;
+
+impl fmt::Display for InvalidOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid resume offset")
+    }
+}
+
+impl std::error::Error for InvalidOffset {}
+
+impl FromStr for ContinueAt {
+    type Err = InvalidOffset;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "-" {
+            return Ok(ContinueAt::Auto);
+        }
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(InvalidOffset);
+        }
+        s.parse().map(ContinueAt::Offset).map_err(|_| InvalidOffset)
+    }
+}
+
+impl ContinueAt {
+    /// The `-C` of a command, the last one winning.
+    pub fn new(curls: &[Curl]) -> Option<Self> {
+        curls.iter().rev().find_map(|curl| match curl {
+            // Values were checked against their types while parsing.
+            Curl::Option { name, value } if name == "--continue-at" => value.parse().ok(),
+            _ => None,
+        })
+    }
+
+    /// The offset the transfer resumes at, given the size of the output
+    /// file if there is one.
+    pub fn offset(&self, existing: Option<u64>) -> u64 {
+        match self {
+            ContinueAt::Auto => existing.unwrap_or(0),
+            ContinueAt::Offset(offset) => *offset,
+        }
+    }
+
+    /// The `Range` header curl asks the rest of the resource with, e.g.
+    /// `bytes=1024-`; `None` at offset 0, where nothing is skipped.
+    pub fn range(offset: u64) -> Option<String> {
+        (offset > 0).then(|| format!("bytes={}-", offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case("-", Ok(ContinueAt::Auto))]
+    #[case("1024", Ok(ContinueAt::Offset(1024)))]
+    #[case("0", Ok(ContinueAt::Offset(0)))]
+    #[case("-5", Err(InvalidOffset))]
+    #[case("1k", Err(InvalidOffset))]
+    #[case("", Err(InvalidOffset))]
+    fn test_parse(#[case] value: &str, #[case] expected: Result<ContinueAt, InvalidOffset>) {
+        assert_eq!(value.parse::<ContinueAt>(), expected);
+    }
+
+    #[rstest]
+    #[case("curl https://a.com/f -C - -o f", Some(ContinueAt::Auto))]
+    #[case(
+        "curl https://a.com/f -C 10 --continue-at 20",
+        Some(ContinueAt::Offset(20))
+    )]
+    #[case("curl https://a.com/f", None)]
+    fn test_new(#[case] command: &str, #[case] expected: Option<ContinueAt>) {
+        assert_eq!(ContinueAt::new(&curl_cmd_parse(command).unwrap()), expected);
+    }
+
+    #[rstest]
+    fn test_offset() {
+        assert_eq!(ContinueAt::Auto.offset(Some(300)), 300);
+        assert_eq!(ContinueAt::Auto.offset(None), 0);
+        assert_eq!(ContinueAt::Offset(10).offset(Some(300)), 10);
+        assert_eq!(ContinueAt::range(300).as_deref(), Some("bytes=300-"));
+        assert_eq!(ContinueAt::range(0), None);
+    }
+}
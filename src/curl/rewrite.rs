@@ -0,0 +1,181 @@
+use super::ast::{Curl, OwnedCurl};
+use super::builder::CurlBuilder;
+use crate::diagnostic::{Code, Diagnostic};
+
+/// Options sending a header on their own, removed along with it.
+const HEADER_OPTIONS: &[(&str, &str)] = &[
+    ("Cookie", "--cookie"),
+    ("User-Agent", "--user-agent"),
+    ("Referer", "--referer"),
+    ("Authorization", "--user"),
+    ("Authorization", "--oauth2-bearer"),
+];
+
+/// A change to a parsed command, see [`rewrite`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    /// Remove the headers with this name, ignoring case, and the options
+    /// sending it, e.g. `-b` for `Cookie` or `-u` for `Authorization`.
+    StripHeader(String),
+    /// Remove the query parameters with this name.
+    StripQuery(String),
+    /// Replace the headers with this name, or add one.
+    SetHeader(String, String),
+    /// Append a query parameter.
+    AddQuery(String, String),
+    /// Replace the host of the URL, and its port when given as `host:port`.
+    SetHost(String),
+    SetMethod(String),
+    /// Replace the start of the URL, e.g. `https://prod.example`, with
+    /// another, e.g. `https://staging.example`; the URL must start with the
+    /// first, up to a `/`, `?` or `#`.
+    Rebase(String, String),
+}
+
+fn url_error(error: impl std::fmt::Display) -> Diagnostic {
+    Diagnostic::new(Code::InvalidOptionValue, error.to_string())
+}
+
+/// Split `host:port`; IPv6 addresses need brackets for a port to be found.
+fn split_port(host: &str) -> Result<(&str, Option<u16>), Diagnostic> {
+    match host.rsplit_once(':') {
+        Some((name, port)) if !name.contains(':') || name.ends_with(']') => port
+            .parse()
+            .map(|port| (name, Some(port)))
+            .map_err(|_| url_error(format!("Invalid port: {}", port))),
+        _ => Ok((host, None)),
+    }
+}
+
+/// Apply `edits` to the command in order, e.g. to strip the cookies of a
+/// command copied from a browser or point it at another server.
+///
+/// ```
+/// use winnowcurl::curl::parser::curl_cmd_parse;
+/// use winnowcurl::curl::render::to_curl_string;
+/// use winnowcurl::curl::rewrite::{Edit, rewrite};
+///
+/// let curls = curl_cmd_parse("curl 'https://example.com/a' -b 's=1' -H 'Accept: */*'")?;
+/// let edits = [
+///     Edit::StripHeader("cookie".into()),
+///     Edit::SetHost("staging.example.com".into()),
+/// ];
+/// assert_eq!(
+///     to_curl_string(&rewrite(&curls, &edits)?),
+///     "curl 'https://staging.example.com/a' \\\n  -H 'Accept: */*'"
+/// );
+/// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+/// ```
+pub fn rewrite(curls: &[Curl<'_>], edits: &[Edit]) -> Result<Vec<OwnedCurl>, Diagnostic> {
+    let mut builder = CurlBuilder::from(curls.to_vec());
+    for edit in edits {
+        match edit {
+            Edit::StripHeader(name) => {
+                builder.remove_header(name);
+                for (header, option) in HEADER_OPTIONS {
+                    if header.eq_ignore_ascii_case(name) {
+                        builder.remove_option(option);
+                    }
+                }
+            }
+            Edit::StripQuery(name) => {
+                builder.remove_query_param(name);
+            }
+            Edit::SetHeader(name, value) => {
+                builder.set_header(name, value);
+            }
+            Edit::AddQuery(name, value) => {
+                builder.add_query_param(name, value);
+            }
+            Edit::SetHost(host) => {
+                let (host, port) = split_port(host)?;
+                let url = builder
+                    .url_mut()
+                    .ok_or_else(|| Diagnostic::new(Code::MissingUrl, "The command has no URL"))?;
+                url.set_host(host).map_err(url_error)?;
+                if port.is_some() {
+                    url.set_port(port).map_err(url_error)?;
+                }
+            }
+            Edit::SetMethod(method) => {
+                builder.set_method(method);
+            }
+            Edit::Rebase(base, target) => {
+                let url = builder
+                    .url_mut()
+                    .ok_or_else(|| Diagnostic::new(Code::MissingUrl, "The command has no URL"))?
+                    .to_string();
+                let base = base.trim_end_matches('/');
+                let rest = url
+                    .strip_prefix(base)
+                    .filter(|rest| rest.is_empty() || rest.starts_with(['/', '?', '#']))
+                    .ok_or_else(|| url_error(format!("{} does not start with {}", url, base)))?;
+                builder.set_url(&format!("{}{}", target.trim_end_matches('/'), rest));
+            }
+        }
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+
+    #[rstest]
+    fn test_rewrite() {
+        let curls = curl_cmd_parse(concat!(
+            "curl 'https://shop.example/api?_=1&page=2' -H 'Cookie: a=1' -b 'b=2' ",
+            "-H 'Authorization: Bearer t' -u me:pw -A 'bot' -H 'Accept: text/html' -X POST"
+        ))
+        .unwrap();
+        let edits = [
+            Edit::StripHeader("cookie".into()),
+            Edit::StripHeader("Authorization".into()),
+            Edit::StripQuery("_".into()),
+            Edit::SetHeader("accept".into(), "*/*".into()),
+            Edit::AddQuery("debug".into(), "a b".into()),
+            Edit::SetHost("staging.example:8443".into()),
+            Edit::SetMethod("PUT".into()),
+        ];
+        assert_eq!(
+            to_curl_string(&rewrite(&curls, &edits).unwrap()),
+            concat!(
+                "curl 'https://staging.example:8443/api?page=2&debug=a+b' \\\n",
+                "  --user-agent 'bot' \\\n",
+                "  -H 'accept: */*' \\\n",
+                "  -X 'PUT'",
+            )
+        );
+    }
+
+    #[rstest]
+    #[case("curl 'https://a.com/x'", Edit::AddQuery("q".into(), "1".into()), "'https://a.com/x?q=1'")]
+    #[case("curl 'https://a.com/x?#top'", Edit::AddQuery("q".into(), "1".into()), "'https://a.com/x?q=1#top'")]
+    #[case("curl 'https://a.com:81/x'", Edit::SetHost("b.com".into()), "'https://b.com:81/x'")]
+    #[case("curl 'http://a.com/x'", Edit::SetHost("[::1]:8080".into()), "'http://[::1]:8080/x'")]
+    #[case("curl 'https://prod.example/v1/x?a=1'", Edit::Rebase("https://prod.example/".into(), "http://localhost:8080".into()), "'http://localhost:8080/v1/x?a=1'")]
+    #[case("curl 'https://prod.example/v1/x'", Edit::Rebase("https://prod.example/v1".into(), "https://staging.example/v2/".into()), "'https://staging.example/v2/x'")]
+    #[case("curl 'https://prod.example'", Edit::Rebase("https://prod.example".into(), "https://staging.example".into()), "'https://staging.example'")]
+    fn test_rewrite_url(#[case] input: &str, #[case] edit: Edit, #[case] expected: &str) {
+        let curls = curl_cmd_parse(input).unwrap();
+        assert_eq!(rewrite(&curls, &[edit]).unwrap()[0].to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(Edit::SetHost("a b".into()))]
+    #[case(Edit::SetHost("b.com:http".into()))]
+    #[case(Edit::SetHost("b.com:0".into()))]
+    #[case(Edit::SetMethod("".into()))]
+    #[case(Edit::Rebase("https://b.com".into(), "https://c.com".into()))]
+    #[case(Edit::Rebase("https://a.co".into(), "https://c.com".into()))]
+    fn test_rewrite_errors(#[case] edit: Edit) {
+        let curls = curl_cmd_parse("curl 'https://a.com/x'").unwrap();
+        assert_eq!(
+            rewrite(&curls, &[edit]).unwrap_err().code,
+            Code::InvalidOptionValue
+        );
+    }
+}
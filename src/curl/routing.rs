@@ -0,0 +1,535 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use super::ast::Curl;
+use crate::url::parser::CurlURL;
+
+/// The protocol spoken to a proxy, `http` unless its URL tells otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyScheme {
+    #[default]
+    Http,
+    Https,
+    Socks4,
+    Socks4a,
+    Socks5,
+    /// SOCKS5 with host names resolved by the proxy.
+    Socks5h,
+}
+
+impl ProxyScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProxyScheme::Http => "http",
+            ProxyScheme::Https => "https",
+            ProxyScheme::Socks4 => "socks4",
+            ProxyScheme::Socks4a => "socks4a",
+            ProxyScheme::Socks5 => "socks5",
+            ProxyScheme::Socks5h => "socks5h",
+        }
+    }
+
+    /// The scheme of `name`, case-insensitively; `None` for schemes curl
+    /// refuses for proxies.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "http" => ProxyScheme::Http,
+            "https" => ProxyScheme::Https,
+            "socks4" => ProxyScheme::Socks4,
+            "socks4a" => ProxyScheme::Socks4a,
+            "socks5" => ProxyScheme::Socks5,
+            "socks5h" => ProxyScheme::Socks5h,
+            _ => return None,
+        })
+    }
+
+    /// The port curl connects to when the proxy URL gives none.
+    pub fn default_port(&self) -> u16 {
+        match self {
+            ProxyScheme::Https => 443,
+            _ => 1080,
+        }
+    }
+}
+
+impl fmt::Display for ProxyScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A `-x`/`--proxy` proxy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Proxy {
+    pub scheme: ProxyScheme,
+    /// The address and credentials of the proxy, parsed as an `http://` URL,
+    /// `https://` for HTTPS proxies, whatever protocol `scheme` tells.
+    pub url: CurlURL<'static>,
+}
+
+impl Proxy {
+    /// The proxy `value` stands for, `http://` assumed without a scheme;
+    /// `None` for an empty value, which disables proxies, or a scheme curl
+    /// does not support.
+    ///
+    /// ```
+    /// use winnowcurl::curl::routing::{Proxy, ProxyScheme};
+    ///
+    /// let proxy = Proxy::new("socks5h://me:pw@proxy.corp").unwrap();
+    /// assert_eq!(proxy.scheme, ProxyScheme::Socks5h);
+    /// assert_eq!(proxy.url.host, "proxy.corp");
+    /// assert_eq!(proxy.port(), 1080);
+    /// assert_eq!(proxy.to_string(), "socks5h://me:pw@proxy.corp");
+    /// ```
+    pub fn new(value: &str) -> Option<Self> {
+        if value.is_empty() {
+            return None;
+        }
+        let (scheme, address) = match value.split_once("://") {
+            Some((scheme, address)) => (ProxyScheme::from_name(scheme)?, address),
+            None => (ProxyScheme::Http, value),
+        };
+        let prefix = match scheme {
+            ProxyScheme::Https => "https",
+            _ => "http",
+        };
+        let url = format!("{}://{}", prefix, address);
+        let Curl::URL(url) = Curl::new_as_url_str(&url) else {
+            unreachable!("URL arguments are always URLs")
+        };
+        Some(Proxy {
+            scheme,
+            url: url.into_owned(),
+        })
+    }
+
+    /// The port connected to, the default one of the scheme unless given.
+    pub fn port(&self) -> u16 {
+        self.url.port.unwrap_or(self.scheme.default_port())
+    }
+}
+
+impl fmt::Display for Proxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let url = self.url.to_string();
+        let address = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+        write!(f, "{}://{}", self.scheme, address)
+    }
+}
+
+/// Why a `--resolve` or `--connect-to` value cannot be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidMapping;
+
+impl fmt::Display for InvalidMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid host mapping")
+    }
+}
+
+impl std::error::Error for InvalidMapping {}
+
+/// Split `value` at its first `n - 1` colons outside of brackets, those of
+/// IPv6 addresses.
+fn split_fields(value: &str, n: usize) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut bracketed = false;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '[' => bracketed = true,
+            ']' => bracketed = false,
+            ':' if !bracketed && fields.len() + 1 < n => {
+                fields.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    fields.push(&value[start..]);
+    fields
+}
+
+/// `host` without the brackets of an IPv6 address.
+fn unbracket(host: &str) -> &str {
+    host.strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host)
+}
+
+/// `host` with brackets when it is an IPv6 address.
+fn bracket(host: &str) -> String {
+    if host.contains(':') {
+        format!("[{}]", host)
+    } else {
+        host.to_string()
+    }
+}
+
+/// A `--resolve` entry, `[+]host:port:address[,address]...` or
+/// `-host:port`, making curl connect to the addresses for the host and port
+/// instead of resolving them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Resolve {
+    /// The host name, `*` standing for any host.
+    pub host: String,
+    pub port: u16,
+    /// The addresses, IPv6 ones without brackets; empty for `-host:port`,
+    /// which removes the entry from the DNS cache.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<String>,
+    /// `+host:...`: the entry times out like a resolved one.
+    pub temporary: bool,
+}
+
+impl FromStr for Resolve {
+    type Err = InvalidMapping;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (removal, temporary, entry) = match s.as_bytes().first() {
+            Some(b'-') => (true, false, &s[1..]),
+            Some(b'+') => (false, true, &s[1..]),
+            _ => (false, false, s),
+        };
+        let fields = split_fields(entry, if removal { 2 } else { 3 });
+        let (host, port, addresses) = match fields[..] {
+            [host, port] if removal => (host, port, None),
+            [host, port, addresses] if !removal => (host, port, Some(addresses)),
+            _ => return Err(InvalidMapping),
+        };
+        let port = port.parse().map_err(|_| InvalidMapping)?;
+        let addresses: Vec<String> = addresses
+            .map(|addresses| {
+                addresses
+                    .split(',')
+                    .map(|address| unbracket(address.trim()).to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if host.is_empty() || addresses.iter().any(String::is_empty) {
+            return Err(InvalidMapping);
+        }
+        Ok(Resolve {
+            host: host.into(),
+            port,
+            addresses,
+            temporary,
+        })
+    }
+}
+
+impl fmt::Display for Resolve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.addresses.is_empty() {
+            return write!(f, "-{}:{}", self.host, self.port);
+        }
+        let addresses: Vec<String> = self.addresses.iter().map(|a| bracket(a)).collect();
+        let prefix = if self.temporary { "+" } else { "" };
+        write!(
+            f,
+            "{}{}:{}:{}",
+            prefix,
+            self.host,
+            self.port,
+            addresses.join(",")
+        )
+    }
+}
+
+/// A `--connect-to HOST1:PORT1:HOST2:PORT2` mapping: requests for the
+/// first host and port connect to the second ones. An empty part matches
+/// any host or port, or keeps the one of the request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConnectTo {
+    /// Hosts are kept without the brackets of IPv6 addresses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_port: Option<u16>,
+}
+
+impl FromStr for ConnectTo {
+    type Err = InvalidMapping;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [host, port, to_host, to_port] = split_fields(s, 4)[..] else {
+            return Err(InvalidMapping);
+        };
+        let name = |host: &str| (!host.is_empty()).then(|| unbracket(host).to_string());
+        let number = |port: &str| match port {
+            "" => Ok(None),
+            port => port.parse().map(Some).map_err(|_| InvalidMapping),
+        };
+        Ok(ConnectTo {
+            host: name(host),
+            port: number(port)?,
+            to_host: name(to_host),
+            to_port: number(to_port)?,
+        })
+    }
+}
+
+impl fmt::Display for ConnectTo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let host = |host: &Option<String>| host.as_deref().map(bracket).unwrap_or_default();
+        let port = |port: Option<u16>| port.map(|port| port.to_string()).unwrap_or_default();
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            host(&self.host),
+            port(self.port),
+            host(&self.to_host),
+            port(self.to_port)
+        )
+    }
+}
+
+/// A `--unix-socket` or `--abstract-unix-socket` the connection is made
+/// over instead of TCP.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnixSocket {
+    pub path: String,
+    /// Whether `path` names a socket of the Linux abstract namespace rather
+    /// than a file.
+    pub abstract_namespace: bool,
+}
+
+/// Where the connection of a command goes: `-x`, `--noproxy`, `--resolve`,
+/// `--connect-to` and `--unix-socket`. When the proxy or socket repeats,
+/// the last one wins; `-x ''` disables the proxy.
+///
+/// ```
+/// use winnowcurl::curl::parser::curl_cmd_parse;
+/// use winnowcurl::curl::routing::Routing;
+///
+/// let curls = curl_cmd_parse(
+///     "curl https://api.corp/x -x proxy.corp:3128 --resolve api.corp:443:10.0.0.7",
+/// )?;
+/// let routing = Routing::new(&curls);
+/// assert_eq!(routing.proxy.unwrap().to_string(), "http://proxy.corp:3128");
+/// assert_eq!(routing.resolve[0].addresses, ["10.0.0.7"]);
+/// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Routing {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<Proxy>,
+    /// The hosts reached without the proxy, `*` standing for all of them.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub noproxy: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub resolve: Vec<Resolve>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub connect_to: Vec<ConnectTo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unix_socket: Option<UnixSocket>,
+}
+
+impl Routing {
+    pub fn new(curls: &[Curl]) -> Self {
+        let mut routing = Self::default();
+        for curl in curls {
+            if let Curl::Option { name, value } = curl {
+                match &**name {
+                    "--proxy" | "--proxy1.0" => routing.proxy = Proxy::new(value),
+                    "--noproxy" => {
+                        routing.noproxy = value
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|host| !host.is_empty())
+                            .map(String::from)
+                            .collect()
+                    }
+                    // Invalid mappings are rejected while parsing.
+                    "--resolve" => routing.resolve.extend(value.parse().ok()),
+                    "--connect-to" => routing.connect_to.extend(value.parse().ok()),
+                    "--unix-socket" | "--abstract-unix-socket" => {
+                        routing.unix_socket = Some(UnixSocket {
+                            path: value.to_string(),
+                            abstract_namespace: name == "--abstract-unix-socket",
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+        routing
+    }
+
+    /// Whether the connection goes straight to the host of the URL, as
+    /// resolved by the system.
+    pub fn is_direct(&self) -> bool {
+        self.proxy.is_none()
+            && self.resolve.is_empty()
+            && self.connect_to.is_empty()
+            && self.unix_socket.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        "proxy.corp:3128",
+        ProxyScheme::Http,
+        "proxy.corp",
+        3128,
+        "http://proxy.corp:3128"
+    )]
+    #[case(
+        "HTTPS://proxy.corp",
+        ProxyScheme::Https,
+        "proxy.corp",
+        443,
+        "https://proxy.corp"
+    )]
+    #[case(
+        "socks5://u:p@[::1]:9050",
+        ProxyScheme::Socks5,
+        "::1",
+        9050,
+        "socks5://u:p@[::1]:9050"
+    )]
+    #[case("socks4a://gw", ProxyScheme::Socks4a, "gw", 1080, "socks4a://gw")]
+    fn test_proxy(
+        #[case] value: &str,
+        #[case] scheme: ProxyScheme,
+        #[case] host: &str,
+        #[case] port: u16,
+        #[case] display: &str,
+    ) {
+        let proxy = Proxy::new(value).unwrap();
+        assert_eq!(proxy.scheme, scheme);
+        assert_eq!(proxy.url.host, host);
+        assert_eq!(proxy.port(), port);
+        assert_eq!(proxy.to_string(), display);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("ftp://proxy.corp")]
+    fn test_no_proxy(#[case] value: &str) {
+        assert_eq!(Proxy::new(value), None);
+    }
+
+    #[rstest]
+    #[case("a.com:443:10.0.0.1", "a.com", 443, vec!["10.0.0.1"], false)]
+    #[case("+*:80:[::1],10.0.0.2", "*", 80, vec!["::1", "10.0.0.2"], true)]
+    #[case("-a.com:443", "a.com", 443, vec![], false)]
+    fn test_resolve(
+        #[case] value: &str,
+        #[case] host: &str,
+        #[case] port: u16,
+        #[case] addresses: Vec<&str>,
+        #[case] temporary: bool,
+    ) {
+        let resolve: Resolve = value.parse().unwrap();
+        assert_eq!(
+            resolve,
+            Resolve {
+                host: host.into(),
+                port,
+                addresses: addresses.into_iter().map(String::from).collect(),
+                temporary,
+            }
+        );
+        assert_eq!(resolve.to_string(), value);
+    }
+
+    #[rstest]
+    #[case("a.com:443")]
+    #[case("a.com:https:10.0.0.1")]
+    #[case(":443:10.0.0.1")]
+    #[case("a.com:443:10.0.0.1,")]
+    #[case("-a.com:443:10.0.0.1")]
+    fn test_invalid_resolve(#[case] value: &str) {
+        assert_eq!(value.parse::<Resolve>(), Err(InvalidMapping));
+    }
+
+    #[rstest]
+    #[case(
+        "a.com:443:b.com:8443",
+        Some("a.com"),
+        Some(443),
+        Some("b.com"),
+        Some(8443)
+    )]
+    #[case("::[::1]:", None, None, Some("::1"), None)]
+    #[case(":80:backend:", None, Some(80), Some("backend"), None)]
+    fn test_connect_to(
+        #[case] value: &str,
+        #[case] host: Option<&str>,
+        #[case] port: Option<u16>,
+        #[case] to_host: Option<&str>,
+        #[case] to_port: Option<u16>,
+    ) {
+        let connect_to: ConnectTo = value.parse().unwrap();
+        assert_eq!(
+            connect_to,
+            ConnectTo {
+                host: host.map(String::from),
+                port,
+                to_host: to_host.map(String::from),
+                to_port,
+            }
+        );
+        assert_eq!(connect_to.to_string(), value);
+    }
+
+    #[rstest]
+    #[case("a.com:443:b.com")]
+    #[case("a.com:443:b.com:80:1")]
+    #[case("a.com:x:b.com:80")]
+    fn test_invalid_connect_to(#[case] value: &str) {
+        assert_eq!(value.parse::<ConnectTo>(), Err(InvalidMapping));
+    }
+
+    #[rstest]
+    fn test_routing() {
+        let curls = curl_cmd_parse(concat!(
+            "curl 'https://a.com' -x old.corp --proxy socks5h://gw.corp:1081 ",
+            "--noproxy 'localhost, .internal' --resolve a.com:443:10.0.0.1 ",
+            "--connect-to a.com:443:b.com:8443 --unix-socket /run/a.sock ",
+            "--abstract-unix-socket b",
+        ))
+        .unwrap();
+        let routing = Routing::new(&curls);
+        assert_eq!(
+            routing.proxy.as_ref().unwrap().to_string(),
+            "socks5h://gw.corp:1081"
+        );
+        assert_eq!(routing.noproxy, ["localhost", ".internal"]);
+        assert_eq!(routing.resolve.len(), 1);
+        assert_eq!(routing.connect_to[0].to_host.as_deref(), Some("b.com"));
+        assert_eq!(
+            routing.unix_socket,
+            Some(UnixSocket {
+                path: "b".into(),
+                abstract_namespace: true,
+            })
+        );
+        assert!(!routing.is_direct());
+
+        let curls = curl_cmd_parse("curl 'https://a.com' -x proxy.corp -x ''").unwrap();
+        assert!(Routing::new(&curls).is_direct());
+    }
+
+    #[rstest]
+    #[case("curl 'https://a.com' --resolve a.com:443", "--resolve expects")]
+    #[case("curl 'https://a.com' --connect-to a.com", "--connect-to expects")]
+    fn test_invalid_mapping_is_rejected(#[case] input: &str, #[case] message: &str) {
+        let err = curl_cmd_parse(input).unwrap_err();
+        assert!(err.message.starts_with(message), "{}", err.message);
+    }
+}
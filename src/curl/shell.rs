@@ -0,0 +1,232 @@
+use super::ast::Curl;
+use super::lexer::Dialect;
+use super::render::to_curl_string_dialect;
+use crate::diagnostic::{Code, Diagnostic};
+
+/// The longest command `cmd.exe` reads, in characters.
+pub const CMD_MAX_LENGTH: usize = 8191;
+
+/// Something the shell a command is written for mangles whatever the
+/// quoting of [`super::render::shell_quote_dialect`].
+///
+/// Bash history expansion of `!` and the backticks and `$` of bash and
+/// PowerShell are escaped by the quoting and never reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GotchaKind {
+    /// A NUL byte, which ends a process argument whatever the shell.
+    Nul,
+    /// A `%` in cmd, expanded as a variable when the command is run from a
+    /// batch file, where it has to be written `%%`.
+    Percent,
+    /// A `!` in cmd, expanded as a variable when delayed expansion is on.
+    Exclamation,
+    /// A control character other than a line break or a tab, which cmd
+    /// cannot write.
+    ControlCharacter(char),
+    /// A `"` passed to `curl.exe`, which PowerShell before 7.3 strips.
+    DoubleQuote,
+    /// An empty argument, which PowerShell before 7.3 drops.
+    EmptyArgument,
+    /// A cmd command longer than [`CMD_MAX_LENGTH`], in characters.
+    TooLong(usize),
+}
+
+/// A gotcha of a command written for a shell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellGotcha {
+    /// Where it is: `URL` or the option as written, `None` for the whole
+    /// command.
+    pub option: Option<String>,
+    pub kind: GotchaKind,
+}
+
+impl ShellGotcha {
+    pub fn diagnostic(&self) -> Diagnostic {
+        let option = self.option.as_deref().unwrap_or("The command");
+        let message = match self.kind {
+            GotchaKind::Nul => format!(
+                "{} holds a NUL byte, which ends the argument in every shell",
+                option
+            ),
+            GotchaKind::Percent => format!(
+                "{} holds a '%', which cmd expands as a variable when the command is run from a batch file; write it '%%' there",
+                option
+            ),
+            GotchaKind::Exclamation => format!(
+                "{} holds a '!', which cmd expands as a variable when delayed expansion is on",
+                option
+            ),
+            GotchaKind::ControlCharacter(c) => {
+                format!("{} holds {:?}, which cmd cannot write", option, c)
+            }
+            GotchaKind::DoubleQuote => format!(
+                "{} holds a '\"', which PowerShell before 7.3 strips from the arguments of curl.exe; escape it as '\\\"' there",
+                option
+            ),
+            GotchaKind::EmptyArgument => format!(
+                "{} is empty, and PowerShell before 7.3 drops the empty arguments of curl.exe",
+                option
+            ),
+            GotchaKind::TooLong(length) => format!(
+                "The command is {} characters long, over the {} cmd reads",
+                length, CMD_MAX_LENGTH
+            ),
+        };
+        Diagnostic::new(Code::ShellExpansion, message)
+    }
+}
+
+/// The argument of a node along with where it is, `None` for flags.
+fn argument(curl: &Curl) -> Option<(String, String)> {
+    match curl {
+        Curl::Method(stru) | Curl::Header(stru) | Curl::Data(stru) => Some((
+            stru.identifier.to_string(),
+            stru.data.as_deref().unwrap_or_default().to_string(),
+        )),
+        Curl::URL(url) => Some(("URL".to_string(), url.to_string())),
+        Curl::Form(field) => Some((field.identifier.to_string(), field.to_string())),
+        Curl::Option { name, value } => Some((name.to_string(), value.to_string())),
+        Curl::Flag(_) | Curl::Unknown(..) => None,
+    }
+}
+
+/// The gotchas of one value of a command written for `dialect`, at most one
+/// of each kind.
+fn value_gotchas(value: &str, dialect: Dialect) -> Vec<GotchaKind> {
+    let mut kinds = Vec::new();
+    if value.contains('\0') {
+        kinds.push(GotchaKind::Nul);
+    }
+    match dialect {
+        Dialect::Cmd => {
+            if value.contains('%') {
+                kinds.push(GotchaKind::Percent);
+            }
+            if value.contains('!') {
+                kinds.push(GotchaKind::Exclamation);
+            }
+            kinds.extend(
+                value
+                    .chars()
+                    .find(|&c| c.is_control() && !matches!(c, '\0' | '\n' | '\t'))
+                    .map(GotchaKind::ControlCharacter),
+            );
+        }
+        Dialect::PowerShell => {
+            if value.contains('"') {
+                kinds.push(GotchaKind::DoubleQuote);
+            }
+            if value.is_empty() {
+                kinds.push(GotchaKind::EmptyArgument);
+            }
+        }
+        Dialect::Bash | Dialect::Auto => {}
+    }
+    kinds
+}
+
+/// Find what the shell of `dialect` would mangle in the command
+/// [`to_curl_string_dialect`] writes for it, bash for [`Dialect::Auto`].
+///
+/// ```
+/// use winnowcurl::curl::lexer::Dialect;
+/// use winnowcurl::curl::parser::curl_cmd_parse;
+/// use winnowcurl::curl::shell::{GotchaKind, find};
+///
+/// let curls = curl_cmd_parse("curl 'https://a.com/?q=100%25' -A ''")?;
+/// let kinds = |dialect| find(&curls, dialect).into_iter().map(|found| found.kind).collect::<Vec<_>>();
+/// assert_eq!(kinds(Dialect::Bash), []);
+/// assert_eq!(kinds(Dialect::Cmd), [GotchaKind::Percent]);
+/// assert_eq!(kinds(Dialect::PowerShell), [GotchaKind::EmptyArgument]);
+/// # Ok::<(), winnowcurl::Diagnostic>(())
+/// ```
+pub fn find(curls: &[Curl], dialect: Dialect) -> Vec<ShellGotcha> {
+    let mut found: Vec<ShellGotcha> = curls
+        .iter()
+        .filter_map(argument)
+        .flat_map(|(option, value)| {
+            value_gotchas(&value, dialect)
+                .into_iter()
+                .map(move |kind| ShellGotcha {
+                    option: Some(option.clone()),
+                    kind,
+                })
+        })
+        .collect();
+    if dialect == Dialect::Cmd {
+        let length = to_curl_string_dialect(curls, dialect).chars().count();
+        if length > CMD_MAX_LENGTH {
+            found.push(ShellGotcha {
+                option: None,
+                kind: GotchaKind::TooLong(length),
+            });
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    fn kinds(command: &str, dialect: Dialect) -> Vec<(Option<String>, GotchaKind)> {
+        find(&curl_cmd_parse(command).unwrap(), dialect)
+            .into_iter()
+            .map(|found| (found.option, found.kind))
+            .collect()
+    }
+
+    #[rstest]
+    #[case(Dialect::Bash, vec![])]
+    #[case(
+        Dialect::Cmd,
+        vec![
+            (Some("URL".to_string()), GotchaKind::Percent),
+            (Some("-d".to_string()), GotchaKind::Exclamation),
+            (Some("-H".to_string()), GotchaKind::ControlCharacter('\r')),
+        ]
+    )]
+    #[case(
+        Dialect::PowerShell,
+        vec![
+            (Some("-d".to_string()), GotchaKind::DoubleQuote),
+            (Some("--user-agent".to_string()), GotchaKind::EmptyArgument),
+        ]
+    )]
+    fn test_find(#[case] dialect: Dialect, #[case] expected: Vec<(Option<String>, GotchaKind)>) {
+        let command = r#"curl 'https://a.com/?q=%20' -d '{"a": "hi!"}' -H $'X: a\r\n b' -A '' -sS"#;
+        assert_eq!(kinds(command, dialect), expected);
+    }
+
+    #[rstest]
+    fn test_find_nul() {
+        assert_eq!(
+            kinds(r"curl https://a.com --data-binary $'a\x00b'", Dialect::Bash),
+            [(Some("--data-binary".to_string()), GotchaKind::Nul)]
+        );
+    }
+
+    #[rstest]
+    fn test_find_too_long() {
+        let command = format!("curl https://a.com -H 'X: {}'", "a".repeat(CMD_MAX_LENGTH));
+        assert_eq!(
+            kinds(&command, Dialect::Cmd),
+            [(None, GotchaKind::TooLong(8228))]
+        );
+        assert_eq!(kinds(&command, Dialect::PowerShell), []);
+    }
+
+    #[rstest]
+    fn test_diagnostic() {
+        let found = ShellGotcha {
+            option: Some("URL".to_string()),
+            kind: GotchaKind::Percent,
+        };
+        assert_eq!(
+            found.diagnostic().to_string(),
+            "warning[WCC003]: URL holds a '%', which cmd expands as a variable when the command is run from a batch file; write it '%%' there"
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead};
+
+use super::ast::{Curl, OwnedCurl};
+use super::batch::{split_commands, starts_with_curl};
+use super::error::ParseError;
+use super::intern::Interner;
+use super::lexer::Dialect;
+use super::parser::curl_cmd_try_parse;
+use crate::Request;
+use crate::diagnostic::{Code, Diagnostic};
+
+/// One command read by a [`CurlCmdIter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedCommand {
+    /// The 1-based line the command starts on.
+    pub line: usize,
+    pub curls: Vec<OwnedCurl>,
+}
+
+/// Why a [`CurlCmdIter`] could not give the next command.
+#[derive(Debug)]
+pub enum StreamError {
+    /// Reading failed; the iterator ends with it.
+    Io(io::Error),
+    /// The command starting on `line` doesn't parse; the iterator goes on
+    /// with the next one.
+    Parse { line: usize, error: ParseError },
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "cannot read commands: {}", e),
+            StreamError::Parse { line, error } => write!(f, "line {}: {}", line, error.message),
+        }
+    }
+}
+
+impl StreamError {
+    pub fn diagnostic(&self) -> Diagnostic {
+        match self {
+            StreamError::Io(_) => Diagnostic::new(Code::UnreadableFile, self.to_string()),
+            StreamError::Parse { error, .. } => Diagnostic::new(error.code, self.to_string()),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Io(e) => Some(e),
+            StreamError::Parse { .. } => None,
+        }
+    }
+}
+
+/// Whether a line may end the command read so far: one not continued with
+/// a backslash, a new `curl` or a separator.
+fn may_end_command(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    !line.trim_end_matches(['\n', '\r']).ends_with('\\')
+        || starts_with_curl(trimmed)
+        || line.contains([';', '&', '|'])
+}
+
+/// Parse the curl commands of a reader lazily, one line at a time, so that
+/// scripts or logs of any size are parsed holding a single command in
+/// memory.
+///
+/// Commands are split as [`split_commands`] does for bash, a command being
+/// complete once the line starting the next one is read. A command that
+/// doesn't parse is reported without stopping the iterator; a read error,
+/// such as a line that isn't UTF-8, ends the input there and is given last.
+///
+/// ```
+/// use winnowcurl::curl::stream::CurlCmdIter;
+///
+/// let script = "# fetch the users\ncurl https://a.com/users \\\n  -H 'Accept: */*'\n\ncurl https://a.com/ -m soon\n";
+/// let mut commands = CurlCmdIter::new(script.as_bytes());
+/// let users = commands.next().unwrap()?;
+/// assert_eq!((users.line, users.curls.len()), (2, 2));
+/// assert!(commands.next().unwrap().is_err());
+/// assert!(commands.next().is_none());
+/// # Ok::<(), winnowcurl::curl::stream::StreamError>(())
+/// ```
+pub struct CurlCmdIter<R> {
+    reader: R,
+    /// The lines read and not yet split into commands.
+    buffer: String,
+    /// The 1-based line `buffer` starts on.
+    line: usize,
+    /// The commands split off `buffer`, with the line each starts on.
+    complete: VecDeque<(usize, String)>,
+    /// The read error to give once `complete` is empty.
+    failed: Option<io::Error>,
+    done: bool,
+}
+
+impl<R: BufRead> CurlCmdIter<R> {
+    pub fn new(reader: R) -> Self {
+        CurlCmdIter {
+            reader,
+            buffer: String::new(),
+            line: 1,
+            complete: VecDeque::new(),
+            failed: None,
+            done: false,
+        }
+    }
+
+    /// The requests the commands send, see [`Request`]. Header names are
+    /// interned, the requests sharing one allocation per name.
+    pub fn requests(self) -> impl Iterator<Item = Result<Request, StreamError>> {
+        let mut interner = Interner::new();
+        self.map(move |command| {
+            let command = command?;
+            Request::from_curls_interned(&command.curls, &mut interner).map_err(|e| {
+                StreamError::Parse {
+                    line: command.line,
+                    error: ParseError::new(e.code, e.message, ""),
+                }
+            })
+        })
+    }
+
+    /// Move the commands of `buffer` to `complete`, all of them at the end
+    /// of the input, or else all but the last one, which may go on.
+    fn split(&mut self, at_end: bool) {
+        let invocations = split_commands(&self.buffer, Dialect::Bash);
+        let Some(last) = invocations.last() else {
+            // Nothing but blank lines, comments and separators.
+            self.line += self.buffer.matches('\n').count();
+            self.buffer.clear();
+            return;
+        };
+        let kept = if at_end { None } else { Some(last) };
+        for invocation in &invocations[..invocations.len() - usize::from(kept.is_some())] {
+            self.complete
+                .push_back((self.line + invocation.line - 1, invocation.source.into()));
+        }
+        match kept {
+            Some(last) => {
+                let offset = last.source.as_ptr() as usize - self.buffer.as_ptr() as usize;
+                self.line += last.line - 1;
+                self.buffer.drain(..offset);
+            }
+            None => self.buffer.clear(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CurlCmdIter<R> {
+    type Item = Result<ParsedCommand, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((line, source)) = self.complete.pop_front() {
+                return Some(
+                    curl_cmd_try_parse(&source)
+                        .map(|curls| ParsedCommand {
+                            line,
+                            curls: curls.into_iter().map(Curl::into_owned).collect(),
+                        })
+                        .map_err(|error| StreamError::Parse { line, error }),
+                );
+            }
+            if self.done {
+                return self.failed.take().map(|e| Err(StreamError::Io(e)));
+            }
+            let start = self.buffer.len();
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => {
+                    self.done = true;
+                    self.split(true);
+                }
+                Ok(_) => {
+                    if may_end_command(&self.buffer[start..]) {
+                        self.split(false);
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.done = true;
+                    self.failed = Some(e);
+                    self.split(true);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+    use std::io::{BufReader, Read};
+
+    /// A reader handing out a few bytes at a time, so that commands span
+    /// many reads.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(3).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    fn streamed(input: &str) -> Vec<(usize, String)> {
+        CurlCmdIter::new(BufReader::with_capacity(4, Trickle(input.as_bytes())))
+            .map(|command| {
+                let command = command.unwrap();
+                (command.line, to_curl_string(&command.curls))
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case("curl a.com")]
+    #[case("curl a.com\n\ncurl b.com\n")]
+    #[case("curl a.com && curl b.com; curl c.com")]
+    #[case("# header\ncurl a.com \\\n  -v\n\n\ncurl b.com; curl c.com\n")]
+    #[case("curl a.com -d 'x;y && z\n\ncurl'\ncurl b.com")]
+    #[case("curl a.com \\\n-H 'X: 1'\n# done\n\n\n# more\ncurl b.com -d é\n")]
+    #[case("curl a.com\r\n\r\ncurl b.com\r\n")]
+    #[case("  \n\n ;; \n")]
+    fn test_stream_matches_batch(#[case] input: &str) {
+        let expected: Vec<(usize, String)> = split_commands(input, Dialect::Bash)
+            .into_iter()
+            .map(|invocation| {
+                let curls = curl_cmd_try_parse(invocation.source).unwrap();
+                (invocation.line, to_curl_string(&curls))
+            })
+            .collect();
+        assert_eq!(streamed(input), expected);
+    }
+
+    #[rstest]
+    fn test_stream_reports_and_goes_on() {
+        let input = "curl a.com\ncurl -m soon b.com\n\nwget c.com\n\ncurl d.com";
+        let results: Vec<_> = CurlCmdIter::new(input.as_bytes()).collect();
+        assert_eq!(results.len(), 4);
+        assert!(matches!(
+            results[1],
+            Err(StreamError::Parse { line: 2, .. })
+        ));
+        assert!(matches!(
+            results[2],
+            Err(StreamError::Parse { line: 4, .. })
+        ));
+        assert_eq!(results[3].as_ref().unwrap().line, 6);
+    }
+
+    #[rstest]
+    fn test_stream_ends_on_read_error() {
+        let results: Vec<_> = CurlCmdIter::new(&b"curl a.com\ncurl \xff.com\n"[..]).collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(StreamError::Io(_))));
+    }
+
+    #[rstest]
+    fn test_stream_requests() {
+        let input = "curl https://a.com -d x=1\ncurl https://b.com -d x=1 -I\n";
+        let results: Vec<_> = CurlCmdIter::new(input.as_bytes()).requests().collect();
+        assert_eq!(results[0].as_ref().unwrap().method, "POST");
+        let error = results[1].as_ref().unwrap_err();
+        assert_eq!(error.diagnostic().code, Code::ConflictingMethods);
+        assert!(error.to_string().starts_with("line 2: "));
+    }
+
+    #[rstest]
+    fn test_stream_holds_one_command() {
+        let mut commands = CurlCmdIter::new(
+            "curl a.com\ncurl b.com\ncurl c.com \\\n  -v\ncurl d.com\n".as_bytes(),
+        );
+        commands.next();
+        assert_eq!(commands.buffer, "curl b.com\n");
+        commands.next();
+        commands.next();
+        assert_eq!(commands.buffer, "curl d.com\n");
+        assert_eq!(commands.line, 5);
+    }
+}
@@ -0,0 +1,150 @@
+use serde::Serialize;
+
+use super::ast::Curl;
+use super::date::{http_date, parse_date};
+
+/// What `-z`/`--time-cond` asks of the time the resource was last modified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeCondKind {
+    /// `-z date`: only transfer a resource modified since the date, sent
+    /// as `If-Modified-Since`.
+    IfModifiedSince,
+    /// `-z -date`: only transfer a resource not modified since the date,
+    /// sent as `If-Unmodified-Since`.
+    IfUnmodifiedSince,
+    /// `-z =date`: only transfer a resource last modified at the date,
+    /// sent as `Last-Modified`.
+    LastModified,
+}
+
+impl TimeCondKind {
+    /// The header the condition is sent as.
+    pub fn header_name(&self) -> &'static str {
+        match self {
+            TimeCondKind::IfModifiedSince => "If-Modified-Since",
+            TimeCondKind::IfUnmodifiedSince => "If-Unmodified-Since",
+            TimeCondKind::LastModified => "Last-Modified",
+        }
+    }
+}
+
+/// The time a `-z`/`--time-cond` condition compares with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeSource {
+    /// A date, in seconds since the epoch.
+    Date(i64),
+    /// The modification time of a local file, which curl reads the argument
+    /// as when it is no date it knows.
+    File(String),
+}
+
+/// A `-z`/`--time-cond` condition, e.g. `-z 'Sun, 06 Nov 1994 08:49:37 GMT'`
+/// or `-z -local.html`.
+///
+/// ```
+/// use winnowcurl::curl::time_cond::{TimeCondKind, TimeCondition, TimeSource};
+///
+/// let condition = TimeCondition::parse("-06 Nov 1994 08:49:37 GMT");
+/// assert_eq!(condition.kind, TimeCondKind::IfUnmodifiedSince);
+/// assert_eq!(condition.source, TimeSource::Date(784_111_777));
+/// assert_eq!(
+///     condition.header(),
+///     Some(("If-Unmodified-Since", "Sun, 06 Nov 1994 08:49:37 GMT".to_string()))
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct TimeCondition {
+    pub kind: TimeCondKind,
+    pub source: TimeSource,
+}
+
+impl TimeCondition {
+    /// Read the argument of `-z` the way curl does: a leading `-` or `=`
+    /// picks the kind of condition, and what follows is a date, see
+    /// [`parse_date`], or else the name of a file.
+    pub fn parse(value: &str) -> Self {
+        let (kind, rest) = if let Some(rest) = value.strip_prefix('-') {
+            (TimeCondKind::IfUnmodifiedSince, rest)
+        } else if let Some(rest) = value.strip_prefix('=') {
+            (TimeCondKind::LastModified, rest)
+        } else if let Some(rest) = value.strip_prefix('+') {
+            (TimeCondKind::IfModifiedSince, rest)
+        } else {
+            (TimeCondKind::IfModifiedSince, value)
+        };
+        let source = match parse_date(rest) {
+            Some(seconds) => TimeSource::Date(seconds),
+            None => TimeSource::File(rest.to_string()),
+        };
+        TimeCondition { kind, source }
+    }
+
+    /// The condition of a command, the last `-z` winning.
+    pub fn new(curls: &[Curl]) -> Option<Self> {
+        curls.iter().rev().find_map(|curl| match curl {
+            Curl::Option { name, value } if name == "--time-cond" => Some(Self::parse(value)),
+            _ => None,
+        })
+    }
+
+    /// The header curl sends for the condition, e.g. `If-Modified-Since:
+    /// Sun, 06 Nov 1994 08:49:37 GMT`; `None` when the time is that of a
+    /// file, only known where the command runs.
+    pub fn header(&self) -> Option<(&'static str, String)> {
+        match self.source {
+            TimeSource::Date(seconds) => Some((self.kind.header_name(), http_date(seconds))),
+            TimeSource::File(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        "19941106",
+        TimeCondKind::IfModifiedSince,
+        TimeSource::Date(784_080_000)
+    )]
+    #[case(
+        "+19941106",
+        TimeCondKind::IfModifiedSince,
+        TimeSource::Date(784_080_000)
+    )]
+    #[case(
+        "-19941106",
+        TimeCondKind::IfUnmodifiedSince,
+        TimeSource::Date(784_080_000)
+    )]
+    #[case("=19941106", TimeCondKind::LastModified, TimeSource::Date(784_080_000))]
+    #[case("local.html", TimeCondKind::IfModifiedSince, TimeSource::File("local.html".into()))]
+    #[case("-local.html", TimeCondKind::IfUnmodifiedSince, TimeSource::File("local.html".into()))]
+    fn test_parse(#[case] value: &str, #[case] kind: TimeCondKind, #[case] source: TimeSource) {
+        assert_eq!(TimeCondition::parse(value), TimeCondition { kind, source });
+    }
+
+    #[rstest]
+    fn test_new() {
+        let curls =
+            curl_cmd_parse("curl https://a.com -z 'local.html' --time-cond '1 Jan 2030'").unwrap();
+        let condition = TimeCondition::new(&curls).unwrap();
+        assert_eq!(
+            condition.header(),
+            Some((
+                "If-Modified-Since",
+                "Tue, 01 Jan 2030 00:00:00 GMT".to_string()
+            ))
+        );
+        assert_eq!(
+            TimeCondition::new(&curl_cmd_parse("curl https://a.com").unwrap()),
+            None
+        );
+        assert_eq!(TimeCondition::parse("local.html").header(), None);
+    }
+}
@@ -0,0 +1,374 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::ast::{Curl, CurlStru, OwnedCurl};
+use super::body::{BodySource, DataArg};
+use super::date::{MONTHS, WEEKDAYS, civil_date, http_date};
+use super::escape::is_form_encoded;
+use super::header::split_header;
+use crate::url::normalize::VolatileParams;
+
+/// The headers whose value says when the command was made.
+const HEADERS: &[&str] = &["Date", "X-Amz-Date", "X-Date"];
+
+/// The query and form parameters taken for timestamps when their value is
+/// a time since the epoch.
+const PARAMS: &[&str] = &["_", "t", "ts", "*time*", "*date*"];
+
+/// The times epoch values are looked for between, 2000 to 2100, so that an
+/// id of ten digits is not taken for one.
+const EPOCH_RANGE: std::ops::Range<u64> = 946_684_800..4_102_444_800;
+
+/// How a time is written in a command, which servers may refuse once it
+/// is stale, e.g. a request signature checked against its `X-Amz-Date`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Timestamp {
+    /// An HTTP date, e.g. `Wed, 01 May 2024 12:30:00 GMT`.
+    HttpDate,
+    /// The ISO 8601 basic format of `X-Amz-Date`, e.g. `20240501T123000Z`.
+    AmzDate,
+    /// Seconds since the epoch, e.g. `1714566600`.
+    EpochSeconds,
+    /// Milliseconds since the epoch, e.g. `1714566600000`.
+    EpochMillis,
+}
+
+impl Timestamp {
+    /// How `value` writes a time, `None` when it is no time.
+    pub fn detect(value: &str) -> Option<Self> {
+        let digits = !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit());
+        match value.len() {
+            10 if digits && value.parse().is_ok_and(|t| EPOCH_RANGE.contains(&t)) => {
+                Some(Timestamp::EpochSeconds)
+            }
+            13 if digits
+                && value
+                    .parse::<u64>()
+                    .is_ok_and(|t| EPOCH_RANGE.contains(&(t / 1000))) =>
+            {
+                Some(Timestamp::EpochMillis)
+            }
+            16 if is_amz_date(value) => Some(Timestamp::AmzDate),
+            _ if is_http_date(value) => Some(Timestamp::HttpDate),
+            _ => None,
+        }
+    }
+
+    /// `time` written the same way.
+    pub fn format(&self, time: SystemTime) -> String {
+        let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let seconds = elapsed.as_secs();
+        let (year, month, day) = civil_date((seconds / 86_400) as i64);
+        let (hour, minute, second) = (seconds / 3600 % 24, seconds / 60 % 60, seconds % 60);
+        match self {
+            Timestamp::HttpDate => http_date(seconds as i64),
+            Timestamp::AmzDate => format!(
+                "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+                year, month, day, hour, minute, second
+            ),
+            Timestamp::EpochSeconds => seconds.to_string(),
+            Timestamp::EpochMillis => elapsed.as_millis().to_string(),
+        }
+    }
+}
+
+fn is_digits(text: &str, len: usize) -> bool {
+    text.len() == len && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Whether `value` is like `20240501T123000Z`.
+fn is_amz_date(value: &str) -> bool {
+    value.len() == 16
+        && is_digits(&value[..8], 8)
+        && value.as_bytes()[8] == b'T'
+        && is_digits(&value[9..15], 6)
+        && value.ends_with('Z')
+}
+
+/// Whether `value` is like `Wed, 01 May 2024 12:30:00 GMT`, the only form
+/// of HTTP date senders may generate.
+fn is_http_date(value: &str) -> bool {
+    let words: Vec<&str> = value.split(' ').collect();
+    let [weekday, day, month, year, time, "GMT"] = words[..] else {
+        return false;
+    };
+    let mut clock = time.split(':');
+    weekday
+        .strip_suffix(',')
+        .is_some_and(|weekday| WEEKDAYS.contains(&weekday))
+        && is_digits(day, 2)
+        && MONTHS.contains(&month)
+        && is_digits(year, 4)
+        && time.len() == 8
+        && clock.all(|part| is_digits(part, 2))
+}
+
+/// Where a [`Found`] timestamp was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    Header,
+    /// A parameter of the URL query.
+    Query,
+    /// A parameter of a form-encoded body.
+    Form,
+}
+
+/// A timestamp of a command, see [`find`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Found {
+    pub location: Location,
+    /// The name of the header or parameter.
+    pub name: String,
+    pub value: String,
+    pub timestamp: Timestamp,
+}
+
+/// The header of a raw `-H` argument holding a timestamp.
+fn header_timestamp(raw: &str) -> Option<(&str, &str, Timestamp)> {
+    let (name, value) = split_header(raw)?;
+    if !HEADERS
+        .iter()
+        .any(|header| header.eq_ignore_ascii_case(name))
+    {
+        return None;
+    }
+    Some((name, value, Timestamp::detect(value)?))
+}
+
+/// The parameters of a `&`-separated `name=value` list holding a time
+/// since the epoch.
+fn param_timestamps(pairs: &str) -> impl Iterator<Item = (&str, &str, Timestamp)> {
+    let params = PARAMS
+        .iter()
+        .fold(VolatileParams::none(), |params, pattern| {
+            params.with_pattern(pattern)
+        });
+    pairs.split('&').filter_map(move |pair| {
+        let (name, value) = pair.split_once('=')?;
+        let timestamp = Timestamp::detect(value).filter(|timestamp| {
+            matches!(timestamp, Timestamp::EpochSeconds | Timestamp::EpochMillis)
+        })?;
+        params.matches(name).then_some((name, value, timestamp))
+    })
+}
+
+/// The form-encoded pairs of a data argument sent as written, `None` for a
+/// body read from a file or a `--data-urlencode` one.
+fn form_pairs<'c>(stru: &'c CurlStru) -> Option<&'c str> {
+    let data = stru.data.as_deref()?;
+    match DataArg::from_stru(stru).source {
+        BodySource::Inline(content) if content == data => Some(data),
+        _ => None,
+    }
+}
+
+/// The query string of `url`, without its fragment.
+fn query(url: &str) -> Option<&str> {
+    let url = url.split_once('#').map_or(url, |(url, _)| url);
+    url.split_once('?').map(|(_, query)| query)
+}
+
+/// The timestamps of a command: the `Date`, `X-Amz-Date` and `X-Date`
+/// headers, and the query and form parameters such as `ts` or `_` holding
+/// a time since the epoch, as pages add to bust caches.
+pub fn find(curls: &[Curl]) -> Vec<Found> {
+    let form_encoded = is_form_encoded(curls);
+    let mut found = Vec::new();
+    let mut push = |location, (name, value, timestamp): (&str, &str, Timestamp)| {
+        found.push(Found {
+            location,
+            name: name.into(),
+            value: value.into(),
+            timestamp,
+        })
+    };
+    for curl in curls {
+        match curl {
+            Curl::Header(stru) => {
+                if let Some(header) = stru.data.as_deref().and_then(header_timestamp) {
+                    push(Location::Header, header);
+                }
+            }
+            Curl::URL(url) => {
+                let url = url.to_string();
+                for param in query(&url).into_iter().flat_map(param_timestamps) {
+                    push(Location::Query, param);
+                }
+            }
+            Curl::Data(stru) if form_encoded => {
+                for param in form_pairs(stru).into_iter().flat_map(param_timestamps) {
+                    push(Location::Form, param);
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// `pairs` with the value of every timestamp parameter replaced by `time`.
+fn refresh_pairs(pairs: &str, time: SystemTime) -> String {
+    let mut refreshed = pairs.to_string();
+    // From the end, so that the offsets of earlier pairs hold.
+    let stale: Vec<_> = param_timestamps(pairs).collect();
+    for (_, value, timestamp) in stale.into_iter().rev() {
+        let start = value.as_ptr() as usize - pairs.as_ptr() as usize;
+        refreshed.replace_range(start..start + value.len(), &timestamp.format(time));
+    }
+    refreshed
+}
+
+/// The command with every timestamp [`find`] reports written anew for
+/// `time`, the way it was written, e.g. to replay a command copied a while
+/// ago. Requests signed over these values, like AWS Signature Version 4
+/// ones, need signing again.
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use winnowcurl::curl::parser::curl_cmd_parse;
+/// use winnowcurl::curl::render::to_curl_string;
+/// use winnowcurl::curl::timestamps::refresh;
+///
+/// let curls = curl_cmd_parse(
+///     "curl 'https://a.com/feed?_=1600000000000' -H 'Date: Sun, 13 Sep 2020 12:26:40 GMT'",
+/// )?;
+/// let now = UNIX_EPOCH + Duration::from_secs(1_714_566_600);
+/// assert_eq!(
+///     to_curl_string(&refresh(&curls, now)),
+///     "curl 'https://a.com/feed?_=1714566600000' \\\n  -H 'Date: Wed, 01 May 2024 12:30:00 GMT'"
+/// );
+/// # Ok::<(), winnowcurl::curl::error::ParseError>(())
+/// ```
+pub fn refresh(curls: &[Curl<'_>], time: SystemTime) -> Vec<OwnedCurl> {
+    let form_encoded = is_form_encoded(curls);
+    curls
+        .iter()
+        .map(|curl| match curl {
+            Curl::Header(stru) => match stru.data.as_deref().and_then(header_timestamp) {
+                Some((name, _, timestamp)) => Curl::Header(CurlStru::new_with_data(
+                    stru.identifier.to_string(),
+                    format!("{}: {}", name, timestamp.format(time)),
+                )),
+                None => curl.clone().into_owned(),
+            },
+            Curl::URL(url) => {
+                let url = url.to_string();
+                let Some(query) = query(&url) else {
+                    return curl.clone().into_owned();
+                };
+                let start = query.as_ptr() as usize - url.as_ptr() as usize;
+                let refreshed = refresh_pairs(query, time);
+                if refreshed == query {
+                    return curl.clone().into_owned();
+                }
+                let mut url = url.clone();
+                url.replace_range(start..start + query.len(), &refreshed);
+                Curl::new_as_url_str(&url).into_owned()
+            }
+            Curl::Data(stru) if form_encoded => match form_pairs(stru) {
+                Some(pairs) => {
+                    let refreshed = refresh_pairs(pairs, time);
+                    if refreshed == pairs {
+                        return curl.clone().into_owned();
+                    }
+                    Curl::Data(CurlStru::new_with_data(
+                        stru.identifier.to_string(),
+                        refreshed,
+                    ))
+                }
+                None => curl.clone().into_owned(),
+            },
+            curl => curl.clone().into_owned(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+    use std::time::Duration;
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[rstest]
+    #[case("Wed, 01 May 2024 12:30:00 GMT", Some(Timestamp::HttpDate))]
+    #[case("20240501T123000Z", Some(Timestamp::AmzDate))]
+    #[case("1714566600", Some(Timestamp::EpochSeconds))]
+    #[case("1714566600123", Some(Timestamp::EpochMillis))]
+    #[case("0000000042", None)]
+    #[case("9999999999", None)]
+    #[case("Wed, 1 May 2024 12:30:00 GMT", None)]
+    #[case("Wed, 01 May 2024 12:30:00 UTC", None)]
+    #[case("2024-05-01T12:30:00Z", None)]
+    #[case("", None)]
+    fn test_detect(#[case] value: &str, #[case] expected: Option<Timestamp>) {
+        assert_eq!(Timestamp::detect(value), expected);
+    }
+
+    #[rstest]
+    #[case(Timestamp::HttpDate, 1_714_566_600, "Wed, 01 May 2024 12:30:00 GMT")]
+    #[case(Timestamp::HttpDate, 951_782_400, "Tue, 29 Feb 2000 00:00:00 GMT")]
+    #[case(Timestamp::HttpDate, 0, "Thu, 01 Jan 1970 00:00:00 GMT")]
+    #[case(Timestamp::AmzDate, 1_735_689_599, "20241231T235959Z")]
+    #[case(Timestamp::EpochSeconds, 1_714_566_600, "1714566600")]
+    #[case(Timestamp::EpochMillis, 1_714_566_600, "1714566600000")]
+    fn test_format(#[case] timestamp: Timestamp, #[case] seconds: u64, #[case] expected: &str) {
+        let formatted = timestamp.format(at(seconds));
+        assert_eq!(formatted, expected);
+        assert_eq!(Timestamp::detect(&formatted), Some(timestamp));
+    }
+
+    #[rstest]
+    fn test_find() {
+        let curls = curl_cmd_parse(concat!(
+            "curl 'https://a.com/?id=1234567890&ts=1600000000#x' -H 'x-amz-date: 20200913T122640Z' ",
+            "-H 'Date: soon' -d 'q=1&time=1600000000000&t=nope'"
+        ))
+        .unwrap();
+        let found: Vec<_> = find(&curls)
+            .into_iter()
+            .map(|found| (found.location, found.name, found.timestamp))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (Location::Query, "ts".into(), Timestamp::EpochSeconds),
+                (Location::Header, "x-amz-date".into(), Timestamp::AmzDate),
+                (Location::Form, "time".into(), Timestamp::EpochMillis),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "curl 'https://a.com/?a=1&_=1600000000000&t=1600000000#ts=1600000000'",
+        "curl 'https://a.com/?a=1&_=1714566600000&t=1714566600#ts=1600000000'"
+    )]
+    #[case(
+        "curl 'https://a.com' -H 'X-Amz-Date: 20200913T122640Z' -H 'Accept: */*'",
+        "curl 'https://a.com' \\\n  -H 'X-Amz-Date: 20240501T123000Z' \\\n  -H 'Accept: */*'"
+    )]
+    #[case(
+        "curl 'https://a.com' -d 'ts=1600000000' -d @ts.txt --data-urlencode 'ts=1600000000'",
+        "curl 'https://a.com' \\\n  -d 'ts=1714566600' \\\n  -d '@ts.txt' \\\n  --data-urlencode 'ts=1600000000'"
+    )]
+    #[case(
+        "curl 'https://a.com' -H 'Content-Type: application/json' -d '{\"ts\":1600000000}'",
+        "curl 'https://a.com' \\\n  -H 'Content-Type: application/json' \\\n  -d '{\"ts\":1600000000}'"
+    )]
+    #[case(
+        "curl 'https://a.com/?id=1600000000'",
+        "curl 'https://a.com/?id=1600000000'"
+    )]
+    fn test_refresh(#[case] input: &str, #[case] expected: &str) {
+        let curls = curl_cmd_parse(input).unwrap();
+        assert_eq!(
+            to_curl_string(&refresh(&curls, at(1_714_566_600))),
+            expected
+        );
+    }
+}
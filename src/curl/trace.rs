@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+
+use super::ast::Curl;
+use super::options;
+
+/// How `--trace` and `--trace-ascii` dump the traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceFormat {
+    /// `--trace`: hex and ASCII side by side.
+    Hex,
+    /// `--trace-ascii`: ASCII only.
+    Ascii,
+}
+
+/// Where the full trace of the transfer is written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraceFile {
+    pub format: TraceFormat,
+    /// `-` stands for stdout, `%` for stderr.
+    pub path: PathBuf,
+}
+
+/// The observability options of a command: `-v`, `--trace`,
+/// `--trace-ascii`, `--trace-time`, `--trace-ids` and `--trace-config`.
+///
+/// As in curl, `-v`, `--trace` and `--trace-ascii` override each other and
+/// the last one given wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceSettings {
+    pub verbose: bool,
+    pub trace: Option<TraceFile>,
+    /// Prefix every trace line with a timestamp.
+    pub trace_time: bool,
+    /// Prefix every trace line with transfer and connection ids.
+    pub trace_ids: bool,
+    /// Components named by `--trace-config`, e.g. `http/2` or `-tls`.
+    pub trace_config: Vec<String>,
+}
+
+impl TraceSettings {
+    pub fn new(curls: &[Curl]) -> Self {
+        let mut settings = Self::default();
+
+        for curl in curls {
+            match curl {
+                Curl::Flag(flag) => match &*flag.identifier {
+                    "--trace-time" => settings.trace_time = true,
+                    "--trace-ids" => settings.trace_ids = true,
+                    identifier if options::flag_matches(identifier, "--verbose") => {
+                        settings.set_verbose()
+                    }
+                    _ => {}
+                },
+                Curl::Option { name, value } => match &**name {
+                    "--trace" => settings.set_trace(TraceFormat::Hex, value),
+                    "--trace-ascii" => settings.set_trace(TraceFormat::Ascii, value),
+                    "--trace-config" => settings.trace_config.extend(
+                        value
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|item| !item.is_empty())
+                            .map(String::from),
+                    ),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    fn set_verbose(&mut self) {
+        self.verbose = true;
+        self.trace = None;
+    }
+
+    fn set_trace(&mut self, format: TraceFormat, path: &str) {
+        self.verbose = false;
+        self.trace = Some(TraceFile {
+            format,
+            path: path.into(),
+        });
+    }
+
+    /// Whether curl would report anything about the transfer itself.
+    pub fn is_enabled(&self) -> bool {
+        self.verbose || self.trace.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    fn test_trace_settings() {
+        let curls = curl_cmd_parse(
+            "curl 'https://a.com' --trace-ascii dump.txt --trace-time --trace-config 'http/2, -tls'",
+        )
+        .unwrap();
+        assert_eq!(
+            TraceSettings::new(&curls),
+            TraceSettings {
+                verbose: false,
+                trace: Some(TraceFile {
+                    format: TraceFormat::Ascii,
+                    path: "dump.txt".into(),
+                }),
+                trace_time: true,
+                trace_ids: false,
+                trace_config: vec!["http/2".into(), "-tls".into()],
+            }
+        );
+    }
+
+    #[rstest]
+    #[case("curl 'https://a.com' -sSv", true, None)]
+    #[case("curl 'https://a.com' -sSL", false, None)]
+    #[case("curl 'https://a.com' -v --trace t.bin", false, Some(TraceFormat::Hex))]
+    #[case("curl 'https://a.com' --trace t.bin --verbose", true, None)]
+    fn test_trace_settings_last_wins(
+        #[case] input: &str,
+        #[case] verbose: bool,
+        #[case] trace: Option<TraceFormat>,
+    ) {
+        let settings = TraceSettings::new(&curl_cmd_parse(input).unwrap());
+        assert_eq!(settings.verbose, verbose);
+        assert_eq!(settings.trace.map(|trace| trace.format), trace);
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use super::ast::Curl;
+use super::options;
+
+/// The `--speed-limit`/`--speed-time` abort condition: a transfer slower
+/// than `bytes_per_second` for `time` is given up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpeedLimit {
+    pub bytes_per_second: u64,
+    pub time: Duration,
+}
+
+impl SpeedLimit {
+    /// curl's `--speed-time` when only `--speed-limit` is given.
+    pub const DEFAULT_TIME: Duration = Duration::from_secs(30);
+    /// curl's `--speed-limit` when only `--speed-time` is given.
+    pub const DEFAULT_BYTES_PER_SECOND: u64 = 1;
+}
+
+/// How the connection is kept healthy while transferring: `--speed-limit`,
+/// `--speed-time`, `--keepalive-time` and `--no-keepalive`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransferSettings {
+    pub speed_limit: Option<SpeedLimit>,
+    /// Idle time before TCP keepalive probes are sent.
+    pub keepalive_time: Option<Duration>,
+    /// `--no-keepalive`: no keepalive probes at all.
+    pub no_keepalive: bool,
+}
+
+impl TransferSettings {
+    pub fn new(curls: &[Curl]) -> Self {
+        let mut bytes_per_second = None;
+        let mut speed_time = None;
+        let mut keepalive_time = None;
+
+        // Values were checked against their types while parsing.
+        for curl in curls {
+            if let Curl::Option { name, value } = curl {
+                match &**name {
+                    "--speed-limit" => bytes_per_second = value.parse::<u64>().ok(),
+                    "--speed-time" => speed_time = options::parse_seconds(value),
+                    "--keepalive-time" => {
+                        keepalive_time = value.parse().ok().map(Duration::from_secs)
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let speed_limit =
+            (bytes_per_second.is_some() || speed_time.is_some()).then(|| SpeedLimit {
+                bytes_per_second: bytes_per_second.unwrap_or(SpeedLimit::DEFAULT_BYTES_PER_SECOND),
+                time: speed_time.unwrap_or(SpeedLimit::DEFAULT_TIME),
+            });
+        Self {
+            speed_limit: speed_limit.filter(|limit| limit.bytes_per_second > 0),
+            keepalive_time,
+            no_keepalive: options::has_flag(curls, "--no-keepalive"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case("-Y 1000 -y 5", Some((1000, 5)))]
+    #[case("--speed-limit 1000", Some((1000, 30)))]
+    #[case("--speed-time 10", Some((1, 10)))]
+    #[case("--speed-limit 0", None)]
+    #[case("-v", None)]
+    fn test_speed_limit(#[case] options: &str, #[case] expected: Option<(u64, u64)>) {
+        let input = format!("curl 'https://a.com' {}", options);
+        let settings = TransferSettings::new(&curl_cmd_parse(&input).unwrap());
+        let expected = expected.map(|(bytes_per_second, secs)| SpeedLimit {
+            bytes_per_second,
+            time: Duration::from_secs(secs),
+        });
+        assert_eq!(settings.speed_limit, expected);
+    }
+
+    #[rstest]
+    fn test_keepalive() {
+        let curls =
+            curl_cmd_parse("curl 'https://a.com' --keepalive-time 20 --no-keepalive").unwrap();
+        let settings = TransferSettings::new(&curls);
+        assert_eq!(settings.keepalive_time, Some(Duration::from_secs(20)));
+        assert!(settings.no_keepalive);
+    }
+}
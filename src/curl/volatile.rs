@@ -0,0 +1,109 @@
+use super::ast::{Curl, CurlStru, OwnedCurl};
+use super::body::{BodySource, DataArg};
+use super::escape::is_form_encoded;
+use crate::url::normalize::{VolatileAction, VolatileParams};
+
+/// The data argument `stru` with its volatile parameters rewritten, `None`
+/// when nothing is left of it.
+fn rewrite_data(
+    stru: &CurlStru,
+    volatile: &VolatileParams,
+    action: VolatileAction,
+    form_encoded: bool,
+) -> Option<OwnedCurl> {
+    let arg = DataArg::from_stru(stru);
+    let BodySource::Inline(content) = &arg.source else {
+        return Some(Curl::Data(stru.clone().into_owned()));
+    };
+    let value = if let Some(name) = arg.name {
+        // `--data-urlencode name=content` is a single parameter.
+        if !volatile.matches(&name) {
+            return Some(Curl::Data(stru.clone().into_owned()));
+        }
+        match action {
+            VolatileAction::Remove => return None,
+            VolatileAction::Templatize => {
+                format!("{}={}", name, VolatileAction::placeholder(&name))
+            }
+        }
+    } else if let Ok(json) = serde_json::from_str::<serde_json::Value>(content) {
+        let mut rewritten = json.clone();
+        volatile.rewrite_json(&mut rewritten, action);
+        if rewritten == json {
+            return Some(Curl::Data(stru.clone().into_owned()));
+        }
+        rewritten.to_string()
+    } else if form_encoded {
+        volatile.rewrite_pairs(content, action)
+    } else {
+        return Some(Curl::Data(stru.clone().into_owned()));
+    };
+    (!value.is_empty())
+        .then(|| Curl::Data(CurlStru::new_with_data(stru.identifier.to_string(), value)))
+}
+
+/// Rewrite the volatile parameters of the URL query and of the body of a
+/// command: form-encoded pairs, members of JSON objects and
+/// `--data-urlencode` names. Bodies read from files are left alone.
+pub fn rewrite(
+    curls: &[Curl<'_>],
+    volatile: &VolatileParams,
+    action: VolatileAction,
+) -> Vec<OwnedCurl> {
+    let form_encoded = is_form_encoded(curls);
+    curls
+        .iter()
+        .filter_map(|curl| match curl {
+            Curl::URL(url) => {
+                let url = url.to_string();
+                let rewritten = volatile.rewrite_url(&url, action);
+                if rewritten == url {
+                    return Some(curl.clone().into_owned());
+                }
+                Some(Curl::new_as_url_str(&rewritten).into_owned())
+            }
+            Curl::Data(stru) => rewrite_data(stru, volatile, action, form_encoded),
+            curl => Some(curl.clone().into_owned()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use crate::curl::render::to_curl_string;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        "curl 'https://a.com/?q=1&_=17' -d 'a=1&ts=2'",
+        VolatileAction::Remove,
+        "curl 'https://a.com/?q=1' \\\n  -d 'a=1'"
+    )]
+    #[case(
+        "curl 'https://a.com/?_=17' -d 'ts=2' --data-urlencode 'nonce=x y'",
+        VolatileAction::Remove,
+        "curl 'https://a.com/'"
+    )]
+    #[case(
+        "curl 'https://a.com/?_=17' --data-urlencode 'nonce=x y' --data-urlencode 'q=z'",
+        VolatileAction::Templatize,
+        "curl 'https://a.com/?_={{_}}' \\\n  --data-urlencode 'nonce={{nonce}}' \\\n  --data-urlencode 'q=z'"
+    )]
+    #[case(
+        r#"curl 'https://a.com' -H 'Content-Type: application/json' -d '{"t":1,"q":2}'"#,
+        VolatileAction::Templatize,
+        "curl 'https://a.com' \\\n  -H 'Content-Type: application/json' \\\n  -d '{\"t\":\"{{t}}\",\"q\":2}'"
+    )]
+    #[case(
+        "curl 'https://a.com' -H 'Content-Type: text/plain' -d 'ts=1' -d @ts.txt",
+        VolatileAction::Remove,
+        "curl 'https://a.com' \\\n  -H 'Content-Type: text/plain' \\\n  -d 'ts=1' \\\n  -d '@ts.txt'"
+    )]
+    fn test_rewrite(#[case] input: &str, #[case] action: VolatileAction, #[case] expected: &str) {
+        let curls = curl_cmd_parse(input).unwrap();
+        let rewritten = rewrite(&curls, &VolatileParams::default(), action);
+        assert_eq!(to_curl_string(&rewritten), expected);
+    }
+}
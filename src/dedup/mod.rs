@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+
+use crate::curl::parser::Curl;
+use crate::curl::volatile;
+use crate::diagnostic::Diagnostic;
+use crate::request::Request;
+use crate::url::normalize::{VolatileAction, VolatileParams, normalize_url};
+
+/// What makes two requests the same: the method, the normalized URL and
+/// what is sent, volatile parameters removed. Headers are left out, as recorded requests differ in
+/// cookies and tracing headers without asking for anything else.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestKey {
+    pub method: String,
+    pub url: String,
+    pub body: Option<String>,
+    /// The `-F` fields, as written.
+    pub form: Vec<String>,
+}
+
+impl RequestKey {
+    pub fn new(curls: &[Curl], volatile: &VolatileParams) -> Result<Self, Diagnostic> {
+        let curls = volatile::rewrite(curls, volatile, VolatileAction::Remove);
+        let request = Request::try_from(curls.as_slice())?;
+        let form = curls
+            .iter()
+            .filter_map(|curl| match curl {
+                Curl::Form(field) => Some(field.to_string()),
+                _ => None,
+            })
+            .collect();
+        Ok(RequestKey {
+            method: request.method.as_str().to_ascii_uppercase(),
+            url: normalize_url(&request.url, volatile),
+            body: request.body,
+            form,
+        })
+    }
+}
+
+/// Keep the first of every group of commands making the same request,
+/// in their original order. Commands curl refuses to send are all kept.
+pub fn dedup<'a, C: AsRef<[Curl<'a>]>>(commands: Vec<C>, volatile: &VolatileParams) -> Vec<C> {
+    let mut seen = HashSet::new();
+    commands
+        .into_iter()
+        .filter(|curls| match RequestKey::new(curls.as_ref(), volatile) {
+            Ok(key) => seen.insert(key),
+            Err(_) => true,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    fn key(input: &str) -> RequestKey {
+        RequestKey::new(&curl_cmd_parse(input).unwrap(), &VolatileParams::default()).unwrap()
+    }
+
+    #[rstest]
+    #[case(
+        "curl 'https://a.com/x?b=2&a=1'",
+        "curl 'HTTPS://A.com:443/x?a=1&b=2&_=17'"
+    )]
+    #[case(
+        "curl 'https://a.com' -H 'Cookie: a=1'",
+        "curl 'https://a.com' -H 'Cookie: a=2'"
+    )]
+    #[case(
+        "curl 'https://a.com' -d 'a=1'",
+        "curl -X post 'https://a.com' -d 'a=1'"
+    )]
+    #[case(
+        r#"curl 'https://a.com' -d '{"q":1,"nonce":"x"}'"#,
+        r#"curl 'https://a.com' -d '{"q":1,"nonce":"y"}'"#
+    )]
+    fn test_same_request(#[case] a: &str, #[case] b: &str) {
+        assert_eq!(key(a), key(b));
+    }
+
+    #[rstest]
+    #[case("curl 'https://a.com/x?a=1'", "curl 'https://a.com/x?a=2'")]
+    #[case("curl 'https://a.com/X'", "curl 'https://a.com/x'")]
+    #[case("curl 'https://a.com' -d 'a=1'", "curl 'https://a.com' -d 'a=2'")]
+    #[case("curl 'https://a.com' -X PUT", "curl 'https://a.com'")]
+    #[case("curl 'https://a.com' -G -d 'a=1'", "curl 'https://a.com' -d 'a=1'")]
+    #[case("curl 'https://a.com' -I", "curl 'https://a.com'")]
+    #[case(
+        "curl 'https://a.com' -F 'f=@a.png'",
+        "curl 'https://a.com' -F 'f=@b.png'"
+    )]
+    fn test_different_request(#[case] a: &str, #[case] b: &str) {
+        assert_ne!(key(a), key(b));
+    }
+
+    #[rstest]
+    fn test_dedup() {
+        let commands: Vec<Vec<Curl>> = [
+            "curl 'https://a.com/?page=1&_=100'",
+            "curl 'https://a.com/?page=2'",
+            "curl 'https://a.com/?_=200&page=1'",
+            "curl 'https://a.com/?page=1&cb=x'",
+        ]
+        .into_iter()
+        .map(|input| curl_cmd_parse(input).unwrap())
+        .collect();
+
+        let unique = dedup(commands.clone(), &VolatileParams::default());
+        assert_eq!(unique, vec![commands[0].clone(), commands[1].clone()]);
+
+        let unique = dedup(commands.clone(), &VolatileParams::none());
+        assert_eq!(unique, commands);
+    }
+}
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::Code;
+
+const BASELINE_VERSION: u32 = 1;
+
+/// Stable fingerprint of a command, insensitive to line wrapping so that a
+/// reformatted runbook does not invalidate its baseline.
+pub fn fingerprint(command: &str) -> String {
+    let normalized = command
+        .replace("\\\r\n", " ")
+        .replace("\\\n", " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // FNV-1a, chosen because it is stable across Rust releases unlike `DefaultHasher`.
+    let hash = normalized
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    format!("{:016x}", hash)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct BaselineEntry {
+    code: Code,
+    fingerprint: String,
+    count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    findings: Vec<BaselineEntry>,
+}
+
+/// Findings accepted when a lint was adopted; only findings beyond these fail.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    entries: BTreeMap<(Code, String), usize>,
+}
+
+impl Baseline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a finding for the command with the given fingerprint.
+    pub fn record(&mut self, code: Code, fingerprint: &str) {
+        *self.entries.entry((code, fingerprint.into())).or_default() += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Keep only the findings not covered by the baseline.
+    ///
+    /// Every recorded finding absorbs one matching occurrence, so a command
+    /// gaining a second duplicate header is still reported.
+    pub fn new_findings<T>(
+        &self,
+        findings: impl IntoIterator<Item = T>,
+        key: impl Fn(&T) -> (Code, String),
+    ) -> Vec<T> {
+        let mut remaining = self.entries.clone();
+        findings
+            .into_iter()
+            .filter(|finding| match remaining.get_mut(&key(finding)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        let file = BaselineFile {
+            version: BASELINE_VERSION,
+            findings: self
+                .entries
+                .iter()
+                .map(|((code, fingerprint), count)| BaselineEntry {
+                    code: *code,
+                    fingerprint: fingerprint.clone(),
+                    count: *count,
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&file).expect("baseline serialization cannot fail")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let file: BaselineFile =
+            serde_json::from_str(json).map_err(|e| format!("invalid baseline: {}", e))?;
+        if file.version != BASELINE_VERSION {
+            return Err(format!(
+                "unsupported baseline version {} (expected {})",
+                file.version, BASELINE_VERSION
+            ));
+        }
+
+        let mut baseline = Self::new();
+        for entry in file.findings {
+            *baseline
+                .entries
+                .entry((entry.code, entry.fingerprint))
+                .or_default() += entry.count;
+        }
+        Ok(baseline)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read baseline {}: {}", path.display(), e))?;
+        Self::from_json(&json)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_json() + "\n")
+            .map_err(|e| format!("cannot write baseline {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_fingerprint_ignores_wrapping() {
+        assert_eq!(
+            fingerprint("curl 'https://a.com' \\\n    -k   -v"),
+            fingerprint("curl 'https://a.com' -k -v")
+        );
+        assert_ne!(
+            fingerprint("curl 'https://a.com' -k"),
+            fingerprint("curl 'https://b.com' -k")
+        );
+    }
+
+    #[rstest]
+    fn test_new_findings() {
+        let mut baseline = Baseline::new();
+        baseline.record(Code::Insecure, "aaa");
+        baseline.record(Code::DuplicateHeader, "bbb");
+
+        let findings = vec![
+            (Code::Insecure, "aaa"),
+            (Code::DuplicateHeader, "bbb"),
+            (Code::DuplicateHeader, "bbb"),
+            (Code::PlainHttp, "aaa"),
+        ];
+        let new = baseline.new_findings(findings, |(code, fp)| (*code, fp.to_string()));
+        assert_eq!(
+            new,
+            vec![(Code::DuplicateHeader, "bbb"), (Code::PlainHttp, "aaa")]
+        );
+    }
+
+    #[rstest]
+    fn test_json_round_trip() {
+        let mut baseline = Baseline::new();
+        baseline.record(Code::PlainHttp, "ccc");
+        baseline.record(Code::Insecure, "aaa");
+        baseline.record(Code::Insecure, "aaa");
+
+        let json = baseline.to_json();
+        assert!(json.contains(r#""code": "WCL010""#));
+        assert_eq!(Baseline::from_json(&json).unwrap(), baseline);
+        assert_eq!(baseline.len(), 3);
+    }
+
+    #[rstest]
+    #[case(r#"{"version": 2, "findings": []}"#)]
+    #[case(r#"{"version": 1, "findings": [{"code": "NOPE", "fingerprint": "a", "count": 1}]}"#)]
+    fn test_from_json_invalid(#[case] input: String) {
+        assert!(Baseline::from_json(&input).is_err());
+    }
+}
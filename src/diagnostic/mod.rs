@@ -0,0 +1,398 @@
+pub mod baseline;
+pub mod suppress;
+
+use serde::de::{Deserialize, Deserializer, Error as _};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+impl Serialize for Severity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Every diagnostic winnowcurl can emit, identified by a code that never changes
+/// once released.
+///
+/// * `WCE` - the command could not be parsed
+/// * `WCC` - the command could not be converted faithfully
+/// * `WCL` - lint findings about the command itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Code {
+    UnterminatedQuote,
+    NotCurl,
+    MissingUrl,
+    UnexpectedToken,
+    InvalidOptionValue,
+    UnreadableFile,
+    ConflictingMethods,
+    OptionNotConverted,
+    CrossOrigin,
+    ShellExpansion,
+    PasswordPrompt,
+    DuplicateHeader,
+    UrlCredentials,
+    UserCredentials,
+    ShadowedOption,
+    CrossHostCredentials,
+    Insecure,
+    PlainHttp,
+    UnencodedCharacter,
+    MissingContentType,
+    InvalidJsonBody,
+    LongCookie,
+    ForeignCookies,
+    UnexpectedBody,
+    InvalidToken,
+    MissingHost,
+    InvalidTe,
+}
+
+/// All codes, in catalog order.
+pub const CATALOG: &[Code] = &[
+    Code::UnterminatedQuote,
+    Code::NotCurl,
+    Code::MissingUrl,
+    Code::UnexpectedToken,
+    Code::InvalidOptionValue,
+    Code::UnreadableFile,
+    Code::ConflictingMethods,
+    Code::OptionNotConverted,
+    Code::CrossOrigin,
+    Code::ShellExpansion,
+    Code::PasswordPrompt,
+    Code::DuplicateHeader,
+    Code::UrlCredentials,
+    Code::UserCredentials,
+    Code::ShadowedOption,
+    Code::CrossHostCredentials,
+    Code::Insecure,
+    Code::PlainHttp,
+    Code::UnencodedCharacter,
+    Code::MissingContentType,
+    Code::InvalidJsonBody,
+    Code::LongCookie,
+    Code::ForeignCookies,
+    Code::UnexpectedBody,
+    Code::InvalidToken,
+    Code::MissingHost,
+    Code::InvalidTe,
+];
+
+impl Code {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Code::UnterminatedQuote => "WCE001",
+            Code::NotCurl => "WCE002",
+            Code::MissingUrl => "WCE003",
+            Code::UnexpectedToken => "WCE004",
+            Code::InvalidOptionValue => "WCE005",
+            Code::UnreadableFile => "WCE006",
+            Code::ConflictingMethods => "WCE007",
+            Code::OptionNotConverted => "WCC001",
+            Code::CrossOrigin => "WCC002",
+            Code::ShellExpansion => "WCC003",
+            Code::PasswordPrompt => "WCC004",
+            Code::DuplicateHeader => "WCL001",
+            Code::UrlCredentials => "WCL002",
+            Code::UserCredentials => "WCL003",
+            Code::ShadowedOption => "WCL004",
+            Code::CrossHostCredentials => "WCL005",
+            Code::Insecure => "WCL010",
+            Code::PlainHttp => "WCL011",
+            Code::UnencodedCharacter => "WCL012",
+            Code::MissingContentType => "WCL020",
+            Code::InvalidJsonBody => "WCL021",
+            Code::LongCookie => "WCL030",
+            Code::ForeignCookies => "WCL031",
+            Code::UnexpectedBody => "WCL040",
+            Code::InvalidToken => "WCL041",
+            Code::MissingHost => "WCL042",
+            Code::InvalidTe => "WCL043",
+        }
+    }
+
+    /// Short kebab-case name, usable wherever the code is.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Code::UnterminatedQuote => "unterminated-quote",
+            Code::NotCurl => "not-curl",
+            Code::MissingUrl => "missing-url",
+            Code::UnexpectedToken => "unexpected-token",
+            Code::InvalidOptionValue => "invalid-option-value",
+            Code::UnreadableFile => "unreadable-file",
+            Code::ConflictingMethods => "conflicting-methods",
+            Code::OptionNotConverted => "option-not-converted",
+            Code::CrossOrigin => "cross-origin",
+            Code::ShellExpansion => "shell-expansion",
+            Code::PasswordPrompt => "password-prompt",
+            Code::DuplicateHeader => "duplicate-header",
+            Code::UrlCredentials => "url-credentials",
+            Code::UserCredentials => "user-credentials",
+            Code::ShadowedOption => "shadowed-option",
+            Code::CrossHostCredentials => "cross-host-credentials",
+            Code::Insecure => "insecure",
+            Code::PlainHttp => "plain-http",
+            Code::UnencodedCharacter => "unencoded-character",
+            Code::MissingContentType => "missing-content-type",
+            Code::InvalidJsonBody => "invalid-json-body",
+            Code::LongCookie => "long-cookie",
+            Code::ForeignCookies => "foreign-cookies",
+            Code::UnexpectedBody => "unexpected-body",
+            Code::InvalidToken => "invalid-token",
+            Code::MissingHost => "missing-host",
+            Code::InvalidTe => "invalid-te",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Code::UnterminatedQuote
+            | Code::NotCurl
+            | Code::MissingUrl
+            | Code::UnexpectedToken
+            | Code::InvalidOptionValue
+            | Code::UnreadableFile
+            | Code::ConflictingMethods => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+
+    /// One paragraph explaining what triggers the code and how to address it.
+    pub fn explanation(&self) -> &'static str {
+        match self {
+            Code::UnterminatedQuote => {
+                "A single or double quote was opened but never closed, so the rest of the command is swallowed into one argument. Close the quote or escape it."
+            }
+            Code::NotCurl => {
+                "The input does not start with the `curl` command. Only curl invocations can be parsed."
+            }
+            Code::MissingUrl => {
+                "No target URL could be found after `curl`. The URL has to be the first argument."
+            }
+            Code::UnexpectedToken => {
+                "An argument could not be recognized as any known curl option or value."
+            }
+            Code::InvalidOptionValue => {
+                "An option expects a number, e.g. `--max-time 30` or `--retry 3`, but was given something else. curl would refuse to run the command."
+            }
+            Code::UnreadableFile => {
+                "A file the command reads its body from, e.g. with `-d @payload.json`, could not be read while resolving files. Check the path, which is taken relative to the current directory."
+            }
+            Code::ConflictingMethods => {
+                "The command asks for two request methods curl cannot combine, e.g. a body with `-d` and `-I`/`--head`, or `-d` and `-F`. curl refuses to run it; keep only one of them."
+            }
+            Code::OptionNotConverted => {
+                "The conversion target has no equivalent for a curl option, so the generated code behaves differently from the command."
+            }
+            Code::CrossOrigin => {
+                "The command was copied from a browser making a cross-origin request: its `Origin` is on another origin than the URL, as `Sec-Fetch-Site` or the `Referer` of a `Sec-Fetch-Mode: cors` request otherwise tell, or it is the CORS preflight (`Access-Control-Request-Method`) of the request rather than the request itself. Server-side code is not subject to CORS, so the `Origin` and `Sec-Fetch-*` headers only matter if the server checks them; code run in a browser needs the server to allow its origin."
+            }
+            Code::ShellExpansion => {
+                "The command is written for a shell which mangles part of it whatever the quoting: cmd expands `%` as a variable in batch files and `!` with delayed expansion, cannot write control characters other than line breaks and tabs, and reads at most 8191 characters; PowerShell before 7.3 strips the `\"` of the arguments of `curl.exe` and drops empty ones; and no shell passes a NUL byte. Bash history expansion, backticks and `$` are escaped by the quoting of `winnowcurl format --shell`."
+            }
+            Code::PasswordPrompt => {
+                "`-u` gives a user without a password, which curl prompts for before sending the request. The generated code has no password to send: Python asks for it with `getpass`, other targets send an empty one until it is filled in."
+            }
+            Code::DuplicateHeader => {
+                "The same header is given more than once. Most servers only honour one of the values."
+            }
+            Code::UrlCredentials => {
+                "The URL embeds a username and password, which ends up in shell history and logs. Prefer `-u` with a prompt or a netrc file."
+            }
+            Code::UserCredentials => {
+                "`-u` carries a password inline. Omit the password to be prompted, or read it from the environment."
+            }
+            Code::ShadowedOption => {
+                "An option standing for a header, `-A`/`--user-agent`, `-e`/`--referer` or `-b`/`--cookie` with cookies, is given along with that header through `-H`. curl sends the `-H` header and ignores the option; drop one of them."
+            }
+            Code::CrossHostCredentials => {
+                "Credentials, an `Authorization` or `Cookie` header or `-u`, are sent to another host than the one of the `Referer`. Commands copied from a browser often carry the session of a page along to a third-party host; check that the credentials are meant for it. Hosts are compared in their IDNA ASCII form, so `bücher.example` and `xn--bcher-kva.example` are the same host."
+            }
+            Code::Insecure => {
+                "`-k`/`--insecure` disables TLS certificate verification and makes the request vulnerable to interception."
+            }
+            Code::PlainHttp => {
+                "The request is sent over plain `http://`, so it can be read and altered in transit. Use `https://`."
+            }
+            Code::UnencodedCharacter => {
+                "The URL or a form-encoded body holds a space, a control character or another character that must be percent-encoded. curl rejects or sends such URLs unchanged, and reads `{}` and `[]` as glob patterns unless `-g` is given. `winnowcurl format --fix` encodes them."
+            }
+            Code::MissingContentType => {
+                "A body is sent without a `Content-Type` header; curl defaults to `application/x-www-form-urlencoded`, which is rarely what JSON payloads want. `--json` sends the body along with the JSON `Content-Type` and `Accept` headers."
+            }
+            Code::InvalidJsonBody => {
+                "The body is sent as JSON, with `--json` or a JSON `Content-Type`, but is not valid JSON, so the server rejects it. Check the quoting: the shell may have eaten quotes or expanded `$` inside double quotes."
+            }
+            Code::LongCookie => {
+                "The Cookie header is unusually long, typically a full browser session copied along with the request."
+            }
+            Code::ForeignCookies => {
+                "The cookies were captured for another host than the one of the URL, typically after the URL of a command copied from a browser was edited, e.g. from production to staging. The server does not know the session and answers 401 or 403. The host is told by a `Host` header, or by `Sec-Fetch-Site: same-origin` along with the `Origin` or `Referer`; log in to the new host and copy its cookies."
+            }
+            Code::UnexpectedBody => {
+                "A GET, HEAD or DELETE request carries a body, e.g. `-X GET` along with `-d`. RFC 9110 gives such a body no meaning, and servers and proxies may ignore it or reject the request. Send the data in the query string with `-G`, or use POST. Only reported with the `rfc9110` feature."
+            }
+            Code::InvalidToken => {
+                "The method or a header name is not an RFC 9110 token, or a header value holds a line break or a NUL byte, so the request is malformed. Only reported with the `rfc9110` feature."
+            }
+            Code::MissingHost => {
+                "The request has no `Host`, because the URL has no host or `-H 'Host:'` removes the header. RFC 9110 requires it in every HTTP/1.1 request. Only reported with the `rfc9110` feature."
+            }
+            Code::InvalidTe => {
+                "A `TE` header lists `chunked`, which a client must never send, or is sent without `TE` in the `Connection` header, which RFC 9110 requires as it only applies to the immediate connection. `--tr-encoding` lets curl send both. Only reported with the `rfc9110` feature."
+            }
+        }
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Accepts either the stable identifier (`WCL010`) or the name (`insecure`).
+impl FromStr for Code {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CATALOG
+            .iter()
+            .find(|code| code.as_str().eq_ignore_ascii_case(s) || code.name() == s)
+            .copied()
+            .ok_or_else(|| format!("unknown diagnostic code: {}", s))
+    }
+}
+
+impl Serialize for Code {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Code {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(D::Error::custom)
+    }
+}
+
+// Errors must convert into `Box<dyn Error + Send + Sync>` and cross await points.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync + 'static>() {}
+    assert_send_sync::<Diagnostic>();
+    assert_send_sync::<Code>();
+    assert_send_sync::<Severity>();
+    assert_send_sync::<baseline::Baseline>();
+    assert_send_sync::<suppress::Suppressions>();
+};
+
+/// A coded message about a curl command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub code: Code,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(code: Code, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.code.severity()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}",
+            self.severity().as_str(),
+            self.code,
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+impl Serialize for Diagnostic {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Diagnostic", 4)?;
+        state.serialize_field("code", &self.code)?;
+        state.serialize_field("name", self.code.name())?;
+        state.serialize_field("severity", self.severity().as_str())?;
+        state.serialize_field("message", &self.message)?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_codes_are_unique() {
+        use std::collections::HashSet;
+
+        let ids: HashSet<_> = CATALOG.iter().map(Code::as_str).collect();
+        let names: HashSet<_> = CATALOG.iter().map(Code::name).collect();
+        assert_eq!(ids.len(), CATALOG.len());
+        assert_eq!(names.len(), CATALOG.len());
+    }
+
+    #[rstest]
+    #[case("WCL010", Code::Insecure)]
+    #[case("wcl010", Code::Insecure)]
+    #[case("insecure", Code::Insecure)]
+    #[case("WCE001", Code::UnterminatedQuote)]
+    fn test_code_from_str(#[case] input: String, #[case] expected: Code) {
+        assert_eq!(input.parse::<Code>().unwrap(), expected)
+    }
+
+    #[rstest]
+    fn test_code_from_str_unknown() {
+        assert!("WCX999".parse::<Code>().is_err());
+    }
+
+    #[rstest]
+    fn test_diagnostic_output() {
+        let diagnostic = Diagnostic::new(Code::NotCurl, "Input does not start with curl");
+        assert_eq!(
+            diagnostic.to_string(),
+            "error[WCE002]: Input does not start with curl"
+        );
+        assert_eq!(
+            serde_json::to_value(&diagnostic).unwrap(),
+            serde_json::json!({
+                "code": "WCE002",
+                "name": "not-curl",
+                "severity": "error",
+                "message": "Input does not start with curl"
+            })
+        );
+    }
+}
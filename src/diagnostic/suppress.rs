@@ -0,0 +1,177 @@
+use std::collections::{HashMap, HashSet};
+
+use super::Code;
+
+/// Comment directive silencing codes for the next command (or the command on
+/// the same line when used as a trailing comment).
+pub const IGNORE_DIRECTIVE: &str = "winnowcurl-ignore:";
+/// Comment directive silencing codes for the whole file.
+pub const IGNORE_FILE_DIRECTIVE: &str = "winnowcurl-ignore-file:";
+
+/// Codes the user accepted, either globally (`--allow`) or through
+/// `# winnowcurl-ignore: WCL010` comments in a script.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Suppressions {
+    allowed: HashSet<Code>,
+    lines: HashMap<usize, HashSet<Code>>,
+}
+
+/// Parse the code list after a directive, e.g. `WCL010, plain-http`.
+fn parse_codes(list: &str) -> Result<Vec<Code>, String> {
+    list.split([',', ' ', '\t'])
+        .filter(|code| !code.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// The text after the `#` starting the shell comment of a script line: one
+/// outside quotes, at the start of a word, so that the `#` of
+/// `'https://a.com/#top'` or `a#b` is not taken for one.
+fn comment(line: &str) -> Option<&str> {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut word_start = true;
+    for (i, c) in line.char_indices() {
+        let literal = escaped;
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (Some('"') | None, '\\') => escaped = true,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '#') if word_start => return Some(&line[i + 1..]),
+            _ => {}
+        }
+        word_start = quote.is_none() && !literal && c.is_whitespace();
+    }
+    None
+}
+
+/// Find a directive inside the `#` comment of a script line.
+fn directive<'l>(line: &'l str, name: &str) -> Option<&'l str> {
+    comment(line)?.trim_start().strip_prefix(name)
+}
+
+impl Suppressions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept `code` everywhere, as `--allow` does.
+    pub fn allow(&mut self, code: Code) -> &mut Self {
+        self.allowed.insert(code);
+        self
+    }
+
+    /// Collect the ignore directives of a script.
+    ///
+    /// Line numbers are 1-based. A directive on a line of its own applies to
+    /// the next line holding anything but a comment.
+    pub fn from_script(script: &str) -> Result<Self, String> {
+        let mut suppressions = Self::new();
+        let mut pending: Vec<Code> = Vec::new();
+
+        for (index, line) in script.lines().enumerate() {
+            let number = index + 1;
+            let trimmed = line.trim_start();
+            let only_comment = trimmed.starts_with('#');
+
+            if let Some(list) = directive(line, IGNORE_FILE_DIRECTIVE) {
+                let codes = parse_codes(list).map_err(|e| format!("line {}: {}", number, e))?;
+                suppressions.allowed.extend(codes);
+            } else if let Some(list) = directive(line, IGNORE_DIRECTIVE) {
+                let codes = parse_codes(list).map_err(|e| format!("line {}: {}", number, e))?;
+                if only_comment {
+                    pending.extend(codes);
+                } else {
+                    suppressions.lines.entry(number).or_default().extend(codes);
+                }
+            }
+
+            if !only_comment && !trimmed.is_empty() && !pending.is_empty() {
+                suppressions
+                    .lines
+                    .entry(number)
+                    .or_default()
+                    .extend(pending.drain(..));
+            }
+        }
+
+        Ok(suppressions)
+    }
+
+    /// Merge another set, e.g. `--allow` flags into a script's directives.
+    pub fn extend(&mut self, other: Suppressions) {
+        self.allowed.extend(other.allowed);
+        for (line, codes) in other.lines {
+            self.lines.entry(line).or_default().extend(codes);
+        }
+    }
+
+    /// Whether `code` reported for the command starting at `line` is accepted.
+    pub fn is_suppressed(&self, code: Code, line: usize) -> bool {
+        self.allowed.contains(&code)
+            || self
+                .lines
+                .get(&line)
+                .is_some_and(|codes| codes.contains(&code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    const SCRIPT: &str = r#"#!/bin/sh
+# winnowcurl-ignore: WCL010
+curl 'https://internal.example.com' -k
+
+curl 'http://example.com/#top' # winnowcurl-ignore: plain-http, WCL010
+# winnowcurl-ignore: WCL011
+
+# an unrelated comment
+curl 'http://example.com' -k
+"#;
+
+    #[rstest]
+    #[case(Code::Insecure, 3, true)]
+    #[case(Code::PlainHttp, 3, false)]
+    #[case(Code::PlainHttp, 5, true)]
+    #[case(Code::Insecure, 5, true)]
+    #[case(Code::PlainHttp, 9, true)]
+    #[case(Code::Insecure, 9, false)]
+    fn test_from_script(#[case] code: Code, #[case] line: usize, #[case] expected: bool) {
+        let suppressions = Suppressions::from_script(SCRIPT).unwrap();
+        assert_eq!(suppressions.is_suppressed(code, line), expected)
+    }
+
+    #[rstest]
+    fn test_file_directive_and_allow() {
+        let mut suppressions =
+            Suppressions::from_script("# winnowcurl-ignore-file: WCL030\ncurl 'https://a.com'")
+                .unwrap();
+        assert!(suppressions.is_suppressed(Code::LongCookie, 2));
+        assert!(!suppressions.is_suppressed(Code::Insecure, 2));
+
+        suppressions.allow(Code::Insecure);
+        assert!(suppressions.is_suppressed(Code::Insecure, 42));
+    }
+
+    #[rstest]
+    #[case("# winnowcurl-ignore: WCL010", Some(" winnowcurl-ignore: WCL010"))]
+    #[case("curl 'https://a.com/#top' # done", Some(" done"))]
+    #[case("curl -d '# winnowcurl-ignore: WCL010' https://a.com", None)]
+    #[case(r#"curl -d "a \" # b" https://a.com"#, None)]
+    #[case("curl https://a.com/#winnowcurl-ignore:WCL010", None)]
+    #[case(r"curl -d a\ #b https://a.com", None)]
+    fn test_comment(#[case] line: &str, #[case] expected: Option<&str>) {
+        assert_eq!(comment(line), expected);
+    }
+
+    #[rstest]
+    fn test_unknown_code() {
+        let err = Suppressions::from_script("\n# winnowcurl-ignore: WCL999").unwrap_err();
+        assert!(err.starts_with("line 2:"));
+    }
+}
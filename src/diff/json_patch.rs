@@ -0,0 +1,195 @@
+use serde::{Serialize, Serializer};
+use serde_json::{Value, json};
+use std::fmt;
+
+/// A single RFC 6902 operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOp {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+impl PatchOp {
+    pub fn path(&self) -> &str {
+        match self {
+            PatchOp::Add { path, .. }
+            | PatchOp::Remove { path }
+            | PatchOp::Replace { path, .. } => path,
+        }
+    }
+
+    /// Render the operation as an RFC 6902 JSON object.
+    pub fn to_json(&self) -> Value {
+        match self {
+            PatchOp::Add { path, value } => json!({ "op": "add", "path": path, "value": value }),
+            PatchOp::Remove { path } => json!({ "op": "remove", "path": path }),
+            PatchOp::Replace { path, value } => {
+                json!({ "op": "replace", "path": path, "value": value })
+            }
+        }
+    }
+}
+
+impl fmt::Display for PatchOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchOp::Add { path, value } => write!(f, "+ {} {}", display_path(path), value),
+            PatchOp::Remove { path } => write!(f, "- {}", display_path(path)),
+            PatchOp::Replace { path, value } => write!(f, "~ {} {}", display_path(path), value),
+        }
+    }
+}
+
+/// Serialized as the RFC 6902 JSON object of [`PatchOp::to_json`].
+impl Serialize for PatchOp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() { "/" } else { path }
+}
+
+/// Render a list of operations as an RFC 6902 patch document.
+pub fn to_patch_document(ops: &[PatchOp]) -> Value {
+    Value::Array(ops.iter().map(PatchOp::to_json).collect())
+}
+
+/// Escape a single reference token as described in RFC 6901.
+pub fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Compute the operations transforming `from` into `to`.
+///
+/// Objects are compared key by key and arrays index by index; any other
+/// mismatch becomes a `replace` of the whole value at that location.
+pub fn diff(from: &Value, to: &Value) -> Vec<PatchOp> {
+    let mut ops = Vec::new();
+    diff_at("", from, to, &mut ops);
+    ops
+}
+
+fn diff_at(path: &str, from: &Value, to: &Value, ops: &mut Vec<PatchOp>) {
+    match (from, to) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, value) in a {
+                let child = format!("{}/{}", path, escape_token(key));
+                match b.get(key) {
+                    Some(other) => diff_at(&child, value, other, ops),
+                    None => ops.push(PatchOp::Remove { path: child }),
+                }
+            }
+            for (key, value) in b {
+                if !a.contains_key(key) {
+                    ops.push(PatchOp::Add {
+                        path: format!("{}/{}", path, escape_token(key)),
+                        value: value.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            let common = a.len().min(b.len());
+            for i in 0..common {
+                diff_at(&format!("{}/{}", path, i), &a[i], &b[i], ops);
+            }
+            // Remove from the end so earlier indices stay valid while applying.
+            for i in (common..a.len()).rev() {
+                ops.push(PatchOp::Remove {
+                    path: format!("{}/{}", path, i),
+                });
+            }
+            for (i, value) in b.iter().enumerate().skip(common) {
+                ops.push(PatchOp::Add {
+                    path: format!("{}/{}", path, i),
+                    value: value.clone(),
+                });
+            }
+        }
+        _ if from != to => ops.push(PatchOp::Replace {
+            path: path.into(),
+            value: to.clone(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("a/b", "a~1b")]
+    #[case("m~n", "m~0n")]
+    #[case("plain", "plain")]
+    fn test_escape_token(#[case] input: String, #[case] expected: String) {
+        assert_eq!(escape_token(&input), expected)
+    }
+
+    #[rstest]
+    fn test_diff_identical() {
+        let value = json!({"key": [1, 2, {"nested": true}]});
+        assert!(diff(&value, &value).is_empty());
+    }
+
+    #[rstest]
+    fn test_diff_objects() {
+        let from = json!({"keep": 1, "gone": 2, "change": "a"});
+        let to = json!({"keep": 1, "change": "b", "new/key": null});
+        let ops = diff(&from, &to);
+        assert_eq!(
+            ops,
+            vec![
+                PatchOp::Remove {
+                    path: "/gone".into()
+                },
+                PatchOp::Replace {
+                    path: "/change".into(),
+                    value: json!("b")
+                },
+                PatchOp::Add {
+                    path: "/new~1key".into(),
+                    value: Value::Null
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_diff_arrays() {
+        let ops = diff(&json!([1, 2, 3, 4]), &json!([1, 5]));
+        assert_eq!(
+            ops,
+            vec![
+                PatchOp::Replace {
+                    path: "/1".into(),
+                    value: json!(5)
+                },
+                PatchOp::Remove { path: "/3".into() },
+                PatchOp::Remove { path: "/2".into() },
+            ]
+        );
+
+        let ops = diff(&json!([1]), &json!([1, {"a": 1}]));
+        assert_eq!(
+            ops,
+            vec![PatchOp::Add {
+                path: "/1".into(),
+                value: json!({"a": 1})
+            }]
+        );
+    }
+
+    #[rstest]
+    fn test_diff_root_replace() {
+        let ops = diff(&json!("text"), &json!({"a": 1}));
+        assert_eq!(
+            to_patch_document(&ops),
+            json!([{"op": "replace", "path": "", "value": {"a": 1}}])
+        );
+    }
+}
@@ -0,0 +1,76 @@
+pub mod json_patch;
+pub mod request;
+#[cfg(feature = "exec")]
+pub mod response;
+
+use serde_json::Value;
+
+use crate::curl::body::join_data;
+use crate::curl::parser::Curl;
+use json_patch::PatchOp;
+
+/// Join every `-d`/`--data` argument the way curl builds the body, see
+/// [`join_data`].
+pub fn request_body(curls: &[Curl]) -> Option<String> {
+    join_data(curls.iter().filter_map(|curl| match curl {
+        Curl::Data(data) => Some((
+            &*data.identifier,
+            data.data.as_deref().unwrap_or_default().to_string(),
+        )),
+        _ => None,
+    }))
+}
+
+/// Interpret a body as JSON, falling back to a JSON string for other payloads
+/// and `null` when no body is sent.
+pub fn body_to_json(body: Option<&str>) -> Value {
+    match body {
+        Some(body) => serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.into())),
+        None => Value::Null,
+    }
+}
+
+/// Compute the RFC 6902 patch transforming the body of `a` into the body of `b`.
+pub fn body_patch(a: &[Curl], b: &[Curl]) -> Vec<PatchOp> {
+    let from = body_to_json(request_body(a).as_deref());
+    let to = body_to_json(request_body(b).as_deref());
+    json_patch::diff(&from, &to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+    use serde_json::json;
+
+    #[rstest]
+    #[case(r#"curl "https://example.com" -d 'a=1' --data 'b=2'"#, Some("a=1&b=2"))]
+    #[case(r#"curl "https://example.com" -X 'GET'"#, None)]
+    fn test_request_body(#[case] input: String, #[case] expected: Option<&str>) {
+        let curls = curl_cmd_parse(&input).unwrap();
+        assert_eq!(request_body(&curls).as_deref(), expected)
+    }
+
+    #[rstest]
+    #[case(Some(r#"{"a": 1}"#), json!({"a": 1}))]
+    #[case(Some("a=1"), json!("a=1"))]
+    #[case(None, Value::Null)]
+    fn test_body_to_json(#[case] input: Option<&str>, #[case] expected: Value) {
+        assert_eq!(body_to_json(input), expected)
+    }
+
+    #[rstest]
+    fn test_body_patch() {
+        let a =
+            curl_cmd_parse(r#"curl "https://example.com" -d '{"user": "bob", "age": 3}'"#).unwrap();
+        let b = curl_cmd_parse(r#"curl "https://example.com" -d '{"user": "amy"}'"#).unwrap();
+        assert_eq!(
+            json_patch::to_patch_document(&body_patch(&a, &b)),
+            json!([
+                {"op": "replace", "path": "/user", "value": "amy"},
+                {"op": "remove", "path": "/age"}
+            ])
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::fmt;
+
+use super::body_to_json;
+use super::json_patch::{self, PatchOp};
+use crate::request::Request;
+use crate::url::codec::UrlCodec;
+
+/// A component whose value differs between two requests.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    pub from: String,
+    pub to: String,
+}
+
+/// A query parameter or header only one request has, or has with other
+/// values. Repeated values are joined with `, `.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", rename_all = "lowercase")]
+pub enum Delta {
+    Added {
+        name: String,
+        value: String,
+    },
+    Removed {
+        name: String,
+        value: String,
+    },
+    Changed {
+        name: String,
+        from: String,
+        to: String,
+    },
+}
+
+/// What differs between two requests, component by component.
+///
+/// Hosts and header names are compared ignoring case, query parameters
+/// decoded, and bodies as JSON, see [`body_to_json`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct RequestDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<Change>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<Change>,
+    /// The host and port.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<Change>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Change>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub query: Vec<Delta>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<Delta>,
+    /// The RFC 6902 patch turning one body into the other.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub body: Vec<PatchOp>,
+}
+
+impl RequestDiff {
+    /// Compare the request `from` with the request `to`.
+    pub fn new(from: &Request, to: &Request) -> Self {
+        let (a, b) = (UrlParts::new(&from.url), UrlParts::new(&to.url));
+        RequestDiff {
+            method: change(from.method.as_str(), to.method.as_str()),
+            scheme: change(a.scheme, b.scheme),
+            host: change(&a.host, &b.host),
+            path: change(a.path, b.path),
+            query: deltas(query_params(a.query), query_params(b.query)),
+            headers: deltas(header_values(from), header_values(to)),
+            body: json_patch::diff(
+                &body_to_json(from.body.as_deref()),
+                &body_to_json(to.body.as_deref()),
+            ),
+        }
+    }
+
+    /// Whether both requests are the same.
+    pub fn is_empty(&self) -> bool {
+        *self == RequestDiff::default()
+    }
+}
+
+pub(super) fn change(from: &str, to: &str) -> Option<Change> {
+    (from != to).then(|| Change {
+        from: from.into(),
+        to: to.into(),
+    })
+}
+
+/// The components of a URL as compared.
+struct UrlParts<'u> {
+    scheme: &'u str,
+    /// Lowercased, without the credentials.
+    host: String,
+    /// `/` when the URL has none.
+    path: &'u str,
+    query: &'u str,
+}
+
+impl<'u> UrlParts<'u> {
+    fn new(url: &'u str) -> Self {
+        let url = url.split('#').next().unwrap_or_default();
+        let (scheme, rest) = url.split_once("://").unwrap_or(("http", url));
+        let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (authority, path) = match rest.find('/') {
+            Some(slash) => rest.split_at(slash),
+            None => (rest, "/"),
+        };
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        UrlParts {
+            scheme,
+            host: host.to_ascii_lowercase(),
+            path,
+            query,
+        }
+    }
+}
+
+/// Values grouped under the key of their name, in order of appearance,
+/// along with the name as first written.
+pub(super) type Grouped = IndexMap<String, (String, Vec<String>)>;
+
+pub(super) fn group(pairs: impl IntoIterator<Item = (String, String, String)>) -> Grouped {
+    let mut grouped = Grouped::new();
+    for (key, name, value) in pairs {
+        grouped
+            .entry(key)
+            .or_insert_with(|| (name, Vec::new()))
+            .1
+            .push(value);
+    }
+    grouped
+}
+
+fn query_params(query: &str) -> Grouped {
+    let codec = UrlCodec::form();
+    group(
+        query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                let name = codec.decode(name).into_owned();
+                (name.clone(), name, codec.decode(value).into_owned())
+            }),
+    )
+}
+
+fn header_values(request: &Request) -> Grouped {
+    group(request.headers.iter().map(|(name, value)| {
+        (
+            name.to_ascii_lowercase(),
+            name.to_string(),
+            value.to_string(),
+        )
+    }))
+}
+
+/// The parameters or headers removed or changed from `from`, then the ones
+/// added in `to`.
+pub(super) fn deltas(from: Grouped, to: Grouped) -> Vec<Delta> {
+    let mut deltas = Vec::new();
+    for (key, (name, values)) in &from {
+        match to.get(key) {
+            None => deltas.push(Delta::Removed {
+                name: name.clone(),
+                value: values.join(", "),
+            }),
+            Some((_, other)) if other != values => deltas.push(Delta::Changed {
+                name: name.clone(),
+                from: values.join(", "),
+                to: other.join(", "),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (key, (name, values)) in to {
+        if !from.contains_key(&key) {
+            deltas.push(Delta::Added {
+                name,
+                value: values.join(", "),
+            });
+        }
+    }
+    deltas
+}
+
+/// One line per difference, e.g.
+///
+/// ```text
+/// method: GET -> POST
+/// query:
+///   + page=2
+/// headers:
+///   ~ Accept: text/html -> */*
+/// body:
+///   ~ /user "amy"
+/// ```
+impl fmt::Display for RequestDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let changes = [
+            ("method", &self.method),
+            ("scheme", &self.scheme),
+            ("host", &self.host),
+            ("path", &self.path),
+        ];
+        for (component, change) in changes {
+            if let Some(Change { from, to }) = change {
+                writeln!(f, "{}: {} -> {}", component, from, to)?;
+            }
+        }
+        write_deltas(f, "query", &self.query, "=")?;
+        write_deltas(f, "headers", &self.headers, ": ")?;
+        write_body(f, &self.body)
+    }
+}
+
+/// The deltas of a component under its name, one per line, `+` for
+/// added, `-` for removed and `~` for changed ones.
+pub(super) fn write_deltas(
+    f: &mut fmt::Formatter<'_>,
+    component: &str,
+    deltas: &[Delta],
+    separator: &str,
+) -> fmt::Result {
+    if deltas.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "{}:", component)?;
+    for delta in deltas {
+        match delta {
+            Delta::Added { name, value } => writeln!(f, "  + {}{}{}", name, separator, value)?,
+            Delta::Removed { name, value } => writeln!(f, "  - {}{}{}", name, separator, value)?,
+            Delta::Changed { name, from, to } => {
+                writeln!(f, "  ~ {}{}{} -> {}", name, separator, from, to)?
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn write_body(f: &mut fmt::Formatter<'_>, body: &[PatchOp]) -> fmt::Result {
+    if !body.is_empty() {
+        writeln!(f, "body:")?;
+        for op in body {
+            writeln!(f, "  {}", op)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use serde_json::json;
+
+    #[rstest]
+    fn test_request_diff() {
+        let from = Request::parse(concat!(
+            "curl 'https://api.example.com/v1/users?page=1&sort=asc&q=a%20b' ",
+            "-H 'Accept: text/html' -H 'X-Trace: 1' -d '{\"user\": \"bob\", \"age\": 3}'"
+        ))
+        .unwrap();
+        let to = Request::parse(concat!(
+            "curl 'https://API.example.com/v2/users?q=a+b&sort=desc&limit=5' -X PUT ",
+            "-H 'accept: */*' -H 'Authorization: Bearer t' -d '{\"user\": \"amy\", \"age\": 3}'"
+        ))
+        .unwrap();
+        let diff = RequestDiff::new(&from, &to);
+
+        assert_eq!(
+            diff.to_string(),
+            concat!(
+                "method: POST -> PUT\n",
+                "path: /v1/users -> /v2/users\n",
+                "query:\n",
+                "  - page=1\n",
+                "  ~ sort=asc -> desc\n",
+                "  + limit=5\n",
+                "headers:\n",
+                "  ~ Accept: text/html -> */*\n",
+                "  - X-Trace: 1\n",
+                "  + Authorization: Bearer t\n",
+                "body:\n",
+                "  ~ /user \"amy\"\n",
+            )
+        );
+        assert_eq!(
+            serde_json::to_value(&diff).unwrap(),
+            json!({
+                "method": {"from": "POST", "to": "PUT"},
+                "path": {"from": "/v1/users", "to": "/v2/users"},
+                "query": [
+                    {"change": "removed", "name": "page", "value": "1"},
+                    {"change": "changed", "name": "sort", "from": "asc", "to": "desc"},
+                    {"change": "added", "name": "limit", "value": "5"}
+                ],
+                "headers": [
+                    {"change": "changed", "name": "Accept", "from": "text/html", "to": "*/*"},
+                    {"change": "removed", "name": "X-Trace", "value": "1"},
+                    {"change": "added", "name": "Authorization", "value": "Bearer t"}
+                ],
+                "body": [{"op": "replace", "path": "/user", "value": "amy"}]
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_request_diff_same_request() {
+        let from = Request::parse("curl 'http://a.com:8080' -H 'A: 1' -H 'B: 2'").unwrap();
+        let to = Request::parse("curl 'http://A.com:8080/' -H 'b: 2' -H 'a: 1'").unwrap();
+        let diff = RequestDiff::new(&from, &to);
+        assert!(diff.is_empty(), "{}", diff);
+        assert_eq!(diff.to_string(), "");
+        assert_eq!(serde_json::to_value(&diff).unwrap(), json!({}));
+    }
+
+    #[rstest]
+    fn test_request_diff_host() {
+        let from = Request::parse("curl 'http://me:pw@a.com/x'").unwrap();
+        let to = Request::parse("curl 'https://b.com:8443/x'").unwrap();
+        assert_eq!(
+            RequestDiff::new(&from, &to).to_string(),
+            "scheme: http -> https\nhost: a.com -> b.com:8443\n"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use serde::Serialize;
+use std::fmt;
+
+use super::body_to_json;
+use super::json_patch::{self, PatchOp};
+use super::request::{Change, Delta, Grouped, change, deltas, group, write_body, write_deltas};
+use crate::exec::Response;
+
+/// Headers which differ from one response to the next whatever the
+/// server, left out of the comparison.
+pub const VOLATILE_HEADERS: &[&str] = &["age", "date", "expires", "x-request-id"];
+
+/// What differs between the responses of two servers to the same request,
+/// e.g. production and staging.
+///
+/// Header names are compared ignoring case, and bodies as JSON, see
+/// [`body_to_json`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ResponseDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Change>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<Delta>,
+    /// The RFC 6902 patch turning one body into the other.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub body: Vec<PatchOp>,
+}
+
+fn header_values(response: &Response, ignored: &[String]) -> Grouped {
+    group(response.headers.iter().filter_map(|(name, value)| {
+        let key = name.to_ascii_lowercase();
+        let ignore = VOLATILE_HEADERS.contains(&key.as_str())
+            || ignored.iter().any(|i| i.eq_ignore_ascii_case(name));
+        (!ignore).then(|| (key, name.clone(), value.clone()))
+    }))
+}
+
+impl ResponseDiff {
+    /// Compare the response `from` with the response `to`, leaving out the
+    /// [`VOLATILE_HEADERS`] and the headers named in `ignored`.
+    pub fn new(from: &Response, to: &Response, ignored: &[String]) -> Self {
+        let body = |response: &Response| {
+            let text = String::from_utf8_lossy(&response.body);
+            body_to_json((!text.is_empty()).then_some(&*text))
+        };
+        ResponseDiff {
+            status: change(&from.status.to_string(), &to.status.to_string()),
+            headers: deltas(header_values(from, ignored), header_values(to, ignored)),
+            body: json_patch::diff(&body(from), &body(to)),
+        }
+    }
+
+    /// Whether both responses are the same.
+    pub fn is_empty(&self) -> bool {
+        *self == ResponseDiff::default()
+    }
+}
+
+/// One line per difference, e.g.
+///
+/// ```text
+/// status: 200 -> 404
+/// headers:
+///   - Cache-Control: max-age=60
+/// body:
+///   - /items/2
+/// ```
+impl fmt::Display for ResponseDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(Change { from, to }) = &self.status {
+            writeln!(f, "status: {} -> {}", from, to)?;
+        }
+        write_deltas(f, "headers", &self.headers, ": ")?;
+        write_body(f, &self.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use serde_json::json;
+
+    fn response(status: u16, headers: &[(&str, &str)], body: &str) -> Response {
+        Response {
+            status,
+            reason: None,
+            version: "HTTP/1.1".into(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body: body.into(),
+        }
+    }
+
+    #[rstest]
+    fn test_response_diff() {
+        let from = response(
+            200,
+            &[
+                ("Date", "Mon, 01 Jan 2024 00:00:00 GMT"),
+                ("Content-Type", "application/json"),
+                ("Cache-Control", "max-age=60"),
+                ("X-Served-By", "prod-1"),
+            ],
+            r#"{"items": [1, 2], "total": 2}"#,
+        );
+        let to = response(
+            404,
+            &[
+                ("date", "Tue, 02 Jan 2024 00:00:00 GMT"),
+                ("content-type", "application/json"),
+                ("X-Served-By", "staging-1"),
+            ],
+            r#"{"total": 2, "items": [1]}"#,
+        );
+        let diff = ResponseDiff::new(&from, &to, &["x-served-by".into()]);
+
+        assert_eq!(
+            diff.to_string(),
+            concat!(
+                "status: 200 -> 404\n",
+                "headers:\n",
+                "  - Cache-Control: max-age=60\n",
+                "body:\n",
+                "  - /items/1\n",
+            )
+        );
+        assert_eq!(
+            serde_json::to_value(&diff).unwrap(),
+            json!({
+                "status": {"from": "200", "to": "404"},
+                "headers": [{"change": "removed", "name": "Cache-Control", "value": "max-age=60"}],
+                "body": [{"op": "remove", "path": "/items/1"}]
+            })
+        );
+    }
+
+    #[rstest]
+    #[case("ok", "ok", true)]
+    #[case("", "", true)]
+    #[case(r#"{"a":1,"b":2}"#, r#"{ "b": 2, "a": 1 }"#, true)]
+    #[case("ok", "not ok", false)]
+    #[case("", "{}", false)]
+    fn test_response_diff_body(#[case] from: &str, #[case] to: &str, #[case] same: bool) {
+        let diff = ResponseDiff::new(&response(200, &[], from), &response(200, &[], to), &[]);
+        assert_eq!(diff.is_empty(), same, "{}", diff);
+    }
+}
@@ -0,0 +1,291 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Methods which change or remove what the server holds.
+pub const DESTRUCTIVE_METHODS: &[&str] = &["DELETE", "PUT", "PATCH"];
+
+/// The file the configuration is read from unless `WINNOWCURL_CONFIG` names
+/// another, relative to `$XDG_CONFIG_HOME` or `~/.config`.
+pub const CONFIG_FILE: &str = "winnowcurl/config.yaml";
+
+/// Whether `method` is one of the [`DESTRUCTIVE_METHODS`], ignoring case.
+pub fn is_destructive(method: &str) -> bool {
+    DESTRUCTIVE_METHODS
+        .iter()
+        .any(|destructive| destructive.eq_ignore_ascii_case(method))
+}
+
+/// What to do before sending a destructive request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Policy {
+    /// Send it once confirmed, by `--yes` or by answering a prompt.
+    #[default]
+    Ask,
+    /// Send it right away.
+    Allow,
+    /// Never send it, even with `--yes`.
+    Deny,
+}
+
+/// Whether a [`Session`](super::Session) sends destructive requests.
+///
+/// ```
+/// use winnowcurl::exec::confirm::{Confirmation, Policy};
+///
+/// let confirmation = Confirmation::new(Policy::Ask);
+/// assert!(confirmation.allows("GET", "https://example.com/orders/7"));
+/// assert!(!confirmation.allows("DELETE", "https://example.com/orders/7"));
+/// assert!(confirmation.yes(true).allows("DELETE", "https://example.com/orders/7"));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Confirmation {
+    pub policy: Policy,
+    /// Confirmed up front, with `--yes`.
+    pub confirmed: bool,
+    /// Asks the user about a request, e.g. `DELETE https://example.com/a`;
+    /// `None` when nobody is there to answer.
+    pub prompt: Option<fn(&str) -> bool>,
+}
+
+/// Everything is sent, as curl does.
+impl Default for Confirmation {
+    fn default() -> Self {
+        Confirmation::new(Policy::Allow)
+    }
+}
+
+impl Confirmation {
+    pub fn new(policy: Policy) -> Self {
+        Confirmation {
+            policy,
+            confirmed: false,
+            prompt: None,
+        }
+    }
+
+    pub fn yes(mut self, confirmed: bool) -> Self {
+        self.confirmed = confirmed;
+        self
+    }
+
+    pub fn prompt(mut self, prompt: fn(&str) -> bool) -> Self {
+        self.prompt = Some(prompt);
+        self
+    }
+
+    /// Whether the request `method url` may be sent.
+    pub fn allows(&self, method: &str, url: &str) -> bool {
+        if !is_destructive(method) {
+            return true;
+        }
+        match self.policy {
+            Policy::Allow => true,
+            Policy::Deny => false,
+            Policy::Ask => {
+                self.confirmed
+                    || self
+                        .prompt
+                        .is_some_and(|prompt| prompt(&format!("{} {}", method, url)))
+            }
+        }
+    }
+}
+
+/// Ask on `output` whether to send `request` and read the answer from
+/// `input`, anything but `y` or `yes` meaning no.
+pub fn ask(request: &str, input: &mut dyn BufRead, output: &mut dyn Write) -> io::Result<bool> {
+    write!(output, "Send {}? [y/N] ", request)?;
+    output.flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes"))
+}
+
+/// Why the configuration could not be read.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Format {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "failed to read {}: {}", path.display(), source)
+            }
+            ConfigError::Format { path, source } => {
+                write!(
+                    f,
+                    "{} is not a valid configuration: {}",
+                    path.display(),
+                    source
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Format { source, .. } => Some(source),
+        }
+    }
+}
+
+/// The settings of the configuration file, e.g.
+///
+/// ```yaml
+/// destructive-methods: deny
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Whether `DELETE`, `PUT` and `PATCH` requests are sent.
+    pub destructive_methods: Policy,
+}
+
+impl Config {
+    /// Where the configuration is read from: `$WINNOWCURL_CONFIG`, or
+    /// [`CONFIG_FILE`] in `$XDG_CONFIG_HOME` or `~/.config`.
+    pub fn path() -> Option<PathBuf> {
+        let var = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+        if let Some(path) = var("WINNOWCURL_CONFIG") {
+            return Some(path.into());
+        }
+        let dir = var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(dir.join(CONFIG_FILE))
+    }
+
+    /// Read the configuration file at `path`.
+    pub fn from_path(path: &Path) -> Result<Self, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.into(),
+            source,
+        })?;
+        Self::parse(&text).map_err(|source| ConfigError::Format {
+            path: path.into(),
+            source,
+        })
+    }
+
+    /// Parse the YAML of a configuration file, empty standing for the
+    /// defaults.
+    pub fn parse(text: &str) -> Result<Self, serde_yaml::Error> {
+        if text.trim().is_empty() {
+            return Ok(Config::default());
+        }
+        serde_yaml::from_str(text)
+    }
+
+    /// Read the configuration file at [`Config::path`], the defaults when
+    /// there is none.
+    pub fn load() -> Result<Self, ConfigError> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::from_path(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn always(_: &str) -> bool {
+        true
+    }
+
+    fn never(_: &str) -> bool {
+        false
+    }
+
+    #[rstest]
+    #[case(Confirmation::new(Policy::Ask), "GET", true)]
+    #[case(Confirmation::new(Policy::Ask), "POST", true)]
+    #[case(Confirmation::new(Policy::Ask), "DELETE", false)]
+    #[case(Confirmation::new(Policy::Ask), "patch", false)]
+    #[case(Confirmation::new(Policy::Ask).yes(true), "PUT", true)]
+    #[case(Confirmation::new(Policy::Ask).prompt(always), "PUT", true)]
+    #[case(Confirmation::new(Policy::Ask).prompt(never), "PUT", false)]
+    #[case(Confirmation::new(Policy::Allow), "DELETE", true)]
+    #[case(Confirmation::new(Policy::Deny).yes(true), "DELETE", false)]
+    #[case(Confirmation::new(Policy::Deny).prompt(always), "DELETE", false)]
+    #[case(Confirmation::new(Policy::Deny), "GET", true)]
+    #[case(Confirmation::default(), "DELETE", true)]
+    fn test_allows(
+        #[case] confirmation: Confirmation,
+        #[case] method: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            confirmation.allows(method, "https://example.com/a"),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("y\n", true)]
+    #[case("YES\n", true)]
+    #[case("n\n", false)]
+    #[case("\n", false)]
+    #[case("", false)]
+    fn test_ask(#[case] answer: &str, #[case] expected: bool) {
+        let mut output = Vec::new();
+        let confirmed = ask(
+            "DELETE https://example.com/a",
+            &mut answer.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(confirmed, expected);
+        assert_eq!(output, b"Send DELETE https://example.com/a? [y/N] ");
+    }
+
+    #[rstest]
+    #[case("", Policy::Ask)]
+    #[case("destructive-methods: allow", Policy::Allow)]
+    #[case("destructive-methods: deny\n", Policy::Deny)]
+    fn test_parse_config(#[case] text: &str, #[case] expected: Policy) {
+        assert_eq!(Config::parse(text).unwrap().destructive_methods, expected);
+    }
+
+    #[rstest]
+    #[case("destructive-methods: sometimes")]
+    #[case("destructive_methods: deny")]
+    fn test_parse_config_invalid(#[case] text: &str) {
+        assert!(Config::parse(text).is_err());
+    }
+
+    #[rstest]
+    fn test_config_from_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(&path, "destructive-methods: deny\n").unwrap();
+        assert_eq!(
+            Config::from_path(&path).unwrap().destructive_methods,
+            Policy::Deny
+        );
+        assert!(matches!(
+            Config::from_path(&dir.path().join("missing.yaml")),
+            Err(ConfigError::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,421 @@
+use std::fmt::Write as _;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reqwest::Url;
+
+/// The first lines of the cookie files curl writes with `-c`.
+const NETSCAPE_HEADER: &str = "# Netscape HTTP Cookie File\n\
+    # https://curl.se/docs/http-cookies.html\n\
+    # This file was generated by winnowcurl! Edit at your own risk.\n\n";
+
+/// The prefix curl gives the lines of `HttpOnly` cookies in a cookie file.
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+/// The current time, in seconds since the epoch.
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// A `Set-Cookie` date, e.g. `Wed, 21 Oct 2015 07:28:00 GMT`, also taken
+/// with dashes between its day, month and year as some servers write it.
+fn parse_date(date: &str) -> Option<u64> {
+    let date = date.trim();
+    let time = httpdate::parse_http_date(date)
+        .or_else(|_| httpdate::parse_http_date(&date.replace('-', " ")))
+        .ok()?;
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|elapsed| elapsed.as_secs())
+}
+
+/// The path a cookie set without a `Path` attribute applies to: the
+/// directory of the URL.
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".into(),
+        Some(end) => url.path()[..end].into(),
+    }
+}
+
+/// Whether `path` is `cookie_path` or below it.
+fn path_matches(path: &str, cookie_path: &str) -> bool {
+    path == cookie_path
+        || path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || path[cookie_path.len()..].starts_with('/'))
+}
+
+/// A cookie of a [`CookieJar`], with the fields of a line of a Netscape
+/// cookie file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredCookie {
+    /// The host the cookie is sent to, lowercase and without a leading dot.
+    pub domain: String,
+    /// Whether the cookie is sent to the subdomains of `domain` too, as
+    /// asked by a `Domain` attribute.
+    pub include_subdomains: bool,
+    pub path: String,
+    /// Whether the cookie is only sent over HTTPS.
+    pub secure: bool,
+    pub http_only: bool,
+    /// When the cookie expires, in seconds since the epoch; `0` for a
+    /// session cookie.
+    pub expires: u64,
+    pub name: String,
+    pub value: String,
+}
+
+impl StoredCookie {
+    /// The cookie of a `Set-Cookie` header received from `url`, `None` when
+    /// the header is malformed or sets a cookie for another domain.
+    pub fn from_set_cookie(header: &str, url: &Url, now: u64) -> Option<Self> {
+        let mut attributes = header.split(';');
+        let (name, value) = attributes.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let host = url.host_str()?.to_ascii_lowercase();
+        let mut cookie = StoredCookie {
+            domain: host.clone(),
+            include_subdomains: false,
+            path: default_path(url),
+            secure: false,
+            http_only: false,
+            expires: 0,
+            name: name.into(),
+            value: value.trim().into(),
+        };
+
+        let mut max_age = None;
+        for attribute in attributes {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                    let matches = host == domain || host.ends_with(&format!(".{}", domain));
+                    if !matches {
+                        return None;
+                    }
+                    cookie.domain = domain;
+                    cookie.include_subdomains = true;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.into(),
+                "expires" => {
+                    if let Some(expires) = parse_date(value) {
+                        // `0` would make it a session cookie.
+                        cookie.expires = expires.max(1);
+                    }
+                }
+                "max-age" => max_age = value.parse::<i64>().ok(),
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                _ => {}
+            }
+        }
+        // `Max-Age` wins over `Expires`, zero or less removing the cookie.
+        if let Some(max_age) = max_age {
+            cookie.expires = match u64::try_from(max_age) {
+                Ok(seconds) if seconds > 0 => now.saturating_add(seconds),
+                _ => 1,
+            };
+        }
+        Some(cookie)
+    }
+
+    /// Whether the cookie has expired at `now`.
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires != 0 && self.expires <= now
+    }
+
+    /// Whether the cookie is sent with a request to `url` at `now`.
+    pub fn matches(&self, url: &Url, now: u64) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        let domain_matches = host == self.domain
+            || self.include_subdomains && host.ends_with(&format!(".{}", self.domain));
+        domain_matches
+            && path_matches(url.path(), &self.path)
+            && (!self.secure || url.scheme() == "https")
+            && !self.is_expired(now)
+    }
+
+    /// The cookie as a line of a Netscape cookie file.
+    fn netscape_line(&self) -> String {
+        let flag = |set: bool| if set { "TRUE" } else { "FALSE" };
+        format!(
+            "{}{}{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            if self.http_only { HTTP_ONLY_PREFIX } else { "" },
+            if self.include_subdomains { "." } else { "" },
+            self.domain,
+            flag(self.include_subdomains),
+            self.path,
+            flag(self.secure),
+            self.expires,
+            self.name,
+            self.value
+        )
+    }
+
+    /// The cookie of a line of a Netscape cookie file, `None` for comments
+    /// and malformed lines.
+    fn from_netscape_line(line: &str) -> Option<Self> {
+        let (http_only, line) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(line) => (true, line),
+            None if line.starts_with('#') => return None,
+            None => (false, line),
+        };
+        let fields: Vec<&str> = line.trim_end_matches('\r').split('\t').collect();
+        let [
+            domain,
+            include_subdomains,
+            path,
+            secure,
+            expires,
+            name,
+            value,
+        ] = fields[..]
+        else {
+            return None;
+        };
+        let include_subdomains = include_subdomains.eq_ignore_ascii_case("TRUE");
+        Some(StoredCookie {
+            domain: domain.trim_start_matches('.').to_ascii_lowercase(),
+            include_subdomains: include_subdomains || domain.starts_with('.'),
+            path: path.into(),
+            secure: secure.eq_ignore_ascii_case("TRUE"),
+            http_only,
+            expires: expires.parse().ok()?,
+            name: name.into(),
+            value: value.into(),
+        })
+    }
+}
+
+/// The cookies curl keeps between the transfers of a command when its
+/// cookie engine is on, read from `-b file` and written to `-c file`.
+///
+/// ```
+/// use reqwest::Url;
+/// use winnowcurl::exec::cookies::CookieJar;
+///
+/// let mut jar = CookieJar::new();
+/// let login = Url::parse("https://a.com/login").unwrap();
+/// jar.set_cookie("sid=42; Path=/; HttpOnly", &login);
+/// jar.set_cookie("theme=dark; Domain=a.com; Path=/app", &login);
+///
+/// let me = Url::parse("https://www.a.com/app/me").unwrap();
+/// assert_eq!(jar.header(&me).as_deref(), Some("theme=dark"));
+/// let me = Url::parse("https://a.com/app/me").unwrap();
+/// assert_eq!(jar.header(&me).as_deref(), Some("theme=dark; sid=42"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CookieJar {
+    cookies: Vec<StoredCookie>,
+}
+
+impl CookieJar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cookies of a Netscape cookie file, as written by curl's `-c` or
+    /// a browser extension; lines that aren't cookies are skipped.
+    pub fn parse(text: &str) -> Self {
+        let mut jar = CookieJar::new();
+        for cookie in text.lines().filter_map(StoredCookie::from_netscape_line) {
+            jar.insert(cookie);
+        }
+        jar
+    }
+
+    pub fn cookies(&self) -> &[StoredCookie] {
+        &self.cookies
+    }
+
+    /// Add a cookie, replacing the one with the same name, domain and path;
+    /// an expired cookie removes it instead.
+    pub fn insert(&mut self, cookie: StoredCookie) {
+        let same = self.cookies.iter().position(|stored| {
+            stored.name == cookie.name
+                && stored.domain == cookie.domain
+                && stored.path == cookie.path
+        });
+        let expired = cookie.is_expired(now());
+        match same {
+            Some(i) if expired => {
+                self.cookies.remove(i);
+            }
+            Some(i) => self.cookies[i] = cookie,
+            None if expired => {}
+            None => self.cookies.push(cookie),
+        }
+    }
+
+    /// Keep the cookie of a `Set-Cookie` header received from `url`.
+    pub fn set_cookie(&mut self, header: &str, url: &Url) {
+        if let Some(cookie) = StoredCookie::from_set_cookie(header, url, now()) {
+            self.insert(cookie);
+        }
+    }
+
+    /// The `Cookie` header sent to `url`, the cookies with the longest
+    /// paths first; `None` without any.
+    pub fn header(&self, url: &Url) -> Option<String> {
+        let now = now();
+        let mut cookies: Vec<&StoredCookie> = self
+            .cookies
+            .iter()
+            .filter(|cookie| cookie.matches(url, now))
+            .collect();
+        cookies.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.len()));
+        let header = cookies
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        (!header.is_empty()).then_some(header)
+    }
+
+    /// The jar as a Netscape cookie file, the way curl writes it for `-c`;
+    /// expired cookies are left out.
+    pub fn to_netscape(&self) -> String {
+        let now = now();
+        let mut file = NETSCAPE_HEADER.to_string();
+        for cookie in self.cookies.iter().filter(|cookie| !cookie.is_expired(now)) {
+            let _ = writeln!(file, "{}", cookie.netscape_line());
+        }
+        file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[rstest]
+    #[case("https://a.com/login", "/")]
+    #[case("https://a.com/", "/")]
+    #[case("https://a.com/app/login", "/app")]
+    #[case("https://a.com/app/v1/", "/app/v1")]
+    fn test_default_path(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(default_path(&url(input)), expected);
+    }
+
+    #[rstest]
+    #[case("/app", "/app", true)]
+    #[case("/app/me", "/app", true)]
+    #[case("/app/me", "/app/", true)]
+    #[case("/apple", "/app", false)]
+    #[case("/", "/app", false)]
+    fn test_path_matches(#[case] path: &str, #[case] cookie_path: &str, #[case] expected: bool) {
+        assert_eq!(path_matches(path, cookie_path), expected);
+    }
+
+    #[rstest]
+    fn test_from_set_cookie() {
+        let cookie = StoredCookie::from_set_cookie(
+            "sid=a=b; Domain=.Example.com; Path=/api; Secure; HttpOnly; Expires=Wed, 21 Oct 2037 07:28:00 GMT",
+            &url("https://www.example.com/login"),
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            cookie,
+            StoredCookie {
+                domain: "example.com".into(),
+                include_subdomains: true,
+                path: "/api".into(),
+                secure: true,
+                http_only: true,
+                expires: 2139722880,
+                name: "sid".into(),
+                value: "a=b".into(),
+            }
+        );
+    }
+
+    #[rstest]
+    #[case("sid=1; Max-Age=60; Expires=Wed, 21 Oct 2037 07:28:00 GMT", 1060)]
+    #[case("sid=1; Max-Age=0", 1)]
+    #[case("sid=1; Expires=Thu, 01-Jan-1970 00:00:00 GMT", 1)]
+    #[case("sid=1; Expires=soon", 0)]
+    fn test_from_set_cookie_expiry(#[case] header: &str, #[case] expected: u64) {
+        let cookie = StoredCookie::from_set_cookie(header, &url("https://a.com/"), 1000).unwrap();
+        assert_eq!(cookie.expires, expected);
+    }
+
+    #[rstest]
+    #[case("sid=1; Domain=b.com")]
+    #[case("sid=1; Domain=xa.com")]
+    #[case("no value")]
+    #[case("=1")]
+    fn test_from_set_cookie_refused(#[case] header: &str) {
+        assert_eq!(
+            StoredCookie::from_set_cookie(header, &url("https://a.com/"), 0),
+            None
+        );
+    }
+
+    #[rstest]
+    fn test_header() {
+        let mut jar = CookieJar::new();
+        jar.set_cookie("host=1", &url("https://a.com/"));
+        jar.set_cookie("wide=2; Domain=a.com", &url("https://a.com/"));
+        jar.set_cookie("secure=3; Secure", &url("https://a.com/"));
+        jar.set_cookie("deep=4; Path=/x/y", &url("https://a.com/"));
+
+        assert_eq!(
+            jar.header(&url("https://a.com/x/y/z")).as_deref(),
+            Some("deep=4; host=1; wide=2; secure=3")
+        );
+        assert_eq!(
+            jar.header(&url("http://sub.a.com/")).as_deref(),
+            Some("wide=2")
+        );
+        assert_eq!(jar.header(&url("https://b.com/")), None);
+    }
+
+    #[rstest]
+    fn test_insert_replaces_and_removes() {
+        let mut jar = CookieJar::new();
+        let login = url("https://a.com/");
+        jar.set_cookie("sid=1", &login);
+        jar.set_cookie("sid=2", &login);
+        assert_eq!(jar.header(&login).as_deref(), Some("sid=2"));
+        jar.set_cookie("sid=; Max-Age=0", &login);
+        assert!(jar.cookies().is_empty());
+    }
+
+    #[rstest]
+    fn test_netscape_round_trip() {
+        let text = concat!(
+            "# Netscape HTTP Cookie File\n",
+            "\n",
+            "#HttpOnly_.example.com\tTRUE\t/\tTRUE\t0\tsid\tabc\n",
+            "api.example.com\tFALSE\t/v1\tFALSE\t4102444800\ttheme\tdark\n",
+            "broken line\n",
+            "gone.example.com\tFALSE\t/\tFALSE\t1\told\tx\n",
+        );
+        let jar = CookieJar::parse(text);
+        assert_eq!(jar.cookies().len(), 2);
+        assert!(jar.cookies()[0].http_only && jar.cookies()[0].include_subdomains);
+
+        let written = jar.to_netscape();
+        assert!(written.starts_with(NETSCAPE_HEADER));
+        assert!(written.ends_with(concat!(
+            "#HttpOnly_.example.com\tTRUE\t/\tTRUE\t0\tsid\tabc\n",
+            "api.example.com\tFALSE\t/v1\tFALSE\t4102444800\ttheme\tdark\n",
+        )));
+        assert_eq!(CookieJar::parse(&written), jar);
+    }
+}
@@ -0,0 +1,1554 @@
+pub mod confirm;
+pub mod cookies;
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use reqwest::Url;
+use reqwest::blocking::{Client, ClientBuilder, Request};
+use reqwest::cookie::CookieStore;
+use reqwest::header::{CONTENT_TYPE, COOKIE, HeaderValue, RANGE};
+use reqwest::redirect::Policy;
+
+use self::confirm::Confirmation;
+use self::cookies::CookieJar;
+
+use crate::curl::date::http_date;
+use crate::curl::header::Headers;
+use crate::curl::network::{Interface, NetworkOptions};
+use crate::curl::options;
+use crate::curl::parallel::ParallelSettings;
+use crate::curl::parser::Curl;
+use crate::curl::resume::ContinueAt;
+use crate::curl::routing::{ProxyScheme, Routing};
+use crate::curl::time_cond::{TimeCondition, TimeSource};
+use crate::curl::trace::TraceSettings;
+use crate::curl::transfer::{SpeedLimit, TransferSettings};
+use crate::diagnostic::Diagnostic;
+use crate::request::auth::Auth;
+use crate::request::body::FORM_URLENCODED;
+
+/// Why a command could not be performed.
+#[derive(Debug)]
+pub enum ExecError {
+    /// curl refuses to send the request, e.g. for mixing `-d` and `-F`.
+    InvalidRequest(Diagnostic),
+    /// The `-X` argument is not a valid HTTP method.
+    InvalidMethod(String),
+    /// Two options that curl refuses to combine were both given.
+    ConflictingOptions(&'static str, &'static str),
+    /// The command relies on an option the HTTP client cannot honour here.
+    Unsupported {
+        option: &'static str,
+        reason: &'static str,
+    },
+    /// Building the client, the request or sending it failed.
+    Http(reqwest::Error),
+    /// Reading the response body failed.
+    Io(io::Error),
+    /// The transfer stayed below `--speed-limit` for `--speed-time`.
+    TooSlow(SpeedLimit),
+    /// `-C` asked for the rest of a resource, and the server sent all of it.
+    CannotResume,
+    /// The `-c` cookie jar could not be written.
+    SaveCookies { path: PathBuf, source: io::Error },
+    /// The `-T` file could not be read.
+    ReadUpload { path: PathBuf, source: io::Error },
+    /// A destructive request was not confirmed, or is denied by the
+    /// configuration.
+    NotConfirmed {
+        method: String,
+        url: String,
+        policy: confirm::Policy,
+    },
+}
+
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecError::InvalidRequest(diagnostic) => write!(f, "{}", diagnostic.message),
+            ExecError::InvalidMethod(method) => write!(f, "invalid HTTP method '{}'", method),
+            ExecError::ConflictingOptions(a, b) => write!(f, "{} and {} cannot be combined", a, b),
+            ExecError::Unsupported { option, reason } => {
+                write!(f, "{} is not supported: {}", option, reason)
+            }
+            ExecError::Http(e) => write!(f, "request failed: {}", e),
+            ExecError::Io(e) => write!(f, "reading the response failed: {}", e),
+            ExecError::TooSlow(limit) => write!(
+                f,
+                "Operation too slow. Less than {} bytes/sec transferred the last {} seconds",
+                limit.bytes_per_second,
+                limit.time.as_secs_f64()
+            ),
+            ExecError::CannotResume => {
+                f.write_str("HTTP server doesn't seem to support byte ranges. Cannot resume.")
+            }
+            ExecError::SaveCookies { path, source } => {
+                write!(
+                    f,
+                    "failed to save cookies in {}: {}",
+                    path.display(),
+                    source
+                )
+            }
+            ExecError::ReadUpload { path, source } => {
+                write!(f, "failed to read {}: {}", path.display(), source)
+            }
+            ExecError::NotConfirmed {
+                method,
+                url,
+                policy: confirm::Policy::Deny,
+            } => write!(
+                f,
+                "{} {} was not sent: the configuration denies destructive requests",
+                method, url
+            ),
+            ExecError::NotConfirmed { method, url, .. } => write!(
+                f,
+                "{} {} was not sent: destructive requests need a confirmation, or --yes",
+                method, url
+            ),
+        }
+    }
+}
+
+impl ExecError {
+    /// The exit code curl ends with on the same error, e.g. 7 when the
+    /// connection is refused.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ExecError::InvalidRequest(_)
+            | ExecError::InvalidMethod(_)
+            | ExecError::ConflictingOptions(..) => USAGE_EXIT_CODE,
+            // CURLE_NOT_BUILT_IN
+            ExecError::Unsupported { .. } => 4,
+            // CURLE_OPERATION_TIMEDOUT
+            ExecError::Http(e) if e.is_timeout() => 28,
+            // CURLE_COULDNT_CONNECT
+            ExecError::Http(e) if e.is_connect() => 7,
+            // CURLE_TOO_MANY_REDIRECTS
+            ExecError::Http(e) if e.is_redirect() => 47,
+            // CURLE_URL_MALFORMAT
+            ExecError::Http(e) if e.is_builder() => 3,
+            // CURLE_RECV_ERROR
+            ExecError::Http(_) | ExecError::Io(_) => 56,
+            ExecError::TooSlow(_) => 28,
+            // CURLE_RANGE_ERROR
+            ExecError::CannotResume => 33,
+            // CURLE_WRITE_ERROR
+            ExecError::SaveCookies { .. } => 23,
+            // CURLE_READ_ERROR
+            ExecError::ReadUpload { .. } => 26,
+            ExecError::NotConfirmed { .. } => NOT_CONFIRMED_EXIT_CODE,
+        }
+    }
+}
+
+impl std::error::Error for ExecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExecError::Http(e) => Some(e),
+            ExecError::Io(e)
+            | ExecError::SaveCookies { source: e, .. }
+            | ExecError::ReadUpload { source: e, .. } => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Diagnostic> for ExecError {
+    fn from(diagnostic: Diagnostic) -> Self {
+        ExecError::InvalidRequest(diagnostic)
+    }
+}
+
+impl From<reqwest::Error> for ExecError {
+    fn from(e: reqwest::Error) -> Self {
+        ExecError::Http(e)
+    }
+}
+
+/// What came back from the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub reason: Option<&'static str>,
+    pub version: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// The status line as curl prints it, e.g. `HTTP/1.1 200 OK`.
+    pub fn status_line(&self) -> String {
+        match self.reason {
+            Some(reason) => format!("{} {} {}", self.version, self.status, reason),
+            None => format!("{} {}", self.version, self.status),
+        }
+    }
+}
+
+/// The exit code of curl when `--fail` or `--fail-with-body` hits an error
+/// status.
+pub const FAIL_EXIT_CODE: i32 = 22;
+
+/// The exit code of curl when a command cannot be run as written, e.g. it
+/// does not parse.
+pub const USAGE_EXIT_CODE: i32 = 2;
+
+/// The exit code of curl when the body cannot be written to its file.
+pub const WRITE_EXIT_CODE: i32 = 23;
+
+/// The exit code when a destructive request was not sent for want of a
+/// confirmation, past the codes of curl so that scripts tell it from any
+/// failure of the request itself.
+pub const NOT_CONFIRMED_EXIT_CODE: i32 = 120;
+
+/// How an HTTP error status ends the command, set by `-f` and
+/// `--fail-with-body`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FailMode {
+    /// Error responses are printed like any other.
+    #[default]
+    Off,
+    /// `-f`/`--fail`: print nothing and exit with [`FAIL_EXIT_CODE`].
+    Fail,
+    /// `--fail-with-body`: print the response, then exit with [`FAIL_EXIT_CODE`].
+    FailWithBody,
+}
+
+impl FailMode {
+    pub fn new(curls: &[Curl]) -> Result<Self, ExecError> {
+        match (
+            options::has_flag(curls, "--fail"),
+            options::has_flag(curls, "--fail-with-body"),
+        ) {
+            (true, true) => Err(ExecError::ConflictingOptions("--fail", "--fail-with-body")),
+            (true, false) => Ok(FailMode::Fail),
+            (false, true) => Ok(FailMode::FailWithBody),
+            (false, false) => Ok(FailMode::Off),
+        }
+    }
+
+    /// Whether a response with `status` fails the command.
+    pub fn fails(&self, status: u16) -> bool {
+        *self != FailMode::Off && status >= 400
+    }
+
+    /// Whether a response with `status` should still be printed.
+    pub fn shows_response(&self, status: u16) -> bool {
+        *self != FailMode::Fail || status < 400
+    }
+}
+
+/// The last value of the option `name` of a command.
+fn last_value<'c>(curls: &'c [Curl], name: &str) -> Option<&'c str> {
+    curls.iter().rev().find_map(|curl| match curl {
+        Curl::Option {
+            name: option,
+            value,
+        } if option == name => Some(value.as_ref()),
+        _ => None,
+    })
+}
+
+/// Where `-o` saves the body of a transfer, under `--output-dir` when given;
+/// `None` when the body is printed, also with `-o -`. The `#1`, `#2`... of
+/// the file name are filled by [`transfers`](crate::curl::parallel::transfers).
+pub fn output_path(curls: &[Curl]) -> Option<PathBuf> {
+    let file = last_value(curls, "--output").filter(|file| *file != "-")?;
+    Some(match last_value(curls, "--output-dir") {
+        Some(dir) => Path::new(dir).join(file),
+        None => PathBuf::from(file),
+    })
+}
+
+/// Write the body of `response` to `path`, creating the directories leading
+/// to it with `--create-dirs`, after what the file holds when `-C` resumed
+/// the transfer.
+pub fn save_body(curls: &[Curl], response: &Response, path: &Path) -> io::Result<()> {
+    if options::has_flag(curls, "--create-dirs")
+        && let Some(dir) = path.parent()
+    {
+        fs::create_dir_all(dir)?;
+    }
+    if resume_offset(curls) > 0 {
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)?;
+        return file.write_all(&response.body);
+    }
+    fs::write(path, &response.body)
+}
+
+/// The offset `-C` resumes the transfer at: after what the file of `-o`
+/// holds with `-C -`; 0 without `-C`.
+pub fn resume_offset(curls: &[Curl]) -> u64 {
+    ContinueAt::new(curls).map_or(0, |continue_at| {
+        let existing = output_path(curls)
+            .and_then(|path| fs::metadata(path).ok())
+            .map(|meta| meta.len());
+        continue_at.offset(existing)
+    })
+}
+
+/// The header of the `-z` condition of a command and the time it carries,
+/// that of the file when given one; `None` when the file is missing, which
+/// makes curl drop the condition. A header given with `-H` is kept instead.
+fn time_condition(curls: &[Curl]) -> Option<(&'static str, i64)> {
+    let condition = TimeCondition::new(curls)?;
+    let time = match &condition.source {
+        TimeSource::Date(seconds) => *seconds,
+        TimeSource::File(path) => {
+            let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+            modified.duration_since(UNIX_EPOCH).ok()?.as_secs() as i64
+        }
+    };
+    Some((condition.kind.header_name(), time))
+}
+
+/// The `--max-time` or `--connect-timeout` of a command.
+fn timeout(curls: &[Curl], option: &str) -> Option<Duration> {
+    curls.iter().rev().find_map(|curl| match curl {
+        Curl::Option { name, value } if name == option => options::parse_seconds(value),
+        _ => None,
+    })
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "tvos",
+    target_os = "visionos",
+    target_os = "watchos",
+))]
+fn bind_device(client: ClientBuilder, name: &str) -> Result<ClientBuilder, ExecError> {
+    Ok(client.interface(name))
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "tvos",
+    target_os = "visionos",
+    target_os = "watchos",
+)))]
+fn bind_device(_: ClientBuilder, _: &str) -> Result<ClientBuilder, ExecError> {
+    Err(ExecError::Unsupported {
+        option: "--interface",
+        reason: "binding to an interface name is not available on this platform",
+    })
+}
+
+/// Apply `--interface`, refusing the options the client cannot honour
+/// rather than silently sending from somewhere else.
+fn bind(client: ClientBuilder, network: &NetworkOptions) -> Result<ClientBuilder, ExecError> {
+    if network.local_port.is_some() {
+        return Err(ExecError::Unsupported {
+            option: "--local-port",
+            reason: "the HTTP client cannot pick the local port",
+        });
+    }
+    if network.dns_interface.is_some() {
+        return Err(ExecError::Unsupported {
+            option: "--dns-interface",
+            reason: "the HTTP client cannot bind its resolver",
+        });
+    }
+
+    match &network.interface {
+        None => Ok(client),
+        Some(Interface::Device(name)) => bind_device(client, name),
+        Some(Interface::Host(host) | Interface::Any(host)) if host.parse::<IpAddr>().is_ok() => {
+            Ok(client.local_address(host.parse::<IpAddr>().ok()))
+        }
+        Some(Interface::Any(name)) => bind_device(client, name),
+        Some(Interface::Host(_)) => Err(ExecError::Unsupported {
+            option: "--interface",
+            reason: "binding to a host name needs it resolved to a local address first",
+        }),
+    }
+}
+
+/// Apply the `-x` proxy, `--noproxy` and the `--resolve` entries for the
+/// host and port of `url`, refusing the routing the client cannot honour
+/// rather than silently connecting somewhere else.
+fn route(
+    mut client: ClientBuilder,
+    routing: &Routing,
+    url: &str,
+) -> Result<ClientBuilder, ExecError> {
+    if !routing.connect_to.is_empty() {
+        return Err(ExecError::Unsupported {
+            option: "--connect-to",
+            reason: "the HTTP client only connects to the host of the URL",
+        });
+    }
+    if routing.unix_socket.is_some() {
+        return Err(ExecError::Unsupported {
+            option: "--unix-socket",
+            reason: "the HTTP client only connects over TCP",
+        });
+    }
+    if let Some(proxy) = &routing.proxy {
+        if !matches!(proxy.scheme, ProxyScheme::Http | ProxyScheme::Https) {
+            return Err(ExecError::Unsupported {
+                option: "--proxy",
+                reason: "the HTTP client is built without SOCKS proxies",
+            });
+        }
+        let no_proxy = reqwest::NoProxy::from_string(&routing.noproxy.join(","));
+        client = client.proxy(reqwest::Proxy::all(proxy.to_string())?.no_proxy(no_proxy));
+    }
+
+    let port = Url::parse(url)
+        .ok()
+        .and_then(|url| url.port_or_known_default());
+    for resolve in &routing.resolve {
+        if resolve.host == "*" {
+            return Err(ExecError::Unsupported {
+                option: "--resolve",
+                reason: "the HTTP client only overrides named hosts",
+            });
+        }
+        // The port of the URL is the one connected to.
+        let addresses: Vec<SocketAddr> = resolve
+            .addresses
+            .iter()
+            .filter_map(|address| address.parse::<IpAddr>().ok())
+            .map(|address| SocketAddr::new(address, 0))
+            .collect();
+        if Some(resolve.port) == port && !addresses.is_empty() {
+            client = client.resolve_to_addrs(&resolve.host, &addresses);
+        }
+    }
+    Ok(client)
+}
+
+/// The cookie options of a command.
+#[derive(Debug, Default)]
+struct CookieOptions<'c> {
+    /// The `-b name=value` cookies, sent as they are.
+    inline: Vec<&'c str>,
+    /// The `-b` files to read cookies from.
+    files: Vec<&'c str>,
+    /// The `-c` file the cookies are written to, the last one winning.
+    jar: Option<&'c str>,
+    /// Whether `-b` or `-c` turn on the cookie engine, which keeps the
+    /// cookies the server sets.
+    enable_engine: bool,
+}
+
+impl<'c> CookieOptions<'c> {
+    fn new(curls: &'c [Curl]) -> Self {
+        let mut options = CookieOptions::default();
+        for curl in curls {
+            let Curl::Option { name, value } = curl else {
+                continue;
+            };
+            match &**name {
+                "--cookie" => {
+                    options.enable_engine = true;
+                    if value.contains('=') {
+                        options.inline.push(value);
+                    } else if !value.is_empty() && value != "-" {
+                        options.files.push(value);
+                    }
+                }
+                "--cookie-jar" => {
+                    options.enable_engine = true;
+                    options.jar = Some(value);
+                }
+                _ => {}
+            }
+        }
+        options
+    }
+}
+
+/// The cookie jar of a [`Session`], handed to the HTTP client so that the
+/// cookies set along redirects are kept too.
+#[derive(Debug, Default)]
+struct SharedJar(Mutex<CookieJar>);
+
+impl SharedJar {
+    fn lock(&self) -> std::sync::MutexGuard<'_, CookieJar> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl CookieStore for SharedJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let mut jar = self.lock();
+        for header in cookie_headers {
+            if let Ok(header) = header.to_str() {
+                jar.set_cookie(header, url);
+            }
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let header = self.lock().header(url)?;
+        HeaderValue::from_str(&header).ok()
+    }
+}
+
+/// Requests performed one after another, such as the parts of a command
+/// separated by `--next` or the commands of a script, sharing a cookie jar.
+///
+/// As with curl, the cookies the server sets are kept once a command
+/// turned the cookie engine on with `-b` or `-c`, and sent with the later
+/// requests they match; `-b file` adds the cookies of a Netscape cookie
+/// file to the jar and `-c file` writes the jar to one after the request.
+#[derive(Debug, Default)]
+pub struct Session {
+    jar: Arc<SharedJar>,
+    cookie_engine: bool,
+    confirmation: Confirmation,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only send the destructive requests `confirmation` allows; all of
+    /// them are sent by default.
+    pub fn with_confirmation(mut self, confirmation: Confirmation) -> Self {
+        self.confirmation = confirmation;
+        self
+    }
+
+    /// The cookies kept so far.
+    pub fn cookie_jar(&self) -> CookieJar {
+        self.jar.lock().clone()
+    }
+
+    /// Build the client and request of `curls` as [`prepare`] does, with
+    /// the cookies of the session.
+    pub fn prepare(&mut self, curls: &[Curl]) -> Result<(Client, Request), ExecError> {
+        FailMode::new(curls)?;
+        if curls.iter().any(|curl| matches!(curl, Curl::Form(_))) {
+            return Err(ExecError::Unsupported {
+                option: "--form",
+                reason: "multipart bodies cannot be sent yet",
+            });
+        }
+        let parts = crate::request::Request::try_from(curls)?;
+
+        let mut client = Client::builder()
+            .danger_accept_invalid_certs(parts.insecure)
+            .redirect(if parts.follow_redirects {
+                Policy::limited(50)
+            } else {
+                Policy::none()
+            })
+            // curl has no overall timeout by default, unlike the blocking client.
+            .timeout(timeout(curls, "--max-time"));
+        if let Some(connect_timeout) = timeout(curls, "--connect-timeout") {
+            client = client.connect_timeout(connect_timeout);
+        }
+        let transfer = TransferSettings::new(curls);
+        if transfer.no_keepalive {
+            client = client.tcp_keepalive(None);
+        } else if let Some(keepalive_time) = transfer.keepalive_time {
+            client = client.tcp_keepalive(keepalive_time);
+        }
+        let cookies = CookieOptions::new(curls);
+        for path in &cookies.files {
+            // curl starts without the cookies of a file it cannot read.
+            if let Ok(text) = fs::read_to_string(path) {
+                let mut jar = self.jar.lock();
+                for cookie in CookieJar::parse(&text).cookies() {
+                    jar.insert(cookie.clone());
+                }
+            }
+        }
+        self.cookie_engine |= cookies.enable_engine;
+        if self.cookie_engine {
+            client = client.cookie_provider(self.jar.clone());
+        }
+        let client = bind(client, &NetworkOptions::new(curls))?;
+        let client = route(client, &parts.routing, &parts.url)?.build()?;
+
+        let method = reqwest::Method::from_bytes(parts.method.as_str().as_bytes())
+            .map_err(|_| ExecError::InvalidMethod(parts.method.to_string()))?;
+        let mut request = client.request(method, &parts.url);
+        // The `-b` cookies are sent below, along with those of the jar.
+        let cookie_header = Headers::from(curls).contains("Cookie");
+        for (name, value) in parts.headers.iter() {
+            if cookie_header || !name.eq_ignore_ascii_case("Cookie") {
+                request = request.header(name, value);
+            }
+        }
+        if !cookies.inline.is_empty() && !cookie_header {
+            // The client leaves the jar out once a Cookie header is given.
+            let mut header = cookies.inline.join("; ");
+            let url = Url::parse(&parts.url).ok();
+            let stored = url
+                .filter(|_| self.cookie_engine)
+                .and_then(|url| self.jar.lock().header(&url));
+            if let Some(stored) = stored {
+                header = format!("{}; {}", header, stored);
+            }
+            request = request.header(COOKIE, header);
+        }
+        if let Some(range) = ContinueAt::range(resume_offset(curls))
+            && !parts.headers.contains("Range")
+        {
+            request = request.header(RANGE, range);
+        }
+        if let Some((name, time)) = time_condition(curls)
+            && !parts.headers.contains(name)
+        {
+            request = request.header(name, http_date(time));
+        }
+        match &parts.auth {
+            Some(Auth::Basic(credentials)) => {
+                request = request.basic_auth(&credentials.user, credentials.password.as_ref());
+            }
+            Some(Auth::Bearer { token }) => request = request.bearer_auth(token),
+            Some(auth) => {
+                return Err(ExecError::Unsupported {
+                    option: auth.flag(),
+                    reason: "only basic and bearer authentication can be sent",
+                });
+            }
+            None => {}
+        }
+        // curl sends `-d` bodies as a form unless told otherwise.
+        if parts.body.is_some() && !parts.headers.contains("Content-Type") {
+            request = request.header(CONTENT_TYPE, FORM_URLENCODED);
+        }
+        if let Some(body) = parts.body {
+            request = request.body(body);
+        } else if let Some(path) = &parts.upload_file {
+            let body = fs::read(path).map_err(|source| ExecError::ReadUpload {
+                path: path.into(),
+                source,
+            })?;
+            request = request.body(body);
+        }
+        let request = request.build()?;
+        Ok((client, request))
+    }
+
+    /// [`Session::prepare`], refusing the requests the confirmation does
+    /// not allow.
+    fn prepare_confirmed(&mut self, curls: &[Curl]) -> Result<(Client, Request), ExecError> {
+        let (client, request) = self.prepare(curls)?;
+        let (method, url) = (request.method().as_str(), request.url().as_str());
+        if !self.confirmation.allows(method, url) {
+            return Err(ExecError::NotConfirmed {
+                method: method.into(),
+                url: url.into(),
+                policy: self.confirmation.policy,
+            });
+        }
+        Ok((client, request))
+    }
+
+    /// Write the cookies of the session to the `-c` file of `curls`.
+    fn save_cookies(&self, curls: &[Curl]) -> Result<(), ExecError> {
+        if let Some(path) = CookieOptions::new(curls).jar {
+            fs::write(path, self.cookie_jar().to_netscape()).map_err(|source| {
+                ExecError::SaveCookies {
+                    path: path.into(),
+                    source,
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Perform the request described by `curls` as [`execute_with_log`]
+    /// does, keeping the cookies it gets.
+    pub fn execute_with_log(
+        &mut self,
+        curls: &[Curl],
+        log: &mut dyn Write,
+    ) -> Result<Response, ExecError> {
+        let (client, request) = self.prepare_confirmed(curls)?;
+        let response = send(&client, request, curls, log)?;
+        self.save_cookies(curls)?;
+        Ok(response)
+    }
+
+    /// Perform `transfers` as curl does with `settings`: one after the
+    /// other, or with `-Z` up to `--parallel-max` at a time, the responses
+    /// and the `-v` logs in the order of the transfers.
+    ///
+    /// Every transfer opens a connection of its own, as with
+    /// `--parallel-immediate`.
+    pub fn execute_all(
+        &mut self,
+        transfers: &[Vec<Curl>],
+        settings: &ParallelSettings,
+        log: &mut dyn Write,
+    ) -> Vec<Result<Response, ExecError>> {
+        if !settings.parallel {
+            return transfers
+                .iter()
+                .map(|curls| self.execute_with_log(curls, log))
+                .collect();
+        }
+
+        let (jobs, queue) = mpsc::channel();
+        for (i, (curls, prepared)) in transfers
+            .iter()
+            .map(|curls| (curls, self.prepare_confirmed(curls)))
+            .enumerate()
+        {
+            let _ = jobs.send((i, curls, prepared));
+        }
+        drop(jobs);
+        let queue = Mutex::new(queue);
+        let mut sent: Vec<_> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..settings.concurrency().min(transfers.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let job = queue.lock().unwrap_or_else(PoisonError::into_inner).recv();
+                            let Ok((i, curls, prepared)) = job else {
+                                return done;
+                            };
+                            let mut logged = Vec::new();
+                            let response = prepared.and_then(|(client, request)| {
+                                send(&client, request, curls, &mut logged)
+                            });
+                            done.push((i, response, logged));
+                        }
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("transfers do not panic"))
+                .collect()
+        });
+        sent.sort_by_key(|(i, _, _)| *i);
+        sent.into_iter()
+            .zip(transfers)
+            .map(|((_, response, logged), curls)| {
+                let _ = log.write_all(&logged);
+                let response = response?;
+                self.save_cookies(curls)?;
+                Ok(response)
+            })
+            .collect()
+    }
+}
+
+/// Send `request` and read its whole response, logging both with `-v`.
+fn send(
+    client: &Client,
+    request: Request,
+    curls: &[Curl],
+    log: &mut dyn Write,
+) -> Result<Response, ExecError> {
+    let verbose = TraceSettings::new(curls).verbose;
+    let speed_limit = TransferSettings::new(curls).speed_limit;
+    if verbose {
+        let _ = log_request(log, &request);
+    }
+    let get = request.method() == reqwest::Method::GET;
+    let response = client.execute(request)?;
+
+    let status = response.status();
+    let version = format!("{:?}", response.version());
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+            (name.as_str().to_owned(), value)
+        })
+        .collect();
+    let body = match speed_limit {
+        Some(limit) => read_body_limited(response, limit)?,
+        None => response.bytes()?.to_vec(),
+    };
+
+    let mut response = Response {
+        status: status.as_u16(),
+        reason: status.canonical_reason(),
+        version,
+        headers,
+        body,
+    };
+    if verbose {
+        let _ = log_response(log, &response);
+    }
+    let offset = resume_offset(curls);
+    if get && offset > 0 && status == reqwest::StatusCode::OK {
+        // Like curl, a whole resource as long as the offset was already
+        // transferred in full.
+        if response.body.len() as u64 != offset {
+            return Err(ExecError::CannotResume);
+        }
+        response.body.clear();
+    }
+    Ok(response)
+}
+
+/// The parts of a command separated by `--next`, each one a request of its
+/// own.
+pub fn next_parts<'c, 'a>(curls: &'c [Curl<'a>]) -> impl Iterator<Item = &'c [Curl<'a>]> {
+    curls
+        .split(options::is_next)
+        .filter(|part| !part.is_empty())
+}
+
+/// Build the client and request curl would use for `curls`, without sending
+/// anything.
+///
+/// The method, headers, body, `-u`, `-b`, `-k`, `-L`, `--max-time`,
+/// `--connect-timeout`, `--keepalive-time` and `--interface` are honoured,
+/// everything else is ignored.
+pub fn prepare(curls: &[Curl]) -> Result<(Client, Request), ExecError> {
+    Session::new().prepare(curls)
+}
+
+/// Tracks the download speed against `--speed-limit`, one `--speed-time`
+/// window at a time.
+#[derive(Debug, Clone, Copy)]
+struct SpeedCheck {
+    limit: SpeedLimit,
+    window_start: Instant,
+    window_bytes: u64,
+}
+
+impl SpeedCheck {
+    fn new(limit: SpeedLimit, now: Instant) -> Self {
+        SpeedCheck {
+            limit,
+            window_start: now,
+            window_bytes: 0,
+        }
+    }
+
+    /// When the current window closes and the speed gets checked.
+    fn deadline(&self) -> Instant {
+        self.window_start + self.limit.time
+    }
+
+    /// Account for `bytes` received at `now`, failing once a whole window
+    /// averaged less than the limit.
+    fn record(&mut self, bytes: usize, now: Instant) -> Result<(), ExecError> {
+        self.window_bytes += bytes as u64;
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed < self.limit.time {
+            return Ok(());
+        }
+
+        let speed = self.window_bytes as f64 / elapsed.as_secs_f64();
+        if speed < self.limit.bytes_per_second as f64 {
+            return Err(ExecError::TooSlow(self.limit));
+        }
+        *self = SpeedCheck::new(self.limit, now);
+        Ok(())
+    }
+}
+
+/// Read the body in chunks so a stalled transfer can be given up on.
+///
+/// The chunks are read on a helper thread, since a blocking read would
+/// never return to check the speed. On abort the thread ends with the
+/// connection.
+fn read_body_limited(
+    mut response: reqwest::blocking::Response,
+    limit: SpeedLimit,
+) -> Result<Vec<u8>, ExecError> {
+    let (sender, receiver) = mpsc::sync_channel::<io::Result<Vec<u8>>>(16);
+    std::thread::spawn(move || {
+        let mut buffer = [0; 16 * 1024];
+        loop {
+            let chunk = match response.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(len) => Ok(buffer[..len].to_vec()),
+                Err(e) => Err(e),
+            };
+            let failed = chunk.is_err();
+            if sender.send(chunk).is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    let mut body = Vec::new();
+    let mut check = SpeedCheck::new(limit, Instant::now());
+    loop {
+        let wait = check.deadline().saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(wait) {
+            Ok(Ok(chunk)) => {
+                body.extend_from_slice(&chunk);
+                check.record(chunk.len(), Instant::now())?;
+            }
+            Ok(Err(e)) => return Err(ExecError::Io(e)),
+            Err(RecvTimeoutError::Timeout) => check.record(0, Instant::now())?,
+            Err(RecvTimeoutError::Disconnected) => return Ok(body),
+        }
+    }
+}
+
+/// Perform the request described by `curls` and read the whole response.
+pub fn execute(curls: &[Curl]) -> Result<Response, ExecError> {
+    execute_with_log(curls, &mut io::sink())
+}
+
+/// Write the request line and headers about to be sent, as `curl -v` does.
+fn log_request(log: &mut dyn Write, request: &Request) -> io::Result<()> {
+    let url = request.url();
+    let target = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_owned(),
+    };
+    writeln!(
+        log,
+        "> {} {} {:?}",
+        request.method(),
+        target,
+        request.version()
+    )?;
+    if let Some(host) = url.host_str() {
+        match url.port() {
+            Some(port) => writeln!(log, "> Host: {}:{}", host, port)?,
+            None => writeln!(log, "> Host: {}", host)?,
+        }
+    }
+    for (name, value) in request.headers() {
+        writeln!(
+            log,
+            "> {}: {}",
+            name,
+            String::from_utf8_lossy(value.as_bytes())
+        )?;
+    }
+    writeln!(log, ">")
+}
+
+fn log_response(log: &mut dyn Write, response: &Response) -> io::Result<()> {
+    writeln!(log, "< {}", response.status_line())?;
+    for (name, value) in &response.headers {
+        writeln!(log, "< {}: {}", name, value)?;
+    }
+    writeln!(log, "<")
+}
+
+/// Like [`execute`], writing the request and response headers to `log` the
+/// way curl does when the command has `-v`.
+pub fn execute_with_log(curls: &[Curl], log: &mut dyn Write) -> Result<Response, ExecError> {
+    Session::new().execute_with_log(curls, log)
+}
+
+#[cfg(test)]
+// The tests send their requests to a local server.
+#[allow(clippy::disallowed_types)]
+mod tests {
+    use super::*;
+    use crate::curl::parallel;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    #[rstest]
+    fn test_prepare() {
+        let curls = curl_cmd_parse(
+            r#"curl 'https://example.com/api' -H 'Accept: */*' -d 'a=1' -u 'me:secret' -m 2.5"#,
+        )
+        .unwrap();
+        let (_, request) = prepare(&curls).unwrap();
+
+        assert_eq!(request.method(), "POST");
+        assert_eq!(request.url().as_str(), "https://example.com/api");
+        assert_eq!(request.headers()["accept"], "*/*");
+        assert_eq!(request.headers()["authorization"], "Basic bWU6c2VjcmV0");
+        assert_eq!(request.body().unwrap().as_bytes(), Some(&b"a=1"[..]));
+        assert_eq!(
+            timeout(&curls, "--max-time"),
+            Some(Duration::from_millis(2500))
+        );
+    }
+
+    #[rstest]
+    fn test_prepare_request_semantics() {
+        let curls = curl_cmd_parse(
+            "curl 'https://example.com/s' -G --data-urlencode 'q=a b' -A 'bot/1' -e 'https://b.com'",
+        )
+        .unwrap();
+        let (_, request) = prepare(&curls).unwrap();
+        assert_eq!(request.method(), "GET");
+        assert_eq!(request.url().as_str(), "https://example.com/s?q=a+b");
+        assert_eq!(request.headers()["user-agent"], "bot/1");
+        assert_eq!(request.headers()["referer"], "https://b.com");
+        assert!(request.body().is_none());
+
+        let curls = curl_cmd_parse("curl 'https://example.com/a' -I").unwrap();
+        assert_eq!(prepare(&curls).unwrap().1.method(), "HEAD");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "notes").unwrap();
+        let command = format!("curl 'https://example.com/a' -T '{}'", path.display());
+        let (_, request) = prepare(&curl_cmd_parse(&command).unwrap()).unwrap();
+        assert_eq!(request.method(), "PUT");
+        assert_eq!(request.body().unwrap().as_bytes(), Some(&b"notes"[..]));
+
+        let command = format!(
+            "curl 'https://example.com/a' -T '{}'",
+            dir.path().join("missing").display()
+        );
+        let e = prepare(&curl_cmd_parse(&command).unwrap()).unwrap_err();
+        assert_eq!(e.exit_code(), 26);
+
+        let curls = curl_cmd_parse("curl 'https://example.com/a' -I -d 'a=1'").unwrap();
+        let e = prepare(&curls).unwrap_err();
+        assert_eq!(e.exit_code(), USAGE_EXIT_CODE);
+    }
+
+    #[rstest]
+    #[case("-sSf", FailMode::Fail)]
+    #[case("--fail", FailMode::Fail)]
+    #[case("--fail-with-body", FailMode::FailWithBody)]
+    #[case("--fail-early", FailMode::Off)]
+    fn test_fail_mode(#[case] flag: &str, #[case] expected: FailMode) {
+        let input = format!("curl 'https://example.com/a' {}", flag);
+        let curls = curl_cmd_parse(&input).unwrap();
+        assert_eq!(FailMode::new(&curls).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(FailMode::Off, 404, false, true)]
+    #[case(FailMode::Fail, 399, false, true)]
+    #[case(FailMode::Fail, 404, true, false)]
+    #[case(FailMode::FailWithBody, 500, true, true)]
+    fn test_fail_mode_status(
+        #[case] mode: FailMode,
+        #[case] status: u16,
+        #[case] fails: bool,
+        #[case] shown: bool,
+    ) {
+        assert_eq!(mode.fails(status), fails);
+        assert_eq!(mode.shows_response(status), shown);
+    }
+
+    #[rstest]
+    #[case("curl 'https://a.com/x'", None)]
+    #[case("curl 'https://a.com/x' -o -", None)]
+    #[case("curl 'https://a.com/x' -o 'x.html'", Some("x.html"))]
+    #[case(
+        "curl 'https://a.com/x' -o 'a' -o 'x.html' --output-dir 'out'",
+        Some("out/x.html")
+    )]
+    fn test_output_path(#[case] command: &str, #[case] expected: Option<&str>) {
+        let curls = curl_cmd_parse(command).unwrap();
+        assert_eq!(output_path(&curls), expected.map(PathBuf::from));
+    }
+
+    #[rstest]
+    fn test_save_body() {
+        let dir = tempfile::tempdir().unwrap();
+        let response = Response {
+            status: 200,
+            reason: Some("OK"),
+            version: "HTTP/1.1".into(),
+            headers: Vec::new(),
+            body: b"saved".to_vec(),
+        };
+        let path = dir.path().join("a/b.txt");
+        let curls = curl_cmd_parse("curl 'https://a.com/x'").unwrap();
+        assert!(save_body(&curls, &response, &path).is_err());
+        let curls = curl_cmd_parse("curl 'https://a.com/x' --create-dirs").unwrap();
+        save_body(&curls, &response, &path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"saved");
+    }
+
+    #[rstest]
+    fn test_prepare_time_condition() {
+        let curls = curl_cmd_parse("curl 'https://a.com/x' -z '-6 Nov 1994 08:49:37'").unwrap();
+        let (_, request) = prepare(&curls).unwrap();
+        assert_eq!(
+            request.headers()["if-unmodified-since"],
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+
+        let curls = curl_cmd_parse(
+            "curl 'https://a.com/x' -z '19941106' -H 'If-Modified-Since: Thu, 01 Jan 2004 00:00:00 GMT'",
+        )
+        .unwrap();
+        let (_, request) = prepare(&curls).unwrap();
+        assert_eq!(
+            request.headers()["if-modified-since"],
+            "Thu, 01 Jan 2004 00:00:00 GMT"
+        );
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let input = format!("curl 'https://a.com/x' -z {}", file.path().display());
+        let (_, request) = prepare(&curl_cmd_parse(&input).unwrap()).unwrap();
+        assert!(request.headers().contains_key("if-modified-since"));
+        let (_, request) =
+            prepare(&curl_cmd_parse("curl 'https://a.com/x' -z missing.html").unwrap()).unwrap();
+        assert!(!request.headers().contains_key("if-modified-since"));
+    }
+
+    #[rstest]
+    fn test_prepare_conflicting_fail_options() {
+        let curls = curl_cmd_parse(r#"curl 'https://example.com/a' -f --fail-with-body"#).unwrap();
+        assert!(matches!(
+            prepare(&curls),
+            Err(ExecError::ConflictingOptions("--fail", "--fail-with-body"))
+        ));
+    }
+
+    #[rstest]
+    #[case("--local-port 4000", "--local-port")]
+    #[case("--dns-interface eth0", "--dns-interface")]
+    #[case("--interface host!example.com", "--interface")]
+    #[case("--connect-to example.com:443:other.com:443", "--connect-to")]
+    #[case("--unix-socket /run/api.sock", "--unix-socket")]
+    #[case("-x socks5://127.0.0.1:1080", "--proxy")]
+    #[case("--resolve '*:443:127.0.0.1'", "--resolve")]
+    fn test_prepare_unsupported_network_options(#[case] options: &str, #[case] expected: &str) {
+        let input = format!("curl 'https://example.com/a' {}", options);
+        let curls = curl_cmd_parse(&input).unwrap();
+        let Err(ExecError::Unsupported { option, .. }) = prepare(&curls) else {
+            panic!("{} should not be supported", options);
+        };
+        assert_eq!(option, expected);
+    }
+
+    #[rstest]
+    fn test_prepare_auth() {
+        let curls = curl_cmd_parse("curl 'https://example.com/a' --oauth2-bearer 't0k'").unwrap();
+        let (_, request) = prepare(&curls).unwrap();
+        assert_eq!(request.headers()["authorization"], "Bearer t0k");
+
+        let curls = curl_cmd_parse("curl 'https://example.com/a' --digest -u 'me:pw'").unwrap();
+        let Err(ExecError::Unsupported { option, .. }) = prepare(&curls) else {
+            panic!("--digest should not be supported");
+        };
+        assert_eq!(option, "--digest");
+    }
+
+    #[rstest]
+    fn test_next_parts() {
+        let curls =
+            curl_cmd_parse("curl https://a.com/login -c j --next https://a.com/me -b j -: ")
+                .unwrap();
+        let parts: Vec<usize> = next_parts(&curls).map(<[Curl]>::len).collect();
+        assert_eq!(parts, vec![2, 2]);
+    }
+
+    #[rstest]
+    fn test_prepare_cookies() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("cookies.txt");
+        std::fs::write(
+            &file,
+            "example.com\tFALSE\t/\tFALSE\t0\tsid\tabc\nother.com\tFALSE\t/\tFALSE\t0\tx\t1\n",
+        )
+        .unwrap();
+        let input = format!(
+            "curl 'https://example.com/a' -b 'theme=dark' -b '{}'",
+            file.display()
+        );
+        let mut session = Session::new();
+        let (_, request) = session.prepare(&curl_cmd_parse(&input).unwrap()).unwrap();
+        assert_eq!(request.headers()["cookie"], "theme=dark; sid=abc");
+        assert_eq!(session.cookie_jar().cookies().len(), 2);
+
+        // A missing file is no error, as with curl.
+        let curls = curl_cmd_parse("curl 'https://example.com/a' -b missing.txt").unwrap();
+        let (_, request) = prepare(&curls).unwrap();
+        assert!(request.headers().get("cookie").is_none());
+    }
+
+    #[rstest]
+    #[case("-c '{jar}'", "sid=42")]
+    #[case("", "")]
+    fn test_session_shares_cookies(#[case] options: &str, #[case] sent: &str) {
+        let (port, server) = serve(vec![
+            b"HTTP/1.1 302 Found\r\nLocation: /home\r\nSet-Cookie: sid=42; Path=/; HttpOnly\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nme",
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let jar = dir.path().join("jar.txt");
+        let options = options.replace("{jar}", &jar.display().to_string());
+        let login = format!("curl 'http://127.0.0.1:{}/login' -L {}", port, options);
+        let me = format!("curl 'http://127.0.0.1:{}/me'", port);
+
+        let mut session = Session::new();
+        for command in [login, me] {
+            let response = session
+                .execute_with_log(&curl_cmd_parse(&command).unwrap(), &mut io::sink())
+                .unwrap();
+            assert_eq!(response.status, 200);
+        }
+        let requests = server.join().unwrap();
+        let cookie = |request: &str| {
+            request
+                .lines()
+                .find_map(|line| line.strip_prefix("cookie: "))
+                .unwrap_or_default()
+                .to_string()
+        };
+        assert_eq!(cookie(&requests[1]), sent, "{}", requests[1]);
+        assert_eq!(cookie(&requests[2]), sent, "{}", requests[2]);
+        if !sent.is_empty() {
+            let saved = std::fs::read_to_string(&jar).unwrap();
+            assert!(
+                saved.contains("#HttpOnly_127.0.0.1\tFALSE\t/\tFALSE\t0\tsid\t42\n"),
+                "{}",
+                saved
+            );
+        }
+    }
+
+    #[rstest]
+    fn test_prepare_unsupported_form() {
+        let curls = curl_cmd_parse("curl 'https://example.com/a' -F 'file=@a.png'").unwrap();
+        let Err(ExecError::Unsupported { option, .. }) = prepare(&curls) else {
+            panic!("-F should not be supported");
+        };
+        assert_eq!(option, "--form");
+    }
+
+    #[rstest]
+    fn test_execute_bound_to_local_address() {
+        let (port, server) = serve_once();
+        let input = format!(
+            "curl 'http://127.0.0.1:{}/a' --interface host!127.0.0.1",
+            port
+        );
+        let response = execute(&curl_cmd_parse(&input).unwrap()).unwrap();
+        server.join().unwrap();
+        assert_eq!(response.status, 201);
+    }
+
+    #[rstest]
+    fn test_speed_check() {
+        let limit = SpeedLimit {
+            bytes_per_second: 100,
+            time: Duration::from_secs(2),
+        };
+        let start = Instant::now();
+        let mut check = SpeedCheck::new(limit, start);
+
+        assert!(check.record(50, start + Duration::from_secs(1)).is_ok());
+        assert!(check.record(150, start + Duration::from_secs(2)).is_ok());
+        assert_eq!(check.deadline(), start + Duration::from_secs(4));
+        assert!(check.record(100, start + Duration::from_secs(3)).is_ok());
+        assert!(matches!(
+            check.record(0, start + Duration::from_secs(4)),
+            Err(ExecError::TooSlow(_))
+        ));
+    }
+
+    #[rstest]
+    fn test_execute_aborts_stalled_download() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.read(&mut [0; 1024]);
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 1000\r\n\r\nslow");
+            std::thread::sleep(Duration::from_secs(3));
+        });
+
+        let input = format!(
+            "curl 'http://127.0.0.1:{}/big' --speed-limit 100 --speed-time 0.5",
+            port
+        );
+        let started = Instant::now();
+        let result = execute(&curl_cmd_parse(&input).unwrap());
+        assert!(matches!(result, Err(ExecError::TooSlow(_))), "{:?}", result);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[rstest]
+    fn test_prepare_invalid_method() {
+        let curls = curl_cmd_parse(r#"curl 'https://example.com/a' -X 'NOT VALID'"#).unwrap();
+        assert!(matches!(prepare(&curls), Err(ExecError::InvalidMethod(_))));
+    }
+
+    #[rstest]
+    #[case(
+        confirm::Policy::Ask,
+        "destructive requests need a confirmation, or --yes"
+    )]
+    #[case(confirm::Policy::Deny, "the configuration denies destructive requests")]
+    fn test_session_not_confirmed(#[case] policy: confirm::Policy, #[case] reason: &str) {
+        // Refused before connecting, so nothing has to listen.
+        let curls = curl_cmd_parse("curl 'http://127.0.0.1:9/items/7' -X DELETE").unwrap();
+        let mut session = Session::new().with_confirmation(Confirmation::new(policy));
+        let error = session
+            .execute_with_log(&curls, &mut io::sink())
+            .unwrap_err();
+        assert!(matches!(error, ExecError::NotConfirmed { .. }));
+        assert_eq!(
+            error.to_string(),
+            format!("DELETE http://127.0.0.1:9/items/7 was not sent: {}", reason)
+        );
+    }
+
+    /// Answer one request per connection with each of `responses` in turn,
+    /// returning what was sent.
+    fn serve(responses: Vec<&'static [u8]>) -> (u16, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = [0; 1024];
+                    let len = stream.read(&mut request).unwrap();
+                    stream.write_all(response).unwrap();
+                    String::from_utf8_lossy(&request[..len]).into_owned()
+                })
+                .collect()
+        });
+        (port, server)
+    }
+
+    /// Answer a single request with a fixed response, returning what was sent.
+    fn serve_once() -> (u16, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let len = stream.read(&mut request).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 201 Created\r\nX-Id: 7\r\nContent-Length: 2\r\n\r\nok")
+                .unwrap();
+            String::from_utf8_lossy(&request[..len]).into_owned()
+        });
+        (port, server)
+    }
+
+    #[rstest]
+    fn test_execute_against_local_server() {
+        let (port, server) = serve_once();
+
+        let input = format!("curl 'http://127.0.0.1:{}/items' -X 'PUT' -d 'x=1'", port);
+        let response = execute(&curl_cmd_parse(&input).unwrap()).unwrap();
+        let request = server.join().unwrap();
+
+        assert!(
+            request.starts_with("PUT /items HTTP/1.1\r\n"),
+            "{}",
+            request
+        );
+        assert!(request.ends_with("\r\n\r\nx=1"), "{}", request);
+        assert_eq!(response.status_line(), "HTTP/1.1 201 Created");
+        assert!(response.headers.contains(&("x-id".into(), "7".into())));
+        assert_eq!(response.body, b"ok");
+    }
+
+    #[rstest]
+    #[case("-d 'x=1'", "content-type: application/x-www-form-urlencoded\r\n")]
+    #[case(
+        "-d '{}' -H 'Content-Type: application/json'",
+        "content-type: application/json\r\n"
+    )]
+    #[case("--json '{}'", "content-type: application/json\r\n")]
+    fn test_execute_content_type(#[case] options: &str, #[case] sent: &str) {
+        let (port, server) = serve_once();
+
+        let input = format!("curl 'http://127.0.0.1:{}/items' {}", port, options);
+        execute(&curl_cmd_parse(&input).unwrap()).unwrap();
+        let request = server.join().unwrap().to_ascii_lowercase();
+
+        assert_eq!(request.matches("content-type:").count(), 1, "{}", request);
+        assert!(request.contains(sent), "{}", request);
+    }
+
+    #[rstest]
+    fn test_execute_resolve() {
+        let (port, server) = serve_once();
+
+        let input = format!(
+            "curl 'http://api.invalid:{0}/items' --resolve 'api.invalid:{0}:127.0.0.1'",
+            port
+        );
+        execute(&curl_cmd_parse(&input).unwrap()).unwrap();
+        let request = server.join().unwrap();
+
+        assert!(
+            request.starts_with("GET /items HTTP/1.1\r\n"),
+            "{}",
+            request
+        );
+        assert!(
+            request.contains(&format!("host: api.invalid:{}\r\n", port)),
+            "{}",
+            request
+        );
+    }
+
+    #[rstest]
+    fn test_execute_proxy() {
+        let (port, server) = serve_once();
+
+        let input = format!("curl 'http://api.invalid/items' -x '127.0.0.1:{}'", port);
+        execute(&curl_cmd_parse(&input).unwrap()).unwrap();
+        let request = server.join().unwrap();
+
+        assert!(
+            request.starts_with("GET http://api.invalid/items HTTP/1.1\r\n"),
+            "{}",
+            request
+        );
+    }
+
+    #[rstest]
+    fn test_execute_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("f.txt");
+        fs::write(&path, "abc").unwrap();
+        let (port, server) = serve(vec![
+            b"HTTP/1.1 206 Partial Content\r\nContent-Length: 3\r\n\r\ndef",
+            b"HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\nabcdef",
+            b"HTTP/1.1 200 OK\r\nContent-Length: 7\r\n\r\nabcdefg",
+        ]);
+        let input = format!(
+            "curl 'http://127.0.0.1:{}/f' -C - -o '{}'",
+            port,
+            path.display()
+        );
+        let curls = curl_cmd_parse(&input).unwrap();
+
+        let response = execute(&curls).unwrap();
+        save_body(&curls, &response, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "abcdef");
+        // Already complete: the whole resource is as long as the file.
+        let response = execute(&curls).unwrap();
+        assert_eq!(response.body, b"");
+        save_body(&curls, &response, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "abcdef");
+        assert!(matches!(execute(&curls), Err(ExecError::CannotResume)));
+
+        let requests = server.join().unwrap();
+        assert!(
+            requests[0].contains("range: bytes=3-\r\n"),
+            "{}",
+            requests[0]
+        );
+        assert!(
+            requests[1].contains("range: bytes=6-\r\n"),
+            "{}",
+            requests[1]
+        );
+    }
+
+    #[rstest]
+    #[case("-C 10", Some("bytes=10-"))]
+    #[case("-C 0", None)]
+    #[case("-C -", None)]
+    #[case("-C 10 -H 'Range: bytes=0-99'", Some("bytes=0-99"))]
+    fn test_prepare_range(#[case] options: &str, #[case] expected: Option<&str>) {
+        let input = format!("curl 'https://a.com/f' {}", options);
+        let (_, request) = prepare(&curl_cmd_parse(&input).unwrap()).unwrap();
+        assert_eq!(
+            request
+                .headers()
+                .get(RANGE)
+                .map(|range| range.to_str().unwrap()),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_execute_all_parallel() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // Both requests are answered once both arrived, which they only do
+        // when sent at the same time.
+        let server = std::thread::spawn(move || {
+            let mut streams: Vec<_> = (0..2)
+                .map(|_| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = [0; 1024];
+                    let len = stream.read(&mut request).unwrap();
+                    let request = String::from_utf8_lossy(&request[..len]).into_owned();
+                    let path = request.split(' ').nth(1).unwrap().to_string();
+                    (stream, path)
+                })
+                .collect();
+            for (stream, path) in &mut streams {
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                    path.len(),
+                    path
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        let input = format!(
+            "curl -Z -m 5 http://127.0.0.1:{0}/1 http://127.0.0.1:{0}/2 -v",
+            port
+        );
+        let curls = curl_cmd_parse(&input).unwrap();
+        let transfers = parallel::transfers(&curls);
+        let mut log = Vec::new();
+        let responses =
+            Session::new().execute_all(&transfers, &ParallelSettings::new(&curls), &mut log);
+        server.join().unwrap();
+
+        let bodies: Vec<_> = responses
+            .into_iter()
+            .map(|response| response.unwrap().body)
+            .collect();
+        assert_eq!(bodies, [b"/1", b"/2"]);
+        let log = String::from_utf8(log).unwrap();
+        assert!(log.find("> GET /1 ").unwrap() < log.find("> GET /2 ").unwrap());
+    }
+
+    #[rstest]
+    #[case("-v", true)]
+    #[case("-s", false)]
+    fn test_execute_verbose_log(#[case] flag: &str, #[case] logged: bool) {
+        let (port, server) = serve_once();
+        let input = format!(
+            "curl 'http://127.0.0.1:{}/a?q=1' -H 'X-A: b' {}",
+            port, flag
+        );
+        let mut log = Vec::new();
+        execute_with_log(&curl_cmd_parse(&input).unwrap(), &mut log).unwrap();
+        server.join().unwrap();
+
+        let log = String::from_utf8(log).unwrap();
+        if logged {
+            let expected = format!(
+                "> GET /a?q=1 HTTP/1.1\n> Host: 127.0.0.1:{}\n> x-a: b\n>\n< HTTP/1.1 201 Created\n",
+                port
+            );
+            assert!(log.starts_with(&expected), "{}", log);
+            assert!(log.contains("< x-id: 7\n"), "{}", log);
+        } else {
+            assert!(log.is_empty());
+        }
+    }
+}
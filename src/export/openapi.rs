@@ -0,0 +1,582 @@
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+use crate::curl::form::FormValue;
+use crate::curl::parser::Curl;
+use crate::request::Request;
+use crate::request::auth::Auth;
+use crate::request::origin::Origin;
+use crate::request::schema::{infer_schema, merge_schemas};
+use crate::url::codec::UrlCodec;
+
+/// The version of the specification documents are written in.
+pub const OPENAPI_VERSION: &str = "3.1.0";
+
+/// Methods OpenAPI has operations for.
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Headers describing the client rather than the API, along with those
+/// OpenAPI ignores as parameters (`Accept`, `Content-Type` and
+/// `Authorization`). `Cookie` is described by cookie parameters instead.
+const SKIPPED_HEADERS: &[&str] = &[
+    "accept",
+    "authorization",
+    "cache-control",
+    "connection",
+    "content-length",
+    "content-type",
+    "cookie",
+    "dnt",
+    "host",
+    "origin",
+    "pragma",
+    "priority",
+    "referer",
+    "te",
+    "upgrade-insecure-requests",
+    "user-agent",
+];
+
+fn is_skipped_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SKIPPED_HEADERS.contains(&name.as_str())
+        || name.starts_with("accept-")
+        || name.starts_with("sec-")
+}
+
+/// A skeletal OpenAPI 3.1 document describing the requests of a batch of
+/// commands.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Document {
+    pub openapi: &'static str,
+    pub info: Info,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<Server>,
+    /// The operations under their templated path, then their method.
+    pub paths: IndexMap<String, IndexMap<String, Operation>>,
+    #[serde(skip_serializing_if = "Components::is_empty")]
+    pub components: Components,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Info {
+    pub title: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Server {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct Components {
+    #[serde(rename = "securitySchemes", skip_serializing_if = "IndexMap::is_empty")]
+    pub security_schemes: IndexMap<String, Value>,
+}
+
+impl Components {
+    fn is_empty(&self) -> bool {
+        self.security_schemes.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Operation {
+    /// The method and the templated path, e.g. `GET /users/{userId}`.
+    pub summary: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<Parameter>,
+    #[serde(rename = "requestBody", skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<RequestBody>,
+    /// The names of the security schemes the operation is called with.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub security: Vec<IndexMap<String, Vec<String>>>,
+    /// How many commands sent the operation.
+    #[serde(skip)]
+    samples: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Parameter {
+    pub name: String,
+    /// `path`, `query`, `header` or `cookie`.
+    #[serde(rename = "in")]
+    pub location: &'static str,
+    /// Whether every command sending the operation gave the parameter.
+    pub required: bool,
+    pub schema: Value,
+    /// How many commands gave the parameter.
+    #[serde(skip)]
+    seen: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RequestBody {
+    /// The schema of the body under its media type.
+    pub content: IndexMap<String, Value>,
+}
+
+/// The schema of a parameter value: an integer, a boolean, or a string with
+/// its format, if it has a well-known one.
+fn value_schema(value: &str) -> Value {
+    if !value.is_empty() && value.parse::<i64>().is_ok() {
+        json!({"type": "integer"})
+    } else if value == "true" || value == "false" {
+        json!({"type": "boolean"})
+    } else {
+        infer_schema(&Value::String(value.into()))
+    }
+}
+
+/// Whether a path segment is an identifier: a number or a UUID.
+fn is_identifier(segment: &str) -> bool {
+    !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit())
+        || infer_schema(&Value::String(segment.into()))["format"] == "uuid"
+}
+
+/// The path of `url` with its identifiers replaced by parameters named
+/// after the segment before them, e.g. `/users/42` gives `/users/{userId}`,
+/// along with the parameters.
+fn templated_path(url: &str) -> (String, Vec<Parameter>) {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let path = rest.find('/').map_or("", |slash| &rest[slash + 1..]);
+
+    let mut templated = String::new();
+    let mut parameters: Vec<Parameter> = Vec::new();
+    let mut previous: Option<&str> = None;
+    for segment in path.split('/') {
+        templated.push('/');
+        if !is_identifier(segment) {
+            templated.push_str(segment);
+            previous = Some(segment).filter(|segment| !segment.is_empty());
+            continue;
+        }
+        let mut name = match previous {
+            Some(resource) => {
+                let resource = resource.strip_suffix('s').unwrap_or(resource);
+                format!("{}Id", resource)
+            }
+            None => "id".into(),
+        };
+        let base = name.clone();
+        let mut n = 1;
+        while parameters.iter().any(|parameter| parameter.name == name) {
+            n += 1;
+            name = format!("{}{}", base, n);
+        }
+        templated.push_str(&format!("{{{}}}", name));
+        parameters.push(Parameter {
+            name,
+            location: "path",
+            required: true,
+            schema: value_schema(segment),
+            seen: 1,
+        });
+        previous = None;
+    }
+    (templated, parameters)
+}
+
+/// The query, header and cookie parameters of `request`.
+fn parameters(request: &Request) -> Vec<Parameter> {
+    let parameter = |name: &str, location, value: &str| Parameter {
+        name: name.into(),
+        location,
+        required: true,
+        schema: value_schema(value),
+        seen: 1,
+    };
+    let codec = UrlCodec::form();
+    let query = request
+        .url
+        .split('#')
+        .next()
+        .and_then(|url| url.split_once('?'))
+        .map_or("", |(_, query)| query);
+    let mut parameters: Vec<Parameter> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            parameter(&codec.decode(name), "query", &codec.decode(value))
+        })
+        .collect();
+    for (name, value) in request.headers.iter() {
+        if !is_skipped_header(name) {
+            parameters.push(parameter(name, "header", value));
+        }
+    }
+    for cookie in request.headers.cookies() {
+        parameters.push(parameter(&cookie.name, "cookie", &cookie.value));
+    }
+    parameters
+}
+
+/// The media type and schema of the body of `request`, if it has one.
+fn body_schema(request: &Request) -> Option<(String, Value)> {
+    if !request.form.is_empty() {
+        let properties: Map<String, Value> = request
+            .form
+            .iter()
+            .map(|field| {
+                let schema = match field.value {
+                    FormValue::File(_) => json!({
+                        "type": "string",
+                        "contentMediaType": field
+                            .content_type
+                            .as_deref()
+                            .unwrap_or("application/octet-stream"),
+                    }),
+                    _ => json!({"type": "string"}),
+                };
+                (field.name.clone(), schema)
+            })
+            .collect();
+        return Some((
+            "multipart/form-data".into(),
+            json!({"type": "object", "properties": properties}),
+        ));
+    }
+    let body = request.body.as_deref()?;
+    let content_type = request.headers.get("Content-Type").map(|value| {
+        value
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    });
+    if let Ok(json) = serde_json::from_str::<Value>(body) {
+        let content_type = content_type.unwrap_or_else(|| "application/json".into());
+        return Some((content_type, infer_schema(&json)));
+    }
+    let content_type = content_type.unwrap_or_else(|| "application/x-www-form-urlencoded".into());
+    if content_type != "application/x-www-form-urlencoded" {
+        return Some((content_type, json!({"type": "string"})));
+    }
+    let codec = UrlCodec::form();
+    let properties: Map<String, Value> = body
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                codec.decode(name).into_owned(),
+                value_schema(&codec.decode(value)),
+            )
+        })
+        .collect();
+    Some((
+        content_type,
+        json!({"type": "object", "properties": properties}),
+    ))
+}
+
+/// The security scheme of the credentials of `request`, under its name.
+fn security_scheme(request: &Request) -> Option<(&'static str, Value)> {
+    match &request.auth {
+        Some(Auth::Basic(_)) => {
+            return Some(("basicAuth", json!({"type": "http", "scheme": "basic"})));
+        }
+        Some(Auth::Bearer { .. }) => {
+            return Some(("bearerAuth", json!({"type": "http", "scheme": "bearer"})));
+        }
+        Some(Auth::Digest(_)) => {
+            return Some(("digestAuth", json!({"type": "http", "scheme": "digest"})));
+        }
+        _ => {}
+    }
+    let authorization = request.headers.get("Authorization")?;
+    let scheme = authorization
+        .split_whitespace()
+        .next()?
+        .to_ascii_lowercase();
+    Some(match scheme.as_str() {
+        "basic" => ("basicAuth", json!({"type": "http", "scheme": "basic"})),
+        "bearer" => ("bearerAuth", json!({"type": "http", "scheme": "bearer"})),
+        _ => ("httpAuth", json!({"type": "http", "scheme": scheme})),
+    })
+}
+
+/// Whether two parameters are the same one, header names ignoring case.
+fn same_parameter(a: &Parameter, b: &Parameter) -> bool {
+    a.location == b.location
+        && if a.location == "header" {
+            a.name.eq_ignore_ascii_case(&b.name)
+        } else {
+            a.name == b.name
+        }
+}
+
+/// Add the parameters of another sample of an operation to `known`.
+fn merge_parameters(known: &mut Vec<Parameter>, parameters: Vec<Parameter>) {
+    for parameter in parameters {
+        match known
+            .iter_mut()
+            .find(|known| same_parameter(known, &parameter))
+        {
+            Some(known) => {
+                known.seen += parameter.seen;
+                known.schema = merge_schemas(known.schema.clone(), parameter.schema);
+            }
+            None => known.push(parameter),
+        }
+    }
+}
+
+impl Document {
+    /// A document titled `title` describing the requests of `commands`.
+    ///
+    /// Commands sending the same method to the same templated path are
+    /// merged into one operation: parameters given by all of them are
+    /// required, and schemas are merged. Commands without a URL or using a
+    /// method OpenAPI has no operation for are left out.
+    pub fn new<'c, 'a: 'c>(
+        title: &str,
+        commands: impl IntoIterator<Item = &'c [Curl<'a>]>,
+    ) -> Self {
+        let mut document = Document {
+            openapi: OPENAPI_VERSION,
+            info: Info {
+                title: title.into(),
+                version: "0.1.0".into(),
+            },
+            servers: Vec::new(),
+            paths: IndexMap::new(),
+            components: Components::default(),
+        };
+        for curls in commands {
+            if let Ok(request) = Request::try_from(curls) {
+                document.add(&request);
+            }
+        }
+        for operation in document.paths.values_mut().flat_map(IndexMap::values_mut) {
+            for parameter in &mut operation.parameters {
+                parameter.required =
+                    parameter.location == "path" || parameter.seen == operation.samples;
+            }
+        }
+        document
+    }
+
+    fn add(&mut self, request: &Request) {
+        let method = request.method.as_str().to_ascii_lowercase();
+        if !METHODS.contains(&method.as_str()) {
+            return;
+        }
+        if let Some(origin) = Origin::of(&request.url) {
+            let url = origin.to_string();
+            if !self.servers.iter().any(|server| server.url == url) {
+                self.servers.push(Server { url });
+            }
+        }
+        let (path, path_parameters) = templated_path(&request.url);
+        let operation = self
+            .paths
+            .entry(path.clone())
+            .or_default()
+            .entry(method)
+            .or_insert_with(|| Operation {
+                summary: format!("{} {}", request.method.as_str().to_ascii_uppercase(), path),
+                parameters: Vec::new(),
+                request_body: None,
+                security: Vec::new(),
+                samples: 0,
+            });
+        operation.samples += 1;
+
+        let mut sample = path_parameters;
+        for mut parameter in parameters(request) {
+            // Only the first of repeated parameters, e.g. `?tag=a&tag=b`,
+            // counts as seen.
+            if sample.iter().any(|known| same_parameter(known, &parameter)) {
+                parameter.seen = 0;
+            }
+            sample.push(parameter);
+        }
+        merge_parameters(&mut operation.parameters, sample);
+
+        if let Some((content_type, schema)) = body_schema(request) {
+            let content = &mut operation
+                .request_body
+                .get_or_insert_with(|| RequestBody {
+                    content: IndexMap::new(),
+                })
+                .content;
+            let merged = match content.shift_remove(&content_type) {
+                Some(known) => merge_schemas(known["schema"].clone(), schema),
+                None => schema,
+            };
+            content.insert(content_type, json!({ "schema": merged }));
+        }
+
+        if let Some((name, scheme)) = security_scheme(request) {
+            self.components
+                .security_schemes
+                .entry(name.into())
+                .or_insert(scheme);
+            if !operation
+                .security
+                .iter()
+                .any(|requirement| requirement.contains_key(name))
+            {
+                operation
+                    .security
+                    .push(IndexMap::from([(name.to_string(), Vec::new())]));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case("https://a.com/users/42", "/users/{userId}", vec!["userId"])]
+    #[case(
+        "https://a.com/orders/7/items/3?x=1",
+        "/orders/{orderId}/items/{itemId}",
+        vec!["orderId", "itemId"]
+    )]
+    #[case(
+        "https://a.com/1/2/550e8400-e29b-41d4-a716-446655440000",
+        "/{id}/{id2}/{id3}",
+        vec!["id", "id2", "id3"]
+    )]
+    #[case("https://a.com", "/", vec![])]
+    #[case("https://a.com/v1/users/", "/v1/users/", vec![])]
+    fn test_templated_path(#[case] url: &str, #[case] expected: &str, #[case] names: Vec<&str>) {
+        let (path, parameters) = templated_path(url);
+        assert_eq!(path, expected);
+        assert_eq!(
+            parameters
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<_>>(),
+            names
+        );
+    }
+
+    #[rstest]
+    fn test_document() {
+        let commands = [
+            curl_cmd_parse(concat!(
+                "curl 'https://api.example.com/v1/users/42?fields=name&verbose=true' ",
+                "-H 'Authorization: Bearer t' -H 'X-Request-Id: 1' -H 'User-Agent: x' -b 'session=abc'"
+            ))
+            .unwrap(),
+            curl_cmd_parse(
+                "curl 'https://api.example.com/v1/users/7?fields=email' -H 'Authorization: Bearer u'",
+            )
+            .unwrap(),
+            curl_cmd_parse(concat!(
+                "curl 'https://api.example.com/v1/users' -H 'Content-Type: application/json' ",
+                r#"-d '{"name": "amy", "age": 3}'"#
+            ))
+            .unwrap(),
+            curl_cmd_parse(concat!(
+                "curl 'https://api.example.com/v1/users' -H 'Content-Type: application/json' ",
+                r#"-d '{"name": "bob"}'"#
+            ))
+            .unwrap(),
+        ];
+        let document = Document::new("Users", commands.iter().map(Vec::as_slice));
+        assert_eq!(
+            serde_json::to_value(&document).unwrap(),
+            json!({
+                "openapi": "3.1.0",
+                "info": {"title": "Users", "version": "0.1.0"},
+                "servers": [{"url": "https://api.example.com"}],
+                "paths": {
+                    "/v1/users/{userId}": {
+                        "get": {
+                            "summary": "GET /v1/users/{userId}",
+                            "parameters": [
+                                {"name": "userId", "in": "path", "required": true, "schema": {"type": "integer"}},
+                                {"name": "fields", "in": "query", "required": true, "schema": {"type": "string"}},
+                                {"name": "verbose", "in": "query", "required": false, "schema": {"type": "boolean"}},
+                                {"name": "X-Request-Id", "in": "header", "required": false, "schema": {"type": "integer"}},
+                                {"name": "session", "in": "cookie", "required": false, "schema": {"type": "string"}}
+                            ],
+                            "security": [{"bearerAuth": []}]
+                        }
+                    },
+                    "/v1/users": {
+                        "post": {
+                            "summary": "POST /v1/users",
+                            "requestBody": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "properties": {
+                                                "name": {"type": "string"},
+                                                "age": {"type": "integer"}
+                                            },
+                                            "required": ["name"]
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "components": {
+                    "securitySchemes": {"bearerAuth": {"type": "http", "scheme": "bearer"}}
+                }
+            })
+        );
+    }
+
+    #[rstest]
+    #[case("curl https://a.com -d 'q=a+b&n=2'", "application/x-www-form-urlencoded", json!({
+        "type": "object",
+        "properties": {"q": {"type": "string"}, "n": {"type": "integer"}}
+    }))]
+    #[case("curl https://a.com -F 'f=@a.png;type=image/png' -F 'n=1'", "multipart/form-data", json!({
+        "type": "object",
+        "properties": {
+            "f": {"type": "string", "contentMediaType": "image/png"},
+            "n": {"type": "string"}
+        }
+    }))]
+    #[case("curl https://a.com -H 'Content-Type: text/plain' -d 'hi'", "text/plain", json!({"type": "string"}))]
+    fn test_body_schema(#[case] input: &str, #[case] content_type: &str, #[case] schema: Value) {
+        let request = Request::parse(input).unwrap();
+        assert_eq!(body_schema(&request), Some((content_type.into(), schema)));
+    }
+
+    #[rstest]
+    #[case("curl https://a.com -u me:pw", Some("basicAuth"))]
+    #[case("curl https://a.com --oauth2-bearer t", Some("bearerAuth"))]
+    #[case("curl https://a.com --digest -u me:pw", Some("digestAuth"))]
+    #[case("curl https://a.com -H 'Authorization: Token t'", Some("httpAuth"))]
+    #[case("curl https://a.com", None)]
+    fn test_security_scheme(#[case] input: &str, #[case] expected: Option<&str>) {
+        let request = Request::parse(input).unwrap();
+        assert_eq!(security_scheme(&request).map(|(name, _)| name), expected);
+    }
+
+    #[rstest]
+    fn test_repeated_parameter_and_unknown_method() {
+        let commands = [
+            curl_cmd_parse("curl 'https://a.com/s?tag=a&tag=1'").unwrap(),
+            curl_cmd_parse("curl 'https://a.com/s' -X PURGE").unwrap(),
+        ];
+        let document = Document::new("A", commands.iter().map(Vec::as_slice));
+        let operations = &document.paths["/s"];
+        assert_eq!(operations.keys().collect::<Vec<_>>(), ["get"]);
+        let parameters = &operations["get"].parameters;
+        assert_eq!(parameters.len(), 1);
+        assert!(parameters[0].required);
+        assert_eq!(parameters[0].schema, json!({"type": ["string", "integer"]}));
+    }
+}
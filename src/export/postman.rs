@@ -0,0 +1,468 @@
+use serde::Serialize;
+
+use crate::codegen::{Target, headers_for};
+use crate::curl::parser::Curl;
+use crate::diagnostic::Diagnostic;
+use crate::request::body::FORM_URLENCODED;
+use crate::request::{self, BasicAuth};
+use crate::url::codec::UrlCodec;
+
+/// The schema every exported collection declares.
+pub const SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// A Postman Collection v2.1 document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Collection {
+    pub info: Info,
+    pub item: Vec<Item>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Info {
+    pub name: String,
+    pub schema: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+    pub name: String,
+    pub request: Request,
+    #[serde(skip_serializing_if = "ProtocolProfileBehavior::is_default")]
+    pub protocol_profile_behavior: ProtocolProfileBehavior,
+}
+
+/// Per-request client settings. Postman follows redirects and checks
+/// certificates unless told otherwise, so only departures are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ProtocolProfileBehavior {
+    #[serde(rename = "followRedirects", skip_serializing_if = "is_true")]
+    pub follow_redirects: bool,
+    #[serde(rename = "strictSSL", skip_serializing_if = "is_true")]
+    pub strict_ssl: bool,
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+impl ProtocolProfileBehavior {
+    fn is_default(&self) -> bool {
+        self.follow_redirects && self.strict_ssl
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Request {
+    pub method: String,
+    pub header: Vec<KeyValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Body>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Auth>,
+    pub url: Url,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: String,
+}
+
+impl KeyValue {
+    fn new(key: &str, value: &str) -> Self {
+        KeyValue {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// A `raw` body, the `urlencoded` fields of a form, or the `file` of `-T`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Body {
+    pub mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub urlencoded: Vec<KeyValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<File>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct File {
+    pub src: String,
+}
+
+/// Basic, digest or bearer authentication, stored by Postman as a list of
+/// typed variables under the name of its type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Auth {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub basic: Vec<AuthVariable>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub digest: Vec<AuthVariable>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bearer: Vec<AuthVariable>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuthVariable {
+    pub key: &'static str,
+    pub value: String,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+}
+
+/// A URL split into the parts Postman edits separately.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Url {
+    pub raw: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    pub host: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+    pub path: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub query: Vec<KeyValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+impl Url {
+    pub fn new(raw: &str) -> Self {
+        let (rest, hash) = match raw.split_once('#') {
+            Some((rest, hash)) => (rest, Some(hash.to_string())),
+            None => (raw, None),
+        };
+        let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (protocol, rest) = match rest.split_once("://") {
+            Some((protocol, rest)) => (Some(protocol.to_string()), rest),
+            None => (None, rest),
+        };
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        let (host, port) = match host.rsplit_once(':') {
+            Some((host, port)) if port.bytes().all(|b| b.is_ascii_digit()) => {
+                (host, Some(port.to_string()))
+            }
+            _ => (host, None),
+        };
+
+        Url {
+            raw: raw.into(),
+            protocol,
+            host: host.split('.').map(String::from).collect(),
+            port,
+            path: path
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(String::from)
+                .collect(),
+            query: query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    KeyValue::new(key, value)
+                })
+                .collect(),
+            hash,
+        }
+    }
+}
+
+impl AuthVariable {
+    fn new(key: &'static str, value: String) -> Self {
+        AuthVariable {
+            key,
+            value,
+            kind: "string",
+        }
+    }
+}
+
+impl Auth {
+    /// The Postman authentication of `auth`, `None` for the schemes Postman
+    /// is not given here.
+    fn new(auth: request::auth::Auth) -> Option<Self> {
+        let mut postman = Auth {
+            kind: "",
+            basic: Vec::new(),
+            digest: Vec::new(),
+            bearer: Vec::new(),
+        };
+        let credentials = |credentials: BasicAuth| {
+            vec![
+                AuthVariable::new("username", credentials.user),
+                AuthVariable::new("password", credentials.password.unwrap_or_default()),
+            ]
+        };
+        match auth {
+            request::auth::Auth::Basic(user) => {
+                postman.kind = "basic";
+                postman.basic = credentials(user);
+            }
+            request::auth::Auth::Digest(user) => {
+                postman.kind = "digest";
+                postman.digest = credentials(user);
+            }
+            request::auth::Auth::Bearer { token } => {
+                postman.kind = "bearer";
+                postman.bearer = vec![AuthVariable::new("token", token)];
+            }
+            _ => return None,
+        }
+        Some(postman)
+    }
+}
+
+impl Request {
+    pub fn new(curls: &[Curl]) -> Result<Self, Diagnostic> {
+        request::Request::try_from(curls).map(Self::from_request)
+    }
+
+    fn from_request(request: request::Request) -> Self {
+        // curl sends a `-d` body as a form unless told otherwise.
+        let form = request.content_type() == Some(FORM_URLENCODED);
+        let header = headers_for(&request, Target::Postman)
+            .into_iter()
+            .map(|(name, value)| KeyValue::new(name, &value))
+            .collect();
+        // A `-d @file` body is sent from the file, as with `-T`.
+        let body = match (request.body_file, request.body, request.upload_file) {
+            (Some(request::body::BodyFile { path: src, .. }), _, _) | (None, None, Some(src)) => {
+                Some(Body {
+                    mode: "file",
+                    raw: None,
+                    urlencoded: Vec::new(),
+                    file: Some(File { src }),
+                })
+            }
+            (None, Some(raw), _) => Some(match urlencoded(&raw).filter(|_| form) {
+                Some(fields) => Body {
+                    mode: "urlencoded",
+                    raw: None,
+                    urlencoded: fields,
+                    file: None,
+                },
+                None => Body {
+                    mode: "raw",
+                    raw: Some(raw),
+                    urlencoded: Vec::new(),
+                    file: None,
+                },
+            }),
+            (None, None, None) => None,
+        };
+        Request {
+            header,
+            body,
+            auth: request.auth.and_then(Auth::new),
+            url: Url::new(&request.url),
+            method: request.method.to_string(),
+        }
+    }
+}
+
+/// The decoded fields of a form-encoded body, which Postman encodes again
+/// when sending it; `None` when that would not give back `body`.
+fn urlencoded(body: &str) -> Option<Vec<KeyValue>> {
+    let codec = UrlCodec::form();
+    body.split('&')
+        .map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            let field = KeyValue::new(&codec.decode(key), &codec.decode(value));
+            (codec.encode(&field.key) == key && codec.encode(&field.value) == value)
+                .then_some(field)
+        })
+        .collect()
+}
+
+impl Item {
+    /// An item named after the method and the URL without its query, e.g.
+    /// `GET example.com/users`.
+    pub fn new(curls: &[Curl]) -> Result<Self, Diagnostic> {
+        let request = request::Request::try_from(curls)?;
+        let protocol_profile_behavior = ProtocolProfileBehavior {
+            follow_redirects: request.follow_redirects,
+            strict_ssl: !request.insecure,
+        };
+        let request = Request::from_request(request);
+        let mut name = format!("{} {}", request.method, request.url.host.join("."));
+        for segment in &request.url.path {
+            name.push('/');
+            name.push_str(segment);
+        }
+        Ok(Item {
+            name,
+            request,
+            protocol_profile_behavior,
+        })
+    }
+}
+
+impl Collection {
+    /// A collection named `name` holding one item per command; commands
+    /// curl refuses to send, e.g. mixing `-d` and `-F`, are left out.
+    pub fn new<'c, 'a: 'c>(name: &str, commands: impl IntoIterator<Item = &'c [Curl<'a>]>) -> Self {
+        Self::with_items(
+            name,
+            commands
+                .into_iter()
+                .filter_map(|curls| Item::new(curls).ok())
+                .collect(),
+        )
+    }
+
+    pub fn with_items(name: &str, item: Vec<Item>) -> Self {
+        Collection {
+            info: Info {
+                name: name.into(),
+                schema: SCHEMA,
+            },
+            item,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    fn test_collection() {
+        let a = curl_cmd_parse(
+            r#"curl 'https://api.example.com:8443/v1/users?page=2&q=x' -H 'Accept: */*'"#,
+        )
+        .unwrap();
+        let b = curl_cmd_parse(
+            r#"curl 'https://api.example.com/v1/users' -H 'Content-Type: application/json' -d '{"name": "n"}' -u 'me:pw' -L"#,
+        )
+        .unwrap();
+        let collection = Collection::new("Users", [&a[..], &b[..]]);
+
+        assert_eq!(
+            serde_json::to_value(&collection).unwrap(),
+            serde_json::json!({
+                "info": {"name": "Users", "schema": SCHEMA},
+                "item": [
+                    {
+                        "name": "GET api.example.com/v1/users",
+                        "request": {
+                            "method": "GET",
+                            "header": [{"key": "Accept", "value": "*/*"}],
+                            "url": {
+                                "raw": "https://api.example.com:8443/v1/users?page=2&q=x",
+                                "protocol": "https",
+                                "host": ["api", "example", "com"],
+                                "port": "8443",
+                                "path": ["v1", "users"],
+                                "query": [
+                                    {"key": "page", "value": "2"},
+                                    {"key": "q", "value": "x"}
+                                ]
+                            }
+                        },
+                        "protocolProfileBehavior": {"followRedirects": false}
+                    },
+                    {
+                        "name": "POST api.example.com/v1/users",
+                        "request": {
+                            "method": "POST",
+                            "header": [{"key": "Content-Type", "value": "application/json"}],
+                            "body": {"mode": "raw", "raw": "{\"name\": \"n\"}"},
+                            "auth": {
+                                "type": "basic",
+                                "basic": [
+                                    {"key": "username", "value": "me", "type": "string"},
+                                    {"key": "password", "value": "pw", "type": "string"}
+                                ]
+                            },
+                            "url": {
+                                "raw": "https://api.example.com/v1/users",
+                                "protocol": "https",
+                                "host": ["api", "example", "com"],
+                                "path": ["v1", "users"]
+                            }
+                        }
+                    }
+                ]
+            })
+        );
+    }
+
+    #[rstest]
+    #[case("localhost", None, vec!["localhost"], None)]
+    #[case("http://u:p@10.0.0.1:80/#top", Some("http"), vec!["10", "0", "0", "1"], Some("top"))]
+    fn test_url_split(
+        #[case] raw: &str,
+        #[case] protocol: Option<&str>,
+        #[case] host: Vec<&str>,
+        #[case] hash: Option<&str>,
+    ) {
+        let url = Url::new(raw);
+        assert_eq!(url.protocol.as_deref(), protocol);
+        assert_eq!(url.host, host);
+        assert_eq!(url.hash.as_deref(), hash);
+    }
+
+    #[rstest]
+    fn test_bearer_auth() {
+        let curls = curl_cmd_parse("curl 'https://a.com/x' --oauth2-bearer 't0k'").unwrap();
+        let collection = Collection::new("Auth", [&curls[..]]);
+        assert_eq!(
+            serde_json::to_value(&collection).unwrap()["item"][0]["request"]["auth"],
+            serde_json::json!({
+                "type": "bearer",
+                "bearer": [{"key": "token", "value": "t0k", "type": "string"}]
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_request_semantics() {
+        let curls = curl_cmd_parse("curl 'https://a.com/x' -G -d 'q=1' -A 'bot/1'").unwrap();
+        let request = Request::new(&curls).unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.url.raw, "https://a.com/x?q=1");
+        assert_eq!(request.body, None);
+        assert_eq!(request.header, vec![KeyValue::new("User-Agent", "bot/1")]);
+
+        let curls = curl_cmd_parse("curl 'https://a.com/x' -d 'a=1' -d 'q=a+b%26c'").unwrap();
+        let json = serde_json::to_value(Request::new(&curls).unwrap()).unwrap();
+        assert_eq!(
+            json["header"],
+            serde_json::json!([{"key": "Content-Type", "value": FORM_URLENCODED}])
+        );
+        assert_eq!(
+            json["body"],
+            serde_json::json!({
+                "mode": "urlencoded",
+                "urlencoded": [{"key": "a", "value": "1"}, {"key": "q", "value": "a b&c"}]
+            })
+        );
+
+        // Bodies Postman would encode differently are sent as they are.
+        let curls = curl_cmd_parse(r#"curl 'https://a.com/x' -d '{"a": 1}'"#).unwrap();
+        let json = serde_json::to_value(Request::new(&curls).unwrap()).unwrap();
+        assert_eq!(json["header"][0]["value"], FORM_URLENCODED);
+        assert_eq!(json["body"]["mode"], "raw");
+
+        let curls = curl_cmd_parse("curl 'https://a.com/x' -T 'notes.txt'").unwrap();
+        let json = serde_json::to_value(Request::new(&curls).unwrap()).unwrap();
+        assert_eq!(json["method"], "PUT");
+        assert_eq!(
+            json["body"],
+            serde_json::json!({"mode": "file", "file": {"src": "notes.txt"}})
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::ffi::{CStr, CString, c_char};
+
+use crate::curl::output::parse_to_json;
+
+/// Parse a NUL-terminated curl command into the JSON of
+/// [`parse_to_json`], e.g. for cgo:
+///
+/// ```c
+/// char *json = winnowcurl_parse("curl https://example.com -d a=1");
+/// /* ... */
+/// winnowcurl_free(json);
+/// ```
+///
+/// The string returned belongs to the caller, who frees it with
+/// [`winnowcurl_free`]. `NULL` is returned when `command` is `NULL` or not
+/// UTF-8.
+///
+/// # Safety
+///
+/// `command` must be `NULL` or point to a NUL-terminated string which
+/// stays valid during the call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winnowcurl_parse(command: *const c_char) -> *mut c_char {
+    if command.is_null() {
+        return std::ptr::null_mut();
+    }
+    // SAFETY: the caller passes a valid NUL-terminated string.
+    let Ok(command) = unsafe { CStr::from_ptr(command) }.to_str() else {
+        return std::ptr::null_mut();
+    };
+    // JSON escapes NUL, so the string has none.
+    CString::new(parse_to_json(command).to_string())
+        .expect("JSON has no NUL byte")
+        .into_raw()
+}
+
+/// Free a string returned by [`winnowcurl_parse`]; `NULL` is ignored.
+///
+/// # Safety
+///
+/// `json` must be `NULL` or a string returned by [`winnowcurl_parse`] not
+/// freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winnowcurl_free(json: *mut c_char) {
+    if !json.is_null() {
+        // SAFETY: the string was allocated by `winnowcurl_parse`.
+        drop(unsafe { CString::from_raw(json) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn parse(command: &CStr) -> Option<serde_json::Value> {
+        unsafe {
+            let json = winnowcurl_parse(command.as_ptr());
+            if json.is_null() {
+                return None;
+            }
+            let value = serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+            winnowcurl_free(json);
+            Some(value)
+        }
+    }
+
+    #[rstest]
+    fn test_parse() {
+        let parsed = parse(c"curl https://a.com -X PUT").unwrap();
+        assert_eq!(parsed["command"]["methods"][0], "PUT");
+
+        let failed = parse(c"curl").unwrap();
+        assert_eq!(failed["error"]["code"], "WCE003");
+
+        assert_eq!(parse(c"curl \xff"), None);
+    }
+
+    #[rstest]
+    fn test_null() {
+        unsafe {
+            assert!(winnowcurl_parse(std::ptr::null()).is_null());
+            winnowcurl_free(std::ptr::null_mut());
+        }
+    }
+}
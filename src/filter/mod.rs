@@ -0,0 +1,399 @@
+use std::str::FromStr;
+
+use winnow::{
+    ModalResult, Parser,
+    ascii::{digit1, multispace0},
+    combinator::{alt, delimited, eof, opt, preceded, repeat, terminated},
+    error::{ContextError, ErrMode},
+    token::{any, none_of, take_while},
+};
+
+use crate::curl::parser::Curl;
+use crate::request::Request;
+use crate::url::host::{url_host, url_port};
+use crate::url::protocol::Schema;
+
+/// What a filter looks at in a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Field {
+    /// The method, uppercased.
+    Method,
+    Url,
+    /// `url.scheme`, lowercased.
+    Scheme,
+    /// `url.host`, lowercased, without port or credentials.
+    Host,
+    /// `url.port`, the default one of the scheme unless given.
+    Port,
+    /// `url.path`, `/` at least.
+    Path,
+    /// `url.query`, without the `?`.
+    Query,
+    /// `header.<name>`, every value of the header joined with `, `.
+    Header(String),
+    Body,
+    Insecure,
+    /// `location`, whether redirects are followed.
+    Location,
+    Compressed,
+    /// `proxy`, the URL of the proxy, e.g. `socks5h://gw:1080`.
+    Proxy,
+    /// `unix-socket`, the path of the socket connected to.
+    UnixSocket,
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = s.strip_prefix("header.").filter(|name| !name.is_empty()) {
+            return Ok(Field::Header(name.into()));
+        }
+        Ok(match s {
+            "method" => Field::Method,
+            "url" => Field::Url,
+            "url.scheme" => Field::Scheme,
+            "url.host" => Field::Host,
+            "url.port" => Field::Port,
+            "url.path" => Field::Path,
+            "url.query" => Field::Query,
+            "body" => Field::Body,
+            "insecure" => Field::Insecure,
+            "location" => Field::Location,
+            "compressed" => Field::Compressed,
+            "proxy" => Field::Proxy,
+            "unix-socket" => Field::UnixSocket,
+            _ => return Err(format!("Unknown field: {}", s)),
+        })
+    }
+}
+
+/// A value of a request, or written in a filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Text(String),
+    Number(u64),
+    Bool(bool),
+}
+
+impl Value {
+    fn as_text(&self) -> String {
+        match self {
+            Value::Text(text) => text.clone(),
+            Value::Number(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+impl Op {
+    /// Whether `value` compares to `literal`, numbers only being ordered.
+    fn apply(self, value: &Value, literal: &Value) -> bool {
+        match self {
+            Op::Eq => value == literal || value.as_text() == literal.as_text(),
+            Op::Ne => !Op::Eq.apply(value, literal),
+            Op::Contains => value.as_text().contains(&literal.as_text()),
+            Op::StartsWith => value.as_text().starts_with(&literal.as_text()),
+            Op::EndsWith => value.as_text().ends_with(&literal.as_text()),
+            Op::Lt | Op::Le | Op::Gt | Op::Ge => {
+                let (Value::Number(a), Value::Number(b)) = (value, literal) else {
+                    return false;
+                };
+                match self {
+                    Op::Lt => a < b,
+                    Op::Le => a <= b,
+                    Op::Gt => a > b,
+                    _ => a >= b,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Compare(Field, Op, Value),
+    /// A field on its own: whether it is present, or true for flags.
+    Present(Field),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+/// The part of a URL after its authority, e.g. `/a?q=1#top`.
+fn after_authority(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.find(['/', '?', '#']).map_or("", |at| &rest[at..])
+}
+
+impl Field {
+    /// The value of the field in `request`, `None` when absent.
+    pub fn value(&self, request: &Request) -> Option<Value> {
+        let url = request.url.as_str();
+        let text = |s: &str| Some(Value::Text(s.into()));
+        match self {
+            Field::Method => text(&request.method.as_str().to_ascii_uppercase()),
+            Field::Url => text(url),
+            Field::Scheme => text(&url.split_once("://")?.0.to_ascii_lowercase()),
+            Field::Host => text(&url_host(url).to_ascii_lowercase()),
+            Field::Port => url_port(url)
+                .or_else(|| Schema::from(url.split_once("://")?.0).default_port())
+                .map(|port| Value::Number(port.into())),
+            Field::Path => {
+                let path = after_authority(url).split(['?', '#']).next()?;
+                text(if path.is_empty() { "/" } else { path })
+            }
+            Field::Query => {
+                let (_, query) = after_authority(url).split_once('?')?;
+                text(query.split('#').next()?)
+            }
+            Field::Header(name) => {
+                let values = request.headers.get_all(name);
+                (!values.is_empty()).then(|| Value::Text(values.join(", ")))
+            }
+            Field::Body => request.body.as_deref().and_then(text),
+            Field::Insecure => Some(Value::Bool(request.insecure)),
+            Field::Location => Some(Value::Bool(request.follow_redirects)),
+            Field::Compressed => Some(Value::Bool(request.compressed)),
+            Field::Proxy => text(&request.routing.proxy.as_ref()?.to_string()),
+            Field::UnixSocket => text(&request.routing.unix_socket.as_ref()?.path),
+        }
+    }
+}
+
+impl Expr {
+    pub fn eval(&self, request: &Request) -> bool {
+        match self {
+            Expr::Compare(field, op, literal) => match field.value(request) {
+                Some(value) => op.apply(&value, literal),
+                None => *op == Op::Ne,
+            },
+            Expr::Present(field) => {
+                !matches!(field.value(request), None | Some(Value::Bool(false)))
+            }
+            Expr::Not(expr) => !expr.eval(request),
+            Expr::And(a, b) => a.eval(request) && b.eval(request),
+            Expr::Or(a, b) => a.eval(request) || b.eval(request),
+        }
+    }
+}
+
+/// `parser`, with the whitespace around it.
+fn ws<'a, O>(
+    parser: impl Parser<&'a str, O, ErrMode<ContextError>>,
+) -> impl Parser<&'a str, O, ErrMode<ContextError>> {
+    delimited(multispace0, parser, multispace0)
+}
+
+/// A double-quoted string, where a backslash escapes the next character.
+fn string(s: &mut &str) -> ModalResult<String> {
+    delimited(
+        '"',
+        repeat(0.., alt((preceded('\\', any), none_of(['"', '\\'])))),
+        '"',
+    )
+    .parse_next(s)
+}
+
+fn literal(s: &mut &str) -> ModalResult<Value> {
+    alt((
+        string.map(Value::Text),
+        digit1.try_map(str::parse).map(Value::Number),
+        "true".value(Value::Bool(true)),
+        "false".value(Value::Bool(false)),
+    ))
+    .parse_next(s)
+}
+
+fn field(s: &mut &str) -> ModalResult<Field> {
+    take_while(1.., |c: char| {
+        c.is_ascii_alphanumeric() || "_.-".contains(c)
+    })
+    .verify_map(|name: &str| name.parse().ok())
+    .parse_next(s)
+}
+
+fn op(s: &mut &str) -> ModalResult<Op> {
+    alt((
+        "==".value(Op::Eq),
+        "!=".value(Op::Ne),
+        "<=".value(Op::Le),
+        ">=".value(Op::Ge),
+        "<".value(Op::Lt),
+        ">".value(Op::Gt),
+        "contains".value(Op::Contains),
+        "startswith".value(Op::StartsWith),
+        "endswith".value(Op::EndsWith),
+    ))
+    .parse_next(s)
+}
+
+fn comparison(s: &mut &str) -> ModalResult<Expr> {
+    (field, opt((ws(op), literal)))
+        .map(|(field, compare)| match compare {
+            Some((op, literal)) => Expr::Compare(field, op, literal),
+            None => Expr::Present(field),
+        })
+        .parse_next(s)
+}
+
+fn primary(s: &mut &str) -> ModalResult<Expr> {
+    ws(alt((
+        preceded('!', primary).map(|expr| Expr::Not(Box::new(expr))),
+        delimited('(', or, ')'),
+        comparison,
+    )))
+    .parse_next(s)
+}
+
+fn and(s: &mut &str) -> ModalResult<Expr> {
+    let first = primary.parse_next(s)?;
+    repeat(0.., preceded("&&", primary))
+        .fold(
+            move || first.clone(),
+            |a, b| Expr::And(Box::new(a), Box::new(b)),
+        )
+        .parse_next(s)
+}
+
+fn or(s: &mut &str) -> ModalResult<Expr> {
+    let first = and.parse_next(s)?;
+    repeat(0.., preceded("||", and))
+        .fold(
+            move || first.clone(),
+            |a, b| Expr::Or(Box::new(a), Box::new(b)),
+        )
+        .parse_next(s)
+}
+
+/// A `--where` expression selecting the commands of a batch by the request
+/// they send.
+///
+/// Fields (`method`, `url`, `url.scheme`, `url.host`, `url.port`,
+/// `url.path`, `url.query`, `header.<name>`, `body`, `insecure`,
+/// `location`, `compressed`) are compared to double-quoted strings or
+/// numbers with `==`, `!=`, `<`, `<=`, `>`, `>=`, `contains`, `startswith`
+/// and `endswith`, or tested on their own for presence. Comparisons combine
+/// with `!`, `&&`, `||` and parentheses.
+///
+/// ```
+/// use winnowcurl::Request;
+/// use winnowcurl::filter::Filter;
+///
+/// let filter: Filter = r#"method == "POST" && url.host endswith "example.com""#.parse()?;
+/// assert!(filter.matches(&Request::parse("curl https://api.example.com -d 'a=1'")?));
+/// assert!(!filter.matches(&Request::parse("curl https://api.example.com")?));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter(pub Expr);
+
+impl Filter {
+    pub fn matches(&self, request: &Request) -> bool {
+        self.0.eval(request)
+    }
+
+    /// Whether the request of a command matches; commands sending no
+    /// request, e.g. with conflicting options, never do.
+    pub fn matches_curls(&self, curls: &[Curl<'_>]) -> bool {
+        Request::try_from(curls).is_ok_and(|request| self.matches(&request))
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        terminated(or, eof)
+            .parse(s)
+            .map(Filter)
+            .map_err(|e| format!("Invalid filter at offset {}:\n{}", e.offset(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(r#"method == "POST""#, true)]
+    #[case(r#"method == "post""#, false)]
+    #[case(r#"method != "PUT""#, true)]
+    #[case(r#"url.host endswith "example.com" && url.port == 8443"#, true)]
+    #[case(r#"url.port > 443 && url.port <= 8443"#, true)]
+    #[case(r#"url.port < "9000""#, false)]
+    #[case(r#"url.path startswith "/v1/" && url.query contains "page=2""#, true)]
+    #[case(r#"url.scheme == "https""#, true)]
+    #[case(r#"header.content-type == "application/json""#, true)]
+    #[case(r#"header.X-Missing != "a""#, true)]
+    #[case("header.authorization || insecure", false)]
+    #[case("!(location) && body", true)]
+    #[case(r#"body contains "\"id\"""#, true)]
+    #[case(
+        r#"method == "GET" || url.host == "API.EXAMPLE.COM" || compressed"#,
+        false
+    )]
+    fn test_matches(#[case] filter: &str, #[case] expected: bool) {
+        let request = Request::parse(concat!(
+            "curl 'https://API.example.com:8443/v1/users?page=2#top' ",
+            r#"-H 'Content-Type: application/json' -d '{"id": 1}'"#
+        ))
+        .unwrap();
+        assert_eq!(
+            filter.parse::<Filter>().unwrap().matches(&request),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_precedence() {
+        let filter: Filter = "insecure || location && compressed".parse().unwrap();
+        assert!(matches!(filter.0, Expr::Or(..)));
+    }
+
+    #[rstest]
+    #[case("curl http://a.com/", "url.path", Some(Value::Text("/".into())))]
+    #[case("curl http://a.com", "url.port", Some(Value::Number(80)))]
+    #[case("curl a.com?q=1", "url.query", Some(Value::Text("q=1".into())))]
+    #[case("curl https://a.com", "url.query", None)]
+    #[case(
+        "curl https://a.com -x gw:3128",
+        "proxy",
+        Some(Value::Text("http://gw:3128".into()))
+    )]
+    #[case(
+        "curl http://localhost/v1 --unix-socket /run/d.sock",
+        "unix-socket",
+        Some(Value::Text("/run/d.sock".into()))
+    )]
+    #[case("curl https://a.com", "proxy", None)]
+    fn test_field_value(#[case] input: &str, #[case] field: &str, #[case] expected: Option<Value>) {
+        let request = Request::parse(input).unwrap();
+        assert_eq!(field.parse::<Field>().unwrap().value(&request), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case(r#"method = "POST""#)]
+    #[case(r#"verb == "POST""#)]
+    #[case(r#"method == "POST" &&"#)]
+    #[case(r#"(method == "POST""#)]
+    #[case(r#"method == POST"#)]
+    fn test_parse_errors(#[case] filter: &str) {
+        assert!(filter.parse::<Filter>().is_err());
+    }
+}
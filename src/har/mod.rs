@@ -0,0 +1,474 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::curl::ast::OwnedCurl;
+use crate::curl::header;
+use crate::curl::parser::Curl;
+use crate::curl::render::to_curl_string;
+use crate::diagnostic::Diagnostic;
+use crate::request::auth::Auth;
+use crate::request::body::FORM_URLENCODED;
+
+/// A `name`/`value` pair, the shape HAR uses for headers, cookies and query
+/// parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameValue {
+    pub name: String,
+    pub value: String,
+}
+
+impl NameValue {
+    fn new(name: &str, value: &str) -> Self {
+        NameValue {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostData {
+    pub mime_type: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+fn http_1_1() -> String {
+    "HTTP/1.1".into()
+}
+
+/// The request half of a HAR entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub method: String,
+    pub url: String,
+    #[serde(default = "http_1_1")]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<NameValue>,
+    #[serde(default)]
+    pub headers: Vec<NameValue>,
+    #[serde(default)]
+    pub query_string: Vec<NameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<PostData>,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+/// The content of a HAR response.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Content {
+    #[serde(default)]
+    pub size: i64,
+    #[serde(default)]
+    pub mime_type: String,
+}
+
+/// The response half of a HAR entry. The requests written here were never
+/// sent, so it holds the status 0 HAR gives to requests without a response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    #[serde(default)]
+    pub status: i64,
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default)]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<NameValue>,
+    #[serde(default)]
+    pub headers: Vec<NameValue>,
+    #[serde(default)]
+    pub content: Content,
+    #[serde(default, rename = "redirectURL")]
+    pub redirect_url: String,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+impl Default for Response {
+    fn default() -> Self {
+        Response {
+            status: 0,
+            status_text: String::new(),
+            http_version: String::new(),
+            cookies: Vec::new(),
+            headers: Vec::new(),
+            content: Content::default(),
+            redirect_url: String::new(),
+            headers_size: unknown_size(),
+            body_size: unknown_size(),
+        }
+    }
+}
+
+/// The cache state of a HAR entry, left empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cache {}
+
+/// The time spent in each phase of a HAR entry, in milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Timings {
+    #[serde(default)]
+    pub send: f64,
+    #[serde(default)]
+    pub wait: f64,
+    #[serde(default)]
+    pub receive: f64,
+}
+
+/// The `startedDateTime` of the entries written here, which were never sent.
+const NOT_STARTED: &str = "1970-01-01T00:00:00.000Z";
+
+fn not_started() -> String {
+    NOT_STARTED.into()
+}
+
+/// An entry of a HAR log. Only the request is read; the fields HAR 1.2
+/// requires besides are filled with placeholders when writing, and default
+/// to those when missing on import.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+    #[serde(default = "not_started")]
+    pub started_date_time: String,
+    #[serde(default)]
+    pub time: f64,
+    pub request: Request,
+    #[serde(default)]
+    pub response: Response,
+    #[serde(default)]
+    pub cache: Cache,
+    #[serde(default)]
+    pub timings: Timings,
+}
+
+impl Entry {
+    pub fn new(request: Request) -> Self {
+        Entry {
+            started_date_time: not_started(),
+            time: 0.0,
+            request,
+            response: Response::default(),
+            cache: Cache::default(),
+            timings: Timings::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Creator {
+    pub name: String,
+    pub version: String,
+}
+
+impl Default for Creator {
+    fn default() -> Self {
+        Creator {
+            name: env!("CARGO_PKG_NAME").into(),
+            version: env!("CARGO_PKG_VERSION").into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Log {
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub creator: Creator,
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+}
+
+/// A HAR document, as exported by browser devtools and proxies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Har {
+    pub log: Log,
+}
+
+impl Har {
+    /// A HAR 1.2 document holding one entry per request.
+    pub fn new(requests: impl IntoIterator<Item = Request>) -> Self {
+        Har {
+            log: Log {
+                version: "1.2".into(),
+                creator: Creator::default(),
+                entries: requests.into_iter().map(Entry::new).collect(),
+            },
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn requests(&self) -> impl Iterator<Item = &Request> {
+        self.log.entries.iter().map(|entry| &entry.request)
+    }
+}
+
+/// Split `a=1; b=2` into cookies.
+fn parse_cookies(header: &str) -> impl Iterator<Item = NameValue> + '_ {
+    header::parse_cookies(header).map(|cookie| NameValue::new(&cookie.name, &cookie.value))
+}
+
+impl Request {
+    /// The HAR request curl would send for `curls`.
+    pub fn from_curls(curls: &[Curl]) -> Result<Self, Diagnostic> {
+        crate::request::Request::try_from(curls).map(|request| Self::new(&request))
+    }
+
+    /// The HAR record of `request`.
+    pub fn new(request: &crate::request::Request) -> Self {
+        let mut headers: Vec<NameValue> = request
+            .headers
+            .iter()
+            .map(|(name, value)| NameValue::new(name, value))
+            .collect();
+        let cookies: Vec<NameValue> = request
+            .headers
+            .get_all("cookie")
+            .iter()
+            .flat_map(|header| parse_cookies(header))
+            .collect();
+        if let Some(value) = request.auth.as_ref().and_then(Auth::authorization) {
+            headers.push(NameValue::new("Authorization", &value));
+        }
+
+        let query_string = request
+            .url
+            .split_once('?')
+            .map(|(_, query)| query.split('#').next().unwrap_or_default())
+            .into_iter()
+            .flat_map(|query| query.split('&'))
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                NameValue::new(name, value)
+            })
+            .collect();
+
+        let post_data = request.body.as_ref().map(|body| PostData {
+            mime_type: request
+                .headers
+                .get("content-type")
+                .unwrap_or(FORM_URLENCODED)
+                .into(),
+            text: body.clone(),
+        });
+
+        Request {
+            method: request.method.to_string(),
+            url: request.url.clone(),
+            http_version: http_1_1(),
+            cookies,
+            headers,
+            query_string,
+            body_size: post_data.as_ref().map_or(0, |data| data.text.len() as i64),
+            post_data,
+            headers_size: unknown_size(),
+        }
+    }
+
+    /// The curl command sending this request, as parsed nodes.
+    ///
+    /// HTTP/2 pseudo-headers and `Content-Length` are left out since curl
+    /// derives them itself, and `-X` is only kept when curl would not pick
+    /// the method on its own.
+    pub fn to_curls(&self) -> Vec<OwnedCurl> {
+        let mut curls = vec![Curl::new_as_url_word(Cow::Owned(self.url.clone()))];
+
+        let body = self.post_data.as_ref().map(|data| data.text.as_str());
+        let implied = if body.is_some_and(|body| !body.is_empty()) {
+            "POST"
+        } else {
+            "GET"
+        };
+        if !self.method.eq_ignore_ascii_case(implied) {
+            curls.extend(Curl::new("-X", self.method.clone()));
+        }
+        for header in &self.headers {
+            if header.name.starts_with(':') || header.name.eq_ignore_ascii_case("content-length") {
+                continue;
+            }
+            curls.extend(Curl::new(
+                "-H",
+                format!("{}: {}", header.name, header.value),
+            ));
+        }
+        if let Some(body) = body {
+            curls.extend(Curl::new("--data-raw", body.to_string()));
+        }
+        curls
+    }
+
+    pub fn to_curl_string(&self) -> String {
+        to_curl_string(&self.to_curls())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    fn test_request_from_curls() {
+        let curls = curl_cmd_parse(
+            r#"curl 'https://example.com/api?a=1&b=2' -H 'Accept: */*' -H 'Content-Type: application/json' -d '{"k": 1}' -b 'sid=x; theme=dark' -u 'me:secret'"#,
+        )
+        .unwrap();
+        let request = Request::from_curls(&curls).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "method": "POST",
+                "url": "https://example.com/api?a=1&b=2",
+                "httpVersion": "HTTP/1.1",
+                "cookies": [
+                    {"name": "sid", "value": "x"},
+                    {"name": "theme", "value": "dark"}
+                ],
+                "headers": [
+                    {"name": "Accept", "value": "*/*"},
+                    {"name": "Content-Type", "value": "application/json"},
+                    {"name": "Cookie", "value": "sid=x; theme=dark"},
+                    {"name": "Authorization", "value": "Basic bWU6c2VjcmV0"}
+                ],
+                "queryString": [
+                    {"name": "a", "value": "1"},
+                    {"name": "b", "value": "2"}
+                ],
+                "postData": {"mimeType": "application/json", "text": "{\"k\": 1}"},
+                "headersSize": -1,
+                "bodySize": 8
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_har_import() {
+        let har = Har::from_json(
+            r#"{"log": {"version": "1.2", "entries": [
+                {"request": {"method": "GET", "url": "https://example.com/a",
+                    "headers": [{"name": ":authority", "value": "example.com"},
+                                {"name": "accept", "value": "text/html"}]},
+                 "response": {"status": 200}, "time": 12.5},
+                {"request": {"method": "PUT", "url": "https://example.com/b",
+                    "headers": [{"name": "Content-Length", "value": "3"}],
+                    "postData": {"mimeType": "text/plain", "text": "it's"}}}
+            ]}}"#,
+        )
+        .unwrap();
+
+        let commands: Vec<String> = har.requests().map(Request::to_curl_string).collect();
+        assert_eq!(
+            commands,
+            vec![
+                "curl 'https://example.com/a' \\\n  -H 'accept: text/html'".to_string(),
+                "curl 'https://example.com/b' \\\n  -X 'PUT' \\\n  --data-raw 'it'\"'\"'s'"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_har_entry_required_fields() {
+        let curls = curl_cmd_parse("curl 'https://example.com/a'").unwrap();
+        let har = Har::new([Request::from_curls(&curls).unwrap()]);
+        let entry = serde_json::to_value(&har).unwrap()["log"]["entries"][0].clone();
+
+        assert_eq!(entry["startedDateTime"], "1970-01-01T00:00:00.000Z");
+        assert_eq!(entry["time"], 0.0);
+        assert_eq!(
+            entry["response"],
+            serde_json::json!({
+                "status": 0,
+                "statusText": "",
+                "httpVersion": "",
+                "cookies": [],
+                "headers": [],
+                "content": {"size": 0, "mimeType": ""},
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": -1
+            })
+        );
+        assert_eq!(entry["cache"], serde_json::json!({}));
+        assert_eq!(
+            entry["timings"],
+            serde_json::json!({"send": 0.0, "wait": 0.0, "receive": 0.0})
+        );
+    }
+
+    #[rstest]
+    fn test_har_round_trip() {
+        let curls = curl_cmd_parse(
+            r#"curl 'https://example.com/api' -X 'PATCH' -H 'Accept: */*' --data-raw 'a=1'"#,
+        )
+        .unwrap();
+        let har = Har::new([Request::from_curls(&curls).unwrap()]);
+        let json = serde_json::to_string(&har).unwrap();
+
+        let imported = Har::from_json(&json).unwrap();
+        let request = imported.requests().next().unwrap();
+        assert_eq!(request.to_curls(), curls);
+    }
+
+    #[rstest]
+    #[case("--oauth2-bearer 't0k'", Some("Bearer t0k"))]
+    #[case("--digest -u 'me:pw'", None)]
+    fn test_request_authorization(#[case] options: &str, #[case] expected: Option<&str>) {
+        let input = format!("curl 'https://example.com/a' {}", options);
+        let request = Request::from_curls(&curl_cmd_parse(&input).unwrap()).unwrap();
+        let authorization = request
+            .headers
+            .iter()
+            .find(|header| header.name == "Authorization")
+            .map(|header| header.value.as_str());
+        assert_eq!(authorization, expected);
+    }
+
+    #[rstest]
+    fn test_request_semantics() {
+        let curls = curl_cmd_parse(
+            "curl 'https://example.com/s' -G -d 'q=1' -A 'bot/1' -H 'Cookie: a=1' -b 'b=2'",
+        )
+        .unwrap();
+        let request = Request::from_curls(&curls).unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.url, "https://example.com/s?q=1");
+        assert_eq!(request.post_data, None);
+        assert_eq!(request.query_string, vec![NameValue::new("q", "1")]);
+        assert_eq!(request.cookies, vec![NameValue::new("a", "1")]);
+        assert_eq!(
+            request.headers,
+            vec![
+                NameValue::new("Cookie", "a=1"),
+                NameValue::new("User-Agent", "bot/1")
+            ]
+        );
+
+        let curls = curl_cmd_parse("curl 'https://example.com/a' -I").unwrap();
+        assert_eq!(Request::from_curls(&curls).unwrap().method, "HEAD");
+        let curls = curl_cmd_parse("curl 'https://example.com/a' -I -d 'a=1'").unwrap();
+        assert!(Request::from_curls(&curls).is_err());
+    }
+}
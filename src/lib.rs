@@ -1,3 +1,131 @@
+//! Parse curl commands and work with the requests they send.
+//!
+//! [`Request`] is the place to start: it parses a command and applies
+//! curl's semantics, so that the method, URL, headers and body are the ones
+//! curl would send.
+//!
+//! ```
+//! use winnowcurl::Request;
+//!
+//! let request = Request::parse("curl https://example.com/users -d 'name=bob' -I -G")?;
+//! assert_eq!(request.method, "HEAD");
+//! assert_eq!(request.url, "https://example.com/users?name=bob");
+//! # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+//! ```
+//!
+//! The syntax tree the command was parsed into, one [`Curl`] node per
+//! option, is available from [`parse`] for tools that need to rewrite the
+//! command itself, e.g. with a [`CurlBuilder`]. Its URL is a [`CurlURL`],
+//! the one URL model of the crate.
+//!
+//! [`prelude`] brings all of these in with a single import.
+
+pub mod codegen;
+pub mod corpus;
 pub mod curl;
+pub mod dedup;
+pub mod diagnostic;
+pub mod diff;
+#[cfg(feature = "exec")]
+pub mod exec;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod filter;
+#[cfg(feature = "test-util")]
+pub mod fixtures;
+pub mod har;
+pub mod lint;
+/// The parse entry points and the types most code works with, for a single
+/// `use winnowcurl::prelude::*;`.
+///
+/// ```
+/// use winnowcurl::prelude::*;
+///
+/// let curls = parse("curl https://example.com/users -d 'name=bob'")?;
+/// let mut builder = CurlBuilder::from(curls);
+/// builder.remove_option("--data");
+/// assert_eq!(to_curl_string(&builder.build()?), "curl 'https://example.com/users'");
+///
+/// let request = parse_request("curl https://example.com/users -d 'name=bob'")?;
+/// assert_eq!(request.method, "POST");
+/// # Ok::<(), Diagnostic>(())
+/// ```
+pub mod prelude;
+pub mod request;
+pub mod session;
+pub mod stats;
 mod test_util;
 pub mod url;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use curl::builder::CurlBuilder;
+pub use curl::lexer::Dialect;
+pub use curl::parser::LossyParse;
+pub use curl::{Curl, OwnedCurl};
+pub use diagnostic::Diagnostic;
+pub use request::Request;
+pub use url::parser::CurlURL;
+pub use url::protocol::Schema;
+
+/// Parse a curl command into its syntax tree, guessing the shell it was
+/// written for, see [`Dialect::detect`].
+///
+/// ```
+/// use winnowcurl::Curl;
+///
+/// let curls = winnowcurl::parse("curl https://example.com -H 'Accept: */*'")?;
+/// assert!(matches!(&curls[0], Curl::URL(url) if url.host == "example.com"));
+/// # Ok::<(), winnowcurl::Diagnostic>(())
+/// ```
+pub fn parse(command: &str) -> Result<Vec<Curl<'_>>, Diagnostic> {
+    parse_dialect(command, Dialect::Auto)
+}
+
+/// [`parse`] for a command written for the shell `dialect`, see
+/// [`curl::parser::curl_cmd_parse_dialect`].
+pub fn parse_dialect(command: &str, dialect: Dialect) -> Result<Vec<Curl<'_>>, Diagnostic> {
+    curl::parser::curl_cmd_parse_dialect(command, dialect)
+}
+
+/// [`parse`] into nodes that own their data, so the command can be dropped.
+pub fn parse_owned(command: &str) -> Result<Vec<OwnedCurl>, Diagnostic> {
+    parse(command).map(|curls| curls.into_iter().map(Curl::into_owned).collect())
+}
+
+/// [`parse`] without giving up on the first problem, see
+/// [`curl::parser::curl_cmd_parse_lossy`].
+pub fn parse_lossy(command: &str) -> LossyParse<'_> {
+    curl::parser::curl_cmd_parse_dialect_lossy(command, Dialect::Auto)
+}
+
+/// Parse a curl command into the request it sends, see [`Request::parse`].
+pub fn parse_request(command: &str) -> Result<Request, Diagnostic> {
+    Request::try_from(parse(command)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("curl https://a.com -d 'a=1'")]
+    #[case("curl \"https://a.com\" ^\n  -d \"a=1\"")]
+    #[case("curl.exe https://a.com `\n  -d 'a=1'")]
+    fn test_parse(#[case] command: &str) {
+        let expected = curl::parser::curl_cmd_parse("curl 'https://a.com' -d 'a=1'").unwrap();
+        assert_eq!(parse(command).unwrap(), expected);
+        assert_eq!(parse_owned(command).unwrap(), expected);
+        assert_eq!(parse_lossy(command).curls, expected);
+        assert_eq!(parse_request(command).unwrap().body.as_deref(), Some("a=1"));
+    }
+
+    #[rstest]
+    fn test_parse_error() {
+        let error = parse("curl -X POST").unwrap_err();
+        assert_eq!(error.code, diagnostic::Code::MissingUrl);
+        assert_eq!(parse_lossy("curl -X POST").warnings.len(), 1);
+    }
+}
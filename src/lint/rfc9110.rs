@@ -0,0 +1,279 @@
+use crate::curl::method::is_token;
+use crate::diagnostic::{Code, Diagnostic};
+use crate::request::Request;
+use crate::url::host::url_host;
+
+/// The methods RFC 9110 gives no meaning to a body of, with the section
+/// saying so.
+const BODILESS: &[(&str, &str)] = &[("GET", "9.3.1"), ("HEAD", "9.3.2"), ("DELETE", "9.3.5")];
+
+/// A requirement of RFC 9110 a request does not meet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub code: Code,
+    /// The section stating the requirement, e.g. `9.3.1`.
+    pub section: &'static str,
+    pub message: String,
+}
+
+impl Violation {
+    fn new(code: Code, section: &'static str, message: String) -> Self {
+        Violation {
+            code,
+            section,
+            message,
+        }
+    }
+
+    /// Where the section is read, e.g.
+    /// `https://www.rfc-editor.org/rfc/rfc9110#section-9.3.1`.
+    pub fn url(&self) -> String {
+        format!(
+            "https://www.rfc-editor.org/rfc/rfc9110#section-{}",
+            self.section
+        )
+    }
+
+    /// The message followed by the section, e.g. `... (RFC 9110 §9.3.1)`.
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(
+            self.code,
+            format!("{} (RFC 9110 §{})", self.message, self.section),
+        )
+    }
+}
+
+/// Checks requests against the requirements of RFC 9110 curl does not
+/// enforce, meant for requests built in code, e.g. with a
+/// [`CurlBuilder`](crate::CurlBuilder).
+///
+/// ```
+/// use winnowcurl::lint::rfc9110::Validator;
+/// use winnowcurl::{CurlBuilder, Request};
+///
+/// let curls = CurlBuilder::new("https://example.com/search")
+///     .method("GET")
+///     .data(r#"{"query": "rust"}"#)
+///     .build()?;
+/// let request = Request::try_from(curls)?;
+/// let violations = Validator::new().validate(&request);
+/// assert_eq!(violations[0].diagnostic().message, "A GET request carries a body (RFC 9110 §9.3.1)");
+/// assert_eq!(Validator::new().allow_body(true).validate(&request), []);
+/// # Ok::<(), winnowcurl::Diagnostic>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Validator {
+    allow_body: bool,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept a body with GET, HEAD and DELETE, which some APIs such as
+    /// Elasticsearch's search expect.
+    pub fn allow_body(&mut self, allow: bool) -> &mut Self {
+        self.allow_body = allow;
+        self
+    }
+
+    /// Every requirement `request` does not meet.
+    pub fn validate(&self, request: &Request) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        if !self.allow_body {
+            violations.extend(unexpected_body(request));
+        }
+        violations.extend(invalid_tokens(request));
+        violations.extend(missing_host(request));
+        violations.extend(invalid_te(request));
+        violations
+    }
+}
+
+/// [`Validator::validate`] with the default settings.
+pub fn validate(request: &Request) -> Vec<Violation> {
+    Validator::new().validate(request)
+}
+
+fn unexpected_body(request: &Request) -> Option<Violation> {
+    let has_body = request.body.is_some() || !request.form.is_empty();
+    let (method, section) = BODILESS
+        .iter()
+        .find(|(method, _)| request.method == *method)?;
+    has_body.then(|| {
+        Violation::new(
+            Code::UnexpectedBody,
+            section,
+            format!("A {} request carries a body", method),
+        )
+    })
+}
+
+/// A method or header name which is not a token, and header values holding
+/// a line break or a NUL byte.
+fn invalid_tokens(request: &Request) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if !is_token(request.method.as_str()) {
+        violations.push(Violation::new(
+            Code::InvalidToken,
+            "9.1",
+            format!("The method {:?} is not a token", request.method.as_str()),
+        ));
+    }
+    for name in request.headers.names() {
+        if !is_token(name) {
+            violations.push(Violation::new(
+                Code::InvalidToken,
+                "5.1",
+                format!("The header name {:?} is not a token", name),
+            ));
+        }
+    }
+    for (name, value) in request.headers.iter() {
+        if value.contains(['\r', '\n', '\0']) {
+            violations.push(Violation::new(
+                Code::InvalidToken,
+                "5.5",
+                format!("The value of {} holds a line break or a NUL byte", name),
+            ));
+        }
+    }
+    violations
+}
+
+fn missing_host(request: &Request) -> Option<Violation> {
+    let removed = request.headers.contains("Host")
+        && request
+            .headers
+            .get_all("Host")
+            .iter()
+            .all(|value| value.is_empty());
+    let message = if url_host(&request.url).is_empty() {
+        "The URL has no host to send as Host"
+    } else if removed {
+        "The Host header is removed"
+    } else {
+        return None;
+    };
+    Some(Violation::new(Code::MissingHost, "7.2", message.into()))
+}
+
+/// The comma-separated elements of every value of `name`, without their
+/// parameters.
+fn list<'a>(request: &'a Request, name: &str) -> impl Iterator<Item = &'a str> {
+    request
+        .headers
+        .get_all(name)
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(|element| element.split(';').next().unwrap_or_default().trim())
+        .filter(|element| !element.is_empty())
+}
+
+fn invalid_te(request: &Request) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut codings = list(request, "TE").peekable();
+    if codings.peek().is_none() {
+        return violations;
+    }
+    if codings.any(|coding| coding.eq_ignore_ascii_case("chunked")) {
+        violations.push(Violation::new(
+            Code::InvalidTe,
+            "10.1.4",
+            "TE lists chunked, which is always accepted".into(),
+        ));
+    }
+    if !list(request, "Connection").any(|option| option.eq_ignore_ascii_case("TE")) {
+        violations.push(Violation::new(
+            Code::InvalidTe,
+            "10.1.4",
+            "TE is sent without TE in the Connection header".into(),
+        ));
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn violations(command: &str) -> Vec<String> {
+        validate(&Request::parse(command).unwrap())
+            .iter()
+            .map(|violation| violation.diagnostic().message)
+            .collect()
+    }
+
+    #[rstest]
+    #[case(
+        "curl https://a.com -X GET -d 'a=1'",
+        "A GET request carries a body (RFC 9110 §9.3.1)"
+    )]
+    #[case(
+        "curl https://a.com -X DELETE -F 'a=1'",
+        "A DELETE request carries a body (RFC 9110 §9.3.5)"
+    )]
+    #[case(
+        "curl https://a.com -X 'GET /'",
+        r#"The method "GET /" is not a token (RFC 9110 §9.1)"#
+    )]
+    #[case(
+        "curl https://a.com -H 'X A: 1'",
+        r#"The header name "X A" is not a token (RFC 9110 §5.1)"#
+    )]
+    #[case(
+        "curl https://a.com -H $'X-A: 1\\r\\nX-B: 2'",
+        "The value of X-A holds a line break or a NUL byte (RFC 9110 §5.5)"
+    )]
+    #[case(
+        "curl https://a.com -H 'Host:'",
+        "The Host header is removed (RFC 9110 §7.2)"
+    )]
+    #[case(
+        "curl 'http:///a'",
+        "The URL has no host to send as Host (RFC 9110 §7.2)"
+    )]
+    #[case(
+        "curl https://a.com -H 'TE: trailers'",
+        "TE is sent without TE in the Connection header (RFC 9110 §10.1.4)"
+    )]
+    fn test_validate(#[case] command: &str, #[case] expected: &str) {
+        assert_eq!(violations(command), [expected]);
+    }
+
+    #[rstest]
+    #[case("curl https://a.com")]
+    #[case("curl https://a.com -d 'a=1'")]
+    #[case("curl https://a.com -G -d 'a=1'")]
+    #[case("curl https://a.com -X PROPFIND -H 'Depth: 1'")]
+    #[case("curl https://a.com -H 'Host: b.com'")]
+    #[case("curl https://a.com -H 'TE: trailers, gzip;q=0.5' -H 'Connection: keep-alive, TE'")]
+    fn test_validate_none(#[case] command: &str) {
+        assert_eq!(violations(command), Vec::<String>::new());
+    }
+
+    #[rstest]
+    fn test_te_chunked() {
+        assert_eq!(
+            violations("curl https://a.com -H 'TE: chunked' -H 'Connection: te'"),
+            ["TE lists chunked, which is always accepted (RFC 9110 §10.1.4)"]
+        );
+    }
+
+    #[rstest]
+    fn test_allow_body() {
+        let request = Request::parse("curl https://a.com -X GET -d 'a=1'").unwrap();
+        assert_eq!(Validator::new().allow_body(true).validate(&request), []);
+    }
+
+    #[rstest]
+    fn test_url() {
+        let violation = Violation::new(Code::MissingHost, "7.2", String::new());
+        assert_eq!(
+            violation.url(),
+            "https://www.rfc-editor.org/rfc/rfc9110#section-7.2"
+        );
+    }
+}
@@ -1,7 +1,62 @@
 use clap::{Parser, Subcommand};
-use curl::parser::{curl_cmd_parse, Curl};
+use codegen::Target;
+use codegen::report::ConversionReport;
+use curl::batch;
+use curl::body;
+use curl::canonical;
+use curl::curlrc;
+use curl::devtools;
+use curl::error::ParseError;
+use curl::escape;
+use curl::explain;
+use curl::files::FileResolver;
+use curl::header::{self, Header};
+use curl::lexer::Dialect;
+use curl::method;
+use curl::output::ParsedCommand;
+#[cfg(feature = "exec")]
+use curl::parallel;
+use curl::parser::{
+    Curl, curl_cmd_parse, curl_cmd_parse_dialect_lossy, curl_cmd_try_parse_dialect,
+};
+use curl::quirks;
+use curl::render;
+use curl::rewrite::{self, Edit};
+use curl::secrets::Redaction;
+use curl::shell;
+use curl::timestamps;
+use diagnostic::baseline::Baseline;
+use diagnostic::{Code, Diagnostic};
+use diff::json_patch;
+use diff::request::RequestDiff;
+use export::openapi;
+use export::postman::Collection;
+use filter::Filter;
+use request::Request;
+use request::origin::OriginAnalysis;
+use session::{Extraction, Script};
+use stats::BatchStats;
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use url::glob::{OutputTemplate, UrlGlob};
+use url::normalize::{VolatileAction, VolatileParams};
 
+pub mod codegen;
+pub mod corpus;
 pub mod curl;
+pub mod dedup;
+pub mod diagnostic;
+pub mod diff;
+#[cfg(feature = "exec")]
+pub mod exec;
+pub mod export;
+pub mod filter;
+pub mod har;
+pub mod lint;
+pub mod request;
+pub mod session;
+pub mod stats;
 mod test_util;
 pub mod url;
 
@@ -11,19 +66,23 @@ pub enum CurlCommand {
     Header,
     Data,
     Flag,
+    Form,
+    Option,
     Url,
 }
 
 impl CurlCommand {
     fn matches_curl(&self, curl: &Curl) -> bool {
-        match (self, curl) {
-            (CurlCommand::Method, Curl::Method(_)) => true,
-            (CurlCommand::Header, Curl::Header(_)) => true,
-            (CurlCommand::Data, Curl::Data(_)) => true,
-            (CurlCommand::Flag, Curl::Flag(_)) => true,
-            (CurlCommand::Url, Curl::URL(_)) => true,
-            _ => false,
-        }
+        matches!(
+            (self, curl),
+            (CurlCommand::Method, Curl::Method(_))
+                | (CurlCommand::Header, Curl::Header(_))
+                | (CurlCommand::Data, Curl::Data(_))
+                | (CurlCommand::Flag, Curl::Flag(_))
+                | (CurlCommand::Form, Curl::Form(_))
+                | (CurlCommand::Option, Curl::Option { .. })
+                | (CurlCommand::Url, Curl::URL(_))
+        )
     }
 }
 
@@ -40,29 +99,1777 @@ struct Cli {
 enum Commands {
     #[command(about = "Parses a curl command")]
     Parse {
-        /// The input curl command string
-        command: String,
+        /// The input curl command string, or `-` to read commands from stdin
+        #[arg(required_unless_present = "file", conflicts_with = "file")]
+        command: Option<String>,
 
-        /// Specifies which part of the curl command to parse (method, header, data, flag, url)
+        /// Reads the curl commands to parse from a file (`-` for stdin)
+        #[arg(long = "file", value_name = "PATH")]
+        file: Option<PathBuf>,
+
+        /// Specifies which part of the curl command to parse (method, header, data, flag, form, option, url)
         #[arg(short = 'p', long = "part", value_name = "PART")]
         part: Option<CurlCommand>,
+
+        /// Only keeps the headers with this name, ignoring case, listing the
+        /// cookies of `Cookie` headers one by one (implies `--part header`)
+        #[arg(long = "header-name", value_name = "NAME")]
+        header_name: Option<String>,
+
+        /// Only keeps the commands whose request matches the expression,
+        /// e.g. `method == "POST" && url.host endswith "example.com"`
+        #[arg(long = "where", value_name = "EXPR")]
+        filter: Option<Filter>,
+
+        /// Output format of the parsed command
+        #[arg(
+            short = 'o',
+            long = "output",
+            visible_alias = "format",
+            short_alias = 'f',
+            value_enum,
+            default_value_t = ParseFormat::Debug
+        )]
+        output: ParseFormat,
+
+        /// Reads the files of `-d @file` style arguments and uses their
+        /// contents instead
+        #[arg(long = "resolve-files")]
+        resolve_files: bool,
+
+        /// Reads the config files of `-K file` options and uses their
+        /// options instead
+        #[arg(long = "resolve-config")]
+        resolve_config: bool,
+
+        /// The shell the command was written for (bash, Windows cmd or
+        /// PowerShell), guessed from its line continuations by default
+        #[arg(long = "dialect", value_enum, default_value_t = Dialect::Auto)]
+        dialect: Dialect,
+
+        /// Keeps going past arguments that cannot be parsed, reporting them
+        /// on stderr and keeping them as `Unknown` nodes
+        #[arg(long = "lenient")]
+        lenient: bool,
+
+        /// Refuses `-X` methods that are neither standard nor registered
+        /// with IANA, such as `-X TEST`, or that are no valid method at all
+        #[arg(long = "strict-methods")]
+        strict_methods: bool,
+
+        /// Hides credentials, cookies and secret fields: `mask` replaces
+        /// them with `***`, `hash` with a salted hash which is the same for
+        /// the same value
+        #[arg(long = "redact", value_enum, value_name = "MODE")]
+        redact: Option<RedactMode>,
+
+        /// The salt of `--redact hash`, to keep secret and unchanged for
+        /// hashes to stay comparable
+        #[arg(long = "salt", required_if_eq("redact", "hash"))]
+        salt: Option<String>,
+    },
+    #[command(about = "Reformats a curl command into a normalized, line-wrapped form")]
+    Format {
+        /// The input curl command string
+        command: String,
+
+        /// Percent-encodes the characters of the URL and form data that curl
+        /// would reject or mangle instead of warning about them
+        #[arg(long = "fix")]
+        fix: bool,
+
+        /// Indents JSON bodies over several lines
+        #[arg(long = "pretty-body")]
+        pretty_body: bool,
+
+        /// Writes options under their long names, in a stable order, with the
+        /// URL normalized, so that equivalent commands print the same
+        #[arg(long = "canonical")]
+        canonical: bool,
+
+        /// Rewrites away the differences between the commands Chrome, Firefox
+        /// and Safari copy for the same request (implies `--canonical`)
+        #[arg(long = "unquirk")]
+        unquirk: bool,
+
+        /// The shell the command was written for (bash, Windows cmd or
+        /// PowerShell), guessed from its line continuations by default
+        #[arg(long = "dialect", value_enum, default_value_t = Dialect::Auto)]
+        dialect: Dialect,
+
+        /// The shell to write the command for (`auto` for the one it was
+        /// written for), warning about what it would mangle
+        #[arg(long = "shell", value_enum, default_value_t = Dialect::Bash)]
+        shell: Dialect,
+    },
+    #[command(about = "Strips, sets and rewrites parts of a curl command and prints it back")]
+    Rewrite {
+        /// The input curl command string
+        command: String,
+
+        /// Removes the headers with this name, ignoring case, along with
+        /// the options sending them, e.g. `-b` for `cookie` (repeatable)
+        #[arg(long = "strip-header", value_name = "NAME")]
+        strip_header: Vec<String>,
+
+        /// Removes the query parameters with this name (repeatable)
+        #[arg(long = "strip-query", value_name = "NAME")]
+        strip_query: Vec<String>,
+
+        /// Replaces the headers with this name, or adds one (repeatable)
+        #[arg(long = "set-header", value_name = "NAME: VALUE", value_parser = header_arg)]
+        set_header: Vec<(String, String)>,
+
+        /// Appends a query parameter (repeatable)
+        #[arg(long = "add-query", value_name = "NAME=VALUE", value_parser = query_arg)]
+        add_query: Vec<(String, String)>,
+
+        /// Replaces the host of the URL, and its port with `host:port`
+        #[arg(long = "set-host", value_name = "HOST")]
+        set_host: Option<String>,
+
+        /// Replaces the request method
+        #[arg(long = "set-method", value_name = "METHOD")]
+        set_method: Option<String>,
+
+        /// Writes the Date and X-Amz-Date headers and the epoch timestamps
+        /// of the query and form body, like `_=1714566600000`, anew for now
+        #[arg(long = "refresh-timestamps")]
+        refresh_timestamps: bool,
+
+        /// The shell the command was written for (bash, Windows cmd or
+        /// PowerShell), guessed from its line continuations by default
+        #[arg(long = "dialect", value_enum, default_value_t = Dialect::Auto)]
+        dialect: Dialect,
+
+        /// The shell to write the command for (`auto` for the one it was
+        /// written for), warning about what it would mangle
+        #[arg(long = "shell", value_enum, default_value_t = Dialect::Bash)]
+        shell: Dialect,
+    },
+    #[command(about = "Converts a curl command to client code")]
+    Convert {
+        /// The input curl command strings, or the paths of HAR files with
+        /// `--from har` and of DevTools exports with `--from chrome`
+        #[arg(required_unless_present = "file")]
+        inputs: Vec<String>,
+
+        /// Also reads a batch of curl commands from a file (`-` for stdin),
+        /// e.g. to describe them all with `--to openapi`
+        #[arg(long = "file", value_name = "PATH", conflicts_with = "from")]
+        file: Option<PathBuf>,
+
+        /// The format of the input
+        #[arg(long = "from", value_enum, default_value_t = ConvertFrom::Curl)]
+        from: ConvertFrom,
+
+        /// The language or library to generate code for (reqwest by default,
+        /// HAR entries and DevTools exports are printed as curl commands
+        /// unless given)
+        #[arg(short = 't', long = "target", visible_alias = "to", value_enum)]
+        target: Option<Target>,
+
+        /// The name of the collection generated with `--to postman`, or the
+        /// title of the document generated with `--to openapi`
+        #[arg(long = "collection-name", default_value = codegen::postman::DEFAULT_COLLECTION_NAME)]
+        collection_name: String,
+
+        /// Only keeps the commands whose request matches the expression,
+        /// e.g. `method == "POST" && url.host endswith "example.com"`
+        #[arg(long = "where", value_name = "EXPR")]
+        filter: Option<Filter>,
+
+        /// Reads the files of `-d @file` style arguments and uses their
+        /// contents instead
+        #[arg(long = "resolve-files")]
+        resolve_files: bool,
+
+        /// Reads the config files of `-K file` options and uses their
+        /// options instead
+        #[arg(long = "resolve-config")]
+        resolve_config: bool,
+
+        /// The shell the command was written for (bash, Windows cmd or
+        /// PowerShell), guessed from its line continuations by default
+        #[arg(long = "dialect", value_enum, default_value_t = Dialect::Auto)]
+        dialect: Dialect,
+    },
+    #[cfg(feature = "exec")]
+    #[command(
+        about = "Performs the requests of curl commands, sharing their cookies as curl does across --next"
+    )]
+    Exec {
+        /// The input curl command string, or several chained with `&&`, `;`
+        /// or newlines
+        command: String,
+
+        /// Stores a value of each response that has one, at a JSONPath of
+        /// its body (`token=$.data.token`) or in a header
+        /// (`next=header:Location`), for the next commands to use as
+        /// `{{token}}` (repeatable)
+        #[arg(long = "extract", value_name = "NAME=PATH", conflicts_with = "compare")]
+        extract: Vec<Extraction>,
+
+        /// Performs each request against `--base` and against `--target`
+        /// and prints what differs between the responses: status, headers
+        /// and body, compared as JSON. Exits with 1 when any does
+        #[arg(long = "compare", requires_all = ["base", "target"])]
+        compare: bool,
+
+        /// The start of the URLs of the commands, e.g. `https://prod.example`
+        #[arg(long = "base", value_name = "URL", requires = "compare")]
+        base: Option<String>,
+
+        /// What `--base` is replaced with, e.g. `https://staging.example`
+        #[arg(long = "target", value_name = "URL", requires = "compare")]
+        target: Option<String>,
+
+        /// Leaves a header out of the comparison (repeatable); Age, Date,
+        /// Expires and X-Request-Id always are
+        #[arg(long = "ignore-header", value_name = "NAME", requires = "compare")]
+        ignore_headers: Vec<String>,
+
+        /// Sends DELETE, PUT and PATCH requests without asking, unless the
+        /// configuration file denies them
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+
+        /// Writes the Date and X-Amz-Date headers and the epoch timestamps
+        /// of the query and form body anew before sending, to replay a
+        /// command copied a while ago
+        #[arg(long = "refresh-timestamps")]
+        refresh_timestamps: bool,
+    },
+    #[command(
+        about = "Compares the requests of two curl commands: method, URL, query, headers and body"
+    )]
+    Diff {
+        /// The curl command to compare from
+        from: String,
+
+        /// The curl command to compare to
+        to: String,
+
+        /// Output format of the difference
+        #[arg(short = 'f', long = "format", value_enum, default_value_t = DiffFormat::Text)]
+        format: DiffFormat,
+
+        /// Reads the files of `-d @file` style arguments and uses their
+        /// contents instead
+        #[arg(long = "resolve-files")]
+        resolve_files: bool,
+
+        /// Reads the config files of `-K file` options and uses their
+        /// options instead
+        #[arg(long = "resolve-config")]
+        resolve_config: bool,
+
+        /// Leaves volatile parameters out of the comparison
+        #[arg(long = "ignore-volatile")]
+        ignore_volatile: bool,
+
+        #[command(flatten)]
+        volatile: VolatileArgs,
+    },
+    #[command(about = "Prints the unique requests of batches of curl commands or HAR files")]
+    Dedup {
+        /// Files of curl commands, HAR files with `--from har` or DevTools
+        /// exports with `--from chrome` (`-` or none for stdin)
+        inputs: Vec<PathBuf>,
+
+        /// The format of the inputs
+        #[arg(long = "from", value_enum, default_value_t = ConvertFrom::Curl)]
+        from: ConvertFrom,
+
+        /// Only keeps the commands whose request matches the expression,
+        /// e.g. `method == "POST" && url.host endswith "example.com"`
+        #[arg(long = "where", value_name = "EXPR")]
+        filter: Option<Filter>,
+
+        #[command(flatten)]
+        volatile: VolatileArgs,
+    },
+    #[command(
+        about = "Profiles batches of curl commands or HAR files: methods, hosts, headers, flags and bodies"
+    )]
+    Stats {
+        /// Files of curl commands, HAR files with `--from har` or DevTools
+        /// exports with `--from chrome` (`-` or none for stdin)
+        inputs: Vec<PathBuf>,
+
+        /// The format of the inputs
+        #[arg(long = "from", value_enum, default_value_t = ConvertFrom::Curl)]
+        from: ConvertFrom,
+
+        /// Only keeps the commands whose request matches the expression,
+        /// e.g. `method == "POST" && url.host endswith "example.com"`
+        #[arg(long = "where", value_name = "EXPR")]
+        filter: Option<Filter>,
+
+        /// How many of the most frequent methods, hosts, headers and flags
+        /// to show
+        #[arg(long = "top", default_value_t = stats::DEFAULT_TOP)]
+        top: usize,
+
+        #[arg(short = 'f', long = "format", value_enum, default_value_t = StatsFormat::Text)]
+        format: StatsFormat,
+    },
+    #[command(
+        about = "Replaces the values of volatile parameters of a curl command with placeholders"
+    )]
+    Templatize {
+        /// The input curl command string
+        command: String,
+
+        #[command(flatten)]
+        volatile: VolatileArgs,
+    },
+    #[command(
+        about = "Lists the URLs a curl command requests, expanding its {a,b} and [1-10] globs, each with the file its -o saves it to"
+    )]
+    Expand {
+        /// The input curl command string
+        command: String,
+
+        /// Prints the number of URLs instead of the URLs
+        #[arg(long = "count")]
+        count: bool,
+    },
+    #[command(about = "Says what every option of a curl command does, flagging the risky ones")]
+    Explain {
+        /// The input curl command string
+        command: String,
+
+        #[arg(short = 'f', long = "format", value_enum, default_value_t = ExplainFormat::Text)]
+        format: ExplainFormat,
+
+        /// The shell the command was written for (bash, Windows cmd or
+        /// PowerShell), guessed from its line continuations by default
+        #[arg(long = "dialect", value_enum, default_value_t = Dialect::Auto)]
+        dialect: Dialect,
     },
+    #[command(
+        about = "Checks the curl commands of scripts, runbooks and Markdown docs against lint rules, failing on denied ones"
+    )]
+    Lint {
+        /// Files holding curl commands; only the fenced code blocks of
+        /// Markdown files are read (`-` or none for stdin)
+        inputs: Vec<PathBuf>,
+
+        /// Fails on a rule, by code or name, e.g. `WCL004` or
+        /// `shadowed-option` (repeatable)
+        #[arg(long = "deny", value_name = "RULE")]
+        deny: Vec<Code>,
+
+        /// Turns a rule off (repeatable)
+        #[arg(long = "allow", value_name = "RULE")]
+        allow: Vec<Code>,
+
+        #[arg(short = 'f', long = "format", value_enum, default_value_t = LintFormat::Text)]
+        format: LintFormat,
+
+        /// The shell the commands were written for (bash, Windows cmd or
+        /// PowerShell), guessed from their line continuations by default
+        #[arg(long = "dialect", value_enum, default_value_t = Dialect::Auto)]
+        dialect: Dialect,
+
+        /// Findings accepted so far, only the others being reported; the
+        /// file is created with the current findings if it doesn't exist
+        #[arg(long = "baseline", value_name = "PATH")]
+        baseline: Option<PathBuf>,
+
+        /// Records the current findings in the baseline, replacing it
+        #[arg(long = "update-baseline", requires = "baseline")]
+        update_baseline: bool,
+    },
+    #[command(about = "Lists diagnostic codes or explains one of them")]
+    Codes {
+        /// A code (e.g. WCL010) or name (e.g. insecure) to explain
+        code: Option<String>,
+    },
+    #[command(
+        about = "Records curl commands into a script replayed later, values of a response filling the next commands"
+    )]
+    Session {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionAction {
+    #[command(about = "Appends curl commands to a session script, creating it if needed")]
+    Record {
+        /// The session script, a JSON file
+        script: PathBuf,
+
+        /// The curl commands to append, several chained with `&&`, `;` or
+        /// newlines, or `-` to read them from stdin
+        command: String,
+
+        /// Stores a value of the response of the last command, at a JSONPath
+        /// of its body (`token=$.data.token`) or in a header
+        /// (`next=header:Location`), for the next commands to use as
+        /// `{{token}}` (repeatable)
+        #[arg(long = "extract", value_name = "NAME=PATH")]
+        extract: Vec<Extraction>,
+    },
+    #[cfg(feature = "exec")]
+    #[command(about = "Performs the requests of a session script one after another")]
+    Replay {
+        /// The session script, a JSON file
+        script: PathBuf,
+
+        /// Sets a variable before the first step (repeatable)
+        #[arg(long = "var", value_name = "NAME=VALUE", value_parser = query_arg)]
+        vars: Vec<(String, String)>,
+
+        /// Sends DELETE, PUT and PATCH requests without asking, unless the
+        /// configuration file denies them
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+}
+
+/// The `--volatile` options, shared by the commands normalizing requests.
+#[derive(Debug, Clone, clap::Args)]
+struct VolatileArgs {
+    /// Treats query and body parameters matching the pattern as volatile,
+    /// where `*` matches any characters (repeatable)
+    #[arg(long = "volatile", value_name = "PATTERN")]
+    volatile: Vec<String>,
+
+    /// Only treats the parameters given with `--volatile` as volatile, not
+    /// the default timestamps and cache busters
+    #[arg(long = "no-default-volatile")]
+    no_default_volatile: bool,
+}
+
+impl VolatileArgs {
+    fn params(&self) -> VolatileParams {
+        let defaults = if self.no_default_volatile {
+            VolatileParams::none()
+        } else {
+            VolatileParams::default()
+        };
+        self.volatile
+            .iter()
+            .fold(defaults, |params, pattern| params.with_pattern(pattern))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ParseFormat {
+    Debug,
+    Json,
+    Yaml,
+    /// One compact JSON document per line
+    Ndjson,
+}
+
+impl ParseFormat {
+    /// Render `value` in a structured format, `None` for [`ParseFormat::Debug`].
+    fn render<T: serde::Serialize>(&self, value: &T) -> Option<Result<String, String>> {
+        match self {
+            ParseFormat::Debug => None,
+            ParseFormat::Json => {
+                Some(serde_json::to_string_pretty(value).map_err(|e| e.to_string()))
+            }
+            ParseFormat::Yaml => Some(serde_yaml::to_string(value).map_err(|e| e.to_string())),
+            ParseFormat::Ndjson => Some(serde_json::to_string(value).map_err(|e| e.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RedactMode {
+    Mask,
+    Hash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConvertFrom {
+    Curl,
+    Har,
+    /// A "Copy all as cURL" export of Chrome's DevTools, for bash or cmd
+    Chrome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiffFormat {
+    /// The differences grouped by component
+    Text,
+    /// The differences grouped by component, as JSON
+    Json,
+    /// The RFC 6902 patch turning one body into the other
+    JsonPatch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatsFormat {
+    /// Histograms
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExplainFormat {
+    /// One option per line, risky ones marked with `!!` or `!`
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LintFormat {
+    /// One finding per line, prefixed with its file and line
+    Text,
+    Json,
+}
+
+/// A finding of `lint` along with the file it was found in.
+#[derive(serde::Serialize)]
+struct LintEntry<'f> {
+    file: String,
+    #[serde(flatten)]
+    finding: &'f lint::Finding,
+}
+
+/// The result of one command of a batch, as printed by `parse`.
+#[derive(serde::Serialize)]
+struct BatchEntry<'c, 'a> {
+    line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<ParsedCommand<'c, 'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'c Diagnostic>,
+}
+
+/// A `--set-header` value, `Name: value`.
+fn header_arg(arg: &str) -> Result<(String, String), String> {
+    header::split_header(arg)
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected `Name: value`, found `{}`", arg))
+}
+
+/// An `--add-query` value, `name=value`, the value being optional.
+fn query_arg(arg: &str) -> Result<(String, String), String> {
+    let (name, value) = arg.split_once('=').unwrap_or((arg, ""));
+    if name.is_empty() {
+        return Err(format!("expected `name=value`, found `{}`", arg));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// Read a whole file, or stdin for `-`.
+fn read_input(path: &std::path::Path) -> std::io::Result<String> {
+    let mut text = String::new();
+    if path.as_os_str() == "-" {
+        std::io::stdin().read_to_string(&mut text)?;
+    } else {
+        text = std::fs::read_to_string(path)?;
+    }
+    Ok(text)
+}
+
+/// Read every input, stdin when none is given; `None` once an error was
+/// reported.
+fn read_inputs(mut inputs: Vec<PathBuf>) -> Option<Vec<(PathBuf, String)>> {
+    if inputs.is_empty() {
+        inputs.push(PathBuf::from("-"));
+    }
+    let mut texts = Vec::new();
+    for path in inputs {
+        match read_input(&path) {
+            Ok(text) => texts.push((path, text)),
+            Err(e) => {
+                eprintln!("Error reading {}: {}", path.display(), e);
+                return None;
+            }
+        }
+    }
+    Some(texts)
+}
+
+/// Parse the commands of batches of curl commands or HAR files matching
+/// `filter`, reporting the commands that fail to parse; `None` when a HAR
+/// file does.
+fn read_commands<'t>(
+    texts: &'t [(PathBuf, String)],
+    from: ConvertFrom,
+    filter: Option<&Filter>,
+) -> Option<Vec<Vec<Curl<'t>>>> {
+    let mut commands: Vec<Vec<Curl>> = Vec::new();
+    for (path, text) in texts {
+        match from {
+            ConvertFrom::Curl => {
+                for invocation in batch::split_commands(text, Dialect::Auto) {
+                    match curl_cmd_parse(invocation.source) {
+                        Ok(curls) => commands.push(curls),
+                        Err(e) => eprintln!("{}:{}: {}", path.display(), invocation.line, e),
+                    }
+                }
+            }
+            ConvertFrom::Chrome => {
+                for request in devtools::split_copy_all(text, Dialect::Auto) {
+                    match request.parse() {
+                        Ok(curls) => commands.push(curls),
+                        Err(e) => eprintln!("{}:{}: {}", path.display(), request.line, e),
+                    }
+                }
+            }
+            ConvertFrom::Har => match har::Har::from_json(text) {
+                Ok(har) => {
+                    for request in har.requests() {
+                        commands.push(request.to_curls());
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error parsing HAR file {}: {}", path.display(), e);
+                    return None;
+                }
+            },
+        }
+    }
+    if let Some(filter) = filter {
+        commands.retain(|curls| filter.matches_curls(curls));
+    }
+    Some(commands)
+}
+
+/// How the commands given on the command line are read.
+#[derive(Debug, Clone, Default)]
+struct ParseSettings {
+    /// The shell the commands were written for.
+    dialect: Dialect,
+    /// Inline the files bodies are read from.
+    resolve_files: bool,
+    /// Inline the options of `-K` config files.
+    resolve_config: bool,
+    /// Report the problems met on stderr instead of failing the parse.
+    lenient: bool,
+    /// Refuse the methods no server would take.
+    strict_methods: bool,
+    /// Hide the secret values of the commands.
+    redaction: Option<Redaction>,
+    /// Write the dates and epoch timestamps of the commands anew for now.
+    refresh_timestamps: bool,
+}
+
+/// The shell of `--shell`, the one `command` was written for when `auto`.
+fn output_shell(shell: Dialect, dialect: Dialect, command: &str) -> Dialect {
+    match shell {
+        Dialect::Auto => dialect.resolve(command),
+        shell => shell,
+    }
+}
+
+/// Print a command written for `shell`, and on stderr what the shell would
+/// mangle in it.
+fn print_command(curls: &[Curl], shell: Dialect, multiline: bool) {
+    if multiline {
+        println!("{}", render::to_curl_string_multiline_dialect(curls, shell));
+    } else {
+        println!("{}", render::to_curl_string_dialect(curls, shell));
+    }
+    for found in shell::find(curls, shell) {
+        eprintln!("{}", found.diagnostic());
+    }
+}
+
+/// Print the status line, headers and body of a response.
+#[cfg(feature = "exec")]
+fn print_response(response: &exec::Response) {
+    use std::io::Write;
+
+    print_head(response);
+    println!();
+    let _ = std::io::stdout().write_all(&response.body);
+}
+
+/// Print the status line and headers of a response.
+#[cfg(feature = "exec")]
+fn print_head(response: &exec::Response) {
+    println!("{}", response.status_line());
+    for (name, value) in &response.headers {
+        println!("{}: {}", name, value);
+    }
+}
+
+/// How destructive requests are confirmed: as the configuration file says,
+/// asking on the terminal when there is one. `None` once an error was
+/// reported.
+#[cfg(feature = "exec")]
+fn confirmation(yes: bool) -> Option<exec::confirm::Confirmation> {
+    use exec::confirm::{Config, Confirmation};
+    use std::io::IsTerminal;
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return None;
+        }
+    };
+    let confirmation = Confirmation::new(config.destructive_methods).yes(yes);
+    if !std::io::stdin().is_terminal() {
+        return Some(confirmation);
+    }
+    Some(confirmation.prompt(|request| {
+        exec::confirm::ask(
+            request,
+            &mut std::io::stdin().lock(),
+            &mut std::io::stderr(),
+        )
+        .unwrap_or(false)
+    }))
+}
+
+/// Print the response to the request of `curls`, its body to the file of
+/// `-o` if any, along with the exit code curl would end with: 0 unless
+/// `--fail` or `--fail-with-body` failed it, the file couldn't be written or
+/// the request couldn't be made, with no response then.
+#[cfg(feature = "exec")]
+fn exec_result(
+    curls: &[Curl],
+    response: Result<exec::Response, exec::ExecError>,
+) -> (Option<exec::Response>, i32) {
+    use std::io::Write;
+
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!("{}", e);
+            return (None, e.exit_code());
+        }
+    };
+    // Already checked while preparing the request.
+    let fail = exec::FailMode::new(curls).unwrap_or_default();
+    let mut code = 0;
+    if fail.shows_response(response.status) {
+        match exec::output_path(curls) {
+            // The status line and headers are still shown, the body goes
+            // to the file of `-o`.
+            Some(path) => {
+                print_head(&response);
+                if let Err(e) = exec::save_body(curls, &response, &path) {
+                    eprintln!("Failed to write {}: {}", path.display(), e);
+                    code = exec::WRITE_EXIT_CODE;
+                }
+            }
+            None => print_response(&response),
+        }
+    }
+    if !fail.fails(response.status) {
+        return (Some(response), code);
+    }
+    let _ = std::io::stdout().flush();
+    eprintln!("The requested URL returned error: {}", response.status);
+    (Some(response), exec::FAIL_EXIT_CODE)
+}
+
+/// Perform the requests of `commands` against `base` and against `target`,
+/// each in a session of its own, and print what differs between their
+/// responses, telling whether anything did.
+#[cfg(feature = "exec")]
+fn exec_compare(
+    commands: &str,
+    settings: &ParseSettings,
+    base: &str,
+    target: &str,
+    ignored: &[String],
+    confirmation: exec::confirm::Confirmation,
+) -> bool {
+    use diff::response::ResponseDiff;
+
+    let mut sessions = (
+        exec::Session::new().with_confirmation(confirmation),
+        exec::Session::new().with_confirmation(confirmation),
+    );
+    let mut differs = false;
+    let mut log = std::io::stderr();
+    for invocation in batch::split_commands(commands, settings.dialect) {
+        let curls = match parse_command(invocation.source, settings) {
+            Ok(curls) => curls,
+            Err(e) => {
+                eprintln!("{}", e);
+                differs = true;
+                continue;
+            }
+        };
+        for part in exec::next_parts(&curls) {
+            let rebased = (
+                rewrite::rewrite(part, &[Edit::Rebase(base.into(), base.into())]),
+                rewrite::rewrite(part, &[Edit::Rebase(base.into(), target.into())]),
+            );
+            let (from, to) = match rebased {
+                (Ok(from), Ok(to)) => (from, to),
+                (Err(e), _) | (_, Err(e)) => {
+                    eprintln!("{}", e);
+                    differs = true;
+                    continue;
+                }
+            };
+            let url = |curls: &[Curl]| {
+                curls.iter().find_map(|curl| match curl {
+                    Curl::URL(url) => Some(url.to_string()),
+                    _ => None,
+                })
+            };
+            println!(
+                "{} {} -> {}",
+                method::Method::of(&from),
+                url(&from).unwrap_or_default(),
+                url(&to).unwrap_or_default()
+            );
+            let responses = (
+                sessions.0.execute_with_log(&from, &mut log),
+                sessions.1.execute_with_log(&to, &mut log),
+            );
+            match responses {
+                (Ok(from), Ok(to)) => {
+                    let diff = ResponseDiff::new(&from, &to, ignored);
+                    if diff.is_empty() {
+                        println!("same");
+                    } else {
+                        print!("{}", diff);
+                        differs = true;
+                    }
+                }
+                (Err(e), _) | (_, Err(e)) => {
+                    eprintln!("{}", e);
+                    differs = true;
+                }
+            }
+        }
+    }
+    differs
+}
+
+/// Parse a command the way `settings` say.
+fn parse_command<'a>(
+    command: &'a str,
+    settings: &ParseSettings,
+) -> Result<Vec<Curl<'a>>, ParseError> {
+    let mut curls = if settings.lenient {
+        let parsed = curl_cmd_parse_dialect_lossy(command, settings.dialect);
+        for warning in ParseError::render_all(&parsed.warnings) {
+            eprintln!("{}", warning);
+        }
+        parsed.curls
+    } else {
+        curl_cmd_try_parse_dialect(command, settings.dialect)?
+    };
+    if settings.strict_methods {
+        method::validate_strict(&curls)?;
+    }
+    if settings.resolve_config {
+        curls = curlrc::inline_configs(&curls, &FileResolver::new()).map_err(ParseError::from)?;
+    }
+    if settings.resolve_files {
+        curls = body::inline_files(&curls, &FileResolver::new())
+            .map_err(|e| ParseError::from(e.diagnostic()))?;
+    }
+    if settings.refresh_timestamps {
+        curls = timestamps::refresh(&curls, SystemTime::now());
+    }
+    Ok(match &settings.redaction {
+        Some(redaction) => curl::secrets::redact(&curls, redaction),
+        None => curls,
+    })
+}
+
+/// Keep the parts of a command selected with `--part` and `--header-name`.
+fn select_part<'a>(
+    curls: Vec<Curl<'a>>,
+    part: Option<CurlCommand>,
+    header_name: Option<&str>,
+) -> Vec<Curl<'a>> {
+    let Some(header_name) = header_name else {
+        return curls
+            .into_iter()
+            .filter(|c| part.is_none_or(|part_type| part_type.matches_curl(c)))
+            .collect();
+    };
+    curls
+        .into_iter()
+        .filter(|c| Header::from_curl(c).is_some_and(|header| header.is(header_name)))
+        .collect()
+}
+
+/// Parse every command of `text` and print the results one after another.
+fn parse_batch(
+    text: &str,
+    part: Option<CurlCommand>,
+    header_name: Option<&str>,
+    filter: Option<&Filter>,
+    output: ParseFormat,
+    settings: &ParseSettings,
+) {
+    let results: Vec<(usize, Result<Vec<Curl>, Diagnostic>)> =
+        batch::split_commands(text, settings.dialect)
+            .into_iter()
+            .filter_map(|invocation| {
+                let curls = parse_command(invocation.source, settings).map_err(Diagnostic::from);
+                if let (Ok(curls), Some(filter)) = (&curls, filter)
+                    && !filter.matches_curls(curls)
+                {
+                    return None;
+                }
+                let curls = curls.map(|curls| select_part(curls, part, header_name));
+                Some((invocation.line, curls))
+            })
+            .collect();
+    let entries: Vec<BatchEntry> = results
+        .iter()
+        .map(|(line, result)| BatchEntry {
+            line: *line,
+            command: result.as_deref().ok().map(ParsedCommand::new),
+            error: result.as_ref().err(),
+        })
+        .collect();
+
+    match output {
+        ParseFormat::Debug => {
+            for (i, (line, result)) in results.iter().enumerate() {
+                match result {
+                    Ok(curls) => {
+                        if i > 0 {
+                            println!();
+                        }
+                        println!("# line {}", line);
+                        for curl in curls {
+                            println!("{:?}", curl);
+                        }
+                    }
+                    Err(e) => eprintln!("line {}: {}", line, e),
+                }
+            }
+        }
+        ParseFormat::Ndjson => {
+            for entry in &entries {
+                match output.render(entry) {
+                    Some(Ok(rendered)) => println!("{}", rendered),
+                    Some(Err(e)) => eprintln!("Error serializing curl command: {}", e),
+                    None => unreachable!("NDJSON is a structured format"),
+                }
+            }
+        }
+        ParseFormat::Json | ParseFormat::Yaml => match output.render(&entries) {
+            Some(Ok(rendered)) => println!("{}", rendered.trim_end()),
+            Some(Err(e)) => eprintln!("Error serializing curl commands: {}", e),
+            None => unreachable!("{:?} is a structured format", output),
+        },
+    }
+}
+
+/// Report on stderr what was lost converting `curls` to `target`, and the
+/// CORS problems of commands copied from a browser when `target` makes
+/// requests.
+fn report(curls: &[Curl], target: Target) {
+    for diagnostic in ConversionReport::new(target, curls).diagnostics() {
+        eprintln!("{}", diagnostic);
+    }
+    if target != Target::Openapi
+        && let Some(analysis) = Request::try_from(curls)
+            .ok()
+            .as_ref()
+            .and_then(OriginAnalysis::new)
+    {
+        for diagnostic in analysis.diagnostics(target == Target::Fetch) {
+            eprintln!("{}", diagnostic);
+        }
+    }
+}
+
+/// Print the code generated for `target`, reporting what was lost on stderr.
+/// Returns whether the command could be converted.
+fn convert(curls: &[Curl], target: Target) -> bool {
+    match target.generator().generate(curls) {
+        Ok(code) => {
+            print!("{}", code);
+            report(curls, target);
+            true
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            false
+        }
+    }
 }
 
 fn main() {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Parse { command, part } => match curl_cmd_parse(&command) {
+        Commands::Parse {
+            command,
+            file,
+            part,
+            header_name,
+            filter,
+            output,
+            resolve_files,
+            resolve_config,
+            dialect,
+            lenient,
+            strict_methods,
+            redact,
+            salt,
+        } => {
+            let settings = ParseSettings {
+                dialect,
+                resolve_files,
+                resolve_config,
+                lenient,
+                strict_methods,
+                redaction: redact.map(|mode| match mode {
+                    RedactMode::Mask => Redaction::Mask,
+                    RedactMode::Hash => Redaction::hash(&salt.unwrap_or_default()),
+                }),
+                ..ParseSettings::default()
+            };
+            let command = match (command, file) {
+                (Some(command), None) if command != "-" => command,
+                (command, file) => {
+                    let path = file.unwrap_or_else(|| PathBuf::from(command.unwrap_or_default()));
+                    match read_input(&path) {
+                        Ok(text) => parse_batch(
+                            &text,
+                            part,
+                            header_name.as_deref(),
+                            filter.as_ref(),
+                            output,
+                            &settings,
+                        ),
+                        Err(e) => eprintln!("Error reading {}: {}", path.display(), e),
+                    }
+                    return;
+                }
+            };
+
+            match parse_command(&command, &settings) {
+                Ok(curls) if filter.as_ref().is_some_and(|f| !f.matches_curls(&curls)) => {}
+                Ok(curls) if header_name.is_some() => {
+                    let headers: Vec<Header> = select_part(curls, part, header_name.as_deref())
+                        .iter()
+                        .filter_map(Header::from_curl)
+                        .collect();
+                    match output.render(&headers) {
+                        None => {
+                            for header in &headers {
+                                println!("{:?}", header);
+                                for cookie in header.cookies() {
+                                    println!("  {:?}", cookie);
+                                }
+                            }
+                        }
+                        Some(Ok(rendered)) => println!("{}", rendered.trim_end()),
+                        Some(Err(e)) => eprintln!("Error serializing headers: {}", e),
+                    }
+                }
+                Ok(curls) => {
+                    let filtered_curls = select_part(curls, part, None);
+                    match output.render(&ParsedCommand::new(&filtered_curls)) {
+                        None => {
+                            for curl in &filtered_curls {
+                                println!("{:?}", curl);
+                            }
+                        }
+                        Some(Ok(rendered)) => println!("{}", rendered.trim_end()),
+                        Some(Err(e)) => eprintln!("Error serializing curl command: {}", e),
+                    }
+                }
+                Err(e) => {
+                    match output.render(&e) {
+                        None => eprintln!("{}", e.render()),
+                        Some(Ok(rendered)) => println!("{}", rendered.trim_end()),
+                        Some(Err(e)) => eprintln!("Error serializing diagnostic: {}", e),
+                    }
+                    std::process::exit(2);
+                }
+            }
+        }
+        Commands::Format {
+            command,
+            fix,
+            pretty_body,
+            canonical,
+            unquirk,
+            dialect,
+            shell,
+        } => match curl_cmd_try_parse_dialect(&command, dialect) {
+            Ok(curls) if fix || pretty_body || canonical || unquirk => {
+                let curls = if fix {
+                    escape::fix(&curls)
+                } else {
+                    curls.into_iter().map(Curl::into_owned).collect()
+                };
+                let curls = if unquirk {
+                    quirks::normalize_copied(&curls)
+                } else if canonical {
+                    canonical::canonicalize(&curls)
+                } else {
+                    curls
+                };
+                let shell = output_shell(shell, dialect, &command);
+                if pretty_body {
+                    print_command(&body::pretty_json(&curls), shell, true);
+                } else {
+                    print_command(&curls, shell, false);
+                }
+            }
+            Ok(curls) => {
+                print_command(&curls, output_shell(shell, dialect, &command), false);
+                for unencoded in escape::find_unencoded(&curls) {
+                    eprintln!("{}", unencoded.diagnostic());
+                }
+                for shadowed in request::shorthand::shadowed(&curls) {
+                    eprintln!("{}", shadowed.diagnostic());
+                }
+                if let Ok(request) = Request::try_from(curls.as_slice()) {
+                    for found in request::referer::cross_host_credentials(&request) {
+                        eprintln!("{}", found.diagnostic());
+                    }
+                    if let Some(found) = request::cookies::foreign_cookies(&request) {
+                        eprintln!("{}", found.diagnostic());
+                    }
+                    if let Some(diagnostic) = request::body::check(&request) {
+                        eprintln!("{}", diagnostic);
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", e.render());
+                std::process::exit(2);
+            }
+        },
+        Commands::Rewrite {
+            command,
+            strip_header,
+            strip_query,
+            set_header,
+            add_query,
+            set_host,
+            set_method,
+            refresh_timestamps,
+            dialect,
+            shell,
+        } => {
+            let edits: Vec<Edit> = strip_header
+                .into_iter()
+                .map(Edit::StripHeader)
+                .chain(strip_query.into_iter().map(Edit::StripQuery))
+                .chain(set_header.into_iter().map(|(n, v)| Edit::SetHeader(n, v)))
+                .chain(add_query.into_iter().map(|(n, v)| Edit::AddQuery(n, v)))
+                .chain(set_host.map(Edit::SetHost))
+                .chain(set_method.map(Edit::SetMethod))
+                .collect();
+            let shell = output_shell(shell, dialect, &command);
+            match curl_cmd_try_parse_dialect(&command, dialect) {
+                Ok(curls) => match rewrite::rewrite(&curls, &edits) {
+                    Ok(curls) if refresh_timestamps => print_command(
+                        &timestamps::refresh(&curls, SystemTime::now()),
+                        shell,
+                        false,
+                    ),
+                    Ok(curls) => print_command(&curls, shell, false),
+                    Err(e) => eprintln!("{}", e),
+                },
+                Err(e) => {
+                    eprintln!("{}", e.render());
+                    std::process::exit(2);
+                }
+            }
+        }
+        Commands::Convert {
+            inputs,
+            file,
+            from,
+            target,
+            collection_name,
+            filter,
+            resolve_files,
+            resolve_config,
+            dialect,
+        } => {
+            let settings = ParseSettings {
+                dialect,
+                resolve_files,
+                resolve_config,
+                ..ParseSettings::default()
+            };
+            let batch = match &file {
+                Some(path) => match read_input(path) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        eprintln!("Error reading {}: {}", path.display(), e);
+                        std::process::exit(2);
+                    }
+                },
+                None => String::new(),
+            };
+            let mut commands: Vec<Vec<Curl>> = Vec::new();
+            let mut failed = false;
+            for invocation in batch::split_commands(&batch, settings.dialect) {
+                match parse_command(invocation.source, &settings) {
+                    Ok(curls) => commands.push(curls),
+                    Err(e) => {
+                        eprintln!("line {}: {}", invocation.line, e);
+                        failed = true;
+                    }
+                }
+            }
+            for input in &inputs {
+                match from {
+                    ConvertFrom::Curl => match parse_command(input, &settings) {
+                        Ok(curls) => commands.push(curls),
+                        Err(e) => {
+                            eprintln!("{}", e.render());
+                            std::process::exit(2);
+                        }
+                    },
+                    ConvertFrom::Har => {
+                        let har = match std::fs::read_to_string(input) {
+                            Ok(json) => har::Har::from_json(&json),
+                            Err(e) => {
+                                eprintln!("Error reading {}: {}", input, e);
+                                std::process::exit(2);
+                            }
+                        };
+                        match har {
+                            Ok(har) => {
+                                for request in har.requests() {
+                                    commands.push(request.to_curls());
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("Error parsing HAR file {}: {}", input, e);
+                                std::process::exit(2);
+                            }
+                        }
+                    }
+                    ConvertFrom::Chrome => {
+                        let text = match read_input(std::path::Path::new(input)) {
+                            Ok(text) => text,
+                            Err(e) => {
+                                eprintln!("Error reading {}: {}", input, e);
+                                std::process::exit(2);
+                            }
+                        };
+                        match devtools::import_copy_all(&text, Dialect::Auto) {
+                            Ok(imported) => commands.extend(imported.into_iter().map(|curls| {
+                                curls.into_iter().map(Curl::into_owned).collect::<Vec<_>>()
+                            })),
+                            Err(e) => {
+                                eprintln!("Error importing {}: {}", input, e);
+                                std::process::exit(2);
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(filter) = &filter {
+                commands.retain(|curls| filter.matches_curls(curls));
+            }
+
+            if target == Some(Target::Postman) {
+                let collection =
+                    Collection::new(&collection_name, commands.iter().map(Vec::as_slice));
+                match serde_json::to_string_pretty(&collection) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => eprintln!("Error serializing collection: {}", e),
+                }
+                for curls in &commands {
+                    match Request::try_from(curls.as_slice()) {
+                        Ok(_) => report(curls, Target::Postman),
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            failed = true;
+                        }
+                    }
+                }
+                if failed {
+                    std::process::exit(2);
+                }
+                return;
+            }
+
+            if target == Some(Target::Openapi) {
+                let document =
+                    openapi::Document::new(&collection_name, commands.iter().map(Vec::as_slice));
+                match serde_json::to_string_pretty(&document) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => eprintln!("Error serializing OpenAPI document: {}", e),
+                }
+                for curls in &commands {
+                    report(curls, Target::Openapi);
+                }
+                if failed {
+                    std::process::exit(2);
+                }
+                return;
+            }
+
+            for (i, curls) in commands.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                let converted = match (from, target) {
+                    (_, Some(target)) => convert(curls, target),
+                    (ConvertFrom::Curl, None) => convert(curls, Target::Reqwest),
+                    (ConvertFrom::Har | ConvertFrom::Chrome, None) => {
+                        println!("{}", render::to_curl_string(curls));
+                        true
+                    }
+                };
+                failed |= !converted;
+            }
+            if failed {
+                std::process::exit(2);
+            }
+        }
+        #[cfg(feature = "exec")]
+        Commands::Exec {
+            command,
+            extract,
+            compare,
+            base,
+            target,
+            ignore_headers,
+            yes,
+            refresh_timestamps,
+        } => {
+            let Some(confirmation) = confirmation(yes) else {
+                std::process::exit(2);
+            };
+            // curl always reads the files, so the request has to as well.
+            let settings = ParseSettings {
+                resolve_files: true,
+                resolve_config: true,
+                refresh_timestamps,
+                ..ParseSettings::default()
+            };
+            if compare {
+                let base = base.unwrap_or_default();
+                let target = target.unwrap_or_default();
+                if exec_compare(
+                    &command,
+                    &settings,
+                    &base,
+                    &target,
+                    &ignore_headers,
+                    confirmation,
+                ) {
+                    std::process::exit(1);
+                }
+                return;
+            }
+            // Chained commands and the parts of a command separated by
+            // `--next` share their cookies.
+            let mut session = exec::Session::new().with_confirmation(confirmation);
+            let mut variables = indexmap::IndexMap::new();
+            // Like curl, the command ends with the code of the last error.
+            let mut exit_code = 0;
+            for invocation in batch::split_commands(&command, settings.dialect) {
+                let curls = match parse_command(invocation.source, &settings) {
+                    // Placeholders are only filled when values are extracted.
+                    Ok(curls) if !extract.is_empty() => {
+                        match session::substitute(&curls, &variables) {
+                            Ok(curls) => curls,
+                            Err(name) => {
+                                eprintln!("Unknown variable {{{{{}}}}}", name);
+                                exit_code = exec::USAGE_EXIT_CODE;
+                                break;
+                            }
+                        }
+                    }
+                    Ok(curls) => curls.into_iter().map(Curl::into_owned).collect(),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        exit_code = exec::USAGE_EXIT_CODE;
+                        continue;
+                    }
+                };
+                // One transfer per URL; `-Z` runs those of every part
+                // together, as curl does, while the others are printed as
+                // soon as they are done.
+                let transfers: Vec<_> = exec::next_parts(&curls)
+                    .flat_map(parallel::transfers)
+                    .collect();
+                let settings = parallel::ParallelSettings::new(&curls);
+                let batch = if settings.parallel {
+                    transfers.len()
+                } else {
+                    1
+                };
+                for transfers in transfers.chunks(batch.max(1)) {
+                    let responses =
+                        session.execute_all(transfers, &settings, &mut std::io::stderr());
+                    for (transfer, response) in transfers.iter().zip(responses) {
+                        let (response, code) = exec_result(transfer, response);
+                        if code != 0 {
+                            exit_code = code;
+                        }
+                        if let Some(response) = &response {
+                            for rule in &extract {
+                                rule.apply(response, &mut variables);
+                            }
+                        }
+                    }
+                }
+            }
+            if exit_code != 0 {
+                std::process::exit(exit_code);
+            }
+        }
+        Commands::Diff {
+            from,
+            to,
+            format,
+            resolve_files,
+            resolve_config,
+            ignore_volatile,
+            volatile,
+        } => {
+            let settings = ParseSettings {
+                resolve_files,
+                resolve_config,
+                ..ParseSettings::default()
+            };
+            let (from, to) = match (
+                parse_command(&from, &settings),
+                parse_command(&to, &settings),
+            ) {
+                (Ok(from), Ok(to)) => (from, to),
+                (Err(e), _) | (_, Err(e)) => {
+                    eprintln!("{}", e);
+                    std::process::exit(2);
+                }
+            };
+
+            let (from, to): (Vec<Curl>, Vec<Curl>) = if ignore_volatile {
+                let params = volatile.params();
+                (
+                    curl::volatile::rewrite(&from, &params, VolatileAction::Remove),
+                    curl::volatile::rewrite(&to, &params, VolatileAction::Remove),
+                )
+            } else {
+                (from, to)
+            };
+            let requests = (
+                Request::try_from(from.as_slice()),
+                Request::try_from(to.as_slice()),
+            );
+            let diff = match requests {
+                (Ok(from), Ok(to)) => RequestDiff::new(&from, &to),
+                (Err(e), _) | (_, Err(e)) => {
+                    eprintln!("{}", e);
+                    std::process::exit(2);
+                }
+            };
+            match format {
+                DiffFormat::Text => print!("{}", diff),
+                DiffFormat::Json => match serde_json::to_string_pretty(&diff) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => eprintln!("Error serializing the difference: {}", e),
+                },
+                DiffFormat::JsonPatch => {
+                    println!("{}", json_patch::to_patch_document(&diff.body));
+                }
+            }
+        }
+        Commands::Dedup {
+            inputs,
+            from,
+            filter,
+            volatile,
+        } => {
+            let Some(texts) = read_inputs(inputs) else {
+                return;
+            };
+            let Some(commands) = read_commands(&texts, from, filter.as_ref()) else {
+                return;
+            };
+
+            let total = commands.len();
+            let unique = dedup::dedup(commands, &volatile.params());
+            for (i, curls) in unique.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!("{}", render::to_curl_string(curls));
+            }
+            eprintln!("{} unique of {} requests", unique.len(), total);
+        }
+        Commands::Stats {
+            inputs,
+            from,
+            filter,
+            top,
+            format,
+        } => {
+            let Some(texts) = read_inputs(inputs) else {
+                return;
+            };
+            let Some(commands) = read_commands(&texts, from, filter.as_ref()) else {
+                return;
+            };
+            let mut stats = BatchStats::new(commands.iter().map(Vec::as_slice));
+            stats.truncate(top);
+            match format {
+                StatsFormat::Text => print!("{}", stats),
+                StatsFormat::Json => match serde_json::to_string_pretty(&stats) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => eprintln!("Error serializing the statistics: {}", e),
+                },
+            }
+        }
+        Commands::Templatize { command, volatile } => match curl_cmd_parse(&command) {
             Ok(curls) => {
-                let filtered_curls = curls
-                    .iter()
-                    .filter(|c| part.map_or(true, |part_type| part_type.matches_curl(c)));
-                for curl in filtered_curls {
-                    println!("{:?}", curl);
+                let templatized =
+                    curl::volatile::rewrite(&curls, &volatile.params(), VolatileAction::Templatize);
+                println!("{}", render::to_curl_string(&templatized));
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        },
+        Commands::Expand { command, count } => match curl_cmd_parse(&command) {
+            Ok(curls) => match UrlGlob::from_curls(&curls) {
+                Some(Ok(glob)) if count => match glob.count() {
+                    Some(n) => println!("{}", n),
+                    None => {
+                        eprintln!("The glob stands for more than {} URLs", u64::MAX);
+                        std::process::exit(2);
+                    }
+                },
+                Some(Ok(glob)) => {
+                    // The first `-o` or `-O` goes with the URL.
+                    let output = match curls.iter().find(|curl| curl::parallel::is_output(curl)) {
+                        Some(Curl::Option { value, .. }) => Some(OutputTemplate::parse(value)),
+                        _ => None,
+                    };
+                    for found in glob.matches() {
+                        match &output {
+                            Some(output) => {
+                                println!("{}\t{}", found.url, output.resolve(&found.values))
+                            }
+                            None => println!("{}", found.url),
+                        }
+                    }
+                }
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    std::process::exit(2);
+                }
+                None => {
+                    eprintln!("The command has no URL");
+                    std::process::exit(2);
+                }
+            },
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        },
+        Commands::Explain {
+            command,
+            format,
+            dialect,
+        } => match curl_cmd_try_parse_dialect(&command, dialect) {
+            Ok(curls) => {
+                let annotations = explain::explain(&curls);
+                match format {
+                    ExplainFormat::Text => print!("{}", explain::Explanation(&annotations)),
+                    ExplainFormat::Json => match serde_json::to_string_pretty(&annotations) {
+                        Ok(json) => println!("{}", json),
+                        Err(e) => eprintln!("Error serializing the explanation: {}", e),
+                    },
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", e.render());
+                std::process::exit(2);
+            }
+        },
+        Commands::Lint {
+            inputs,
+            deny,
+            allow,
+            format,
+            dialect,
+            baseline,
+            update_baseline,
+        } => {
+            let mut levels = lint::Levels::new();
+            for (codes, level) in [(deny, lint::Level::Deny), (allow, lint::Level::Allow)] {
+                for code in codes {
+                    if !lint::RULES.contains(&code) {
+                        eprintln!("{} is not a lint rule, see `winnowcurl codes`", code);
+                        std::process::exit(2);
+                    }
+                    levels.set(code, level);
+                }
+            }
+            let Some(texts) = read_inputs(inputs) else {
+                std::process::exit(2);
+            };
+            let mut entries = Vec::new();
+            for (path, text) in &texts {
+                match lint::lint_text(text, dialect, &levels) {
+                    Ok(findings) => {
+                        entries.extend(findings.into_iter().map(|finding| (path, finding)))
+                    }
+                    Err(e) => {
+                        eprintln!("{}: {}", path.display(), e);
+                        std::process::exit(2);
+                    }
+                }
+            }
+            if let Some(path) = &baseline {
+                if update_baseline || !path.exists() {
+                    let mut recorded = Baseline::new();
+                    for (_, finding) in &entries {
+                        recorded.record(finding.diagnostic.code, &finding.fingerprint);
+                    }
+                    if let Err(e) = recorded.save(path) {
+                        eprintln!("{}", e);
+                        std::process::exit(2);
+                    }
+                    eprintln!("Recorded {} findings in {}", recorded.len(), path.display());
+                    return;
+                }
+                match Baseline::load(path) {
+                    Ok(accepted) => {
+                        entries = accepted.new_findings(entries, |(_, finding)| {
+                            (finding.diagnostic.code, finding.fingerprint.clone())
+                        })
+                    }
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(2);
+                    }
+                }
+            }
+            match format {
+                LintFormat::Text => {
+                    for (path, finding) in &entries {
+                        println!("{}:{}", path.display(), finding);
+                    }
+                }
+                LintFormat::Json => {
+                    let entries: Vec<_> = entries
+                        .iter()
+                        .map(|(path, finding)| LintEntry {
+                            file: path.display().to_string(),
+                            finding,
+                        })
+                        .collect();
+                    match serde_json::to_string_pretty(&entries) {
+                        Ok(json) => println!("{}", json),
+                        Err(e) => eprintln!("Error serializing the findings: {}", e),
+                    }
+                }
+            }
+            if entries
+                .iter()
+                .any(|(_, finding)| finding.level == lint::Level::Deny)
+            {
+                std::process::exit(1);
+            }
+        }
+        Commands::Session {
+            action:
+                SessionAction::Record {
+                    script,
+                    command,
+                    extract,
+                },
+        } => {
+            let text = if command == "-" {
+                match read_input(std::path::Path::new("-")) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        eprintln!("Error reading -: {}", e);
+                        return;
+                    }
+                }
+            } else {
+                command
+            };
+            // Recording appends to the script, one command at a time if need be.
+            let loaded = if script.exists() {
+                Script::load(&script)
+            } else {
+                Ok(Script::default())
+            };
+            let mut recorded = match loaded {
+                Ok(recorded) => recorded,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return;
+                }
+            };
+            let invocations = batch::split_commands(&text, Dialect::Auto);
+            if invocations.is_empty() {
+                eprintln!("No curl command to record");
+                return;
+            }
+            for invocation in invocations {
+                if let Err(e) = recorded.record(invocation.source) {
+                    eprintln!("{}", e.render());
+                    return;
+                }
+            }
+            if let Some(last) = recorded.steps.last_mut() {
+                last.extract.extend(extract);
+            }
+            if let Err(e) = recorded.save(&script) {
+                eprintln!("{}", e);
+            }
+        }
+        #[cfg(feature = "exec")]
+        Commands::Session {
+            action: SessionAction::Replay { script, vars, yes },
+        } => {
+            let Some(confirmation) = confirmation(yes) else {
+                std::process::exit(2);
+            };
+            let script = match Script::load(&script) {
+                Ok(script) => script,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return;
+                }
+            };
+            let mut replay =
+                session::Replay::new(vars.into_iter().collect()).with_confirmation(confirmation);
+            for (index, step) in script.steps.iter().enumerate() {
+                match replay.step(index + 1, step, &mut std::io::stderr()) {
+                    Ok(responses) => responses.iter().for_each(print_response),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(e.exit_code());
+                    }
+                }
+            }
+        }
+        Commands::Codes { code } => match code {
+            Some(code) => match code.parse::<Code>() {
+                Ok(code) => {
+                    println!("{} ({}, {})", code, code.name(), code.severity().as_str());
+                    println!();
+                    println!("{}", code.explanation());
+                }
+                Err(e) => eprintln!("{}", e),
+            },
+            None => {
+                for code in diagnostic::CATALOG {
+                    println!("{}  {:<8} {}", code, code.severity().as_str(), code.name());
                 }
             }
-            Err(e) => eprintln!("Error parsing curl command: {}", e),
         },
     }
 }
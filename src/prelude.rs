@@ -0,0 +1,5 @@
+pub use crate::curl::render::to_curl_string;
+pub use crate::{
+    Curl, CurlBuilder, CurlURL, Diagnostic, Dialect, LossyParse, OwnedCurl, Request, Schema, parse,
+    parse_dialect, parse_lossy, parse_owned, parse_request,
+};
@@ -0,0 +1,254 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::Serialize;
+
+use super::BasicAuth;
+use crate::curl::parser::Curl;
+
+/// How a request authenticates: `-u` credentials sent with the scheme its
+/// selector flags pick, `--oauth2-bearer` or `--aws-sigv4`.
+///
+/// ```
+/// use winnowcurl::Request;
+/// use winnowcurl::request::auth::Auth;
+///
+/// let request = Request::parse("curl https://a.com --digest -u admin:s3cret")?;
+/// let Some(Auth::Digest(credentials)) = &request.auth else { panic!() };
+/// assert_eq!(credentials.password.as_deref(), Some("s3cret"));
+///
+/// let request = Request::parse("curl https://a.com --oauth2-bearer t0k3n")?;
+/// assert_eq!(request.auth.unwrap().authorization().as_deref(), Some("Bearer t0k3n"));
+/// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "scheme", rename_all = "kebab-case")]
+pub enum Auth {
+    /// `-u`, or `--basic -u`: an `Authorization: Basic` header.
+    Basic(BasicAuth),
+    /// `--digest -u`: the credentials answer the challenge of the server.
+    Digest(BasicAuth),
+    /// `--ntlm -u` or `--ntlm-wb -u`.
+    Ntlm(BasicAuth),
+    /// `--negotiate -u`: SPNEGO, the credentials usually being `-u :`.
+    Negotiate(BasicAuth),
+    /// `--anyauth -u`: the most secure scheme the server offers.
+    Any(BasicAuth),
+    /// `--oauth2-bearer`: an `Authorization: Bearer` header.
+    Bearer { token: String },
+    /// `--aws-sigv4 provider1[:provider2[:region[:service]]]`: the request
+    /// is signed with the `-u` access key and secret.
+    AwsSigv4 {
+        provider: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        credentials: Option<BasicAuth>,
+    },
+}
+
+/// The scheme flags picking how `-u` credentials are sent.
+const SCHEME_FLAGS: &[&str] = &[
+    "--basic",
+    "--digest",
+    "--ntlm",
+    "--ntlm-wb",
+    "--negotiate",
+    "--anyauth",
+];
+
+/// The credentials of a `-u` argument, without a password when curl would
+/// prompt for it.
+pub fn basic_auth(value: &str) -> BasicAuth {
+    match value.split_once(':') {
+        Some((user, password)) => BasicAuth {
+            user: user.into(),
+            password: Some(password.into()),
+        },
+        None => BasicAuth {
+            user: value.into(),
+            password: None,
+        },
+    }
+}
+
+impl Auth {
+    /// The authentication of a command, `None` without credentials. The
+    /// last `-u` and scheme flag win; `--aws-sigv4` takes precedence over
+    /// `--oauth2-bearer`, which takes precedence over `-u`.
+    pub fn new(curls: &[Curl]) -> Option<Self> {
+        let mut credentials = None;
+        let mut scheme = "--basic";
+        let mut bearer = None;
+        let mut aws_sigv4 = None;
+        for curl in curls {
+            match curl {
+                Curl::Option { name, value } => match &**name {
+                    "--user" => credentials = Some(basic_auth(value)),
+                    "--oauth2-bearer" => bearer = Some(value.to_string()),
+                    "--aws-sigv4" => aws_sigv4 = Some(value.to_string()),
+                    _ => {}
+                },
+                Curl::Flag(flag) => {
+                    if let Some(flag) = SCHEME_FLAGS.iter().find(|f| **f == flag.identifier) {
+                        scheme = flag;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(provider) = aws_sigv4 {
+            return Some(Auth::AwsSigv4 {
+                provider,
+                credentials,
+            });
+        }
+        if let Some(token) = bearer {
+            return Some(Auth::Bearer { token });
+        }
+        let credentials = credentials?;
+        Some(match scheme {
+            "--digest" => Auth::Digest(credentials),
+            "--ntlm" | "--ntlm-wb" => Auth::Ntlm(credentials),
+            "--negotiate" => Auth::Negotiate(credentials),
+            "--anyauth" => Auth::Any(credentials),
+            _ => Auth::Basic(credentials),
+        })
+    }
+
+    /// The option carrying the secret: `-u`, `--oauth2-bearer` or, for
+    /// `--aws-sigv4`, `-u` again.
+    pub fn option(&self) -> &'static str {
+        match self {
+            Auth::Bearer { .. } => "--oauth2-bearer",
+            _ => "-u",
+        }
+    }
+
+    /// The scheme flag selecting the authentication, e.g. `--digest`; `-u`
+    /// for basic authentication.
+    pub fn flag(&self) -> &'static str {
+        match self {
+            Auth::Basic(_) => "-u",
+            Auth::Digest(_) => "--digest",
+            Auth::Ntlm(_) => "--ntlm",
+            Auth::Negotiate(_) => "--negotiate",
+            Auth::Any(_) => "--anyauth",
+            Auth::Bearer { .. } => "--oauth2-bearer",
+            Auth::AwsSigv4 { .. } => "--aws-sigv4",
+        }
+    }
+
+    /// The `-u` credentials, whatever scheme sends them.
+    pub fn credentials(&self) -> Option<&BasicAuth> {
+        match self {
+            Auth::Basic(credentials)
+            | Auth::Digest(credentials)
+            | Auth::Ntlm(credentials)
+            | Auth::Negotiate(credentials)
+            | Auth::Any(credentials) => Some(credentials),
+            Auth::Bearer { .. } => None,
+            Auth::AwsSigv4 { credentials, .. } => credentials.as_ref(),
+        }
+    }
+
+    /// The `Authorization` header curl sends up front, `None` for schemes
+    /// answering a challenge of the server or signing the request.
+    pub fn authorization(&self) -> Option<String> {
+        match self {
+            Auth::Basic(credentials) => Some(format!(
+                "Basic {}",
+                STANDARD.encode(format!(
+                    "{}:{}",
+                    credentials.user,
+                    credentials.password.as_deref().unwrap_or_default()
+                ))
+            )),
+            Auth::Bearer { token } => Some(format!("Bearer {}", token)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    fn credentials(user: &str, password: Option<&str>) -> BasicAuth {
+        BasicAuth {
+            user: user.into(),
+            password: password.map(String::from),
+        }
+    }
+
+    #[rstest]
+    #[case("me:pw", credentials("me", Some("pw")))]
+    #[case("me:a:b", credentials("me", Some("a:b")))]
+    #[case("me", credentials("me", None))]
+    #[case(":", credentials("", Some("")))]
+    fn test_basic_auth(#[case] value: &str, #[case] expected: BasicAuth) {
+        assert_eq!(basic_auth(value), expected);
+    }
+
+    #[rstest]
+    #[case("curl https://a.com", None)]
+    #[case(
+        "curl https://a.com -u me:pw",
+        Some(Auth::Basic(credentials("me", Some("pw"))))
+    )]
+    #[case(
+        "curl https://a.com --digest -u me:pw --basic",
+        Some(Auth::Basic(credentials("me", Some("pw"))))
+    )]
+    #[case(
+        "curl https://a.com -u me --ntlm-wb",
+        Some(Auth::Ntlm(credentials("me", None)))
+    )]
+    #[case(
+        "curl https://a.com --negotiate -u :",
+        Some(Auth::Negotiate(credentials("", Some(""))))
+    )]
+    #[case(
+        "curl https://a.com --anyauth -u a:b",
+        Some(Auth::Any(credentials("a", Some("b"))))
+    )]
+    #[case("curl https://a.com --digest", None)]
+    #[case(
+        "curl https://a.com -u me:pw --oauth2-bearer t",
+        Some(Auth::Bearer { token: "t".into() })
+    )]
+    #[case(
+        "curl https://a.com --aws-sigv4 aws:amz:eu-west-1:s3 -u AKID:secret",
+        Some(Auth::AwsSigv4 {
+            provider: "aws:amz:eu-west-1:s3".into(),
+            credentials: Some(credentials("AKID", Some("secret"))),
+        })
+    )]
+    fn test_auth(#[case] input: &str, #[case] expected: Option<Auth>) {
+        assert_eq!(Auth::new(&curl_cmd_parse(input).unwrap()), expected);
+    }
+
+    #[rstest]
+    #[case("curl https://a.com -u me:secret", Some("Basic bWU6c2VjcmV0"))]
+    #[case("curl https://a.com -u me", Some("Basic bWU6"))]
+    #[case("curl https://a.com --oauth2-bearer abc", Some("Bearer abc"))]
+    #[case("curl https://a.com --digest -u me:secret", None)]
+    fn test_authorization(#[case] input: &str, #[case] expected: Option<&str>) {
+        let auth = Auth::new(&curl_cmd_parse(input).unwrap()).unwrap();
+        assert_eq!(auth.authorization().as_deref(), expected);
+    }
+
+    #[rstest]
+    fn test_serialize() {
+        let auth = Auth::Digest(credentials("me", None));
+        assert_eq!(
+            serde_json::to_value(&auth).unwrap(),
+            serde_json::json!({"scheme": "digest", "user": "me"})
+        );
+        let auth = Auth::Bearer { token: "t".into() };
+        assert_eq!(
+            serde_json::to_value(&auth).unwrap(),
+            serde_json::json!({"scheme": "bearer", "token": "t"})
+        );
+    }
+}
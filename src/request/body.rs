@@ -0,0 +1,235 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use super::Request;
+use crate::curl::body::{BodySource, DataArg};
+use crate::curl::parser::Curl;
+use crate::diagnostic::{Code, Diagnostic};
+
+/// The media type curl sends `-d` bodies with unless told otherwise.
+pub const FORM_URLENCODED: &str = "application/x-www-form-urlencoded";
+
+/// A request body, parsed when it is JSON.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum Body {
+    Json(Value),
+    /// Any other body, as sent.
+    Text(String),
+}
+
+/// The file a body made of a single `-d @file` argument is read from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BodyFile {
+    pub path: String,
+    /// Whether the file is sent as is, as `--data-binary` and `--json` do;
+    /// the other data options drop its line breaks.
+    pub binary: bool,
+}
+
+impl BodyFile {
+    /// The file of `curls` when their only data argument reads one.
+    /// `--data-urlencode` files are left out, their contents being encoded.
+    pub fn new(curls: &[Curl]) -> Option<Self> {
+        let mut data = curls.iter().filter_map(|curl| match curl {
+            Curl::Data(stru) => Some(stru),
+            _ => None,
+        });
+        let (Some(stru), None) = (data.next(), data.next()) else {
+            return None;
+        };
+        if stru.identifier == "--data-urlencode" {
+            return None;
+        }
+        let BodySource::File(path) = DataArg::from_stru(stru).source else {
+            return None;
+        };
+        Some(BodyFile {
+            path: path.to_string_lossy().into_owned(),
+            binary: matches!(&*stru.identifier, "--data-binary" | "--json"),
+        })
+    }
+}
+
+/// Whether a `Content-Type` is JSON: `application/json`, or a `+json` type
+/// like `application/problem+json`, parameters ignored.
+pub fn is_json_media_type(content_type: &str) -> bool {
+    let media_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    media_type == "application/json" || media_type.ends_with("+json")
+}
+
+/// The value of `body` when it reads as a JSON object or array, the
+/// payloads APIs take.
+fn looks_like_json(body: &str) -> Option<Value> {
+    let trimmed = body.trim_start();
+    if !trimmed.starts_with(['{', '[']) {
+        return None;
+    }
+    serde_json::from_str(body).ok()
+}
+
+impl Body {
+    /// Parse `body` sent with `content_type`. JSON media types must hold
+    /// valid JSON; without a `Content-Type`, the body is JSON when it reads
+    /// as an object or array.
+    pub fn parse(body: &str, content_type: Option<&str>) -> Result<Self, Diagnostic> {
+        match content_type {
+            Some(content_type) if is_json_media_type(content_type) => {
+                serde_json::from_str(body).map(Body::Json).map_err(|e| {
+                    Diagnostic::new(
+                        Code::InvalidJsonBody,
+                        format!(
+                            "The body is sent as {} but is not valid JSON: {}",
+                            content_type, e
+                        ),
+                    )
+                })
+            }
+            Some(_) => Ok(Body::Text(body.into())),
+            None => Ok(looks_like_json(body).map_or_else(|| Body::Text(body.into()), Body::Json)),
+        }
+    }
+
+    /// The body pretty-printed when JSON, as sent otherwise.
+    pub fn to_pretty_string(&self) -> String {
+        match self {
+            Body::Json(json) => serde_json::to_string_pretty(json).unwrap_or_default(),
+            Body::Text(text) => text.clone(),
+        }
+    }
+}
+
+impl Request {
+    /// The `Content-Type` the request is sent with: the header, otherwise
+    /// the one curl picks for `-F` and `-d`.
+    pub fn content_type(&self) -> Option<&str> {
+        if let Some(content_type) = self.headers.get("Content-Type") {
+            Some(content_type)
+        } else if !self.form.is_empty() {
+            Some("multipart/form-data")
+        } else if self.body.is_some() {
+            Some(FORM_URLENCODED)
+        } else {
+            None
+        }
+    }
+
+    /// The body, parsed as its `Content-Type` header tells, inferred when
+    /// there is none, see [`Body::parse`]; `None` when no body is sent.
+    ///
+    /// ```
+    /// use winnowcurl::Request;
+    /// use winnowcurl::request::body::Body;
+    ///
+    /// let request = Request::parse(r#"curl https://a.com --json '{"id": 1}'"#)?;
+    /// assert_eq!(request.headers.get("Accept"), Some("application/json"));
+    /// assert_eq!(request.parsed_body()?, Some(Body::Json(serde_json::json!({"id": 1}))));
+    /// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+    /// ```
+    pub fn parsed_body(&self) -> Result<Option<Body>, Diagnostic> {
+        self.body
+            .as_deref()
+            .map(|body| Body::parse(body, self.headers.get("Content-Type")))
+            .transpose()
+    }
+}
+
+/// What is wrong with the body of `request`: JSON sent without a
+/// `Content-Type`, which curl sends as form data, or a JSON `Content-Type`
+/// over a body which is not JSON.
+pub fn check(request: &Request) -> Option<Diagnostic> {
+    match request.parsed_body() {
+        Err(diagnostic) => Some(diagnostic),
+        Ok(Some(Body::Json(_))) if !request.headers.contains("Content-Type") => {
+            Some(Diagnostic::new(
+                Code::MissingContentType,
+                format!(
+                    "The body is JSON but is sent as {}; use --json or add -H 'Content-Type: application/json'",
+                    FORM_URLENCODED
+                ),
+            ))
+        }
+        Ok(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use serde_json::json;
+
+    #[rstest]
+    #[case("application/json", true)]
+    #[case("Application/JSON; charset=utf-8", true)]
+    #[case("application/problem+json", true)]
+    #[case("application/jsonp", false)]
+    #[case("text/plain", false)]
+    fn test_is_json_media_type(#[case] content_type: &str, #[case] expected: bool) {
+        assert_eq!(is_json_media_type(content_type), expected);
+    }
+
+    #[rstest]
+    #[case(r#"{"a": [1]}"#, None, Body::Json(json!({"a": [1]})))]
+    #[case("[]", Some("application/vnd.api+json"), Body::Json(json!([])))]
+    #[case("42", Some("application/json"), Body::Json(json!(42)))]
+    #[case("42", None, Body::Text("42".into()))]
+    #[case("{oops", None, Body::Text("{oops".into()))]
+    #[case(r#"{"a": 1}"#, Some("text/plain"), Body::Text(r#"{"a": 1}"#.into()))]
+    fn test_body_parse(
+        #[case] body: &str,
+        #[case] content_type: Option<&str>,
+        #[case] expected: Body,
+    ) {
+        assert_eq!(Body::parse(body, content_type).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("curl https://a.com -d 'a=1'", Some(FORM_URLENCODED))]
+    #[case("curl https://a.com --json '{}'", Some("application/json"))]
+    #[case("curl https://a.com -F 'f=1'", Some("multipart/form-data"))]
+    #[case(
+        "curl https://a.com -H 'Content-Type: text/csv' -d 'a'",
+        Some("text/csv")
+    )]
+    #[case("curl https://a.com", None)]
+    fn test_content_type(#[case] input: &str, #[case] expected: Option<&str>) {
+        assert_eq!(Request::parse(input).unwrap().content_type(), expected);
+    }
+
+    #[rstest]
+    fn test_json_option() {
+        let request =
+            Request::parse(r#"curl https://a.com --json '{"a":' --json ' 1}' -H 'Accept: */*'"#)
+                .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.body.as_deref(), Some(r#"{"a": 1}"#));
+        assert_eq!(
+            request.headers.to_string(),
+            "Accept: */*\nContent-Type: application/json"
+        );
+        assert_eq!(
+            request.parsed_body().unwrap().unwrap().to_pretty_string(),
+            "{\n  \"a\": 1\n}"
+        );
+    }
+
+    #[rstest]
+    #[case(r#"curl https://a.com -d '{"a": 1}'"#, Some(Code::MissingContentType))]
+    #[case(r#"curl https://a.com --json '{"a": 1'"#, Some(Code::InvalidJsonBody))]
+    #[case(
+        "curl https://a.com -H 'Content-Type: application/json' -d 'a=1'",
+        Some(Code::InvalidJsonBody)
+    )]
+    #[case(r#"curl https://a.com --json '{"a": 1}'"#, None)]
+    #[case("curl https://a.com -d 'a=1'", None)]
+    fn test_check(#[case] input: &str, #[case] expected: Option<Code>) {
+        let request = Request::parse(input).unwrap();
+        assert_eq!(check(&request).map(|diagnostic| diagnostic.code), expected);
+    }
+}
@@ -0,0 +1,117 @@
+use super::Request;
+use crate::diagnostic::{Code, Diagnostic};
+use crate::url::host::{same_host, url_host};
+
+/// Cookies captured for another host than the one of the URL, e.g. when the
+/// URL of a command copied from a browser was edited afterwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignCookies {
+    /// The host of the URL, as written.
+    pub host: String,
+    /// The host the cookies were sent to when captured, as written.
+    pub captured_for: String,
+    /// What tells that host, e.g. `the Host header`.
+    pub evidence: &'static str,
+}
+
+impl ForeignCookies {
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(
+            Code::ForeignCookies,
+            format!(
+                "The cookies were captured for {}, as told by {}, but are sent to {}",
+                self.captured_for, self.evidence, self.host
+            ),
+        )
+    }
+}
+
+/// The host the request was first made to, when its headers tell: a `Host`
+/// header, or the `Origin` or `Referer` of a request the browser found to be
+/// `same-origin`.
+pub fn captured_host(request: &Request) -> Option<(&str, &'static str)> {
+    if let Some(host) = request.headers.get("Host") {
+        return Some((url_host(host), "the Host header"));
+    }
+    let same_origin = request
+        .headers
+        .get("Sec-Fetch-Site")
+        .is_some_and(|site| site.eq_ignore_ascii_case("same-origin"));
+    if !same_origin {
+        return None;
+    }
+    [
+        ("Origin", "Sec-Fetch-Site: same-origin and the Origin"),
+        ("Referer", "Sec-Fetch-Site: same-origin and the Referer"),
+    ]
+    .into_iter()
+    .find_map(|(name, evidence)| {
+        let value = request.headers.get(name)?;
+        value.contains("://").then(|| (url_host(value), evidence))
+    })
+}
+
+/// The cookies of `request` when they were captured for another host than
+/// its URL's, see [`captured_host`]. Hosts are compared with [`same_host`].
+pub fn foreign_cookies(request: &Request) -> Option<ForeignCookies> {
+    if !request.headers.contains("Cookie") {
+        return None;
+    }
+    let (captured_for, evidence) = captured_host(request)?;
+    let host = url_host(&request.url);
+    (!captured_for.is_empty() && !same_host(host, captured_for)).then(|| ForeignCookies {
+        host: host.into(),
+        captured_for: captured_for.into(),
+        evidence,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        "curl https://staging.example.com/me -H 'Host: www.example.com:443' -b 's=1'",
+        Some("www.example.com")
+    )]
+    #[case(
+        "curl http://localhost:8080/me -H 'sec-fetch-site: same-origin' -e 'https://www.example.com/account' -H 'Cookie: s=1'",
+        Some("www.example.com")
+    )]
+    #[case(
+        "curl https://www.example.com/me -H 'Sec-Fetch-Site: same-origin' -H 'Origin: https://WWW.example.com' -b 's=1'",
+        None
+    )]
+    #[case(
+        "curl https://xn--bcher-kva.example/me -H 'Host: bücher.example' -b 's=1'",
+        None
+    )]
+    #[case(
+        "curl https://api.example.com/me -H 'Sec-Fetch-Site: same-site' -H 'Origin: https://www.example.com' -b 's=1'",
+        None
+    )]
+    #[case("curl https://staging.example.com/me -H 'Host: www.example.com'", None)]
+    fn test_foreign_cookies(#[case] input: &str, #[case] expected: Option<&str>) {
+        let request = Request::parse(input).unwrap();
+        assert_eq!(
+            foreign_cookies(&request)
+                .as_ref()
+                .map(|found| found.captured_for.as_str()),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_diagnostic() {
+        let request = Request::parse(
+            "curl https://staging.example.com -H 'Sec-Fetch-Site: same-origin' -H 'Origin: https://example.com' -b 's=1'",
+        )
+        .unwrap();
+        assert_eq!(
+            foreign_cookies(&request).unwrap().diagnostic().to_string(),
+            "warning[WCL031]: The cookies were captured for example.com, as told by Sec-Fetch-Site: same-origin and the Origin, but are sent to staging.example.com"
+        );
+    }
+}
@@ -0,0 +1,417 @@
+pub mod auth;
+pub mod body;
+pub mod cookies;
+pub mod origin;
+pub mod referer;
+pub mod schema;
+pub mod shorthand;
+
+use serde::Serialize;
+
+use crate::curl::body::{BodySource, DataArg, join_data};
+use crate::curl::form::FormField;
+use crate::curl::header::Headers;
+use crate::curl::intern::Interner;
+use crate::curl::method::Method;
+use crate::curl::options::has_flag;
+use crate::curl::parser::{Curl, curl_cmd_parse};
+use crate::curl::resume::ContinueAt;
+use crate::curl::routing::Routing;
+use crate::curl::time_cond::TimeCondition;
+use crate::diagnostic::{Code, Diagnostic};
+use crate::url::codec::UrlCodec;
+use auth::Auth;
+use body::BodyFile;
+
+/// `-u user:password` credentials.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BasicAuth {
+    pub user: String,
+    /// Absent when curl would prompt for it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// The HTTP request a curl command sends, with curl's defaults applied.
+///
+/// ```
+/// use winnowcurl::Request;
+///
+/// let request = Request::parse("curl example.com/search -G -d 'q=rust' -H 'Accept: text/html'")?;
+/// assert_eq!(request.method, "GET");
+/// assert_eq!(request.url, "http://example.com/search?q=rust");
+/// assert_eq!(request.headers.get("accept"), Some("text/html"));
+/// assert_eq!(request.body, None);
+/// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Request {
+    /// `-X` if given, otherwise the method [implied](Method::implied) by
+    /// the options.
+    pub method: Method,
+    /// The URL, `http://` added when it has no scheme and the data moved
+    /// into the query with `-G`.
+    pub url: String,
+    /// The `-H` headers, and those of `-A`, `-e` and `-b` unless given with
+    /// `-H` as well.
+    pub headers: Headers,
+    /// Every data argument joined with `&`, `--json` ones appended as is and
+    /// `--data-urlencode` contents encoded. `@file` arguments are kept as written.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// `-d @file` as the only data argument: the file `body` names, read
+    /// when the request is made.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_file: Option<BodyFile>,
+    /// `-T`: the file sent as the body, read when the request is made.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upload_file: Option<String>,
+    /// The fields of a multipart body.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub form: Vec<FormField>,
+    /// The `-u` credentials and how they are sent, or the `--oauth2-bearer`
+    /// token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Auth>,
+    /// `-k`: the certificate of the server is not verified.
+    pub insecure: bool,
+    /// `-L`: redirects are followed.
+    pub follow_redirects: bool,
+    /// `--compressed`: a compressed response is asked for and decoded.
+    pub compressed: bool,
+    /// The proxy, resolver overrides and socket the connection goes through.
+    #[serde(skip_serializing_if = "Routing::is_direct")]
+    pub routing: Routing,
+    /// `-z`: the resource is only transferred if modified since, or before,
+    /// a date.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_cond: Option<TimeCondition>,
+    /// `-C`: the transfer resumes after the start of the resource.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continue_at: Option<ContinueAt>,
+}
+
+/// The part of the body a `--data-urlencode` argument contributes; file
+/// references are kept as written.
+///
+/// Everything but unreserved characters is percent-encoded, except spaces
+/// which are sent as `+`, as curl does.
+fn urlencoded_part(arg: &DataArg, raw: &str) -> String {
+    let codec = UrlCodec::form();
+    match (&arg.source, &arg.name) {
+        (BodySource::Inline(content), Some(name)) => format!("{}={}", name, codec.encode(content)),
+        (BodySource::Inline(content), None) => codec.encode(content).into_owned(),
+        _ => raw.to_string(),
+    }
+}
+
+/// Append `query` to the query string of `url`, ahead of any fragment.
+fn append_query(url: &str, query: &str) -> String {
+    let (url, fragment) = match url.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (url, None),
+    };
+    let separator = if url.contains('?') { '&' } else { '?' };
+    let mut appended = format!("{}{}{}", url, separator, query);
+    if let Some(fragment) = fragment {
+        appended.push('#');
+        appended.push_str(fragment);
+    }
+    appended
+}
+
+fn conflict(asked: &str, other: &str) -> Diagnostic {
+    Diagnostic::new(
+        Code::ConflictingMethods,
+        format!(
+            "You can only select one HTTP request method! You asked for both {} and {}.",
+            asked, other
+        ),
+    )
+}
+
+impl Request {
+    /// Parse a curl command into the request it sends.
+    pub fn parse(command: &str) -> Result<Self, Diagnostic> {
+        Self::try_from(curl_cmd_parse(command)?.as_slice())
+    }
+
+    /// The JSON Schema of a JSON body, see [`schema::infer_schema`]; `None`
+    /// when the body is absent or not JSON.
+    pub fn infer_body_schema(&self) -> Option<serde_json::Value> {
+        let body: serde_json::Value = serde_json::from_str(self.body.as_deref()?).ok()?;
+        Some(schema::document_schema(&body))
+    }
+}
+
+impl Request {
+    /// Like [`Request::try_from`], taking the header names from `interner`
+    /// so that the requests of a batch share one allocation per name.
+    pub fn from_curls_interned(
+        curls: &[Curl],
+        interner: &mut Interner,
+    ) -> Result<Self, Diagnostic> {
+        Self::with_headers(curls, Headers::from_curls_interned(curls, interner))
+    }
+
+    /// The request of `curls`, whose `-H` headers are `headers`.
+    fn with_headers(curls: &[Curl], headers: Headers) -> Result<Self, Diagnostic> {
+        let mut url = None;
+        let mut upload_file = None;
+        let mut body_parts = Vec::new();
+        let mut form = Vec::new();
+
+        for curl in curls {
+            match curl {
+                Curl::URL(curl_url) => {
+                    let mut curl_url = curl_url.to_string();
+                    // curl assumes http:// for URLs without a scheme.
+                    if !curl_url.contains("://") {
+                        curl_url.insert_str(0, "http://");
+                    }
+                    // A part requesting several URLs, e.g. with `-Z`, is
+                    // described by its first one.
+                    url.get_or_insert(curl_url);
+                }
+                Curl::Data(stru) => {
+                    let raw = stru.data.as_deref().unwrap_or_default();
+                    body_parts.push((
+                        &*stru.identifier,
+                        if stru.identifier == "--data-urlencode" {
+                            urlencoded_part(&DataArg::from_stru(stru), raw)
+                        } else {
+                            raw.to_string()
+                        },
+                    ));
+                }
+                Curl::Form(field) => form.push(field.clone()),
+                Curl::Option { name, value } if name == "--upload-file" => {
+                    upload_file = Some(value.to_string());
+                }
+                _ => {}
+            }
+        }
+
+        let url = url.ok_or_else(|| Diagnostic::new(Code::MissingUrl, "The command has no URL"))?;
+        let head = has_flag(curls, "--head");
+        let get = has_flag(curls, "--get");
+        let data = !body_parts.is_empty();
+        let multipart = !form.is_empty();
+
+        if multipart && data {
+            return Err(conflict("multipart formpost (-F)", "POST (-d)"));
+        }
+        if multipart && (head || get) {
+            let other = if head { "HEAD (-I)" } else { "GET (-G)" };
+            return Err(conflict("multipart formpost (-F)", other));
+        }
+        if head && data && !get {
+            return Err(conflict("POST (-d)", "HEAD (-I)"));
+        }
+
+        let (url, body) = match join_data(body_parts) {
+            Some(query) if get => (append_query(&url, &query), None),
+            body => (url, body),
+        };
+
+        Ok(Request {
+            method: Method::of(curls),
+            url,
+            headers: shorthand::complete_headers(curls, headers),
+            body_file: body.as_ref().and_then(|_| BodyFile::new(curls)),
+            body,
+            upload_file,
+            form,
+            auth: Auth::new(curls),
+            insecure: has_flag(curls, "--insecure"),
+            follow_redirects: has_flag(curls, "--location"),
+            compressed: has_flag(curls, "--compressed"),
+            routing: Routing::new(curls),
+            time_cond: TimeCondition::new(curls),
+            continue_at: ContinueAt::new(curls),
+        })
+    }
+}
+
+impl TryFrom<&[Curl<'_>]> for Request {
+    type Error = Diagnostic;
+
+    fn try_from(curls: &[Curl<'_>]) -> Result<Self, Self::Error> {
+        Self::with_headers(curls, Headers::from(curls))
+    }
+}
+
+impl TryFrom<Vec<Curl<'_>>> for Request {
+    type Error = Diagnostic;
+
+    fn try_from(curls: Vec<Curl<'_>>) -> Result<Self, Self::Error> {
+        Self::try_from(curls.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("curl https://a.com", "GET", "https://a.com", None)]
+    #[case(
+        "curl https://a.com -d 'a=1' -d 'b=2'",
+        "POST",
+        "https://a.com",
+        Some("a=1&b=2")
+    )]
+    #[case(
+        "curl https://a.com -X PUT -d 'a=1'",
+        "PUT",
+        "https://a.com",
+        Some("a=1")
+    )]
+    #[case("curl https://a.com -I", "HEAD", "https://a.com", None)]
+    #[case("curl https://a.com -X GET -I", "GET", "https://a.com", None)]
+    #[case(
+        "curl https://a.com/s?x=1 -G -d 'q=a'",
+        "GET",
+        "https://a.com/s?x=1&q=a",
+        None
+    )]
+    #[case(
+        "curl https://a.com/s -G -d 'q=a' -I",
+        "HEAD",
+        "https://a.com/s?q=a",
+        None
+    )]
+    #[case("curl https://a.com/s -G", "GET", "https://a.com/s", None)]
+    #[case("curl https://a.com -T file.txt", "PUT", "https://a.com", None)]
+    #[case("curl a.com -F 'f=@a.png'", "POST", "http://a.com", None)]
+    #[case(
+        "curl https://a.com --data-urlencode 'q=a b&c' --data-urlencode '=é'",
+        "POST",
+        "https://a.com",
+        Some("q=a+b%26c&%C3%A9")
+    )]
+    #[case(
+        "curl https://a.com --data-urlencode 'q@notes.txt'",
+        "POST",
+        "https://a.com",
+        Some("q@notes.txt")
+    )]
+    fn test_request(
+        #[case] input: &str,
+        #[case] method: &str,
+        #[case] url: &str,
+        #[case] body: Option<&str>,
+    ) {
+        let request = Request::parse(input).unwrap();
+        assert_eq!(request.method, method);
+        assert_eq!(request.url, url);
+        assert_eq!(request.body.as_deref(), body);
+    }
+
+    #[rstest]
+    fn test_request_upload_file() {
+        let request = Request::parse("curl https://a.com -T notes.txt").unwrap();
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.upload_file.as_deref(), Some("notes.txt"));
+        assert_eq!(request.body, None);
+    }
+
+    #[rstest]
+    #[case("curl https://a.com -d @body.txt", Some(("body.txt", false)))]
+    #[case("curl https://a.com --data-binary @body.bin", Some(("body.bin", true)))]
+    #[case("curl https://a.com --json @body.json", Some(("body.json", true)))]
+    #[case("curl https://a.com -d @a.txt -d b=1", None)]
+    #[case("curl https://a.com --data-urlencode q@a.txt", None)]
+    #[case("curl https://a.com --data-raw @a.txt", None)]
+    #[case("curl https://a.com -d @-", None)]
+    #[case("curl https://a.com -G -d @a.txt", None)]
+    fn test_request_body_file(#[case] input: &str, #[case] expected: Option<(&str, bool)>) {
+        let request = Request::parse(input).unwrap();
+        assert_eq!(
+            request.body_file,
+            expected.map(|(path, binary)| BodyFile {
+                path: path.into(),
+                binary
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_request_from_curls_interned() {
+        let mut interner = Interner::new();
+        for input in [
+            "curl https://a.com -H 'Accept: */*' -A 'bot/1'",
+            "curl https://b.com -H 'accept: */*' -d 'a=1'",
+        ] {
+            let curls = curl_cmd_parse(input).unwrap();
+            assert_eq!(
+                Request::from_curls_interned(&curls, &mut interner),
+                Request::try_from(curls.as_slice())
+            );
+        }
+        // `Accept` and its folded form `accept`, written in the second command.
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[rstest]
+    fn test_request_fields() {
+        let curls = curl_cmd_parse(
+            "curl https://a.com -u bob:pw -H 'Accept: */*' -k -L --compressed -F 'n=1'",
+        )
+        .unwrap();
+        let request = Request::try_from(curls).unwrap();
+        assert_eq!(
+            request.auth,
+            Some(Auth::Basic(BasicAuth {
+                user: "bob".into(),
+                password: Some("pw".into())
+            }))
+        );
+        assert_eq!(request.headers.get("accept"), Some("*/*"));
+        assert_eq!(request.headers.get("user-agent"), None);
+        assert_eq!(request.form.len(), 1);
+        assert!(request.insecure && request.follow_redirects && request.compressed);
+        assert!(request.routing.is_direct());
+    }
+
+    #[rstest]
+    fn test_request_routing() {
+        let request =
+            Request::parse("curl https://a.com -x socks5h://gw:1080 --unix-socket /run/a.sock")
+                .unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["routing"]["proxy"]["scheme"], "socks5h");
+        assert_eq!(json["routing"]["proxy"]["url"]["host"], "gw");
+        assert_eq!(json["routing"]["unix_socket"]["path"], "/run/a.sock");
+
+        let request = Request::parse("curl https://a.com").unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json.get("routing").is_none());
+    }
+
+    #[rstest]
+    fn test_infer_body_schema() {
+        let request = Request::parse(r#"curl https://a.com -d '{"at": "2024-05-01"}'"#).unwrap();
+        assert_eq!(
+            request.infer_body_schema(),
+            Some(serde_json::json!({
+                "$schema": schema::SCHEMA_DIALECT,
+                "type": "object",
+                "properties": {"at": {"type": "string", "format": "date"}},
+                "required": ["at"]
+            }))
+        );
+        let request = Request::parse("curl https://a.com -d 'a=1'").unwrap();
+        assert_eq!(request.infer_body_schema(), None);
+    }
+
+    #[rstest]
+    #[case("curl https://a.com -I -d 'a=1'")]
+    #[case("curl https://a.com -d 'a=1' -F 'f=1'")]
+    #[case("curl https://a.com -G -F 'f=1'")]
+    fn test_conflicting_methods(#[case] input: &str) {
+        let e = Request::parse(input).unwrap_err();
+        assert_eq!(e.code, Code::ConflictingMethods);
+    }
+}
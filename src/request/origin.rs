@@ -0,0 +1,297 @@
+use std::fmt;
+
+use serde::{Serialize, Serializer};
+
+use super::Request;
+use crate::curl::method::Method;
+use crate::diagnostic::{Code, Diagnostic};
+use crate::url::host::{to_ascii, url_host, url_port};
+use crate::url::protocol::Schema;
+
+/// The origin of a URL: its scheme, host and port, the host in its ASCII
+/// form and the port made explicit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    pub scheme: String,
+    pub host: String,
+    /// `None` for schemes without a default port.
+    pub port: Option<u16>,
+}
+
+impl Origin {
+    /// The origin of an absolute URL, `None` when it has no scheme or host,
+    /// e.g. for `Origin: null`.
+    pub fn of(url: &str) -> Option<Self> {
+        let (scheme, _) = url.split_once("://")?;
+        let host = url_host(url);
+        if host.is_empty() {
+            return None;
+        }
+        let scheme = scheme.to_ascii_lowercase();
+        Some(Origin {
+            port: url_port(url).or_else(|| Schema::from(scheme.as_str()).default_port()),
+            host: to_ascii(host).unwrap_or_else(|| host.to_ascii_lowercase()),
+            scheme,
+        })
+    }
+}
+
+/// `scheme://host`, followed by the port unless it is the default one.
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.host)?;
+        match self.port {
+            Some(port) if Some(port) != Schema::from(self.scheme.as_str()).default_port() => {
+                write!(f, ":{}", port)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Serialize for Origin {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// How the page a request was made from relates to its URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Relation {
+    SameOrigin,
+    /// Another origin, or an opaque one like `Origin: null`.
+    CrossOrigin,
+}
+
+/// An `Origin` or `Referer` header and how it relates to the URL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Related {
+    /// The header value, as written.
+    pub value: String,
+    pub relation: Relation,
+}
+
+impl Related {
+    fn new(value: &str, target: &Origin) -> Self {
+        let relation = match Origin::of(value) {
+            Some(origin) if origin == *target => Relation::SameOrigin,
+            _ => Relation::CrossOrigin,
+        };
+        Related {
+            value: value.into(),
+            relation,
+        }
+    }
+}
+
+/// Where a request copied from a browser was made from, relative to the
+/// origin it is sent to.
+///
+/// ```
+/// use winnowcurl::Request;
+/// use winnowcurl::request::origin::{OriginAnalysis, Relation};
+///
+/// let request = Request::parse(
+///     "curl https://api.example.com/cart -H 'Origin: https://shop.example.com' -H 'sec-fetch-site: same-site'",
+/// )?;
+/// let analysis = OriginAnalysis::new(&request).unwrap();
+/// assert_eq!(analysis.origin.unwrap().relation, Relation::CrossOrigin);
+/// assert_eq!(analysis.fetch_site.as_deref(), Some("same-site"));
+/// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OriginAnalysis {
+    /// The origin of the URL.
+    pub target: Origin,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<Related>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referer: Option<Related>,
+    /// The `Sec-Fetch-Site` header, how the browser itself classified the
+    /// request: `same-origin`, `same-site`, `cross-site` or `none`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch_site: Option<String>,
+    /// The `Sec-Fetch-Mode` header, e.g. `cors` for requests made with
+    /// `fetch` or `navigate` for pages.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch_mode: Option<String>,
+    /// Whether the request is a CORS preflight, an `OPTIONS` request with
+    /// `Access-Control-Request-Method`.
+    pub preflight: bool,
+}
+
+impl OriginAnalysis {
+    /// Analyze the headers of `request`; `None` when its URL has no origin.
+    pub fn new(request: &Request) -> Option<Self> {
+        let target = Origin::of(&request.url)?;
+        let related = |name| {
+            request
+                .headers
+                .get(name)
+                .map(|value| Related::new(value, &target))
+        };
+        Some(OriginAnalysis {
+            origin: related("Origin"),
+            referer: related("Referer"),
+            fetch_site: request
+                .headers
+                .get("Sec-Fetch-Site")
+                .map(|site| site.to_ascii_lowercase()),
+            fetch_mode: request
+                .headers
+                .get("Sec-Fetch-Mode")
+                .map(|mode| mode.to_ascii_lowercase()),
+            preflight: request.method == Method::Options
+                && request.headers.contains("Access-Control-Request-Method"),
+            target,
+        })
+    }
+
+    /// Whether the browser made the request from another origin. The
+    /// `Origin` header tells, or else the browser's own `Sec-Fetch-Site`.
+    /// A `Referer` only tells for a `Sec-Fetch-Mode: cors` request: pages
+    /// and images loaded from another site send one too, without CORS
+    /// getting involved.
+    pub fn is_cross_origin(&self) -> bool {
+        if let Some(origin) = &self.origin {
+            return origin.relation == Relation::CrossOrigin;
+        }
+        if let Some(site) = self.fetch_site.as_deref() {
+            return matches!(site, "same-site" | "cross-site");
+        }
+        self.fetch_mode.as_deref() == Some("cors")
+            && self
+                .referer
+                .as_ref()
+                .is_some_and(|referer| referer.relation == Relation::CrossOrigin)
+    }
+
+    /// The CORS problems the request runs into once converted, for code run
+    /// in a browser when `browser`, otherwise on a server.
+    pub fn diagnostics(&self, browser: bool) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        if self.preflight {
+            diagnostics.push(Diagnostic::new(
+                Code::CrossOrigin,
+                "The command is the CORS preflight of a request, not the request itself",
+            ));
+        }
+        if !self.is_cross_origin() {
+            return diagnostics;
+        }
+        let from = match (&self.origin, &self.referer) {
+            (Some(related), _) | (None, Some(related)) => related.value.as_str(),
+            (None, None) => "another site",
+        };
+        diagnostics.push(Diagnostic::new(
+            Code::CrossOrigin,
+            if browser {
+                format!(
+                    "The request to {} was made from {}; run from another origin it only succeeds if the server allows that origin with CORS",
+                    self.target, from
+                )
+            } else {
+                format!(
+                    "The request to {} was made by a browser from {}; CORS does not apply to server-side code, and the Origin and Sec-Fetch-* headers can be dropped unless the server checks them",
+                    self.target, from
+                )
+            },
+        ));
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("https://Bücher.example:443/x", "https://xn--bcher-kva.example")]
+    #[case("http://me@a.com:8080?q", "http://a.com:8080")]
+    #[case("HTTP://[::1]/", "http://[::1]")]
+    fn test_origin(#[case] url: &str, #[case] expected: &str) {
+        assert_eq!(Origin::of(url).unwrap().to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("null")]
+    #[case("a.com/x")]
+    fn test_origin_opaque(#[case] value: &str) {
+        assert_eq!(Origin::of(value), None);
+    }
+
+    #[rstest]
+    #[case("curl https://a.com/x -H 'Origin: https://a.com'", false)]
+    #[case("curl https://a.com/x -H 'Origin: http://a.com'", true)]
+    #[case("curl https://a.com/x -H 'Origin: https://a.com:8443'", true)]
+    #[case("curl https://a.com/x -H 'Origin: null'", true)]
+    #[case(
+        "curl https://xn--bcher-kva.example -e 'https://bücher.example/p'",
+        false
+    )]
+    #[case("curl https://api.a.com -e 'https://www.a.com/p'", false)]
+    #[case(
+        "curl https://api.a.com -e 'https://www.a.com/p' -H 'Sec-Fetch-Mode: cors'",
+        true
+    )]
+    #[case(
+        "curl https://api.a.com -e 'https://www.a.com/p' -H 'Sec-Fetch-Mode: navigate'",
+        false
+    )]
+    #[case(
+        "curl https://api.a.com -e 'https://www.a.com/p' -H 'Sec-Fetch-Site: same-origin'",
+        false
+    )]
+    #[case(
+        "curl https://a.com -H 'Origin: https://a.com' -e 'https://b.com/'",
+        false
+    )]
+    #[case("curl https://a.com -H 'Sec-Fetch-Site: same-site'", true)]
+    #[case("curl https://a.com -H 'Sec-Fetch-Site: none'", false)]
+    #[case("curl https://a.com", false)]
+    fn test_is_cross_origin(#[case] input: &str, #[case] expected: bool) {
+        let request = Request::parse(input).unwrap();
+        assert_eq!(
+            OriginAnalysis::new(&request).unwrap().is_cross_origin(),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_diagnostics() {
+        let request = Request::parse(concat!(
+            "curl https://api.a.com/cart -X OPTIONS -H 'Origin: https://shop.a.com' ",
+            "-H 'Access-Control-Request-Method: POST'"
+        ))
+        .unwrap();
+        let analysis = OriginAnalysis::new(&request).unwrap();
+        assert!(analysis.preflight);
+        let messages: Vec<String> = analysis
+            .diagnostics(false)
+            .iter()
+            .map(Diagnostic::to_string)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "warning[WCC002]: The command is the CORS preflight of a request, not the request itself",
+                "warning[WCC002]: The request to https://api.a.com was made by a browser from https://shop.a.com; CORS does not apply to server-side code, and the Origin and Sec-Fetch-* headers can be dropped unless the server checks them",
+            ]
+        );
+        assert!(
+            analysis.diagnostics(true)[1]
+                .message
+                .contains("allows that origin")
+        );
+        assert_eq!(
+            serde_json::to_value(&analysis).unwrap(),
+            serde_json::json!({
+                "target": "https://api.a.com",
+                "origin": {"value": "https://shop.a.com", "relation": "cross-origin"},
+                "preflight": true
+            })
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use super::Request;
+use super::auth::Auth;
+use crate::diagnostic::{Code, Diagnostic};
+use crate::url::host::{same_host, url_host};
+
+/// Credentials sent to another host than the one of the `Referer`, e.g. the
+/// cookies of a page copied along with a request to a third-party API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossHostCredentials {
+    /// `Authorization`, `Cookie`, `-u` or `--oauth2-bearer`.
+    pub credential: &'static str,
+    /// The host of the URL, as written.
+    pub host: String,
+    /// The host of the `Referer`, as written.
+    pub referer_host: String,
+}
+
+impl CrossHostCredentials {
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(
+            Code::CrossHostCredentials,
+            format!(
+                "{} credentials are sent to {}, another host than the Referer {}",
+                self.credential, self.host, self.referer_host
+            ),
+        )
+    }
+}
+
+/// Every kind of credentials `request` sends to another host than its
+/// `Referer`, see [`same_host`].
+pub fn cross_host_credentials(request: &Request) -> Vec<CrossHostCredentials> {
+    let Some(referer) = request.headers.get("Referer") else {
+        return Vec::new();
+    };
+    let (host, referer_host) = (url_host(&request.url), url_host(referer));
+    if referer_host.is_empty() || same_host(host, referer_host) {
+        return Vec::new();
+    }
+    let sent = [
+        ("Authorization", request.headers.contains("Authorization")),
+        ("Cookie", request.headers.contains("Cookie")),
+    ];
+    sent.into_iter()
+        .filter(|(_, sent)| *sent)
+        .map(|(credential, _)| credential)
+        .chain(request.auth.as_ref().map(Auth::option))
+        .map(|credential| CrossHostCredentials {
+            credential,
+            host: host.into(),
+            referer_host: referer_host.into(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        "curl https://api.other.com -e 'https://shop.example/cart' -b 's=1' -H 'Authorization: Bearer t'",
+        vec!["Authorization", "Cookie"]
+    )]
+    #[case("curl https://api.other.com -H 'Referer: https://shop.example/' -u me:pw", vec!["-u"])]
+    #[case("curl https://xn--bcher-kva.example/a -e 'https://Bücher.example/' -b 's=1'", vec![])]
+    #[case("curl https://shop.example:8443/a -e 'https://me@shop.example/' -b 's=1'", vec![])]
+    #[case("curl https://api.other.com -e 'https://shop.example/'", vec![])]
+    #[case("curl https://api.other.com -b 's=1'", vec![])]
+    fn test_cross_host_credentials(#[case] input: &str, #[case] expected: Vec<&str>) {
+        let request = Request::parse(input).unwrap();
+        let credentials: Vec<&str> = cross_host_credentials(&request)
+            .iter()
+            .map(|found| found.credential)
+            .collect();
+        assert_eq!(credentials, expected);
+    }
+
+    #[rstest]
+    fn test_diagnostic() {
+        let request =
+            Request::parse("curl https://аpple.com -e 'https://apple.com/' -b 's=1'").unwrap();
+        assert_eq!(
+            cross_host_credentials(&request)[0].diagnostic().to_string(),
+            "warning[WCL005]: Cookie credentials are sent to аpple.com, another host than the Referer apple.com"
+        );
+    }
+}
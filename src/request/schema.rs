@@ -0,0 +1,341 @@
+use serde_json::{Map, Value, json};
+
+/// The JSON Schema dialect of inferred schemas.
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+fn all_digits(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// `YYYY-MM-DD`.
+fn is_date(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('-').collect();
+    matches!(parts.as_slice(), [year, month, day]
+        if year.len() == 4 && month.len() == 2 && day.len() == 2
+            && parts.iter().all(|part| all_digits(part))
+            && (1..=12).contains(&month.parse::<u8>().unwrap_or(0))
+            && (1..=31).contains(&day.parse::<u8>().unwrap_or(0)))
+}
+
+/// `HH:MM:SS` with optional fractional seconds.
+fn is_time(text: &str) -> bool {
+    let (time, fraction) = text.split_once('.').unwrap_or((text, "0"));
+    let parts: Vec<&str> = time.split(':').collect();
+    parts.len() == 3
+        && parts.iter().all(|part| part.len() == 2 && all_digits(part))
+        && all_digits(fraction)
+}
+
+/// An RFC 3339 timestamp such as `2024-05-01T12:30:00Z`.
+fn is_date_time(text: &str) -> bool {
+    let Some((date, time)) = text.split_once(['T', 't']) else {
+        return false;
+    };
+    let time = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        time
+    } else {
+        match time.rfind(['+', '-']) {
+            Some(sign) if is_offset(&time[sign + 1..]) => &time[..sign],
+            _ => return false,
+        }
+    };
+    is_date(date) && is_time(time)
+}
+
+/// The `HH:MM` of a UTC offset.
+fn is_offset(text: &str) -> bool {
+    matches!(text.split_once(':'), Some((hours, minutes))
+        if hours.len() == 2 && minutes.len() == 2 && all_digits(hours) && all_digits(minutes))
+}
+
+fn is_uuid(text: &str) -> bool {
+    text.len() == 36
+        && text.split('-').map(str::len).eq([8, 4, 4, 4, 12])
+        && text.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+}
+
+fn is_email(text: &str) -> bool {
+    match text.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !text.contains(char::is_whitespace)
+                && !domain.contains('@')
+        }
+        None => false,
+    }
+}
+
+fn is_uri(text: &str) -> bool {
+    ["http://", "https://"]
+        .iter()
+        .any(|scheme| text.starts_with(scheme) && text.len() > scheme.len())
+        && !text.contains(char::is_whitespace)
+}
+
+/// The format of a string value, if it has a well-known one.
+fn string_format(text: &str) -> Option<&'static str> {
+    if is_date_time(text) {
+        Some("date-time")
+    } else if is_date(text) {
+        Some("date")
+    } else if is_uuid(text) {
+        Some("uuid")
+    } else if is_email(text) {
+        Some("email")
+    } else if is_uri(text) {
+        Some("uri")
+    } else {
+        None
+    }
+}
+
+/// Infer the schema of a JSON value: its types, the keys of its objects,
+/// all of them required, and the formats of its strings. The schemas of the
+/// items of an array are merged into one.
+pub fn infer_schema(value: &Value) -> Value {
+    let mut schema = Map::new();
+    match value {
+        Value::Null => {
+            schema.insert("type".into(), "null".into());
+        }
+        Value::Bool(_) => {
+            schema.insert("type".into(), "boolean".into());
+        }
+        Value::Number(number) => {
+            let integer = number.is_i64() || number.is_u64();
+            schema.insert(
+                "type".into(),
+                if integer { "integer" } else { "number" }.into(),
+            );
+        }
+        Value::String(text) => {
+            schema.insert("type".into(), "string".into());
+            if let Some(format) = string_format(text) {
+                schema.insert("format".into(), format.into());
+            }
+        }
+        Value::Array(items) => {
+            schema.insert("type".into(), "array".into());
+            if let Some(items) = items.iter().map(infer_schema).reduce(merge_schemas) {
+                schema.insert("items".into(), items);
+            }
+        }
+        Value::Object(object) => {
+            schema.insert("type".into(), "object".into());
+            let properties: Map<String, Value> = object
+                .iter()
+                .map(|(key, value)| (key.clone(), infer_schema(value)))
+                .collect();
+            let required: Vec<Value> = object.keys().map(|key| key.as_str().into()).collect();
+            schema.insert("properties".into(), properties.into());
+            if !required.is_empty() {
+                schema.insert("required".into(), required.into());
+            }
+        }
+    }
+    schema.into()
+}
+
+/// The types a schema allows.
+fn types(schema: &Value) -> Vec<&str> {
+    match &schema["type"] {
+        Value::String(single) => vec![single.as_str()],
+        Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A schema allowing the values of both `a` and `b`.
+///
+/// Types are unioned, `integer` widening to `number`. Object keys missing
+/// from either side are no longer required and string formats which differ
+/// are dropped.
+pub fn merge_schemas(a: Value, b: Value) -> Value {
+    if a == b {
+        return a;
+    }
+    let (a_types, b_types) = (types(&a), types(&b));
+    let mut union: Vec<&str> = a_types.clone();
+    for t in &b_types {
+        if !union.contains(t) {
+            union.push(t);
+        }
+    }
+    if union.contains(&"number") {
+        union.retain(|t| *t != "integer");
+    }
+    let both = |t: &str| a_types.contains(&t) && b_types.contains(&t);
+
+    let mut schema = Map::new();
+    schema.insert(
+        "type".into(),
+        match union.as_slice() {
+            [single] => (*single).into(),
+            _ => union.iter().map(|t| Value::from(*t)).collect(),
+        },
+    );
+
+    let format = match (a.get("format"), b.get("format")) {
+        (Some(a_format), Some(b_format)) if a_format == b_format => Some(a_format.clone()),
+        (Some(format), None) if !both("string") => Some(format.clone()),
+        (None, Some(format)) if !both("string") => Some(format.clone()),
+        _ => None,
+    };
+    if let Some(format) = format {
+        schema.insert("format".into(), format);
+    }
+
+    match (a.get("properties"), b.get("properties")) {
+        (Some(Value::Object(a_properties)), Some(Value::Object(b_properties))) => {
+            let mut properties = a_properties.clone();
+            for (key, b_property) in b_properties {
+                match properties.get_mut(key) {
+                    Some(a_property) => {
+                        *a_property = merge_schemas(a_property.take(), b_property.clone())
+                    }
+                    None => {
+                        properties.insert(key.clone(), b_property.clone());
+                    }
+                }
+            }
+            schema.insert("properties".into(), properties.into());
+
+            let b_required = b.get("required").and_then(Value::as_array);
+            let required: Vec<Value> = a
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter(|key| b_required.is_some_and(|required| required.contains(key)))
+                .cloned()
+                .collect();
+            if !required.is_empty() {
+                schema.insert("required".into(), required.into());
+            }
+        }
+        _ => {
+            for side in [&a, &b] {
+                for key in ["properties", "required"] {
+                    if let Some(value) = side.get(key) {
+                        schema.insert(key.into(), value.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    let items = match (a.get("items"), b.get("items")) {
+        (Some(a_items), Some(b_items)) => Some(merge_schemas(a_items.clone(), b_items.clone())),
+        (items, None) | (None, items) => items.cloned(),
+    };
+    if let Some(items) = items {
+        schema.insert("items".into(), items);
+    }
+    schema.into()
+}
+
+/// The schema of a JSON document, declaring the dialect it is written in.
+pub fn document_schema(value: &Value) -> Value {
+    let mut schema = json!({ "$schema": SCHEMA_DIALECT });
+    if let (Some(schema), Value::Object(inferred)) = (schema.as_object_mut(), infer_schema(value)) {
+        schema.extend(inferred);
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("2024-05-01T12:30:00Z", Some("date-time"))]
+    #[case("2024-05-01T12:30:00.123+02:00", Some("date-time"))]
+    #[case("2024-05-01", Some("date"))]
+    #[case("2024-13-01", None)]
+    #[case("123e4567-e89b-12d3-a456-426614174000", Some("uuid"))]
+    #[case("me@example.com", Some("email"))]
+    #[case("https://example.com/a", Some("uri"))]
+    #[case("hello", None)]
+    fn test_string_format(#[case] text: &str, #[case] expected: Option<&str>) {
+        assert_eq!(string_format(text), expected);
+    }
+
+    #[rstest]
+    fn test_infer_schema() {
+        let value = json!({
+            "id": "123e4567-e89b-12d3-a456-426614174000",
+            "count": 2,
+            "price": 9.5,
+            "tags": ["a", "b"],
+            "owner": null,
+            "active": true
+        });
+        assert_eq!(
+            infer_schema(&value),
+            json!({
+                "type": "object",
+                "properties": {
+                    "id": {"type": "string", "format": "uuid"},
+                    "count": {"type": "integer"},
+                    "price": {"type": "number"},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "owner": {"type": "null"},
+                    "active": {"type": "boolean"}
+                },
+                "required": ["id", "count", "price", "tags", "owner", "active"]
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_infer_schema_merges_items() {
+        let value = json!([
+            {"id": 1, "name": "a", "at": "2024-05-01"},
+            {"id": 2.5, "at": "soon", "parent": null},
+            {"id": 3, "name": null, "at": "2024-05-02", "parent": {"id": 1}}
+        ]);
+        assert_eq!(
+            infer_schema(&value),
+            json!({
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "id": {"type": "number"},
+                        "name": {"type": ["string", "null"]},
+                        "at": {"type": "string"},
+                        "parent": {
+                            "type": ["null", "object"],
+                            "properties": {"id": {"type": "integer"}},
+                            "required": ["id"]
+                        }
+                    },
+                    "required": ["id", "at"]
+                }
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_merge_schemas_keeps_key_order() {
+        let schema = infer_schema(&json!([{"a": 1, "b": 2}, {"b": 3, "c": 4}]));
+        let keys: Vec<&String> = schema["items"]["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect();
+        assert_eq!(keys, ["a", "b", "c"]);
+    }
+
+    #[rstest]
+    #[case(json!([]), json!({"type": "array"}))]
+    #[case(json!({}), json!({"type": "object", "properties": {}}))]
+    #[case(json!([1, "x"]), json!({"type": "array", "items": {"type": ["integer", "string"]}}))]
+    fn test_infer_schema_edge_cases(#[case] value: Value, #[case] expected: Value) {
+        assert_eq!(infer_schema(&value), expected);
+    }
+}
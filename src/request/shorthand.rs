@@ -0,0 +1,155 @@
+use crate::curl::header::Headers;
+use crate::curl::parser::Curl;
+use crate::diagnostic::{Code, Diagnostic};
+
+/// Options curl turns into a header, unless that header is given with `-H`.
+const SHORTHANDS: &[(&str, &str)] = &[
+    ("--user-agent", "User-Agent"),
+    ("--referer", "Referer"),
+    ("--cookie", "Cookie"),
+];
+
+/// The headers `--json` sends, unless given with `-H`.
+const JSON_HEADERS: &[(&str, &str)] = &[
+    ("Content-Type", "application/json"),
+    ("Accept", "application/json"),
+];
+
+/// A shorthand option ignored by curl because its header is also given
+/// with `-H`, e.g. `-A` next to `-H 'User-Agent: ...'`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shadowed {
+    /// The long name of the option.
+    pub option: &'static str,
+    pub header: &'static str,
+}
+
+impl Shadowed {
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(
+            Code::ShadowedOption,
+            format!(
+                "{} is ignored because a {} header is given",
+                self.option, self.header
+            ),
+        )
+    }
+}
+
+/// The header value a shorthand option sends, `None` when it sends none.
+///
+/// The last `-A` and `-e` win, `;auto` is dropped from `-e`, and every
+/// `-b` holding cookies is joined, the others naming cookie files.
+fn shorthand_value(curls: &[Curl], option: &str) -> Option<String> {
+    let mut values = curls.iter().filter_map(|curl| match curl {
+        Curl::Option { name, value } if name == option => Some(&**value),
+        _ => None,
+    });
+    let value = match option {
+        "--cookie" => values
+            .filter(|value| value.contains('='))
+            .collect::<Vec<_>>()
+            .join("; "),
+        "--referer" => values.next_back()?.trim_end_matches(";auto").to_string(),
+        _ => values.next_back()?.to_string(),
+    };
+    (!value.is_empty()).then_some(value)
+}
+
+/// Every shorthand option whose header is replaced by a `-H` one.
+pub fn shadowed(curls: &[Curl]) -> Vec<Shadowed> {
+    let headers = Headers::from(curls);
+    SHORTHANDS
+        .iter()
+        .filter(|(option, header)| {
+            headers.contains(header) && shorthand_value(curls, option).is_some()
+        })
+        .map(|&(option, header)| Shadowed { option, header })
+        .collect()
+}
+
+/// Add the headers of `--json` to `headers` when `curls` use it, unless
+/// already there.
+pub fn add_json_headers(curls: &[Curl], headers: &mut Headers) {
+    let json = curls
+        .iter()
+        .any(|curl| matches!(curl, Curl::Data(stru) if stru.identifier == "--json"));
+    if !json {
+        return;
+    }
+    for (name, value) in JSON_HEADERS {
+        if !headers.contains(name) {
+            headers.append(name, value);
+        }
+    }
+}
+
+/// The headers curl sends for `curls`: the `-H` ones, followed by those of
+/// the shorthand options and `--json` they don't override.
+pub fn resolve_headers(curls: &[Curl]) -> Headers {
+    complete_headers(curls, Headers::from(curls))
+}
+
+/// Add the headers of the shorthand options and `--json` to the `-H`
+/// headers of `curls`, see [`resolve_headers`].
+pub fn complete_headers(curls: &[Curl], mut headers: Headers) -> Headers {
+    for (option, header) in SHORTHANDS {
+        if !headers.contains(header)
+            && let Some(value) = shorthand_value(curls, option)
+        {
+            headers.append(header, &value);
+        }
+    }
+    add_json_headers(curls, &mut headers);
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    #[rstest]
+    #[case("curl https://a.com -A 'bot/1' -A 'bot/2'", "User-Agent: bot/2")]
+    #[case("curl https://a.com -A ''", "")]
+    #[case("curl https://a.com -e 'https://b.com;auto'", "Referer: https://b.com")]
+    #[case("curl https://a.com -e ';auto'", "")]
+    #[case("curl https://a.com -b 'a=1' -b jar.txt -b 'b=2'", "Cookie: a=1; b=2")]
+    #[case(
+        "curl https://a.com -H 'user-agent: x' -A 'bot' -b 'a=1'",
+        "user-agent: x\nCookie: a=1"
+    )]
+    #[case(
+        "curl https://a.com --json '{}'",
+        "Content-Type: application/json\nAccept: application/json"
+    )]
+    #[case(
+        "curl https://a.com -H 'accept: */*' --json '{}'",
+        "accept: */*\nContent-Type: application/json"
+    )]
+    fn test_resolve_headers(#[case] input: &str, #[case] expected: &str) {
+        let curls = curl_cmd_parse(input).unwrap();
+        assert_eq!(resolve_headers(&curls).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("curl https://a.com -H 'User-Agent: x' -A 'bot'", vec!["--user-agent"])]
+    #[case("curl https://a.com -H 'Referer: x' -e 'y' -H 'Cookie: a=1' -b 'b=2'", vec!["--referer", "--cookie"])]
+    #[case("curl https://a.com -H 'Cookie: a=1' -b jar.txt", vec![])]
+    #[case("curl https://a.com -A 'bot'", vec![])]
+    fn test_shadowed(#[case] input: &str, #[case] expected: Vec<&str>) {
+        let curls = curl_cmd_parse(input).unwrap();
+        let options: Vec<&str> = shadowed(&curls).iter().map(|s| s.option).collect();
+        assert_eq!(options, expected);
+    }
+
+    #[rstest]
+    fn test_diagnostic() {
+        let curls = curl_cmd_parse("curl https://a.com -H 'User-Agent: x' -A 'bot'").unwrap();
+        assert_eq!(
+            shadowed(&curls)[0].diagnostic().to_string(),
+            "warning[WCL004]: --user-agent is ignored because a User-Agent header is given"
+        );
+    }
+}
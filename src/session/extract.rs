@@ -0,0 +1,263 @@
+use serde::de::{Deserialize, Deserializer, Error as _};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use winnow::ModalResult;
+use winnow::ascii::dec_uint;
+use winnow::combinator::{alt, cut_err, delimited, eof, preceded, repeat, separated, terminated};
+use winnow::prelude::*;
+use winnow::token::{rest, take_till, take_while};
+
+/// A step of a JSON path: the key of an object or the index of an array.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Where a value lies in a response.
+///
+/// ```
+/// use winnowcurl::session::extract::Extractor;
+///
+/// let token: Extractor = "$.data.access_token".parse().unwrap();
+/// assert_eq!(token.apply(&[], br#"{"data":{"access_token":"t0k"}}"#).as_deref(), Some("t0k"));
+///
+/// let location: Extractor = "header:Location".parse().unwrap();
+/// let headers = [("location".to_string(), "/orders/7".to_string())];
+/// assert_eq!(location.apply(&headers, b"").as_deref(), Some("/orders/7"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Extractor {
+    /// A JSONPath into the body, e.g. `$.items[0].id` or `$['a.b']`.
+    /// Paths without the `$`, e.g. `items.0.id`, are read as keys and
+    /// indexes separated by dots.
+    Json(Vec<Segment>),
+    /// A response header, e.g. `header:Location`, its name matched
+    /// ignoring case.
+    Header(String),
+}
+
+/// A key after a `.`, up to the next `.` or `[`.
+fn dotted_key(s: &mut &str) -> ModalResult<Segment> {
+    take_while(1.., |c| c != '.' && c != '[')
+        .map(|key: &str| Segment::Key(key.into()))
+        .parse_next(s)
+}
+
+/// A `[0]` index or a `['key']` or `["key"]` key.
+fn bracketed(s: &mut &str) -> ModalResult<Segment> {
+    delimited(
+        '[',
+        alt((
+            dec_uint.map(Segment::Index),
+            delimited('\'', take_till(0.., '\''), '\'').map(|k: &str| Segment::Key(k.into())),
+            delimited('"', take_till(0.., '"'), '"').map(|k: &str| Segment::Key(k.into())),
+        )),
+        ']',
+    )
+    .parse_next(s)
+}
+
+fn json_path(s: &mut &str) -> ModalResult<Vec<Segment>> {
+    preceded(
+        '$',
+        cut_err(terminated(
+            repeat(0.., alt((preceded('.', dotted_key), bracketed))),
+            eof,
+        )),
+    )
+    .parse_next(s)
+}
+
+/// The older `items.0.id` form, numbers standing for indexes.
+fn dotted_path(s: &mut &str) -> ModalResult<Vec<Segment>> {
+    let segment = take_while(1.., |c| c != '.').map(|segment: &str| match segment.parse() {
+        Ok(index) => Segment::Index(index),
+        Err(_) => Segment::Key(segment.into()),
+    });
+    terminated(separated(1.., segment, '.'), eof).parse_next(s)
+}
+
+fn extractor(s: &mut &str) -> ModalResult<Extractor> {
+    alt((
+        preceded(
+            "header:",
+            cut_err(rest.verify(|name: &str| !name.trim().is_empty())),
+        )
+        .map(|name: &str| Extractor::Header(name.trim().into())),
+        json_path.map(Extractor::Json),
+        dotted_path.map(Extractor::Json),
+    ))
+    .parse_next(s)
+}
+
+impl Extractor {
+    /// The value in a response, strings without their quotes and other
+    /// JSON values as JSON; `None` when it is absent or `null`, or when the
+    /// body isn't JSON.
+    pub fn apply(&self, headers: &[(String, String)], body: &[u8]) -> Option<String> {
+        match self {
+            Extractor::Header(name) => headers
+                .iter()
+                .find(|(header, _)| header.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.clone()),
+            Extractor::Json(segments) => {
+                let json: serde_json::Value = serde_json::from_slice(body).ok()?;
+                let value = segments
+                    .iter()
+                    .try_fold(&json, |value, segment| match segment {
+                        Segment::Key(key) => value.as_object()?.get(key),
+                        Segment::Index(index) => value.as_array()?.get(*index),
+                    })?;
+                match value {
+                    serde_json::Value::Null => None,
+                    serde_json::Value::String(string) => Some(string.clone()),
+                    value => Some(value.to_string()),
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Extractor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        extractor.parse(s).map_err(|_| {
+            format!(
+                "expected a JSONPath like `$.data.id` or `header:Name`, found `{}`",
+                s
+            )
+        })
+    }
+}
+
+/// Written back as a JSONPath, keys which aren't plain words quoted.
+impl fmt::Display for Extractor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Extractor::Header(name) => write!(f, "header:{}", name),
+            Extractor::Json(segments) => {
+                f.write_str("$")?;
+                for segment in segments {
+                    match segment {
+                        Segment::Index(index) => write!(f, "[{}]", index)?,
+                        Segment::Key(key)
+                            if !key.is_empty()
+                                && key
+                                    .chars()
+                                    .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-')) =>
+                        {
+                            write!(f, ".{}", key)?
+                        }
+                        Segment::Key(key) if !key.contains('\'') => write!(f, "['{}']", key)?,
+                        Segment::Key(key) => write!(f, "[\"{}\"]", key)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Serialize for Extractor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Extractor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn key(key: &str) -> Segment {
+        Segment::Key(key.into())
+    }
+
+    #[rstest]
+    #[case("$.data.access_token", Extractor::Json(vec![key("data"), key("access_token")]))]
+    #[case("$.items[0].id", Extractor::Json(vec![key("items"), Segment::Index(0), key("id")]))]
+    #[case("$['a.b'][\"c'd\"]", Extractor::Json(vec![key("a.b"), key("c'd")]))]
+    #[case("$", Extractor::Json(vec![]))]
+    #[case("items.0.id", Extractor::Json(vec![key("items"), Segment::Index(0), key("id")]))]
+    #[case("header:Location", Extractor::Header("Location".into()))]
+    #[case("header: ETag ", Extractor::Header("ETag".into()))]
+    fn test_parse(#[case] input: &str, #[case] expected: Extractor) {
+        assert_eq!(input.parse::<Extractor>(), Ok(expected));
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("$.")]
+    #[case("$.items[x]")]
+    #[case("$.items[0")]
+    #[case("items..id")]
+    #[case("header:")]
+    fn test_parse_invalid(#[case] input: &str) {
+        assert!(input.parse::<Extractor>().is_err());
+    }
+
+    #[rstest]
+    #[case("$.data.token", "$.data.token")]
+    #[case("items.0.id", "$.items[0].id")]
+    #[case("$['a.b']['c d']", "$['a.b']['c d']")]
+    #[case("$[\"it's\"]", "$[\"it's\"]")]
+    #[case("header:ETag", "header:ETag")]
+    fn test_display(#[case] input: &str, #[case] expected: &str) {
+        let extractor: Extractor = input.parse().unwrap();
+        assert_eq!(extractor.to_string(), expected);
+        assert_eq!(expected.parse::<Extractor>(), Ok(extractor));
+    }
+
+    #[rstest]
+    #[case(r#"{"data":{"token":"abc"}}"#, "$.data.token", Some("abc"))]
+    #[case(r#"{"data":{"token":"abc"}}"#, "data.token", Some("abc"))]
+    #[case(r#"{"items":[{"id":1},{"id":2}]}"#, "$.items[1].id", Some("2"))]
+    #[case(r#"{"items":[{"id":1},{"id":2}]}"#, "items.1.id", Some("2"))]
+    #[case(r#"{"a.b":"dotted"}"#, "$['a.b']", Some("dotted"))]
+    #[case(r#"{"ok":true}"#, "$.ok", Some("true"))]
+    #[case(r#"{"list":[1,2]}"#, "$.list", Some("[1,2]"))]
+    #[case(r#"{"token":null}"#, "$.token", None)]
+    #[case(r#"{"items":[]}"#, "$.items[0]", None)]
+    #[case(r#"{"items":{"0":1}}"#, "$.items[0]", None)]
+    #[case("not json", "$.token", None)]
+    fn test_apply_json(#[case] body: &str, #[case] path: &str, #[case] expected: Option<&str>) {
+        let extractor: Extractor = path.parse().unwrap();
+        assert_eq!(extractor.apply(&[], body.as_bytes()).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case("header:Location", Some("/orders/7"))]
+    #[case("header:x-request-id", Some("r-1"))]
+    #[case("header:ETag", None)]
+    fn test_apply_header(#[case] extractor: &str, #[case] expected: Option<&str>) {
+        let headers = [
+            ("Location".to_string(), "/orders/7".to_string()),
+            ("X-Request-Id".to_string(), "r-1".to_string()),
+        ];
+        let extractor: Extractor = extractor.parse().unwrap();
+        assert_eq!(extractor.apply(&headers, b"{}").as_deref(), expected);
+    }
+
+    #[rstest]
+    fn test_serde() {
+        let extractor: Extractor = "items.0".parse().unwrap();
+        let json = serde_json::to_value(&extractor).unwrap();
+        assert_eq!(json, "$.items[0]");
+        assert_eq!(
+            serde_json::from_value::<Extractor>(json).unwrap(),
+            extractor
+        );
+        assert!(serde_json::from_value::<Extractor>("header:".into()).is_err());
+    }
+}
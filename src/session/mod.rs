@@ -0,0 +1,504 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::curl::ast::{Curl, CurlStru, OwnedCurl};
+use crate::curl::error::ParseError;
+use crate::curl::parser::curl_cmd_try_parse;
+use crate::curl::render::to_curl_string;
+#[cfg(feature = "exec")]
+use crate::exec::{self, ExecError, Response};
+
+pub mod extract;
+
+use extract::Extractor;
+
+/// A value taken from the response of a step, e.g. `token=$.data.token` or
+/// `next=header:Location`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Extraction {
+    /// The variable the value is stored in, used as `{{name}}` by the next
+    /// steps.
+    pub name: String,
+    /// Where the value lies in the response.
+    pub path: Extractor,
+}
+
+impl Extraction {
+    /// Store the value of `response` in `variables`, telling whether it had
+    /// one.
+    #[cfg(feature = "exec")]
+    pub fn apply(&self, response: &Response, variables: &mut IndexMap<String, String>) -> bool {
+        match self.path.apply(&response.headers, &response.body) {
+            Some(value) => {
+                variables.insert(self.name.clone(), value);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl FromStr for Extraction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((name, path)) if is_variable_name(name) => Ok(Extraction {
+                name: name.into(),
+                path: path.parse()?,
+            }),
+            _ => Err(format!("expected `name=path`, found `{}`", s)),
+        }
+    }
+}
+
+/// One command of a script and the values taken from its response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Step {
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extract: Vec<Extraction>,
+}
+
+/// Commands recorded to be replayed one after another, the values a step
+/// extracts from its response filling the `{{name}}` placeholders of the
+/// next ones.
+///
+/// ```
+/// use winnowcurl::session::Script;
+///
+/// let mut script = Script::default();
+/// script
+///     .record("curl https://a.com/login -d user=me")?
+///     .extract
+///     .push("token=$.data.token".parse().unwrap());
+/// script.record("curl https://a.com/me -H 'Authorization: Bearer {{token}}'")?;
+/// assert_eq!(script.steps.len(), 2);
+/// # Ok::<(), winnowcurl::curl::error::ParseError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Script {
+    pub steps: Vec<Step>,
+}
+
+/// Why a script could not be loaded, saved or replayed. Steps are counted
+/// from 1.
+#[derive(Debug)]
+pub enum SessionError {
+    /// The script file could not be read or written.
+    Io { path: PathBuf, source: io::Error },
+    /// The script file isn't a script.
+    Format {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    /// The command of a step doesn't parse.
+    Parse { step: usize, error: ParseError },
+    /// A step uses a variable no earlier step extracted.
+    UnknownVariable { step: usize, name: String },
+    /// The response of a step has no value to extract, `path` written as
+    /// a JSONPath or `header:Name`.
+    Extract {
+        step: usize,
+        name: String,
+        path: String,
+    },
+    /// The request of a step failed.
+    #[cfg(feature = "exec")]
+    Exec { step: usize, error: ExecError },
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::Io { path, source } => {
+                write!(f, "cannot access {}: {}", path.display(), source)
+            }
+            SessionError::Format { path, source } => {
+                write!(f, "{} is not a session script: {}", path.display(), source)
+            }
+            SessionError::Parse { step, error } => write!(f, "step {}: {}", step, error.message),
+            SessionError::UnknownVariable { step, name } => {
+                write!(f, "step {}: unknown variable {{{{{}}}}}", step, name)
+            }
+            SessionError::Extract { step, name, path } => write!(
+                f,
+                "step {}: the response has no value at '{}' for {}",
+                step, path, name
+            ),
+            #[cfg(feature = "exec")]
+            SessionError::Exec { step, error } => write!(f, "step {}: {}", step, error),
+        }
+    }
+}
+
+impl SessionError {
+    /// The exit code a replay stopped by the error ends with: that of the
+    /// failed request, see [`ExecError::exit_code`], and 1 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            #[cfg(feature = "exec")]
+            SessionError::Exec { error, .. } => error.exit_code(),
+            _ => 1,
+        }
+    }
+}
+
+impl std::error::Error for SessionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SessionError::Io { source, .. } => Some(source),
+            SessionError::Format { source, .. } => Some(source),
+            #[cfg(feature = "exec")]
+            SessionError::Exec { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Script {
+    /// Read a script saved with [`Script::save`].
+    pub fn load(path: &Path) -> Result<Self, SessionError> {
+        let text = fs::read_to_string(path).map_err(|source| SessionError::Io {
+            path: path.into(),
+            source,
+        })?;
+        serde_json::from_str(&text).map_err(|source| SessionError::Format {
+            path: path.into(),
+            source,
+        })
+    }
+
+    /// Write the script as JSON.
+    pub fn save(&self, path: &Path) -> Result<(), SessionError> {
+        let json = serde_json::to_string_pretty(self).expect("scripts serialize");
+        fs::write(path, json + "\n").map_err(|source| SessionError::Io {
+            path: path.into(),
+            source,
+        })
+    }
+
+    /// Append a command as a new step, normalized the way
+    /// [`to_curl_string`] writes it.
+    pub fn record(&mut self, command: &str) -> Result<&mut Step, ParseError> {
+        let curls = curl_cmd_try_parse(command)?;
+        self.steps.push(Step {
+            command: to_curl_string(&curls),
+            extract: Vec::new(),
+        });
+        Ok(self.steps.last_mut().expect("a step was just pushed"))
+    }
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Replace the `{{name}}` placeholders of `text`, failing with the name of
+/// the first variable missing from `variables`.
+fn fill<'t>(text: &'t str, variables: &IndexMap<String, String>) -> Result<Cow<'t, str>, String> {
+    if !text.contains("{{") {
+        return Ok(Cow::Borrowed(text));
+    }
+    let mut filled = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        match after.find("}}").map(|end| &after[..end]) {
+            Some(name) if is_variable_name(name) => {
+                let value = variables.get(name).ok_or_else(|| name.to_string())?;
+                filled.push_str(&rest[..start]);
+                filled.push_str(value);
+                rest = &after[name.len() + 2..];
+            }
+            _ => {
+                filled.push_str(&rest[..start + 2]);
+                rest = after;
+            }
+        }
+    }
+    filled.push_str(rest);
+    Ok(Cow::Owned(filled))
+}
+
+/// Fill the `{{name}}` placeholders of the URL, method, headers, data and
+/// option values of a command; multipart fields are kept as they are.
+pub fn substitute(
+    curls: &[Curl<'_>],
+    variables: &IndexMap<String, String>,
+) -> Result<Vec<OwnedCurl>, String> {
+    let stru = |stru: &CurlStru| -> Result<CurlStru<'static>, String> {
+        Ok(CurlStru {
+            identifier: stru.identifier.to_string().into(),
+            data: match &stru.data {
+                Some(data) => Some(fill(data, variables)?.into_owned().into()),
+                None => None,
+            },
+        })
+    };
+    curls
+        .iter()
+        .map(|curl| {
+            Ok(match curl {
+                Curl::URL(url) => {
+                    let url = url.to_string();
+                    Curl::new_as_url_str(&fill(&url, variables)?).into_owned()
+                }
+                Curl::Method(s) => Curl::Method(stru(s)?),
+                Curl::Header(s) => Curl::Header(stru(s)?),
+                Curl::Data(s) => Curl::Data(stru(s)?),
+                Curl::Option { name, value } => Curl::Option {
+                    name: name.to_string().into(),
+                    value: fill(value, variables)?.into_owned().into(),
+                },
+                curl => curl.clone().into_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Replays the steps of a [`Script`] through one [`exec::Session`], so
+/// that they share their cookies as well.
+#[cfg(feature = "exec")]
+#[derive(Debug, Default)]
+pub struct Replay {
+    session: exec::Session,
+    variables: IndexMap<String, String>,
+}
+
+#[cfg(feature = "exec")]
+impl Replay {
+    /// A replay starting with `variables` already set.
+    pub fn new(variables: IndexMap<String, String>) -> Self {
+        Replay {
+            session: exec::Session::new(),
+            variables,
+        }
+    }
+
+    /// Only send the destructive requests `confirmation` allows.
+    pub fn with_confirmation(mut self, confirmation: exec::confirm::Confirmation) -> Self {
+        self.session = self.session.with_confirmation(confirmation);
+        self
+    }
+
+    /// The variables set so far.
+    pub fn variables(&self) -> &IndexMap<String, String> {
+        &self.variables
+    }
+
+    /// Perform the requests of the step `index` and extract the values of
+    /// its last response. Files of `-d @file` and `-K file` are read, as
+    /// curl would.
+    pub fn step(
+        &mut self,
+        index: usize,
+        step: &Step,
+        log: &mut dyn io::Write,
+    ) -> Result<Vec<Response>, SessionError> {
+        use crate::curl::files::FileResolver;
+        use crate::curl::{body, curlrc};
+
+        let parse_error = |error| SessionError::Parse { step: index, error };
+        let curls = curl_cmd_try_parse(&step.command).map_err(parse_error)?;
+        let curls = substitute(&curls, &self.variables)
+            .map_err(|name| SessionError::UnknownVariable { step: index, name })?;
+        let resolver = FileResolver::new();
+        let curls = curlrc::inline_configs(&curls, &resolver)
+            .map_err(|e| parse_error(ParseError::from(e)))?;
+        let curls = body::inline_files(&curls, &resolver)
+            .map_err(|e| parse_error(ParseError::from(e.diagnostic())))?;
+
+        let mut responses = Vec::new();
+        for part in exec::next_parts(&curls) {
+            let response = self
+                .session
+                .execute_with_log(part, log)
+                .map_err(|error| SessionError::Exec { step: index, error })?;
+            responses.push(response);
+        }
+        if let Some(last) = responses.last() {
+            for rule in &step.extract {
+                if !rule.apply(last, &mut self.variables) {
+                    return Err(SessionError::Extract {
+                        step: index,
+                        name: rule.name.clone(),
+                        path: rule.path.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(responses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    fn variables(pairs: &[(&str, &str)]) -> IndexMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[rstest]
+    #[case("token=$.data.token", Some(("token", "$.data.token")))]
+    #[case("id=items.0.id", Some(("id", "$.items[0].id")))]
+    #[case("next=header:Location", Some(("next", "header:Location")))]
+    #[case("token", None)]
+    #[case("=data", None)]
+    #[case("a b=data", None)]
+    #[case("token=", None)]
+    #[case("id=$.items[x]", None)]
+    fn test_extraction_from_str(#[case] input: &str, #[case] expected: Option<(&str, &str)>) {
+        let extraction = input.parse::<Extraction>().ok();
+        assert_eq!(
+            extraction
+                .as_ref()
+                .map(|e| (e.name.as_str(), e.path.to_string())),
+            expected.map(|(name, path)| (name, path.to_string()))
+        );
+    }
+
+    #[rstest]
+    #[case("Bearer {{token}}", Ok("Bearer t0k"))]
+    #[case("{{id}}/{{id}}", Ok("7/7"))]
+    #[case("{\"a\":{{id}}}", Ok("{\"a\":7}"))]
+    #[case("{{\"a\":1}}", Ok("{{\"a\":1}}"))]
+    #[case("no placeholder", Ok("no placeholder"))]
+    #[case("{{unset}}", Err("unset"))]
+    fn test_fill(#[case] text: &str, #[case] expected: Result<&str, &str>) {
+        let vars = variables(&[("token", "t0k"), ("id", "7")]);
+        assert_eq!(
+            fill(text, &vars).map(Cow::into_owned),
+            expected.map(String::from).map_err(String::from)
+        );
+    }
+
+    #[rstest]
+    fn test_substitute() {
+        let curls = curl_cmd_parse(
+            "curl 'https://a.com/users/{{id}}' -X {{method}} -H 'Authorization: Bearer {{token}}' -d '{\"id\":{{id}}}' -u '{{user}}:pw'",
+        )
+        .unwrap();
+        let vars = variables(&[
+            ("id", "7"),
+            ("method", "PATCH"),
+            ("token", "t0k"),
+            ("user", "me"),
+        ]);
+        assert_eq!(
+            to_curl_string(&substitute(&curls, &vars).unwrap()),
+            to_curl_string(
+                &curl_cmd_parse(
+                    "curl https://a.com/users/7 -X PATCH -H 'Authorization: Bearer t0k' -d '{\"id\":7}' -u me:pw"
+                )
+                .unwrap()
+            )
+        );
+        assert_eq!(
+            substitute(&curls, &variables(&[("id", "7")])),
+            Err("method".into())
+        );
+    }
+
+    #[rstest]
+    fn test_record_and_load() {
+        let mut script = Script::default();
+        script
+            .record("curl a.com/login -d user=me")
+            .unwrap()
+            .extract
+            .push("token=token".parse().unwrap());
+        script
+            .record("curl a.com/me -H 'Authorization: Bearer {{token}}'")
+            .unwrap();
+        assert!(script.record("curl a.com -m soon").is_err());
+        assert_eq!(script.steps.len(), 2);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flow.json");
+        script.save(&path).unwrap();
+        assert_eq!(Script::load(&path).unwrap(), script);
+
+        fs::write(&path, "[]").unwrap();
+        assert!(matches!(
+            Script::load(&path),
+            Err(SessionError::Format { .. })
+        ));
+    }
+
+    #[cfg(feature = "exec")]
+    #[rstest]
+    #[allow(clippy::disallowed_types)]
+    fn test_replay() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            [
+                &b"HTTP/1.1 200 OK\r\nConnection: close\r\nLocation: /me\r\nContent-Length: 24\r\n\r\n{\"data\":{\"token\":\"t0k\"}}"[..],
+                b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n",
+            ]
+            .map(|response| {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 1024];
+                let len = stream.read(&mut request).unwrap();
+                stream.write_all(response).unwrap();
+                String::from_utf8_lossy(&request[..len]).into_owned()
+            })
+        });
+
+        let mut script = Script::default();
+        script
+            .record(&format!("curl http://127.0.0.1:{}/login -d u=me", port))
+            .unwrap()
+            .extract
+            .extend(["token=$.data.token", "next=header:Location"].map(|e| e.parse().unwrap()));
+        script
+            .record(&format!(
+                "curl http://127.0.0.1:{}/me -H 'Authorization: Bearer {{{{token}}}}' -H 'X-From: {{{{next}}}}'",
+                port
+            ))
+            .unwrap();
+
+        let mut replay = Replay::default();
+        let mut log = Vec::new();
+        for (index, step) in script.steps.iter().enumerate() {
+            replay.step(index + 1, step, &mut log).unwrap();
+        }
+        let requests = server.join().unwrap();
+        assert_eq!(
+            replay.variables(),
+            &variables(&[("token", "t0k"), ("next", "/me")])
+        );
+        assert!(
+            requests[1].contains("authorization: Bearer t0k\r\n"),
+            "{}",
+            requests[1]
+        );
+        assert!(requests[1].contains("x-from: /me\r\n"), "{}", requests[1]);
+
+        let mut replay = Replay::default();
+        assert!(matches!(
+            replay.step(2, &script.steps[1], &mut log),
+            Err(SessionError::UnknownVariable { step: 2, .. })
+        ));
+    }
+}
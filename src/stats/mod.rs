@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::curl::options;
+use crate::curl::parser::Curl;
+use crate::request::Request;
+use crate::url::host::url_host;
+
+/// How many entries of each list the text report shows.
+pub const DEFAULT_TOP: usize = 10;
+
+/// The width of the longest bar of the text report.
+const BAR_WIDTH: usize = 30;
+
+/// How often a value was met.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Count {
+    pub name: String,
+    pub count: usize,
+}
+
+/// A profile of a batch of commands: which methods, hosts, headers and
+/// options they use, and how large their bodies are.
+///
+/// ```
+/// use winnowcurl::curl::parser::curl_cmd_parse;
+/// use winnowcurl::stats::BatchStats;
+///
+/// let commands = [
+///     curl_cmd_parse("curl https://api.example.com/a -H 'Accept: */*'")?,
+///     curl_cmd_parse("curl https://api.example.com/b -d 'x=1' -k")?,
+/// ];
+/// let stats = BatchStats::new(commands.iter().map(Vec::as_slice));
+/// assert_eq!(stats.hosts[0].name, "api.example.com");
+/// assert_eq!(stats.hosts[0].count, 2);
+/// assert_eq!(stats.average_body_size, Some(3.0));
+/// # Ok::<(), winnowcurl::diagnostic::Diagnostic>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchStats {
+    pub commands: usize,
+    /// Commands whose options conflict, so that they send no request. They
+    /// only count towards `commands` and `flags`.
+    pub invalid: usize,
+    /// The lists below are sorted by decreasing count, then by name.
+    pub methods: Vec<Count>,
+    /// Hosts, lowercased.
+    pub hosts: Vec<Count>,
+    /// How many commands send each header, names lowercased.
+    pub headers: Vec<Count>,
+    /// How many commands give each flag or option other than the URL, `-X`,
+    /// `-H`, `-d` and `-F`, under its long name.
+    pub flags: Vec<Count>,
+    /// Commands sending a body, multipart ones excepted.
+    pub bodies: usize,
+    /// The average size of those bodies, in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_body_size: Option<f64>,
+}
+
+/// The counts sorted by decreasing count, then by name.
+fn sorted(counts: HashMap<String, usize>) -> Vec<Count> {
+    let mut counts: Vec<Count> = counts
+        .into_iter()
+        .map(|(name, count)| Count { name, count })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    counts
+}
+
+impl BatchStats {
+    pub fn new<'c, 'a: 'c>(commands: impl IntoIterator<Item = &'c [Curl<'a>]>) -> Self {
+        let mut stats = BatchStats {
+            commands: 0,
+            invalid: 0,
+            methods: Vec::new(),
+            hosts: Vec::new(),
+            headers: Vec::new(),
+            flags: Vec::new(),
+            bodies: 0,
+            average_body_size: None,
+        };
+        let (mut methods, mut hosts, mut headers, mut flags) = Default::default();
+        let mut body_bytes = 0;
+
+        for curls in commands {
+            stats.commands += 1;
+            let mut names: Vec<String> = curls
+                .iter()
+                .flat_map(|curl| match curl {
+                    Curl::Flag(flag) => options::long_flag_names(&flag.identifier),
+                    Curl::Option { name, .. } => vec![name.to_string()],
+                    _ => Vec::new(),
+                })
+                .collect();
+            names.sort();
+            names.dedup();
+            tally(&mut flags, names);
+
+            let Ok(request) = Request::try_from(curls) else {
+                stats.invalid += 1;
+                continue;
+            };
+            tally(&mut methods, [request.method.as_str().to_ascii_uppercase()]);
+            tally(&mut hosts, [url_host(&request.url).to_ascii_lowercase()]);
+            let mut names: Vec<String> = request
+                .headers
+                .names()
+                .map(str::to_ascii_lowercase)
+                .collect();
+            names.sort();
+            names.dedup();
+            tally(&mut headers, names);
+            if let Some(body) = &request.body {
+                stats.bodies += 1;
+                body_bytes += body.len();
+            }
+        }
+
+        stats.methods = sorted(methods);
+        stats.hosts = sorted(hosts);
+        stats.headers = sorted(headers);
+        stats.flags = sorted(flags);
+        stats.average_body_size =
+            (stats.bodies > 0).then(|| body_bytes as f64 / stats.bodies as f64);
+        stats
+    }
+
+    /// Keep the `n` most frequent entries of each list.
+    pub fn truncate(&mut self, n: usize) {
+        for counts in [
+            &mut self.methods,
+            &mut self.hosts,
+            &mut self.headers,
+            &mut self.flags,
+        ] {
+            counts.truncate(n);
+        }
+    }
+}
+
+fn tally(counts: &mut HashMap<String, usize>, names: impl IntoIterator<Item = String>) {
+    for name in names {
+        *counts.entry(name).or_default() += 1;
+    }
+}
+
+/// A histogram per list, each bar as long as the share of the commands it
+/// stands for, e.g.
+///
+/// ```text
+/// 3 commands
+///
+/// methods:
+///   GET   2  ████████████████████
+///   POST  1  ██████████
+/// ```
+impl fmt::Display for BatchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} commands", self.commands)?;
+        if self.invalid > 0 {
+            write!(f, ", {} invalid", self.invalid)?;
+        }
+        writeln!(f)?;
+        for (title, counts) in [
+            ("methods", &self.methods),
+            ("hosts", &self.hosts),
+            ("headers", &self.headers),
+            ("flags", &self.flags),
+        ] {
+            if counts.is_empty() {
+                continue;
+            }
+            writeln!(f, "\n{}:", title)?;
+            let name_width = counts.iter().map(|c| c.name.chars().count()).max();
+            let count_width = counts.iter().map(|c| c.count.to_string().len()).max();
+            for Count { name, count } in counts {
+                let bar = (count * BAR_WIDTH).div_ceil(self.commands.max(1));
+                writeln!(
+                    f,
+                    "  {:<name_width$}  {:>count_width$}  {}",
+                    name,
+                    count,
+                    "█".repeat(bar),
+                    name_width = name_width.unwrap_or_default(),
+                    count_width = count_width.unwrap_or_default(),
+                )?;
+            }
+        }
+        write!(f, "\nbodies: {}", self.bodies)?;
+        if let Some(average) = self.average_body_size {
+            write!(f, ", {:.1} bytes on average", average)?;
+        }
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    fn stats(inputs: &[&str]) -> BatchStats {
+        let commands: Vec<Vec<Curl>> = inputs
+            .iter()
+            .map(|input| curl_cmd_parse(input).unwrap())
+            .collect();
+        BatchStats::new(commands.iter().map(Vec::as_slice))
+    }
+
+    #[rstest]
+    fn test_batch_stats() {
+        let stats = stats(&[
+            "curl https://A.com/x -H 'Accept: */*' -H 'accept: text/html' -k",
+            "curl https://a.com/y -d 'hello' --insecure -m 5",
+            "curl https://b.com -X put -d 'hi' -H 'X-Id: 1'",
+            "curl https://b.com -d 'a=1' -F 'f=@x'",
+        ]);
+        let counts = |counts: &[Count]| -> Vec<(String, usize)> {
+            counts.iter().map(|c| (c.name.clone(), c.count)).collect()
+        };
+        let expected = |pairs: &[(&str, usize)]| -> Vec<(String, usize)> {
+            pairs.iter().map(|&(name, n)| (name.into(), n)).collect()
+        };
+        assert_eq!(stats.commands, 4);
+        assert_eq!(stats.invalid, 1);
+        assert_eq!(
+            counts(&stats.methods),
+            expected(&[("GET", 1), ("POST", 1), ("PUT", 1)])
+        );
+        assert_eq!(
+            counts(&stats.hosts),
+            expected(&[("a.com", 2), ("b.com", 1)])
+        );
+        assert_eq!(
+            counts(&stats.headers),
+            expected(&[("accept", 1), ("x-id", 1)])
+        );
+        assert_eq!(
+            counts(&stats.flags),
+            expected(&[("--insecure", 2), ("--max-time", 1)])
+        );
+        assert_eq!(stats.bodies, 2);
+        assert_eq!(stats.average_body_size, Some(3.5));
+    }
+
+    #[rstest]
+    fn test_display() {
+        let mut stats = stats(&[
+            "curl https://a.com -k",
+            "curl https://a.com -d 'abcd'",
+            "curl https://b.com",
+        ]);
+        stats.truncate(1);
+        assert_eq!(
+            stats.to_string(),
+            concat!(
+                "3 commands\n",
+                "\n",
+                "methods:\n",
+                "  GET  2  ████████████████████\n",
+                "\n",
+                "hosts:\n",
+                "  a.com  2  ████████████████████\n",
+                "\n",
+                "flags:\n",
+                "  --insecure  1  ██████████\n",
+                "\n",
+                "bodies: 1, 4.0 bytes on average\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// Percent-encoding and decoding of URL components.
+///
+/// By default `+` is an ordinary character. Query strings and form bodies,
+/// encoded as `application/x-www-form-urlencoded`, use it for a space
+/// instead, see [`UrlCodec::form`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UrlCodec {
+    plus_as_space: bool,
+}
+
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
+impl UrlCodec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The codec of `application/x-www-form-urlencoded`, where `+` stands
+    /// for a space.
+    pub fn form() -> Self {
+        Self::new().with_plus_as_space(true)
+    }
+
+    pub fn with_plus_as_space(mut self, plus_as_space: bool) -> Self {
+        self.plus_as_space = plus_as_space;
+        self
+    }
+
+    /// Decode the `%XX` sequences of `value`.
+    ///
+    /// Malformed sequences are kept as they are, and bytes which don't make
+    /// up UTF-8 are replaced by `U+FFFD`.
+    pub fn decode<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        if !(value.contains('%') || self.plus_as_space && value.contains('+')) {
+            return Cow::Borrowed(value);
+        }
+        let bytes = value.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'%' => match (
+                    bytes.get(i + 1).copied().and_then(hex_value),
+                    bytes.get(i + 2).copied().and_then(hex_value),
+                ) {
+                    (Some(high), Some(low)) => {
+                        decoded.push(high << 4 | low);
+                        i += 3;
+                        continue;
+                    }
+                    _ => decoded.push(b'%'),
+                },
+                b'+' if self.plus_as_space => decoded.push(b' '),
+                byte => decoded.push(byte),
+            }
+            i += 1;
+        }
+        Cow::Owned(String::from_utf8_lossy(&decoded).into_owned())
+    }
+
+    /// Percent-encode every byte of `value` but the unreserved characters
+    /// `A-Z a-z 0-9 - . _ ~`, spaces becoming `+` when they stand for one.
+    pub fn encode<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        if value.bytes().all(is_unreserved) {
+            return Cow::Borrowed(value);
+        }
+        let mut encoded = String::with_capacity(value.len() * 3);
+        for byte in value.bytes() {
+            match byte {
+                b' ' if self.plus_as_space => encoded.push('+'),
+                byte if is_unreserved(byte) => encoded.push(byte as char),
+                byte => {
+                    let _ = write!(encoded, "%{:02X}", byte);
+                }
+            }
+        }
+        Cow::Owned(encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(UrlCodec::new(), "01%2C02%2C03", "01,02,03")]
+    #[case(UrlCodec::new(), "a+b%20c", "a+b c")]
+    #[case(UrlCodec::form(), "a+b%20c", "a b c")]
+    #[case(UrlCodec::new(), "caf%C3%A9", "café")]
+    #[case(UrlCodec::new(), "100%", "100%")]
+    #[case(UrlCodec::new(), "%zz%4", "%zz%4")]
+    #[case(UrlCodec::new(), "%ff", "\u{fffd}")]
+    fn test_decode(#[case] codec: UrlCodec, #[case] value: &str, #[case] expected: &str) {
+        assert_eq!(codec.decode(value), expected);
+    }
+
+    #[rstest]
+    #[case(UrlCodec::new(), "plain-text_1.0~", "plain-text_1.0~")]
+    #[case(UrlCodec::new(), "a b&c=d", "a%20b%26c%3Dd")]
+    #[case(UrlCodec::form(), "a b+c", "a+b%2Bc")]
+    #[case(UrlCodec::new(), "é", "%C3%A9")]
+    fn test_encode(#[case] codec: UrlCodec, #[case] value: &str, #[case] expected: &str) {
+        assert_eq!(codec.encode(value), expected);
+    }
+
+    #[rstest]
+    #[case("a b+c/é%")]
+    #[case("")]
+    fn test_round_trip(#[case] value: &str) {
+        for codec in [UrlCodec::new(), UrlCodec::form()] {
+            assert_eq!(codec.decode(&codec.encode(value)), value);
+        }
+    }
+
+    #[rstest]
+    fn test_borrows_when_unchanged() {
+        assert!(matches!(UrlCodec::form().decode("abc"), Cow::Borrowed(_)));
+        assert!(matches!(UrlCodec::new().encode("abc"), Cow::Borrowed(_)));
+    }
+}
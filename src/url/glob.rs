@@ -0,0 +1,616 @@
+use std::fmt;
+
+use crate::curl::options::has_flag;
+use crate::curl::parser::Curl;
+
+/// A piece of a URL glob pattern.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GlobPart {
+    /// Text taken as is.
+    Literal(String),
+    /// `{a,b,c}`: one of the alternatives.
+    Set(Vec<String>),
+    /// `[1-10]`, `[001-100:5]`: numbers, zero padded to `width` digits.
+    Numbers {
+        start: u64,
+        end: u64,
+        step: u64,
+        width: usize,
+    },
+    /// `[a-z]`, `[A-Z:2]`: letters.
+    Letters { start: char, end: char, step: u32 },
+}
+
+impl GlobPart {
+    /// The number of texts the part stands for.
+    pub fn len(&self) -> u64 {
+        match self {
+            GlobPart::Literal(_) => 1,
+            GlobPart::Set(set) => set.len() as u64,
+            GlobPart::Numbers {
+                start, end, step, ..
+            } => ((end - start) / step).saturating_add(1),
+            GlobPart::Letters { start, end, step } => {
+                (*end as u64 - *start as u64) / *step as u64 + 1
+            }
+        }
+    }
+
+    /// Whether the part stands for no text at all, which never happens for
+    /// a parsed part.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The `index`th text the part stands for, `index` being below
+    /// [`GlobPart::len`].
+    pub fn value(&self, index: u64) -> String {
+        match self {
+            GlobPart::Literal(text) => text.clone(),
+            GlobPart::Set(set) => set[index as usize].clone(),
+            GlobPart::Numbers {
+                start, step, width, ..
+            } => format!("{:0width$}", start + index * step, width = *width),
+            GlobPart::Letters { start, step, .. } => {
+                char::from(*start as u8 + (index * *step as u64) as u8).to_string()
+            }
+        }
+    }
+}
+
+/// Why a URL is not a valid glob pattern, as curl would refuse it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlobError {
+    /// A `{` or `[` is never closed, or a `}` or `]` never opened.
+    Unmatched { position: usize },
+    /// A `{` or `[` is opened inside another one.
+    Nested { position: usize },
+    /// A `[...]` range which is malformed, reversed or has a zero step.
+    BadRange { position: usize },
+}
+
+impl fmt::Display for GlobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (message, position) = match self {
+            GlobError::Unmatched { position } => ("unmatched brace or bracket", position),
+            GlobError::Nested { position } => ("nested brace or bracket", position),
+            GlobError::BadRange { position } => ("bad range", position),
+        };
+        write!(f, "{} in URL position {}", message, position)
+    }
+}
+
+impl std::error::Error for GlobError {}
+
+/// A URL as curl reads it, with `{...}` and `[...]` standing for several
+/// URLs unless globbing is turned off with `-g`/`--globoff`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UrlGlob {
+    pub parts: Vec<GlobPart>,
+}
+
+/// Whether the `[` at the start of `rest` opens an IPv6 address, possibly
+/// with a zone id like `%25eth0`, which curl never takes for a range.
+fn is_ipv6_literal(rest: &str) -> bool {
+    rest[1..].split_once(']').is_some_and(|(host, _)| {
+        let (address, zone) = host.split_once('%').unwrap_or((host, ""));
+        address.contains(':')
+            && address
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() || matches!(c, ':' | '.'))
+            && zone.chars().all(|c| c.is_ascii_alphanumeric())
+    })
+}
+
+/// Parse the inside of a `[...]` range, `position` being that of the `[`.
+fn parse_range(range: &str, position: usize) -> Result<GlobPart, GlobError> {
+    let bad_range = GlobError::BadRange { position };
+    let (bounds, step) = match range.split_once(':') {
+        Some((bounds, step)) => (bounds, Some(step)),
+        None => (range, None),
+    };
+    let (start, end) = bounds.split_once('-').ok_or(bad_range)?;
+
+    let mut letters = (start.chars(), end.chars());
+    if let ((Some(start), None), (Some(end), None)) = (
+        (letters.0.next(), letters.0.next()),
+        (letters.1.next(), letters.1.next()),
+    ) && start.is_ascii_alphabetic()
+    {
+        let step = step
+            .map_or(Ok(1), str::parse::<u32>)
+            .map_err(|_| bad_range)?;
+        let same_case = start.is_ascii_lowercase() == end.is_ascii_lowercase();
+        if !end.is_ascii_alphabetic() || !same_case || end < start || step == 0 {
+            return Err(bad_range);
+        }
+        return Ok(GlobPart::Letters { start, end, step });
+    }
+
+    let number = |n: &str| {
+        if n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(bad_range);
+        }
+        n.parse::<u64>().map_err(|_| bad_range)
+    };
+    let (first, last) = (number(start)?, number(end)?);
+    let step = step.map_or(Ok(1), number)?;
+    if last < first || step == 0 {
+        return Err(bad_range);
+    }
+    let width = if start.len() > 1 && start.starts_with('0') {
+        start.len()
+    } else {
+        0
+    };
+    Ok(GlobPart::Numbers {
+        start: first,
+        end: last,
+        step,
+        width,
+    })
+}
+
+impl UrlGlob {
+    /// Parse the glob patterns of `url`.
+    ///
+    /// A backslash makes the following brace, bracket or comma literal.
+    /// Positions in errors count characters from 1 like curl's messages do.
+    pub fn parse(url: &str) -> Result<Self, GlobError> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = url.char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            let position = url[..i].chars().count() + 1;
+            match c {
+                '\\' if chars
+                    .peek()
+                    .is_some_and(|(_, next)| matches!(next, '{' | '}' | '[' | ']' | ',')) =>
+                {
+                    literal.extend(chars.next().map(|(_, escaped)| escaped));
+                }
+                '[' if is_ipv6_literal(&url[i..]) => {
+                    literal.push('[');
+                    for (_, c) in chars.by_ref() {
+                        literal.push(c);
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                '{' => {
+                    let mut set = vec![String::new()];
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, ',')) => set.push(String::new()),
+                            Some((_, '\\')) if chars.peek().is_some() => {
+                                set.last_mut().unwrap().extend(chars.next().map(|(_, c)| c));
+                            }
+                            Some((j, '{' | '[')) => {
+                                let position = url[..j].chars().count() + 1;
+                                return Err(GlobError::Nested { position });
+                            }
+                            Some((_, c)) => set.last_mut().unwrap().push(c),
+                            None => return Err(GlobError::Unmatched { position }),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        parts.push(GlobPart::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(GlobPart::Set(set));
+                }
+                '[' => {
+                    let rest = &url[i + 1..];
+                    let end = rest.find(']').ok_or(GlobError::Unmatched { position })?;
+                    let range = &rest[..end];
+                    if let Some(nested) = range.find(['[', '{']) {
+                        let position = position + range[..nested].chars().count() + 1;
+                        return Err(GlobError::Nested { position });
+                    }
+                    if !literal.is_empty() {
+                        parts.push(GlobPart::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(parse_range(range, position)?);
+                    for _ in 0..range.chars().count() + 1 {
+                        chars.next();
+                    }
+                }
+                '}' | ']' => return Err(GlobError::Unmatched { position }),
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() || parts.is_empty() {
+            parts.push(GlobPart::Literal(literal));
+        }
+        Ok(UrlGlob { parts })
+    }
+
+    /// A pattern matching `url` literally, as curl reads it with `-g`.
+    pub fn literal(url: &str) -> Self {
+        UrlGlob {
+            parts: vec![GlobPart::Literal(url.into())],
+        }
+    }
+
+    /// Read `url` the way curl does, honouring `globoff`.
+    pub fn new(url: &str, globoff: bool) -> Result<Self, GlobError> {
+        if globoff {
+            Ok(UrlGlob::literal(url))
+        } else {
+            UrlGlob::parse(url)
+        }
+    }
+
+    /// The glob of the URL of a command, literal when `-g` is given; `None`
+    /// without a URL.
+    pub fn from_curls(curls: &[Curl]) -> Option<Result<Self, GlobError>> {
+        let url = curls.iter().find_map(|curl| match curl {
+            Curl::URL(url) => Some(url.to_string()),
+            _ => None,
+        })?;
+        Some(UrlGlob::new(&url, has_flag(curls, "--globoff")))
+    }
+
+    /// The number of URLs the pattern stands for, `None` when it doesn't fit
+    /// in a `u64`.
+    pub fn count(&self) -> Option<u64> {
+        self.parts
+            .iter()
+            .try_fold(1u64, |count, part| count.checked_mul(part.len()))
+    }
+
+    /// Every URL the pattern stands for, in the order curl requests them:
+    /// the last glob changes fastest.
+    pub fn expand(&self) -> Expand<'_> {
+        Expand {
+            matches: self.matches(),
+        }
+    }
+
+    /// Every URL the pattern stands for, like [`UrlGlob::expand`], with the
+    /// texts of its globs to fill the `#N` of an [`OutputTemplate`].
+    pub fn matches(&self) -> Matches<'_> {
+        Matches {
+            glob: self,
+            indices: Some(vec![0; self.parts.len()]),
+        }
+    }
+
+    /// Whether the pattern stands for a single URL.
+    pub fn is_literal(&self) -> bool {
+        self.parts
+            .iter()
+            .all(|part| matches!(part, GlobPart::Literal(_)))
+    }
+}
+
+/// One of the URLs a [`UrlGlob`] stands for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GlobMatch {
+    pub url: String,
+    /// The text of every `{...}` and `[...]` glob, in order, e.g. `["img",
+    /// "2"]` for `http://a.com/img/f2.png` of `http://a.com/{img,text}/f[1-2].png`.
+    pub values: Vec<String>,
+}
+
+/// The URLs of a [`UrlGlob`] with the texts of their globs, see
+/// [`UrlGlob::matches`].
+#[derive(Debug, Clone)]
+pub struct Matches<'a> {
+    glob: &'a UrlGlob,
+    /// The index of the current value of every part, `None` once done.
+    indices: Option<Vec<u64>>,
+}
+
+impl Iterator for Matches<'_> {
+    type Item = GlobMatch;
+
+    fn next(&mut self) -> Option<GlobMatch> {
+        let indices = self.indices.as_mut()?;
+        let parts = &self.glob.parts;
+        let mut url = String::new();
+        let mut values = Vec::new();
+        for (part, &index) in parts.iter().zip(indices.iter()) {
+            let value = part.value(index);
+            url.push_str(&value);
+            if !matches!(part, GlobPart::Literal(_)) {
+                values.push(value);
+            }
+        }
+
+        let mut done = true;
+        for (part, index) in parts.iter().zip(indices.iter_mut()).rev() {
+            *index += 1;
+            if *index < part.len() {
+                done = false;
+                break;
+            }
+            *index = 0;
+        }
+        if done {
+            self.indices = None;
+        }
+        Some(GlobMatch { url, values })
+    }
+}
+
+/// The URLs of a [`UrlGlob`], see [`UrlGlob::expand`].
+#[derive(Debug, Clone)]
+pub struct Expand<'a> {
+    matches: Matches<'a>,
+}
+
+impl Iterator for Expand<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.matches.next().map(|found| found.url)
+    }
+}
+
+/// A piece of an [`OutputTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TemplatePart {
+    /// Text taken as is.
+    Literal(String),
+    /// `#N`, with the digits as written, e.g. `01` for `#01`.
+    Variable(String),
+}
+
+/// The file name of `-o`/`--output`, where `#1`, `#2`... stand for the
+/// current text of the first, second... glob of the URL, e.g. `file_#1.txt`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OutputTemplate {
+    pub parts: Vec<TemplatePart>,
+}
+
+impl OutputTemplate {
+    /// Split `file` at its `#N` variables; a `#` not followed by a digit is
+    /// text.
+    pub fn parse(file: &str) -> Self {
+        let mut parts = Vec::new();
+        let mut rest = file;
+        while let Some(hash) = rest.find('#') {
+            let after = &rest[hash + 1..];
+            let digits = after
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after.len());
+            if digits == 0 {
+                push_literal(&mut parts, &rest[..hash + 1]);
+                rest = after;
+                continue;
+            }
+            push_literal(&mut parts, &rest[..hash]);
+            parts.push(TemplatePart::Variable(after[..digits].into()));
+            rest = &after[digits..];
+        }
+        push_literal(&mut parts, rest);
+        OutputTemplate { parts }
+    }
+
+    /// Whether the file name has no variable.
+    pub fn is_literal(&self) -> bool {
+        self.parts
+            .iter()
+            .all(|part| matches!(part, TemplatePart::Literal(_)))
+    }
+
+    /// The file name for the URL whose globs stand for `values`, see
+    /// [`GlobMatch::values`]. Like curl, a variable without a glob, such as
+    /// `#0` or `#3` with two globs, is kept as written.
+    pub fn resolve(&self, values: &[String]) -> String {
+        let mut file = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(text) => file.push_str(text),
+                TemplatePart::Variable(digits) => {
+                    let value = digits
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|n| n.checked_sub(1))
+                        .and_then(|i| values.get(i));
+                    match value {
+                        Some(value) => file.push_str(value),
+                        None => {
+                            file.push('#');
+                            file.push_str(digits);
+                        }
+                    }
+                }
+            }
+        }
+        file
+    }
+}
+
+/// Append `text` to the last literal of `parts`, or as a new one.
+fn push_literal(parts: &mut Vec<TemplatePart>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match parts.last_mut() {
+        Some(TemplatePart::Literal(literal)) => literal.push_str(text),
+        _ => parts.push(TemplatePart::Literal(text.into())),
+    }
+}
+
+impl fmt::Display for OutputTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(text) => f.write_str(text)?,
+                TemplatePart::Variable(digits) => write!(f, "#{}", digits)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curl::parser::curl_cmd_parse;
+    use rstest::*;
+
+    fn literal(text: &str) -> GlobPart {
+        GlobPart::Literal(text.into())
+    }
+
+    #[rstest]
+    #[case("http://a.com/x", vec![literal("http://a.com/x")])]
+    #[case("", vec![literal("")])]
+    #[case(
+        "http://a.com/{img,text}/f.png",
+        vec![
+            literal("http://a.com/"),
+            GlobPart::Set(vec!["img".into(), "text".into()]),
+            literal("/f.png"),
+        ]
+    )]
+    #[case(
+        "http://a.com/f[1-10].png",
+        vec![
+            literal("http://a.com/f"),
+            GlobPart::Numbers { start: 1, end: 10, step: 1, width: 0 },
+            literal(".png"),
+        ]
+    )]
+    #[case(
+        "http://a.com/[001-100:5]",
+        vec![
+            literal("http://a.com/"),
+            GlobPart::Numbers { start: 1, end: 100, step: 5, width: 3 },
+        ]
+    )]
+    #[case(
+        "http://a.com/[a-z:2]{,x}",
+        vec![
+            literal("http://a.com/"),
+            GlobPart::Letters { start: 'a', end: 'z', step: 2 },
+            GlobPart::Set(vec!["".into(), "x".into()]),
+        ]
+    )]
+    #[case(r"http://a.com/\{a\}\[1\]", vec![literal("http://a.com/{a}[1]")])]
+    #[case(r"http://a.com/{a\,b,c}", vec![literal("http://a.com/"), GlobPart::Set(vec!["a,b".into(), "c".into()])])]
+    #[case("http://[::1]:3000/x", vec![literal("http://[::1]:3000/x")])]
+    #[case("http://[fe80::1%25eth0]/", vec![literal("http://[fe80::1%25eth0]/")])]
+    fn test_parse(#[case] url: &str, #[case] expected: Vec<GlobPart>) {
+        assert_eq!(UrlGlob::parse(url), Ok(UrlGlob { parts: expected }));
+    }
+
+    #[rstest]
+    #[case("http://a.com/{a,b", GlobError::Unmatched { position: 14 })]
+    #[case("http://a.com/[1-3", GlobError::Unmatched { position: 14 })]
+    #[case("http://a.com/a}", GlobError::Unmatched { position: 15 })]
+    #[case("http://a.com/{a,{b}}", GlobError::Nested { position: 17 })]
+    #[case("http://a.com/[1-{2}]", GlobError::Nested { position: 17 })]
+    #[case("http://a.com/[3-1]", GlobError::BadRange { position: 14 })]
+    #[case("http://a.com/[1-3:0]", GlobError::BadRange { position: 14 })]
+    #[case("http://a.com/[a-Z]", GlobError::BadRange { position: 14 })]
+    #[case("http://a.com/[a-9]", GlobError::BadRange { position: 14 })]
+    #[case("http://a.com/[x]", GlobError::BadRange { position: 14 })]
+    fn test_parse_errors(#[case] url: &str, #[case] expected: GlobError) {
+        assert_eq!(UrlGlob::parse(url), Err(expected));
+    }
+
+    #[rstest]
+    #[case("curl 'http://a.com/x'", true, true)]
+    #[case("curl 'http://a.com/x' -g", true, true)]
+    #[case("curl 'http://a.com/{a,b}'", true, false)]
+    #[case("curl 'http://a.com/{a,b}' -g", true, true)]
+    #[case("curl 'http://a.com/f[1-3].png?x=1'", true, false)]
+    #[case("curl 'http://a.com/f[1-3].png?x=1' --globoff", true, true)]
+    #[case("curl 'http://a.com/{a,b' -sg", true, true)]
+    #[case("curl 'http://a.com/{a,b'", false, false)]
+    #[case("curl 'http://a.com/[3-1]'", false, false)]
+    #[case("curl 'http://[::1]:8080/'", true, true)]
+    fn test_from_curls(#[case] input: &str, #[case] valid: bool, #[case] literal: bool) {
+        let curls = curl_cmd_parse(input).unwrap();
+        let glob = UrlGlob::from_curls(&curls).unwrap();
+        assert_eq!(glob.is_ok(), valid, "{:?}", glob);
+        assert_eq!(glob.is_ok_and(|glob| glob.is_literal()), literal);
+    }
+
+    #[rstest]
+    fn test_from_curls_keeps_braces_literal_with_globoff() {
+        let curls = curl_cmd_parse("curl 'http://a.com/{a,b}?q=[1]' -g").unwrap();
+        let glob = UrlGlob::from_curls(&curls).unwrap().unwrap();
+        assert_eq!(glob.parts, vec![literal("http://a.com/{a,b}?q=[1]")]);
+    }
+
+    #[rstest]
+    #[case("http://a.com/x", vec!["http://a.com/x"])]
+    #[case(
+        "http://a.com/{img,text}/f[1-2].png",
+        vec![
+            "http://a.com/img/f1.png",
+            "http://a.com/img/f2.png",
+            "http://a.com/text/f1.png",
+            "http://a.com/text/f2.png",
+        ]
+    )]
+    #[case("http://a.com/[08-12:2]", vec!["http://a.com/08", "http://a.com/10", "http://a.com/12"])]
+    #[case("http://a.com/[a-e:2]{,x}", vec![
+        "http://a.com/a", "http://a.com/ax", "http://a.com/c",
+        "http://a.com/cx", "http://a.com/e", "http://a.com/ex",
+    ])]
+    fn test_expand(#[case] url: &str, #[case] expected: Vec<&str>) {
+        let glob = UrlGlob::parse(url).unwrap();
+        assert_eq!(glob.expand().collect::<Vec<_>>(), expected);
+        assert_eq!(glob.count(), Some(expected.len() as u64));
+    }
+
+    #[rstest]
+    fn test_count_overflow() {
+        let glob = UrlGlob::parse("http://a.com/[0-99999999999][0-99999999999]").unwrap();
+        assert_eq!(glob.count(), None);
+        assert_eq!(glob.expand().nth(1).as_deref(), Some("http://a.com/01"));
+    }
+
+    #[rstest]
+    fn test_matches() {
+        let glob = UrlGlob::parse("http://a.com/{img,text}/f[1-2].png").unwrap();
+        let found: Vec<_> = glob.matches().collect();
+        assert_eq!(found.len(), 4);
+        assert_eq!(found[1].url, "http://a.com/img/f2.png");
+        assert_eq!(found[1].values, ["img", "2"]);
+        let glob = UrlGlob::parse("http://a.com/x").unwrap();
+        assert_eq!(glob.matches().next().unwrap().values, Vec::<String>::new());
+    }
+
+    #[rstest]
+    #[case("file_#1.txt", vec![
+        TemplatePart::Literal("file_".into()),
+        TemplatePart::Variable("1".into()),
+        TemplatePart::Literal(".txt".into()),
+    ])]
+    #[case("#2#10", vec![TemplatePart::Variable("2".into()), TemplatePart::Variable("10".into())])]
+    #[case("a#b#", vec![TemplatePart::Literal("a#b#".into())])]
+    #[case("", vec![])]
+    fn test_output_template_parse(#[case] file: &str, #[case] expected: Vec<TemplatePart>) {
+        let template = OutputTemplate::parse(file);
+        assert_eq!(template.parts, expected);
+        assert_eq!(template.to_string(), file);
+    }
+
+    #[rstest]
+    #[case("http://a.com/{img,text}/f[1-2].png", "#1_#2.png", "img_2.png")]
+    #[case("http://a.com/{img,text}/f[1-2].png", "#2-#1", "2-img")]
+    #[case("http://a.com/[08-12:2]", "page#01.html", "page10.html")]
+    #[case("http://a.com/[a-c]", "#1#1", "bb")]
+    #[case("http://a.com/[1-2]", "#0_#2_#1", "#0_#2_2")]
+    #[case("http://a.com/{x,y}", "out#2", "out#2")]
+    #[case(
+        "http://a.com/[1-2]",
+        "#99999999999999999999999",
+        "#99999999999999999999999"
+    )]
+    fn test_output_template_resolve(#[case] url: &str, #[case] file: &str, #[case] expected: &str) {
+        let glob = UrlGlob::parse(url).unwrap();
+        let found = glob.matches().nth(1).unwrap();
+        assert_eq!(OutputTemplate::parse(file).resolve(&found.values), expected);
+    }
+}
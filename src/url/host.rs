@@ -0,0 +1,102 @@
+use super::codec::UrlCodec;
+
+/// The authority of `url`, without the credentials.
+fn authority(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.find(['/', '?', '#']).map_or(rest, |end| &rest[..end]);
+    authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host)
+}
+
+/// The host of `url`, without the credentials and the port; IPv6 addresses
+/// keep their brackets.
+pub fn url_host(url: &str) -> &str {
+    let host = authority(url);
+    if host.starts_with('[') {
+        return host.find(']').map_or(host, |end| &host[..=end]);
+    }
+    host.rsplit_once(':').map_or(host, |(host, _)| host)
+}
+
+/// The port `url` gives, `None` when it gives none or an invalid one.
+pub fn url_port(url: &str) -> Option<u16> {
+    let authority = authority(url);
+    authority[url_host(url).len()..]
+        .strip_prefix(':')?
+        .parse()
+        .ok()
+}
+
+/// The ASCII form of a host, as sent on the wire: lowercased, percent-encoded
+/// bytes decoded, internationalized labels encoded with Punycode (IDNA), and
+/// the trailing dot of a fully qualified name dropped. `None` when it is not
+/// a valid host name.
+///
+/// ```
+/// use winnowcurl::url::host::to_ascii;
+///
+/// assert_eq!(to_ascii("Bücher.Example.").as_deref(), Some("xn--bcher-kva.example"));
+/// ```
+pub fn to_ascii(host: &str) -> Option<String> {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    if host.starts_with('[') {
+        return Some(host.to_ascii_lowercase());
+    }
+    let host = UrlCodec::new().decode(host);
+    idna::domain_to_ascii(&host)
+        .ok()
+        .filter(|host| !host.is_empty())
+}
+
+/// Whether two hosts name the same server, comparing their ASCII forms so
+/// that `bücher.example` and `xn--bcher-kva.example` match while look-alike
+/// characters from other scripts don't. Hosts which are not valid names are
+/// compared ignoring ASCII case.
+pub fn same_host(a: &str, b: &str) -> bool {
+    match (to_ascii(a), to_ascii(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.eq_ignore_ascii_case(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("https://me:pw@Example.com:8443/a?b#c", "Example.com")]
+    #[case("example.com?to=me@b.com", "example.com")]
+    #[case("http://[::1]:8080/", "[::1]")]
+    #[case("bücher.example/x", "bücher.example")]
+    fn test_url_host(#[case] url: &str, #[case] expected: &str) {
+        assert_eq!(url_host(url), expected);
+    }
+
+    #[rstest]
+    #[case("https://me:pw@Example.com:8443/a?b#c", Some(8443))]
+    #[case("http://[::1]:8080/", Some(8080))]
+    #[case("http://[::1]/", None)]
+    #[case("a.com:http/", None)]
+    #[case("a.com/x:80", None)]
+    fn test_url_port(#[case] url: &str, #[case] expected: Option<u16>) {
+        assert_eq!(url_port(url), expected);
+    }
+
+    #[rstest]
+    #[case("bücher.example", "xn--bcher-kva.example", true)]
+    #[case("BÜCHER.example", "bücher.example", true)]
+    #[case("b%C3%BCcher.example", "bücher.example", true)]
+    #[case("ｅｘａｍｐｌｅ.com", "example.com", true)]
+    #[case("Example.COM.", "example.com", true)]
+    #[case("[::1]", "[::1]", true)]
+    // The first letter is a Cyrillic а.
+    #[case("аpple.com", "apple.com", false)]
+    #[case("a.example", "b.example", false)]
+    #[case("www.example.com", "example.com", false)]
+    fn test_same_host(#[case] a: &str, #[case] b: &str, #[case] expected: bool) {
+        assert_eq!(same_host(a, b), expected);
+        assert_eq!(same_host(b, a), expected);
+    }
+}
@@ -1,2 +1,6 @@
+pub mod codec;
+pub mod glob;
+pub mod host;
+pub mod normalize;
 pub mod parser;
 pub mod protocol;
@@ -0,0 +1,328 @@
+/// Query parameters whose values change on every request, like timestamps
+/// and cache busters, and which say nothing about what is requested.
+///
+/// Parameters are matched by name, case-insensitively; a `*` in a pattern
+/// matches any run of characters, e.g. `utm_*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolatileParams {
+    patterns: Vec<String>,
+}
+
+impl VolatileParams {
+    /// The parameters treated as volatile unless told otherwise.
+    pub const DEFAULT_PATTERNS: &[&str] = &[
+        "_",
+        "t",
+        "ts",
+        "timestamp",
+        "nonce",
+        "cb",
+        "cachebuster",
+        "cache_buster",
+        "rand",
+        "random",
+    ];
+
+    /// No parameter is volatile.
+    pub fn none() -> Self {
+        VolatileParams {
+            patterns: Vec::new(),
+        }
+    }
+
+    pub fn with_pattern(mut self, pattern: &str) -> Self {
+        self.patterns.push(pattern.to_ascii_lowercase());
+        self
+    }
+
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.patterns.iter().map(String::as_str)
+    }
+
+    /// Whether the parameter called `name` is volatile.
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        self.patterns
+            .iter()
+            .any(|pattern| wildcard_match(pattern, &name))
+    }
+}
+
+impl Default for VolatileParams {
+    fn default() -> Self {
+        Self::DEFAULT_PATTERNS
+            .iter()
+            .fold(Self::none(), |params, pattern| params.with_pattern(pattern))
+    }
+}
+
+/// What to do with a volatile parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VolatileAction {
+    /// Drop the parameter.
+    #[default]
+    Remove,
+    /// Keep the parameter, its value replaced by a `{{name}}` placeholder.
+    Templatize,
+}
+
+impl VolatileAction {
+    /// The placeholder standing for the value of the parameter `name`.
+    pub fn placeholder(name: &str) -> String {
+        format!("{{{{{}}}}}", name)
+    }
+}
+
+impl VolatileParams {
+    /// Rewrite the volatile pairs of a `&`-separated `name=value` list, like
+    /// a query string or a form-encoded body. Other pairs are kept as is.
+    pub fn rewrite_pairs(&self, pairs: &str, action: VolatileAction) -> String {
+        pairs
+            .split('&')
+            .filter_map(|pair| {
+                let name = pair.split_once('=').map_or(pair, |(name, _)| name);
+                if pair.is_empty() || !self.matches(name) {
+                    return Some(pair.to_string());
+                }
+                match action {
+                    VolatileAction::Remove => None,
+                    VolatileAction::Templatize => {
+                        Some(format!("{}={}", name, VolatileAction::placeholder(name)))
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Rewrite the query string of `url`, dropping the `?` when nothing is
+    /// left of it.
+    pub fn rewrite_url(&self, url: &str, action: VolatileAction) -> String {
+        let (url, fragment) = match url.split_once('#') {
+            Some((url, fragment)) => (url, Some(fragment)),
+            None => (url, None),
+        };
+        let mut rewritten = match url.split_once('?') {
+            Some((base, query)) => match self.rewrite_pairs(query, action) {
+                query if query.is_empty() => base.to_string(),
+                query => format!("{}?{}", base, query),
+            },
+            None => url.to_string(),
+        };
+        if let Some(fragment) = fragment {
+            rewritten.push('#');
+            rewritten.push_str(fragment);
+        }
+        rewritten
+    }
+
+    /// Rewrite the volatile members of every object of a JSON document.
+    pub fn rewrite_json(&self, value: &mut serde_json::Value, action: VolatileAction) {
+        match value {
+            serde_json::Value::Object(map) => {
+                match action {
+                    VolatileAction::Remove => map.retain(|name, _| !self.matches(name)),
+                    VolatileAction::Templatize => {
+                        for (name, value) in map.iter_mut() {
+                            if self.matches(name) {
+                                *value = VolatileAction::placeholder(name).into();
+                            }
+                        }
+                    }
+                }
+                for value in map.values_mut() {
+                    self.rewrite_json(value, action);
+                }
+            }
+            serde_json::Value::Array(values) => {
+                for value in values {
+                    self.rewrite_json(value, action);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Match `text` against `pattern`, where `*` stands for any run of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut pieces = pattern.split('*');
+    let first = pieces.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut pieces: Vec<&str> = pieces.collect();
+    let Some(last) = pieces.pop() else {
+        // No wildcard at all.
+        return rest.is_empty();
+    };
+    for piece in pieces {
+        match rest.find(piece) {
+            Some(at) => rest = &rest[at + piece.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Rewrite a URL into a canonical form, so that URLs requesting the same
+/// resource compare equal.
+///
+/// The scheme and host are lowercased, default ports and the fragment
+/// dropped, an empty path becomes `/`, and query parameters are sorted with
+/// the volatile ones removed. Percent-encoding is left untouched.
+pub fn normalize_url(url: &str, volatile: &VolatileParams) -> String {
+    let url = url.split_once('#').map_or(url, |(url, _)| url);
+    let (url, query) = match url.split_once('?') {
+        Some((url, query)) => (url, Some(query)),
+        None => (url, None),
+    };
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_ascii_lowercase(), rest),
+        None => ("http".into(), url),
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(slash) => rest.split_at(slash),
+        None => (rest, "/"),
+    };
+    let (userinfo, host) = match authority.rsplit_once('@') {
+        Some((userinfo, host)) => (Some(userinfo), host),
+        None => (None, authority),
+    };
+    let mut host = host.to_ascii_lowercase();
+    let default_port = match scheme.as_str() {
+        "http" | "ws" => Some(":80"),
+        "https" | "wss" => Some(":443"),
+        "ftp" => Some(":21"),
+        _ => None,
+    };
+    if let Some(port) = default_port
+        && host.ends_with(port)
+    {
+        host.truncate(host.len() - port.len());
+    }
+
+    let mut normalized = format!("{}://", scheme);
+    if let Some(userinfo) = userinfo {
+        normalized.push_str(userinfo);
+        normalized.push('@');
+    }
+    normalized.push_str(&host);
+    normalized.push_str(path);
+
+    let mut params: Vec<&str> = query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .filter(|param| !param.is_empty())
+        .filter(|param| {
+            let name = param.split_once('=').map_or(*param, |(name, _)| name);
+            !volatile.matches(name)
+        })
+        .collect();
+    params.sort_by_key(|param| param.split_once('=').map_or(*param, |(name, _)| name));
+    if !params.is_empty() {
+        normalized.push('?');
+        normalized.push_str(&params.join("&"));
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("_", "_", true)]
+    #[case("_", "__", false)]
+    #[case("utm_*", "utm_source", true)]
+    #[case("utm_*", "utm_", true)]
+    #[case("utm_*", "xutm_source", false)]
+    #[case("*_ts", "request_ts", true)]
+    #[case("a*b*c", "aXXbYYc", true)]
+    #[case("a*b*c", "aXXcYYb", false)]
+    fn test_wildcard_match(#[case] pattern: &str, #[case] text: &str, #[case] expected: bool) {
+        assert_eq!(wildcard_match(pattern, text), expected);
+    }
+
+    #[rstest]
+    fn test_volatile_params() {
+        let volatile = VolatileParams::default().with_pattern("UTM_*");
+        assert!(volatile.matches("_"));
+        assert!(volatile.matches("Timestamp"));
+        assert!(volatile.matches("utm_campaign"));
+        assert!(!volatile.matches("page"));
+        assert!(!VolatileParams::none().matches("_"));
+    }
+
+    #[rstest]
+    #[case("a=1&_=2&t=3", VolatileAction::Remove, "a=1")]
+    #[case("_=2&a=1", VolatileAction::Templatize, "_={{_}}&a=1")]
+    #[case("nonce&a=1", VolatileAction::Templatize, "nonce={{nonce}}&a=1")]
+    #[case("a=1&&b", VolatileAction::Remove, "a=1&&b")]
+    fn test_rewrite_pairs(
+        #[case] pairs: &str,
+        #[case] action: VolatileAction,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            VolatileParams::default().rewrite_pairs(pairs, action),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("https://a.com/x?_=1", VolatileAction::Remove, "https://a.com/x")]
+    #[case(
+        "https://a.com/x?q=1&ts=9#top",
+        VolatileAction::Remove,
+        "https://a.com/x?q=1#top"
+    )]
+    #[case(
+        "https://a.com/x?ts=9&q=1",
+        VolatileAction::Templatize,
+        "https://a.com/x?ts={{ts}}&q=1"
+    )]
+    #[case(
+        "https://a.com/x#a?_=1",
+        VolatileAction::Remove,
+        "https://a.com/x#a?_=1"
+    )]
+    fn test_rewrite_url(#[case] url: &str, #[case] action: VolatileAction, #[case] expected: &str) {
+        assert_eq!(VolatileParams::default().rewrite_url(url, action), expected);
+    }
+
+    #[rstest]
+    fn test_rewrite_json() {
+        let volatile = VolatileParams::default();
+        let document = serde_json::json!({"q": 1, "nonce": "x", "items": [{"ts": 5, "id": 2}]});
+
+        let mut removed = document.clone();
+        volatile.rewrite_json(&mut removed, VolatileAction::Remove);
+        assert_eq!(removed, serde_json::json!({"q": 1, "items": [{"id": 2}]}));
+
+        let mut templatized = document;
+        volatile.rewrite_json(&mut templatized, VolatileAction::Templatize);
+        assert_eq!(
+            templatized,
+            serde_json::json!({"q": 1, "nonce": "{{nonce}}", "items": [{"ts": "{{ts}}", "id": 2}]})
+        );
+    }
+
+    #[rstest]
+    #[case("HTTPS://Example.COM", "https://example.com/")]
+    #[case("http://example.com:80/a", "http://example.com/a")]
+    #[case("https://example.com:8443/a", "https://example.com:8443/a")]
+    #[case("https://example.com/a#top", "https://example.com/a")]
+    #[case("https://example.com/A/b?", "https://example.com/A/b")]
+    #[case("https://example.com/?b=2&a=1&a=0", "https://example.com/?a=1&a=0&b=2")]
+    #[case(
+        "https://example.com/?_=1700000000&q=x&t=5",
+        "https://example.com/?q=x"
+    )]
+    #[case("https://Me:Pw@Example.com/", "https://Me:Pw@example.com/")]
+    #[case("example.com/a?x=%2f", "http://example.com/a?x=%2f")]
+    fn test_normalize_url(#[case] url: &str, #[case] expected: &str) {
+        assert_eq!(normalize_url(url, &VolatileParams::default()), expected);
+    }
+}
@@ -1,5 +1,11 @@
-#[derive(Debug, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum Schema {
+    #[default]
     HTTPS,
     HTTP,
     FTP,
@@ -12,9 +18,33 @@ pub enum Schema {
     UNKNOWN,
 }
 
-impl Default for Schema {
-    fn default() -> Self {
-        Self::HTTPS
+impl Schema {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Schema::HTTPS => "https",
+            Schema::HTTP => "http",
+            Schema::FTP => "ftp",
+            Schema::SFTP => "sftp",
+            Schema::TFTP => "tftp",
+            Schema::TELNET => "telnet",
+            Schema::LDAP => "ldap",
+            Schema::WS => "ws",
+            Schema::WSS => "wss",
+            Schema::UNKNOWN => "unknown",
+        }
+    }
+    /// The port used when the URL gives none, `None` for unknown schemes.
+    pub fn default_port(&self) -> Option<u16> {
+        match self {
+            Schema::HTTP | Schema::WS => Some(80),
+            Schema::HTTPS | Schema::WSS => Some(443),
+            Schema::FTP => Some(21),
+            Schema::SFTP => Some(22),
+            Schema::TFTP => Some(69),
+            Schema::TELNET => Some(23),
+            Schema::LDAP => Some(389),
+            Schema::UNKNOWN => None,
+        }
     }
 }
 
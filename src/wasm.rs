@@ -0,0 +1,19 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::curl::output::parse_to_json;
+
+/// Parse a curl command into a plain object, `{command}` or `{error}` as
+/// [`parse_to_json`] gives them.
+///
+/// ```js
+/// import { parse } from "winnowcurl";
+///
+/// const { command, error } = parse("curl https://example.com -d a=1");
+/// ```
+#[wasm_bindgen]
+pub fn parse(command: &str) -> JsValue {
+    parse_to_json(command)
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .expect("JSON values convert to JavaScript")
+}
@@ -0,0 +1,43 @@
+//! Keeps `winnowcurl-parse` small when built with the `minimal` profile.
+//! The build takes a while, so the test only runs when asked:
+//! `cargo test --test binary_size -- --ignored`.
+
+use std::path::Path;
+use std::process::Command;
+
+/// The largest `winnowcurl-parse` may get, in bytes; about 470 KiB on
+/// x86_64 Linux when last raised.
+const MAX_SIZE: u64 = 600 * 1024;
+
+#[test]
+#[ignore = "builds the crate with the minimal profile"]
+fn test_minimal_parse_binary_size() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target = root.join("target");
+    let status = Command::new(env!("CARGO"))
+        .current_dir(root)
+        .args([
+            "build",
+            "--profile",
+            "minimal",
+            "--no-default-features",
+            "--bin",
+            "winnowcurl-parse",
+            "--target-dir",
+        ])
+        .arg(&target)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let binary = target
+        .join("minimal")
+        .join(format!("winnowcurl-parse{}", std::env::consts::EXE_SUFFIX));
+    let size = binary.metadata().unwrap().len();
+    assert!(
+        size <= MAX_SIZE,
+        "winnowcurl-parse is {} bytes, over {}",
+        size,
+        MAX_SIZE
+    );
+}
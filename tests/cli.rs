@@ -0,0 +1,100 @@
+//! Exit codes of the subcommands reading a curl command, which give 2 like
+//! `winnowcurl parse` when the command does not parse.
+
+#![cfg(feature = "cli")]
+#![allow(clippy::disallowed_types)]
+
+use std::process::{Command, Stdio};
+
+fn status(args: &[&str]) -> Option<i32> {
+    Command::new(env!("CARGO_BIN_EXE_winnowcurl"))
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+        .status
+        .code()
+}
+
+#[test]
+fn unparsable_command() {
+    for args in [
+        &["parse", "curl -X"][..],
+        &["format", "curl -X"],
+        &["rewrite", "curl -X", "--strip-header", "Accept"],
+        &["convert", "curl -X", "--target", "python"],
+        &["diff", "curl https://a.com", "curl -X"],
+        &["explain", "curl -X"],
+        &["templatize", "curl -X"],
+        &["expand", "curl -X"],
+        &["expand", "curl -s"],
+        &["expand", "curl 'https://a.com/[1-'"],
+    ] {
+        assert_eq!(status(args), Some(2), "{:?}", args);
+    }
+}
+
+#[test]
+fn unreadable_file() {
+    assert_eq!(
+        status(&["convert", "--file", "/nonexistent/commands.sh"]),
+        Some(2)
+    );
+}
+
+#[test]
+fn parsed_command() {
+    assert_eq!(status(&["convert", "curl https://a.com"]), Some(0));
+    assert_eq!(status(&["format", "curl https://a.com"]), Some(0));
+    assert_eq!(status(&["templatize", "curl https://a.com"]), Some(0));
+}
+
+#[test]
+fn lint_baseline() {
+    let dir = std::env::temp_dir().join(format!("winnowcurl-baseline-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("script.sh");
+    let baseline = dir.join("baseline.json");
+    let _ = std::fs::remove_file(&baseline);
+    let lint = |script_text: &str| {
+        std::fs::write(&script, script_text).unwrap();
+        status(&[
+            "lint",
+            "--deny",
+            "insecure",
+            "--baseline",
+            baseline.to_str().unwrap(),
+            script.to_str().unwrap(),
+        ])
+    };
+
+    // The first run records the findings, which then no longer fail.
+    assert_eq!(lint("curl -k https://a.com\n"), Some(0));
+    assert!(baseline.exists());
+    assert_eq!(lint("curl -k https://a.com\n"), Some(0));
+    assert_eq!(
+        lint("curl -k https://a.com\ncurl -k https://b.com\n"),
+        Some(1)
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn batch_dialect_continuations() {
+    let path = std::env::temp_dir().join(format!("winnowcurl-ps-{}.txt", std::process::id()));
+    std::fs::write(
+        &path,
+        "curl.exe https://a.com `\n  -H \"X: 1\"\ncurl.exe https://b.com\n",
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_winnowcurl"))
+        .args(["parse", "--dialect", "powershell", "--file"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Some(\"X: 1\")"), "{}", stdout);
+    assert_eq!(stdout.matches("# line").count(), 2, "{}", stdout);
+}
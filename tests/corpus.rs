@@ -0,0 +1,33 @@
+//! Parses every command of `tests/corpus` and compares the result with the
+//! JSON next to it. After a deliberate change, `WINNOWCURL_BLESS=1 cargo
+//! test --test corpus` rewrites the expected files; review their diff.
+
+use std::path::Path;
+use winnowcurl::corpus;
+
+#[test]
+fn test_corpus() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let cases = corpus::load(&dir).unwrap();
+    assert!(!cases.is_empty());
+
+    let mismatches = corpus::check(&cases);
+    if std::env::var_os("WINNOWCURL_BLESS").is_some() {
+        for mismatch in &mismatches {
+            let case = cases.iter().find(|case| case.name == mismatch.name);
+            case.unwrap().bless(&mismatch.actual).unwrap();
+        }
+        return;
+    }
+    assert!(
+        mismatches.is_empty(),
+        "{} of {} corpus commands parse differently:\n{}",
+        mismatches.len(),
+        cases.len(),
+        mismatches
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+}
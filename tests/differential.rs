@@ -0,0 +1,185 @@
+//! Sends the commands of `tests/corpus` with the system curl to a local
+//! server and compares the requests it receives with what winnowcurl parses,
+//! see `winnowcurl::corpus::wire`. It needs `curl` and `sh`, so it only runs
+//! when asked: `cargo test --test differential -- --ignored`.
+#![allow(clippy::disallowed_types)]
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use winnowcurl::Request;
+use winnowcurl::corpus::wire::{WireRequest, compare};
+use winnowcurl::corpus::{self, Case};
+use winnowcurl::curl::lexer::Dialect;
+use winnowcurl::curl::options::is_next;
+use winnowcurl::curl::parser::curl_cmd_parse;
+
+/// Where winnowcurl knowingly differs from the curl it was last compared
+/// with, as `case: divergence`; drop a line once the difference is gone.
+const KNOWN_DIVERGENCES: &[&str] = &[];
+
+/// How long curl gets to send its request.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Read one request from `stream`, its body up to `Content-Length`, and
+/// answer it with an empty response.
+fn read_request(mut stream: TcpStream) -> Vec<u8> {
+    stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+    let mut bytes = Vec::new();
+    let mut buffer = [0; 4096];
+    let mut continued = false;
+    loop {
+        if let Some(request) = WireRequest::parse(&bytes) {
+            let length = request
+                .headers
+                .get("content-length")
+                .and_then(|length| length.parse().ok())
+                .unwrap_or(0);
+            if request.body.len() >= length {
+                break;
+            }
+            if !continued && request.headers.get("expect") == Some("100-continue") {
+                let _ = stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n");
+                continued = true;
+            }
+        }
+        match stream.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => bytes.extend_from_slice(&buffer[..read]),
+        }
+    }
+    let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    bytes
+}
+
+/// Accept the first connection to `listener` within [`TIMEOUT`].
+fn serve_one(listener: TcpListener) -> Option<Vec<u8>> {
+    listener.set_nonblocking(true).unwrap();
+    let deadline = Instant::now() + TIMEOUT;
+    while Instant::now() < deadline {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false).unwrap();
+                return Some(read_request(stream));
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(Duration::from_millis(10)),
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+/// The names of the shell variables of `command`, e.g. `OPENAI_API_KEY`.
+fn variables(command: &str) -> Vec<&str> {
+    command
+        .split('$')
+        .skip(1)
+        .map(|rest| {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            &rest[..end]
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// What the system curl sends for `command`, `None` when it sends nothing,
+/// e.g. because a file it uploads is missing.
+fn send(command: &str, listener: TcpListener, dir: &Path) -> Option<WireRequest> {
+    let server = thread::spawn(move || serve_one(listener));
+    let command = command.replacen(
+        "curl",
+        "curl -q -s -o /dev/null --max-time 5 --noproxy '*'",
+        1,
+    );
+    let mut shell = Command::new("sh");
+    shell
+        .arg("-c")
+        .arg(&command)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Variables stay as written, as winnowcurl keeps them.
+    for name in variables(&command) {
+        shell.env(name, format!("${}", name));
+    }
+    shell.status().unwrap();
+    WireRequest::parse(&server.join().unwrap()?)
+}
+
+/// The request of the first part of `command`, the only one the server
+/// answers.
+fn first_request(command: &str) -> Option<Request> {
+    let curls = curl_cmd_parse(command).ok()?;
+    Request::try_from(curls.split(is_next).next()?).ok()
+}
+
+/// The command of `case` with the origin of its URL replaced by `base`,
+/// `None` when it is not a Bash command with the origin written out.
+fn retarget(case: &Case, base: &str) -> Option<String> {
+    if Dialect::detect(&case.command) != Dialect::Bash {
+        return None;
+    }
+    let url = first_request(&case.command)?.url;
+    let rest = url.split_once("://")?.1;
+    let origin = &url[..url.len() - rest.len() + rest.find(['/', '?']).unwrap_or(rest.len())];
+    case.command
+        .contains(origin)
+        .then(|| case.command.replace(origin, base))
+}
+
+#[test]
+#[ignore = "runs the system curl"]
+fn test_corpus_matches_curl() {
+    if Command::new("curl").arg("--version").output().is_err() {
+        eprintln!("curl is not installed");
+        return;
+    }
+    let cases = corpus::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let mut compared = 0;
+    let mut divergences = Vec::new();
+    for case in &cases {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let Some(command) = retarget(case, &base) else {
+            continue;
+        };
+        let Some(wire) = send(&command, listener, dir.path()) else {
+            eprintln!("{}: curl sent nothing", case.name);
+            continue;
+        };
+        let request = first_request(&command).unwrap();
+        compared += 1;
+        divergences.extend(
+            compare(&request, &wire)
+                .into_iter()
+                .map(|divergence| format!("{}: {}", case.name, divergence)),
+        );
+    }
+    assert!(compared > 0);
+    let new: Vec<_> = divergences
+        .iter()
+        .filter(|divergence| !KNOWN_DIVERGENCES.contains(&divergence.as_str()))
+        .collect();
+    assert!(
+        new.is_empty(),
+        "{} commands compared, curl sent something else than expected:\n{}",
+        compared,
+        new.iter()
+            .map(|d| d.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    let gone: Vec<_> = KNOWN_DIVERGENCES
+        .iter()
+        .filter(|known| !divergences.iter().any(|divergence| divergence == *known))
+        .collect();
+    assert!(gone.is_empty(), "no longer divergent: {:?}", gone);
+}
@@ -0,0 +1,45 @@
+//! Exit codes of `winnowcurl exec`, which scripts rely on to tell a
+//! request that failed from one that went through.
+
+#![cfg(all(feature = "cli", feature = "exec"))]
+#![allow(clippy::disallowed_types)]
+
+use std::net::TcpListener;
+use std::process::{Command, Output, Stdio};
+
+fn exec(command: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_winnowcurl"))
+        .args(["exec", command])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+/// A local port nothing listens on.
+fn closed_port() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap().port()
+}
+
+#[test]
+fn connection_refused() {
+    let output = exec(&format!("curl http://127.0.0.1:{}/", closed_port()));
+    assert_eq!(output.status.code(), Some(7));
+    assert!(!output.stderr.is_empty());
+}
+
+#[test]
+fn unparsable_command() {
+    assert_eq!(exec("curl -X").status.code(), Some(2));
+}
+
+#[test]
+fn destructive_request_not_confirmed() {
+    // Without a terminal to ask on, DELETE is refused before connecting.
+    let output = exec(&format!(
+        "curl -X DELETE http://127.0.0.1:{}/items/7",
+        closed_port()
+    ));
+    assert_eq!(output.status.code(), Some(120));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("was not sent"));
+}
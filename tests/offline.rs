@@ -0,0 +1,114 @@
+//! The default build parses and converts commands without ever reaching the
+//! network: crates which open sockets may only come with an opt-in feature,
+//! such as `exec`. `clippy.toml` keeps the sources from opening sockets
+//! with the standard library.
+
+const MANIFEST: &str = include_str!("../Cargo.toml");
+
+/// Crates which open sockets, directly or through an HTTP client.
+const NETWORK_CRATES: &[&str] = &[
+    "attohttpc",
+    "curl",
+    "h2",
+    "hyper",
+    "isahc",
+    "mio",
+    "reqwest",
+    "socket2",
+    "surf",
+    "tokio",
+    "ureq",
+];
+
+/// The `name = value` lines of a section of the manifest.
+fn section(name: &str) -> Vec<(&'static str, &'static str)> {
+    let header = format!("[{}]", name);
+    MANIFEST
+        .lines()
+        .skip_while(|line| line.trim() != header)
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(|line| line.split_once(" = "))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+/// The dependencies the build has whatever its features.
+fn required_dependencies() -> Vec<&'static str> {
+    section("dependencies")
+        .into_iter()
+        .filter(|(_, spec)| !spec.contains("optional = true"))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// The optional dependencies the default features enable, following the
+/// features they enable in turn.
+fn default_dependencies() -> Vec<String> {
+    let features = section("features");
+    let mut pending = vec!["default".to_string()];
+    let mut dependencies = Vec::new();
+    while let Some(feature) = pending.pop() {
+        let Some((_, enabled)) = features.iter().find(|(name, _)| *name == feature) else {
+            continue;
+        };
+        for item in enabled
+            .trim_matches(['[', ']'])
+            .split(',')
+            .map(|item| item.trim().trim_matches('"'))
+            .filter(|item| !item.is_empty())
+        {
+            match item.split_once('/') {
+                // `winnow/simd` turns on a feature of a required dependency.
+                Some((dependency, _)) => {
+                    dependencies.push(dependency.trim_start_matches("dep:").into())
+                }
+                None => match item.strip_prefix("dep:") {
+                    Some(dependency) => dependencies.push(dependency.into()),
+                    None => pending.push(item.into()),
+                },
+            }
+        }
+    }
+    dependencies
+}
+
+#[test]
+fn test_required_dependencies_are_offline() {
+    let dependencies = required_dependencies();
+    assert!(dependencies.contains(&"winnow"), "{:?}", dependencies);
+    for dependency in dependencies {
+        assert!(
+            !NETWORK_CRATES.contains(&dependency),
+            "{} reaches the network, make it optional",
+            dependency
+        );
+    }
+}
+
+#[test]
+fn test_default_features_are_offline() {
+    let dependencies = default_dependencies();
+    assert!(
+        dependencies.contains(&"memchr".to_string()),
+        "{:?}",
+        dependencies
+    );
+    for dependency in dependencies {
+        assert!(
+            !NETWORK_CRATES.contains(&dependency.as_str()),
+            "the default features enable {}, which reaches the network",
+            dependency
+        );
+    }
+}
+
+#[test]
+fn test_network_crates_are_optional() {
+    let dependencies = section("dependencies");
+    let (_, reqwest) = dependencies
+        .iter()
+        .find(|(name, _)| *name == "reqwest")
+        .expect("exec sends requests with reqwest");
+    assert!(reqwest.contains("optional = true"));
+}